@@ -19,6 +19,7 @@
 #![feature(rustc_attrs)]
 #![cfg_attr(test, feature(test))]
 
+use rustc_data_structures::profiling::record_arena_chunk;
 use smallvec::SmallVec;
 
 use std::alloc::Layout;
@@ -272,6 +273,7 @@ fn grow(&self, additional: usize) {
             // Also ensure that this chunk can fit `additional`.
             new_cap = cmp::max(additional, new_cap);
 
+            record_arena_chunk(new_cap * elem_size);
             let mut chunk = TypedArenaChunk::<T>::new(new_cap);
             self.ptr.set(chunk.start());
             self.end.set(chunk.end());
@@ -378,6 +380,7 @@ fn grow(&self, additional: usize) {
             // Also ensure that this chunk can fit `additional`.
             new_cap = cmp::max(additional, new_cap);
 
+            record_arena_chunk(new_cap);
             let mut chunk = TypedArenaChunk::<u8>::new(new_cap);
             self.start.set(chunk.start());
             self.end.set(chunk.end());
@@ -1,4 +1,8 @@
 //! Attributes injected into the crate root from command line using `-Z crate-attr`.
+//!
+//! Each occurrence of the flag may contain several attributes separated by top-level commas,
+//! e.g. `-Z crate-attr=feature(foo),allow(dead_code)`. Commas nested inside delimiters or
+//! literals are part of the attribute they belong to.
 
 use rustc_ast::attr::mk_attr;
 use rustc_ast::token;
@@ -8,27 +12,68 @@
 
 pub fn inject(mut krate: ast::Crate, parse_sess: &ParseSess, attrs: &[String]) -> ast::Crate {
     for raw_attr in attrs {
-        let mut parser = rustc_parse::new_parser_from_source_str(
+        let origin_note =
+            format!("this attribute was passed on the command line: `-Z crate-attr={}`", raw_attr);
+
+        let mut parser = match rustc_parse::maybe_new_parser_from_source_str(
             parse_sess,
             FileName::cli_crate_attr_source_code(&raw_attr),
             raw_attr.clone(),
-        );
-
-        let start_span = parser.token.span;
-        let AttrItem { path, args, tokens: _ } = match parser.parse_attr_item(false) {
-            Ok(ai) => ai,
-            Err(mut err) => {
-                err.emit();
+        ) {
+            Ok(parser) => parser,
+            Err(diagnostics) => {
+                for mut diagnostic in diagnostics {
+                    diagnostic.note(&origin_note);
+                    parse_sess.span_diagnostic.emit_diagnostic(&diagnostic);
+                }
                 continue;
             }
         };
-        let end_span = parser.token.span;
-        if parser.token != token::Eof {
-            parse_sess.span_diagnostic.span_err(start_span.to(end_span), "invalid crate attribute");
+
+        // A common mistake is to pass the attribute with its surrounding `#![...]`,
+        // which would otherwise surface as a confusing "expected identifier" error.
+        if parser.token == token::Pound {
+            let span = parser.token.span;
+            parse_sess
+                .span_diagnostic
+                .struct_span_err(span, "`-Z crate-attr` expects the contents of an attribute")
+                .span_label(span, "remove the surrounding `#![...]`")
+                .note(&origin_note)
+                .emit();
             continue;
         }
 
-        krate.attrs.push(mk_attr(AttrStyle::Inner, path, args, start_span.to(end_span)));
+        loop {
+            let start_span = parser.token.span;
+            let AttrItem { path, args, tokens: _ } = match parser.parse_attr_item(false) {
+                Ok(ai) => ai,
+                Err(mut err) => {
+                    err.note(&origin_note);
+                    err.emit();
+                    break;
+                }
+            };
+            let end_span = parser.prev_token.span;
+            let span = start_span.to(end_span);
+
+            if parser.token != token::Eof && parser.token != token::Comma {
+                parse_sess
+                    .span_diagnostic
+                    .struct_span_err(span.to(parser.token.span), "invalid crate attribute")
+                    .span_label(parser.token.span, "expected `,` or the end of the attribute list")
+                    .note(&origin_note)
+                    .emit();
+                break;
+            }
+
+            krate.attrs.push(mk_attr(AttrStyle::Inner, path, args, span));
+
+            // Allow a trailing comma, mostly for the benefit of build systems
+            // that join attribute lists naively.
+            if !parser.eat(&token::Comma) || parser.token == token::Eof {
+                break;
+            }
+        }
     }
 
     krate
@@ -8,6 +8,7 @@
 use rustc_expand::base::{ExtCtxt, ResolverExpand};
 use rustc_expand::expand::{AstFragment, ExpansionConfig};
 use rustc_feature::Features;
+use rustc_session::config::PanicAbortTestsFormat;
 use rustc_session::Session;
 use rustc_span::hygiene::{AstPass, SyntaxContext, Transparency};
 use rustc_span::symbol::{sym, Ident, Symbol};
@@ -18,6 +19,10 @@
 
 use std::{iter, mem};
 
+/// The environment variable through which libtest selects the test to run in a subprocess
+/// when none is given with `-Z panic-abort-tests-env`.
+const DEFAULT_TEST_INVOKER_VAR: &str = "__RUST_TEST_INVOKE";
+
 struct Test {
     span: Span,
     ident: Ident,
@@ -30,6 +35,7 @@ struct TestCtxt<'a> {
     test_cases: Vec<Test>,
     reexport_test_harness_main: Option<Symbol>,
     test_runner: Option<ast::Path>,
+    test_attr: Option<Symbol>,
 }
 
 // Traverse the crate, collecting all the test functions, eliding any
@@ -37,7 +43,6 @@ struct TestCtxt<'a> {
 pub fn inject(sess: &Session, resolver: &mut dyn ResolverExpand, krate: &mut ast::Crate) {
     let span_diagnostic = sess.diagnostic();
     let panic_strategy = sess.panic_strategy();
-    let platform_panic_strategy = sess.target.panic_strategy;
 
     // Check for #![reexport_test_harness_main = "some_name"] which gives the
     // main test function the name `some_name` without hygiene. This needs to be
@@ -49,22 +54,18 @@ pub fn inject(sess: &Session, resolver: &mut dyn ResolverExpand, krate: &mut ast
     // Do this here so that the test_runner crate attribute gets marked as used
     // even in non-test builds
     let test_runner = get_test_runner(sess, span_diagnostic, &krate);
+    // `-Z test-runner` takes precedence over the attribute.
+    let test_runner = match &sess.opts.debugging_opts.test_runner {
+        Some(path) => get_cmdline_test_runner(sess, path),
+        None => test_runner,
+    };
 
     if sess.opts.test {
         let panic_strategy = match (panic_strategy, sess.opts.debugging_opts.panic_abort_tests) {
             (PanicStrategy::Abort, true) => PanicStrategy::Abort,
-            (PanicStrategy::Abort, false) => {
-                if panic_strategy == platform_panic_strategy {
-                    // Silently allow compiling with panic=abort on these platforms,
-                    // but with old behavior (abort if a test fails).
-                } else {
-                    span_diagnostic.err(
-                        "building tests with panic=abort is not supported \
-                                         without `-Zpanic_abort_tests`",
-                    );
-                }
-                PanicStrategy::Unwind
-            }
+            // Use the old behavior (abort if a test fails). An explicit `-C panic=abort`
+            // has already been reported when the session was created.
+            (PanicStrategy::Abort, false) => PanicStrategy::Unwind,
             (PanicStrategy::Unwind, _) => PanicStrategy::Unwind,
         };
         generate_test_harness(
@@ -120,9 +121,19 @@ fn visit_crate(&mut self, c: &mut ast::Crate) {
 
     fn flat_map_item(&mut self, i: P<ast::Item>) -> SmallVec<[P<ast::Item>; 1]> {
         let mut item = i.into_inner();
-        if is_test_case(&self.cx.ext_cx.sess, &item) {
+        if is_test_case(&self.cx.ext_cx.sess, &item, self.cx.test_attr) {
             debug!("this is a test item");
 
+            // Like `#[test_case]` items, the ones marked with the attribute of `-Z test-attr`
+            // must be public for the harness to reach them from the crate root.
+            if !self.cx.ext_cx.sess.contains_name(&item.attrs, sym::rustc_test_marker) {
+                item.vis = ast::Visibility {
+                    span: item.vis.span,
+                    kind: ast::VisibilityKind::Public,
+                    tokens: None,
+                };
+            }
+
             let test = Test { span: item.span, ident: item.ident };
             self.tests.push(test);
         }
@@ -217,7 +228,7 @@ fn generate_test_harness(
     let mut econfig = ExpansionConfig::default("test".to_string());
     econfig.features = Some(features);
 
-    let ext_cx = ExtCtxt::new(sess, econfig, resolver, None);
+    let ext_cx = ExtCtxt::new(sess, econfig, resolver, None, None);
 
     let expn_id = ext_cx.resolver.expansion_for_ast_pass(
         DUMMY_SP,
@@ -231,6 +242,15 @@ fn generate_test_harness(
     let mut cleaner = EntryPointCleaner { sess, depth: 0, def_site };
     cleaner.visit_crate(krate);
 
+    let test_attr = match &sess.opts.debugging_opts.test_attr {
+        Some(attr) if rustc_lexer::is_ident(attr) => Some(Symbol::intern(attr)),
+        Some(attr) => {
+            sess.err(&format!("`-Z test-attr` must be the name of an attribute, not `{}`", attr));
+            None
+        }
+        None => None,
+    };
+
     let cx = TestCtxt {
         ext_cx,
         panic_strategy,
@@ -238,6 +258,7 @@ fn generate_test_harness(
         test_cases: Vec::new(),
         reexport_test_harness_main,
         test_runner,
+        test_attr,
     };
 
     TestHarnessGenerator { cx, tests: Vec::new() }.visit_crate(krate);
@@ -272,15 +293,31 @@ fn generate_test_harness(
 ///
 /// [`TestCtxt::reexport_test_harness_main`] provides a different name for the `main`
 /// function and [`TestCtxt::test_runner`] provides a path that replaces
-/// `test::test_main_static`.
+/// `test::test_main_static`. The latter can also be given with `-Z test-runner`.
 fn mk_main(cx: &mut TestCtxt<'_>) -> P<ast::Item> {
     let sp = cx.def_site;
     let ecx = &cx.ext_cx;
     let test_id = Ident::new(sym::test, sp);
 
-    let runner_name = match cx.panic_strategy {
-        PanicStrategy::Unwind => "test_main_static",
-        PanicStrategy::Abort => "test_main_static_abort",
+    // The subprocess protocol is only passed on when it isn't the default one, so that
+    // test crates without `test_main_static_abort_with_protocol` keep working.
+    let debugging_opts = &ecx.sess.opts.debugging_opts;
+    let protocol = match cx.panic_strategy {
+        PanicStrategy::Abort
+            if debugging_opts.panic_abort_tests_env.is_some()
+                || debugging_opts.panic_abort_tests_format != PanicAbortTestsFormat::ExitCode =>
+        {
+            let env = debugging_opts.panic_abort_tests_env.as_deref();
+            let env = env.unwrap_or(DEFAULT_TEST_INVOKER_VAR);
+            Some((env, debugging_opts.panic_abort_tests_format.desc()))
+        }
+        _ => None,
+    };
+
+    let runner_name = match (cx.panic_strategy, protocol) {
+        (PanicStrategy::Unwind, _) => "test_main_static",
+        (PanicStrategy::Abort, None) => "test_main_static_abort",
+        (PanicStrategy::Abort, Some(_)) => "test_main_static_abort_with_protocol",
     };
 
     // test::test_main_static(...)
@@ -292,7 +329,13 @@ fn mk_main(cx: &mut TestCtxt<'_>) -> P<ast::Item> {
     test_runner.span = sp;
 
     let test_main_path_expr = ecx.expr_path(test_runner);
-    let call_test_main = ecx.expr_call(sp, test_main_path_expr, vec![mk_tests_slice(cx, sp)]);
+    let mut args = vec![mk_tests_slice(cx, sp)];
+    // A custom test runner only takes the tests.
+    if let (None, Some((env, format))) = (&cx.test_runner, protocol) {
+        args.push(ecx.expr_str(sp, Symbol::intern(env)));
+        args.push(ecx.expr_str(sp, Symbol::intern(format)));
+    }
+    let call_test_main = ecx.expr_call(sp, test_main_path_expr, args);
     let call_test_main = ecx.stmt_expr(call_test_main);
 
     // extern crate test
@@ -361,8 +404,9 @@ fn mk_tests_slice(cx: &TestCtxt<'_>, sp: Span) -> P<ast::Expr> {
     )
 }
 
-fn is_test_case(sess: &Session, i: &ast::Item) -> bool {
+fn is_test_case(sess: &Session, i: &ast::Item, test_attr: Option<Symbol>) -> bool {
     sess.contains_name(&i.attrs, sym::rustc_test_marker)
+        || test_attr.map_or(false, |attr| sess.contains_name(&i.attrs, attr))
 }
 
 fn get_test_runner(
@@ -382,3 +426,24 @@ fn get_test_runner(
     }
     None
 }
+
+/// Parses the path given with `-Z test-runner`. It is resolved as if it was written at the
+/// crate root, so it may name a function in an extern crate or, with `crate::`, in this one.
+fn get_cmdline_test_runner(sess: &Session, path: &str) -> Option<ast::Path> {
+    let (root, relative) = match path.strip_prefix("::") {
+        Some(relative) => (Some(ast::PathSegment::path_root(DUMMY_SP)), relative),
+        None => (None, path),
+    };
+    let mut segments: Vec<_> = root.into_iter().collect();
+    for segment in relative.split("::") {
+        if !rustc_lexer::is_ident(segment) {
+            sess.err(&format!(
+                "`-Z test-runner` must be a path like `my_harness::run_tests`, not `{}`",
+                path
+            ));
+            return None;
+        }
+        segments.push(ast::PathSegment::from_ident(Ident::from_str(segment)));
+    }
+    Some(ast::Path { span: DUMMY_SP, segments, tokens: None })
+}
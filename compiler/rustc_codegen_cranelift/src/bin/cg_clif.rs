@@ -46,6 +46,7 @@ fn config(&mut self, config: &mut interface::Config) {
         // If a --prints=... option has been given, we don't print the "total"
         // time because it will mess up the --prints output. See #64339.
         self.time_passes = config.opts.prints.is_empty()
+            && config.opts.custom_prints.is_empty()
             && (config.opts.debugging_opts.time_passes || config.opts.debugging_opts.time);
 
         config.opts.cg.panic = Some(PanicStrategy::Abort);
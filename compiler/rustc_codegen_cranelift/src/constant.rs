@@ -400,6 +400,16 @@ fn define_all_allocs(tcx: TyCtxt<'_>, module: &mut dyn Module, cx: &mut Constant
                 ("", &*section_name)
             };
             data_ctx.set_segment_section(segment_name, section_name);
+        } else if tcx.sess.opts.debugging_opts.data_sections.unwrap_or(false)
+            && !tcx.sess.target.is_like_osx
+        {
+            // Like for -Zfunction-sections, cg_clif only splits data into separate sections when
+            // explicitly asked to. Mach-O uses subsections via symbols instead.
+            let decl = module.declarations().get_data_decl(data_id);
+            if !decl.tls {
+                let prefix = if decl.writable { ".data" } else { ".rodata" };
+                data_ctx.set_segment_section("", &format!("{}.{}", prefix, decl.name));
+            }
         }
 
         let bytes = alloc.inspect_with_uninit_and_ptr_outside_interpreter(0..alloc.len()).to_vec();
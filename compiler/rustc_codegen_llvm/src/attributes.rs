@@ -9,12 +9,13 @@
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::ty::layout::HasTyCtxt;
 use rustc_middle::ty::{self, TyCtxt};
-use rustc_session::config::OptLevel;
+use rustc_session::config::{FunctionReturn, Hotpatch, OptLevel};
 use rustc_session::Session;
 use rustc_target::spec::abi::Abi;
 use rustc_target::spec::{FramePointer, SanitizerSet, StackProbeType, StackProtector};
 
 use crate::attributes;
+use crate::builder::Builder;
 use crate::llvm::AttributePlace::Function;
 use crate::llvm::{self, Attribute};
 use crate::llvm_util;
@@ -55,6 +56,9 @@ pub fn sanitize(cx: &CodegenCx<'ll, '_>, no_sanitize: SanitizerSet, llfn: &'ll V
     if enabled.contains(SanitizerSet::HWADDRESS) {
         llvm::Attribute::SanitizeHWAddress.apply_llfn(Function, llfn);
     }
+    if enabled.contains(SanitizerSet::SAFESTACK) {
+        llvm::Attribute::SafeStack.apply_llfn(Function, llfn);
+    }
 }
 
 /// Tell LLVM to emit or not emit the information necessary to unwind the stack for the function.
@@ -92,7 +96,12 @@ pub fn set_frame_pointer_type(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
 
 /// Tell LLVM what instrument function to insert.
 #[inline]
-fn set_instrument_function(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
+fn set_instrument_function(
+    cx: &CodegenCx<'ll, 'tcx>,
+    llfn: &'ll Value,
+    instance: ty::Instance<'tcx>,
+    is_naked: bool,
+) {
     if cx.sess().instrument_mcount() {
         // Similar to `clang -pg` behavior. Handled by the
         // `post-inline-ee-instrument` LLVM pass.
@@ -108,6 +117,125 @@ fn set_instrument_function(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
             &mcount_name,
         );
     }
+
+    // Naked functions have no prologue or epilogue to put the calls in.
+    let (entry, exit) = match cx.sess().instrument_function_hooks() {
+        Some(hooks) if !is_naked => hooks,
+        _ => return,
+    };
+    if cx.sess().instrument_functions_filtered() {
+        // Paths start with the name of the crate, and have `{impl#0}` or `{closure#0}` for
+        // items without a name, so closures are left out with the function they are in.
+        let def_id = instance.def_id();
+        let path = format!(
+            "{}{}",
+            cx.tcx.crate_name(def_id.krate),
+            cx.tcx.def_path(def_id).to_string_no_crate_verbose()
+        );
+        if cx.sess().instrument_functions_excluded(&path) {
+            return;
+        }
+    }
+
+    // By default, similar to `clang -finstrument-functions-after-inlining`, so functions
+    // that are inlined aren't reported, and handled by the `post-inline-ee-instrument` LLVM
+    // pass. With `-Z instrument-functions-post-inline=no`, similar to
+    // `clang -finstrument-functions` and handled by the `ee-instrument` pass, which runs
+    // before inlining. Both pass the addresses of the function and of its call site to the
+    // hooks, but only know the hooks of GCC by name, so other hooks are called through
+    // functions of those names.
+    let (entry_attr, exit_attr) = if cx.sess().opts.debugging_opts.instrument_functions_post_inline
+    {
+        (cstr!("instrument-function-entry-inlined"), cstr!("instrument-function-exit-inlined"))
+    } else {
+        (cstr!("instrument-function-entry"), cstr!("instrument-function-exit"))
+    };
+    define_instrument_function_hook(cx, "__cyg_profile_func_enter", entry);
+    llvm::AddFunctionAttrStringValue(
+        llfn,
+        llvm::AttributePlace::Function,
+        entry_attr,
+        cstr!("__cyg_profile_func_enter"),
+    );
+    if let Some(exit) = exit {
+        define_instrument_function_hook(cx, "__cyg_profile_func_exit", exit);
+        llvm::AddFunctionAttrStringValue(
+            llfn,
+            llvm::AttributePlace::Function,
+            exit_attr,
+            cstr!("__cyg_profile_func_exit"),
+        );
+    }
+}
+
+/// Defines the GCC hook `gcc_hook` of the codegen unit as an internal function calling `hook`
+/// with the same arguments, unless `hook` is the GCC hook itself.
+fn define_instrument_function_hook(cx: &CodegenCx<'ll, '_>, gcc_hook: &str, hook: &str) {
+    if hook == gcc_hook || cx.get_defined_value(gcc_hook).is_some() {
+        return;
+    }
+
+    let fn_ty = cx.type_func(&[cx.type_i8p(), cx.type_i8p()], cx.type_void());
+    let llfn = cx.declare_cfn(gcc_hook, llvm::UnnamedAddr::Global, fn_ty);
+    llvm::set_linkage(llfn, llvm::Linkage::InternalLinkage);
+    // The hook may already be declared with another type, by an `extern` block.
+    let callee = cx.declare_cfn(hook, llvm::UnnamedAddr::No, fn_ty);
+    let callee = cx.const_bitcast(callee, cx.type_ptr_to(fn_ty));
+
+    let llbb = Builder::append_block(cx, llfn, "start");
+    let mut bx = Builder::build(cx, llbb);
+    let args = [llvm::get_param(llfn, 0), llvm::get_param(llfn, 1)];
+    bx.call(fn_ty, callee, &args, None);
+    bx.ret_void();
+
+    // Nothing calls it until the calls are inserted, which the `post-inline-ee-instrument`
+    // pass only does after optimizations.
+    cx.add_compiler_used_global(llfn);
+}
+
+/// Tell LLVM to make the function hot-patchable, as requested with `-Z hotpatch`.
+fn set_hotpatch(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value, is_naked: bool) {
+    // Naked functions are left as they are written.
+    let (entry, prefix) = match cx.sess().opts.debugging_opts.hotpatch {
+        None => return,
+        _ if is_naked => return,
+        // Similar to `clang-cl /hotpatch`: the first instruction is at least two bytes long, so
+        // that it can be replaced with a short jump to the padding before the function. MSVC's
+        // linker adds the padding with `/FUNCTIONPADMIN`, other linkers have NOPs emitted instead,
+        // as many as `/FUNCTIONPADMIN` adds by default.
+        Some(Hotpatch::Prologue) => {
+            llvm::AddFunctionAttrStringValue(
+                llfn,
+                llvm::AttributePlace::Function,
+                cstr!("patchable-function"),
+                cstr!("prologue-short-redirect"),
+            );
+            if cx.sess().target.is_like_msvc {
+                return;
+            }
+            (0, if cx.sess().target.arch == "x86" { 5 } else { 6 })
+        }
+        // Similar to `clang -fpatchable-function-entry`.
+        Some(Hotpatch::NopSled { entry, prefix }) => (entry, prefix),
+    };
+    if entry != 0 {
+        let entry = CString::new(entry.to_string()).unwrap();
+        llvm::AddFunctionAttrStringValue(
+            llfn,
+            llvm::AttributePlace::Function,
+            cstr!("patchable-function-entry"),
+            &entry,
+        );
+    }
+    if prefix != 0 {
+        let prefix = CString::new(prefix.to_string()).unwrap();
+        llvm::AddFunctionAttrStringValue(
+            llfn,
+            llvm::AttributePlace::Function,
+            cstr!("patchable-function-prefix"),
+            &prefix,
+        );
+    }
 }
 
 fn set_probestack(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
@@ -172,6 +300,15 @@ fn set_stackprotector(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
     sspattr.apply_llfn(Function, llfn)
 }
 
+fn set_function_return(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
+    match cx.sess().opts.debugging_opts.function_return {
+        FunctionReturn::Keep => {}
+        // Older LLVMs don't have the attribute, which was already reported as an error.
+        FunctionReturn::ThunkExtern if llvm_util::get_version() < (15, 0, 0) => {}
+        FunctionReturn::ThunkExtern => Attribute::FnRetThunkExtern.apply_llfn(Function, llfn),
+    }
+}
+
 pub fn apply_target_cpu_attr(cx: &CodegenCx<'ll, '_>, llfn: &'ll Value) {
     let target_cpu = SmallCStr::new(llvm_util::target_cpu(cx.tcx.sess));
     llvm::AddFunctionAttrStringValue(
@@ -280,9 +417,16 @@ pub fn from_fn_attrs(cx: &CodegenCx<'ll, 'tcx>, llfn: &'ll Value, instance: ty::
 
     // FIXME: none of these three functions interact with source level attributes.
     set_frame_pointer_type(cx, llfn);
-    set_instrument_function(cx, llfn);
+    set_instrument_function(
+        cx,
+        llfn,
+        instance,
+        codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED),
+    );
+    set_hotpatch(cx, llfn, codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED));
     set_probestack(cx, llfn);
     set_stackprotector(cx, llfn);
+    set_function_return(cx, llfn);
 
     if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
         Attribute::Cold.apply_llfn(Function, llfn);
@@ -173,9 +173,8 @@ pub fn target_machine_factory(
     let (opt_level, _) = to_llvm_opt_settings(optlvl);
     let use_softfp = sess.opts.cg.soft_float;
 
-    let ffunction_sections =
-        sess.opts.debugging_opts.function_sections.unwrap_or(sess.target.function_sections);
-    let fdata_sections = ffunction_sections;
+    let ffunction_sections = sess.function_sections();
+    let fdata_sections = sess.data_sections();
     let funique_section_names = !sess.opts.debugging_opts.no_unique_section_names;
 
     let code_model = to_llvm_code_model(sess.code_model());
@@ -196,7 +195,8 @@ pub fn target_machine_factory(
     let abi = SmallCStr::new(&sess.target.llvm_abiname);
     let trap_unreachable =
         sess.opts.debugging_opts.trap_unreachable.unwrap_or(sess.target.trap_unreachable);
-    let emit_stack_size_section = sess.opts.debugging_opts.emit_stack_sizes;
+    let emit_stack_size_section = sess.opts.debugging_opts.emit_stack_sizes
+        || sess.opts.debugging_opts.stack_depth_report.is_some();
 
     let asm_comments = sess.asm_comments();
     let relax_elf_relocations =
@@ -256,6 +256,18 @@ pub(crate) fn save_temp_bitcode(
     }
 }
 
+/// The stage of the compilation of a module that diagnostic handlers are set up for, which
+/// tells apart the files of `-Z remark-dir`.
+#[derive(Clone, Copy)]
+pub enum CodegenDiagnosticsStage {
+    /// Optimizing the module.
+    Opt,
+    /// Linking other modules into it for fat LTO.
+    Lto,
+    /// Emitting its object code.
+    Codegen,
+}
+
 pub struct DiagnosticHandlers<'a> {
     data: *mut (&'a CodegenContext<LlvmCodegenBackend>, &'a Handler),
     llcx: &'a llvm::Context,
@@ -267,9 +279,11 @@ pub fn new(
         cgcx: &'a CodegenContext<LlvmCodegenBackend>,
         handler: &'a Handler,
         llcx: &'a llvm::Context,
+        module: &ModuleCodegen<ModuleLlvm>,
+        stage: CodegenDiagnosticsStage,
     ) -> Self {
         let remark_passes_all: bool;
-        let remark_passes: Vec<CString>;
+        let mut remark_passes: Vec<CString>;
         match &cgcx.remark {
             Passes::All => {
                 remark_passes_all = true;
@@ -281,8 +295,21 @@ pub fn new(
                     passes.iter().map(|name| CString::new(name.as_str()).unwrap()).collect();
             }
         };
+        // `-Z stack-protector-report` is made of the remarks of the stack protector pass.
+        if !remark_passes_all && cgcx.opts.debugging_opts.stack_protector_report.is_some() {
+            remark_passes.push(CString::new(STACK_PROTECTOR_PASS).unwrap());
+        }
         let remark_passes: Vec<*const c_char> =
             remark_passes.iter().map(|name: &CString| name.as_ptr()).collect();
+        // The `.opt.yaml` extension is the one that LLVM's opt-viewer looks for.
+        let remark_file = cgcx.remark_dir.as_ref().map(|dir| {
+            let stage = match stage {
+                CodegenDiagnosticsStage::Opt => "opt",
+                CodegenDiagnosticsStage::Lto => "lto",
+                CodegenDiagnosticsStage::Codegen => "codegen",
+            };
+            path_to_c_string(&dir.join(format!("{}.{}.opt.yaml", module.name, stage)))
+        });
         let data = Box::into_raw(Box::new((cgcx, handler)));
         unsafe {
             let old_handler = llvm::LLVMRustContextGetDiagnosticHandler(llcx);
@@ -293,6 +320,7 @@ pub fn new(
                 remark_passes_all,
                 remark_passes.as_ptr(),
                 remark_passes.len(),
+                remark_file.as_ref().map_or(std::ptr::null(), |file| file.as_ptr()),
             );
             llvm::LLVMRustSetInlineAsmDiagnosticHandler(llcx, inline_asm_handler, data.cast());
             DiagnosticHandlers { data, llcx, old_handler }
@@ -342,6 +370,29 @@ fn report_inline_asm(
     report_inline_asm(cgcx, smdiag.message, smdiag.level, cookie, smdiag.source);
 }
 
+/// The name of LLVM's stack protector pass, whose remarks say which functions it protects.
+const STACK_PROTECTOR_PASS: &str = "stack-protector";
+
+/// Records a function that LLVM's stack protector pass protected for `-Z stack-protector-report`,
+/// from the remark it emits, like "Stack protection applied to function foo due to the address
+/// of a local variable being taken".
+fn report_stack_protection(
+    cgcx: &CodegenContext<LlvmCodegenBackend>,
+    opt: &llvm::diagnostic::OptimizationDiagnostic<'_>,
+) {
+    let symbol = llvm::get_value_name(opt.function);
+    let symbol = String::from_utf8_lossy(symbol);
+    let function = match rustc_demangle::try_demangle(&symbol) {
+        Ok(demangled) => format!("{:#}", demangled),
+        Err(_) => symbol.into_owned(),
+    };
+    let reason = match opt.message.split_once(" due to ") {
+        Some((_, reason)) => reason.to_string(),
+        None => opt.message.clone(),
+    };
+    cgcx.diag_emitter.stack_protection(function, reason);
+}
+
 unsafe extern "C" fn diagnostic_handler(info: &DiagnosticInfo, user: *mut c_void) {
     if user.is_null() {
         return;
@@ -354,6 +405,12 @@ fn report_inline_asm(
         }
 
         llvm::diagnostic::Optimization(opt) => {
+            if opt.pass_name == STACK_PROTECTOR_PASS
+                && cgcx.opts.debugging_opts.stack_protector_report.is_some()
+            {
+                report_stack_protection(cgcx, &opt);
+            }
+
             let enabled = match cgcx.remark {
                 Passes::All => true,
                 Passes::Some(ref v) => v.iter().any(|s| *s == opt.pass_name),
@@ -492,6 +549,7 @@ pub(crate) unsafe fn optimize_with_new_llvm_pass_manager(
         pgo_use_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
         config.instrument_coverage,
         config.instrument_gcov,
+        config.instrument_functions_pre_inline,
         pgo_sample_use_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
         config.debug_info_for_profiling,
         llvm_selfprofiler,
@@ -515,7 +573,8 @@ pub(crate) unsafe fn optimize(
     let llmod = module.module_llvm.llmod();
     let llcx = &*module.module_llvm.llcx;
     let tm = &*module.module_llvm.tm;
-    let _handlers = DiagnosticHandlers::new(cgcx, diag_handler, llcx);
+    let _handlers =
+        DiagnosticHandlers::new(cgcx, diag_handler, llcx, module, CodegenDiagnosticsStage::Opt);
 
     let module_name = module.name.clone();
     let module_name = Some(&module_name[..]);
@@ -602,6 +661,9 @@ pub(crate) unsafe fn optimize(
             if config.debug_info_for_profiling {
                 llvm::LLVMRustAddPass(mpm, find_pass("add-discriminators").unwrap());
             }
+            if config.instrument_functions_pre_inline {
+                llvm::LLVMRustAddPass(fpm, find_pass("ee-instrument").unwrap());
+            }
 
             add_sanitizer_passes(config, &mut extra_passes);
 
@@ -740,7 +802,13 @@ pub(crate) unsafe fn codegen(
         let tm = &*module.module_llvm.tm;
         let module_name = module.name.clone();
         let module_name = Some(&module_name[..]);
-        let handlers = DiagnosticHandlers::new(cgcx, diag_handler, llcx);
+        let handlers = DiagnosticHandlers::new(
+            cgcx,
+            diag_handler,
+            llcx,
+            &module,
+            CodegenDiagnosticsStage::Codegen,
+        );
 
         if cgcx.msvc_imps_needed {
             create_msvc_imps(cgcx, llcx, llmod);
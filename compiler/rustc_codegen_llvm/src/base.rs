@@ -14,16 +14,20 @@
 use super::ModuleLlvm;
 
 use crate::attributes;
+use crate::back::lto::ModuleBuffer;
 use crate::builder::Builder;
 use crate::context::CodegenCx;
 use crate::llvm;
 use crate::value::Value;
 
+use rustc_codegen_ssa::back::stack_depth::codegen_call_graph;
 use rustc_codegen_ssa::base::maybe_create_entry_wrapper;
 use rustc_codegen_ssa::mono_item::MonoItemExt;
 use rustc_codegen_ssa::traits::*;
 use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
 use rustc_data_structures::small_c_str::SmallCStr;
+use rustc_errors::FatalError;
+use rustc_fs_util::path_to_c_string;
 use rustc_middle::dep_graph;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrs;
 use rustc_middle::mir::mono::{Linkage, Visibility};
@@ -32,6 +36,8 @@
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::SanitizerSet;
 
+use libc::{c_char, size_t};
+use std::fs;
 use std::time::Instant;
 
 pub struct ValueIter<'ll> {
@@ -75,6 +81,12 @@ pub fn compile_codegen_unit(
     // the time we needed for codegenning it.
     let cost = time_to_codegen.as_nanos() as u64;
 
+    if tcx.sess.opts.debugging_opts.assert_module_determinism {
+        // The dep node of the codegen unit was created by the first codegen.
+        let second_module = tcx.dep_graph.with_ignore(|| module_codegen(tcx, cgu_name));
+        assert_module_determinism(tcx, &module, &second_module);
+    }
+
     fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm> {
         let cgu = tcx.codegen_unit(cgu_name);
         let _prof_timer = tcx.prof.generic_activity_with_args(
@@ -101,6 +113,9 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
                 attributes::sanitize(&cx, SanitizerSet::empty(), entry);
             }
 
+            // Record the calls made in this codegen unit for `-Z stack-depth-report`.
+            codegen_call_graph(&cx, tcx, cgu);
+
             // Run replace-all-uses-with for statics that need it
             for &(old_g, new_g) in cx.statics_to_rauw().borrow().iter() {
                 unsafe {
@@ -140,6 +155,58 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
     (module, cost)
 }
 
+/// Compares the LLVM bitcode of two codegens of the same codegen unit, for
+/// `-Z assert-module-determinism`. If they differ, the LLVM IR of both is written next to the
+/// other temporary outputs and compilation stops.
+fn assert_module_determinism(
+    tcx: TyCtxt<'_>,
+    first: &ModuleCodegen<ModuleLlvm>,
+    second: &ModuleCodegen<ModuleLlvm>,
+) {
+    let first_bitcode = ModuleBuffer::new(first.module_llvm.llmod());
+    let second_bitcode = ModuleBuffer::new(second.module_llvm.llmod());
+    if first_bitcode.data() == second_bitcode.data() {
+        return;
+    }
+
+    extern "C" fn no_demangle(_: *const c_char, _: size_t, _: *mut c_char, _: size_t) -> size_t {
+        0
+    }
+
+    let outputs = tcx.output_filenames(());
+    let write_ir = |module: &ModuleCodegen<ModuleLlvm>, extension: &str| {
+        let path = outputs.temp_path_ext(extension, Some(module.name.as_str()));
+        let path_c = path_to_c_string(&path);
+        let result = unsafe {
+            llvm::LLVMRustPrintModule(module.module_llvm.llmod(), path_c.as_ptr(), no_demangle)
+        };
+        result.into_result().ok().map(|()| path)
+    };
+
+    let mut err =
+        tcx.sess.struct_fatal(&format!("codegen unit `{}` is not deterministic", first.name));
+    if let (Some(first_path), Some(second_path)) =
+        (write_ir(first, "first.ll"), write_ir(second, "second.ll"))
+    {
+        if let (Ok(first_ir), Ok(second_ir)) =
+            (fs::read_to_string(&first_path), fs::read_to_string(&second_path))
+        {
+            let first_difference =
+                first_ir.lines().zip(second_ir.lines()).enumerate().find(|(_, (a, b))| a != b);
+            if let Some((line, (a, b))) = first_difference {
+                err.note(&format!("the LLVM IR first differs on line {}:\n{}\n{}", line + 1, a, b));
+            }
+        }
+        err.note(&format!(
+            "the LLVM IR of both codegens was written to `{}` and `{}`",
+            first_path.display(),
+            second_path.display()
+        ));
+    }
+    err.emit();
+    FatalError.raise()
+}
+
 pub fn set_link_section(llval: &Value, attrs: &CodegenFnAttrs) {
     let sect = match attrs.link_section {
         Some(name) => name,
@@ -134,7 +134,9 @@ pub fn get_fn(cx: &CodegenCx<'ll, 'tcx>, instance: Instance<'tcx>) -> &'ll Value
                     } else {
                         // This is a monomorphization of a generic function
                         // defined in an upstream crate.
-                        if instance.upstream_monomorphization(tcx).is_some() {
+                        if instance.upstream_monomorphization(tcx).is_some()
+                            || instance.shared_generics_provider(tcx).is_some()
+                        {
                             // This is instantiated in another crate. It cannot
                             // be `hidden`.
                         } else {
@@ -21,14 +21,14 @@
 };
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_middle::{bug, span_bug};
-use rustc_session::config::{CFGuard, CrateType, DebugInfo};
+use rustc_session::config::{CFGuard, CrateType, DebugInfo, FunctionReturn};
 use rustc_session::Session;
 use rustc_span::source_map::Span;
 use rustc_span::symbol::Symbol;
 use rustc_target::abi::{
     call::FnAbi, HasDataLayout, PointeeInfo, Size, TargetDataLayout, VariantIdx,
 };
-use rustc_target::spec::{HasTargetSpec, RelocModel, Target, TlsModel};
+use rustc_target::spec::{HasTargetSpec, RelocModel, StackProtector, Target, TlsModel};
 use smallvec::SmallVec;
 
 use std::cell::{Cell, RefCell};
@@ -227,6 +227,32 @@ pub unsafe fn create_module(
         llvm::LLVMRustAddModuleFlag(llmod, canonical_jump_tables, 1);
     }
 
+    // Tells the ThinLTO bitcode writer to split the module, like Clang does with
+    // `-fsplit-lto-unit`, so that the bitcode can be linked with Clang's with LTO.
+    if sess.split_lto_unit() {
+        llvm::LLVMRustSetModuleSplitLTOUnit(llmod);
+    }
+
+    // Let the stack protector load its guard from somewhere else than the target's
+    // default, e.g. a global for kernels that have no usable TLS. Older LLVMs were already
+    // rejected by `llvm_util::configure_llvm`.
+    if sess.stack_protector() != StackProtector::None && llvm_util::get_version() >= (13, 0, 0) {
+        if let Some(guard) = sess.opts.debugging_opts.stack_protector_guard {
+            let kind = guard.desc();
+            llvm::LLVMRustSetModuleStackProtectorGuard(llmod, kind.as_ptr().cast(), kind.len());
+        }
+        if let Some(offset) = sess.opts.debugging_opts.stack_protector_guard_offset {
+            llvm::LLVMRustSetModuleStackProtectorGuardOffset(llmod, offset);
+        }
+    }
+
+    // Lets LLVM add the function return thunk to the functions it creates itself, and keeps it
+    // on the functions of modules merged by LTO.
+    if sess.opts.debugging_opts.function_return == FunctionReturn::ThunkExtern {
+        let thunk_extern = "function_return_thunk_extern\0".as_ptr().cast();
+        llvm::LLVMRustAddModuleFlag(llmod, thunk_extern, 1);
+    }
+
     // Control Flow Guard is currently only supported by the MSVC linker on Windows.
     if sess.target.is_like_msvc {
         match sess.opts.cg.control_flow_guard {
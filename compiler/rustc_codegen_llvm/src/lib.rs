@@ -28,6 +28,7 @@
 use rustc_metadata::EncodedMetadata;
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json::ToJson;
 use rustc_session::config::{OptLevel, OutputFilenames, PrintRequest};
 use rustc_session::Session;
 use rustc_span::symbol::Symbol;
@@ -247,62 +248,42 @@ fn init(&self, sess: &Session) {
     }
 
     fn print(&self, req: PrintRequest, sess: &Session) {
+        let names: &[&str] = match req {
+            PrintRequest::RelocationModels => {
+                &["static", "pic", "pie", "dynamic-no-pic", "ropi", "rwpi", "ropi-rwpi", "default"]
+            }
+            PrintRequest::CodeModels => &["tiny", "small", "kernel", "medium", "large"],
+            PrintRequest::TlsModels => {
+                &["global-dynamic", "local-dynamic", "initial-exec", "local-exec"]
+            }
+            req => return llvm_util::print(req, sess),
+        };
+        if sess.opts.print_json {
+            sess.print_json(req, names.to_json());
+            return;
+        }
         match req {
             PrintRequest::RelocationModels => {
                 println!("Available relocation models:");
-                for name in &[
-                    "static",
-                    "pic",
-                    "pie",
-                    "dynamic-no-pic",
-                    "ropi",
-                    "rwpi",
-                    "ropi-rwpi",
-                    "default",
-                ] {
+                for name in names {
                     println!("    {}", name);
                 }
                 println!();
             }
             PrintRequest::CodeModels => {
                 println!("Available code models:");
-                for name in &["tiny", "small", "kernel", "medium", "large"] {
+                for name in names {
                     println!("    {}", name);
                 }
                 println!();
             }
             PrintRequest::TlsModels => {
                 println!("Available TLS models:");
-                for name in &["global-dynamic", "local-dynamic", "initial-exec", "local-exec"] {
+                for name in names {
                     println!("    {}", name);
                 }
                 println!();
             }
-            PrintRequest::StackProtectorStrategies => {
-                println!(
-                    r#"Available stack protector strategies:
-    all
-        Generate stack canaries in all functions.
-
-    strong
-        Generate stack canaries in a function if it either:
-        - has a local variable of `[T; N]` type, regardless of `T` and `N`
-        - takes the address of a local variable.
-
-          (Note that a local variable being borrowed is not equivalent to its
-          address being taken: e.g. some borrows may be removed by optimization,
-          while by-value argument passing may be implemented with reference to a
-          local stack variable in the ABI.)
-
-    basic
-        Generate stack canaries in functions with:
-        - local variables of `[T; N]` type, where `T` is byte-sized and `N` > 8.
-
-    none
-        Do not generate stack canaries.
-"#
-                );
-            }
             req => llvm_util::print(req, sess),
         }
     }
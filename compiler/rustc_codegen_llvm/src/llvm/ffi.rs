@@ -169,6 +169,8 @@ pub enum Attribute {
     StackProtectReq = 30,
     StackProtectStrong = 31,
     StackProtect = 32,
+    SafeStack = 33,
+    FnRetThunkExtern = 34,
 }
 
 /// LLVMIntPredicate
@@ -1119,6 +1121,11 @@ pub fn LLVMConstExtractValue(
     pub fn LLVMRustGetVisibility(Global: &Value) -> Visibility;
     pub fn LLVMRustSetVisibility(Global: &Value, Viz: Visibility);
     pub fn LLVMRustSetDSOLocal(Global: &Value, is_dso_local: bool);
+    pub fn LLVMRustAddFunctionAnnotation(
+        Fn: &Value,
+        Annotation: *const c_char,
+        AnnotationLen: size_t,
+    );
     pub fn LLVMGetAlignment(Global: &Value) -> c_uint;
     pub fn LLVMSetAlignment(Global: &Value, Bytes: c_uint);
     pub fn LLVMSetDLLStorageClass(V: &Value, C: DLLStorageClass);
@@ -1894,6 +1901,8 @@ pub fn LLVMRustCoverageCreatePGOFuncNameVar(F: &'a Value, FuncName: *const c_cha
     pub fn LLVMRustVersionPatch() -> u32;
 
     pub fn LLVMRustAddModuleFlag(M: &Module, name: *const c_char, value: u32);
+    pub fn LLVMRustSetModuleStackProtectorGuard(M: &Module, kind: *const c_char, kind_len: size_t);
+    pub fn LLVMRustSetModuleStackProtectorGuardOffset(M: &Module, offset: c_int);
 
     pub fn LLVMRustMetadataAsValue(C: &'a Context, MD: &'a Metadata) -> &'a Value;
 
@@ -2218,6 +2227,8 @@ pub fn LLVMRustAddLastExtensionPasses(
     pub fn LLVMRustHasFeature(T: &TargetMachine, s: *const c_char) -> bool;
 
     pub fn LLVMRustPrintTargetCPUs(T: &TargetMachine);
+    pub fn LLVMRustGetTargetCPUsCount(T: &TargetMachine) -> size_t;
+    pub fn LLVMRustGetTargetCPU(T: &TargetMachine, Index: size_t) -> *const c_char;
     pub fn LLVMRustGetTargetFeaturesCount(T: &TargetMachine) -> size_t;
     pub fn LLVMRustGetTargetFeature(
         T: &TargetMachine,
@@ -2297,6 +2308,7 @@ pub fn LLVMRustOptimizeWithNewPassManager(
         PGOUsePath: *const c_char,
         InstrumentCoverage: bool,
         InstrumentGCOV: bool,
+        InstrumentFunctionsPreInline: bool,
         PGOSampleUsePath: *const c_char,
         DebugInfoForProfiling: bool,
         llvm_selfprofiler: *mut c_void,
@@ -2414,6 +2426,7 @@ pub fn LLVMRustBuildOperandBundleDef(
     pub fn LLVMRustUnsetComdat(V: &Value);
     pub fn LLVMRustSetModulePICLevel(M: &Module);
     pub fn LLVMRustSetModulePIELevel(M: &Module);
+    pub fn LLVMRustSetModuleSplitLTOUnit(M: &Module);
     pub fn LLVMRustSetModuleCodeModel(M: &Module, Model: CodeModel);
     pub fn LLVMRustModuleBufferCreate(M: &Module) -> &'static mut ModuleBuffer;
     pub fn LLVMRustModuleBufferPtr(p: &ModuleBuffer) -> *const u8;
@@ -2489,6 +2502,7 @@ pub fn LLVMRustContextConfigureDiagnosticHandler(
         remark_all_passes: bool,
         remark_passes: *const *const c_char,
         remark_passes_len: usize,
+        remark_file_path: *const c_char,
     );
 
 }
@@ -2,14 +2,16 @@
 use crate::{llvm, llvm_util};
 use libc::c_int;
 use libloading::Library;
-use rustc_codegen_ssa::target_features::supported_target_features;
+use rustc_codegen_ssa::target_features::{implied_target_features, supported_target_features};
 use rustc_data_structures::fx::FxHashSet;
 use rustc_fs_util::path_to_c_string;
 use rustc_middle::bug;
-use rustc_session::config::PrintRequest;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{FunctionReturn, PrintRequest, WasmFeature};
 use rustc_session::Session;
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::{MergeFunctions, PanicStrategy};
+use std::collections::BTreeMap;
 use std::ffi::{CStr, CString};
 use tracing::debug;
 
@@ -99,10 +101,53 @@ fn llvm_arg_to_arg_name(full_arg: &str) -> &str {
             }
         }
 
+        if sess.opts.debugging_opts.safestack_use_pointer_address {
+            add("-safestack-use-pointer-address", false);
+        }
+
         if sess.target.os == "emscripten" && sess.panic_strategy() == PanicStrategy::Unwind {
             add("-enable-emscripten-cxx-exceptions", false);
         }
 
+        // The other WebAssembly targets unwind with the exception handling proposal, which
+        // LLVM only lowers unwinding to since LLVM 14.
+        if sess.target.is_like_wasm
+            && sess.target.os != "emscripten"
+            && sess.panic_strategy() == PanicStrategy::Unwind
+            && sess.wasm_exception_handling()
+        {
+            if llvm_util::get_version() < (14, 0, 0) {
+                sess.err(
+                    "`-C panic=unwind` with the `exception-handling` WebAssembly feature \
+                     requires LLVM 14 or later",
+                );
+            } else {
+                add("-wasm-enable-eh", false);
+            }
+        }
+
+        if (sess.opts.debugging_opts.stack_protector_guard.is_some()
+            || sess.opts.debugging_opts.stack_protector_guard_offset.is_some())
+            && llvm_util::get_version() < (13, 0, 0)
+        {
+            sess.err(
+                "`-Z stack-protector-guard` and `-Z stack-protector-guard-offset` \
+                 require LLVM 13 or later",
+            );
+        }
+
+        if sess.wasm_feature(WasmFeature::MultiMemory) == Some(true)
+            && llvm_util::get_version() < (17, 0, 0)
+        {
+            sess.err("the `multi-memory` WebAssembly feature requires LLVM 17 or later");
+        }
+
+        if sess.opts.debugging_opts.function_return != FunctionReturn::Keep
+            && llvm_util::get_version() < (15, 0, 0)
+        {
+            sess.err("`-Z function-return` requires LLVM 15 or later");
+        }
+
         // HACK(eddyb) LLVM inserts `llvm.assume` calls to preserve align attributes
         // during inlining. Unfortunately these may block other optimizations.
         add("-preserve-alignment-assumptions-during-inlining=false", false);
@@ -271,6 +316,24 @@ fn print_target_features(sess: &Session, tm: &llvm::TargetMachine) {
         "crt-static",
         "Enables C Run-time Libraries to be statically linked",
     )]);
+
+    if sess.opts.print_json {
+        let features = rustc_target_features
+            .iter()
+            .map(|feature| (feature, "rustc"))
+            .chain(target_features.iter().map(|feature| (feature, "llvm")))
+            .map(|(&(name, desc), kind)| {
+                let mut obj = BTreeMap::new();
+                obj.insert("name".to_string(), name.to_json());
+                obj.insert("description".to_string(), desc.to_json());
+                obj.insert("kind".to_string(), kind.to_json());
+                Json::Object(obj)
+            })
+            .collect();
+        sess.print_json(PrintRequest::TargetFeatures, Json::Array(features));
+        return;
+    }
+
     let max_feature_len = target_features
         .iter()
         .chain(rustc_target_features.iter())
@@ -295,12 +358,131 @@ fn print_target_features(sess: &Session, tm: &llvm::TargetMachine) {
     println!("and may be renamed or removed in a future version of LLVM or rustc.\n");
 }
 
+/// Prints the features rustc supports for the target as JSON, with the names LLVM knows them by,
+/// their stability and the features they directly imply.
+fn print_target_features_json(sess: &Session) {
+    let implied = implied_target_features(sess);
+    let features = supported_target_features(sess)
+        .iter()
+        .map(|&(feature, gate)| {
+            let implies = implied
+                .iter()
+                .find(|&&(name, _)| name == feature)
+                .map_or(&[][..], |&(_, implies)| implies);
+            let llvm_names = to_llvm_feature(sess, feature);
+            let mut obj = BTreeMap::new();
+            obj.insert("name".to_string(), feature.to_json());
+            obj.insert(
+                "llvm_names".to_string(),
+                Json::Array(llvm_names.iter().map(|name| name.to_json()).collect()),
+            );
+            let stability = if gate.is_none() { "stable" } else { "unstable" };
+            obj.insert("stability".to_string(), stability.to_json());
+            obj.insert("feature_gate".to_string(), gate.map(|gate| gate.to_string()).to_json());
+            obj.insert(
+                "implies".to_string(),
+                Json::Array(implies.iter().map(|name| name.to_json()).collect()),
+            );
+            Json::Object(obj)
+        })
+        .collect();
+    let features = Json::Array(features);
+    if sess.opts.print_json {
+        sess.print_json(PrintRequest::TargetFeaturesJson, features);
+    } else {
+        println!("{}", features.pretty());
+    }
+}
+
+/// Prints the CPUs LLVM knows for the target as JSON, with `native` first if the target is
+/// the host's architecture.
+fn print_target_cpus_json(sess: &Session, tm: &llvm::TargetMachine) {
+    let mut cpus = Vec::new();
+    if sess.target.arch == std::env::consts::ARCH {
+        let mut obj = BTreeMap::new();
+        obj.insert("name".to_string(), "native".to_json());
+        obj.insert("host".to_string(), handle_native("native").to_json());
+        cpus.push(Json::Object(obj));
+    }
+    let len = unsafe { llvm::LLVMRustGetTargetCPUsCount(tm) };
+    for i in 0..len {
+        let cpu = unsafe { llvm::LLVMRustGetTargetCPU(tm, i) };
+        if cpu.is_null() {
+            bug!("LLVM returned a `null` target CPU string");
+        }
+        let cpu = unsafe { CStr::from_ptr(cpu) }.to_str().unwrap_or_else(|e| {
+            bug!("LLVM returned a non-utf8 CPU string: {}", e);
+        });
+        let mut obj = BTreeMap::new();
+        obj.insert("name".to_string(), cpu.to_json());
+        cpus.push(Json::Object(obj));
+    }
+    sess.print_json(PrintRequest::TargetCPUs, Json::Array(cpus));
+}
+
+/// The stack protector strategies, and the functions LLVM protects with each of them.
+const STACK_PROTECTOR_STRATEGIES: &[(&str, &str)] = &[
+    ("all", "every function"),
+    (
+        "strong",
+        "the functions with an array or a value containing one on the stack, a local variable \
+         whose address is taken, or a dynamically sized stack allocation",
+    ),
+    (
+        "basic",
+        "the functions with a byte array of at least 8 bytes or a value containing one on the \
+         stack, or a dynamically sized stack allocation",
+    ),
+    ("none", "no function"),
+];
+
+fn print_stack_protector_strategies(sess: &Session) {
+    let selected = sess.stack_protector().to_string();
+    if sess.opts.print_json {
+        let strategies = STACK_PROTECTOR_STRATEGIES
+            .iter()
+            .map(|(name, protects)| {
+                let mut obj = BTreeMap::new();
+                obj.insert("name".to_string(), name.to_json());
+                obj.insert("protects".to_string(), protects.to_json());
+                obj.insert("selected".to_string(), (selected == *name).to_json());
+                Json::Object(obj)
+            })
+            .collect();
+        sess.print_json(PrintRequest::StackProtectorStrategies, Json::Array(strategies));
+        return;
+    }
+
+    println!("Available stack protector strategies, which `-Z stack-protector` selects:");
+    for (name, protects) in STACK_PROTECTOR_STRATEGIES {
+        let note = if selected == *name { " (selected)" } else { "" };
+        println!("    {}{}: protects {}.", name, note, protects);
+    }
+    println!();
+    println!(
+        "A protected function places a guard value on the stack on entry and aborts the \
+         process if it changed on return, as a buffer overflow would do. Use \
+         `-Z stack-protector-report` to see which functions are protected, and why."
+    );
+    if !sess.target.options.supports_stack_protector {
+        println!();
+        println!(
+            "The `{}` target doesn't support stack smash protection.",
+            sess.opts.target_triple
+        );
+    }
+    println!();
+}
+
 pub(crate) fn print(req: PrintRequest, sess: &Session) {
     require_inited();
     let tm = create_informational_target_machine(sess);
     match req {
+        PrintRequest::TargetCPUs if sess.opts.print_json => print_target_cpus_json(sess, tm),
         PrintRequest::TargetCPUs => unsafe { llvm::LLVMRustPrintTargetCPUs(tm) },
         PrintRequest::TargetFeatures => print_target_features(sess, tm),
+        PrintRequest::TargetFeaturesJson => print_target_features_json(sess),
+        PrintRequest::StackProtectorStrategies => print_stack_protector_strategies(sess),
         _ => bug!("rustc_codegen_llvm can't handle print request: {:?}", req),
     }
 }
@@ -401,6 +583,12 @@ pub fn llvm_global_features(sess: &Session) -> Vec<String> {
     // Features implied by an implicit or explicit `--target`.
     features.extend(sess.target.features.split(',').flat_map(&filter));
 
+    // -Zwasm-features, leaving out `multimemory` before LLVM 17, which doesn't know about it,
+    // and which is an error if it is enabled.
+    features.extend(sess.wasm_target_features().into_iter().filter(|feature| {
+        &feature[1..] != WasmFeature::MultiMemory.llvm_feature() || get_version() >= (17, 0, 0)
+    }));
+
     // -Ctarget-features
     features.extend(sess.opts.cg.target_feature.split(',').flat_map(&filter));
 
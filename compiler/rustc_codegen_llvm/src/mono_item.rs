@@ -6,8 +6,9 @@
 use rustc_codegen_ssa::traits::*;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 pub use rustc_middle::mir::mono::MonoItem;
-use rustc_middle::mir::mono::{Linkage, Visibility};
+use rustc_middle::mir::mono::{InstantiationMode, Linkage, Visibility};
 use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Instance, TypeFoldable};
 use rustc_session::config::CrateType;
 use rustc_target::spec::RelocModel;
@@ -82,6 +83,10 @@ fn predefine_fn(
 
         attributes::from_fn_attrs(self, lldecl, instance);
 
+        if self.tcx.sess.opts.debugging_opts.annotate_llvm_ir {
+            self.annotate_fn(lldecl, instance);
+        }
+
         unsafe {
             if self.should_assume_dso_local(lldecl, false) {
                 llvm::LLVMRustSetDSOLocal(lldecl, true);
@@ -93,6 +98,26 @@ fn predefine_fn(
 }
 
 impl CodegenCx<'ll, 'tcx> {
+    /// Records where a function comes from, for `-Z annotate-llvm-ir`. Each line becomes a
+    /// comment above the function's definition in the emitted LLVM IR.
+    fn annotate_fn(&self, llfn: &'ll llvm::Value, instance: Instance<'tcx>) {
+        let span = self.tcx.def_span(instance.def_id());
+        let provenance = match MonoItem::Fn(instance).instantiation_mode(self.tcx) {
+            InstantiationMode::GloballyShared { .. } => "shared",
+            InstantiationMode::LocalCopy => "local copy",
+        };
+        let lines = [
+            format!("rust item: {}", with_no_trimmed_paths(|| instance.to_string())),
+            format!("rust span: {}", self.sess().source_map().span_to_embeddable_string(span)),
+            format!("rust cgu: {} ({})", self.codegen_unit.name(), provenance),
+        ];
+        for line in &lines {
+            unsafe {
+                llvm::LLVMRustAddFunctionAnnotation(llfn, line.as_ptr().cast(), line.len());
+            }
+        }
+    }
+
     /// Whether a definition or declaration can be assumed to be local to a group of
     /// libraries that form a single DSO or executable.
     pub(crate) unsafe fn should_assume_dso_local(
@@ -3,10 +3,14 @@
 
 use std::ffi::{OsStr, OsString};
 use std::fmt;
-use std::io;
+use std::io::{self, Read};
 use std::mem;
-use std::process::{self, Output};
+use std::process::{self, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use rustc_errors::pluralize;
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::LldFlavor;
 
@@ -95,6 +99,18 @@ pub fn output(&mut self) -> io::Result<Output> {
         self.command().output()
     }
 
+    /// Like `output`, but kills the process and fails with `TimedOut` if it runs for
+    /// longer than `timeout`.
+    pub fn output_with_timeout(&self, timeout: Option<Duration>) -> io::Result<Output> {
+        let child = self
+            .command()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        wait_with_timeout(child, timeout)
+    }
+
     pub fn command(&self) -> process::Command {
         let mut ret = match self.program {
             Program::Normal(ref p) => process::Command::new(p),
@@ -140,7 +156,16 @@ pub fn take_args(&mut self) -> Vec<OsString> {
 
     /// Returns a `true` if we're pretty sure that this'll blow OS spawn limits,
     /// or `false` if we should attempt to spawn and see what the OS says.
-    pub fn very_likely_to_exceed_some_spawn_limit(&self) -> bool {
+    ///
+    /// `threshold` is the `-Z link-arg-file-threshold` value, which replaces the guess
+    /// below on every platform.
+    pub fn very_likely_to_exceed_some_spawn_limit(&self, threshold: Option<usize>) -> bool {
+        if let Some(threshold) = threshold {
+            // Count the separating space and a pair of quotes for each argument.
+            let command_line_len = self.args.iter().map(|a| a.len() + 3).sum::<usize>();
+            return command_line_len > threshold;
+        }
+
         // We mostly only care about Windows in this method, on Unix the limits
         // can be gargantuan anyway so we're pretty unlikely to hit them
         if cfg!(unix) {
@@ -182,6 +207,83 @@ pub fn very_likely_to_exceed_some_spawn_limit(&self) -> bool {
     }
 }
 
+/// The error of a tool that `wait_with_timeout` killed, with the output it wrote until then.
+#[derive(Debug)]
+pub struct TimedOut {
+    pub timeout: Duration,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.timeout.as_secs();
+        write!(f, "timed out after {} second{}", secs, pluralize!(secs))
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Like `Child::wait_with_output`, but kills the child and fails with `TimedOut` if it runs
+/// for longer than `timeout`. The error carries a `TimedOut` with the output so far.
+pub fn wait_with_timeout(
+    mut child: process::Child,
+    timeout: Option<Duration>,
+) -> io::Result<Output> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return child.wait_with_output(),
+    };
+
+    // Drain the pipes on other threads, so that a child filling them up doesn't block
+    // until it is killed. The output is shared, so that it can be reported on a timeout
+    // without waiting for the threads.
+    fn drain(
+        pipe: Option<impl Read + Send + 'static>,
+    ) -> (Arc<Mutex<Vec<u8>>>, thread::JoinHandle<io::Result<()>>) {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let thread_buf = buf.clone();
+        let thread = thread::spawn(move || {
+            if let Some(mut pipe) = pipe {
+                let mut chunk = [0; 4096];
+                loop {
+                    match pipe.read(&mut chunk) {
+                        Ok(0) => break,
+                        Ok(n) => thread_buf.lock().unwrap().extend_from_slice(&chunk[..n]),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            Ok(())
+        });
+        (buf, thread)
+    }
+    let (stdout, stdout_thread) = drain(child.stdout.take());
+    let (stderr, stderr_thread) = drain(child.stderr.take());
+    let take = |buf: &Mutex<Vec<u8>>| mem::take(&mut *buf.lock().unwrap());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // The reader threads are left behind, as grandchildren of the tool may still
+            // hold the pipes open.
+            let _ = child.kill();
+            let _ = child.wait();
+            let timed_out = TimedOut { timeout, stdout: take(&stdout), stderr: take(&stderr) };
+            return Err(io::Error::new(io::ErrorKind::TimedOut, timed_out));
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    stdout_thread.join().unwrap()?;
+    stderr_thread.join().unwrap()?;
+    Ok(Output { status, stdout: take(&stdout), stderr: take(&stderr) })
+}
+
 impl fmt::Debug for Command {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.command().fmt(f)
@@ -1,11 +1,13 @@
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::temp_dir::MaybeTempDir;
-use rustc_errors::{ErrorReported, Handler};
+use rustc_errors::{DiagnosticBuilder, ErrorReported, FatalError, Handler};
 use rustc_fs_util::fix_windows_verbatim_for_gcc;
 use rustc_hir::def_id::CrateNum;
 use rustc_middle::middle::dependency_format::Linkage;
-use rustc_session::config::{self, CFGuard, CrateType, DebugInfo, LdImpl, Strip};
-use rustc_session::config::{OutputFilenames, OutputType, PrintRequest};
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{self, BinarySizeReport, CFGuard, CrateType, DebugInfo, Hotpatch};
+use rustc_session::config::{LdImpl, Strip};
+use rustc_session::config::{OutputFilenames, OutputType, PrintRequest, StopAfter, WasiComponent};
 use rustc_session::cstore::DllImport;
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::search_paths::PathKind;
@@ -19,10 +21,12 @@
 use rustc_target::spec::{PanicStrategy, RelocModel, RelroLevel, SanitizerSet, Target};
 
 use super::archive::{find_library, ArchiveBuilder};
-use super::command::Command;
+use super::command::{wait_with_timeout, Command, TimedOut};
 use super::linker::{self, Linker};
 use super::metadata::create_rmeta_file;
 use super::rpath::{self, RPathConfig};
+use super::size_report::{print_binary_size_report, print_size_report};
+use super::stack_depth::write_stack_depth_report;
 use crate::{
     looks_like_rust_object_file, CodegenResults, CompiledModule, CrateInfo, NativeLib,
     METADATA_FILENAME,
@@ -32,12 +36,20 @@
 use regex::Regex;
 use tempfile::Builder as TempFileBuilder;
 
-use std::ffi::OsString;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::{OsStr, OsString};
 use std::lazy::OnceCell;
 use std::path::{Path, PathBuf};
 use std::process::{ExitStatus, Output, Stdio};
+use std::time::Duration;
 use std::{ascii, char, env, fmt, fs, io, mem, str};
 
+mod platform;
+mod resolve;
+
+pub use self::platform::*;
+pub use self::resolve::*;
+
 pub fn ensure_removed(diag_handler: &Handler, path: &Path) {
     if let Err(e) = fs::remove_file(path) {
         if e.kind() != io::ErrorKind::NotFound {
@@ -55,9 +67,22 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
 ) -> Result<(), ErrorReported> {
     let _timer = sess.timer("link_binary");
     let output_metadata = sess.opts.output_types.contains_key(&OutputType::Metadata);
+    // A link plan is run after this session ends, so it needs the temporary files of the
+    // crate types that are linked natively. Rlibs and staticlibs are done with them here.
+    let linked_natively = |ty: CrateType| !matches!(ty, CrateType::Rlib | CrateType::Staticlib);
+    let link_plan = sess.opts.prints.contains(&PrintRequest::LinkPlan)
+        && sess.crate_types().iter().any(|&ty| linked_natively(ty));
+    let size_report = sess.opts.debugging_opts.binary_size_report.filter(|report| report.linked);
+    if size_report.is_some()
+        && sess.crate_types().iter().all(|ty| matches!(ty, CrateType::Rlib | CrateType::Staticlib))
+    {
+        sess.warn(
+            "`-Z binary-size-report=linked` only reports on executables and dynamic libraries",
+        );
+    }
     for &crate_type in sess.crate_types().iter() {
-        // Ignore executable crates if we have -Z no-codegen, as they will error.
-        if (sess.opts.debugging_opts.no_codegen || !sess.opts.output_types.should_codegen())
+        // Ignore executable crates if we have -Z stop-after=analysis, as they will error.
+        if (sess.opts.stops_after(StopAfter::Analysis) || !sess.opts.output_types.should_codegen())
             && !output_metadata
             && crate_type == CrateType::Executable
         {
@@ -78,12 +103,22 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
             }
         });
 
-        if outputs.outputs.should_link() {
-            let tmpdir = TempFileBuilder::new()
-                .prefix("rustc")
-                .tempdir()
+        // Nothing is linked when stopping after codegen, or an earlier stage.
+        if outputs.outputs.should_link() && !sess.opts.stops_after(StopAfter::Codegen) {
+            // The system temp directory is generally outside the output sandbox,
+            // so keep intermediate files next to the outputs instead.
+            let mut builder = TempFileBuilder::new();
+            builder.prefix("rustc");
+            let tmpdir = if sess.output_sandbox.is_some() {
+                builder.tempdir_in(&outputs.out_directory)
+            } else {
+                builder.tempdir()
+            };
+            let tmpdir = tmpdir
                 .unwrap_or_else(|err| sess.fatal(&format!("couldn't create a temp dir: {}", err)));
-            let path = MaybeTempDir::new(tmpdir, sess.opts.cg.save_temps);
+            let keep_temps = sess.opts.cg.save_temps || (link_plan && linked_natively(crate_type));
+            let path =
+                MaybeTempDir::new(tmpdir, keep_temps).keep_on_error(sess.keep_temps_on_error());
             let out_filename = out_filename(
                 sess,
                 crate_type,
@@ -113,8 +148,19 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
                         codegen_results,
                         path.as_ref(),
                     );
+                    if crate_type == CrateType::Executable {
+                        maybe_write_stack_depth_report(sess, crate_type, &out_filename);
+                    }
+                    if let Some(report) = size_report {
+                        let _timer = sess.timer("print_size_report");
+                        let format = report.format;
+                        print_size_report(sess, format, crate_type, &out_filename, codegen_results);
+                    }
                 }
             }
+            sess.record_artifact(&out_filename, OutputType::Exe.shorthand());
+            // Only kept with `-C save-temps`.
+            sess.record_artifact(path.as_ref(), "temp");
             if sess.opts.json_artifact_notifications {
                 sess.parse_sess.span_diagnostic.emit_artifact_notification(&out_filename, "link");
             }
@@ -132,33 +178,30 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
                 }
             }
         }
-    }
-
-    // Remove the temporary object file and metadata if we aren't saving temps
-    sess.time("link_binary_remove_temps", || {
-        if !sess.opts.cg.save_temps {
-            let remove_temps_from_module = |module: &CompiledModule| {
-                if let Some(ref obj) = module.object {
-                    ensure_removed(sess.diagnostic(), obj);
-                }
 
-                if let Some(ref obj) = module.dwarf_object {
-                    ensure_removed(sess.diagnostic(), obj);
-                }
-            };
+        if sess.opts.prints.contains(&PrintRequest::ExportedSymbols) {
+            print_exported_symbols(sess, crate_type, codegen_results);
+        }
+    }
 
-            if sess.opts.output_types.should_link() && !preserve_objects_for_their_debuginfo(sess) {
-                for module in &codegen_results.modules {
-                    remove_temps_from_module(module);
-                }
-            }
+    if let Some(BinarySizeReport { linked: false, format }) =
+        sess.opts.debugging_opts.binary_size_report
+    {
+        print_binary_size_report(sess, format, codegen_results);
+    }
 
-            if let Some(ref metadata_module) = codegen_results.metadata_module {
-                remove_temps_from_module(metadata_module);
-            }
+    if sess.opts.output_types.contains_key(&OutputType::ExportedSymbols) {
+        emit_exported_symbols(sess, codegen_results, outputs);
+    }
 
-            if let Some(ref allocator_module) = codegen_results.allocator_module {
-                remove_temps_from_module(allocator_module);
+    // Remove the temporary object file and metadata if we aren't saving temps
+    sess.time("link_binary_remove_temps", || {
+        if !sess.opts.cg.save_temps
+            && !(sess.keep_temps_on_error() && sess.has_errors())
+            && !link_plan
+        {
+            for obj in temp_objects(sess, codegen_results) {
+                ensure_removed(sess.diagnostic(), &obj);
             }
         }
     });
@@ -166,6 +209,32 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
     Ok(())
 }
 
+/// Returns the object files that are removed once linking is done, unless they are kept
+/// with `-C save-temps`.
+fn temp_objects(sess: &Session, codegen_results: &CodegenResults) -> Vec<PathBuf> {
+    let mut objects = Vec::new();
+    let mut add_module = |module: &CompiledModule| {
+        objects.extend(module.object.clone());
+        objects.extend(module.dwarf_object.clone());
+    };
+
+    if sess.opts.output_types.should_link() && !preserve_objects_for_their_debuginfo(sess) {
+        for module in &codegen_results.modules {
+            add_module(module);
+        }
+    }
+
+    if let Some(ref metadata_module) = codegen_results.metadata_module {
+        add_module(metadata_module);
+    }
+
+    if let Some(ref allocator_module) = codegen_results.allocator_module {
+        add_module(allocator_module);
+    }
+
+    objects
+}
+
 pub fn each_linked_rlib(
     info: &CrateInfo,
     f: &mut dyn FnMut(CrateNum, &Path),
@@ -517,14 +586,18 @@ fn link_dwarf_object<'a>(sess: &'a Session, executable_out_filename: &Path) {
     cmd.arg(&dwp_out_filename);
 
     let mut new_path = sess.get_tools_search_paths(false);
-    if let Some(path) = env::var_os("PATH") {
+    if let Some(path) = sess.env_var_os("PATH") {
         new_path.extend(env::split_paths(&path));
     }
+    sess.record_tool_lookup(Path::new(LLVM_DWP_EXECUTABLE), &new_path);
     let new_path = env::join_paths(new_path).unwrap();
     cmd.env("PATH", new_path);
 
     info!("{:?}", &cmd);
-    match sess.time("run_dwp", || cmd.output()) {
+    let prog = sess.time("run_dwp", || {
+        run_tool(sess, LLVM_DWP_EXECUTABLE, || cmd.output_with_timeout(tool_timeout(sess)))
+    });
+    match prog {
         Ok(prog) if !prog.status.success() => {
             sess.struct_err(&format!(
                 "linking dwarf objects with `{}` failed: {}",
@@ -551,6 +624,7 @@ fn link_dwarf_object<'a>(sess: &'a Session, executable_out_filename: &Path) {
             if !dwp_not_found {
                 err.note(&format!("{:?}", &cmd));
             }
+            note_timed_out_output(&mut err, &e);
 
             err.emit();
         }
@@ -569,7 +643,7 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
     tmpdir: &Path,
 ) {
     info!("preparing {:?} to {:?}", crate_type, out_filename);
-    let (linker_path, flavor) = linker_and_flavor(sess);
+    let ResolvedLinker { linker: linker_path, flavor, steps: linker_steps } = resolve_linker(sess);
     let mut cmd = linker_with_args::<B>(
         &linker_path,
         flavor,
@@ -596,16 +670,24 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
     // May have not found libraries in the right formats.
     sess.abort_if_errors();
 
+    if sess.opts.prints.contains(&PrintRequest::LinkPlan) {
+        print_link_plan(sess, &cmd, out_filename, codegen_results, tmpdir);
+        return;
+    }
+
     // Invoke the system linker
     info!("{:?}", &cmd);
-    let retry_on_segfault = env::var("RUSTC_RETRY_LINKER_ON_SEGFAULT").is_ok();
+    let retry_on_segfault = sess.env_var("RUSTC_RETRY_LINKER_ON_SEGFAULT").is_ok();
     let unknown_arg_regex =
         Regex::new(r"(unknown|unrecognized) (command line )?(option|argument)").unwrap();
     let mut prog;
     let mut i = 0;
     loop {
         i += 1;
-        prog = sess.time("run_linker", || exec_linker(sess, &cmd, out_filename, tmpdir));
+        prog = sess.time("run_linker", || {
+            let linker = linker_path.display().to_string();
+            run_tool(sess, &linker, || exec_linker(sess, &cmd, out_filename, tmpdir))
+        });
         let output = match prog {
             Ok(ref output) => output,
             Err(_) => break,
@@ -842,10 +924,22 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
 
             linker_error.note(&e.to_string());
 
-            if !linker_not_found {
+            if linker_not_found {
+                let mut steps = linker_steps;
+                locate_linker(sess, &linker_path, flavor, &mut steps);
+                for step in steps {
+                    linker_error.note(&step);
+                }
+                linker_error.help(
+                    "pass `-C linker=<path>` to use a different linker, or \
+                     `--print resolved-linker -Z unstable-options` to see how it was chosen",
+                );
+            } else {
                 linker_error.note(&format!("{:?}", &cmd));
             }
 
+            note_timed_out_output(&mut linker_error, &e);
+
             linker_error.emit();
 
             if sess.target.is_like_msvc && linker_not_found {
@@ -875,7 +969,9 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
         // debug information. Note that this will read debug information from
         // the objects on the filesystem which we'll clean up later.
         SplitDebuginfo::Packed if sess.target.is_like_osx => {
-            let prog = Command::new("dsymutil").arg(out_filename).output();
+            let mut cmd = Command::new("dsymutil");
+            cmd.arg(out_filename);
+            let prog = run_tool(sess, "dsymutil", || cmd.output_with_timeout(tool_timeout(sess)));
             match prog {
                 Ok(prog) => {
                     if !prog.status.success() {
@@ -885,11 +981,18 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
                             "processing debug info with `dsymutil` failed: {}",
                             prog.status
                         ))
+                        .note(&format!("{:?}", &cmd))
                         .note(&escape_string(&output))
                         .emit();
                     }
                 }
-                Err(e) => sess.fatal(&format!("unable to run `dsymutil`: {}", e)),
+                Err(e) => {
+                    let mut err = sess.struct_fatal(&format!("unable to run `dsymutil`: {}", e));
+                    err.note(&format!("{:?}", &cmd));
+                    note_timed_out_output(&mut err, &e);
+                    err.emit();
+                    FatalError.raise();
+                }
             }
         }
 
@@ -912,6 +1015,27 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
     }
 }
 
+/// Writes the report of `-Z stack-depth-report`, which needs the stack sizes of all the
+/// functions of the executable, and so is only written for statically linked ELF executables
+/// that keep their symbols.
+fn maybe_write_stack_depth_report(sess: &Session, crate_type: CrateType, out_filename: &Path) {
+    let path = match sess.opts.debugging_opts.stack_depth_report {
+        Some(ref path) => path,
+        None => return,
+    };
+    let reason = if !sess.target_produces_elf() {
+        "the target doesn't produce ELF executables"
+    } else if !sess.crt_static(Some(crate_type)) {
+        "the executable isn't statically linked"
+    } else if strip_value(sess) == Strip::Symbols {
+        "the symbols of the executable are stripped"
+    } else {
+        let _timer = sess.timer("write_stack_depth_report");
+        return write_stack_depth_report(sess, out_filename, path);
+    };
+    sess.warn(&format!("no stack depth report is written: {}", reason));
+}
+
 // Temporarily support both -Z strip and -C strip
 fn strip_value(sess: &Session) -> Strip {
     match (sess.opts.debugging_opts.strip, sess.opts.cg.strip) {
@@ -925,7 +1049,8 @@ fn strip_symbols_in_osx<'a>(sess: &'a Session, out_filename: &Path, option: Opti
     if let Some(option) = option {
         cmd.arg(option);
     }
-    let prog = cmd.arg(out_filename).output();
+    cmd.arg(out_filename);
+    let prog = run_tool(sess, "strip", || cmd.output_with_timeout(tool_timeout(sess)));
     match prog {
         Ok(prog) => {
             if !prog.status.success() {
@@ -935,11 +1060,61 @@ fn strip_symbols_in_osx<'a>(sess: &'a Session, out_filename: &Path, option: Opti
                     "stripping debug info with `strip` failed: {}",
                     prog.status
                 ))
+                .note(&format!("{:?}", &cmd))
                 .note(&escape_string(&output))
                 .emit();
             }
         }
-        Err(e) => sess.fatal(&format!("unable to run `strip`: {}", e)),
+        Err(e) => {
+            let mut err = sess.struct_fatal(&format!("unable to run `strip`: {}", e));
+            err.note(&format!("{:?}", &cmd));
+            note_timed_out_output(&mut err, &e);
+            err.emit();
+            FatalError.raise();
+        }
+    }
+}
+
+/// Returns the `-Z external-tool-timeout` limit on how long external tools may run.
+fn tool_timeout(sess: &Session) -> Option<Duration> {
+    sess.opts.debugging_opts.external_tool_timeout.map(Duration::from_secs)
+}
+
+/// Runs an external tool with `run`, retrying up to `-Z external-tool-retries` times if it
+/// fails to start or times out. Tools that can't be found aren't retried.
+fn run_tool(
+    sess: &Session,
+    name: &str,
+    mut run: impl FnMut() -> io::Result<Output>,
+) -> io::Result<Output> {
+    let mut retries = sess.opts.debugging_opts.external_tool_retries;
+    loop {
+        match run() {
+            Err(e) if retries > 0 && e.kind() != io::ErrorKind::NotFound => {
+                retries -= 1;
+                sess.warn(&format!(
+                    "running `{}` failed: {}; retrying ({} {} left)",
+                    name,
+                    e,
+                    retries,
+                    if retries == 1 { "retry" } else { "retries" }
+                ));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Adds the output of a tool that `-Z external-tool-timeout` killed to `err`, if `e` is
+/// such a timeout.
+fn note_timed_out_output(err: &mut DiagnosticBuilder<'_>, e: &io::Error) {
+    if let Some(timed_out) = e.get_ref().and_then(|e| e.downcast_ref::<TimedOut>()) {
+        let mut output = timed_out.stderr.clone();
+        output.extend_from_slice(&timed_out.stdout);
+        if !output.is_empty() {
+            err.note(&escape_stdout_stderr_string(&output));
+        }
+        err.help("the time limit is set with `-Z external-tool-timeout`");
     }
 }
 
@@ -1040,95 +1215,6 @@ pub fn ignored_for_lto(sess: &Session, info: &CrateInfo, cnum: CrateNum) -> bool
         && (info.compiler_builtins == Some(cnum) || info.is_no_builtins.contains(&cnum))
 }
 
-// This functions tries to determine the appropriate linker (and corresponding LinkerFlavor) to use
-pub fn linker_and_flavor(sess: &Session) -> (PathBuf, LinkerFlavor) {
-    fn infer_from(
-        sess: &Session,
-        linker: Option<PathBuf>,
-        flavor: Option<LinkerFlavor>,
-    ) -> Option<(PathBuf, LinkerFlavor)> {
-        match (linker, flavor) {
-            (Some(linker), Some(flavor)) => Some((linker, flavor)),
-            // only the linker flavor is known; use the default linker for the selected flavor
-            (None, Some(flavor)) => Some((
-                PathBuf::from(match flavor {
-                    LinkerFlavor::Em => {
-                        if cfg!(windows) {
-                            "emcc.bat"
-                        } else {
-                            "emcc"
-                        }
-                    }
-                    LinkerFlavor::Gcc => {
-                        if cfg!(any(target_os = "solaris", target_os = "illumos")) {
-                            // On historical Solaris systems, "cc" may have
-                            // been Sun Studio, which is not flag-compatible
-                            // with "gcc".  This history casts a long shadow,
-                            // and many modern illumos distributions today
-                            // ship GCC as "gcc" without also making it
-                            // available as "cc".
-                            "gcc"
-                        } else {
-                            "cc"
-                        }
-                    }
-                    LinkerFlavor::Ld => "ld",
-                    LinkerFlavor::Msvc => "link.exe",
-                    LinkerFlavor::Lld(_) => "lld",
-                    LinkerFlavor::PtxLinker => "rust-ptx-linker",
-                    LinkerFlavor::BpfLinker => "bpf-linker",
-                }),
-                flavor,
-            )),
-            (Some(linker), None) => {
-                let stem = linker.file_stem().and_then(|stem| stem.to_str()).unwrap_or_else(|| {
-                    sess.fatal("couldn't extract file stem from specified linker")
-                });
-
-                let flavor = if stem == "emcc" {
-                    LinkerFlavor::Em
-                } else if stem == "gcc"
-                    || stem.ends_with("-gcc")
-                    || stem == "clang"
-                    || stem.ends_with("-clang")
-                {
-                    LinkerFlavor::Gcc
-                } else if stem == "wasm-ld" || stem.ends_with("-wasm-ld") {
-                    LinkerFlavor::Lld(LldFlavor::Wasm)
-                } else if stem == "ld" || stem == "ld.lld" || stem.ends_with("-ld") {
-                    LinkerFlavor::Ld
-                } else if stem == "link" || stem == "lld-link" {
-                    LinkerFlavor::Msvc
-                } else if stem == "lld" || stem == "rust-lld" {
-                    LinkerFlavor::Lld(sess.target.lld_flavor)
-                } else {
-                    // fall back to the value in the target spec
-                    sess.target.linker_flavor
-                };
-
-                Some((linker, flavor))
-            }
-            (None, None) => None,
-        }
-    }
-
-    // linker and linker flavor specified via command line have precedence over what the target
-    // specification specifies
-    if let Some(ret) = infer_from(sess, sess.opts.cg.linker.clone(), sess.opts.cg.linker_flavor) {
-        return ret;
-    }
-
-    if let Some(ret) = infer_from(
-        sess,
-        sess.target.linker.clone().map(PathBuf::from),
-        Some(sess.target.linker_flavor),
-    ) {
-        return ret;
-    }
-
-    bug!("Not enough information provided to determine how to invoke the linker");
-}
-
 /// Returns a boolean indicating whether we should preserve the object files on
 /// the filesystem for their debug information. This is often useful with
 /// split-dwarf like schemes.
@@ -1190,7 +1276,9 @@ fn print_native_static_libs(sess: &Session, all_native_libs: &[NativeLib]) {
             }
         })
         .collect();
-    if !lib_args.is_empty() {
+    if sess.opts.print_json {
+        sess.print_json(PrintRequest::NativeStaticLibs, lib_args.to_json());
+    } else if !lib_args.is_empty() {
         sess.note_without_error(
             "Link against the following native artifacts when linking \
                                  against this static library. The order and any duplication \
@@ -1201,6 +1289,242 @@ fn print_native_static_libs(sess: &Session, all_native_libs: &[NativeLib]) {
     }
 }
 
+/// Returns the sorted symbols exported from the artifact of the given crate type, or `None`
+/// for rlibs, whose Rust symbols are only exported once they are linked into something else.
+fn sorted_exported_symbols(
+    sess: &Session,
+    crate_type: CrateType,
+    codegen_results: &CodegenResults,
+) -> Option<Vec<String>> {
+    let symbols = match crate_type {
+        CrateType::Rlib => return None,
+        // Executables only export what the target requires them to.
+        CrateType::Executable if sess.target.override_export_symbols.is_none() => &[][..],
+        _ => &codegen_results.crate_info.exported_symbols[&crate_type][..],
+    };
+    let mut symbols = symbols.to_vec();
+    symbols.sort();
+    Some(symbols)
+}
+
+/// Prints the symbols exported from the artifact of the given crate type as a single line
+/// of JSON, e.g. `{"crate_type":"cdylib","symbols":["foo"]}`.
+fn print_exported_symbols(sess: &Session, crate_type: CrateType, codegen_results: &CodegenResults) {
+    let symbols = match sorted_exported_symbols(sess, crate_type, codegen_results) {
+        Some(symbols) => symbols,
+        None => return,
+    };
+
+    let mut obj = BTreeMap::new();
+    obj.insert("crate_type".to_string(), crate_type.to_string().to_json());
+    obj.insert("symbols".to_string(), symbols.to_json());
+    if sess.opts.print_json {
+        sess.print_json(PrintRequest::ExportedSymbols, Json::Object(obj));
+    } else {
+        println!("{}", Json::Object(obj));
+    }
+}
+
+/// Writes the symbols exported for each crate type to the `--emit exported-symbols` file, as
+/// a JSON object mapping crate types to their symbols, e.g. `{"cdylib":["foo"]}`.
+fn emit_exported_symbols(
+    sess: &Session,
+    codegen_results: &CodegenResults,
+    outputs: &OutputFilenames,
+) {
+    let mut obj = BTreeMap::new();
+    for &crate_type in sess.crate_types().iter() {
+        if let Some(symbols) = sorted_exported_symbols(sess, crate_type, codegen_results) {
+            obj.insert(crate_type.to_string(), symbols.to_json());
+        }
+    }
+
+    let path = outputs.path(OutputType::ExportedSymbols);
+    if let Err(e) = fs::write(&path, Json::Object(obj).to_string()) {
+        sess.fatal(&format!("could not emit exported symbols to `{}`: {}", path.display(), e));
+    }
+    sess.record_artifact(&path, OutputType::ExportedSymbols.shorthand());
+}
+
+/// Prints the linker invocation as a line of JSON for `--print link-plan`, instead of running
+/// it. The plan can be run later with `-Z run-link-plan`.
+fn print_link_plan(
+    sess: &Session,
+    cmd: &Command,
+    out_filename: &Path,
+    codegen_results: &CodegenResults,
+    tmpdir: &Path,
+) {
+    let plan_str = |s: &OsStr| match s.to_str() {
+        Some(s) => s.to_string(),
+        None => sess.fatal(&format!("cannot write the non-UTF-8 string {:?} to a link plan", s)),
+    };
+
+    let command = cmd.command();
+    let args: Vec<String> = command.get_args().map(plan_str).collect();
+    // `null` removes a variable from the linker's environment.
+    let env: BTreeMap<String, Json> = command
+        .get_envs()
+        .map(|(key, value)| (plan_str(key), value.map_or(Json::Null, |v| plan_str(v).to_json())))
+        .collect();
+    // Build systems use these to schedule the link and dedupe shared inputs.
+    let inputs: Vec<String> = args.iter().filter(|arg| Path::new(arg).is_file()).cloned().collect();
+    // Everything the session would have removed after linking.
+    let mut temps = Vec::new();
+    if !sess.opts.cg.save_temps {
+        temps.push(plan_str(tmpdir.as_os_str()));
+        temps.extend(
+            temp_objects(sess, codegen_results).iter().map(|obj| plan_str(obj.as_os_str())),
+        );
+    }
+
+    // Post-processing of the linked output needs the output, so it can't be planned.
+    let post_processed = (sess.split_debuginfo() == SplitDebuginfo::Packed
+        && sess.opts.debuginfo != DebugInfo::None
+        && !sess.target.is_like_msvc)
+        || (sess.target.is_like_osx && strip_value(sess) != Strip::None);
+    if post_processed {
+        sess.warn(
+            "the link plan does not include processing the linked output with external tools",
+        );
+    }
+
+    let mut plan = BTreeMap::new();
+    plan.insert("target".to_string(), sess.opts.target_triple.to_string().to_json());
+    plan.insert("program".to_string(), plan_str(command.get_program()).to_json());
+    plan.insert("args".to_string(), args.to_json());
+    plan.insert("env".to_string(), Json::Object(env));
+    plan.insert("inputs".to_string(), inputs.to_json());
+    plan.insert("output".to_string(), plan_str(out_filename.as_os_str()).to_json());
+    plan.insert("tmpdir".to_string(), plan_str(tmpdir.as_os_str()).to_json());
+    plan.insert("temps".to_string(), temps.to_json());
+    if sess.opts.print_json {
+        sess.print_json(PrintRequest::LinkPlan, Json::Object(plan));
+    } else {
+        println!("{}", Json::Object(plan));
+    }
+}
+
+/// Runs the linker invocations printed by `--print link-plan`, one JSON line each, for
+/// `-Z run-link-plan`, and removes the temporary files the compiler kept for them. The plans
+/// of one session share its object files, so those are only removed once all plans linked.
+pub fn run_link_plan(sess: &Session, plans: &str) -> Result<(), ErrorReported> {
+    let mut temps = BTreeSet::new();
+    let mut found_plan = false;
+    for line in plans.lines().filter(|line| !line.trim().is_empty()) {
+        let plan = match line.parse::<Json>() {
+            Ok(Json::Object(plan)) => plan,
+            Ok(_) => {
+                sess.err("failed to decode link plan: expected a JSON object");
+                return Err(ErrorReported);
+            }
+            Err(err) => {
+                sess.err(&format!("failed to decode link plan: {}", err));
+                return Err(ErrorReported);
+            }
+        };
+        // With `--print-json` the plans are wrapped, and mixed with other print requests.
+        let plan = match plan.get("print").map(Json::as_string) {
+            None => plan,
+            Some(Some(name)) if name != PrintRequest::LinkPlan.name() => continue,
+            Some(_) => match plan.get("value") {
+                Some(Json::Object(plan)) => plan.clone(),
+                _ => {
+                    sess.err("failed to decode link plan: expected a JSON object");
+                    return Err(ErrorReported);
+                }
+            },
+        };
+        found_plan = true;
+        temps.extend(run_single_link_plan(sess, &plan)?);
+    }
+    if !found_plan {
+        sess.err("the input contains no link plan");
+        return Err(ErrorReported);
+    }
+
+    for temp in temps {
+        let temp = Path::new(&temp);
+        if temp.is_dir() {
+            if let Err(e) = fs::remove_dir_all(temp) {
+                sess.err(&format!("failed to remove {}: {}", temp.display(), e));
+            }
+        } else {
+            ensure_removed(sess.diagnostic(), temp);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the linker invocation of a single link plan, and returns the temporary files to remove
+/// afterwards.
+fn run_single_link_plan(
+    sess: &Session,
+    plan: &BTreeMap<String, Json>,
+) -> Result<Vec<String>, ErrorReported> {
+    let invalid = |field: &str| {
+        sess.err(&format!("invalid link plan: missing or malformed `{}`", field));
+        ErrorReported
+    };
+    let string =
+        |field: &str| plan.get(field).and_then(Json::as_string).ok_or_else(|| invalid(field));
+    let strings = |field: &str| {
+        plan.get(field)
+            .and_then(Json::as_array)
+            .and_then(|array| array.iter().map(Json::as_string).collect::<Option<Vec<_>>>())
+            .ok_or_else(|| invalid(field))
+    };
+
+    let target = string("target")?;
+    if target != sess.opts.target_triple.to_string() {
+        sess.err(&format!(
+            "the link plan is for target `{}`, not `{}`",
+            target, sess.opts.target_triple
+        ));
+        return Err(ErrorReported);
+    }
+
+    let program = string("program")?;
+    let mut cmd = Command::new(program);
+    cmd.args(strings("args")?);
+    for (key, value) in plan.get("env").and_then(Json::as_object).ok_or_else(|| invalid("env"))? {
+        match value {
+            Json::String(value) => cmd.env(key, value),
+            Json::Null => cmd.env_remove(key),
+            _ => return Err(invalid("env")),
+        };
+    }
+    let out_filename = Path::new(string("output")?);
+    let tmpdir = Path::new(string("tmpdir")?);
+    let temps = strings("temps")?;
+
+    info!("{:?}", &cmd);
+    let prog = sess.time("run_linker", || {
+        run_tool(sess, program, || exec_linker(sess, &cmd, out_filename, tmpdir))
+    });
+    match prog {
+        Ok(prog) if prog.status.success() => {}
+        Ok(prog) => {
+            let mut output = prog.stderr.clone();
+            output.extend_from_slice(&prog.stdout);
+            sess.struct_err(&format!("linking with `{}` failed: {}", program, prog.status))
+                .note(&format!("{:?}", &cmd))
+                .note(&escape_stdout_stderr_string(&output))
+                .emit();
+            return Err(ErrorReported);
+        }
+        Err(e) => {
+            let mut err = sess.struct_err(&format!("could not exec the linker `{}`", program));
+            err.note(&e.to_string());
+            err.note(&format!("{:?}", &cmd));
+            note_timed_out_output(&mut err, &e);
+            err.emit();
+            return Err(ErrorReported);
+        }
+    }
+    Ok(temps.into_iter().map(str::to_string).collect())
+}
+
 fn get_object_file_path(sess: &Session, name: &str, self_contained: bool) -> PathBuf {
     let fs = sess.target_filesearch(PathKind::Native);
     let file_path = fs.get_lib_path().join(name);
@@ -1238,10 +1562,11 @@ fn exec_linker(
     // that contains all the arguments. The theory is that this is then
     // accepted on all linkers and the linker will read all its options out of
     // there instead of looking at the command line.
-    if !cmd.very_likely_to_exceed_some_spawn_limit() {
+    let threshold = sess.opts.debugging_opts.link_arg_file_threshold;
+    if !cmd.very_likely_to_exceed_some_spawn_limit(threshold) {
         match cmd.command().stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
             Ok(child) => {
-                let output = child.wait_with_output();
+                let output = wait_with_timeout(child, tool_timeout(sess));
                 flush_linked_file(&output, out_filename)?;
                 return output;
             }
@@ -1276,9 +1601,12 @@ fn exec_linker(
         args.into_bytes()
     };
     fs::write(&file, &bytes)?;
+    // Linkers other than `link.exe` don't understand verbatim paths, which the
+    // temporary directory can be in when it's deeply nested on Windows.
+    let file = if sess.target.is_like_msvc { file } else { fix_windows_verbatim_for_gcc(&file) };
     cmd2.arg(format!("@{}", file.display()));
     info!("invoking linker {:?}", cmd2);
-    let output = cmd2.output();
+    let output = cmd2.output_with_timeout(tool_timeout(sess));
     flush_linked_file(&output, out_filename)?;
     return output;
 
@@ -1339,17 +1667,28 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 // https://docs.microsoft.com/en-us/cpp/build/reference/at-specify-a-linker-response-file
                 //
                 // Unfortunately there's not a great specification of the
-                // syntax I could find online (at least) but some local
-                // testing showed that this seemed sufficient-ish to catch
-                // at least a few edge cases.
+                // syntax I could find online (at least), but it follows the
+                // usual rules for Windows command lines: backslashes are only
+                // special in front of a quote, where each of them, and the
+                // quote itself, has to be escaped with another backslash.
+                // That includes the closing quote, so a path ending in a
+                // backslash keeps it.
                 write!(f, "\"")?;
+                let mut backslashes = 0;
                 for c in self.arg.chars() {
                     match c {
-                        '"' => write!(f, "\\{}", c)?,
-                        c => write!(f, "{}", c)?,
+                        '\\' => backslashes += 1,
+                        '"' => {
+                            write!(f, "{}\\\"", "\\".repeat(backslashes * 2))?;
+                            backslashes = 0;
+                        }
+                        c => {
+                            write!(f, "{}{}", "\\".repeat(backslashes), c)?;
+                            backslashes = 0;
+                        }
                     }
                 }
-                write!(f, "\"")?;
+                write!(f, "{}\"", "\\".repeat(backslashes * 2))?;
             } else {
                 // This is documented at https://linux.die.net/man/1/ld, namely:
                 //
@@ -1363,7 +1702,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 // ensure the line is interpreted as one whole argument.
                 for c in self.arg.chars() {
                     match c {
-                        '\\' | ' ' => write!(f, "\\{}", c)?,
+                        '\\' | '\'' | '"' => write!(f, "\\{}", c)?,
+                        c if c.is_whitespace() => write!(f, "\\{}", c)?,
                         c => write!(f, "{}", c)?,
                     }
                 }
@@ -1413,7 +1753,7 @@ fn detect_self_contained_mingw(sess: &Session) -> bool {
     } else {
         linker
     };
-    for dir in env::split_paths(&env::var_os("PATH").unwrap_or_default()) {
+    for dir in env::split_paths(&sess.env_var_os("PATH").unwrap_or_default()) {
         let full_path = dir.join(&linker_with_extension);
         // If linker comes from sysroot assume self-contained mode
         if full_path.is_file() && !full_path.starts_with(&sess.sysroot) {
@@ -1596,12 +1936,16 @@ fn add_library_search_dirs(cmd: &mut dyn Linker, sess: &Session, self_contained:
 /// Add options making relocation sections in the produced ELF files read-only
 /// and suppressing lazy binding.
 fn add_relro_args(cmd: &mut dyn Linker, sess: &Session) {
-    match sess.opts.debugging_opts.relro_level.unwrap_or(sess.target.relro_level) {
+    match sess.relro_level() {
         RelroLevel::Full => cmd.full_relro(),
         RelroLevel::Partial => cmd.partial_relro(),
         RelroLevel::Off => cmd.no_relro(),
         RelroLevel::None => {}
     }
+    // Full RELRO already suppresses lazy binding.
+    if sess.relro_level() != RelroLevel::Full && sess.bind_now() {
+        cmd.bind_now();
+    }
 }
 
 /// Add library search paths used at runtime by dynamic linkers.
@@ -1814,6 +2158,10 @@ fn add_order_independent_options(
 
     add_apple_sdk(cmd, sess, flavor);
 
+    add_apple_deployment_target(cmd, sess, flavor);
+
+    add_android_api_level(cmd, sess, flavor);
+
     add_link_script(cmd, sess, tmpdir, crate_type);
 
     if sess.target.is_like_fuchsia && crate_type == CrateType::Executable {
@@ -1843,6 +2191,15 @@ fn add_order_independent_options(
         } else {
             "DISABLE_EXCEPTION_CATCHING=0"
         });
+        // Checked against the settings Emscripten knows when the session was created.
+        for (key, value) in &sess.opts.debugging_opts.emscripten_flags {
+            cmd.arg("-s");
+            cmd.arg(format!("{}={}", key, value));
+        }
+    }
+
+    if let Some(component) = &sess.opts.debugging_opts.wasi_component {
+        add_wasi_component_args(cmd, sess, component, crate_type);
     }
 
     if flavor == LinkerFlavor::PtxLinker {
@@ -1873,7 +2230,7 @@ fn add_order_independent_options(
 
     // Try to strip as much out of the generated object by removing unused
     // sections if possible. See more comments in linker.rs
-    if !sess.link_dead_code() {
+    if sess.link_time_gc_sections() {
         // If PGO is enabled sometimes gc_sections will remove the profile data section
         // as it appears to be unused. This can then cause the PGO profile file to lose
         // some functions. If we are generating a profile we shouldn't strip those metadata
@@ -1881,6 +2238,9 @@ fn add_order_independent_options(
         let keep_metadata =
             crate_type == CrateType::Dylib || sess.opts.cg.profile_generate.enabled();
         cmd.gc_sections(keep_metadata);
+    } else if sess.opts.cg.link_time_gc_sections == Some(false) {
+        // Explicitly disabled, which also overrides targets whose pre-link arguments enable it.
+        cmd.no_gc_sections();
     }
 
     cmd.set_output_kind(link_output_kind, out_filename);
@@ -1907,6 +2267,10 @@ fn add_order_independent_options(
         cmd.control_flow_guard();
     }
 
+    if sess.opts.debugging_opts.hotpatch == Some(Hotpatch::Prologue) {
+        cmd.hotpatch();
+    }
+
     add_rpath_args(cmd, sess, codegen_results, out_filename);
 }
 
@@ -2349,96 +2713,6 @@ fn are_upstream_rust_objects_already_included(sess: &Session) -> bool {
     }
 }
 
-fn add_apple_sdk(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
-    let arch = &sess.target.arch;
-    let os = &sess.target.os;
-    let llvm_target = &sess.target.llvm_target;
-    if sess.target.vendor != "apple"
-        || !matches!(os.as_str(), "ios" | "tvos")
-        || flavor != LinkerFlavor::Gcc
-    {
-        return;
-    }
-    let sdk_name = match (arch.as_str(), os.as_str()) {
-        ("aarch64", "tvos") => "appletvos",
-        ("x86_64", "tvos") => "appletvsimulator",
-        ("arm", "ios") => "iphoneos",
-        ("aarch64", "ios") if llvm_target.contains("macabi") => "macosx",
-        ("aarch64", "ios") if llvm_target.contains("sim") => "iphonesimulator",
-        ("aarch64", "ios") => "iphoneos",
-        ("x86", "ios") => "iphonesimulator",
-        ("x86_64", "ios") if llvm_target.contains("macabi") => "macosx",
-        ("x86_64", "ios") => "iphonesimulator",
-        _ => {
-            sess.err(&format!("unsupported arch `{}` for os `{}`", arch, os));
-            return;
-        }
-    };
-    let sdk_root = match get_apple_sdk_root(sdk_name) {
-        Ok(s) => s,
-        Err(e) => {
-            sess.err(&e);
-            return;
-        }
-    };
-    if llvm_target.contains("macabi") {
-        cmd.args(&["-target", llvm_target])
-    } else {
-        let arch_name = llvm_target.split('-').next().expect("LLVM target must have a hyphen");
-        cmd.args(&["-arch", arch_name])
-    }
-    cmd.args(&["-isysroot", &sdk_root, "-Wl,-syslibroot", &sdk_root]);
-}
-
-fn get_apple_sdk_root(sdk_name: &str) -> Result<String, String> {
-    // Following what clang does
-    // (https://github.com/llvm/llvm-project/blob/
-    // 296a80102a9b72c3eda80558fb78a3ed8849b341/clang/lib/Driver/ToolChains/Darwin.cpp#L1661-L1678)
-    // to allow the SDK path to be set. (For clang, xcrun sets
-    // SDKROOT; for rustc, the user or build system can set it, or we
-    // can fall back to checking for xcrun on PATH.)
-    if let Ok(sdkroot) = env::var("SDKROOT") {
-        let p = Path::new(&sdkroot);
-        match sdk_name {
-            // Ignore `SDKROOT` if it's clearly set for the wrong platform.
-            "appletvos"
-                if sdkroot.contains("TVSimulator.platform")
-                    || sdkroot.contains("MacOSX.platform") => {}
-            "appletvsimulator"
-                if sdkroot.contains("TVOS.platform") || sdkroot.contains("MacOSX.platform") => {}
-            "iphoneos"
-                if sdkroot.contains("iPhoneSimulator.platform")
-                    || sdkroot.contains("MacOSX.platform") => {}
-            "iphonesimulator"
-                if sdkroot.contains("iPhoneOS.platform") || sdkroot.contains("MacOSX.platform") => {
-            }
-            "macosx10.15"
-                if sdkroot.contains("iPhoneOS.platform")
-                    || sdkroot.contains("iPhoneSimulator.platform") => {}
-            // Ignore `SDKROOT` if it's not a valid path.
-            _ if !p.is_absolute() || p == Path::new("/") || !p.exists() => {}
-            _ => return Ok(sdkroot),
-        }
-    }
-    let res =
-        Command::new("xcrun").arg("--show-sdk-path").arg("-sdk").arg(sdk_name).output().and_then(
-            |output| {
-                if output.status.success() {
-                    Ok(String::from_utf8(output.stdout).unwrap())
-                } else {
-                    let error = String::from_utf8(output.stderr);
-                    let error = format!("process exit with error: {}", error.unwrap());
-                    Err(io::Error::new(io::ErrorKind::Other, &error[..]))
-                }
-            },
-        );
-
-    match res {
-        Ok(output) => Ok(output.trim().to_string()),
-        Err(e) => Err(format!("failed to get {} SDK path: {}", sdk_name, e)),
-    }
-}
-
 fn add_gcc_ld_path(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
     if let Some(ld_impl) = sess.opts.debugging_opts.gcc_ld {
         if let LinkerFlavor::Gcc = flavor {
@@ -0,0 +1,179 @@
+//! The linker arguments specific to Apple, Android and WASI component targets: the SDK and
+//! deployment target for Apple, the API level for the NDK, and the adapter and world for
+//! `wasm-component-ld`.
+
+use super::*;
+
+/// Passes `wasm-component-ld` the adapter module implementing WASI preview1 on top of
+/// preview2 and, if any, the component type of the world the component targets.
+pub(super) fn add_wasi_component_args(
+    cmd: &mut dyn Linker,
+    sess: &Session,
+    component: &WasiComponent,
+    crate_type: CrateType,
+) {
+    let adapter = match &component.adapter {
+        Some(adapter) => adapter.clone(),
+        None => {
+            // Only commands have a `_start` for the command adapter to export as `wasi:cli/run`.
+            let model = if crate_type == CrateType::Executable && !sess.is_wasi_reactor() {
+                "command"
+            } else {
+                "reactor"
+            };
+            let lib_path = sess.target_filesearch(PathKind::All).get_self_contained_lib_path();
+            lib_path.join(format!("wasi_snapshot_preview1.{}.wasm", model))
+        }
+    };
+    if !adapter.is_file() {
+        sess.fatal(&format!(
+            "couldn't find the WASI adapter module `{}`; pass one with \
+             `-Z wasi-component=adapter=<path>`",
+            adapter.display()
+        ));
+    }
+    cmd.arg("--adapt");
+    let mut arg = OsString::from("wasi_snapshot_preview1=");
+    arg.push(&adapter);
+    cmd.arg(arg);
+
+    if let Some(world) = &component.world {
+        cmd.arg("--component-type");
+        cmd.arg(world);
+    }
+}
+
+pub(super) fn add_apple_sdk(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
+    let arch = &sess.target.arch;
+    let os = &sess.target.os;
+    let llvm_target = &sess.target.llvm_target;
+    if sess.target.vendor != "apple" || flavor != LinkerFlavor::Gcc {
+        return;
+    }
+    if os == "macos" {
+        // The C compiler finds the macOS SDK by itself, unless `-Z sdk-root` names one.
+        if let Some(sdk_root) = &sess.opts.debugging_opts.sdk_root {
+            let sdk_root = sdk_root.display().to_string();
+            cmd.args(&["-isysroot", &sdk_root, "-Wl,-syslibroot", &sdk_root]);
+        }
+        return;
+    }
+    if !matches!(os.as_str(), "ios" | "tvos") {
+        return;
+    }
+    let sdk_name = match (arch.as_str(), os.as_str()) {
+        ("aarch64", "tvos") => "appletvos",
+        ("x86_64", "tvos") => "appletvsimulator",
+        ("arm", "ios") => "iphoneos",
+        ("aarch64", "ios") if llvm_target.contains("macabi") => "macosx",
+        ("aarch64", "ios") if llvm_target.contains("sim") => "iphonesimulator",
+        ("aarch64", "ios") => "iphoneos",
+        ("x86", "ios") => "iphonesimulator",
+        ("x86_64", "ios") if llvm_target.contains("macabi") => "macosx",
+        ("x86_64", "ios") => "iphonesimulator",
+        _ => {
+            sess.err(&format!("unsupported arch `{}` for os `{}`", arch, os));
+            return;
+        }
+    };
+    let sdk_root = match get_apple_sdk_root(sess, sdk_name) {
+        Ok(s) => s,
+        Err(e) => {
+            sess.err(&e);
+            return;
+        }
+    };
+    if llvm_target.contains("macabi") {
+        cmd.args(&["-target", llvm_target])
+    } else {
+        let arch_name = llvm_target.split('-').next().expect("LLVM target must have a hyphen");
+        cmd.args(&["-arch", arch_name])
+    }
+    cmd.args(&["-isysroot", &sdk_root, "-Wl,-syslibroot", &sdk_root]);
+}
+
+/// Passes the deployment target of `-Z apple-deployment-target` to the C compiler driving the
+/// linker, which would otherwise take it from the environment.
+pub(super) fn add_apple_deployment_target(
+    cmd: &mut dyn Linker,
+    sess: &Session,
+    flavor: LinkerFlavor,
+) {
+    let (major, minor) = match sess.opts.debugging_opts.apple_deployment_target {
+        Some(version) if flavor == LinkerFlavor::Gcc => version,
+        _ => return,
+    };
+    let flag = if sess.target.os == "macos" {
+        "-mmacosx-version-min"
+    } else if sess.target.is_apple_simulator() {
+        "-mios-simulator-version-min"
+    } else {
+        "-miphoneos-version-min"
+    };
+    cmd.arg(format!("{}={}.{}", flag, major, minor));
+}
+
+/// Passes the target with the API level of `-Z android-api-level` to the NDK's clang, which
+/// links against the libraries of the NDK for that API level.
+pub(super) fn add_android_api_level(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) {
+    if sess.opts.debugging_opts.android_api_level.is_some() && flavor == LinkerFlavor::Gcc {
+        // `-Z android-api-level` set the API level in the LLVM target.
+        cmd.arg(format!("--target={}", sess.target.llvm_target));
+    }
+}
+
+fn get_apple_sdk_root(sess: &Session, sdk_name: &str) -> Result<String, String> {
+    // `-Z sdk-root` was checked against the target when the session was created.
+    if let Some(sdk_root) = &sess.opts.debugging_opts.sdk_root {
+        return Ok(sdk_root.display().to_string());
+    }
+    // Following what clang does
+    // (https://github.com/llvm/llvm-project/blob/
+    // 296a80102a9b72c3eda80558fb78a3ed8849b341/clang/lib/Driver/ToolChains/Darwin.cpp#L1661-L1678)
+    // to allow the SDK path to be set. (For clang, xcrun sets
+    // SDKROOT; for rustc, the user or build system can set it, or we
+    // can fall back to checking for xcrun on PATH.)
+    if let Ok(sdkroot) = sess.env_var("SDKROOT") {
+        let p = Path::new(&sdkroot);
+        match sdk_name {
+            // Ignore `SDKROOT` if it's clearly set for the wrong platform.
+            "appletvos"
+                if sdkroot.contains("TVSimulator.platform")
+                    || sdkroot.contains("MacOSX.platform") => {}
+            "appletvsimulator"
+                if sdkroot.contains("TVOS.platform") || sdkroot.contains("MacOSX.platform") => {}
+            "iphoneos"
+                if sdkroot.contains("iPhoneSimulator.platform")
+                    || sdkroot.contains("MacOSX.platform") => {}
+            "iphonesimulator"
+                if sdkroot.contains("iPhoneOS.platform") || sdkroot.contains("MacOSX.platform") => {
+            }
+            "macosx10.15"
+                if sdkroot.contains("iPhoneOS.platform")
+                    || sdkroot.contains("iPhoneSimulator.platform") => {}
+            // Ignore `SDKROOT` if it's not a valid path.
+            _ if !p.is_absolute() || p == Path::new("/") || !p.exists() => {}
+            _ => return Ok(sdkroot),
+        }
+    }
+    if let Some(path) = sess.env_var_os("PATH") {
+        sess.record_tool_lookup(Path::new("xcrun"), &env::split_paths(&path).collect::<Vec<_>>());
+    }
+    let res =
+        Command::new("xcrun").arg("--show-sdk-path").arg("-sdk").arg(sdk_name).output().and_then(
+            |output| {
+                if output.status.success() {
+                    Ok(String::from_utf8(output.stdout).unwrap())
+                } else {
+                    let error = String::from_utf8(output.stderr);
+                    let error = format!("process exit with error: {}", error.unwrap());
+                    Err(io::Error::new(io::ErrorKind::Other, &error[..]))
+                }
+            },
+        );
+
+    match res {
+        Ok(output) => Ok(output.trim().to_string()),
+        Err(e) => Err(format!("failed to get {} SDK path: {}", sdk_name, e)),
+    }
+}
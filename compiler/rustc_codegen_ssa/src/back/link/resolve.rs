@@ -0,0 +1,298 @@
+//! Choosing the linker to run and its flavor from the command line, the target and the
+//! environment, recording each decision for `--print resolved-linker` and for the errors
+//! reported when the linker isn't found.
+
+use super::*;
+
+// This functions tries to determine the appropriate linker (and corresponding LinkerFlavor) to use
+pub fn linker_and_flavor(sess: &Session) -> (PathBuf, LinkerFlavor) {
+    let resolved = resolve_linker(sess);
+    (resolved.linker, resolved.flavor)
+}
+
+/// The linker chosen by `resolve_linker`, along with a description of each decision that
+/// led to it, for `--print resolved-linker` and "linker not found" errors.
+pub struct ResolvedLinker {
+    pub linker: PathBuf,
+    pub flavor: LinkerFlavor,
+    pub steps: Vec<String>,
+}
+
+pub fn resolve_linker(sess: &Session) -> ResolvedLinker {
+    fn infer_from(
+        sess: &Session,
+        source: &str,
+        linker: Option<PathBuf>,
+        flavor: Option<LinkerFlavor>,
+        steps: &mut Vec<String>,
+    ) -> Option<(PathBuf, LinkerFlavor)> {
+        match (linker, flavor) {
+            (Some(linker), Some(flavor)) => {
+                steps.push(format!(
+                    "using linker `{}` with flavor `{}` from {}",
+                    linker.display(),
+                    flavor.desc(),
+                    source
+                ));
+                Some((linker, flavor))
+            }
+            // only the linker flavor is known; use the default linker for the selected flavor
+            (None, Some(flavor)) => {
+                let linker = PathBuf::from(match flavor {
+                    LinkerFlavor::Em => {
+                        if cfg!(windows) {
+                            "emcc.bat"
+                        } else {
+                            "emcc"
+                        }
+                    }
+                    LinkerFlavor::Gcc => {
+                        if cfg!(any(target_os = "solaris", target_os = "illumos")) {
+                            // On historical Solaris systems, "cc" may have
+                            // been Sun Studio, which is not flag-compatible
+                            // with "gcc".  This history casts a long shadow,
+                            // and many modern illumos distributions today
+                            // ship GCC as "gcc" without also making it
+                            // available as "cc".
+                            "gcc"
+                        } else {
+                            "cc"
+                        }
+                    }
+                    LinkerFlavor::Ld => "ld",
+                    LinkerFlavor::Msvc => "link.exe",
+                    LinkerFlavor::Lld(_) => "lld",
+                    LinkerFlavor::PtxLinker => "rust-ptx-linker",
+                    LinkerFlavor::BpfLinker => "bpf-linker",
+                });
+                steps.push(format!(
+                    "{} sets the linker flavor `{}` but no linker; using its default linker `{}`",
+                    source,
+                    flavor.desc(),
+                    linker.display()
+                ));
+                Some((linker, flavor))
+            }
+            (Some(linker), None) => {
+                let stem = linker.file_stem().and_then(|stem| stem.to_str()).unwrap_or_else(|| {
+                    sess.fatal("couldn't extract file stem from specified linker")
+                });
+
+                let flavor = if stem == "emcc" {
+                    LinkerFlavor::Em
+                } else if stem == "gcc"
+                    || stem.ends_with("-gcc")
+                    || stem == "clang"
+                    || stem.ends_with("-clang")
+                {
+                    LinkerFlavor::Gcc
+                } else if stem == "wasm-ld"
+                    || stem.ends_with("-wasm-ld")
+                    || stem == "wasm-component-ld"
+                {
+                    LinkerFlavor::Lld(LldFlavor::Wasm)
+                } else if stem == "ld" || stem == "ld.lld" || stem.ends_with("-ld") {
+                    LinkerFlavor::Ld
+                } else if stem == "link" || stem == "lld-link" {
+                    LinkerFlavor::Msvc
+                } else if stem == "lld" || stem == "rust-lld" {
+                    LinkerFlavor::Lld(sess.target.lld_flavor)
+                } else {
+                    // fall back to the value in the target spec
+                    steps.push(format!(
+                        "using linker `{}` from {}; its name `{}` doesn't imply a flavor, \
+                         so using the target's flavor `{}`",
+                        linker.display(),
+                        source,
+                        stem,
+                        sess.target.linker_flavor.desc()
+                    ));
+                    return Some((linker, sess.target.linker_flavor));
+                };
+
+                steps.push(format!(
+                    "using linker `{}` from {}; inferred flavor `{}` from its name `{}`",
+                    linker.display(),
+                    source,
+                    flavor.desc(),
+                    stem
+                ));
+                Some((linker, flavor))
+            }
+            (None, None) => None,
+        }
+    }
+
+    let mut steps = Vec::new();
+
+    // linker and linker flavor specified via command line have precedence over what the target
+    // specification specifies
+    if let Some((linker, flavor)) = infer_from(
+        sess,
+        "the command line",
+        sess.opts.cg.linker.clone(),
+        sess.opts.cg.linker_flavor,
+        &mut steps,
+    ) {
+        return ResolvedLinker { linker, flavor, steps };
+    }
+
+    // components are linked by `wasm-component-ld`, which drives `wasm-ld` and then turns the
+    // module into a component
+    if sess.opts.debugging_opts.wasi_component.is_some() {
+        if let Some((linker, flavor)) = infer_from(
+            sess,
+            "`-Z wasi-component`",
+            Some(PathBuf::from("wasm-component-ld")),
+            Some(LinkerFlavor::Lld(LldFlavor::Wasm)),
+            &mut steps,
+        ) {
+            return ResolvedLinker { linker, flavor, steps };
+        }
+    }
+
+    let source = format!("the target specification for `{}`", sess.opts.target_triple);
+    if let Some((linker, flavor)) = infer_from(
+        sess,
+        &source,
+        sess.target.linker.clone().map(PathBuf::from),
+        Some(sess.target.linker_flavor),
+        &mut steps,
+    ) {
+        return ResolvedLinker { linker, flavor, steps };
+    }
+
+    bug!("Not enough information provided to determine how to invoke the linker");
+}
+
+/// Finds the program that will be run for `linker`, the same way `linker::get_linker` sets
+/// up the linker's `PATH`, recording what was searched in `steps`.
+pub(super) fn locate_linker(
+    sess: &Session,
+    linker: &Path,
+    flavor: LinkerFlavor,
+    steps: &mut Vec<String>,
+) -> Option<PathBuf> {
+    if flavor == LinkerFlavor::Msvc && sess.opts.cg.linker.is_none() && sess.target.linker.is_none()
+    {
+        match windows_registry::find_tool(&sess.opts.target_triple.triple(), "link.exe") {
+            Some(tool) => {
+                steps.push(format!(
+                    "found `link.exe` through the Visual Studio installation at `{}`",
+                    tool.path().display()
+                ));
+                return Some(tool.path().to_path_buf());
+            }
+            None => steps.push("no Visual Studio installation provides `link.exe`".to_string()),
+        }
+    }
+
+    if linker.parent() != Some(Path::new("")) {
+        return if linker.is_file() {
+            Some(linker.to_path_buf())
+        } else {
+            steps.push(format!("`{}` does not exist", linker.display()));
+            None
+        };
+    }
+
+    let program = if cfg!(windows) && linker.extension().is_none() {
+        linker.with_extension("exe")
+    } else {
+        linker.to_path_buf()
+    };
+    let tools_dirs = sess.get_tools_search_paths(false);
+    let path_dirs: Vec<PathBuf> =
+        sess.env_var_os("PATH").map(|path| env::split_paths(&path).collect()).unwrap_or_default();
+    if let Some(dir) = tools_dirs.iter().find(|dir| dir.join(&program).is_file()) {
+        steps.push(format!("found `{}` in the sysroot at `{}`", program.display(), dir.display()));
+        return Some(dir.join(&program));
+    }
+    if let Some((i, dir)) =
+        path_dirs.iter().enumerate().find(|(_, dir)| dir.join(&program).is_file())
+    {
+        steps.push(format!(
+            "found `{}` in PATH entry {} of {} (`{}`)",
+            program.display(),
+            i + 1,
+            path_dirs.len(),
+            dir.display()
+        ));
+        return Some(dir.join(&program));
+    }
+    steps.push(format!(
+        "`{}` is not in the sysroot's tool directories or any of the {} PATH entries",
+        program.display(),
+        path_dirs.len()
+    ));
+    None
+}
+
+/// Describes what a `cc`-style linker actually is: the program its symlinks resolve to,
+/// and the compiler driver that `--version` identifies, which matters when `cc` is a
+/// compiler cache or a different compiler than its name suggests.
+fn describe_cc_wrapper(sess: &Session, program: &Path, steps: &mut Vec<String>) {
+    if let Ok(canonical) = fs::canonicalize(program) {
+        if canonical != program {
+            steps.push(format!("`{}` resolves to `{}`", program.display(), canonical.display()));
+        }
+        let stem = canonical.file_stem().and_then(|stem| stem.to_str()).unwrap_or("");
+        if ["ccache", "sccache", "distcc"].contains(&stem) {
+            steps.push(format!("`{}` is the compiler wrapper `{}`", program.display(), stem));
+        }
+    }
+
+    let output = Command::new(program).arg("--version").output_with_timeout(tool_timeout(sess));
+    let version = match output {
+        Ok(output) if output.status.success() => output.stdout,
+        _ => {
+            steps.push(format!("`{} --version` failed", program.display()));
+            return;
+        }
+    };
+    let version = String::from_utf8_lossy(&version);
+    let first_line = version.lines().next().unwrap_or("").trim();
+    let driver = if first_line.contains("clang") {
+        "clang"
+    } else if first_line.contains("gcc") || first_line.contains("GCC") {
+        "gcc"
+    } else {
+        "an unrecognized compiler driver"
+    };
+    steps.push(format!("`{} --version` reports {}: {}", program.display(), driver, first_line));
+}
+
+/// Prints the linker `link_natively` would run and how it was chosen, for
+/// `--print resolved-linker`.
+pub fn print_resolved_linker(sess: &Session) {
+    let ResolvedLinker { linker, flavor, mut steps } = resolve_linker(sess);
+    let program = locate_linker(sess, &linker, flavor, &mut steps);
+    if let (Some(program), LinkerFlavor::Gcc) = (&program, flavor) {
+        describe_cc_wrapper(sess, program, &mut steps);
+    }
+    if flavor == LinkerFlavor::Gcc {
+        if let Some(LdImpl::Lld) = sess.opts.debugging_opts.gcc_ld {
+            steps.push("`-Z gcc-ld=lld` makes it link with the sysroot's `rust-lld`".to_string());
+        }
+    }
+
+    if sess.opts.print_json {
+        let mut obj = BTreeMap::new();
+        obj.insert("linker".to_string(), linker.display().to_string().to_json());
+        obj.insert("flavor".to_string(), flavor.desc().to_json());
+        obj.insert("path".to_string(), program.map(|p| p.display().to_string()).to_json());
+        obj.insert("steps".to_string(), steps.to_json());
+        sess.print_json(PrintRequest::ResolvedLinker, Json::Object(obj));
+        return;
+    }
+
+    println!("linker: {}", linker.display());
+    println!("flavor: {}", flavor.desc());
+    match program {
+        Some(program) => println!("path: {}", program.display()),
+        None => println!("path: (not found)"),
+    }
+    for step in steps {
+        println!("step: {}", step);
+    }
+}
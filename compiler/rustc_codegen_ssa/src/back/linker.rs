@@ -12,6 +12,7 @@
 
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_middle::middle::dependency_format::Linkage;
+use rustc_middle::middle::exported_symbols::SymbolExportLevel;
 use rustc_middle::ty::TyCtxt;
 use rustc_serialize::{json, Encoder};
 use rustc_session::config::{self, CrateType, DebugInfo, LinkerPluginLto, Lto, OptLevel, Strip};
@@ -57,6 +58,12 @@ pub fn get_linker<'a>(
     let mut cmd = match linker.to_str() {
         Some(linker) if cfg!(windows) && linker.ends_with(".bat") => Command::bat_script(linker),
         _ => match flavor {
+            // `wasm-component-ld` is a `wasm-ld` driver, not an `lld` multiplexer taking `-flavor`
+            LinkerFlavor::Lld(LldFlavor::Wasm)
+                if sess.opts.debugging_opts.wasi_component.is_some() =>
+            {
+                Command::new(linker)
+            }
             LinkerFlavor::Lld(f) => Command::lld(linker, f),
             LinkerFlavor::Msvc if sess.opts.cg.linker.is_none() && sess.target.linker.is_none() => {
                 Command::new(msvc_tool.as_ref().map_or(linker, |t| t.path()))
@@ -117,10 +124,11 @@ pub fn get_linker<'a>(
     }
 
     if !msvc_changed_path {
-        if let Some(path) = env::var_os("PATH") {
+        if let Some(path) = sess.env_var_os("PATH") {
             new_path.extend(env::split_paths(&path));
         }
     }
+    sess.record_tool_lookup(linker, &new_path);
     cmd.env("PATH", env::join_paths(new_path).unwrap());
 
     // FIXME: Move `/LIBPATH` addition for uwp targets from the linker construction
@@ -178,6 +186,7 @@ pub trait Linker {
     fn full_relro(&mut self);
     fn partial_relro(&mut self);
     fn no_relro(&mut self);
+    fn bind_now(&mut self) {}
     fn optimize(&mut self);
     fn pgo_gen(&mut self);
     fn control_flow_guard(&mut self);
@@ -190,6 +199,7 @@ pub trait Linker {
     fn group_end(&mut self);
     fn linker_plugin_lto(&mut self);
     fn add_eh_frame_header(&mut self) {}
+    fn hotpatch(&mut self) {}
     fn add_no_exec(&mut self) {}
     fn add_as_needed(&mut self) {}
     fn reset_per_library_state(&mut self) {}
@@ -482,6 +492,9 @@ fn partial_relro(&mut self) {
     fn no_relro(&mut self) {
         self.linker_arg("-znorelro");
     }
+    fn bind_now(&mut self) {
+        self.linker_arg("-znow");
+    }
 
     fn link_rust_dylib(&mut self, lib: Symbol, _path: &Path) {
         self.hint_dynamic();
@@ -558,7 +571,12 @@ fn gc_sections(&mut self, keep_metadata: bool) {
         // eliminate the metadata. If we're building an executable, however,
         // --gc-sections drops the size of hello world from 1.8MB to 597K, a 67%
         // reduction.
-        } else if (self.sess.target.linker_is_gnu || self.sess.target.is_like_wasm)
+        //
+        // When it's forced with `-C link-time-gc-sections`, `--gc-sections` is also passed
+        // to linkers that aren't known to support it.
+        } else if (self.sess.target.linker_is_gnu
+            || self.sess.target.is_like_wasm
+            || self.sess.opts.cg.link_time_gc_sections == Some(true))
             && !keep_metadata
         {
             self.linker_arg("--gc-sections");
@@ -913,6 +931,11 @@ fn control_flow_guard(&mut self) {
         self.cmd.arg("/guard:cf");
     }
 
+    fn hotpatch(&mut self) {
+        // Pads the functions so that their hot-patchable prologue can jump before them.
+        self.cmd.arg("/FUNCTIONPADMIN");
+    }
+
     fn debuginfo(&mut self, strip: Strip) {
         match strip {
             Strip::None => {
@@ -1360,16 +1383,33 @@ pub(crate) fn exported_symbols(tcx: TyCtxt<'_>, crate_type: CrateType) -> Vec<St
         return exports.clone();
     }
 
+    // Only the exports of dynamic libraries are restricted by `-Z symbol-visibility-file`.
+    // The Rust-level symbols of a Rust dylib are what its Rust dependents link against, so
+    // those are always kept.
+    let visibility_file = tcx
+        .sess
+        .opts
+        .debugging_opts
+        .symbol_visibility_file
+        .as_ref()
+        .filter(|_| matches!(crate_type, CrateType::Dylib | CrateType::Cdylib));
     let mut symbols = Vec::new();
+    let mut rust_symbols = Vec::new();
+    let mut push_symbol = |name, level| {
+        if visibility_file.is_some() && level == SymbolExportLevel::Rust {
+            rust_symbols.push(name);
+        } else {
+            symbols.push(name);
+        }
+    };
 
     let export_threshold = symbol_export::crates_export_threshold(&[crate_type]);
     for &(symbol, level) in tcx.exported_symbols(LOCAL_CRATE).iter() {
         if level.is_below_threshold(export_threshold) {
-            symbols.push(symbol_export::symbol_name_for_instance_in_crate(
-                tcx,
-                symbol,
-                LOCAL_CRATE,
-            ));
+            push_symbol(
+                symbol_export::symbol_name_for_instance_in_crate(tcx, symbol, LOCAL_CRATE),
+                level,
+            );
         }
     }
 
@@ -1386,14 +1426,105 @@ pub(crate) fn exported_symbols(tcx: TyCtxt<'_>, crate_type: CrateType) -> Vec<St
                     continue;
                 }
 
-                symbols.push(symbol_export::symbol_name_for_instance_in_crate(tcx, symbol, cnum));
+                push_symbol(
+                    symbol_export::symbol_name_for_instance_in_crate(tcx, symbol, cnum),
+                    level,
+                );
             }
         }
     }
 
+    if let Some(path) = visibility_file {
+        let contents = fs::read_to_string(path).unwrap_or_else(|e| {
+            tcx.sess.fatal(&format!(
+                "failed to read symbol visibility file `{}`: {}",
+                path.display(),
+                e
+            ))
+        });
+        // MSVC fails the link on a `.def` file naming an undefined symbol.
+        let allow_undefined = !tcx.sess.target.is_like_msvc;
+        let (restricted, unknown) =
+            apply_symbol_visibility_file(&contents, symbols, allow_undefined);
+        for pattern in unknown {
+            tcx.sess.warn(&format!(
+                "`{}` from the symbol visibility file is not a symbol of this crate or its \
+                 dependencies and is not exported",
+                pattern
+            ));
+        }
+        symbols = restricted;
+        symbols.extend(rust_symbols);
+    }
+
     symbols
 }
 
+/// Restricts `symbols` to the ones matching a pattern from the contents of a
+/// `-Z symbol-visibility-file`.
+///
+/// Patterns without wildcards are exported even if rustc does not know about the symbol,
+/// so that symbols defined by linked native code can be listed as well. Without
+/// `allow_undefined`, such patterns are returned separately instead.
+fn apply_symbol_visibility_file<'a>(
+    contents: &'a str,
+    symbols: Vec<String>,
+    allow_undefined: bool,
+) -> (Vec<String>, Vec<&'a str>) {
+    let patterns: Vec<&str> = contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+
+    let mut exported: Vec<String> = symbols
+        .into_iter()
+        .filter(|symbol| patterns.iter().any(|pattern| symbol_pattern_matches(pattern, symbol)))
+        .collect();
+    let mut unknown = Vec::new();
+    for pattern in patterns {
+        if pattern.contains(&['*', '?'][..]) || exported.iter().any(|symbol| symbol == pattern) {
+            continue;
+        }
+        if allow_undefined {
+            exported.push(pattern.to_string());
+        } else {
+            unknown.push(pattern);
+        }
+    }
+    (exported, unknown)
+}
+
+/// Matches `symbol` against a pattern where `*` matches any sequence of characters and
+/// `?` matches any single character.
+fn symbol_pattern_matches(pattern: &str, symbol: &str) -> bool {
+    let (pattern, symbol) = (pattern.as_bytes(), symbol.as_bytes());
+    let (mut p, mut s) = (0, 0);
+    // Position of the last `*` and the symbol position it currently stands in for.
+    let mut star = None;
+    while s < symbol.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, s));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == symbol[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => match star {
+                Some((star_p, star_s)) => {
+                    star = Some((star_p, star_s + 1));
+                    p = star_p + 1;
+                    s = star_s + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
 /// Much simplified and explicit CLI for the NVPTX linker. The linker operates
 /// with bitcode and uses LLVM backend to generate a PTX assembly.
 pub struct PtxLinker<'a> {
@@ -1607,3 +1738,6 @@ fn group_end(&mut self) {}
 
     fn linker_plugin_lto(&mut self) {}
 }
+
+#[cfg(test)]
+mod tests;
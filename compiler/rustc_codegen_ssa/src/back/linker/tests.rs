@@ -0,0 +1,41 @@
+use super::{apply_symbol_visibility_file, symbol_pattern_matches};
+
+fn symbols(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_symbol_pattern_matches() {
+    assert!(symbol_pattern_matches("api_init", "api_init"));
+    assert!(!symbol_pattern_matches("api_init", "api_init2"));
+    assert!(symbol_pattern_matches("api_*", "api_"));
+    assert!(symbol_pattern_matches("api_*", "api_shutdown"));
+    assert!(!symbol_pattern_matches("api_*", "internal_api_init"));
+    assert!(symbol_pattern_matches("*_init", "api_init"));
+    assert!(symbol_pattern_matches("a*b*c", "axxbyybc"));
+    assert!(!symbol_pattern_matches("a*b*c", "axxbyyb"));
+    assert!(symbol_pattern_matches("api_?", "api_1"));
+    assert!(!symbol_pattern_matches("api_?", "api_"));
+    assert!(!symbol_pattern_matches("api_?", "api_12"));
+}
+
+#[test]
+fn test_apply_symbol_visibility_file() {
+    let contents = "# Public C API\napi_*  # wildcard\n\nnative_version\n";
+    let (exported, unknown) = apply_symbol_visibility_file(
+        contents,
+        symbols(&["api_init", "internal_helper", "api_shutdown"]),
+        true,
+    );
+    assert_eq!(exported, ["api_init", "api_shutdown", "native_version"]);
+    assert!(unknown.is_empty());
+}
+
+#[test]
+fn test_apply_symbol_visibility_file_without_undefined() {
+    let contents = "api_init\nnative_version\n";
+    let (exported, unknown) =
+        apply_symbol_visibility_file(contents, symbols(&["api_init", "internal_helper"]), false);
+    assert_eq!(exported, ["api_init"]);
+    assert_eq!(unknown, ["native_version"]);
+}
@@ -0,0 +1,187 @@
+//! The linker script template of `-Z generate-linker-script-template`, a starting point for the
+//! linker script of a bare-metal executable.
+//!
+//! The template places the code and read-only data in a `FLASH` memory region and the writable
+//! data in a `RAM` one, whose origins and lengths are left for the user to fill in. It defines
+//! the symbols a startup routine needs to initialize `.data` and `.bss` and to run the static
+//! constructors, which are in `.init_array` or `.ctors` depending on the target, the bounds of the
+//! stack and heap, and an output section for each `#[link_section]` used by the crate.
+
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::DUMMY_SP;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Where the sections of the crate that aren't one of the standard ones are placed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Placement {
+    /// Code and read-only data, placed in `FLASH`.
+    ReadOnly,
+    /// Writable data, placed in `RAM` and initialized from a copy in `FLASH`.
+    Writable,
+}
+
+/// The input sections that the standard output sections of the template already contain.
+const STANDARD_SECTION_PREFIXES: &[&str] =
+    &[".text", ".rodata", ".data", ".bss", ".init_array", ".fini_array", ".ctors", ".dtors"];
+
+/// Writes the linker script template of `-Z generate-linker-script-template` to `path`.
+pub fn write_linker_script_template(
+    tcx: TyCtxt<'_>,
+    codegen_units: &[CodegenUnit<'_>],
+    path: &Path,
+) {
+    let sess = tcx.sess;
+    if !sess.target_produces_elf() {
+        sess.warn(&format!(
+            "linker script templates can only be generated for ELF targets, not for `{}`",
+            sess.opts.target_triple
+        ));
+        return;
+    }
+
+    let mut custom_sections = BTreeMap::new();
+    for cgu in codegen_units {
+        for &item in cgu.items().keys() {
+            let (def_id, placement) = match item {
+                MonoItem::Fn(instance) => (instance.def_id(), Placement::ReadOnly),
+                MonoItem::Static(def_id) => {
+                    let ty = tcx.type_of(def_id);
+                    let writable = tcx.is_mutable_static(def_id)
+                        || !ty.is_freeze(tcx.at(DUMMY_SP), ty::ParamEnv::reveal_all());
+                    (def_id, if writable { Placement::Writable } else { Placement::ReadOnly })
+                }
+                MonoItem::GlobalAsm(_) => continue,
+            };
+            let section = match tcx.codegen_fn_attrs(def_id).link_section {
+                Some(section) => section.to_string(),
+                None => continue,
+            };
+            if STANDARD_SECTION_PREFIXES
+                .iter()
+                .any(|prefix| section == *prefix || section.starts_with(&format!("{}.", prefix)))
+            {
+                continue;
+            }
+            // A section that has both code or read-only data and writable data must be writable.
+            let entry = custom_sections.entry(section).or_insert(placement);
+            *entry = (*entry).max(placement);
+        }
+    }
+
+    let use_ctors_section =
+        sess.opts.debugging_opts.use_ctors_section.unwrap_or(sess.target.use_ctors_section);
+    let align = tcx.data_layout.pointer_align.abi.bytes();
+
+    let entry = if tcx.entry_fn(()).is_some() {
+        "ENTRY(main);"
+    } else {
+        "/* ENTRY(reset_handler); TODO */"
+    };
+    let mut script = format!(
+        r#"/* Linker script template for `{crate_name}` on `{target}`.
+ *
+ * Fill in the origins and lengths of the memory regions of the device, and
+ * the entry point if the crate doesn't define `main`. */
+
+MEMORY
+{{
+  FLASH (rx)  : ORIGIN = 0x00000000, LENGTH = 0 /* TODO */
+  RAM   (rwx) : ORIGIN = 0x00000000, LENGTH = 0 /* TODO */
+}}
+
+{entry}
+
+/* The stack grows downwards from the end of RAM. */
+_stack_start = ORIGIN(RAM) + LENGTH(RAM);
+
+SECTIONS
+{{
+"#,
+        crate_name = tcx.crate_name(LOCAL_CRATE),
+        target = sess.opts.target_triple,
+        entry = entry,
+    );
+
+    for (section, _) in custom_sections.iter().filter(|(_, &p)| p == Placement::ReadOnly) {
+        writeln!(
+            script,
+            "  {section} : ALIGN({align})\n  {{\n    KEEP(*({section}));\n  }} > FLASH\n",
+            section = section,
+            align = align
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        script,
+        "  .text : ALIGN({align})\n  {{\n    *(.text .text.*);\n  }} > FLASH\n\n  \
+        .rodata : ALIGN({align})\n  {{\n    *(.rodata .rodata.*);\n  }} > FLASH\n",
+        align = align
+    )
+    .unwrap();
+
+    let (ctors, dtors) =
+        if use_ctors_section { ("ctors", "dtors") } else { ("init_array", "fini_array") };
+    for name in [ctors, dtors] {
+        writeln!(
+            script,
+            "  .{name} : ALIGN({align})\n  {{\n    __{name}_start = .;\n    \
+            KEEP(*(SORT_BY_INIT_PRIORITY(.{name}.*)));\n    KEEP(*(.{name}));\n    \
+            __{name}_end = .;\n  }} > FLASH\n",
+            name = name,
+            align = align
+        )
+        .unwrap();
+    }
+
+    let writable_sections: String = custom_sections
+        .iter()
+        .filter(|(_, &p)| p == Placement::Writable)
+        .map(|(section, _)| format!("    KEEP(*({}));\n", section))
+        .collect();
+    write!(
+        script,
+        r#"  /* Copied from FLASH to RAM by the startup code, from `_sidata` to `_sdata`. */
+  .data : ALIGN({align})
+  {{
+    _sdata = .;
+    *(.data .data.*);
+{writable_sections}    . = ALIGN({align});
+    _edata = .;
+  }} > RAM AT > FLASH
+  _sidata = LOADADDR(.data);
+
+  /* Zeroed by the startup code, from `_sbss` to `_ebss`. */
+  .bss (NOLOAD) : ALIGN({align})
+  {{
+    _sbss = .;
+    *(.bss .bss.*);
+    *(COMMON);
+    . = ALIGN({align});
+    _ebss = .;
+  }} > RAM
+
+  /* The heap is the RAM left between the static data and the stack. */
+  _heap_start = _ebss;
+  _heap_end = _stack_start;
+}}
+"#,
+        align = align,
+        writable_sections = writable_sections,
+    )
+    .unwrap();
+
+    if let Err(err) = fs::write(path, script) {
+        sess.err(&format!(
+            "failed to write linker script template to `{}`: {}",
+            path.display(),
+            err
+        ));
+    }
+}
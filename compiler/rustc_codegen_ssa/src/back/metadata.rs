@@ -143,11 +143,18 @@ fn create_object_file(sess: &Session) -> Option<write::Object> {
             let e_flags = elf::EF_MIPS_ARCH_64R2 | elf::EF_MIPS_CPIC | elf::EF_MIPS_PIC;
             file.flags = FileFlags::Elf { e_flags };
         }
-        Architecture::Riscv64 if sess.target.options.features.contains("+d") => {
-            // copied from `riscv64-linux-gnu-gcc foo.c -c`, note though
-            // that the `+d` target feature represents whether the double
-            // float abi is enabled.
-            let e_flags = elf::EF_RISCV_RVC | elf::EF_RISCV_FLOAT_ABI_DOUBLE;
+        Architecture::Riscv32 | Architecture::Riscv64 => {
+            // The linker refuses to link object files with different floating-point ABIs, so
+            // the flags must match those of the object files LLVM generates for the ABI, which
+            // may have been selected with `-Z riscv-abi`.
+            let mut e_flags = match &*sess.target.llvm_abiname {
+                "ilp32f" | "lp64f" => elf::EF_RISCV_FLOAT_ABI_SINGLE,
+                "ilp32d" | "lp64d" => elf::EF_RISCV_FLOAT_ABI_DOUBLE,
+                _ => elf::EF_RISCV_FLOAT_ABI_SOFT,
+            };
+            if sess.target.options.features.contains("+c") {
+                e_flags |= elf::EF_RISCV_RVC;
+            }
             file.flags = FileFlags::Elf { e_flags };
         }
         _ => {}
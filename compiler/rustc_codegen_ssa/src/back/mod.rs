@@ -2,8 +2,12 @@
 pub mod command;
 pub mod link;
 pub mod linker;
+pub mod linker_script;
 pub mod lto;
 pub mod metadata;
 pub mod rpath;
+pub mod shared_generics;
+pub mod size_report;
+pub mod stack_depth;
 pub mod symbol_export;
 pub mod write;
@@ -0,0 +1,194 @@
+//! The index of `-Z shared-generics-dir`, a directory holding one file per crate compiled with
+//! it. Each file lists the generic instances the crate exports, which the crates compiled after
+//! it link to instead of instantiating them again. The files are made of lines of the form:
+//!
+//! ```text
+//! crate <name> <stable crate id>
+//! instance <instance hash> <symbol name>
+//! ```
+//!
+//! A crate linking to the instances of another crate of the index has to be linked together
+//! with it. The crates providing the instances of a crate are recorded in its metadata, and
+//! checked when linking the final artifacts.
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
+use rustc_middle::middle::exported_symbols::{instance_hash, ExportedSymbol, SharedGenericsIndex};
+use rustc_middle::ty::query::Providers;
+use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_session::config::CrateType;
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+fn shared_generics_index(tcx: TyCtxt<'_>, (): ()) -> SharedGenericsIndex {
+    let mut index = SharedGenericsIndex::default();
+    let dir = match tcx.sess.opts.debugging_opts.shared_generics_dir {
+        Some(ref dir) if tcx.sess.opts.share_generics() => dir,
+        _ => return index,
+    };
+
+    let mut paths = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "generics"))
+            .collect::<Vec<_>>(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return index,
+        Err(err) => tcx.sess.fatal(&format!(
+            "failed to read the shared generics index `{}`: {}",
+            dir.display(),
+            err
+        )),
+    };
+    // When several crates export the same instance, the first one by file name provides it,
+    // regardless of the order the directory is listed in.
+    paths.sort();
+
+    for path in paths {
+        let read = fs::read_to_string(&path).ok().and_then(|contents| read_file(&contents));
+        let (stable_crate_id, name, instances) = match read {
+            Some(read) => read,
+            None => {
+                tcx.sess.warn(&format!(
+                    "ignoring malformed shared generics index file `{}`",
+                    path.display()
+                ));
+                continue;
+            }
+        };
+        for (hash, symbol_name) in instances {
+            index.instances.entry(hash).or_insert((stable_crate_id, symbol_name));
+        }
+        index.crates.insert(stable_crate_id, name);
+    }
+    index
+}
+
+fn read_file(contents: &str) -> Option<(u64, String, Vec<(Fingerprint, String)>)> {
+    let mut lines = contents.lines();
+    let (name, stable_crate_id) = match *lines.next()?.split(' ').collect::<Vec<_>>() {
+        ["crate", name, stable_crate_id] => (name, parse_u64(stable_crate_id)?),
+        _ => return None,
+    };
+
+    let mut instances = Vec::new();
+    for line in lines {
+        match *line.splitn(3, ' ').collect::<Vec<_>>() {
+            ["instance", hash, symbol_name] if hash.len() == 32 => {
+                let hash = Fingerprint::new(parse_u64(&hash[..16])?, parse_u64(&hash[16..])?);
+                instances.push((hash, symbol_name.to_string()));
+            }
+            _ => return None,
+        }
+    }
+    Some((stable_crate_id, name.to_string(), instances))
+}
+
+fn parse_u64(hex: &str) -> Option<u64> {
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// Writes the index file of the local crate, if it exports generic instances, and checks that
+/// the final artifacts link the crates providing the instances that their crates link to.
+pub fn update_index(tcx: TyCtxt<'_>) {
+    let dir = match tcx.sess.opts.debugging_opts.shared_generics_dir {
+        Some(ref dir) if tcx.sess.opts.share_generics() => dir,
+        _ => return,
+    };
+
+    if tcx.sess.crate_types().iter().any(|&crate_type| crate_type != CrateType::Rlib) {
+        check_providers_linked(tcx);
+    }
+
+    if tcx.local_crate_exports_generics() {
+        if let Err(err) = write_file(tcx, dir) {
+            tcx.sess.err(&format!(
+                "failed to write to the shared generics index `{}`: {}",
+                dir.display(),
+                err
+            ));
+        }
+    }
+}
+
+fn write_file(tcx: TyCtxt<'_>, dir: &Path) -> io::Result<()> {
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let stable_crate_id = tcx.sess.local_stable_crate_id().to_u64();
+
+    let mut instances: Vec<_> = tcx
+        .exported_symbols(LOCAL_CRATE)
+        .iter()
+        .filter_map(|&(exported_symbol, _)| match exported_symbol {
+            ExportedSymbol::Generic(def_id, substs) => Some(Instance::new(def_id, substs)),
+            ExportedSymbol::DropGlue(ty) => Some(Instance::resolve_drop_in_place(tcx, ty)),
+            ExportedSymbol::NonGeneric(_) | ExportedSymbol::NoDefId(_) => None,
+        })
+        .map(|instance| (instance_hash(tcx, instance), tcx.symbol_name(instance).name))
+        .collect();
+    instances.sort_unstable();
+
+    let mut contents = format!("crate {} {:016x}\n", crate_name, stable_crate_id);
+    for (hash, symbol_name) in instances {
+        let (hash_0, hash_1) = hash.as_value();
+        writeln!(contents, "instance {:016x}{:016x} {}", hash_0, hash_1, symbol_name).unwrap();
+    }
+
+    // Crates compiled concurrently may read the index while the file is written, so that it
+    // is only moved into place once complete.
+    fs::create_dir_all(dir)?;
+    let file_name = format!("{}-{:016x}.generics", crate_name, stable_crate_id);
+    let temp_path = dir.join(format!("{}.tmp{}", file_name, std::process::id()));
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, dir.join(file_name))
+}
+
+fn check_providers_linked(tcx: TyCtxt<'_>) {
+    let index = tcx.shared_generics_index(());
+    let linked: FxHashSet<u64> = tcx
+        .crates(())
+        .iter()
+        .map(|&cnum| tcx.stable_crate_id(cnum).to_u64())
+        .chain(Some(tcx.sess.local_stable_crate_id().to_u64()))
+        .collect();
+
+    for cnum in Some(LOCAL_CRATE).into_iter().chain(tcx.crates(()).iter().copied()) {
+        let providers = tcx.shared_generics_providers(cnum);
+        for provider in providers.iter().filter(|provider| !linked.contains(provider)) {
+            let provider_name = index.crates.get(provider).map_or("<unknown>", |name| name);
+            tcx.sess
+                .struct_err(&format!(
+                    "crate `{}` links to generic instantiations of crate `{}`, \
+                     which isn't linked into `{}`",
+                    tcx.crate_name(cnum),
+                    provider_name,
+                    tcx.crate_name(LOCAL_CRATE),
+                ))
+                .note("the instantiations were shared through `-Z shared-generics-dir`")
+                .emit();
+        }
+    }
+}
+
+/// Returns the crates providing the instances the local crate links to, which the collector
+/// records while it decides which instances to instantiate.
+fn shared_generics_providers(tcx: TyCtxt<'_>, cnum: CrateNum) -> &[u64] {
+    assert_eq!(cnum, LOCAL_CRATE);
+    if tcx.sess.opts.debugging_opts.shared_generics_dir.is_none() || !tcx.sess.opts.share_generics()
+    {
+        return &[];
+    }
+
+    tcx.collect_and_partition_mono_items(());
+    let mut providers: Vec<u64> =
+        tcx.sess.shared_generics_providers.lock().iter().copied().collect();
+    providers.sort_unstable();
+    tcx.arena.alloc_from_iter(providers)
+}
+
+pub fn provide(providers: &mut Providers) {
+    providers.shared_generics_index = shared_generics_index;
+    providers.shared_generics_providers = shared_generics_providers;
+}
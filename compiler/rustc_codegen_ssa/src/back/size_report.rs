@@ -0,0 +1,482 @@
+//! The reports of `-Z binary-size-report`, which attributes the bytes of the object files
+//! generated for each codegen unit to the mono items they were generated for, or with
+//! `-Z binary-size-report=linked`, the code and data of a linked executable or dynamic library
+//! to the crates and items they come from.
+//!
+//! The symbols of the mono items, and with `linked` those exported by the other crates,
+//! are collected while the `TyCtxt` is still around, into `CrateInfo::size_report_symbols`.
+//! Once codegen is done, the sizes of those symbols are read back from the object files,
+//! before they are removed, or from the linked artifact. The symbols of the linked artifact
+//! that aren't among them are attributed to the crate whose object files define them, which
+//! are the object files of the codegen units and the members of the rlibs linked statically.
+
+use super::symbol_export::symbol_name_for_instance_in_crate;
+use crate::{CodegenResults, CompiledModule};
+
+use object::read::archive::ArchiveFile;
+use object::{Object, ObjectSection, ObjectSymbol, SectionIndex, SectionKind, SymbolKind};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
+use rustc_middle::middle::dependency_format::Linkage;
+use rustc_middle::middle::exported_symbols::ExportedSymbol;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{CrateType, ReportFormat};
+use rustc_session::Session;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// How many of the largest items the human-readable report lists.
+const HUMAN_REPORT_ITEMS: usize = 20;
+
+/// What the compiler knows about the mono item a symbol was generated for.
+#[derive(Debug, Encodable, Decodable)]
+pub struct SymbolProvenance {
+    /// The mono item, with its generic arguments.
+    pub item: String,
+    /// The path of the generic function the item is an instantiation of, if it is one.
+    pub generic: Option<String>,
+    /// The crate defining the item, or the generic function it instantiates.
+    pub crate_name: String,
+}
+
+/// Collects the symbols of the mono items of the local crate, for `-Z binary-size-report`, and
+/// of the items exported by the other crates, for `-Z binary-size-report=linked`.
+pub fn collect_symbol_provenance(tcx: TyCtxt<'_>) -> FxHashMap<String, SymbolProvenance> {
+    let path_str = |def_id| with_no_trimmed_paths(|| tcx.def_path_str(def_id));
+    let mut symbols = FxHashMap::default();
+    for cgu in tcx.collect_and_partition_mono_items(()).1 {
+        for &item in cgu.items().keys() {
+            let (item_name, generic, krate) = match item {
+                MonoItem::Fn(instance) => {
+                    let def_id = instance.def_id();
+                    let is_generic = instance.substs.non_erasable_generics().next().is_some();
+                    let name = with_no_trimmed_paths(|| instance.to_string());
+                    (name, is_generic.then(|| path_str(def_id)), def_id.krate)
+                }
+                MonoItem::Static(def_id) => (path_str(def_id), None, def_id.krate),
+                // Global assembly doesn't define a symbol of its own.
+                MonoItem::GlobalAsm(_) => continue,
+            };
+            let provenance = SymbolProvenance {
+                item: item_name,
+                generic,
+                crate_name: tcx.crate_name(krate).to_string(),
+            };
+            symbols.insert(item.symbol_name(tcx).name.to_string(), provenance);
+        }
+    }
+
+    if !tcx.sess.opts.debugging_opts.binary_size_report.map_or(false, |report| report.linked) {
+        return symbols;
+    }
+    for &cnum in tcx.crates(()) {
+        for &(symbol, _) in tcx.exported_symbols(cnum) {
+            let (instance, generic) = match symbol {
+                ExportedSymbol::NonGeneric(def_id) => (Instance::mono(tcx, def_id), None),
+                ExportedSymbol::Generic(def_id, substs) => {
+                    (Instance::new(def_id, substs), Some(path_str(def_id)))
+                }
+                ExportedSymbol::DropGlue(ty) => (Instance::resolve_drop_in_place(tcx, ty), None),
+                ExportedSymbol::NoDefId(_) => continue,
+            };
+            let provenance = SymbolProvenance {
+                item: with_no_trimmed_paths(|| instance.to_string()),
+                generic,
+                crate_name: tcx.crate_name(instance.def_id().krate).to_string(),
+            };
+            symbols.insert(symbol_name_for_instance_in_crate(tcx, symbol, cnum), provenance);
+        }
+    }
+    symbols
+}
+
+/// A symbol defined in one of the generated object files.
+struct SizedSymbol<'a> {
+    name: String,
+    cgu: &'a str,
+    size: u64,
+    provenance: Option<&'a SymbolProvenance>,
+}
+
+/// Prints the report of `-Z binary-size-report`.
+pub fn print_binary_size_report(
+    sess: &Session,
+    format: ReportFormat,
+    codegen_results: &CodegenResults,
+) {
+    let provenance = match codegen_results.crate_info.size_report_symbols {
+        Some(ref provenance) => provenance,
+        None => return,
+    };
+
+    let modules = codegen_results.modules.iter().chain(codegen_results.allocator_module.iter());
+    let mut symbols = Vec::new();
+    for module in modules {
+        if let Err(err) = read_module_symbols(module, provenance, &mut symbols) {
+            sess.warn(&format!(
+                "couldn't read the symbols of codegen unit `{}` for `-Z binary-size-report`: {}",
+                module.name, err
+            ));
+        }
+    }
+    symbols.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    let total: u64 = symbols.iter().map(|symbol| symbol.size).sum();
+    let mut crates: BTreeMap<&str, u64> = BTreeMap::new();
+    // The number of instantiations and total size of each generic function.
+    let mut generics: BTreeMap<&str, (usize, u64)> = BTreeMap::new();
+    for symbol in &symbols {
+        let krate = symbol.provenance.map_or("<unknown>", |p| &p.crate_name);
+        *crates.entry(krate).or_default() += symbol.size;
+        if let Some(generic) = symbol.provenance.and_then(|p| p.generic.as_deref()) {
+            let entry = generics.entry(generic).or_default();
+            entry.0 += 1;
+            entry.1 += symbol.size;
+        }
+    }
+    let mut crates: Vec<_> = crates.into_iter().collect();
+    crates.sort_by(|a, b| b.1.cmp(&a.1));
+    let mut generics: Vec<_> = generics.into_iter().collect();
+    generics.sort_by(|a, b| (b.1).1.cmp(&(a.1).1));
+
+    if format == ReportFormat::Json {
+        let crates = crates
+            .iter()
+            .map(|&(name, size)| {
+                json_object(vec![("name", name.to_json()), ("size", size.to_json())])
+            })
+            .collect();
+        let generics = generics
+            .iter()
+            .map(|&(name, (instantiations, size))| {
+                json_object(vec![
+                    ("name", name.to_json()),
+                    ("instantiations", instantiations.to_json()),
+                    ("size", size.to_json()),
+                ])
+            })
+            .collect();
+        let items = symbols
+            .iter()
+            .map(|symbol| {
+                let provenance =
+                    |f: fn(&SymbolProvenance) -> Json| symbol.provenance.map_or(Json::Null, f);
+                json_object(vec![
+                    ("symbol", symbol.name.to_json()),
+                    ("item", provenance(|p| p.item.to_json())),
+                    ("generic", provenance(|p| p.generic.to_json())),
+                    ("crate", provenance(|p| p.crate_name.to_json())),
+                    ("cgu", symbol.cgu.to_json()),
+                    ("size", symbol.size.to_json()),
+                ])
+            })
+            .collect();
+        let report = json_object(vec![
+            ("total", total.to_json()),
+            ("crates", Json::Array(crates)),
+            ("generics", Json::Array(generics)),
+            ("items", Json::Array(items)),
+        ]);
+        println!("{}", report);
+        return;
+    }
+
+    let percent = |size: u64| if total == 0 { 0.0 } else { size as f64 * 100.0 / total as f64 };
+    println!("binary size report: {} bytes", total);
+    println!();
+    println!("{:>10} {:>6}  crate", "size", "%");
+    for &(name, size) in &crates {
+        println!("{:>10} {:>5.1}%  {}", size, percent(size), name);
+    }
+    if !generics.is_empty() {
+        println!();
+        println!("{:>10} {:>6} {:>6}  generic", "size", "%", "count");
+        for &(name, (instantiations, size)) in &generics {
+            println!("{:>10} {:>5.1}% {:>6}  {}", size, percent(size), instantiations, name);
+        }
+    }
+    println!();
+    println!("{:>10} {:>6}  item (codegen unit)", "size", "%");
+    for symbol in symbols.iter().take(HUMAN_REPORT_ITEMS) {
+        let name = symbol.provenance.map_or(&symbol.name, |p| &p.item);
+        println!("{:>10} {:>5.1}%  {} ({})", symbol.size, percent(symbol.size), name, symbol.cgu);
+    }
+    if symbols.len() > HUMAN_REPORT_ITEMS {
+        println!("{:>10} {:>6}  {} more items", "", "", symbols.len() - HUMAN_REPORT_ITEMS);
+    }
+}
+
+/// A symbol defined in a linked artifact.
+struct LinkedSymbol<'a> {
+    name: String,
+    size: u64,
+    /// Whether the symbol is code rather than data.
+    is_text: bool,
+    crate_name: &'a str,
+    provenance: Option<&'a SymbolProvenance>,
+}
+
+/// Prints the report of `-Z binary-size-report=linked` for the artifact of type `crate_type` linked to
+/// `out_filename`.
+pub fn print_size_report(
+    sess: &Session,
+    format: ReportFormat,
+    crate_type: CrateType,
+    out_filename: &Path,
+    codegen_results: &CodegenResults,
+) {
+    let provenance = match codegen_results.crate_info.size_report_symbols {
+        Some(ref provenance) => provenance,
+        None => return,
+    };
+    let artifact = out_filename.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let sizes = fs::read(out_filename)
+        .map_err(|err| err.to_string())
+        .and_then(|data| symbol_sizes(&data).map_err(|err| err.to_string()));
+    let sizes = match sizes {
+        Ok(sizes) if sizes.is_empty() => {
+            sess.warn(&format!(
+                "no size report is printed for `{}`: it has no symbol table, its symbols may \
+                have been stripped",
+                artifact
+            ));
+            return;
+        }
+        Ok(sizes) => sizes,
+        Err(err) => {
+            sess.warn(&format!("couldn't read the symbols of `{}`: {}", artifact, err));
+            return;
+        }
+    };
+
+    let info = &codegen_results.crate_info;
+    let local_crate_name = info.local_crate_name.to_string();
+    let crate_symbols = crate_symbols(sess, crate_type, codegen_results);
+    let mut symbols: Vec<_> = sizes
+        .into_iter()
+        .map(|(name, size, kind)| {
+            let provenance = lookup(provenance, &name);
+            let crate_name: &str = match (provenance, lookup(&crate_symbols, &name)) {
+                (Some(provenance), _) => &provenance.crate_name,
+                (None, Some(&cnum)) if cnum == LOCAL_CRATE => &local_crate_name,
+                (None, Some(cnum)) => &info.crate_name[cnum],
+                (None, None) => "<unknown>",
+            };
+            let is_text = kind == SectionKind::Text;
+            LinkedSymbol { name, size, is_text, crate_name, provenance }
+        })
+        .collect();
+    symbols.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    // The bytes of code and data of the whole artifact and of each crate.
+    let mut total = (0, 0);
+    let mut crates: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for symbol in &symbols {
+        let entry = crates.entry(symbol.crate_name).or_default();
+        if symbol.is_text {
+            total.0 += symbol.size;
+            entry.0 += symbol.size;
+        } else {
+            total.1 += symbol.size;
+            entry.1 += symbol.size;
+        }
+    }
+    let mut crates: Vec<_> = crates.into_iter().collect();
+    crates.sort_by(|a, b| (b.1.0 + b.1.1).cmp(&(a.1.0 + a.1.1)));
+
+    let section = |symbol: &LinkedSymbol<'_>| if symbol.is_text { "text" } else { "data" };
+    if format == ReportFormat::Json {
+        let crates = crates
+            .iter()
+            .map(|&(name, (text, data))| {
+                json_object(vec![
+                    ("name", name.to_json()),
+                    ("text", text.to_json()),
+                    ("data", data.to_json()),
+                ])
+            })
+            .collect();
+        let items = symbols
+            .iter()
+            .map(|symbol| {
+                let provenance =
+                    |f: fn(&SymbolProvenance) -> Json| symbol.provenance.map_or(Json::Null, f);
+                json_object(vec![
+                    ("symbol", symbol.name.to_json()),
+                    ("item", provenance(|p| p.item.to_json())),
+                    ("generic", provenance(|p| p.generic.to_json())),
+                    ("crate", symbol.crate_name.to_json()),
+                    ("section", section(symbol).to_json()),
+                    ("size", symbol.size.to_json()),
+                ])
+            })
+            .collect();
+        let report = json_object(vec![
+            ("artifact", artifact.to_json()),
+            ("text", total.0.to_json()),
+            ("data", total.1.to_json()),
+            ("crates", Json::Array(crates)),
+            ("items", Json::Array(items)),
+        ]);
+        println!("{}", report);
+        return;
+    }
+
+    println!(
+        "size report for `{}`: {} bytes of code, {} bytes of data",
+        artifact, total.0, total.1
+    );
+    println!();
+    println!("{:>10} {:>10}  crate", "text", "data");
+    for &(name, (text, data)) in &crates {
+        println!("{:>10} {:>10}  {}", text, data, name);
+    }
+    println!();
+    println!("{:>10} {:>7}  item (crate)", "size", "section");
+    for symbol in symbols.iter().take(HUMAN_REPORT_ITEMS) {
+        let name = symbol.provenance.map_or(&symbol.name, |p| &p.item);
+        println!("{:>10} {:>7}  {} ({})", symbol.size, section(symbol), name, symbol.crate_name);
+    }
+    if symbols.len() > HUMAN_REPORT_ITEMS {
+        println!("{:>10} {:>7}  {} more items", "", "", symbols.len() - HUMAN_REPORT_ITEMS);
+    }
+}
+
+/// Builds a JSON object with the given fields.
+fn json_object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+/// Maps the symbols defined by the object files of the codegen units of the local crate, and of
+/// the rlibs linked statically into the artifact of type `crate_type`, to their crate.
+fn crate_symbols(
+    sess: &Session,
+    crate_type: CrateType,
+    codegen_results: &CodegenResults,
+) -> FxHashMap<String, CrateNum> {
+    let info = &codegen_results.crate_info;
+    let mut symbols = FxHashMap::default();
+    let mut objects = Vec::new();
+    let modules = codegen_results.modules.iter().chain(codegen_results.allocator_module.iter());
+    objects.extend(modules.filter_map(|module| Some((module.object.as_deref()?, LOCAL_CRATE))));
+    let linkage =
+        info.dependency_formats.iter().find_map(|(ty, list)| (*ty == crate_type).then(|| list));
+    for (index, &linkage) in linkage.into_iter().flatten().enumerate() {
+        let cnum = CrateNum::new(index + 1);
+        if linkage == Linkage::Static {
+            if let Some((ref rlib, _)) = info.used_crate_source[&cnum].rlib {
+                objects.push((rlib.as_path(), cnum));
+            }
+        }
+    }
+
+    for (path, cnum) in objects {
+        if let Err(err) = read_defined_symbols(path, cnum, &mut symbols) {
+            sess.warn(&format!(
+                "couldn't read the symbols of `{}` for `-Z binary-size-report=linked`: {}",
+                path.display(),
+                err
+            ));
+        }
+    }
+    symbols
+}
+
+/// Maps the symbols defined by the object file or archive at `path` to `cnum`.
+fn read_defined_symbols(
+    path: &Path,
+    cnum: CrateNum,
+    symbols: &mut FxHashMap<String, CrateNum>,
+) -> Result<(), String> {
+    let data = fs::read(path).map_err(|err| err.to_string())?;
+    let mut add_object = |data: &[u8]| {
+        // Archives also contain members that aren't object files, such as the metadata.
+        for (name, ..) in symbol_sizes(data).unwrap_or_default() {
+            symbols.insert(name, cnum);
+        }
+    };
+    if !data.starts_with(b"!<arch>\n") {
+        add_object(&data);
+        return Ok(());
+    }
+    let archive = ArchiveFile::parse(&*data).map_err(|err| err.to_string())?;
+    for member in archive.members() {
+        let member = member.map_err(|err| err.to_string())?;
+        add_object(member.data(&*data).map_err(|err| err.to_string())?);
+    }
+    Ok(())
+}
+
+/// Reads the sizes of the code and data symbols defined by the object file of `module`.
+fn read_module_symbols<'a>(
+    module: &'a CompiledModule,
+    provenance: &'a FxHashMap<String, SymbolProvenance>,
+    symbols: &mut Vec<SizedSymbol<'a>>,
+) -> Result<(), String> {
+    let path = match module.object {
+        Some(ref path) => path,
+        None => return Ok(()),
+    };
+    let data = fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let sizes = symbol_sizes(&data).map_err(|err| format!("{}: {}", path.display(), err))?;
+    for (name, size, _) in sizes {
+        let item = lookup(provenance, &name);
+        symbols.push(SizedSymbol { name, cgu: &module.name, size, provenance: item });
+    }
+    Ok(())
+}
+
+/// Looks up the entry of a symbol of an object file in a map keyed by symbol name.
+fn lookup<'a, T>(map: &'a FxHashMap<String, T>, name: &str) -> Option<&'a T> {
+    // ThinLTO suffixes the names of the internal symbols it promotes with `.llvm.<hash>`, and
+    // some targets, like macOS, prefix the symbol names with an underscore.
+    let name = name.find(".llvm.").map_or(name, |end| &name[..end]);
+    map.get(name).or_else(|| map.get(name.strip_prefix('_')?))
+}
+
+/// Returns the names, sizes and section kinds of the code and data symbols defined in an
+/// object file. Symbols without a recorded size, as in Mach-O and COFF files, extend to the
+/// next symbol of their section, or to its end.
+fn symbol_sizes(data: &[u8]) -> Result<Vec<(String, u64, SectionKind)>, object::Error> {
+    let file = object::File::parse(data)?;
+    let mut defined: Vec<(SectionIndex, u64, u64, &str)> = Vec::new();
+    for symbol in file.symbols() {
+        let is_code_or_data = matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data);
+        if !symbol.is_definition() || !is_code_or_data {
+            continue;
+        }
+        if let Some(section) = symbol.section_index() {
+            defined.push((section, symbol.address(), symbol.size(), symbol.name()?));
+        }
+    }
+    defined.sort_by_key(|&(section, address, ..)| (section.0, address));
+
+    let mut sizes = Vec::with_capacity(defined.len());
+    for (i, &(section, address, size, name)) in defined.iter().enumerate() {
+        let kind = file.section_by_index(section)?.kind();
+        let size = if size != 0 {
+            size
+        } else {
+            let next = defined[i + 1..]
+                .iter()
+                .take_while(|&&(next_section, ..)| next_section == section)
+                .map(|&(_, next_address, ..)| next_address)
+                .find(|&next_address| next_address > address);
+            let end = match next {
+                Some(next) => next,
+                None => {
+                    let section = file.section_by_index(section)?;
+                    section.address() + section.size()
+                }
+            };
+            end.saturating_sub(address)
+        };
+        sizes.push((name.to_string(), size, kind));
+    }
+    Ok(sizes)
+}
@@ -0,0 +1,459 @@
+//! The report of `-Z stack-depth-report`, which gives the worst-case stack depth of each entry
+//! point of a statically linked executable.
+//!
+//! Each codegen unit records the calls made by its functions in the `.rustc_callgraph` section,
+//! which is not loaded at run time, and LLVM records the stack size of each function in the
+//! `.stack_sizes` section. The linker concatenates those sections like any other, so that once
+//! the executable is linked, both are read back from it and combined. The entry points are the
+//! functions that no function calls directly, such as `main` and interrupt handlers.
+//!
+//! The call graph section is made of lines of the form:
+//!
+//! ```text
+//! F <symbol> <readable name>
+//! C <caller symbol> <callee symbol>
+//! I <caller symbol> <description of the indirect call>
+//! ```
+
+use crate::traits::AsmMethods;
+
+use object::{Object, ObjectSection, ObjectSymbol, SymbolKind};
+use rustc_ast::{InlineAsmOptions, InlineAsmTemplatePiece};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::lang_items::LangItem;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::mir::{AssertKind, TerminatorKind};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_session::config::EntryFnType;
+use rustc_session::Session;
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+const CALL_GRAPH_SECTION: &str = ".rustc_callgraph";
+const STACK_SIZES_SECTION: &str = ".stack_sizes";
+
+/// Defines the part of the call graph section recording the calls made by the functions of
+/// `cgu`, with `-Z stack-depth-report`.
+pub fn codegen_call_graph<'tcx, Cx: AsmMethods>(
+    cx: &Cx,
+    tcx: TyCtxt<'tcx>,
+    cgu: &CodegenUnit<'tcx>,
+) {
+    let sess = tcx.sess;
+    if sess.opts.debugging_opts.stack_depth_report.is_none()
+        || !sess.target_produces_elf()
+        || sess.asm_arch.is_none()
+    {
+        return;
+    }
+
+    let mut records = String::new();
+    for (item, _) in cgu.items_in_deterministic_order(tcx) {
+        let instance = match item {
+            MonoItem::Fn(instance) => instance,
+            MonoItem::Static(_) | MonoItem::GlobalAsm(_) => continue,
+        };
+        let symbol = tcx.symbol_name(instance).name;
+        let name = with_no_trimmed_paths(|| instance.to_string());
+        writeln!(records, "F {} {}", symbol, name).unwrap();
+        for callee in callees(tcx, instance) {
+            match callee {
+                Callee::Direct(callee) => {
+                    writeln!(records, "C {} {}", symbol, tcx.symbol_name(callee).name).unwrap()
+                }
+                Callee::Indirect(description) => {
+                    writeln!(records, "I {} {}", symbol, description).unwrap()
+                }
+            }
+        }
+    }
+    if let Some(callee) = entry_wrapper_callee(tcx, cgu) {
+        writeln!(records, "F main main").unwrap();
+        writeln!(records, "C main {}", tcx.symbol_name(callee).name).unwrap();
+    }
+    if records.is_empty() {
+        return;
+    }
+
+    // The section has no flags, so that it isn't loaded at run time nor discarded by
+    // `--gc-sections`.
+    let mut asm = format!(".pushsection {},\"\",%progbits\n", CALL_GRAPH_SECTION);
+    for line in records.lines() {
+        let line = line.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(asm, ".ascii \"{}\\n\"", line).unwrap();
+    }
+    asm.push_str(".popsection\n");
+    cx.codegen_global_asm(
+        &[InlineAsmTemplatePiece::String(asm)],
+        &[],
+        InlineAsmOptions::ATT_SYNTAX,
+        &[],
+    );
+}
+
+enum Callee<'tcx> {
+    Direct(Instance<'tcx>),
+    Indirect(String),
+}
+
+/// The functions `instance` calls, and the calls it makes through function pointers and
+/// vtables, which can't be followed.
+fn callees<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Vec<Callee<'tcx>> {
+    let param_env = ty::ParamEnv::reveal_all();
+    let body = tcx.instance_mir(instance.def);
+    let monomorphize =
+        |ty: ty::Ty<'tcx>| instance.subst_mir_and_normalize_erasing_regions(tcx, param_env, ty);
+
+    let mut callees = Vec::new();
+    for data in body.basic_blocks() {
+        match data.terminator().kind {
+            TerminatorKind::Call { ref func, .. } => {
+                let func_ty = monomorphize(func.ty(body, tcx));
+                match *func_ty.kind() {
+                    ty::FnDef(def_id, substs) => {
+                        match Instance::resolve(tcx, param_env, def_id, substs) {
+                            Ok(Some(callee)) => match callee.def {
+                                InstanceDef::Intrinsic(_) => {}
+                                InstanceDef::Virtual(..) => {
+                                    let callee = with_no_trimmed_paths(|| callee.to_string());
+                                    let description =
+                                        format!("call of `{}` through a vtable", callee);
+                                    callees.push(Callee::Indirect(description));
+                                }
+                                _ => callees.push(Callee::Direct(callee.polymorphize(tcx))),
+                            },
+                            Ok(None) | Err(_) => {}
+                        }
+                    }
+                    ty::FnPtr(sig) => {
+                        let sig = with_no_trimmed_paths(|| sig.to_string());
+                        let description =
+                            format!("call through a function pointer of type `{}`", sig);
+                        callees.push(Callee::Indirect(description));
+                    }
+                    _ => {}
+                }
+            }
+            TerminatorKind::Drop { place, .. } | TerminatorKind::DropAndReplace { place, .. } => {
+                let ty = monomorphize(place.ty(body, tcx).ty);
+                let callee = Instance::resolve_drop_in_place(tcx, ty);
+                if !matches!(callee.def, InstanceDef::DropGlue(_, None)) {
+                    callees.push(Callee::Direct(callee.polymorphize(tcx)));
+                }
+            }
+            TerminatorKind::Assert { ref msg, .. } => {
+                let lang_item = match **msg {
+                    AssertKind::BoundsCheck { .. } => LangItem::PanicBoundsCheck,
+                    _ => LangItem::Panic,
+                };
+                let def_id = tcx.require_lang_item(lang_item, None);
+                callees.push(Callee::Direct(Instance::mono(tcx, def_id)));
+            }
+            _ => {}
+        }
+    }
+    callees
+}
+
+/// The function the C `main` generated in `cgu` calls, if it is generated in it.
+fn entry_wrapper_callee<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    cgu: &CodegenUnit<'tcx>,
+) -> Option<Instance<'tcx>> {
+    let (main_def_id, entry_type) = tcx.entry_fn(())?;
+    let main = Instance::mono(tcx, main_def_id);
+    // This must be kept in sync with `maybe_create_entry_wrapper`.
+    let generated_here = if main_def_id.is_local() {
+        cgu.contains_item(&MonoItem::Fn(main))
+    } else {
+        cgu.is_primary()
+    };
+    if !generated_here {
+        return None;
+    }
+    if entry_type == EntryFnType::Start {
+        return Some(main);
+    }
+
+    let start_def_id = tcx.require_lang_item(LangItem::Start, None);
+    let main_ret_ty = tcx.fn_sig(main_def_id).output();
+    let main_ret_ty = tcx.erase_regions(main_ret_ty.no_bound_vars().unwrap());
+    let substs = tcx.intern_substs(&[main_ret_ty.into()]);
+    Instance::resolve(tcx, ty::ParamEnv::reveal_all(), start_def_id, substs).ok().flatten()
+}
+
+/// A function of the executable.
+#[derive(Default)]
+struct Function {
+    name: Option<String>,
+    /// Whether the executable defines the function, which it doesn't if LLVM inlined all of
+    /// its calls.
+    defined: bool,
+    stack_size: Option<u64>,
+    callees: Vec<usize>,
+    indirect_calls: Vec<String>,
+}
+
+/// The worst-case stack depth from a function.
+#[derive(Clone)]
+struct Depth {
+    bytes: u64,
+    /// The functions of the deepest call chain, starting with the function itself.
+    path: Vec<usize>,
+    /// The functions reachable from the function whose stack size isn't known.
+    unknown: BTreeSet<usize>,
+    /// The functions reachable from the function that make indirect calls, which aren't
+    /// followed.
+    indirect: BTreeSet<usize>,
+    /// A function reachable from the function that can call itself, through which the depth
+    /// is unbounded.
+    recursion: Option<usize>,
+}
+
+/// Writes the report of `-Z stack-depth-report` for the executable `out_filename`.
+pub fn write_stack_depth_report(sess: &Session, out_filename: &Path, path: &Path) {
+    let data = match fs::read(out_filename) {
+        Ok(data) => data,
+        Err(err) => {
+            sess.err(&format!("failed to read `{}`: {}", out_filename.display(), err));
+            return;
+        }
+    };
+    let file = match object::File::parse(&*data) {
+        Ok(file) => file,
+        Err(err) => {
+            sess.err(&format!("failed to parse `{}`: {}", out_filename.display(), err));
+            return;
+        }
+    };
+
+    let (call_graph, stack_sizes) = match (
+        file.section_by_name(CALL_GRAPH_SECTION).and_then(|section| section.data().ok()),
+        file.section_by_name(STACK_SIZES_SECTION).and_then(|section| section.data().ok()),
+    ) {
+        (Some(call_graph), Some(stack_sizes)) => (call_graph, stack_sizes),
+        _ => {
+            sess.warn(&format!(
+                "`{}` has no call graph or stack sizes, no stack depth report is written",
+                out_filename.display()
+            ));
+            return;
+        }
+    };
+
+    let mut functions = Vec::new();
+    let mut indices = FxHashMap::default();
+    let mut index_of = |symbol: &str| {
+        *indices.entry(symbol.to_string()).or_insert_with(|| {
+            functions.push((symbol.to_string(), Function::default()));
+            functions.len() - 1
+        })
+    };
+
+    let sizes_by_address = read_stack_sizes(&file, stack_sizes);
+    let mut calls = Vec::new();
+    let mut names = Vec::new();
+    let mut indirect_calls = Vec::new();
+    for line in String::from_utf8_lossy(call_graph).lines() {
+        let mut parts = line.splitn(3, ' ');
+        let (kind, symbol, rest) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(kind), Some(symbol), Some(rest)) => (kind, symbol, rest),
+            _ => continue,
+        };
+        let index = index_of(symbol);
+        match kind {
+            "F" => names.push((index, rest.to_string())),
+            "C" => calls.push((index, index_of(rest))),
+            "I" => indirect_calls.push((index, rest.to_string())),
+            _ => {}
+        }
+    }
+    for symbol in file.symbols().filter(|symbol| symbol.kind() == SymbolKind::Text) {
+        let name = match symbol.name() {
+            Ok(name) if !name.is_empty() => name,
+            _ => continue,
+        };
+        // Thumb functions have the lowest bit of their address set in the symbol table.
+        let size = sizes_by_address
+            .get(&symbol.address())
+            .or_else(|| sizes_by_address.get(&(symbol.address() & !1)));
+        let index = index_of(name);
+        let function = &mut functions[index].1;
+        function.defined = true;
+        // Functions with internal linkage can be defined in several object files.
+        if let Some(&size) = size {
+            function.stack_size = Some(function.stack_size.map_or(size, |s| s.max(size)));
+        }
+    }
+    for (index, name) in names {
+        functions[index].1.name = Some(name);
+    }
+    for (caller, callee) in calls {
+        functions[caller].1.callees.push(callee);
+    }
+    for (caller, description) in indirect_calls {
+        functions[caller].1.indirect_calls.push(description);
+    }
+    for (_, function) in &mut functions {
+        function.callees.sort_unstable();
+        function.callees.dedup();
+    }
+
+    // The entry points are the functions of the call graph that no function calls directly.
+    let mut called = vec![false; functions.len()];
+    for (_, function) in &functions {
+        for &callee in &function.callees {
+            called[callee] = true;
+        }
+    }
+    let mut memo = vec![None; functions.len()];
+    let mut depths: Vec<(usize, Depth)> = (0..functions.len())
+        .filter(|&index| !called[index] && functions[index].1.name.is_some())
+        .map(|index| (index, depth(&functions, index, &mut memo, &mut Vec::new())))
+        .collect();
+    depths.sort_by(|(a, a_depth), (b, b_depth)| {
+        let a_key = (a_depth.recursion.is_none(), std::cmp::Reverse(a_depth.bytes));
+        let b_key = (b_depth.recursion.is_none(), std::cmp::Reverse(b_depth.bytes));
+        a_key.cmp(&b_key).then_with(|| functions[*a].0.cmp(&functions[*b].0))
+    });
+
+    if let Err(err) = write_report(&functions, &depths, path) {
+        sess.err(&format!("failed to write the stack depth report `{}`: {}", path.display(), err));
+    }
+}
+
+/// Reads the `.stack_sizes` section: for each function, its address followed by its stack size
+/// as an ULEB128.
+fn read_stack_sizes(file: &object::File<'_>, mut data: &[u8]) -> FxHashMap<u64, u64> {
+    let address_size = if file.is_64() { 8 } else { 4 };
+    let mut sizes = FxHashMap::default();
+    while data.len() > address_size {
+        let (address, rest) = data.split_at(address_size);
+        let mut bytes = [0; 8];
+        let address = if file.is_little_endian() {
+            bytes[..address_size].copy_from_slice(address);
+            u64::from_le_bytes(bytes)
+        } else {
+            bytes[8 - address_size..].copy_from_slice(address);
+            u64::from_be_bytes(bytes)
+        };
+        let (mut size, mut shift, mut len) = (0u64, 0, 0);
+        for &byte in rest {
+            size |= u64::from(byte & 0x7f) << shift;
+            shift += 7;
+            len += 1;
+            if byte & 0x80 == 0 || shift >= 64 {
+                break;
+            }
+        }
+        sizes.insert(address, size);
+        data = &rest[len..];
+    }
+    sizes
+}
+
+fn depth(
+    functions: &[(String, Function)],
+    index: usize,
+    memo: &mut Vec<Option<Depth>>,
+    stack: &mut Vec<usize>,
+) -> Depth {
+    if let Some(ref depth) = memo[index] {
+        return depth.clone();
+    }
+    if stack.contains(&index) {
+        return Depth {
+            bytes: 0,
+            path: vec![index],
+            unknown: BTreeSet::new(),
+            indirect: BTreeSet::new(),
+            recursion: Some(index),
+        };
+    }
+
+    let function = &functions[index].1;
+    // A Rust function that isn't defined was inlined into its callers, and so uses their
+    // frames.
+    let own_size = match (function.stack_size, function.defined) {
+        (Some(size), _) => Some(size),
+        (None, false) if function.name.is_some() => Some(0),
+        (None, _) => None,
+    };
+
+    stack.push(index);
+    let mut deepest: Option<Depth> = None;
+    let mut unknown = BTreeSet::new();
+    let mut indirect = BTreeSet::new();
+    let mut recursion = None;
+    for &callee in &function.callees {
+        let callee_depth = depth(functions, callee, memo, stack);
+        unknown.extend(callee_depth.unknown.iter().copied());
+        indirect.extend(callee_depth.indirect.iter().copied());
+        recursion = recursion.or(callee_depth.recursion);
+        if deepest.as_ref().map_or(true, |deepest| callee_depth.bytes > deepest.bytes) {
+            deepest = Some(callee_depth);
+        }
+    }
+    stack.pop();
+
+    if own_size.is_none() {
+        unknown.insert(index);
+    }
+    if !function.indirect_calls.is_empty() {
+        indirect.insert(index);
+    }
+    let mut path = vec![index];
+    let mut bytes = own_size.unwrap_or(0);
+    if let Some(deepest) = deepest {
+        bytes += deepest.bytes;
+        path.extend(deepest.path);
+    }
+    let depth = Depth { bytes, path, unknown, indirect, recursion };
+    // Depths computed while a caller is on the stack may miss the calls back to it.
+    if depth.recursion.is_none() {
+        memo[index] = Some(depth.clone());
+    }
+    depth
+}
+
+fn write_report(
+    functions: &[(String, Function)],
+    depths: &[(usize, Depth)],
+    path: &Path,
+) -> io::Result<()> {
+    let name = |index: usize| {
+        let (ref symbol, ref function) = functions[index];
+        function.name.clone().unwrap_or_else(|| symbol.clone())
+    };
+
+    let mut file = BufWriter::new(fs::File::create(path)?);
+    for (index, depth) in depths {
+        match depth.recursion {
+            Some(recursive) => {
+                writeln!(file, "{}: unbounded, `{}` is recursive", name(*index), name(recursive))?
+            }
+            None => writeln!(file, "{}: {} bytes", name(*index), depth.bytes)?,
+        }
+        for &step in &depth.path {
+            match functions[step].1.stack_size {
+                Some(size) => writeln!(file, "    {} ({} bytes)", name(step), size)?,
+                None => writeln!(file, "    {}", name(step))?,
+            }
+        }
+        for &unknown in &depth.unknown {
+            writeln!(file, "    note: the stack size of `{}` is unknown", name(unknown))?;
+        }
+        for &caller in &depth.indirect {
+            for description in &functions[caller].1.indirect_calls {
+                let caller = name(caller);
+                writeln!(file, "    note: `{}` makes a {}, not followed", caller, description)?;
+            }
+        }
+    }
+    file.flush()
+}
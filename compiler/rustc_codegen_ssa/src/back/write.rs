@@ -8,6 +8,8 @@
 
 use crate::traits::*;
 use jobserver::{Acquired, Client};
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro128StarStar;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::memmap::Mmap;
 use rustc_data_structures::profiling::SelfProfilerRef;
@@ -27,7 +29,7 @@
 use rustc_middle::ty::TyCtxt;
 use rustc_session::cgu_reuse_tracker::CguReuseTracker;
 use rustc_session::config::{self, CrateType, Lto, OutputFilenames, OutputType};
-use rustc_session::config::{Passes, SwitchWithOptPath};
+use rustc_session::config::{Passes, StopAfter, SwitchWithOptPath};
 use rustc_session::Session;
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::sym;
@@ -35,6 +37,7 @@
 use rustc_target::spec::{MergeFunctions, PanicStrategy, SanitizerSet};
 
 use std::any::Any;
+use std::cell::RefCell;
 use std::fs;
 use std::io;
 use std::mem;
@@ -87,6 +90,9 @@ pub struct ModuleConfig {
     pub debug_info_for_profiling: bool,
     pub instrument_coverage: bool,
     pub instrument_gcov: bool,
+    /// Whether the calls of `-C instrument-functions` and `-Z instrument-functions` are
+    /// inserted before inlining, with `-Z instrument-functions-post-inline=no`.
+    pub instrument_functions_pre_inline: bool,
 
     pub sanitizer: SanitizerSet,
     pub sanitizer_recover: SanitizerSet,
@@ -188,6 +194,11 @@ macro_rules! if_regular {
                 sess.opts.debugging_opts.profile && !is_compiler_builtins,
                 false
             ),
+            instrument_functions_pre_inline: if_regular!(
+                sess.instrument_function_hooks().is_some()
+                    && !sess.opts.debugging_opts.instrument_functions_post_inline,
+                false
+            ),
 
             sanitizer: if_regular!(sess.opts.debugging_opts.sanitizer, SanitizerSet::empty()),
             sanitizer_recover: if_regular!(
@@ -334,6 +345,8 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub diag_emitter: SharedEmitter,
     // LLVM optimizations for which we want to print remarks.
     pub remark: Passes,
+    // Directory into which the remarks are written, instead of being printed.
+    pub remark_dir: Option<PathBuf>,
     // Worker thread number
     pub worker: usize,
     // The incremental compilation session directory, or None if we are not
@@ -581,7 +594,15 @@ fn produce_final_output_artifacts(
                 user_wants_objects = true;
                 copy_if_one_unit(OutputType::Object, true);
             }
-            OutputType::Mir | OutputType::Metadata | OutputType::Exe | OutputType::DepInfo => {}
+            OutputType::Mir
+            | OutputType::HirJson
+            | OutputType::AnalysisJson
+            | OutputType::CallGraph
+            | OutputType::CallGraphDot
+            | OutputType::Metadata
+            | OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::ExportedSymbols => {}
         }
     }
 
@@ -654,6 +675,32 @@ fn produce_final_output_artifacts(
     //  - #crate#.crate.metadata.o
     //  - #crate#.bc
     // These are used in linking steps and will be cleaned up afterward.
+
+    // Record everything that may have been produced; files removed by now or
+    // during linking are left out of the artifact manifest.
+    for output_type in crate_output.outputs.keys() {
+        if matches!(
+            *output_type,
+            OutputType::Bitcode
+                | OutputType::LlvmAssembly
+                | OutputType::Assembly
+                | OutputType::Object
+        ) {
+            sess.record_artifact(&crate_output.path(*output_type), output_type.shorthand());
+        }
+    }
+    let modules = compiled_modules.modules.iter().chain(compiled_modules.allocator_module.iter());
+    for module in modules {
+        for path in [&module.object, &module.dwarf_object, &module.bytecode].into_iter().flatten() {
+            sess.record_artifact(path, "codegen-unit");
+        }
+        for output_type in [OutputType::LlvmAssembly, OutputType::Assembly] {
+            if crate_output.outputs.contains_key(&output_type) {
+                let path = crate_output.temp_path(output_type, Some(&module.name));
+                sess.record_artifact(&path, "codegen-unit");
+            }
+        }
+    }
 }
 
 pub enum WorkItem<B: WriteBackendMethods> {
@@ -1019,7 +1066,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         each_linked_rlib_for_lto.push((cnum, path.to_path_buf()));
     }));
 
-    let ol = if tcx.sess.opts.debugging_opts.no_codegen
+    let ol = if tcx.sess.opts.stops_after(StopAfter::Analysis)
         || !tcx.sess.opts.output_types.should_codegen()
     {
         // If we know that we won’t be doing codegen, create target machines without optimisation.
@@ -1027,6 +1074,17 @@ fn start_executing_work<B: ExtraBackendMethods>(
     } else {
         tcx.backend_optimization_level(())
     };
+    let remark_dir = sess.opts.debugging_opts.remark_dir.clone();
+    if let Some(ref dir) = remark_dir {
+        if let Err(err) = fs::create_dir_all(dir) {
+            sess.fatal(&format!(
+                "failed to create the remark directory `{}`: {}",
+                dir.display(),
+                err
+            ));
+        }
+    }
+
     let cgcx = CodegenContext::<B> {
         backend: backend.clone(),
         crate_types: sess.crate_types().to_vec(),
@@ -1040,6 +1098,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         prof: sess.prof.clone(),
         exported_symbols,
         remark: sess.opts.cg.remark.clone(),
+        remark_dir,
         worker: 0,
         incr_comp_session_dir: sess.incr_comp_session_dir_opt().map(|r| r.clone()),
         cgu_reuse_tracker: sess.cgu_reuse_tracker.clone(),
@@ -1225,6 +1284,17 @@ fn start_executing_work<B: ExtraBackendMethods>(
         // This is the queue of LLVM work items that still need processing.
         let mut work_items = Vec::<(WorkItem<B>, u64)>::new();
 
+        // With `-Z shuffle-cgus`, work items are taken from the queue in a random order
+        // rather than most expensive first, which also shuffles the threads they run on.
+        let mut shuffle_rng =
+            cgcx.opts.debugging_opts.shuffle_cgus.map(Xoshiro128StarStar::seed_from_u64);
+        let mut next_work_item = |work_items: &mut Vec<(WorkItem<B>, u64)>| match &mut shuffle_rng {
+            Some(rng) if !work_items.is_empty() => {
+                Some(work_items.remove(rng.gen_range(0..work_items.len())))
+            }
+            _ => work_items.pop(),
+        };
+
         // This are the Jobserver Tokens we currently hold. Does not include
         // the implicit Token the compiler process owns no matter what.
         let mut tokens = Vec::new();
@@ -1274,8 +1344,8 @@ fn start_executing_work<B: ExtraBackendMethods>(
                         // The queue is full enough to not let the worker
                         // threads starve. Use the implicit Token to do some
                         // LLVM work too.
-                        let (item, _) =
-                            work_items.pop().expect("queue empty - queue_full_enough() broken?");
+                        let (item, _) = next_work_item(&mut work_items)
+                            .expect("queue empty - queue_full_enough() broken?");
                         let cgcx = CodegenContext {
                             worker: get_worker_id(&mut free_worker_ids),
                             ..cgcx.clone()
@@ -1327,7 +1397,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
                 // Token is free to use for LLVM work.
                 match main_thread_worker_state {
                     MainThreadWorkerState::Idle => {
-                        if let Some((item, _)) = work_items.pop() {
+                        if let Some((item, _)) = next_work_item(&mut work_items) {
                             let cgcx = CodegenContext {
                                 worker: get_worker_id(&mut free_worker_ids),
                                 ..cgcx.clone()
@@ -1364,7 +1434,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
             // Spin up what work we can, only doing this while we've got available
             // parallelism slots and work left to spawn.
             while !codegen_aborted && !work_items.is_empty() && running < tokens.len() {
-                let (item, _) = work_items.pop().unwrap();
+                let (item, _) = next_work_item(&mut work_items).unwrap();
 
                 maybe_start_llvm_timer(prof, cgcx.config(item.module_kind()), &mut llvm_start_time);
 
@@ -1652,6 +1722,10 @@ fn drop(&mut self) {
         // Note that we ignore any `FatalError` coming out of `execute_work_item`,
         // as a diagnostic was already sent off to the main thread - just
         // surface that there was an error in this worker.
+        // Workers run alongside each other and the main thread, so this is where the
+        // process has the most threads.
+        cgcx.prof.sample_thread_count();
+
         bomb.result = {
             let _prof_timer = work.start_profiling(&cgcx);
             Some(execute_work_item(&cgcx, work))
@@ -1665,6 +1739,7 @@ enum SharedEmitterMessage {
     InlineAsmError(u32, String, Level, Option<(String, Vec<InnerSpan>)>),
     AbortIfErrors,
     Fatal(String),
+    StackProtection { function: String, reason: String },
 }
 
 #[derive(Clone)]
@@ -1674,13 +1749,17 @@ pub struct SharedEmitter {
 
 pub struct SharedEmitterMain {
     receiver: Receiver<SharedEmitterMessage>,
+    /// The functions that received stack smash protection, and why, for
+    /// `-Z stack-protector-report`.
+    stack_protections: RefCell<Vec<(String, String)>>,
 }
 
 impl SharedEmitter {
     pub fn new() -> (SharedEmitter, SharedEmitterMain) {
         let (sender, receiver) = channel();
 
-        (SharedEmitter { sender }, SharedEmitterMain { receiver })
+        let stack_protections = RefCell::new(Vec::new());
+        (SharedEmitter { sender }, SharedEmitterMain { receiver, stack_protections })
     }
 
     pub fn inline_asm_error(
@@ -1696,6 +1775,12 @@ pub fn inline_asm_error(
     pub fn fatal(&self, msg: &str) {
         drop(self.sender.send(SharedEmitterMessage::Fatal(msg.to_string())));
     }
+
+    /// Records that the backend protected `function` against stack smashing because of `reason`,
+    /// for `-Z stack-protector-report`.
+    pub fn stack_protection(&self, function: String, reason: String) {
+        drop(self.sender.send(SharedEmitterMessage::StackProtection { function, reason }));
+    }
 }
 
 impl Emitter for SharedEmitter {
@@ -1779,6 +1864,9 @@ pub fn check(&self, sess: &Session, blocking: bool) {
                 Ok(SharedEmitterMessage::Fatal(msg)) => {
                     sess.fatal(&msg);
                 }
+                Ok(SharedEmitterMessage::StackProtection { function, reason }) => {
+                    self.stack_protections.borrow_mut().push((function, reason));
+                }
                 Err(_) => {
                     break;
                 }
@@ -1787,6 +1875,34 @@ pub fn check(&self, sess: &Session, blocking: bool) {
     }
 }
 
+/// Writes the report of `-Z stack-protector-report`, with a line for each function that received
+/// stack smash protection and each reason the backend gave for it.
+fn write_stack_protector_report(
+    sess: &Session,
+    path: &Path,
+    mut stack_protections: Vec<(String, String)>,
+) {
+    stack_protections.sort();
+    stack_protections.dedup();
+
+    let mut report = format!(
+        "# Functions protected with `-Z stack-protector={}` on `{}`\n",
+        sess.stack_protector(),
+        sess.opts.target_triple
+    );
+    for (function, reason) in stack_protections {
+        report.push_str(&format!("{}: {}\n", function, reason));
+    }
+
+    if let Err(err) = fs::write(path, report) {
+        sess.err(&format!(
+            "failed to write stack protector report to `{}`: {}",
+            path.display(),
+            err
+        ));
+    }
+}
+
 pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub backend: B,
     pub metadata: EncodedMetadata,
@@ -1824,6 +1940,11 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxHashMap<WorkProductId, W
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
+        if let Some(path) = &sess.opts.debugging_opts.stack_protector_report {
+            let stack_protections = self.shared_emitter_main.stack_protections.into_inner();
+            write_stack_protector_report(sess, path, stack_protections);
+        }
+
         // FIXME: time_llvm_passes support - does this use a global context or
         // something?
         if sess.codegen_units() == 1 && sess.time_llvm_passes() {
@@ -1,4 +1,7 @@
+use crate::back::linker_script::write_linker_script_template;
 use crate::back::metadata::create_compressed_metadata_file;
+use crate::back::shared_generics;
+use crate::back::size_report::collect_symbol_provenance;
 use crate::back::write::{
     compute_per_cgu_lto_type, start_async_codegen, submit_codegened_module_to_llvm,
     submit_post_lto_module_to_llvm, submit_pre_lto_module_to_llvm, ComputedLtoType, OngoingCodegen,
@@ -28,8 +31,8 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_session::cgu_reuse_tracker::CguReuse;
-use rustc_session::config::{self, EntryFnType, OutputType};
-use rustc_session::Session;
+use rustc_session::config::{self, EntryFnType, OutputType, StopAfter};
+use rustc_session::{Session, TimingPhase};
 use rustc_span::symbol::sym;
 use rustc_target::abi::{Align, VariantIdx};
 
@@ -38,6 +41,8 @@
 use std::time::{Duration, Instant};
 
 use itertools::Itertools;
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_xoshiro::Xoshiro128StarStar;
 
 pub fn bin_op_to_icmp_predicate(op: hir::BinOpKind, signed: bool) -> IntPredicate {
     match op {
@@ -491,8 +496,10 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     metadata: EncodedMetadata,
     need_metadata_module: bool,
 ) -> OngoingCodegen<B> {
-    // Skip crate items and just output metadata in -Z no-codegen mode.
-    if tcx.sess.opts.debugging_opts.no_codegen || !tcx.sess.opts.output_types.should_codegen() {
+    // Skip crate items and just output metadata in -Z stop-after=analysis mode.
+    if tcx.sess.opts.stops_after(StopAfter::Analysis)
+        || !tcx.sess.opts.output_types.should_codegen()
+    {
         let ongoing_codegen = start_async_codegen(backend, tcx, target_cpu, metadata, None, 1);
 
         ongoing_codegen.codegen_finished(tcx);
@@ -508,6 +515,16 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     // codegen units.
     let codegen_units = tcx.collect_and_partition_mono_items(()).1;
 
+    if tcx.sess.opts.debugging_opts.shared_generics_dir.is_some() {
+        tcx.sess.time("update_shared_generics_index", || shared_generics::update_index(tcx));
+    }
+
+    if let Some(path) = &tcx.sess.opts.debugging_opts.generate_linker_script_template {
+        tcx.sess.time("write_linker_script_template", || {
+            write_linker_script_template(tcx, codegen_units, path)
+        });
+    }
+
     // Force all codegen_unit queries so they are already either red or green
     // when compile_codegen_unit accesses them. We are not able to re-execute
     // the codegen_unit query from just the DepNode, so an unknown color would
@@ -609,7 +626,15 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         sorted_cgus.sort_by_cached_key(|cgu| cgu.size_estimate());
 
         let (first_half, second_half) = sorted_cgus.split_at(sorted_cgus.len() / 2);
-        second_half.iter().rev().interleave(first_half).copied().collect()
+        let mut codegen_units: Vec<_> =
+            second_half.iter().rev().interleave(first_half).copied().collect();
+
+        // `-Z shuffle-cgus` trades this order for a random one, to shake out failures
+        // that depend on the order codegen units are processed in.
+        if let Some(seed) = tcx.sess.opts.debugging_opts.shuffle_cgus {
+            codegen_units.shuffle(&mut Xoshiro128StarStar::seed_from_u64(seed));
+        }
+        codegen_units
     };
 
     // The non-parallel compiler can only translate codegen units to LLVM IR
@@ -638,7 +663,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 
                 let pre_compiled_cgus = par_iter(cgus)
                     .map(|(i, _)| {
+                        let start_time = Instant::now();
                         let module = backend.compile_codegen_unit(tcx, codegen_units[i].name());
+                        record_cgu_module_times(tcx, codegen_units[i], start_time.elapsed());
                         (i, module)
                     })
                     .collect();
@@ -682,7 +709,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                     } else {
                         let start_time = Instant::now();
                         let module = backend.compile_codegen_unit(tcx, cgu.name());
-                        total_codegen_time += start_time.elapsed();
+                        let codegen_time = start_time.elapsed();
+                        record_cgu_module_times(tcx, cgu, codegen_time);
+                        total_codegen_time += codegen_time;
                         module
                     };
                 // This will unwind if there are errors, which triggers our `AbortCodegenOnDrop`
@@ -829,6 +858,8 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             .filter(|&cnum| !tcx.dep_kind(cnum).macros_only())
             .collect();
 
+        let size_report = tcx.sess.opts.debugging_opts.binary_size_report.is_some();
+
         let mut info = CrateInfo {
             target_cpu,
             exported_symbols,
@@ -845,6 +876,7 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             missing_lang_items: Default::default(),
             dependency_formats: tcx.dependency_formats(()),
             windows_subsystem,
+            size_report_symbols: size_report.then(|| collect_symbol_provenance(tcx)),
         };
         let lang_items = tcx.lang_items();
 
@@ -922,11 +954,45 @@ pub fn provide(providers: &mut Providers) {
     };
 }
 
+/// Splits the time spent generating the code of `cgu` between its items, in proportion to their
+/// size estimates, for `-Z module-timings`.
+fn record_cgu_module_times<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>, duration: Duration) {
+    if tcx.sess.opts.debugging_opts.module_timings.is_none() {
+        return;
+    }
+    let items: Vec<_> = cgu
+        .items()
+        .keys()
+        .map(|item| {
+            let def_id = match *item {
+                MonoItem::Fn(instance) => instance.def_id(),
+                MonoItem::Static(def_id) => def_id,
+                MonoItem::GlobalAsm(item_id) => item_id.def_id.to_def_id(),
+            };
+            (def_id, item.size_estimate(tcx))
+        })
+        .collect();
+    let total_size: usize = items.iter().map(|&(_, size)| size).sum();
+    if total_size == 0 {
+        return;
+    }
+    for (def_id, size) in items {
+        let share = duration.mul_f64(size as f64 / total_size as f64);
+        tcx.record_module_time(def_id, TimingPhase::Codegen, share);
+    }
+}
+
 fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguReuse {
     if !tcx.dep_graph.is_fully_enabled() {
         return CguReuse::No;
     }
 
+    // The report is made of what the backend says while it compiles the CGUs, so the ones it
+    // doesn't compile again would be missing from it.
+    if tcx.sess.opts.debugging_opts.stack_protector_report.is_some() {
+        return CguReuse::No;
+    }
+
     let work_product_id = &cgu.work_product_id();
     if tcx.dep_graph.previous_work_product(work_product_id).is_none() {
         // We don't have anything cached for this CGU. This can happen
@@ -19,6 +19,8 @@
 #[macro_use]
 extern crate rustc_middle;
 
+use crate::back::size_report::SymbolProvenance;
+
 use rustc_ast as ast;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lrc;
@@ -151,6 +153,9 @@ pub struct CrateInfo {
     pub missing_lang_items: FxHashMap<CrateNum, Vec<LangItem>>,
     pub dependency_formats: Lrc<Dependencies>,
     pub windows_subsystem: Option<String>,
+    /// The symbols of the local mono items, for `-Z binary-size-report`, and of the items
+    /// exported by the other crates, with `linked`.
+    pub size_report_symbols: Option<FxHashMap<String, SymbolProvenance>>,
 }
 
 #[derive(Encodable, Decodable)]
@@ -164,6 +169,7 @@ pub struct CodegenResults {
 
 pub fn provide(providers: &mut Providers) {
     crate::back::symbol_export::provide(providers);
+    crate::back::shared_generics::provide(providers);
     crate::base::provide(providers);
     crate::target_features::provide(providers);
 }
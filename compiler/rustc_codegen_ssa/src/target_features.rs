@@ -223,6 +223,108 @@
 
 const BPF_ALLOWED_FEATURES: &[(&str, Option<Symbol>)] = &[("alu32", Some(sym::bpf_target_feature))];
 
+// The features that each feature above directly implies: enabling it in `-C target-feature`
+// or `#[target_feature]` also enables them. Features that imply nothing are left out, and
+// every feature named here must be in the list of supported features of its target.
+
+const ARM_IMPLIED_FEATURES: &[(&str, &[&str])] = &[
+    ("aes", &["neon"]),
+    ("crypto", &["neon"]),
+    ("dotprod", &["neon"]),
+    ("fp-armv8", &["vfp4"]),
+    ("i8mm", &["neon"]),
+    ("neon", &["vfp3"]),
+    ("sha2", &["neon"]),
+    ("v6", &["v5te"]),
+    ("v6k", &["v6"]),
+    ("v6t2", &["v6k", "thumb2"]),
+    ("v7", &["v6t2"]),
+    ("v8", &["v7"]),
+    ("vfp3", &["vfp2"]),
+    ("vfp4", &["vfp3"]),
+];
+
+const AARCH64_IMPLIED_FEATURES: &[(&str, &[&str])] = &[
+    ("aes", &["neon"]),
+    ("bf16", &["neon"]),
+    ("dotprod", &["neon"]),
+    ("f32mm", &["sve"]),
+    ("f64mm", &["sve"]),
+    ("fcma", &["neon"]),
+    ("fhm", &["fp16"]),
+    ("fp16", &["fp"]),
+    ("i8mm", &["neon"]),
+    ("jsconv", &["fp"]),
+    ("neon", &["fp"]),
+    ("rcpc2", &["rcpc"]),
+    ("rdm", &["neon"]),
+    ("sha2", &["neon"]),
+    ("sha3", &["sha2"]),
+    ("sm4", &["neon"]),
+    ("sve", &["fp16"]),
+    ("sve2", &["sve"]),
+    ("sve2-aes", &["sve2", "aes"]),
+    ("sve2-bitperm", &["sve2"]),
+    ("sve2-sha3", &["sve2", "sha3"]),
+    ("sve2-sm4", &["sve2", "sm4"]),
+    ("v8.1a", &["crc", "lse", "rdm", "pan", "lor", "vh"]),
+    ("v8.2a", &["v8.1a", "ras", "dpb"]),
+    ("v8.3a", &["v8.2a", "rcpc", "pauth", "fcma", "jsconv"]),
+    ("v8.4a", &["v8.3a", "dotprod", "dit", "flagm"]),
+    ("v8.5a", &["v8.4a", "ssbs", "sb", "dpb2", "bti"]),
+    ("v8.6a", &["v8.5a", "bf16", "i8mm"]),
+    ("v8.7a", &["v8.6a"]),
+];
+
+const X86_IMPLIED_FEATURES: &[(&str, &[&str])] = &[
+    ("aes", &["sse2"]),
+    ("avx", &["sse4.2"]),
+    ("avx2", &["avx"]),
+    ("avx512bf16", &["avx512bw"]),
+    ("avx512bitalg", &["avx512bw"]),
+    ("avx512bw", &["avx512f"]),
+    ("avx512cd", &["avx512f"]),
+    ("avx512dq", &["avx512f"]),
+    ("avx512er", &["avx512f"]),
+    ("avx512f", &["avx2", "fma", "f16c"]),
+    ("avx512gfni", &["avx512f"]),
+    ("avx512ifma", &["avx512f"]),
+    ("avx512pf", &["avx512f"]),
+    ("avx512vaes", &["avx512f", "aes"]),
+    ("avx512vbmi", &["avx512bw"]),
+    ("avx512vbmi2", &["avx512bw"]),
+    ("avx512vl", &["avx512f"]),
+    ("avx512vnni", &["avx512f"]),
+    ("avx512vp2intersect", &["avx512f"]),
+    ("avx512vpclmulqdq", &["avx512f", "pclmulqdq"]),
+    ("avx512vpopcntdq", &["avx512f"]),
+    ("f16c", &["avx"]),
+    ("fma", &["avx"]),
+    ("pclmulqdq", &["sse2"]),
+    ("sha", &["sse2"]),
+    ("sse2", &["sse"]),
+    ("sse3", &["sse2"]),
+    ("sse4.1", &["ssse3"]),
+    ("sse4.2", &["sse4.1"]),
+    ("sse4a", &["sse3"]),
+    ("ssse3", &["sse3"]),
+    ("xsavec", &["xsave"]),
+    ("xsaveopt", &["xsave"]),
+    ("xsaves", &["xsave"]),
+];
+
+const HEXAGON_IMPLIED_FEATURES: &[(&str, &[&str])] = &[("hvx-length128b", &["hvx"])];
+
+const POWERPC_IMPLIED_FEATURES: &[(&str, &[&str])] = &[
+    ("power8-altivec", &["altivec"]),
+    ("power8-vector", &["vsx", "power8-altivec"]),
+    ("power9-altivec", &["power8-altivec"]),
+    ("power9-vector", &["power8-vector", "power9-altivec"]),
+    ("vsx", &["altivec"]),
+];
+
+const RISCV_IMPLIED_FEATURES: &[(&str, &[&str])] = &[("d", &["f"])];
+
 /// When rustdoc is running, provide a list of all known features so that all their respective
 /// primitives may be documented.
 ///
@@ -256,6 +358,22 @@ pub fn supported_target_features(sess: &Session) -> &'static [(&'static str, Opt
     }
 }
 
+/// The features that each supported feature of the target directly implies, for the ones that
+/// imply any.
+pub fn implied_target_features(
+    sess: &Session,
+) -> &'static [(&'static str, &'static [&'static str])] {
+    match &*sess.target.arch {
+        "arm" => ARM_IMPLIED_FEATURES,
+        "aarch64" => AARCH64_IMPLIED_FEATURES,
+        "x86" | "x86_64" => X86_IMPLIED_FEATURES,
+        "hexagon" => HEXAGON_IMPLIED_FEATURES,
+        "powerpc" | "powerpc64" => POWERPC_IMPLIED_FEATURES,
+        "riscv32" | "riscv64" => RISCV_IMPLIED_FEATURES,
+        _ => &[],
+    }
+}
+
 pub(crate) fn provide(providers: &mut Providers) {
     providers.supported_target_features = |tcx, cnum| {
         assert_eq!(cnum, LOCAL_CRATE);
@@ -268,3 +386,6 @@ pub(crate) fn provide(providers: &mut Providers) {
         }
     };
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,26 @@
+use super::*;
+
+fn check_implied_features(allowed: &[(&str, Option<Symbol>)], implied: &[(&str, &[&str])]) {
+    let is_allowed = |feature: &str| allowed.iter().any(|&(name, _)| name == feature);
+    for &(feature, implied_features) in implied {
+        assert!(is_allowed(feature), "`{}` implies features but isn't supported", feature);
+        for implied_feature in implied_features {
+            assert!(
+                is_allowed(implied_feature),
+                "`{}` implies `{}`, which isn't supported",
+                feature,
+                implied_feature
+            );
+        }
+    }
+}
+
+#[test]
+fn test_implied_features_are_supported() {
+    check_implied_features(ARM_ALLOWED_FEATURES, ARM_IMPLIED_FEATURES);
+    check_implied_features(AARCH64_ALLOWED_FEATURES, AARCH64_IMPLIED_FEATURES);
+    check_implied_features(X86_ALLOWED_FEATURES, X86_IMPLIED_FEATURES);
+    check_implied_features(HEXAGON_ALLOWED_FEATURES, HEXAGON_IMPLIED_FEATURES);
+    check_implied_features(POWERPC_ALLOWED_FEATURES, POWERPC_IMPLIED_FEATURES);
+    check_implied_features(RISCV_ALLOWED_FEATURES, RISCV_IMPLIED_FEATURES);
+}
@@ -91,6 +91,7 @@
 use std::fs;
 use std::path::Path;
 use std::process;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -111,6 +112,7 @@ struct EventFilter: u32 {
         const LLVM                = 1 << 7;
         const INCR_RESULT_HASHING = 1 << 8;
         const ARTIFACT_SIZES = 1 << 9;
+        const QUERY_MEMORY        = 1 << 10;
 
         const DEFAULT = Self::GENERIC_ACTIVITIES.bits |
                         Self::QUERY_PROVIDERS.bits |
@@ -139,8 +141,19 @@ struct EventFilter: u32 {
     ("llvm", EventFilter::LLVM),
     ("incr-result-hashing", EventFilter::INCR_RESULT_HASHING),
     ("artifact-sizes", EventFilter::ARTIFACT_SIZES),
+    ("memory", EventFilter::QUERY_MEMORY),
 ];
 
+/// The total size of the chunks allocated by the arenas of `rustc_arena`, which
+/// `-Z self-profile-events=memory` attributes to the queries allocating them.
+static ARENA_CHUNK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Records that an arena allocated a new chunk of `bytes` bytes.
+#[inline]
+pub fn record_arena_chunk(bytes: usize) {
+    ARENA_CHUNK_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
 /// Something that uniquely identifies a query invocation.
 pub struct QueryInvocationId(pub u32);
 
@@ -162,6 +175,10 @@ pub struct SelfProfilerRef {
 
     // Print extra verbose generic activities to stdout
     print_extra_verbose_generic_activities: bool,
+
+    // The highest number of threads of the process seen by `sample_thread_count`, if the
+    // compiler was asked to track it.
+    thread_count_high_water_mark: Option<Arc<AtomicUsize>>,
 }
 
 impl SelfProfilerRef {
@@ -169,6 +186,7 @@ pub fn new(
         profiler: Option<Arc<SelfProfiler>>,
         print_verbose_generic_activities: bool,
         print_extra_verbose_generic_activities: bool,
+        track_thread_count: bool,
     ) -> SelfProfilerRef {
         // If there is no SelfProfiler then the filter mask is set to NONE,
         // ensuring that nothing ever tries to actually access it.
@@ -180,6 +198,30 @@ pub fn new(
             event_filter_mask,
             print_verbose_generic_activities,
             print_extra_verbose_generic_activities,
+            thread_count_high_water_mark: track_thread_count
+                .then(|| Arc::new(AtomicUsize::new(get_thread_count().unwrap_or(0)))),
+        }
+    }
+
+    /// Updates the high-water mark of the number of threads of the process, if it is
+    /// tracked. This is done at the start of each verbose generic activity, and should
+    /// also be done by threads that may run in between, such as codegen workers.
+    pub fn sample_thread_count(&self) {
+        if let Some(ref high_water_mark) = self.thread_count_high_water_mark {
+            if let Some(count) = get_thread_count() {
+                high_water_mark.fetch_max(count, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// The highest number of threads of the process seen so far, if it is tracked and
+    /// the platform can tell.
+    pub fn thread_count_high_water_mark(&self) -> Option<usize> {
+        let high_water_mark = self.thread_count_high_water_mark.as_ref()?;
+        self.sample_thread_count();
+        match high_water_mark.load(Ordering::Relaxed) {
+            0 => None,
+            count => Some(count),
         }
     }
 
@@ -220,6 +262,7 @@ pub fn verbose_generic_activity<'a>(
         let message =
             if self.print_verbose_generic_activities { Some(event_label.to_owned()) } else { None };
 
+        self.sample_thread_count();
         VerboseTimingGuard::start(message, self.generic_activity(event_label))
     }
 
@@ -346,6 +389,18 @@ pub fn query_provider(&self) -> TimingGuard<'_> {
         })
     }
 
+    /// Start measuring the memory used by a query provider. The increase of the peak
+    /// resident set size and the arena chunks allocated in the meantime are recorded
+    /// when `finish_with_query_invocation_id` is called on the returned guard.
+    #[inline(always)]
+    pub fn query_memory(&self) -> QueryMemoryGuard<'_> {
+        if unlikely!(self.event_filter_mask.contains(EventFilter::QUERY_MEMORY)) {
+            QueryMemoryGuard::start(self.profiler.as_ref().unwrap())
+        } else {
+            QueryMemoryGuard(None)
+        }
+    }
+
     /// Record a query in-memory cache hit.
     #[inline(always)]
     pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
@@ -456,6 +511,8 @@ pub struct SelfProfiler {
     query_blocked_event_kind: StringId,
     query_cache_hit_event_kind: StringId,
     artifact_size_event_kind: StringId,
+    query_peak_rss_event_kind: StringId,
+    query_arena_bytes_event_kind: StringId,
 }
 
 impl SelfProfiler {
@@ -479,6 +536,8 @@ pub fn new(
         let query_blocked_event_kind = profiler.alloc_string("QueryBlocked");
         let query_cache_hit_event_kind = profiler.alloc_string("QueryCacheHit");
         let artifact_size_event_kind = profiler.alloc_string("ArtifactSize");
+        let query_peak_rss_event_kind = profiler.alloc_string("QueryPeakRssDelta");
+        let query_arena_bytes_event_kind = profiler.alloc_string("QueryArenaBytes");
 
         let mut event_filter_mask = EventFilter::empty();
 
@@ -524,6 +583,8 @@ pub fn new(
             query_blocked_event_kind,
             query_cache_hit_event_kind,
             artifact_size_event_kind,
+            query_peak_rss_event_kind,
+            query_arena_bytes_event_kind,
         })
     }
 
@@ -624,6 +685,50 @@ pub fn run<R>(self, f: impl FnOnce() -> R) -> R {
     }
 }
 
+/// Measures the memory used by a query provider, for `-Z self-profile-events=memory`.
+///
+/// The measurements are process-wide, so they include the memory used by the queries the
+/// provider calls and, in the parallel compiler, by the queries running on other threads.
+#[must_use]
+pub struct QueryMemoryGuard<'a>(Option<(&'a SelfProfiler, usize, u64)>);
+
+impl<'a> QueryMemoryGuard<'a> {
+    #[inline(never)]
+    fn start(profiler: &'a SelfProfiler) -> QueryMemoryGuard<'a> {
+        let peak_rss = get_process_resource_usage().peak_rss.unwrap_or(0);
+        let arena_bytes = ARENA_CHUNK_BYTES.load(Ordering::Relaxed);
+        QueryMemoryGuard(Some((profiler, peak_rss, arena_bytes)))
+    }
+
+    /// Records the increase of the peak resident set size and the bytes of the arena chunks
+    /// allocated since the guard was created, as integer events of the query invocation.
+    #[inline]
+    pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
+        if let Some((profiler, start_peak_rss, start_arena_bytes)) = self.0 {
+            cold_path(|| {
+                let peak_rss = get_process_resource_usage().peak_rss.unwrap_or(0);
+                let arena_bytes = ARENA_CHUNK_BYTES.load(Ordering::Relaxed);
+                let event_id = StringId::new_virtual(query_invocation_id.0);
+                let event_id = EventId::from_virtual(event_id);
+                let thread_id = get_thread_id();
+
+                profiler.profiler.record_integer_event(
+                    profiler.query_peak_rss_event_kind,
+                    event_id,
+                    thread_id,
+                    peak_rss.saturating_sub(start_peak_rss) as u64,
+                );
+                profiler.profiler.record_integer_event(
+                    profiler.query_arena_bytes_event_kind,
+                    event_id,
+                    thread_id,
+                    arena_bytes - start_arena_bytes,
+                );
+            });
+        }
+    }
+}
+
 #[must_use]
 pub struct VerboseTimingGuard<'a> {
     start_and_message: Option<(Instant, Option<usize>, String)>,
@@ -727,3 +832,91 @@ pub fn get_resident_set_size() -> Option<usize> {
         }
     }
 }
+
+/// The resources used by the process so far, as far as the platform can tell.
+#[derive(Default)]
+pub struct ProcessResourceUsage {
+    /// The peak resident set size, in bytes.
+    pub peak_rss: Option<usize>,
+    pub user_time: Option<Duration>,
+    pub system_time: Option<Duration>,
+}
+
+cfg_if! {
+    if #[cfg(windows)] {
+        pub fn get_process_resource_usage() -> ProcessResourceUsage {
+            use std::mem::{self, MaybeUninit};
+            use winapi::shared::minwindef::{DWORD, FILETIME};
+            use winapi::um::processthreadsapi::{GetCurrentProcess, GetProcessTimes};
+            use winapi::um::psapi::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+
+            let mut usage = ProcessResourceUsage::default();
+            let process = unsafe { GetCurrentProcess() };
+
+            let mut pmc = MaybeUninit::<PROCESS_MEMORY_COUNTERS>::uninit();
+            if unsafe {
+                GetProcessMemoryInfo(process, pmc.as_mut_ptr(), mem::size_of_val(&pmc) as DWORD)
+            } != 0 {
+                let pmc = unsafe { pmc.assume_init() };
+                usage.peak_rss = Some(pmc.PeakWorkingSetSize as usize);
+            }
+
+            let zero = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
+            let (mut creation, mut exit, mut kernel, mut user) = (zero, zero, zero, zero);
+            if unsafe {
+                GetProcessTimes(process, &mut creation, &mut exit, &mut kernel, &mut user)
+            } != 0 {
+                // `FILETIME`s count in units of 100 nanoseconds.
+                let to_duration = |time: FILETIME| {
+                    let ticks = (time.dwHighDateTime as u64) << 32 | time.dwLowDateTime as u64;
+                    Duration::from_nanos(ticks * 100)
+                };
+                usage.system_time = Some(to_duration(kernel));
+                usage.user_time = Some(to_duration(user));
+            }
+            usage
+        }
+    } else if #[cfg(unix)] {
+        pub fn get_process_resource_usage() -> ProcessResourceUsage {
+            use std::mem::MaybeUninit;
+
+            let mut rusage = MaybeUninit::<libc::rusage>::uninit();
+            if unsafe { libc::getrusage(libc::RUSAGE_SELF, rusage.as_mut_ptr()) } != 0 {
+                return ProcessResourceUsage::default();
+            }
+            let rusage = unsafe { rusage.assume_init() };
+            let to_duration = |time: libc::timeval| {
+                Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000)
+            };
+            // `ru_maxrss` is in bytes on macOS, and in kilobytes everywhere else.
+            let peak_rss = if cfg!(target_os = "macos") {
+                rusage.ru_maxrss as usize
+            } else {
+                rusage.ru_maxrss as usize * 1024
+            };
+            ProcessResourceUsage {
+                peak_rss: Some(peak_rss),
+                user_time: Some(to_duration(rusage.ru_utime)),
+                system_time: Some(to_duration(rusage.ru_stime)),
+            }
+        }
+    } else {
+        pub fn get_process_resource_usage() -> ProcessResourceUsage {
+            ProcessResourceUsage::default()
+        }
+    }
+}
+
+cfg_if! {
+    if #[cfg(any(target_os = "linux", target_os = "android"))] {
+        pub fn get_thread_count() -> Option<usize> {
+            let status = fs::read_to_string("/proc/self/status").ok()?;
+            let line = status.lines().find(|line| line.starts_with("Threads:"))?;
+            line["Threads:".len()..].trim().parse().ok()
+        }
+    } else {
+        pub fn get_thread_count() -> Option<usize> {
+            None
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::env;
+
 // This is the amount of bytes that need to be left on the stack before increasing the size.
 // It must be at least as large as the stack required by any code that does not call
 // `ensure_sufficient_stack`.
@@ -15,3 +17,14 @@
 pub fn ensure_sufficient_stack<R>(f: impl FnOnce() -> R) -> R {
     stacker::maybe_grow(RED_ZONE, STACK_PER_RECURSION, f)
 }
+
+/// The stack size of the threads the compiler runs on.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Returns the stack size to give the threads the compiler runs on, which is also the one
+/// that code run on behalf of the compiler, like proc macros, should get.
+pub fn get_stack_size() -> Option<usize> {
+    // FIXME: Hacks on hacks. If the env is trying to override the stack size
+    // then *don't* set it explicitly.
+    env::var_os("RUST_MIN_STACK").is_none().then_some(STACK_SIZE)
+}
@@ -6,8 +6,10 @@
 #[derive(Debug)]
 pub struct MaybeTempDir {
     dir: ManuallyDrop<TempDir>,
-    // Whether the TempDir should be deleted on drop.
+    // Whether the TempDir should be kept on drop.
     keep: bool,
+    // Whether the TempDir should be kept when dropped while unwinding from an error.
+    keep_on_error: bool,
 }
 
 impl Drop for MaybeTempDir {
@@ -15,7 +17,7 @@ fn drop(&mut self) {
         // SAFETY: We are in the destructor, and no further access will
         // occur.
         let dir = unsafe { ManuallyDrop::take(&mut self.dir) };
-        if self.keep {
+        if self.keep || (self.keep_on_error && std::thread::panicking()) {
             dir.into_path();
         }
     }
@@ -29,6 +31,12 @@ fn as_ref(&self) -> &Path {
 
 impl MaybeTempDir {
     pub fn new(dir: TempDir, keep_on_drop: bool) -> MaybeTempDir {
-        MaybeTempDir { dir: ManuallyDrop::new(dir), keep: keep_on_drop }
+        MaybeTempDir { dir: ManuallyDrop::new(dir), keep: keep_on_drop, keep_on_error: false }
+    }
+
+    /// Also keep the directory if it is dropped while unwinding, e.g. from a fatal error.
+    pub fn keep_on_error(mut self, keep_on_error: bool) -> MaybeTempDir {
+        self.keep_on_error = keep_on_error;
+        self
     }
 }
@@ -2,40 +2,112 @@
 use std::fmt;
 use std::fs;
 use std::io;
+use std::path::PathBuf;
 
-fn arg_expand(arg: String) -> Result<Vec<String>, Error> {
-    if let Some(path) = arg.strip_prefix('@') {
-        let file = match fs::read_to_string(path) {
-            Ok(file) => file,
-            Err(ref err) if err.kind() == io::ErrorKind::InvalidData => {
-                return Err(Error::Utf8Error(Some(path.to_string())));
+/// How deeply argument files may include other argument files.
+const MAX_NESTING_DEPTH: usize = 16;
+
+/// Removes the quotes from a line of an argument file written as `"..."`, in which `\"`
+/// and `\\` stand for `"` and `\`. Returns `None` for lines that aren't quoted.
+fn unquote(line: &str) -> Option<String> {
+    let inner = line.strip_prefix('"')?.strip_suffix('"')?;
+    let mut arg = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next)) if next == '"' || next == '\\' => {
+                arg.push(next);
+                chars.next();
             }
-            Err(err) => return Err(Error::IOError(path.to_string(), err)),
-        };
-        Ok(file.lines().map(ToString::to_string).collect())
-    } else {
-        Ok(vec![arg])
+            _ => arg.push(c),
+        }
+    }
+    Some(arg)
+}
+
+/// Expands `arg` into `args`, reading it as an argument file if it starts with `@`.
+/// Unless `extended` is set, every line of the file is taken literally. Otherwise, quoted
+/// lines are unquoted and lines starting with `@` are read as argument files in turn.
+/// `stack` holds the argument files currently being read, to catch files that
+/// include themselves.
+fn arg_expand(
+    arg: String,
+    extended: bool,
+    stack: &mut Vec<PathBuf>,
+    args: &mut Vec<String>,
+) -> Result<(), Error> {
+    let path = match arg.strip_prefix('@') {
+        Some(path) => path,
+        None => {
+            args.push(arg);
+            return Ok(());
+        }
+    };
+
+    if stack.len() >= MAX_NESTING_DEPTH {
+        return Err(Error::TooDeep(path.to_string()));
+    }
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    if stack.contains(&canonical) {
+        return Err(Error::Cycle(path.to_string()));
+    }
+
+    let file = match fs::read_to_string(path) {
+        Ok(file) => file,
+        Err(ref err) if err.kind() == io::ErrorKind::InvalidData => {
+            return Err(Error::Utf8Error(Some(path.to_string())));
+        }
+        Err(err) => return Err(Error::IOError(path.to_string(), err)),
+    };
+
+    stack.push(canonical);
+    for line in file.lines() {
+        if !extended {
+            args.push(line.to_string());
+            continue;
+        }
+        // Quoted lines are taken literally, even if they start with `@`.
+        match unquote(line) {
+            Some(arg) => args.push(arg),
+            None => arg_expand(line.to_string(), extended, stack, args)?,
+        }
     }
+    stack.pop();
+    Ok(())
 }
 
 pub fn arg_expand_all(at_args: &[String]) -> Vec<String> {
+    let args = expand_all(at_args, false);
+    // Nested and quoted argument files are unstable, so argument files are only read that
+    // way if the arguments read from them literally enable unstable options.
+    if unstable_options_enabled(&args) { expand_all(at_args, true) } else { args }
+}
+
+fn expand_all(at_args: &[String], extended: bool) -> Vec<String> {
     let mut args = Vec::new();
     for arg in at_args {
-        match arg_expand(arg.clone()) {
-            Ok(arg) => args.extend(arg),
-            Err(err) => rustc_session::early_error(
+        if let Err(err) = arg_expand(arg.clone(), extended, &mut Vec::new(), &mut args) {
+            rustc_session::early_error(
                 rustc_session::config::ErrorOutputType::default(),
                 &format!("Failed to load argument file: {}", err),
-            ),
+            )
         }
     }
     args
 }
 
+/// Returns whether `args` pass `-Z unstable-options`.
+fn unstable_options_enabled(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "-Zunstable-options")
+        || args.windows(2).any(|pair| pair[0] == "-Z" && pair[1] == "unstable-options")
+}
+
 #[derive(Debug)]
 pub enum Error {
     Utf8Error(Option<String>),
     IOError(String, io::Error),
+    Cycle(String),
+    TooDeep(String),
 }
 
 impl fmt::Display for Error {
@@ -44,6 +116,12 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
             Error::Utf8Error(None) => write!(fmt, "Utf8 error"),
             Error::Utf8Error(Some(path)) => write!(fmt, "Utf8 error in {}", path),
             Error::IOError(path, err) => write!(fmt, "IO Error: {}: {}", path, err),
+            Error::Cycle(path) => write!(fmt, "{} includes itself", path),
+            Error::TooDeep(path) => write!(
+                fmt,
+                "{} is nested more than {} argument files deep",
+                path, MAX_NESTING_DEPTH
+            ),
         }
     }
 }
@@ -0,0 +1,109 @@
+//! Rendering of `-Z help` and `-C help`.
+//!
+//! Options are grouped by the category they are declared with in `options!`, and each one
+//! is listed with its declared default, if it has one.
+
+use rustc_errors::ColorConfig;
+use rustc_session::config::OptionCategory;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use std::io::{self, Write};
+
+/// The name, setter, value description, help text, category and default of each option.
+type FlagList<T> =
+    [(&'static str, T, &'static str, &'static str, OptionCategory, Option<&'static str>)];
+
+pub fn print_flag_list<T>(
+    title: &str,
+    cmdline_opt: &str,
+    stability: &str,
+    flag_list: &FlagList<T>,
+    filter: Option<&str>,
+    color: ColorConfig,
+) {
+    let choice = match color {
+        ColorConfig::Always => ColorChoice::AlwaysAnsi,
+        ColorConfig::Auto if atty::is(atty::Stream::Stdout) => ColorChoice::Auto,
+        ColorConfig::Auto | ColorConfig::Never => ColorChoice::Never,
+    };
+    let mut out = StandardStream::stdout(choice);
+    // Like `println!`, give up quietly if stdout goes away.
+    let _ = write_flag_list(&mut out, title, cmdline_opt, stability, flag_list, filter);
+}
+
+fn write_flag_list<T>(
+    out: &mut StandardStream,
+    title: &str,
+    cmdline_opt: &str,
+    stability: &str,
+    flag_list: &FlagList<T>,
+    filter: Option<&str>,
+) -> io::Result<()> {
+    let filter = filter.map(|filter| filter.to_lowercase().replace('_', "-"));
+    let flags: Vec<_> = flag_list
+        .iter()
+        .map(|&(name, _, _, desc, category, default)| {
+            (name.replace('_', "-"), desc, category, default)
+        })
+        .filter(|(cmdline_name, desc, ..)| match &filter {
+            Some(filter) => {
+                cmdline_name.contains(filter.as_str()) || desc.to_lowercase().contains(filter)
+            }
+            None => true,
+        })
+        .collect();
+
+    let mut heading = ColorSpec::new();
+    heading.set_bold(true);
+    let mut flag = ColorSpec::new();
+    flag.set_fg(Some(Color::Green)).set_bold(true);
+    let mut note = ColorSpec::new();
+    note.set_fg(Some(Color::Cyan));
+    let mut warning = ColorSpec::new();
+    warning.set_fg(Some(Color::Yellow)).set_bold(true);
+
+    out.set_color(&heading)?;
+    write!(out, "\n{} ({})", title, stability)?;
+    if let Some(filter) = &filter {
+        write!(out, " matching `{}`", filter)?;
+    }
+    writeln!(out, ":")?;
+    out.reset()?;
+
+    if flags.is_empty() {
+        writeln!(out, "\n    no options match")?;
+        return Ok(());
+    }
+
+    let max_len = flags.iter().map(|(name, ..)| name.chars().count()).max().unwrap_or(0);
+
+    for &category in OptionCategory::ALL {
+        let mut in_category = flags.iter().filter(|(.., c, _)| *c == category).peekable();
+        if in_category.peek().is_none() {
+            continue;
+        }
+
+        out.set_color(&heading)?;
+        writeln!(out, "\n  {}:", category.name())?;
+        out.reset()?;
+
+        for (cmdline_name, desc, _, default) in in_category {
+            write!(out, "    {} ", cmdline_opt)?;
+            out.set_color(&flag)?;
+            write!(out, "{:>width$}", cmdline_name, width = max_len)?;
+            out.reset()?;
+            write!(out, "=val -- {}", desc)?;
+            if desc.starts_with("this option is deprecated") {
+                out.set_color(&warning)?;
+                write!(out, " [deprecated]")?;
+                out.reset()?;
+            } else if let Some(default) = default {
+                out.set_color(&note)?;
+                write!(out, " [default: {}]", default)?;
+                out.reset()?;
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
@@ -15,7 +15,7 @@
 pub extern crate rustc_plugin_impl as plugin;
 
 use rustc_ast as ast;
-use rustc_codegen_ssa::{traits::CodegenBackend, CodegenResults};
+use rustc_codegen_ssa::{back::link, traits::CodegenBackend, CodegenResults};
 use rustc_data_structures::profiling::{get_resident_set_size, print_time_passes_entry};
 use rustc_data_structures::sync::SeqCst;
 use rustc_errors::registry::{InvalidErrorCode, Registry};
@@ -29,23 +29,26 @@
 use rustc_save_analysis::DumpHandler;
 use rustc_serialize::json::{self, ToJson};
 use rustc_session::config::{nightly_options, CG_OPTIONS, DB_OPTIONS};
-use rustc_session::config::{ErrorOutputType, Input, OutputType, PrintRequest, TrimmedDefPaths};
+use rustc_session::config::{
+    ErrorOutputType, Input, OutputType, PrintRequest, StopAfter, TrimmedDefPaths,
+};
 use rustc_session::cstore::MetadataLoader;
 use rustc_session::getopts;
-use rustc_session::lint::{Lint, LintId};
+use rustc_session::lint::{FutureIncompatibilityReason, Lint, LintConfigKind, LintId};
 use rustc_session::{config, DiagnosticOutput, Session};
 use rustc_session::{early_error, early_error_no_abort, early_warn};
 use rustc_span::source_map::{FileLoader, FileName};
-use rustc_span::symbol::sym;
+use rustc_span::symbol::{sym, Symbol};
 
 use std::borrow::Cow;
 use std::cmp::max;
+use std::collections::BTreeMap;
 use std::default::Default;
 use std::env;
 use std::ffi::OsString;
 use std::fs;
 use std::io::{self, Read, Write};
-use std::lazy::SyncLazy;
+use std::lazy::{SyncLazy, SyncOnceCell};
 use std::panic::{self, catch_unwind};
 use std::path::PathBuf;
 use std::process::{self, Command, Stdio};
@@ -53,6 +56,7 @@
 use std::time::Instant;
 
 pub mod args;
+mod flag_help;
 pub mod pretty;
 
 /// Exit status code used for successful compilation and help output.
@@ -122,6 +126,7 @@ fn config(&mut self, config: &mut interface::Config) {
         // If a --prints=... option has been given, we don't print the "total"
         // time because it will mess up the --prints output. See #64339.
         self.time_passes = config.opts.prints.is_empty()
+            && config.opts.custom_prints.is_empty()
             && (config.opts.debugging_opts.time_passes || config.opts.debugging_opts.time);
         config.opts.trimmed_def_paths = TrimmedDefPaths::GoodPath;
     }
@@ -208,6 +213,10 @@ fn run_compiler(
 
     let sopts = config::build_session_options(&matches);
 
+    if sopts.debugging_opts.print_args_json {
+        print_args_json(&args, &matches);
+    }
+
     if let Some(ref code) = matches.opt_str("explain") {
         handle_explain(diagnostics_registry(), code, sopts.error_format);
         return Ok(());
@@ -231,6 +240,7 @@ fn run_compiler(
         override_queries: None,
         make_codegen_backend,
         registry: diagnostics_registry(),
+        custom_print_requests: Vec::new(),
     };
 
     match make_input(config.opts.error_format, &matches.free) {
@@ -247,28 +257,11 @@ fn run_compiler(
                 interface::run_compiler(config, |compiler| {
                     let sopts = &compiler.session().opts;
                     if sopts.describe_lints {
-                        let mut lint_store = rustc_lint::new_lint_store(
-                            sopts.debugging_opts.no_interleave_lints,
-                            compiler.session().unstable_options(),
-                        );
-                        let registered_lints =
-                            if let Some(register_lints) = compiler.register_lints() {
-                                register_lints(compiler.session(), &mut lint_store);
-                                true
-                            } else {
-                                false
-                            };
+                        let (lint_store, registered_lints) = new_lint_store(compiler);
                         describe_lints(compiler.session(), &lint_store, registered_lints);
                         return;
                     }
-                    let should_stop = RustcDefaultCalls::print_crate_info(
-                        &***compiler.codegen_backend(),
-                        compiler.session(),
-                        None,
-                        compiler.output_dir(),
-                        compiler.output_file(),
-                        compiler.temps_dir(),
-                    );
+                    let should_stop = RustcDefaultCalls::print_crate_info(compiler, None);
 
                     if should_stop == Compilation::Stop {
                         return;
@@ -290,22 +283,17 @@ fn run_compiler(
 
     interface::run_compiler(config, |compiler| {
         let sess = compiler.session();
-        let should_stop = RustcDefaultCalls::print_crate_info(
-            &***compiler.codegen_backend(),
-            sess,
-            Some(compiler.input()),
-            compiler.output_dir(),
-            compiler.output_file(),
-            compiler.temps_dir(),
-        )
-        .and_then(|| {
-            RustcDefaultCalls::list_metadata(
-                sess,
-                &*compiler.codegen_backend().metadata_loader(),
-                compiler.input(),
-            )
-        })
-        .and_then(|| RustcDefaultCalls::try_process_rlink(sess, compiler));
+        record_crate_version_for_ice(sess);
+        let should_stop = RustcDefaultCalls::print_crate_info(compiler, Some(compiler.input()))
+            .and_then(|| {
+                RustcDefaultCalls::list_metadata(
+                    sess,
+                    &*compiler.codegen_backend().metadata_loader(),
+                    compiler.input(),
+                )
+            })
+            .and_then(|| RustcDefaultCalls::try_process_rlink(sess, compiler))
+            .and_then(|| RustcDefaultCalls::try_run_link_plan(sess, compiler));
 
         if should_stop == Compilation::Stop {
             return sess.compile_status();
@@ -346,7 +334,7 @@ fn run_compiler(
                 return early_exit();
             }
 
-            if sess.opts.debugging_opts.parse_only
+            if sess.opts.stops_after(StopAfter::Parsing)
                 || sess.opts.debugging_opts.show_span.is_some()
                 || sess.opts.debugging_opts.ast_json_noexpand
             {
@@ -378,7 +366,7 @@ fn run_compiler(
 
             queries.global_ctxt()?;
 
-            if sess.opts.debugging_opts.no_analysis || sess.opts.debugging_opts.ast_json {
+            if sess.opts.stops_after(StopAfter::Expansion) || sess.opts.debugging_opts.ast_json {
                 return early_exit();
             }
 
@@ -406,6 +394,28 @@ fn run_compiler(
                 return early_exit();
             }
 
+            if sess.opts.output_types.contains_key(&OutputType::HirJson) {
+                let outputs = queries.prepare_outputs()?.peek();
+                queries
+                    .global_ctxt()?
+                    .peek_mut()
+                    .enter(|tcx| rustc_interface::write_hir_json(tcx, &outputs));
+            }
+
+            if sess.opts.output_types.contains_key(&OutputType::AnalysisJson) {
+                let outputs = queries.prepare_outputs()?.peek();
+                queries
+                    .global_ctxt()?
+                    .peek_mut()
+                    .enter(|tcx| rustc_interface::write_analysis_json(tcx, &outputs));
+            }
+
+            // With incremental compilation, the dep graph is saved and the session directory
+            // finalized on the way to linking, so those sessions don't stop here.
+            if sess.opts.output_types.is_frontend_only() && sess.opts.incremental.is_none() {
+                return early_exit();
+            }
+
             queries.ongoing_codegen()?;
 
             if sess.opts.debugging_opts.print_type_sizes {
@@ -619,6 +629,22 @@ pub fn try_process_rlink(sess: &Session, compiler: &interface::Compiler) -> Comp
         }
     }
 
+    pub fn try_run_link_plan(sess: &Session, compiler: &interface::Compiler) -> Compilation {
+        if sess.opts.debugging_opts.run_link_plan {
+            if let Input::File(file) = compiler.input() {
+                let plan = fs::read_to_string(file).unwrap_or_else(|err| {
+                    sess.fatal(&format!("failed to read link plan: {}", err));
+                });
+                abort_on_err(link::run_link_plan(sess, &plan), sess);
+            } else {
+                sess.fatal("link plan must be a file")
+            }
+            Compilation::Stop
+        } else {
+            Compilation::Continue
+        }
+    }
+
     pub fn list_metadata(
         sess: &Session,
         metadata_loader: &dyn MetadataLoader,
@@ -643,18 +669,24 @@ pub fn list_metadata(
         Compilation::Continue
     }
 
-    fn print_crate_info(
-        codegen_backend: &dyn CodegenBackend,
-        sess: &Session,
-        input: Option<&Input>,
-        odir: &Option<PathBuf>,
-        ofile: &Option<PathBuf>,
-        temps_dir: &Option<PathBuf>,
-    ) -> Compilation {
+    fn print_crate_info(compiler: &interface::Compiler, input: Option<&Input>) -> Compilation {
         use rustc_session::config::PrintRequest::*;
-        // PrintRequest::NativeStaticLibs is special - printed during linking
-        // (empty iterator returns true)
-        if sess.opts.prints.iter().all(|&p| p == PrintRequest::NativeStaticLibs) {
+        let codegen_backend = &***compiler.codegen_backend();
+        let sess = compiler.session();
+        // PrintRequest::NativeStaticLibs, PrintRequest::ExportedSymbols and
+        // PrintRequest::LinkPlan are special - printed during linking, and
+        // PrintRequest::BuildEnvReport at the end of the session (empty iterator returns true)
+        if sess.opts.custom_prints.is_empty()
+            && sess.opts.prints.iter().all(|&p| {
+                matches!(
+                    p,
+                    PrintRequest::NativeStaticLibs
+                        | PrintRequest::ExportedSymbols
+                        | PrintRequest::LinkPlan
+                        | PrintRequest::BuildEnvReport
+                )
+            })
+        {
             return Compilation::Continue;
         }
 
@@ -677,63 +709,161 @@ fn print_crate_info(
                     let mut targets =
                         rustc_target::spec::TARGETS.iter().copied().collect::<Vec<_>>();
                     targets.sort_unstable();
-                    println!("{}", targets.join("\n"));
+                    if sess.opts.print_json {
+                        let targets: Vec<String> = targets.iter().map(|t| t.to_string()).collect();
+                        sess.print_json(*req, targets.to_json());
+                    } else {
+                        println!("{}", targets.join("\n"));
+                    }
+                }
+                LintsJson => {
+                    let (lint_store, _) = new_lint_store(compiler);
+                    print_lints_json(sess, &lint_store);
+                }
+                HostTuple => {
+                    let host = rustc_session::config::host_triple();
+                    if sess.opts.print_json {
+                        sess.print_json(*req, host.to_json());
+                    } else {
+                        println!("{}", host);
+                    }
+                }
+                TargetAliases => {
+                    let aliases = rustc_target::spec::TARGET_ALIASES;
+                    if sess.opts.print_json {
+                        let aliases = aliases
+                            .iter()
+                            .map(|&(alias, triple)| {
+                                let mut obj = BTreeMap::new();
+                                obj.insert("alias".to_string(), alias.to_json());
+                                obj.insert("target".to_string(), triple.to_json());
+                                json::Json::Object(obj)
+                            })
+                            .collect();
+                        sess.print_json(*req, json::Json::Array(aliases));
+                    } else {
+                        let width = aliases.iter().map(|(alias, _)| alias.len()).max().unwrap_or(0);
+                        for (alias, triple) in aliases {
+                            println!("{:<width$} {}", alias, triple, width = width);
+                        }
+                    }
+                }
+                Sysroot | TargetLibdir => {
+                    let dir = match *req {
+                        Sysroot => &sess.sysroot,
+                        _ => &sess.target_tlib_path.dir,
+                    };
+                    if sess.opts.print_json {
+                        sess.print_json(*req, dir.display().to_string().to_json());
+                    } else {
+                        println!("{}", dir.display());
+                    }
+                }
+                TargetSpec => {
+                    if sess.opts.print_json {
+                        sess.print_json(*req, sess.target.to_json());
+                    } else {
+                        println!("{}", sess.target.to_json().pretty());
+                    }
                 }
-                Sysroot => println!("{}", sess.sysroot.display()),
-                TargetLibdir => println!("{}", sess.target_tlib_path.dir.display()),
-                TargetSpec => println!("{}", sess.target.to_json().pretty()),
                 FileNames | CrateName => {
                     let input = input.unwrap_or_else(|| {
                         early_error(ErrorOutputType::default(), "no input file provided")
                     });
                     let attrs = attrs.as_ref().unwrap();
                     let t_outputs = rustc_interface::util::build_output_filenames(
-                        input, odir, ofile, temps_dir, attrs, sess,
+                        input,
+                        compiler.output_dir(),
+                        compiler.output_file(),
+                        compiler.temps_dir(),
+                        attrs,
+                        sess,
                     );
                     let id = rustc_session::output::find_crate_name(sess, attrs, input);
                     if *req == PrintRequest::CrateName {
-                        println!("{}", id);
+                        if sess.opts.print_json {
+                            sess.print_json(*req, id.to_json());
+                        } else {
+                            println!("{}", id);
+                        }
                         continue;
                     }
                     let crate_types = collect_crate_types(sess, attrs);
-                    for &style in &crate_types {
-                        let fname =
-                            rustc_session::output::filename_for_input(sess, style, &id, &t_outputs);
-                        println!("{}", fname.file_name().unwrap().to_string_lossy());
+                    let fnames: Vec<String> = crate_types
+                        .iter()
+                        .map(|&style| {
+                            let fname = rustc_session::output::filename_for_input(
+                                sess, style, &id, &t_outputs,
+                            );
+                            fname.file_name().unwrap().to_string_lossy().into_owned()
+                        })
+                        .collect();
+                    if sess.opts.print_json {
+                        sess.print_json(*req, fnames.to_json());
+                    } else {
+                        for fname in fnames {
+                            println!("{}", fname);
+                        }
                     }
                 }
-                Cfg => {
+                Cfg | CfgJson => {
                     let mut cfgs = sess
                         .parse_sess
                         .config
                         .iter()
-                        .filter_map(|&(name, value)| {
-                            // Note that crt-static is a specially recognized cfg
-                            // directive that's printed out here as part of
-                            // rust-lang/rust#37406, but in general the
-                            // `target_feature` cfg is gated under
-                            // rust-lang/rust#29717. For now this is just
-                            // specifically allowing the crt-static cfg and that's
-                            // it, this is intended to get into Cargo and then go
-                            // through to build scripts.
-                            if (name != sym::target_feature || value != Some(sym::crt_dash_static))
-                                && !sess.is_nightly_build()
-                                && find_gated_cfg(|cfg_sym| cfg_sym == name).is_some()
-                            {
-                                return None;
+                        .filter(|&&(name, value)| is_printable_cfg(sess, name, value))
+                        .map(|&(name, value)| {
+                            (name.to_string(), value.map(|v| v.to_string()), (name, value))
+                        })
+                        .collect::<Vec<_>>();
+                    cfgs.sort();
+
+                    if *req == Cfg && !sess.opts.print_json {
+                        for (name, value, _) in cfgs {
+                            match value {
+                                Some(value) => println!("{}=\"{}\"", name, value),
+                                None => println!("{}", name),
                             }
+                        }
+                        continue;
+                    }
 
-                            if let Some(value) = value {
-                                Some(format!("{}=\"{}\"", name, value))
-                            } else {
-                                Some(name.to_string())
+                    let cfgs = cfgs
+                        .into_iter()
+                        .map(|(name, value, key)| {
+                            let mut obj = BTreeMap::new();
+                            obj.insert("name".to_string(), name.to_json());
+                            obj.insert("value".to_string(), value.to_json());
+                            if *req == CfgJson {
+                                let sources = sess
+                                    .parse_sess
+                                    .config_provenance
+                                    .get(&key)
+                                    .map(|sources| {
+                                        sources.iter().map(|s| s.as_str().to_json()).collect()
+                                    })
+                                    .unwrap_or_default();
+                                obj.insert("sources".to_string(), json::Json::Array(sources));
                             }
+                            json::Json::Object(obj)
                         })
-                        .collect::<Vec<String>>();
-
-                    cfgs.sort();
-                    for cfg in cfgs {
-                        println!("{}", cfg);
+                        .collect();
+                    if sess.opts.print_json {
+                        sess.print_json(*req, json::Json::Array(cfgs));
+                    } else {
+                        println!("{}", json::Json::Array(cfgs).pretty());
+                    }
+                }
+                ResolvedLinker => link::print_resolved_linker(sess),
+                EnabledTargetFeatures => {
+                    let features: Vec<String> =
+                        sess.enabled_target_features().iter().map(|f| f.to_string()).collect();
+                    if sess.opts.print_json {
+                        sess.print_json(*req, features.to_json());
+                    } else {
+                        for feature in features {
+                            println!("{}", feature);
+                        }
                     }
                 }
                 RelocationModels
@@ -741,17 +871,41 @@ fn print_crate_info(
                 | TlsModels
                 | TargetCPUs
                 | StackProtectorStrategies
-                | TargetFeatures => {
+                | TargetFeatures
+                | TargetFeaturesJson => {
                     codegen_backend.print(*req, sess);
                 }
                 // Any output here interferes with Cargo's parsing of other printed output
-                PrintRequest::NativeStaticLibs => {}
+                PrintRequest::NativeStaticLibs
+                | PrintRequest::ExportedSymbols
+                | PrintRequest::LinkPlan
+                | PrintRequest::BuildEnvReport => {}
             }
         }
+        // The requests registered by the driver, which `interface::run_compiler` has checked.
+        for name in &sess.opts.custom_prints {
+            let request = compiler.custom_print_requests().iter().find(|r| r.name() == *name);
+            request.unwrap().print(sess, input);
+        }
         Compilation::Stop
     }
 }
 
+/// Whether `cfg(name = "value")` may be shown by `--print cfg`.
+fn is_printable_cfg(sess: &Session, name: Symbol, value: Option<Symbol>) -> bool {
+    // Note that crt-static is a specially recognized cfg
+    // directive that's printed out here as part of
+    // rust-lang/rust#37406, but in general the
+    // `target_feature` cfg is gated under
+    // rust-lang/rust#29717. For now this is just
+    // specifically allowing the crt-static cfg and that's
+    // it, this is intended to get into Cargo and then go
+    // through to build scripts.
+    (name == sym::target_feature && value == Some(sym::crt_dash_static))
+        || sess.is_nightly_build()
+        || find_gated_cfg(|cfg_sym| cfg_sym == name).is_none()
+}
+
 /// Prints version information
 pub fn version(binary: &str, matches: &getopts::Matches) {
     let verbose = matches.opt_present("verbose");
@@ -819,6 +973,127 @@ fn print_wall_help() {
     );
 }
 
+/// Creates the lint store used to describe lints when there is no crate to load lint
+/// plugins from, which has the lints of `-Z lint-plugin` libraries and of the driver's
+/// `register_lints` callback. Also returns whether there were any such lints to register.
+fn new_lint_store(compiler: &interface::Compiler) -> (LintStore, bool) {
+    let sess = compiler.session();
+    let mut lint_store = rustc_lint::new_lint_store(sess, sess.unstable_options());
+    let lint_plugins = &sess.opts.debugging_opts.lint_plugin;
+    lint_store
+        .load_plugin_registrars(lint_plugins)
+        .unwrap_or_else(|err| sess.fatal(&err.to_string()));
+    let registered_lints = if let Some(register_lints) = compiler.register_lints() {
+        register_lints(sess, &mut lint_store);
+        true
+    } else {
+        !lint_plugins.is_empty()
+    };
+    (lint_store, registered_lints)
+}
+
+/// Prints every lint and lint group in `lint_store` as JSON, for `--print lints-json`.
+fn print_lints_json(sess: &Session, lint_store: &LintStore) {
+    let groups = lint_store.get_lint_groups();
+
+    let mut lints = lint_store.get_lints().to_vec();
+    lints.sort_by_cached_key(|lint| lint.name_lower());
+    let lints: Vec<json::Json> = lints
+        .into_iter()
+        .map(|lint| {
+            let mut member_of: Vec<String> = groups
+                .iter()
+                .filter(|(_, ids, _)| ids.contains(&LintId::of(lint)))
+                .map(|(name, ..)| name.to_string())
+                .collect();
+            member_of.sort();
+            let future_incompatible = lint.future_incompatible.map(|info| {
+                let reason = match info.reason {
+                    FutureIncompatibilityReason::FutureReleaseError => "future-release-error",
+                    FutureIncompatibilityReason::FutureReleaseErrorReportNow => {
+                        "future-release-error-report-now"
+                    }
+                    FutureIncompatibilityReason::EditionError(_) => "edition-error",
+                    FutureIncompatibilityReason::EditionSemanticsChange(_) => {
+                        "edition-semantics-change"
+                    }
+                };
+                let mut obj = BTreeMap::new();
+                obj.insert("reference".to_string(), info.reference.to_json());
+                obj.insert("reason".to_string(), reason.to_json());
+                obj.insert(
+                    "edition".to_string(),
+                    info.reason.edition().map(|edition| edition.to_string()).to_json(),
+                );
+                json::Json::Object(obj)
+            });
+            let config = lint
+                .config
+                .iter()
+                .map(|option| {
+                    let kind = match option.kind {
+                        LintConfigKind::Bool => "bool",
+                        LintConfigKind::Number => "number",
+                        LintConfigKind::String => "string",
+                    };
+                    let mut obj = BTreeMap::new();
+                    obj.insert("name".to_string(), option.name.to_json());
+                    obj.insert("kind".to_string(), kind.to_json());
+                    obj.insert("description".to_string(), option.desc.to_json());
+                    json::Json::Object(obj)
+                })
+                .collect();
+
+            let mut obj = BTreeMap::new();
+            obj.insert("name".to_string(), lint.name_lower().to_json());
+            obj.insert(
+                "default_level".to_string(),
+                lint.default_level(sess.edition()).as_str().to_json(),
+            );
+            obj.insert("description".to_string(), lint.desc.to_json());
+            obj.insert(
+                "feature_gate".to_string(),
+                lint.feature_gate.map(|gate| gate.to_string()).to_json(),
+            );
+            obj.insert(
+                "future_incompatible".to_string(),
+                future_incompatible.unwrap_or(json::Json::Null),
+            );
+            obj.insert("groups".to_string(), member_of.to_json());
+            obj.insert("config".to_string(), json::Json::Array(config));
+            obj.insert("is_plugin".to_string(), lint.is_plugin.to_json());
+            json::Json::Object(obj)
+        })
+        .collect();
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .map(|(name, ids, is_plugin)| {
+            let mut lints: Vec<String> = ids.iter().map(|id| id.lint.name_lower()).collect();
+            lints.sort();
+            let mut obj = BTreeMap::new();
+            obj.insert("name".to_string(), name.to_json());
+            obj.insert("lints".to_string(), lints.to_json());
+            obj.insert("is_plugin".to_string(), is_plugin.to_json());
+            (name, json::Json::Object(obj))
+        })
+        .collect();
+    groups.sort_by_key(|&(name, _)| name);
+
+    let mut manifest = BTreeMap::new();
+    manifest.insert("lints".to_string(), json::Json::Array(lints));
+    manifest.insert(
+        "groups".to_string(),
+        json::Json::Array(groups.into_iter().map(|(_, group)| group).collect()),
+    );
+    let manifest = json::Json::Object(manifest);
+    if sess.opts.print_json {
+        sess.print_json(PrintRequest::LintsJson, manifest);
+    } else {
+        println!("{}", manifest.pretty());
+    }
+}
+
 /// Write to stdout lint command options, together with a list of all available lints
 pub fn describe_lints(sess: &Session, lint_store: &LintStore, loaded_plugins: bool) {
     println!(
@@ -940,31 +1215,76 @@ fn sort_lint_groups(
     }
 }
 
-fn describe_debug_flags() {
-    println!("\nAvailable options:\n");
-    print_flag_list("-Z", config::DB_OPTIONS);
+fn describe_debug_flags(matches: &getopts::Matches, filter: Option<&str>) {
+    flag_help::print_flag_list(
+        "Available options",
+        "-Z",
+        "unstable, requires a nightly compiler",
+        config::DB_OPTIONS,
+        filter,
+        config::parse_color(matches),
+    );
 }
 
-fn describe_codegen_flags() {
-    println!("\nAvailable codegen options:\n");
-    print_flag_list("-C", config::CG_OPTIONS);
+fn describe_codegen_flags(matches: &getopts::Matches, filter: Option<&str>) {
+    flag_help::print_flag_list(
+        "Available codegen options",
+        "-C",
+        "stable",
+        config::CG_OPTIONS,
+        filter,
+        config::parse_color(matches),
+    );
 }
 
-fn print_flag_list<T>(
-    cmdline_opt: &str,
-    flag_list: &[(&'static str, T, &'static str, &'static str)],
-) {
-    let max_len = flag_list.iter().map(|&(name, _, _, _)| name.chars().count()).max().unwrap_or(0);
-
-    for &(name, _, _, desc) in flag_list {
-        println!(
-            "    {} {:>width$}=val -- {}",
-            cmdline_opt,
-            name.replace('_', "-"),
-            desc,
-            width = max_len
-        );
+/// Returns `Some` if `flags` asks for help, with the `<substring>` of `help=<substring>`.
+fn help_filter(flags: &[String]) -> Option<Option<&str>> {
+    flags.iter().find_map(|flag| match flag.strip_prefix("help") {
+        Some("") => Some(None),
+        Some(filter) => filter.strip_prefix('=').map(Some),
+        None => None,
+    })
+}
+
+/// Prints the command line for `-Z print-args-json`: the arguments after expanding argument
+/// files, and the options they set in a normalized form, in the order they were given.
+fn print_args_json(args: &[String], matches: &getopts::Matches) {
+    let mut options = Vec::new();
+    for group in config::rustc_optgroups() {
+        let values = matches.opt_strs_pos(group.name);
+        if values.is_empty() {
+            options.extend(
+                matches.opt_positions(group.name).into_iter().map(|pos| (pos, group.name, None)),
+            );
+        } else {
+            options.extend(values.into_iter().map(|(pos, value)| (pos, group.name, Some(value))));
+        }
     }
+    options.sort_by_key(|&(pos, ..)| pos);
+
+    let options = options
+        .into_iter()
+        .map(|(_, name, value)| {
+            let mut obj = BTreeMap::new();
+            obj.insert("option".to_string(), name.to_json());
+            // `-C opt_level=3` and `-C opt-level=3` are the same option.
+            let value = match (name, value) {
+                ("codegen" | "Z", Some(value)) => Some(match value.split_once('=') {
+                    Some((key, value)) => format!("{}={}", key.replace('_', "-"), value),
+                    None => value.replace('_', "-"),
+                }),
+                (_, value) => value,
+            };
+            obj.insert("value".to_string(), value.to_json());
+            json::Json::Object(obj)
+        })
+        .collect();
+
+    let mut obj = BTreeMap::new();
+    obj.insert("args".to_string(), args[1..].to_json());
+    obj.insert("options".to_string(), json::Json::Array(options));
+    obj.insert("inputs".to_string(), matches.free.to_json());
+    eprintln!("{}", json::Json::Object(obj));
 }
 
 /// Process command line options. Emits messages as appropriate. If compilation
@@ -1052,15 +1372,15 @@ pub fn handle_options(args: &[String]) -> Option<getopts::Matches> {
 
     // Don't handle -W help here, because we might first load plugins.
     let debug_flags = matches.opt_strs("Z");
-    if debug_flags.iter().any(|x| *x == "help") {
-        describe_debug_flags();
+    if let Some(filter) = help_filter(&debug_flags) {
+        describe_debug_flags(&matches, filter);
         return None;
     }
 
     let cg_flags = matches.opt_strs("C");
 
-    if cg_flags.iter().any(|x| *x == "help") {
-        describe_codegen_flags();
+    if let Some(filter) = help_filter(&cg_flags) {
+        describe_codegen_flags(&matches, filter);
         return None;
     }
 
@@ -1140,6 +1460,25 @@ fn extra_compiler_flags() -> Option<(Vec<String>, bool)> {
     if !result.is_empty() { Some((result, excluded_cargo_defaults)) } else { None }
 }
 
+/// The name and version of the crate being compiled for the ICE report, recorded when the
+/// session is created rather than read from the arguments again while panicking.
+static CRATE_VERSION_FOR_ICE: SyncOnceCell<String> = SyncOnceCell::new();
+
+/// Records the name and version of the crate being compiled for the ICE report, if it was given a
+/// version with `--crate-version`.
+fn record_crate_version_for_ice(sess: &Session) {
+    let version = match sess.crate_version() {
+        Some(version) => version,
+        None => return,
+    };
+    let crate_version = match &sess.opts.crate_name {
+        Some(name) => format!("compiling crate `{}` version {}", name, version),
+        None => format!("compiling crate version {}", version),
+    };
+    // Only the first session of the process is reported.
+    let _ = CRATE_VERSION_FOR_ICE.set(crate_version);
+}
+
 /// Runs a closure and catches unwinds triggered by fatal errors.
 ///
 /// The compiler currently unwinds with a special sentinel value to abort
@@ -1216,6 +1555,10 @@ pub fn report_ice(info: &panic::PanicInfo<'_>, bug_report_url: &str) {
         .into(),
     ];
 
+    if let Some(crate_version) = CRATE_VERSION_FOR_ICE.get() {
+        xs.push(crate_version.as_str().into());
+    }
+
     if let Some((flags, excluded_cargo_defaults)) = extra_compiler_flags() {
         xs.push(format!("compiler flags: {}", flags.join(" ")).into());
 
@@ -5,15 +5,18 @@
 use rustc_errors::ErrorReported;
 use rustc_hir as hir;
 use rustc_hir_pretty as pprust_hir;
+use rustc_interface::hir_json::{object, span_json};
 use rustc_middle::hir::map as hir_map;
 use rustc_middle::mir::{write_mir_graphviz, write_mir_pretty};
 use rustc_middle::ty::{self, TyCtxt};
+use rustc_serialize::json::{Json, ToJson};
 use rustc_session::config::{Input, PpAstTreeMode, PpHirMode, PpMode, PpSourceMode};
 use rustc_session::Session;
+use rustc_span::hygiene::{ExpnId, Transparency};
 use rustc_span::symbol::Ident;
-use rustc_span::FileName;
+use rustc_span::{FileName, Span};
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::fmt::Write;
 use std::path::Path;
 
@@ -55,6 +58,14 @@ fn call_with_pp_support<'tcx, A, F>(
             let annotation = HygieneAnnotation { sess };
             f(&annotation)
         }
+        ExpandedHygieneJson => {
+            let annotation = HygieneJsonAnnotation {
+                sess,
+                idents: RefCell::new(Vec::new()),
+                nodes: RefCell::new(Vec::new()),
+            };
+            f(&annotation)
+        }
     }
 }
 fn call_with_pp_support_hir<A, F>(ppmode: &PpHirMode, tcx: TyCtxt<'_>, f: F) -> A
@@ -90,6 +101,11 @@ trait PrinterSupport: pprust::PpAnn {
     /// (Rust does not yet support upcasting from a trait object to
     /// an object for one of its supertraits.)
     fn pp_ann(&self) -> &dyn pprust::PpAnn;
+
+    /// Turns the pretty-printed crate into the final output.
+    fn finish(&self, out: String) -> String {
+        out
+    }
 }
 
 trait HirPrinterSupport<'hir>: pprust_hir::PpAnn {
@@ -289,6 +305,156 @@ fn post(&self, s: &mut pprust::State<'_>, node: pprust::AnnNode<'_>) {
     }
 }
 
+/// The version of the output of `-Z unpretty=expanded,hygiene,json`.
+const HYGIENE_JSON_FORMAT_VERSION: u32 = 1;
+
+/// Prints the expanded source as JSON, along with the hygiene marks and the macro backtrace of
+/// each identifier in it, in the order they are printed. Other tokens, like literals, keywords
+/// and punctuation, aren't nodes of the AST of their own, so they are covered by the spans of
+/// the expressions, patterns, blocks and items they are part of, which are listed too.
+struct HygieneJsonAnnotation<'a> {
+    sess: &'a Session,
+    idents: RefCell<Vec<Ident>>,
+    /// The kind and span of each expression, pattern, block and item, in the order they start.
+    nodes: RefCell<Vec<(&'static str, Span)>>,
+}
+
+impl<'a> PrinterSupport for HygieneJsonAnnotation<'a> {
+    fn sess(&self) -> &Session {
+        self.sess
+    }
+
+    fn pp_ann(&self) -> &dyn pprust::PpAnn {
+        self
+    }
+
+    fn finish(&self, out: String) -> String {
+        // The expansions the identifiers and nodes come from, and all their parents, in the
+        // order they are first seen.
+        let mut expns = Vec::new();
+
+        let idents = self
+            .idents
+            .borrow()
+            .iter()
+            .map(|ident| {
+                let mut fields = vec![
+                    ("name", ident.name.to_string().to_json()),
+                    ("span", span_json(self.sess, ident.span)),
+                ];
+                fields.extend(self.hygiene_fields(ident.span, &mut expns));
+                object(fields)
+            })
+            .collect();
+
+        let nodes = self
+            .nodes
+            .borrow()
+            .iter()
+            .map(|&(kind, span)| {
+                let mut fields =
+                    vec![("kind", kind.to_json()), ("span", span_json(self.sess, span))];
+                fields.extend(self.hygiene_fields(span, &mut expns));
+                object(fields)
+            })
+            .collect();
+
+        let expansions = expns
+            .into_iter()
+            .map(|expn| {
+                let expn_data = expn.expn_data();
+                object(vec![
+                    ("id", expn_id_json(expn)),
+                    ("kind", expn_data.kind.descr().to_json()),
+                    ("parent", expn_id_json(expn_data.parent)),
+                    ("call_site", span_json(self.sess, expn_data.call_site)),
+                    ("def_site", span_json(self.sess, expn_data.def_site)),
+                ])
+            })
+            .collect();
+
+        let json = object(vec![
+            ("format_version", HYGIENE_JSON_FORMAT_VERSION.to_json()),
+            ("source", out.to_json()),
+            ("idents", Json::Array(idents)),
+            ("nodes", Json::Array(nodes)),
+            ("expansions", Json::Array(expansions)),
+        ]);
+        format!("{}\n", json)
+    }
+}
+
+impl<'a> HygieneJsonAnnotation<'a> {
+    /// The syntax context of `span`, with its hygiene marks and macro backtrace. The expansions
+    /// they refer to are added to `expns`.
+    fn hygiene_fields(&self, span: Span, expns: &mut Vec<ExpnId>) -> Vec<(&'static str, Json)> {
+        let ctxt = span.ctxt();
+        let marks = ctxt
+            .marks()
+            .into_iter()
+            .map(|(expn, transparency)| {
+                add_expn(expns, expn);
+                let transparency = match transparency {
+                    Transparency::Transparent => "transparent",
+                    Transparency::SemiTransparent => "semi-transparent",
+                    Transparency::Opaque => "opaque",
+                };
+                object(vec![("expn", expn_id_json(expn)), ("transparency", transparency.to_json())])
+            })
+            .collect();
+        let backtrace = span
+            .macro_backtrace()
+            .map(|expn_data| {
+                object(vec![
+                    ("kind", expn_data.kind.descr().to_json()),
+                    ("call_site", span_json(self.sess, expn_data.call_site)),
+                ])
+            })
+            .collect();
+        vec![
+            ("ctxt", format!("{:?}", ctxt).to_json()),
+            ("outer_expn", expn_id_json(ctxt.outer_expn())),
+            ("marks", Json::Array(marks)),
+            ("backtrace", Json::Array(backtrace)),
+        ]
+    }
+}
+
+impl<'a> pprust::PpAnn for HygieneJsonAnnotation<'a> {
+    fn pre(&self, _s: &mut pprust::State<'_>, node: pprust::AnnNode<'_>) {
+        let node = match node {
+            pprust::AnnNode::Expr(expr) => ("expr", expr.span),
+            pprust::AnnNode::Pat(pat) => ("pat", pat.span),
+            pprust::AnnNode::Block(block) => ("block", block.span),
+            pprust::AnnNode::Item(item) => ("item", item.span),
+            _ => return,
+        };
+        self.nodes.borrow_mut().push(node);
+    }
+
+    fn post(&self, _s: &mut pprust::State<'_>, node: pprust::AnnNode<'_>) {
+        if let pprust::AnnNode::Ident(&ident) = node {
+            self.idents.borrow_mut().push(ident);
+        }
+    }
+}
+
+/// Adds `expn` and its parents to `expns`, unless they are in it already.
+fn add_expn(expns: &mut Vec<ExpnId>, mut expn: ExpnId) {
+    while expn != ExpnId::root() && !expns.contains(&expn) {
+        expns.push(expn);
+        expn = expn.expn_data().parent;
+    }
+}
+
+/// The id of an expansion, as `CRATE:INDEX`, or `null` for the root expansion.
+fn expn_id_json(expn: ExpnId) -> Json {
+    if expn == ExpnId::root() {
+        return Json::Null;
+    }
+    format!("{}:{}", expn.krate.as_u32(), expn.local_id.as_u32()).to_json()
+}
+
 struct TypedAnnotation<'tcx> {
     tcx: TyCtxt<'tcx>,
     maybe_typeck_results: Cell<Option<&'tcx ty::TypeckResults<'tcx>>>,
@@ -384,7 +550,7 @@ pub fn print_after_parsing(
                 debug!("pretty printing source code {:?}", s);
                 let sess = annotation.sess();
                 let parse = &sess.parse_sess;
-                pprust::print_crate(
+                annotation.finish(pprust::print_crate(
                     sess.source_map(),
                     krate,
                     src_name,
@@ -392,7 +558,7 @@ pub fn print_after_parsing(
                     annotation.pp_ann(),
                     false,
                     parse.edition,
-                )
+                ))
             })
         }
         AstTree(PpAstTreeMode::Normal) => {
@@ -426,7 +592,7 @@ pub fn print_after_hir_lowering<'tcx>(
                 debug!("pretty printing source code {:?}", s);
                 let sess = annotation.sess();
                 let parse = &sess.parse_sess;
-                pprust::print_crate(
+                annotation.finish(pprust::print_crate(
                     sess.source_map(),
                     krate,
                     src_name,
@@ -434,7 +600,7 @@ pub fn print_after_hir_lowering<'tcx>(
                     annotation.pp_ann(),
                     true,
                     parse.edition,
-                )
+                ))
             })
         }
 
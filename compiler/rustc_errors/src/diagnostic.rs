@@ -116,7 +116,12 @@ pub fn is_error(&self) -> bool {
         match self.level {
             Level::Bug | Level::Fatal | Level::Error { .. } | Level::FailureNote => true,
 
-            Level::Warning | Level::Note | Level::Help | Level::Cancelled | Level::Allow => false,
+            Level::Warning
+            | Level::Note
+            | Level::Help
+            | Level::Cancelled
+            | Level::Allow
+            | Level::Expect(_) => false,
         }
     }
 
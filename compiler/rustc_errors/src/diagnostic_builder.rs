@@ -1,5 +1,6 @@
 use crate::{Diagnostic, DiagnosticId, DiagnosticStyledString};
 use crate::{Handler, Level, StashKey};
+use rustc_data_structures::sync::Lock;
 use rustc_lint_defs::Applicability;
 
 use rustc_span::{MultiSpan, Span};
@@ -28,6 +29,8 @@ struct DiagnosticBuilderInner<'a> {
     handler: &'a Handler,
     diagnostic: Diagnostic,
     allow_suggestions: bool,
+    /// Where `emit` adds the diagnostic instead of emitting it, see `buffer_emission`.
+    emission_buffer: Option<&'a Lock<Vec<Diagnostic>>>,
 }
 
 /// In general, the `DiagnosticBuilder` uses deref to allow access to
@@ -100,10 +103,25 @@ fn deref_mut(&mut self) -> &mut Diagnostic {
 impl<'a> DiagnosticBuilder<'a> {
     /// Emit the diagnostic.
     pub fn emit(&mut self) {
-        self.0.handler.emit_diagnostic(&self);
+        match self.0.emission_buffer {
+            Some(buffer) => buffer.lock().push(self.0.diagnostic.clone()),
+            None => self.0.handler.emit_diagnostic(&self),
+        }
         self.cancel();
     }
 
+    /// Makes `emit` add the diagnostic to `buffer` rather than emit it, for the caller to emit
+    /// the buffered diagnostics later on, e.g. in a deterministic order after running several
+    /// passes concurrently.
+    pub fn buffer_emission<'b>(self, buffer: &'b Lock<Vec<Diagnostic>>) -> DiagnosticBuilder<'b>
+    where
+        'a: 'b,
+    {
+        let mut builder: DiagnosticBuilder<'b> = self;
+        builder.0.emission_buffer = Some(buffer);
+        builder
+    }
+
     /// Emit the diagnostic unless `delay` is true,
     /// in which case the emission will be delayed as a bug.
     ///
@@ -428,6 +446,7 @@ pub fn allow_suggestions(&mut self, allow: bool) -> &mut Self {
             handler,
             diagnostic,
             allow_suggestions: true,
+            emission_buffer: None,
         }))
     }
 }
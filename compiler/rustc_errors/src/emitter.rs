@@ -16,7 +16,7 @@
 use crate::styled_buffer::StyledBuffer;
 use crate::{
     CodeSuggestion, Diagnostic, DiagnosticId, Handler, Level, SubDiagnostic, SubstitutionHighlight,
-    SuggestionStyle,
+    SuggestionStyle, TimingEvent, UnusedExtern,
 };
 
 use rustc_lint_defs::pluralize;
@@ -198,7 +198,11 @@ fn emit_artifact_notification(&mut self, _path: &Path, _artifact_type: &str) {}
     fn emit_future_breakage_report(&mut self, _diags: Vec<Diagnostic>) {}
 
     /// Emit list of unused externs
-    fn emit_unused_externs(&mut self, _lint_level: &str, _unused_externs: &[&str]) {}
+    fn emit_unused_externs(&mut self, _lint_level: &str, _unused_externs: &[UnusedExtern]) {}
+
+    /// Emit a notification that a section of the compilation has started or ended.
+    /// This is currently only supported for the JSON format.
+    fn emit_timing_section(&mut self, _section: &str, _event: TimingEvent) {}
 
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
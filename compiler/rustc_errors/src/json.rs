@@ -14,7 +14,9 @@
 use crate::emitter::{Emitter, HumanReadableErrorType};
 use crate::registry::Registry;
 use crate::DiagnosticId;
+use crate::TimingEvent;
 use crate::ToolMetadata;
+use crate::UnusedExtern;
 use crate::{CodeSuggestion, SubDiagnostic};
 use rustc_lint_defs::Applicability;
 
@@ -24,6 +26,7 @@
 use std::io::{self, Write};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::vec;
 
 use rustc_serialize::json::{as_json, as_pretty_json};
@@ -138,7 +141,7 @@ fn emit_future_breakage_report(&mut self, diags: Vec<crate::Diagnostic>) {
         let data: Vec<FutureBreakageItem> = diags
             .into_iter()
             .map(|mut diag| {
-                if diag.level == crate::Level::Allow {
+                if matches!(diag.level, crate::Level::Allow | crate::Level::Expect(_)) {
                     diag.level = crate::Level::Warning;
                 }
                 FutureBreakageItem { diagnostic: Diagnostic::from_errors_diagnostic(&diag, self) }
@@ -156,8 +159,10 @@ fn emit_future_breakage_report(&mut self, diags: Vec<crate::Diagnostic>) {
         }
     }
 
-    fn emit_unused_externs(&mut self, lint_level: &str, unused_externs: &[&str]) {
-        let data = UnusedExterns { lint_level, unused_extern_names: unused_externs };
+    fn emit_unused_externs(&mut self, lint_level: &str, unused_externs: &[UnusedExtern]) {
+        let unused_extern_names =
+            unused_externs.iter().map(|unused_extern| unused_extern.name.as_str()).collect();
+        let data = UnusedExterns { lint_level, unused_extern_names, unused_externs };
         let result = if self.pretty {
             writeln!(&mut self.dst, "{}", as_pretty_json(&data))
         } else {
@@ -169,6 +174,22 @@ fn emit_unused_externs(&mut self, lint_level: &str, unused_externs: &[&str]) {
         }
     }
 
+    fn emit_timing_section(&mut self, section: &str, event: TimingEvent) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_micros() as u64);
+        let data = SectionTiming { section_timing: section, event: event.as_str(), timestamp };
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", as_pretty_json(&data))
+        } else {
+            writeln!(&mut self.dst, "{}", as_json(&data))
+        }
+        .and_then(|_| self.dst.flush());
+        if let Err(e) = result {
+            panic!("failed to print timing section: {:?}", e);
+        }
+    }
+
     fn source_map(&self) -> Option<&Lrc<SourceMap>> {
         Some(&self.sm)
     }
@@ -336,11 +357,23 @@ struct FutureIncompatReport {
 // We could unify this struct the one in rustdoc but they have different
 // ownership semantics, so doing so would create wasteful allocations.
 #[derive(Encodable)]
-struct UnusedExterns<'a, 'b, 'c> {
+struct UnusedExterns<'a, 'b> {
     /// The severity level of the unused dependencies lint
     lint_level: &'a str,
     /// List of unused externs by their names.
-    unused_extern_names: &'b [&'c str],
+    unused_extern_names: Vec<&'b str>,
+    /// List of unused externs with the location of their dependency and their crate file.
+    unused_externs: &'b [UnusedExtern],
+}
+
+#[derive(Encodable)]
+struct SectionTiming<'a> {
+    /// The name of the section of the compilation.
+    section_timing: &'a str,
+    /// Whether the section has started (`"start"`) or ended (`"end"`).
+    event: &'a str,
+    /// Microseconds since the UNIX epoch.
+    timestamp: u64,
 }
 
 impl Diagnostic {
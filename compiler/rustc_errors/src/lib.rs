@@ -25,6 +25,7 @@
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
+use rustc_lint_defs::LintExpectationId;
 pub use rustc_lint_defs::{pluralize, Applicability};
 use rustc_serialize::json::Json;
 use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
@@ -113,6 +114,18 @@ fn encode(&self, e: &mut S) -> Result<(), S::Error> {
     }
 }
 
+/// An unused `--extern` crate, as reported with `--json=unused-externs`.
+#[derive(Clone, Debug, Encodable)]
+pub struct UnusedExtern {
+    /// The name of the crate.
+    pub name: String,
+    /// The location of the dependency given by the build system with `--extern-location`, with
+    /// raw locations as JSON strings.
+    pub location: Option<Json>,
+    /// The path of the crate file given with `--extern`, canonicalized.
+    pub path: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Hash, Encodable, Decodable)]
 pub struct CodeSuggestion {
     /// Each substitute can have multiple variants due to multiple
@@ -446,6 +459,9 @@ struct HandlerInner {
 
     future_breakage_diagnostics: Vec<Diagnostic>,
 
+    /// The ids of the `#[expect]` attributes fulfilled by the emitted diagnostics.
+    fulfilled_expectations: FxHashSet<LintExpectationId>,
+
     /// If set to `true`, no warning or error will be emitted.
     quiet: bool,
 }
@@ -563,6 +579,7 @@ pub fn with_emitter_and_flags(
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                fulfilled_expectations: Default::default(),
                 quiet: false,
             }),
         }
@@ -705,6 +722,11 @@ pub fn struct_allow(&self, msg: &str) -> DiagnosticBuilder<'_> {
         DiagnosticBuilder::new(self, Level::Allow, msg)
     }
 
+    /// Construct a builder at the `Expect` level with the `msg`.
+    pub fn struct_expect(&self, msg: &str, id: LintExpectationId) -> DiagnosticBuilder<'_> {
+        DiagnosticBuilder::new(self, Level::Expect(id), msg)
+    }
+
     /// Construct a builder at the `Error` level at the given `span` and with the `msg`.
     pub fn struct_span_err(&self, span: impl Into<MultiSpan>, msg: &str) -> DiagnosticBuilder<'_> {
         let mut result = self.struct_err(msg);
@@ -887,6 +909,12 @@ pub fn take_future_breakage_diagnostics(&self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.inner.borrow_mut().future_breakage_diagnostics)
     }
 
+    /// Returns the ids of the `#[expect]` attributes fulfilled by the diagnostics emitted so far,
+    /// including the ones replayed from the incremental cache.
+    pub fn fulfilled_expectations(&self) -> FxHashSet<LintExpectationId> {
+        self.inner.borrow().fulfilled_expectations.clone()
+    }
+
     pub fn abort_if_errors(&self) {
         self.inner.borrow_mut().abort_if_errors()
     }
@@ -921,10 +949,14 @@ pub fn emit_future_breakage_report(&self, diags: Vec<Diagnostic>) {
         self.inner.borrow_mut().emitter.emit_future_breakage_report(diags)
     }
 
-    pub fn emit_unused_externs(&self, lint_level: &str, unused_externs: &[&str]) {
+    pub fn emit_unused_externs(&self, lint_level: &str, unused_externs: &[UnusedExtern]) {
         self.inner.borrow_mut().emit_unused_externs(lint_level, unused_externs)
     }
 
+    pub fn emit_timing_section(&self, section: &str, event: TimingEvent) {
+        self.inner.borrow_mut().emitter.emit_timing_section(section, event)
+    }
+
     pub fn delay_as_bug(&self, diagnostic: Diagnostic) {
         self.inner.borrow_mut().delay_as_bug(diagnostic)
     }
@@ -966,6 +998,11 @@ fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
 
         (*TRACK_DIAGNOSTICS)(diagnostic);
 
+        if let Expect(id) = diagnostic.level {
+            self.fulfilled_expectations.insert(id);
+            return;
+        }
+
         if diagnostic.level == Allow {
             return;
         }
@@ -1006,7 +1043,7 @@ fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
         self.emitter.emit_artifact_notification(path, artifact_type);
     }
 
-    fn emit_unused_externs(&mut self, lint_level: &str, unused_externs: &[&str]) {
+    fn emit_unused_externs(&mut self, lint_level: &str, unused_externs: &[UnusedExtern]) {
         self.emitter.emit_unused_externs(lint_level, unused_externs);
     }
 
@@ -1236,6 +1273,23 @@ fn decorate(mut self) -> Diagnostic {
     }
 }
 
+/// Whether a section of the compilation reported by [`Handler::emit_timing_section`] has
+/// started or ended.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TimingEvent {
+    Start,
+    End,
+}
+
+impl TimingEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimingEvent::Start => "start",
+            TimingEvent::End => "end",
+        }
+    }
+}
+
 #[derive(Copy, PartialEq, Clone, Hash, Debug, Encodable, Decodable)]
 pub enum Level {
     Bug,
@@ -1250,6 +1304,8 @@ pub enum Level {
     Cancelled,
     FailureNote,
     Allow,
+    /// An allowed lint diagnostic that fulfills the `#[expect]` attribute with the id.
+    Expect(LintExpectationId),
 }
 
 impl fmt::Display for Level {
@@ -1275,7 +1331,7 @@ fn color(self) -> ColorSpec {
                 spec.set_fg(Some(Color::Cyan)).set_intense(true);
             }
             FailureNote => {}
-            Allow | Cancelled => unreachable!(),
+            Allow | Expect(_) | Cancelled => unreachable!(),
         }
         spec
     }
@@ -1289,7 +1345,7 @@ pub fn to_str(self) -> &'static str {
             Help => "help",
             FailureNote => "failure-note",
             Cancelled => panic!("Shouldn't call on cancelled error"),
-            Allow => panic!("Shouldn't call on allowed error"),
+            Allow | Expect(_) => panic!("Shouldn't call on allowed error"),
         }
     }
 
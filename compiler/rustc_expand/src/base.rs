@@ -4,7 +4,7 @@
 use rustc_ast::attr::MarkedAttrs;
 use rustc_ast::ptr::P;
 use rustc_ast::token::{self, Nonterminal};
-use rustc_ast::tokenstream::{CanSynthesizeMissingTokens, TokenStream};
+use rustc_ast::tokenstream::{CanSynthesizeMissingTokens, TokenStream, TokenTree};
 use rustc_ast::visit::{AssocCtxt, Visitor};
 use rustc_ast::{self as ast, AstLike, Attribute, Item, NodeId, PatKind};
 use rustc_attr::{self as attr, Deprecation, Stability};
@@ -12,7 +12,7 @@
 use rustc_data_structures::sync::{self, Lrc};
 use rustc_errors::{Applicability, DiagnosticBuilder, ErrorReported};
 use rustc_lint_defs::builtin::PROC_MACRO_BACK_COMPAT;
-use rustc_lint_defs::BuiltinLintDiagnostics;
+use rustc_lint_defs::{BuiltinLintDiagnostics, Level};
 use rustc_parse::{self, nt_to_tokenstream, parser, MACRO_ARGUMENTS};
 use rustc_session::{parse::ParseSess, Limit, Session};
 use rustc_span::def_id::{CrateNum, DefId, LocalDefId};
@@ -27,6 +27,7 @@
 use std::iter;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Duration;
 
 crate use rustc_span::hygiene::MacroKind;
 
@@ -953,12 +954,17 @@ pub struct ExpansionData {
     pub prior_type_ascription: Option<(Span, bool)>,
     /// Some parent node that is close to this macro call
     pub lint_node_id: NodeId,
+    /// The lint level attributes (`#[allow]`, `#[deny]`, ...) of the nodes enclosing this
+    /// macro call, one list per node, outermost first
+    pub lint_attrs: Rc<Vec<Vec<Attribute>>>,
     pub is_trailing_mac: bool,
 }
 
 type OnExternModLoaded<'a> =
     Option<&'a dyn Fn(Ident, Vec<Attribute>, Vec<P<Item>>, Span) -> (Vec<Attribute>, Vec<P<Item>>)>;
 
+type LintLevelQuery<'a> = Option<&'a dyn Fn(&str, &[Vec<Attribute>]) -> Option<Level>>;
+
 /// One of these is made during expansion and incrementally updated as we go;
 /// when a macro expansion occurs, the resulting nodes have the `backtrace()
 /// -> expn_data` of their expansion context stored into their span.
@@ -977,10 +983,19 @@ pub struct ExtCtxt<'a> {
     ///
     /// `Ident` is the module name.
     pub(super) extern_mod_loaded: OnExternModLoaded<'a>,
+    /// Computes the level of a lint in the scope of the lint level attributes of the nodes
+    /// enclosing a macro call, for `proc_macro::lint_levels`.
+    ///
+    /// Returns `None` for unknown lints and for lint groups.
+    pub(super) lint_level_query: LintLevelQuery<'a>,
     /// When we 'expand' an inert attribute, we leave it
     /// in the AST, but insert it here so that we know
     /// not to expand it again.
     pub(super) expanded_inert_attrs: MarkedAttrs,
+    /// The number of tokens produced by the macro being expanded, and the time taken by the
+    /// expansions nested in it, for `-Z macro-stats`.
+    pub(super) produced_tokens: usize,
+    pub(super) nested_expansion_time: Duration,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -989,6 +1004,7 @@ pub fn new(
         ecfg: expand::ExpansionConfig<'a>,
         resolver: &'a mut dyn ResolverExpand,
         extern_mod_loaded: OnExternModLoaded<'a>,
+        lint_level_query: LintLevelQuery<'a>,
     ) -> ExtCtxt<'a> {
         ExtCtxt {
             sess,
@@ -996,6 +1012,7 @@ pub fn new(
             reduced_recursion_limit: None,
             resolver,
             extern_mod_loaded,
+            lint_level_query,
             root_path: PathBuf::new(),
             current_expansion: ExpansionData {
                 id: LocalExpnId::ROOT,
@@ -1004,11 +1021,32 @@ pub fn new(
                 dir_ownership: DirOwnership::Owned { relative: None },
                 prior_type_ascription: None,
                 lint_node_id: ast::CRATE_NODE_ID,
+                lint_attrs: Default::default(),
                 is_trailing_mac: false,
             },
             force_mode: false,
             expansions: FxHashMap::default(),
             expanded_inert_attrs: MarkedAttrs::new(),
+            produced_tokens: 0,
+            nested_expansion_time: Duration::ZERO,
+        }
+    }
+
+    /// Records the tokens produced by the macro being expanded, for `-Z macro-stats`.
+    pub fn record_produced_tokens(&mut self, stream: &TokenStream) {
+        fn count(stream: &TokenStream) -> usize {
+            stream
+                .trees()
+                .map(|tree| match tree {
+                    TokenTree::Token(_) => 1,
+                    // The delimiters are tokens too.
+                    TokenTree::Delimited(_, _, inner) => 2 + count(&inner),
+                })
+                .sum()
+        }
+
+        if self.sess.opts.debugging_opts.macro_stats {
+            self.produced_tokens += count(stream);
         }
     }
 
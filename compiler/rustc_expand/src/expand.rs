@@ -27,9 +27,10 @@
 };
 use rustc_parse::validate_attr;
 use rustc_session::lint::builtin::{UNUSED_ATTRIBUTES, UNUSED_DOC_COMMENTS};
-use rustc_session::lint::BuiltinLintDiagnostics;
+use rustc_session::lint::{BuiltinLintDiagnostics, Level};
 use rustc_session::parse::{feature_err, ParseSess};
 use rustc_session::Limit;
+use rustc_span::hygiene::{ExpnKind, MacroKind};
 use rustc_span::symbol::{sym, Ident};
 use rustc_span::{FileName, LocalExpnId, Span};
 
@@ -37,6 +38,7 @@
 use std::ops::DerefMut;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Instant;
 use std::{iter, mem};
 
 macro_rules! ast_fragments {
@@ -448,7 +450,7 @@ pub fn fully_expand_fragment(&mut self, input_fragment: AstFragment) -> AstFragm
             self.cx.force_mode = force;
 
             let fragment_kind = invoc.fragment_kind;
-            let (expanded_fragment, new_invocations) = match self.expand_invoc(invoc, &ext.kind) {
+            let (expanded_fragment, new_invocations) = match self.expand_invoc(invoc, &ext) {
                 ExpandResult::Ready(fragment) => {
                     let mut derive_invocations = Vec::new();
                     let derive_placeholders = self
@@ -617,7 +619,49 @@ fn error_wrong_fragment_kind(&mut self, kind: AstFragmentKind, mac: &ast::MacCal
         self.cx.trace_macros_diag();
     }
 
+    /// Expands `invoc`, recording the expansion for `-Z macro-stats`.
     fn expand_invoc(
+        &mut self,
+        invoc: Invocation,
+        ext: &SyntaxExtension,
+    ) -> ExpandResult<AstFragment, Invocation> {
+        if !self.cx.sess.opts.debugging_opts.macro_stats
+            || matches!(ext.kind, SyntaxExtensionKind::NonMacroAttr)
+        {
+            return self.expand_invoc_inner(invoc, &ext.kind);
+        }
+        let name = match invoc.expansion_data.id.expn_data().kind {
+            ExpnKind::Macro(MacroKind::Bang, name) => format!("{}!", name),
+            ExpnKind::Macro(MacroKind::Attr, name) => format!("#[{}]", name),
+            ExpnKind::Macro(MacroKind::Derive, name) => format!("#[derive({})]", name),
+            _ => return self.expand_invoc_inner(invoc, &ext.kind),
+        };
+        let kind = match ext.kind {
+            _ if ext.builtin_name.is_some() => "builtin",
+            SyntaxExtensionKind::Bang(..)
+            | SyntaxExtensionKind::Attr(..)
+            | SyntaxExtensionKind::Derive(..) => "proc macro",
+            _ => "declarative",
+        };
+
+        // Eager expansions, e.g. of the arguments of `concat!`, expand other macros while this
+        // one is expanded, which are recorded separately.
+        let outer_tokens = mem::take(&mut self.cx.produced_tokens);
+        let outer_nested_time = mem::take(&mut self.cx.nested_expansion_time);
+        let start = Instant::now();
+        let result = self.expand_invoc_inner(invoc, &ext.kind);
+        let elapsed = start.elapsed();
+        // An invocation that is retried is only recorded once it's expanded.
+        if let ExpandResult::Ready(_) = result {
+            let time = elapsed.saturating_sub(self.cx.nested_expansion_time);
+            self.cx.sess.record_macro_expansion(kind, name, self.cx.produced_tokens, time);
+        }
+        self.cx.produced_tokens = outer_tokens;
+        self.cx.nested_expansion_time = outer_nested_time + elapsed;
+        result
+    }
+
+    fn expand_invoc_inner(
         &mut self,
         invoc: Invocation,
         ext: &SyntaxExtensionKind,
@@ -1074,6 +1118,23 @@ fn configure<T: AstLike>(&mut self, node: T) -> Option<T> {
         self.cfg.configure(node)
     }
 
+    /// Records the lint level attributes among `attrs` for the macro calls inside their node,
+    /// returning the previously recorded ones to restore once the node has been visited.
+    fn enter_lint_attrs(&mut self, attrs: &[ast::Attribute]) -> Rc<Vec<Vec<ast::Attribute>>> {
+        let old_lint_attrs = self.cx.current_expansion.lint_attrs.clone();
+        let lint_attrs: Vec<_> = attrs
+            .iter()
+            .filter(|attr| Level::from_attr(attr).is_some())
+            .cloned()
+            .collect();
+        if !lint_attrs.is_empty() {
+            let mut new_lint_attrs = (*old_lint_attrs).clone();
+            new_lint_attrs.push(lint_attrs);
+            self.cx.current_expansion.lint_attrs = Rc::new(new_lint_attrs);
+        }
+        old_lint_attrs
+    }
+
     // Detect use of feature-gated or invalid attributes on macro invocations
     // since they will not be detected after macro expansion.
     fn check_attributes(&self, attrs: &[ast::Attribute], call: &MacCall) {
@@ -1140,12 +1201,16 @@ fn check_attributes(&self, attrs: &[ast::Attribute], call: &MacCall) {
 /// * `self` is the 'self' parameter for the current method,
 /// * `id` is a mutable reference to the `NodeId` field
 ///    of the current AST node.
+/// * `attrs` are the attributes of the current AST node,
+///    whose lint level attributes are recorded for the
+///    macro calls inside it (see `ExpansionData::lint_attrs`).
 /// * `closure` is a closure that executes the
 ///   `noop_visit_*` / `noop_flat_map_*` method
 ///   for the current AST node.
 macro_rules! assign_id {
-    ($self:ident, $id:expr, $closure:expr) => {{
+    ($self:ident, $id:expr, $attrs:expr, $closure:expr) => {{
         let old_id = $self.cx.current_expansion.lint_node_id;
+        let old_lint_attrs = $self.enter_lint_attrs($attrs);
         if $self.monotonic {
             debug_assert_eq!(*$id, ast::DUMMY_NODE_ID);
             let new_id = $self.cx.resolver.next_node_id();
@@ -1154,6 +1219,7 @@ macro_rules! assign_id {
         }
         let ret = ($closure)();
         $self.cx.current_expansion.lint_node_id = old_id;
+        $self.cx.current_expansion.lint_attrs = old_lint_attrs;
         ret
     }};
 }
@@ -1202,7 +1268,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 self.check_attributes(&expr.attrs, &mac);
                 self.collect_bang(mac, AstFragmentKind::Expr).make_expr().into_inner()
             } else {
-                assign_id!(self, &mut expr.id, || {
+                assign_id!(self, &mut expr.id, &expr.attrs, || {
                     ensure_sufficient_stack(|| noop_visit_expr(&mut expr, self));
                 });
                 expr
@@ -1219,7 +1285,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 .make_arms();
         }
 
-        assign_id!(self, &mut arm.id, || noop_flat_map_arm(arm, self))
+        assign_id!(self, &mut arm.id, &arm.attrs, || noop_flat_map_arm(arm, self))
     }
 
     fn flat_map_expr_field(&mut self, field: ast::ExprField) -> SmallVec<[ast::ExprField; 1]> {
@@ -1231,7 +1297,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 .make_expr_fields();
         }
 
-        assign_id!(self, &mut field.id, || noop_flat_map_expr_field(field, self))
+        assign_id!(self, &mut field.id, &field.attrs, || noop_flat_map_expr_field(field, self))
     }
 
     fn flat_map_pat_field(&mut self, fp: ast::PatField) -> SmallVec<[ast::PatField; 1]> {
@@ -1243,7 +1309,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 .make_pat_fields();
         }
 
-        assign_id!(self, &mut fp.id, || noop_flat_map_pat_field(fp, self))
+        assign_id!(self, &mut fp.id, &fp.attrs, || noop_flat_map_pat_field(fp, self))
     }
 
     fn flat_map_param(&mut self, p: ast::Param) -> SmallVec<[ast::Param; 1]> {
@@ -1255,7 +1321,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 .make_params();
         }
 
-        assign_id!(self, &mut p.id, || noop_flat_map_param(p, self))
+        assign_id!(self, &mut p.id, &p.attrs, || noop_flat_map_param(p, self))
     }
 
     fn flat_map_field_def(&mut self, sf: ast::FieldDef) -> SmallVec<[ast::FieldDef; 1]> {
@@ -1267,7 +1333,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 .make_field_defs();
         }
 
-        assign_id!(self, &mut sf.id, || noop_flat_map_field_def(sf, self))
+        assign_id!(self, &mut sf.id, &sf.attrs, || noop_flat_map_field_def(sf, self))
     }
 
     fn flat_map_variant(&mut self, variant: ast::Variant) -> SmallVec<[ast::Variant; 1]> {
@@ -1279,7 +1345,7 @@ fn visit_expr(&mut self, expr: &mut P<ast::Expr>) {
                 .make_variants();
         }
 
-        assign_id!(self, &mut variant.id, || noop_flat_map_variant(variant, self))
+        assign_id!(self, &mut variant.id, &variant.attrs, || noop_flat_map_variant(variant, self))
     }
 
     fn filter_map_expr(&mut self, expr: P<ast::Expr>) -> Option<P<ast::Expr>> {
@@ -1300,7 +1366,7 @@ fn filter_map_expr(&mut self, expr: P<ast::Expr>) -> Option<P<ast::Expr>> {
                     .make_opt_expr()
                     .map(|expr| expr.into_inner())
             } else {
-                assign_id!(self, &mut expr.id, || {
+                assign_id!(self, &mut expr.id, &expr.attrs, || {
                     Some({
                         noop_visit_expr(&mut expr, self);
                         expr
@@ -1373,7 +1439,7 @@ fn visit_pat(&mut self, pat: &mut P<ast::Pat>) {
                 // entirely due to a `#[cfg]` on the contained expression
                 noop_flat_map_stmt(stmt, self)
             }
-            _ => assign_id!(self, &mut stmt.id, || noop_flat_map_stmt(stmt, self)),
+            _ => assign_id!(self, &mut stmt.id, stmt.attrs(), || noop_flat_map_stmt(stmt, self)),
         };
         self.cx.current_expansion.is_trailing_mac = false;
         res
@@ -1483,7 +1549,8 @@ fn visit_block(&mut self, block: &mut P<Block>) {
                 let orig_dir_ownership =
                     mem::replace(&mut self.cx.current_expansion.dir_ownership, dir_ownership);
 
-                let result = assign_id!(self, &mut item.id, || noop_flat_map_item(item, self));
+                let result =
+                    assign_id!(self, &mut item.id, &item.attrs, || noop_flat_map_item(item, self));
 
                 // Restore the module info.
                 self.cx.current_expansion.dir_ownership = orig_dir_ownership;
@@ -1495,7 +1562,7 @@ fn visit_block(&mut self, block: &mut P<Block>) {
                 item.attrs = attrs;
                 // The crate root is special - don't assign an ID to it.
                 if !(matches!(item.kind, ast::ItemKind::Mod(..)) && ident == Ident::empty()) {
-                    assign_id!(self, &mut item.id, || noop_flat_map_item(item, self))
+                    assign_id!(self, &mut item.id, &item.attrs, || noop_flat_map_item(item, self))
                 } else {
                     noop_flat_map_item(item, self)
                 }
@@ -1523,7 +1590,7 @@ fn visit_block(&mut self, block: &mut P<Block>) {
                 })
             }
             _ => {
-                assign_id!(self, &mut item.id, || noop_flat_map_assoc_item(item, self))
+                assign_id!(self, &mut item.id, &item.attrs, || noop_flat_map_assoc_item(item, self))
             }
         }
     }
@@ -1548,7 +1615,7 @@ fn visit_block(&mut self, block: &mut P<Block>) {
                 })
             }
             _ => {
-                assign_id!(self, &mut item.id, || noop_flat_map_assoc_item(item, self))
+                assign_id!(self, &mut item.id, &item.attrs, || noop_flat_map_assoc_item(item, self))
             }
         }
     }
@@ -1592,10 +1659,9 @@ fn flat_map_foreign_item(
                 })
             }
             _ => {
-                assign_id!(self, &mut foreign_item.id, || noop_flat_map_foreign_item(
-                    foreign_item,
-                    self
-                ))
+                assign_id!(self, &mut foreign_item.id, &foreign_item.attrs, || {
+                    noop_flat_map_foreign_item(foreign_item, self)
+                })
             }
         }
     }
@@ -1616,7 +1682,7 @@ fn flat_map_generic_param(
                 .make_generic_params();
         }
 
-        assign_id!(self, &mut param.id, || noop_flat_map_generic_param(param, self))
+        assign_id!(self, &mut param.id, &param.attrs, || noop_flat_map_generic_param(param, self))
     }
 
     fn visit_id(&mut self, id: &mut ast::NodeId) {
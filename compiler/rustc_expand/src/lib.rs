@@ -3,8 +3,10 @@
 #![cfg_attr(bootstrap, feature(destructuring_assignment))]
 #![feature(if_let_guard)]
 #![feature(let_else)]
+#![feature(once_cell)]
 #![feature(proc_macro_diagnostic)]
 #![feature(proc_macro_internals)]
+#![feature(proc_macro_lint_levels)]
 #![feature(proc_macro_span)]
 #![feature(try_blocks)]
 #![recursion_limit = "256"]
@@ -15,6 +17,8 @@
 extern crate proc_macro as pm;
 
 mod placeholders;
+mod proc_macro_cache;
+mod proc_macro_sandbox;
 mod proc_macro_server;
 
 pub use mbe::macro_rules::compile_declarative_macro;
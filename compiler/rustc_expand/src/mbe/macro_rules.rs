@@ -285,6 +285,7 @@ fn generic_extension<'cx>(
                         return DummyResult::any(arm_span);
                     }
                 };
+                cx.record_produced_tokens(&tts);
 
                 // Replace all the tokens for the corresponding positions in the macro, to maintain
                 // proper positions in error reporting, while maintaining the macro_backtrace.
@@ -1,20 +1,56 @@
 use crate::base::{self, *};
+use crate::proc_macro_cache::CacheEntry;
+use crate::proc_macro_sandbox;
 use crate::proc_macro_server;
 
 use rustc_ast as ast;
 use rustc_ast::ptr::P;
 use rustc_ast::token;
 use rustc_ast::tokenstream::{CanSynthesizeMissingTokens, TokenStream, TokenTree};
+use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::ErrorReported;
 use rustc_parse::nt_to_tokenstream;
 use rustc_parse::parser::ForceCollect;
-use rustc_span::{Span, DUMMY_SP};
+use rustc_span::{Span, Symbol, DUMMY_SP};
 
-const EXEC_STRATEGY: pm::bridge::server::SameThread = pm::bridge::server::SameThread;
+/// Identifies a proc macro declared `#[proc_macro_pure]`, whose output only depends
+/// on its input, so that `-Z cache-proc-macros` may reuse its expansions.
+#[derive(Clone, Copy, Debug)]
+pub struct PureProcMacro {
+    pub crate_hash: Svh,
+    pub name: Symbol,
+}
+
+/// Returns the cached output for `cache` if there is one, and otherwise runs
+/// `expand` and caches its output if it succeeded without errors. The output is
+/// recorded for `-Z macro-stats` either way.
+fn expand_cached<'a>(
+    ecx: &mut ExtCtxt<'a>,
+    cache: Option<CacheEntry>,
+    expand: impl FnOnce(&mut ExtCtxt<'a>) -> Result<TokenStream, ErrorReported>,
+) -> Result<TokenStream, ErrorReported> {
+    let stream = match cache {
+        Some(cache) => match cache.load(ecx) {
+            Some(stream) => stream,
+            None => {
+                let error_count_before = ecx.sess.parse_sess.span_diagnostic.err_count();
+                let stream = expand(ecx)?;
+                if ecx.sess.parse_sess.span_diagnostic.err_count() == error_count_before {
+                    cache.store(ecx, &stream);
+                }
+                stream
+            }
+        },
+        None => expand(ecx)?,
+    };
+    ecx.record_produced_tokens(&stream);
+    Ok(stream)
+}
 
 pub struct BangProcMacro {
     pub client: pm::bridge::client::Client<fn(pm::TokenStream) -> pm::TokenStream>,
+    pub pure: Option<PureProcMacro>,
 }
 
 impl base::ProcMacro for BangProcMacro {
@@ -24,21 +60,28 @@ fn expand<'cx>(
         span: Span,
         input: TokenStream,
     ) -> Result<TokenStream, ErrorReported> {
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&EXEC_STRATEGY, server, input, proc_macro_backtrace).map_err(|e| {
-            let mut err = ecx.struct_span_err(span, "proc macro panicked");
-            if let Some(s) = e.as_str() {
-                err.help(&format!("message: {}", s));
-            }
-            err.emit();
-            ErrorReported
+        let cache = CacheEntry::new(ecx, self.pure, "bang", &[&input]);
+        expand_cached(ecx, cache, |ecx| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let (strategy, sandbox) = proc_macro_sandbox::exec_strategy(ecx, span)?;
+            let server = proc_macro_server::Rustc::new(ecx);
+            let result = self.client.run(&strategy, server, input, proc_macro_backtrace);
+            proc_macro_sandbox::report_accesses(ecx, span, sandbox);
+            result.map_err(|e| {
+                let mut err = ecx.struct_span_err(span, "proc macro panicked");
+                if let Some(s) = e.as_str() {
+                    err.help(&format!("message: {}", s));
+                }
+                err.emit();
+                ErrorReported
+            })
         })
     }
 }
 
 pub struct AttrProcMacro {
     pub client: pm::bridge::client::Client<fn(pm::TokenStream, pm::TokenStream) -> pm::TokenStream>,
+    pub pure: Option<PureProcMacro>,
 }
 
 impl base::AttrProcMacro for AttrProcMacro {
@@ -49,11 +92,15 @@ fn expand<'cx>(
         annotation: TokenStream,
         annotated: TokenStream,
     ) -> Result<TokenStream, ErrorReported> {
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client
-            .run(&EXEC_STRATEGY, server, annotation, annotated, proc_macro_backtrace)
-            .map_err(|e| {
+        let cache = CacheEntry::new(ecx, self.pure, "attr", &[&annotation, &annotated]);
+        expand_cached(ecx, cache, |ecx| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let (strategy, sandbox) = proc_macro_sandbox::exec_strategy(ecx, span)?;
+            let server = proc_macro_server::Rustc::new(ecx);
+            let result =
+                self.client.run(&strategy, server, annotation, annotated, proc_macro_backtrace);
+            proc_macro_sandbox::report_accesses(ecx, span, sandbox);
+            result.map_err(|e| {
                 let mut err = ecx.struct_span_err(span, "custom attribute panicked");
                 if let Some(s) = e.as_str() {
                     err.help(&format!("message: {}", s));
@@ -61,11 +108,13 @@ fn expand<'cx>(
                 err.emit();
                 ErrorReported
             })
+        })
     }
 }
 
 pub struct ProcMacroDerive {
     pub client: pm::bridge::client::Client<fn(pm::TokenStream) -> pm::TokenStream>,
+    pub pure: Option<PureProcMacro>,
 }
 
 impl MultiItemModifier for ProcMacroDerive {
@@ -99,18 +148,25 @@ fn expand(
             nt_to_tokenstream(&item, &ecx.sess.parse_sess, CanSynthesizeMissingTokens::No)
         };
 
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let server = proc_macro_server::Rustc::new(ecx);
-        let stream = match self.client.run(&EXEC_STRATEGY, server, input, proc_macro_backtrace) {
-            Ok(stream) => stream,
-            Err(e) => {
+        let cache = CacheEntry::new(ecx, self.pure, "derive", &[&input]);
+        let result = expand_cached(ecx, cache, |ecx| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let (strategy, sandbox) = proc_macro_sandbox::exec_strategy(ecx, span)?;
+            let server = proc_macro_server::Rustc::new(ecx);
+            let result = self.client.run(&strategy, server, input, proc_macro_backtrace);
+            proc_macro_sandbox::report_accesses(ecx, span, sandbox);
+            result.map_err(|e| {
                 let mut err = ecx.struct_span_err(span, "proc-macro derive panicked");
                 if let Some(s) = e.as_str() {
                     err.help(&format!("message: {}", s));
                 }
                 err.emit();
-                return ExpandResult::Ready(vec![]);
-            }
+                ErrorReported
+            })
+        });
+        let stream = match result {
+            Ok(stream) => stream,
+            Err(ErrorReported) => return ExpandResult::Ready(vec![]),
         };
 
         let error_count_before = ecx.sess.parse_sess.span_diagnostic.err_count();
@@ -0,0 +1,210 @@
+//! Support for `-Z cache-proc-macros`, which reuses the output of proc macros
+//! declared `#[proc_macro_pure]` across compiler sessions.
+//!
+//! Expansions are keyed by the macro's crate hash and name, the compiler
+//! version, the call site's edition and the printed input tokens, and stored
+//! as printed output tokens together with where the span of each token came
+//! from: one of the input tokens, or the invocation's call site, mixed site or
+//! definition site. On a hit, the output is re-lexed and every token gets the
+//! corresponding span of the current invocation back, syntax context included,
+//! so hygiene and `$crate` resolve as they would for a fresh expansion.
+//! Expansions with tokens whose spans have any other origin are not cached.
+
+use crate::base::ExtCtxt;
+use crate::proc_macro::PureProcMacro;
+
+use rustc_ast::token::Token;
+use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_span::{FileName, Span};
+
+use std::fs;
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::process;
+
+/// The cache entry for one invocation of a pure proc macro.
+crate struct CacheEntry {
+    path: PathBuf,
+    /// The spans of the input tokens, in the order `flatten_spans` visits them.
+    input_spans: Vec<Span>,
+    call_site: Span,
+    mixed_site: Span,
+    def_site: Span,
+}
+
+impl CacheEntry {
+    /// Returns the cache entry for an invocation of `pure` with the given inputs, or
+    /// `None` if the macro isn't pure or `-Z cache-proc-macros` wasn't passed.
+    crate fn new(
+        ecx: &ExtCtxt<'_>,
+        pure: Option<PureProcMacro>,
+        kind: &str,
+        inputs: &[&TokenStream],
+    ) -> Option<CacheEntry> {
+        let dir = ecx.sess.opts.debugging_opts.cache_proc_macros.as_ref()?;
+        let pure = pure?;
+
+        let mut input_spans = Vec::new();
+        for input in inputs {
+            flatten_spans(input, &mut input_spans);
+        }
+
+        let expn_data = ecx.current_expansion.id.expn_data();
+        let mut hasher = StableHasher::new();
+        option_env!("CFG_VERSION").unwrap_or("unknown version").hash(&mut hasher);
+        pure.crate_hash.as_u64().hash(&mut hasher);
+        pure.name.as_str().hash(&mut hasher);
+        kind.hash(&mut hasher);
+        expn_data.edition.to_string().hash(&mut hasher);
+        // Inputs that print the same can still differ in how they are split into tokens,
+        // which would make the input indices of the spans mean different things.
+        input_spans.len().hash(&mut hasher);
+        for input in inputs {
+            pprust::tts_to_string(input).hash(&mut hasher);
+        }
+        let key: Fingerprint = hasher.finish();
+
+        Some(CacheEntry {
+            path: dir.join(format!("{}.tokens", key.to_hex())),
+            input_spans,
+            call_site: ecx.with_call_site_ctxt(expn_data.call_site),
+            mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
+            def_site: ecx.with_def_site_ctxt(expn_data.def_site),
+        })
+    }
+
+    /// Returns the cached output, if there is one that can still be lexed into the
+    /// tokens it was stored from.
+    crate fn load(&self, ecx: &ExtCtxt<'_>) -> Option<TokenStream> {
+        let contents = fs::read_to_string(&self.path).ok()?;
+        let (origins, src) = contents.split_once('\n')?;
+        let spans = origins
+            .split(' ')
+            .filter(|origin| !origin.is_empty())
+            .map(|origin| match origin {
+                "c" => Some(self.call_site),
+                "m" => Some(self.mixed_site),
+                "d" => Some(self.def_site),
+                index => self.input_spans.get(index.parse::<usize>().ok()?).copied(),
+            })
+            .collect::<Option<Vec<Span>>>()?;
+
+        let stream = lex(ecx, src.to_string())?;
+        let mut spans = spans.into_iter();
+        let stream = respan(stream, &mut spans)?;
+        if spans.next().is_some() {
+            return None;
+        }
+        Some(stream)
+    }
+
+    /// Stores the output of a successful expansion, unless some of its tokens have
+    /// spans that can't be recreated in another session.
+    crate fn store(&self, ecx: &ExtCtxt<'_>, output: &TokenStream) {
+        let mut spans = Vec::new();
+        flatten_spans(output, &mut spans);
+        let input_indices: FxHashMap<Span, usize> =
+            self.input_spans.iter().enumerate().map(|(i, &span)| (span, i)).rev().collect();
+        let mut origins = String::new();
+        for span in &spans {
+            let origin = if *span == self.call_site {
+                "c".to_string()
+            } else if *span == self.mixed_site {
+                "m".to_string()
+            } else if *span == self.def_site {
+                "d".to_string()
+            } else if let Some(index) = input_indices.get(span) {
+                index.to_string()
+            } else {
+                return;
+            };
+            origins.push_str(&origin);
+            origins.push(' ');
+        }
+
+        // Printing doesn't always round-trip, e.g. for interpolated tokens, so only
+        // store the output if it lexes into as many tokens as it has.
+        let src = pprust::tts_to_string(output);
+        let mut relexed = Vec::new();
+        match lex(ecx, src.clone()) {
+            Some(stream) => flatten_spans(&stream, &mut relexed),
+            None => return,
+        }
+        if relexed.len() != spans.len() {
+            return;
+        }
+
+        // Write to a temporary file first, so that concurrent sessions sharing the cache
+        // never observe a partial entry.
+        let tmp = self.path.with_extension(format!("tmp{}", process::id()));
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&tmp, format!("{}\n{}", origins, src)))
+            .and_then(|()| fs::rename(&tmp, &self.path));
+        if let Err(e) = result {
+            ecx.sess.warn(&format!(
+                "failed to write proc macro cache entry `{}`: {}",
+                self.path.display(),
+                e
+            ));
+        }
+    }
+}
+
+/// Lexes printed tokens, returning `None` if they don't form a valid token stream.
+fn lex(ecx: &ExtCtxt<'_>, src: String) -> Option<TokenStream> {
+    let sess = &ecx.sess.parse_sess;
+    let file = sess.source_map().new_source_file(FileName::proc_macro_source_code(&src), src);
+    match rustc_parse::maybe_file_to_stream(sess, file, None) {
+        Ok((stream, unmatched)) if unmatched.is_empty() => Some(stream),
+        Ok(_) => None,
+        Err(diags) => {
+            // A corrupt entry is a cache miss, not an error in the user's code.
+            for mut diag in diags {
+                diag.cancel();
+            }
+            None
+        }
+    }
+}
+
+/// Appends the spans of the tokens of `stream` to `spans`, with the opening and closing
+/// delimiters of a group before and after the spans of its contents.
+fn flatten_spans(stream: &TokenStream, spans: &mut Vec<Span>) {
+    for tree in stream.trees() {
+        match tree {
+            TokenTree::Token(token) => spans.push(token.span),
+            TokenTree::Delimited(delim_span, _, inner) => {
+                spans.push(delim_span.open);
+                flatten_spans(&inner, spans);
+                spans.push(delim_span.close);
+            }
+        }
+    }
+}
+
+/// Gives the tokens of `stream` the spans from `spans`, in the order of `flatten_spans`.
+/// Returns `None` if there are fewer spans than tokens.
+fn respan(stream: TokenStream, spans: &mut impl Iterator<Item = Span>) -> Option<TokenStream> {
+    let mut trees = Vec::with_capacity(stream.len());
+    let mut cursor = stream.into_trees();
+    while let Some((tree, spacing)) = cursor.next_with_spacing() {
+        let tree = match tree {
+            TokenTree::Token(token) => TokenTree::Token(Token::new(token.kind, spans.next()?)),
+            TokenTree::Delimited(_, delim, inner) => {
+                let open = spans.next()?;
+                let inner = respan(inner, spans)?;
+                let close = spans.next()?;
+                TokenTree::Delimited(DelimSpan { open, close }, delim, inner)
+            }
+        };
+        trees.push((tree, spacing));
+    }
+    Some(TokenStream::new(trees))
+}
@@ -0,0 +1,543 @@
+//! Support for `-Z proc-macro-sandbox`, which restricts what procedural macros
+//! may do while they run.
+//!
+//! On Linux, a sandboxed macro runs on a thread of its own that carries a
+//! seccomp filter trapping the syscalls that open files, run programs or create
+//! sockets. The `SIGSYS` handler records each attempt and then either fails it
+//! with `EACCES` (`strict`) or performs it on the macro's behalf (`warn`).
+//! Seccomp filters apply per thread, so the compiler itself is unaffected.
+//!
+//! Each invocation claims a slot of its own, whose index the filter passes to
+//! the handler with every trap. Threads spawned by the macro inherit the filter,
+//! so their attempts are recorded for the same invocation. The handler is
+//! installed the first time a macro is sandboxed and passes any `SIGSYS` not
+//! raised by a sandbox filter on to the handler it replaced.
+//!
+//! The sandbox runs in the compiler's address space, so it guards against
+//! macros that access the environment by accident or out of habit; it is not a
+//! security boundary against code deliberately attacking the compiler.
+
+use crate::base::ExtCtxt;
+
+use rustc_data_structures::stack::get_stack_size;
+use rustc_errors::ErrorReported;
+use rustc_session::config::ProcMacroSandbox;
+use rustc_span::Span;
+
+use pm::bridge::server::MaybeCrossThread;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// An operation a sandboxed proc macro attempted.
+enum Access {
+    Open(PathBuf),
+    Exec(PathBuf),
+    Network,
+}
+
+/// Returns the execution strategy for a proc macro invoked at `span`, which
+/// installs the sandbox on the macro's thread if one was requested. The sandbox
+/// has to be passed to `report_accesses` once the macro has run.
+crate fn exec_strategy(
+    ecx: &ExtCtxt<'_>,
+    span: Span,
+) -> Result<(MaybeCrossThread, Option<Arc<imp::Sandbox>>), ErrorReported> {
+    let mode = ecx.sess.opts.debugging_opts.proc_macro_sandbox;
+    if mode == ProcMacroSandbox::Off {
+        return Ok((MaybeCrossThread { setup: None, stack_size: None }, None));
+    }
+    match imp::Sandbox::new(mode) {
+        Ok(sandbox) => {
+            let setup_sandbox = sandbox.clone();
+            let strategy = MaybeCrossThread {
+                setup: Some(Arc::new(move || setup_sandbox.setup())),
+                stack_size: get_stack_size(),
+            };
+            Ok((strategy, Some(sandbox)))
+        }
+        Err(msg) => {
+            ecx.struct_span_err(
+                span,
+                &format!("cannot run proc macro with `-Z proc-macro-sandbox={}`", mode.desc()),
+            )
+            .note(&msg)
+            .emit();
+            Err(ErrorReported)
+        }
+    }
+}
+
+/// Reports the accesses a sandboxed proc macro invoked at `span` attempted, and
+/// records the files it opened for `-Z file-access-log`.
+crate fn report_accesses(ecx: &ExtCtxt<'_>, span: Span, sandbox: Option<Arc<imp::Sandbox>>) {
+    let sandbox = match sandbox {
+        Some(sandbox) => sandbox,
+        None => return,
+    };
+    let mode = ecx.sess.opts.debugging_opts.proc_macro_sandbox;
+    let (accesses, truncated) = sandbox.take_accesses();
+    for access in &accesses {
+        let what = match access {
+            Access::Open(path) => {
+                ecx.sess.record_proc_macro_access(path);
+                format!("open `{}`", path.display())
+            }
+            Access::Exec(path) => format!("run `{}`", path.display()),
+            Access::Network => "create a network socket".to_string(),
+        };
+        let mut diag = if mode == ProcMacroSandbox::Strict {
+            let mut diag = ecx.struct_span_err(span, &format!("proc macro tried to {}", what));
+            diag.note("the access was denied by `-Z proc-macro-sandbox=strict`");
+            diag
+        } else {
+            let mut diag = ecx.struct_span_warn(span, &format!("proc macro tried to {}", what));
+            diag.note("the access was reported because of `-Z proc-macro-sandbox=warn`");
+            diag
+        };
+        diag.emit();
+    }
+    if truncated {
+        ecx.struct_span_warn(span, "proc macro made further accesses which are not shown").emit();
+    }
+    if sandbox.setup_failed() {
+        ecx.struct_span_err(span, "failed to sandbox proc macro, so it ran unrestricted").emit();
+    }
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod imp {
+    use super::Access;
+
+    use rustc_session::config::ProcMacroSandbox;
+
+    use std::cell::UnsafeCell;
+    use std::ffi::OsStr;
+    use std::io;
+    use std::lazy::SyncOnceCell;
+    use std::mem;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    // Classic BPF and seccomp constants, see `linux/filter.h` and `linux/seccomp.h`.
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_JMP_JEQ_K: u16 = 0x15;
+    const BPF_JMP_JGE_K: u16 = 0x35;
+    const BPF_RET_K: u16 = 0x06;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    const SECCOMP_RET_TRAP: u32 = 0x0003_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+
+    // Offsets into `struct seccomp_data`.
+    const DATA_NR: u32 = 0;
+    const DATA_ARCH: u32 = 4;
+    const DATA_ARG5_LOW: u32 = 16 + 5 * 8;
+
+    /// Syscalls made by the `SIGSYS` handler in `warn` mode pass this value as their
+    /// (otherwise unused) sixth argument so that the filter lets them through.
+    const BYPASS: u32 = 0x5ec0_3b1d;
+
+    /// The kinds of trapped syscalls, which decide how an attempt is reported.
+    #[derive(Clone, Copy)]
+    enum Kind {
+        /// Opens the path in the given argument.
+        Open(usize),
+        /// Runs the program at the path in the given argument.
+        Exec(usize),
+        Network,
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    mod arch {
+        use super::Kind::{self, *};
+
+        pub(super) const AUDIT_ARCH: u32 = 0xc000_003e;
+        /// Syscall numbers at or above this belong to the x32 ABI.
+        pub(super) const FOREIGN_NR: u32 = 0x4000_0000;
+        pub(super) const TRAPPED: &[(u32, Kind)] = &[
+            (2, Open(0)),   // open
+            (85, Open(0)),  // creat
+            (257, Open(1)), // openat
+            (437, Open(1)), // openat2
+            (59, Exec(0)),  // execve
+            (322, Exec(1)), // execveat
+            (41, Network),  // socket
+            (42, Network),  // connect
+            (425, Network), // io_uring_setup, which could open files behind the filter's back
+        ];
+
+        pub(super) unsafe fn syscall(ctx: &libc::ucontext_t) -> (libc::c_long, [libc::c_long; 5]) {
+            let regs = &ctx.uc_mcontext.gregs;
+            let arg = |reg: libc::c_int| regs[reg as usize] as libc::c_long;
+            (
+                arg(libc::REG_RAX),
+                [
+                    arg(libc::REG_RDI),
+                    arg(libc::REG_RSI),
+                    arg(libc::REG_RDX),
+                    arg(libc::REG_R10),
+                    arg(libc::REG_R8),
+                ],
+            )
+        }
+
+        pub(super) unsafe fn set_return(ctx: &mut libc::ucontext_t, ret: libc::c_long) {
+            ctx.uc_mcontext.gregs[libc::REG_RAX as usize] = ret as libc::greg_t;
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    mod arch {
+        use super::Kind::{self, *};
+
+        pub(super) const AUDIT_ARCH: u32 = 0xc000_00b7;
+        /// aarch64 has no second syscall ABI to guard against.
+        pub(super) const FOREIGN_NR: u32 = u32::MAX;
+        pub(super) const TRAPPED: &[(u32, Kind)] = &[
+            (56, Open(1)),  // openat
+            (437, Open(1)), // openat2
+            (221, Exec(0)), // execve
+            (281, Exec(1)), // execveat
+            (198, Network), // socket
+            (203, Network), // connect
+            (425, Network), // io_uring_setup, which could open files behind the filter's back
+        ];
+
+        pub(super) unsafe fn syscall(ctx: &libc::ucontext_t) -> (libc::c_long, [libc::c_long; 5]) {
+            let regs = &ctx.uc_mcontext.regs;
+            let arg = |i: usize| regs[i] as libc::c_long;
+            (arg(8), [arg(0), arg(1), arg(2), arg(3), arg(4)])
+        }
+
+        pub(super) unsafe fn set_return(ctx: &mut libc::ucontext_t, ret: libc::c_long) {
+            ctx.uc_mcontext.regs[0] = ret as libc::c_ulong;
+        }
+    }
+
+    const MAX_RECORDS: usize = 64;
+    const MAX_PATH: usize = 256;
+    /// How many proc macros can be sandboxed at the same time, e.g. by several sessions.
+    const MAX_SANDBOXES: usize = 16;
+
+    /// The filter of the sandbox in slot `i` returns `SECCOMP_RET_TRAP | (SLOT_TAG + i)`,
+    /// which the kernel passes to the handler as the signal's `si_errno`.
+    const SLOT_TAG: u32 = 0x5b00;
+    /// The `si_code` of a `SIGSYS` raised by a seccomp filter.
+    const SYS_SECCOMP: libc::c_int = 1;
+
+    #[derive(Clone, Copy)]
+    struct Record {
+        kind: Kind,
+        len: usize,
+        path: [u8; MAX_PATH],
+    }
+
+    const EMPTY_RECORD: Record = Record { kind: Kind::Network, len: 0, path: [0; MAX_PATH] };
+
+    /// The state of one sandboxed proc macro invocation, which the `SIGSYS` handler finds
+    /// through the tag of the trap.
+    struct Slot {
+        in_use: AtomicBool,
+        warn: AtomicBool,
+        setup_failed: AtomicBool,
+        record_count: AtomicUsize,
+        // Only written by the `SIGSYS` handler on the macro's threads, each record at
+        // most once as claimed through `record_count`, and only read after the macro
+        // has returned. Threads the macro leaves running afterwards are not supported.
+        records: UnsafeCell<[Record; MAX_RECORDS]>,
+    }
+
+    // SAFETY: `records` is only accessed as described above.
+    unsafe impl Sync for Slot {}
+
+    const EMPTY_SLOT: Slot = Slot {
+        in_use: AtomicBool::new(false),
+        warn: AtomicBool::new(false),
+        setup_failed: AtomicBool::new(false),
+        record_count: AtomicUsize::new(0),
+        records: UnsafeCell::new([EMPTY_RECORD; MAX_RECORDS]),
+    };
+
+    static SLOTS: [Slot; MAX_SANDBOXES] = [EMPTY_SLOT; MAX_SANDBOXES];
+
+    /// The `SIGSYS` action that was in place before the handler was installed, which
+    /// signals not raised by a sandbox filter are passed on to.
+    static PREVIOUS_ACTION: SyncOnceCell<Result<libc::sigaction, String>> = SyncOnceCell::new();
+
+    /// The sandbox of one proc macro invocation, which owns a slot until it is dropped.
+    crate struct Sandbox {
+        slot: usize,
+    }
+
+    impl Sandbox {
+        /// Checks once that the sandbox can be installed and installs the `SIGSYS`
+        /// handler, then claims a slot for the returned sandbox.
+        pub(super) fn new(mode: ProcMacroSandbox) -> Result<Arc<Sandbox>, String> {
+            static SUPPORTED: SyncOnceCell<Result<(), String>> = SyncOnceCell::new();
+
+            SUPPORTED
+                .get_or_init(|| {
+                    // Installing the filter on a throwaway thread leaves this one
+                    // unrestricted.
+                    match thread::spawn(move || install_filter(false, 0)).join() {
+                        Ok(Ok(())) => Ok(()),
+                        Ok(Err(e)) => Err(format!("cannot install a seccomp filter: {}", e)),
+                        Err(_) => Err("cannot install a seccomp filter".to_string()),
+                    }
+                })
+                .clone()?;
+            PREVIOUS_ACTION
+                .get_or_init(|| {
+                    install_handler()
+                        .map_err(|e| format!("cannot install a `SIGSYS` handler: {}", e))
+                })
+                .as_ref()
+                .map_err(|e| e.clone())?;
+
+            let slot = SLOTS
+                .iter()
+                .position(|slot| {
+                    slot.in_use
+                        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                        .is_ok()
+                })
+                .ok_or_else(|| {
+                    format!("no more than {} proc macros can be sandboxed at once", MAX_SANDBOXES)
+                })?;
+            SLOTS[slot].warn.store(mode == ProcMacroSandbox::Warn, Ordering::Relaxed);
+            SLOTS[slot].setup_failed.store(false, Ordering::Relaxed);
+            SLOTS[slot].record_count.store(0, Ordering::Relaxed);
+            Ok(Arc::new(Sandbox { slot }))
+        }
+
+        /// Installs the filter on the macro's thread, before the macro runs.
+        pub(super) fn setup(&self) {
+            let slot = &SLOTS[self.slot];
+            if install_filter(slot.warn.load(Ordering::Relaxed), self.slot).is_err() {
+                slot.setup_failed.store(true, Ordering::Relaxed);
+            }
+        }
+
+        pub(super) fn setup_failed(&self) -> bool {
+            SLOTS[self.slot].setup_failed.load(Ordering::Relaxed)
+        }
+
+        pub(super) fn take_accesses(&self) -> (Vec<Access>, bool) {
+            let slot = &SLOTS[self.slot];
+            let count = slot.record_count.swap(0, Ordering::Relaxed);
+            // SAFETY: the macro has returned, so the handler no longer runs for this slot.
+            let records = unsafe { &(*slot.records.get())[..count.min(MAX_RECORDS)] };
+            let accesses = records
+                .iter()
+                .map(|record| {
+                    let path = || PathBuf::from(OsStr::from_bytes(&record.path[..record.len]));
+                    match record.kind {
+                        Kind::Open(_) => Access::Open(path()),
+                        Kind::Exec(_) => Access::Exec(path()),
+                        Kind::Network => Access::Network,
+                    }
+                })
+                .collect();
+            (accesses, count > MAX_RECORDS)
+        }
+    }
+
+    impl Drop for Sandbox {
+        fn drop(&mut self) {
+            SLOTS[self.slot].in_use.store(false, Ordering::Release);
+        }
+    }
+
+    fn install_handler() -> io::Result<libc::sigaction> {
+        unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = handle_sigsys as usize;
+            action.sa_flags = libc::SA_SIGINFO;
+            libc::sigemptyset(&mut action.sa_mask);
+            let mut previous_action = mem::zeroed();
+            if libc::sigaction(libc::SIGSYS, &action, &mut previous_action) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(previous_action)
+        }
+    }
+
+    fn install_filter(warn: bool, slot: usize) -> io::Result<()> {
+        let filter = build_filter(warn, slot);
+        let prog = libc::sock_fprog {
+            len: filter.len() as libc::c_ushort,
+            filter: filter.as_ptr() as *mut libc::sock_filter,
+        };
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0
+                || libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER, &prog as *const _)
+                    != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    fn stmt(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter { code, jt: 0, jf: 0, k }
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    /// Builds a filter trapping the syscalls in `arch::TRAPPED` with the tag of `slot`.
+    /// Syscalls through a foreign ABI fail outright, since their numbers would not match
+    /// the list.
+    fn build_filter(allow_bypass: bool, slot: usize) -> Vec<libc::sock_filter> {
+        let deny = SECCOMP_RET_ERRNO | libc::EACCES as u32;
+        let trapped = arch::TRAPPED.len();
+
+        let mut filter = vec![
+            stmt(BPF_LD_W_ABS, DATA_ARCH),
+            jump(BPF_JMP_JEQ_K, arch::AUDIT_ARCH, 1, 0),
+            stmt(BPF_RET_K, deny),
+            stmt(BPF_LD_W_ABS, DATA_NR),
+            jump(BPF_JMP_JGE_K, arch::FOREIGN_NR, 0, 1),
+            stmt(BPF_RET_K, deny),
+        ];
+        // Each match skips the remaining comparisons and the `ALLOW` after them.
+        for (i, &(nr, _)) in arch::TRAPPED.iter().enumerate() {
+            filter.push(jump(BPF_JMP_JEQ_K, nr, (trapped - i) as u8, 0));
+        }
+        filter.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+        if allow_bypass {
+            filter.push(stmt(BPF_LD_W_ABS, DATA_ARG5_LOW));
+            filter.push(jump(BPF_JMP_JEQ_K, BYPASS, 0, 1));
+            filter.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+        }
+        filter.push(stmt(BPF_RET_K, SECCOMP_RET_TRAP | (SLOT_TAG + slot as u32)));
+        filter
+    }
+
+    /// Records a trapped syscall, then fails it or performs it depending on the mode.
+    /// Only async-signal-safe operations may happen in here.
+    extern "C" fn handle_sigsys(
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        ctx: *mut libc::c_void,
+    ) {
+        unsafe {
+            let tag = (*info).si_errno as u32;
+            if (*info).si_code != SYS_SECCOMP
+                || !(SLOT_TAG..SLOT_TAG + MAX_SANDBOXES as u32).contains(&tag)
+            {
+                return chain_sigsys(signal, info, ctx);
+            }
+            let sandbox = &SLOTS[(tag - SLOT_TAG) as usize];
+            let ucontext = &mut *(ctx as *mut libc::ucontext_t);
+            let (nr, args) = arch::syscall(ucontext);
+            let trapped = arch::TRAPPED.iter().find(|&&(trapped, _)| trapped as libc::c_long == nr);
+            let kind = match trapped {
+                // A thread the macro left running after its sandbox was dropped is denied
+                // everything, without a record.
+                Some(&(_, kind)) if sandbox.in_use.load(Ordering::Acquire) => kind,
+                _ => return arch::set_return(ucontext, -(libc::EACCES as libc::c_long)),
+            };
+
+            let slot = sandbox.record_count.fetch_add(1, Ordering::Relaxed);
+            if slot < MAX_RECORDS {
+                let record = &mut (*sandbox.records.get())[slot];
+                record.kind = kind;
+                record.len = 0;
+                if let Kind::Open(arg) | Kind::Exec(arg) = kind {
+                    let path = args[arg] as *const u8;
+                    while !path.is_null() && record.len < MAX_PATH {
+                        let byte = *path.add(record.len);
+                        if byte == 0 {
+                            break;
+                        }
+                        record.path[record.len] = byte;
+                        record.len += 1;
+                    }
+                }
+            }
+
+            let ret = if sandbox.warn.load(Ordering::Relaxed) {
+                let errno = libc::__errno_location();
+                let saved = *errno;
+                let ret = libc::syscall(
+                    nr,
+                    args[0],
+                    args[1],
+                    args[2],
+                    args[3],
+                    args[4],
+                    BYPASS as libc::c_long,
+                );
+                let ret = if ret == -1 { -(*errno as libc::c_long) } else { ret };
+                *errno = saved;
+                ret
+            } else {
+                -(libc::EACCES as libc::c_long)
+            };
+            arch::set_return(ucontext, ret);
+        }
+    }
+
+    /// Passes a `SIGSYS` that no sandbox raised on to the action the handler replaced.
+    unsafe fn chain_sigsys(
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        ctx: *mut libc::c_void,
+    ) {
+        let previous = match PREVIOUS_ACTION.get() {
+            Some(Ok(previous)) => previous,
+            _ => return,
+        };
+        if previous.sa_sigaction == libc::SIG_IGN {
+            return;
+        }
+        if previous.sa_sigaction == libc::SIG_DFL {
+            // The default action terminates the process, which re-raising the signal with
+            // the default action in place does once this handler returns.
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        } else if previous.sa_flags & libc::SA_SIGINFO != 0 {
+            let handler: extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) =
+                mem::transmute(previous.sa_sigaction);
+            handler(signal, info, ctx);
+        } else {
+            let handler: extern "C" fn(libc::c_int) = mem::transmute(previous.sa_sigaction);
+            handler(signal);
+        }
+    }
+}
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod imp {
+    use super::Access;
+
+    use rustc_session::config::ProcMacroSandbox;
+
+    use std::sync::Arc;
+
+    crate enum Sandbox {}
+
+    impl Sandbox {
+        pub(super) fn new(_mode: ProcMacroSandbox) -> Result<Arc<Sandbox>, String> {
+            Err("the sandbox is only supported on Linux hosts running on x86_64 or aarch64"
+                .to_string())
+        }
+
+        pub(super) fn setup(&self) {
+            match *self {}
+        }
+
+        pub(super) fn setup_failed(&self) -> bool {
+            match *self {}
+        }
+
+        pub(super) fn take_accesses(&self) -> (Vec<Access>, bool) {
+            match *self {}
+        }
+    }
+}
@@ -19,6 +19,7 @@
 use rustc_span::{BytePos, FileName, MultiSpan, Pos, RealFileName, SourceFile, Span};
 
 use pm::bridge::{server, TokenTree};
+use pm::lint_levels::LintLevel;
 use pm::{Delimiter, Level, LineColumn, Spacing};
 use std::ops::Bound;
 use std::{ascii, panic};
@@ -282,6 +283,17 @@ fn to_internal(self) -> rustc_errors::Level {
     }
 }
 
+impl FromInternal<rustc_lint_defs::Level> for LintLevel {
+    fn from_internal(level: rustc_lint_defs::Level) -> Self {
+        match level {
+            rustc_lint_defs::Level::Allow | rustc_lint_defs::Level::Expect(_) => LintLevel::Allow,
+            rustc_lint_defs::Level::Warn | rustc_lint_defs::Level::ForceWarn => LintLevel::Warn,
+            rustc_lint_defs::Level::Deny => LintLevel::Deny,
+            rustc_lint_defs::Level::Forbid => LintLevel::Forbid,
+        }
+    }
+}
+
 pub struct FreeFunctions;
 
 #[derive(Clone)]
@@ -412,6 +424,12 @@ fn track_env_var(&mut self, var: &str, value: Option<&str>) {
     fn track_path(&mut self, path: &str) {
         self.sess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
     }
+
+    fn lint_level(&mut self, lint: &str) -> Option<LintLevel> {
+        let lint_level_query = self.ecx.lint_level_query?;
+        let level = lint_level_query(lint, &self.ecx.current_expansion.lint_attrs)?;
+        Some(LintLevel::from_internal(level))
+    }
 }
 
 impl server::TokenStream for Rustc<'_, '_> {
@@ -151,8 +151,13 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, no_niche, "1.42.0", None, None),
     /// Allows using `#[omit_gdb_pretty_printer_section]`.
     (active, omit_gdb_pretty_printer_section, "1.5.0", None, None),
+    /// Allows `#[polymorphize(always)]` and `#[polymorphize(never)]`, which decide whether the
+    /// items they're on are polymorphized by `-Z polymorphize`.
+    (active, polymorphize, "1.59.0", None, None),
     /// Allows using `#[prelude_import]` on glob `use` items.
     (active, prelude_import, "1.2.0", None, None),
+    /// Allows `#[proc_macro_pure]`, declaring that a proc macro's output only depends on its input.
+    (active, proc_macro_pure, "1.59.0", None, None),
     /// Used to identify crates that contain the profiler runtime.
     (active, profiler_runtime, "1.18.0", None, None),
     /// Allows using `rustc_*` attributes (RFC 572).
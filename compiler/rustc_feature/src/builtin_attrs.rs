@@ -284,6 +284,10 @@ pub struct BuiltinAttribute {
     ungated!(
         allow, Normal, template!(List: r#"lint1, lint2, ..., /*opt*/ reason = "...""#), DuplicatesOk
     ),
+    gated!(
+        expect, Normal, template!(List: r#"lint1, lint2, ..., /*opt*/ reason = "...""#),
+        DuplicatesOk, lint_reasons, experimental!(expect)
+    ),
     ungated!(
         forbid, Normal, template!(List: r#"lint1, lint2, ..., /*opt*/ reason = "...""#), DuplicatesOk
     ),
@@ -433,6 +437,11 @@ pub struct BuiltinAttribute {
     ),
     gated!(ffi_pure, Normal, template!(Word), WarnFollowing, experimental!(ffi_pure)),
     gated!(ffi_const, Normal, template!(Word), WarnFollowing, experimental!(ffi_const)),
+    gated!(proc_macro_pure, Normal, template!(Word), WarnFollowing, experimental!(proc_macro_pure)),
+    gated!(
+        polymorphize, Normal, template!(List: "always|never"), ErrorFollowing,
+        experimental!(polymorphize)
+    ),
     gated!(
         register_attr, CrateLevel, template!(List: "attr1, attr2, ..."), DuplicatesOk,
         experimental!(register_attr),
@@ -118,9 +118,12 @@
 
 use rand::{thread_rng, RngCore};
 
+mod gc;
 #[cfg(test)]
 mod tests;
 
+pub use gc::garbage_collect_incremental_dir;
+
 const LOCK_FILE_EXT: &str = ".lock";
 const DEP_GRAPH_FILENAME: &str = "dep-graph.bin";
 const STAGING_DEP_GRAPH_FILENAME: &str = "dep-graph.part.bin";
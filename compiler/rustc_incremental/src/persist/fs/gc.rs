@@ -0,0 +1,214 @@
+//! Garbage collection of the whole incremental compilation directory, for
+//! `-Z incremental-gc`.
+//!
+//! The garbage collection in the parent module only looks at the crate
+//! directory of the current session, so the directories of crates that aren't
+//! compiled anymore, or that got a new stable crate id when their `-C metadata`
+//! changed, are never cleaned up. This removes the crate directories that
+//! haven't been used for longer than the maximum age of the policy, and then
+//! the least recently used ones until the incremental directory fits in its
+//! maximum size. Crate directories are only removed when none of their session
+//! directories is in use, the same way as in the parent module, and the crate
+//! directory of the current session is always kept.
+
+use super::{
+    extract_timestamp_from_session_dir, is_old_enough_to_be_collected,
+    is_session_directory_lock_file, lock_file_path, safe_remove_dir_all, string_to_timestamp,
+};
+use rustc_data_structures::flock;
+use rustc_session::config::IncrementalGc;
+use rustc_session::Session;
+
+use std::fs as std_fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+struct CrateDirectory {
+    path: PathBuf,
+    /// The creation time of its most recent session directory.
+    last_used: SystemTime,
+    size: u64,
+}
+
+/// Prunes the incremental compilation directory according to `policy`.
+pub fn garbage_collect_incremental_dir(sess: &Session, policy: &IncrementalGc) -> io::Result<()> {
+    debug!("garbage_collect_incremental_dir() - begin");
+
+    let incr_dir = sess.opts.incremental.as_ref().unwrap();
+    let session_directory = sess.incr_comp_session_dir();
+    let current_crate_directory = session_directory.parent().unwrap();
+
+    let mut crate_directories = vec![];
+    let mut total_size = 0;
+    for dir_entry in incr_dir.read_dir()? {
+        let dir_entry = match dir_entry {
+            Ok(dir_entry) => dir_entry,
+            _ => {
+                // Ignore any errors
+                continue;
+            }
+        };
+        if !dir_entry.file_type().map_or(false, |file_type| file_type.is_dir()) {
+            // This is something we don't know, leave it alone
+            continue;
+        }
+
+        let path = dir_entry.path();
+        let size = directory_size(&path);
+        total_size += size;
+        if path == current_crate_directory {
+            continue;
+        }
+
+        match last_used(&path) {
+            Some(last_used) => crate_directories.push(CrateDirectory { path, last_used, size }),
+            None => remove_orphaned_crate_directory(&path),
+        }
+    }
+
+    // Least recently used first, so that those are the first to go for the size limit.
+    crate_directories.sort_by_key(|crate_directory| crate_directory.last_used);
+
+    let now = SystemTime::now();
+    let mut removed = 0;
+    let mut freed = 0;
+    for crate_directory in crate_directories {
+        let expired = policy.max_age.map_or(false, |max_age| {
+            now.duration_since(crate_directory.last_used).map_or(false, |age| age > max_age)
+        });
+        let oversized = policy.max_size.map_or(false, |max_size| total_size > max_size);
+        // A compilation session may just have been started in a directory whose lock file
+        // hasn't been created yet, see `is_old_enough_to_be_collected`.
+        if !(expired || oversized) || !is_old_enough_to_be_collected(crate_directory.last_used) {
+            continue;
+        }
+
+        if remove_crate_directory(sess, &crate_directory.path) {
+            total_size -= crate_directory.size;
+            removed += 1;
+            freed += crate_directory.size;
+        }
+    }
+
+    if sess.opts.debugging_opts.incremental_info {
+        eprintln!(
+            "[incremental] garbage collection removed {} crate directories, freeing {} bytes",
+            removed, freed
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns when the crate directory was last used, or `None` if it has no session directories.
+fn last_used(crate_directory: &Path) -> Option<SystemTime> {
+    let entries = crate_directory.read_dir().ok()?;
+    entries
+        .filter_map(|dir_entry| dir_entry.ok())
+        .filter_map(|dir_entry| {
+            extract_timestamp_from_session_dir(&dir_entry.file_name().to_string_lossy()).ok()
+        })
+        .max()
+}
+
+/// Returns the total size of the files in a directory, ignoring the ones that can't be read.
+fn directory_size(path: &Path) -> u64 {
+    let entries = match path.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    entries
+        .filter_map(|dir_entry| dir_entry.ok())
+        .map(|dir_entry| match dir_entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&dir_entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Removes a crate directory if none of its session directories is in use, returning whether
+/// it was removed.
+fn remove_crate_directory(sess: &Session, crate_directory: &Path) -> bool {
+    let entries = match crate_directory.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    // Hold on to an exclusive lock on every session directory until the crate directory is
+    // removed, so that no compilation session starts copying from one of them meanwhile.
+    let mut locks = vec![];
+    for dir_entry in entries.filter_map(|dir_entry| dir_entry.ok()) {
+        let directory_name = dir_entry.file_name();
+        if extract_timestamp_from_session_dir(&directory_name.to_string_lossy()).is_err() {
+            continue;
+        }
+        let lock_file_path = lock_file_path(&dir_entry.path());
+        if !lock_file_path.exists() {
+            // Session directories without a lock file aren't in use.
+            continue;
+        }
+        match flock::Lock::new(
+            &lock_file_path,
+            false, // don't wait
+            false, // don't create the lock-file
+            true,  // get an exclusive lock
+        ) {
+            Ok(lock) => locks.push(lock),
+            Err(_) => {
+                debug!(
+                    "garbage_collect_incremental_dir() - not collecting `{}`, still in use",
+                    crate_directory.display()
+                );
+                return false;
+            }
+        }
+    }
+
+    debug!("garbage_collect_incremental_dir() - deleting `{}`", crate_directory.display());
+    let result = safe_remove_dir_all(crate_directory);
+    if let Err(err) = &result {
+        sess.warn(&format!(
+            "Failed to garbage collect incremental compilation crate directory `{}`: {}",
+            crate_directory.display(),
+            err
+        ));
+    }
+
+    // Let's make it explicit that the file locks are released at this point,
+    // or rather, that we held on to them until here
+    drop(locks);
+
+    result.is_ok()
+}
+
+/// Removes a crate directory without session directories, along with the lock files left
+/// behind in it, unless a compilation session is about to use it.
+fn remove_orphaned_crate_directory(crate_directory: &Path) {
+    let entries = match crate_directory.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for dir_entry in entries.filter_map(|dir_entry| dir_entry.ok()) {
+        let file_name = dir_entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if !is_session_directory_lock_file(&file_name) {
+            continue;
+        }
+        // Lock files are named after the timestamp and random part of their session directory.
+        let old_enough = file_name.split('-').nth(1).map_or(false, |timestamp| {
+            string_to_timestamp(timestamp).map_or(false, is_old_enough_to_be_collected)
+        });
+        if old_enough {
+            debug!(
+                "garbage_collect_incremental_dir() - deleting orphaned lock file `{}`",
+                dir_entry.path().display()
+            );
+            let _ = std_fs::remove_file(dir_entry.path());
+        }
+    }
+
+    // Only succeeds if nothing else is left.
+    let _ = std_fs::remove_dir(crate_directory);
+}
@@ -0,0 +1,467 @@
+//! The output of `--emit analysis-json`, an index of the definitions of the crate and of the
+//! references to them, for tools like code indexers.
+//!
+//! Definitions are identified by stable ids, the hex digits of their `DefPathHash`, which
+//! stay the same across compilations and identify definitions of other crates too. Local
+//! variables are identified by the stable id of the item they are in and their local id in
+//! it, as `ITEM:LOCAL_ID`. The output is an object with the fields:
+//!
+//! * `defs`, the definitions of the crate, with their kind, path, location, type, visibility
+//!   and documentation;
+//! * `external_defs`, the definitions of other crates referenced by this one;
+//! * `locals`, the variables bound by patterns, with their type;
+//! * `refs`, the paths, method calls and field accesses referring to a definition or a local;
+//! * `imports`, the `use` items, with what they import;
+//! * `macro_refs`, the macro invocations, with the macro they invoke;
+//! * `impls`, the impl blocks, with the trait they implement and their self type;
+//! * `trait_resolutions`, the uses of a trait item that type checking could resolve to the
+//!   item of a specific impl.
+//!
+//! The format is documented in `src/doc/rustc/src/json.md`, and any incompatible change to
+//! it must bump `FORMAT_VERSION`.
+
+use crate::hir_json::{object, span_json};
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc_hir::HirId;
+use rustc_middle::hir::map::Map;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, DefIdTree, Instance, TyCtxt, TypeFoldable};
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{OutputFilenames, OutputType};
+use rustc_span::hygiene::ExpnKind;
+use rustc_span::symbol::{kw, Ident};
+use rustc_span::Span;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The version of the format described in the module documentation.
+const FORMAT_VERSION: u32 = 1;
+
+/// Writes the analysis of the crate for `--emit analysis-json`.
+pub fn write_analysis_json(tcx: TyCtxt<'_>, outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::AnalysisJson);
+    if let Err(e) = emit_analysis_json(tcx, &path) {
+        tcx.sess.err(&format!("could not emit analysis as JSON to `{}`: {}", path.display(), e));
+        tcx.sess.abort_if_errors();
+    }
+    tcx.sess.record_artifact(&path, OutputType::AnalysisJson.shorthand());
+}
+
+fn emit_analysis_json(tcx: TyCtxt<'_>, path: &Path) -> io::Result<()> {
+    let json = tcx.sess.time("analysis_json", || {
+        with_no_trimmed_paths(|| {
+            let mut collector = AnalysisCollector {
+                tcx,
+                maybe_typeck_results: None,
+                defs: Vec::new(),
+                external_defs: BTreeMap::new(),
+                locals: Vec::new(),
+                refs: Vec::new(),
+                imports: Vec::new(),
+                macro_refs: Vec::new(),
+                seen_macro_calls: FxHashSet::default(),
+                impls: Vec::new(),
+                trait_resolutions: Vec::new(),
+            };
+            tcx.hir().walk_toplevel_module(&mut collector);
+            let external_defs = collector
+                .external_defs
+                .iter()
+                .map(|(id, &def_id)| {
+                    object(vec![
+                        ("id", id.to_json()),
+                        ("crate", tcx.crate_name(def_id.krate).to_string().to_json()),
+                        ("kind", tcx.def_kind(def_id).descr(def_id).to_json()),
+                        ("path", tcx.def_path_str(def_id).to_json()),
+                    ])
+                })
+                .collect();
+            object(vec![
+                ("format_version", FORMAT_VERSION.to_json()),
+                ("crate_name", tcx.crate_name(LOCAL_CRATE).to_string().to_json()),
+                ("defs", Json::Array(collector.defs)),
+                ("external_defs", Json::Array(external_defs)),
+                ("locals", Json::Array(collector.locals)),
+                ("refs", Json::Array(collector.refs)),
+                ("imports", Json::Array(collector.imports)),
+                ("macro_refs", Json::Array(collector.macro_refs)),
+                ("impls", Json::Array(collector.impls)),
+                ("trait_resolutions", Json::Array(collector.trait_resolutions)),
+            ])
+        })
+    });
+
+    let mut file = io::BufWriter::new(File::create(path)?);
+    writeln!(file, "{}", json)?;
+    file.flush()
+}
+
+/// The stable id of a definition, the same across compilations.
+fn stable_id(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    let (hi, lo) = tcx.def_path_hash(def_id).0.as_value();
+    format!("{:016x}{:016x}", hi, lo)
+}
+
+/// The stable id of a local variable, made of that of the item it is in and its local id.
+fn local_id(tcx: TyCtxt<'_>, hir_id: HirId) -> String {
+    format!("{}:{}", stable_id(tcx, hir_id.owner.to_def_id()), hir_id.local_id.as_u32())
+}
+
+struct AnalysisCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The results of type checking the body being visited, if any.
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    defs: Vec<Json>,
+    /// The definitions of other crates referenced so far, by stable id.
+    external_defs: BTreeMap<String, DefId>,
+    locals: Vec<Json>,
+    refs: Vec<Json>,
+    imports: Vec<Json>,
+    macro_refs: Vec<Json>,
+    /// The call sites and macros of the invocations recorded in `macro_refs`, as the nodes
+    /// expanded from one invocation all lead back to it.
+    seen_macro_calls: FxHashSet<(Span, DefId)>,
+    impls: Vec<Json>,
+    trait_resolutions: Vec<Json>,
+}
+
+impl<'tcx> AnalysisCollector<'tcx> {
+    fn def_id_json(&mut self, def_id: DefId) -> Json {
+        let id = stable_id(self.tcx, def_id);
+        if !def_id.is_local() {
+            self.external_defs.insert(id.clone(), def_id);
+        }
+        id.to_json()
+    }
+
+    fn record_def(&mut self, def_id: DefId, ident: Ident, span: Span) {
+        let tcx = self.tcx;
+        let def_kind = tcx.def_kind(def_id);
+        // Impl blocks have no name.
+        let (name, name_span) = if ident.name == kw::Empty {
+            (Json::Null, Json::Null)
+        } else {
+            (ident.to_string().to_json(), span_json(tcx.sess, ident.span))
+        };
+        let ty = match def_kind {
+            DefKind::Fn | DefKind::AssocFn => Some(tcx.fn_sig(def_id).to_string()),
+            DefKind::Const
+            | DefKind::AssocConst
+            | DefKind::Static
+            | DefKind::Field
+            | DefKind::TyAlias
+            | DefKind::Struct
+            | DefKind::Enum
+            | DefKind::Union => Some(tcx.type_of(def_id).to_string()),
+            _ => None,
+        };
+        // Items of trait impls inherit the visibility of the trait, and impl blocks have none,
+        // so they aren't in the table of the resolver.
+        let visibility = match tcx.resolutions(()).visibilities.get(&def_id.expect_local()) {
+            Some(ty::Visibility::Public) => "pub".to_json(),
+            Some(&ty::Visibility::Restricted(module)) => stable_id(tcx, module).to_json(),
+            Some(ty::Visibility::Invisible) | None => Json::Null,
+        };
+        let docs: Vec<String> = tcx
+            .get_attrs(def_id)
+            .iter()
+            .filter_map(|attr| attr.doc_str())
+            .map(|doc| doc.to_string())
+            .collect();
+        let docs = if docs.is_empty() { Json::Null } else { docs.join("\n").to_json() };
+        let def = object(vec![
+            ("id", stable_id(tcx, def_id).to_json()),
+            ("kind", def_kind.descr(def_id).to_json()),
+            ("name", name),
+            ("path", tcx.def_path_str(def_id).to_json()),
+            ("span", span_json(tcx.sess, span)),
+            ("name_span", name_span),
+            ("parent", tcx.parent(def_id).map(|parent| stable_id(tcx, parent)).to_json()),
+            ("type", ty.to_json()),
+            ("visibility", visibility),
+            ("docs", docs),
+        ]);
+        self.defs.push(def);
+    }
+
+    fn record_import(&mut self, item: &hir::Item<'_>, path: &hir::Path<'_>, kind: hir::UseKind) {
+        let tcx = self.tcx;
+        let (kind, name) = match kind {
+            hir::UseKind::Single => ("single", item.ident.to_string().to_json()),
+            hir::UseKind::Glob => ("glob", Json::Null),
+            // The stem of `use a::{b, c}` is lowered to an item of its own, next to the items
+            // of `b` and `c`, and imports nothing.
+            hir::UseKind::ListStem => return,
+        };
+        let def = match path.res.opt_def_id() {
+            Some(def_id) => self.def_id_json(def_id),
+            None => Json::Null,
+        };
+        let parent = tcx.parent(item.def_id.to_def_id()).map(|parent| stable_id(tcx, parent));
+        let import = object(vec![
+            ("kind", kind.to_json()),
+            ("name", name),
+            ("span", span_json(tcx.sess, item.span)),
+            ("parent", parent.to_json()),
+            ("def", def),
+        ]);
+        self.imports.push(import);
+    }
+
+    /// Records the macro invocations `span` was expanded from, once per invocation.
+    fn record_macro_refs(&mut self, span: Span) {
+        if !span.from_expansion() {
+            return;
+        }
+        for expn_data in span.macro_backtrace() {
+            let def_id = match (&expn_data.kind, expn_data.macro_def_id) {
+                (ExpnKind::Macro(..), Some(def_id)) => def_id,
+                _ => continue,
+            };
+            if !self.seen_macro_calls.insert((expn_data.call_site, def_id)) {
+                continue;
+            }
+            let macro_ref = object(vec![
+                ("span", span_json(self.tcx.sess, expn_data.call_site)),
+                ("def", self.def_id_json(def_id)),
+            ]);
+            self.macro_refs.push(macro_ref);
+        }
+    }
+
+    fn record_ref(&mut self, kind: &str, span: Span, res: Res) {
+        let target = match res {
+            Res::Local(hir_id) => ("local", local_id(self.tcx, hir_id).to_json()),
+            _ => match res.opt_def_id() {
+                Some(def_id) => ("def", self.def_id_json(def_id)),
+                None => return,
+            },
+        };
+        let span = span_json(self.tcx.sess, span);
+        self.refs.push(object(vec![("kind", kind.to_json()), ("span", span), target]));
+    }
+
+    /// Records a reference resolved by type checking, like a method call, and the impl item
+    /// it resolves to if it refers to a trait item.
+    fn record_type_dependent_ref(&mut self, kind: &str, hir_id: HirId, span: Span) {
+        let typeck_results = match self.maybe_typeck_results {
+            Some(typeck_results) => typeck_results,
+            None => return,
+        };
+        let def_id = match typeck_results.type_dependent_def_id(hir_id) {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        let def_kind = self.tcx.def_kind(def_id);
+        self.record_ref(kind, span, Res::Def(def_kind, def_id));
+        self.record_trait_resolution(hir_id, span, def_id);
+    }
+
+    fn record_trait_resolution(&mut self, hir_id: HirId, span: Span, def_id: DefId) {
+        let tcx = self.tcx;
+        let typeck_results = match self.maybe_typeck_results {
+            Some(typeck_results) => typeck_results,
+            None => return,
+        };
+        if tcx.trait_of_item(def_id).is_none() {
+            return;
+        }
+        let substs = match typeck_results.node_substs_opt(hir_id) {
+            Some(substs) if !substs.needs_infer() && !substs.references_error() => substs,
+            _ => return,
+        };
+        let param_env = tcx.param_env_reveal_all_normalized(hir_id.owner.to_def_id());
+        let substs = match tcx.try_normalize_erasing_regions(param_env, substs) {
+            Ok(substs) => substs,
+            Err(_) => return,
+        };
+        let instance = match Instance::resolve(tcx, param_env, def_id, substs) {
+            Ok(Some(instance)) if instance.def_id() != def_id => instance,
+            _ => return,
+        };
+        let resolution = object(vec![
+            ("span", span_json(tcx.sess, span)),
+            ("trait_item", self.def_id_json(def_id)),
+            ("impl_item", self.def_id_json(instance.def_id())),
+        ]);
+        self.trait_resolutions.push(resolution);
+    }
+
+    fn record_field_ref(&mut self, hir_id: HirId, span: Span, variant: &ty::VariantDef) {
+        let typeck_results = match self.maybe_typeck_results {
+            Some(typeck_results) => typeck_results,
+            None => return,
+        };
+        if let Some(&index) = typeck_results.field_indices().get(hir_id) {
+            let def_id = variant.fields[index].did;
+            self.record_ref("field", span, Res::Def(DefKind::Field, def_id));
+        }
+    }
+
+    fn record_impl(&mut self, item: &hir::Item<'_>) {
+        let tcx = self.tcx;
+        let def_id = item.def_id.to_def_id();
+        let trait_def_id = tcx.impl_trait_ref(def_id).map(|trait_ref| trait_ref.def_id);
+        let impl_ = object(vec![
+            ("id", stable_id(tcx, def_id).to_json()),
+            ("trait", trait_def_id.map(|def_id| self.def_id_json(def_id)).unwrap_or(Json::Null)),
+            ("self_ty", tcx.type_of(def_id).to_string().to_json()),
+        ]);
+        self.impls.push(impl_);
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for AnalysisCollector<'tcx> {
+    type Map = Map<'tcx>;
+
+    fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+        NestedVisitorMap::All(self.tcx.hir())
+    }
+
+    fn visit_nested_body(&mut self, body_id: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body_id));
+        let body = self.tcx.hir().body(body_id);
+        self.visit_body(body);
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        self.record_macro_refs(item.span);
+        match item.kind {
+            hir::ItemKind::Use(path, kind) => self.record_import(item, path, kind),
+            hir::ItemKind::GlobalAsm(..) => {}
+            hir::ItemKind::Impl(..) => {
+                self.record_def(item.def_id.to_def_id(), item.ident, item.span);
+                self.record_impl(item);
+            }
+            _ => self.record_def(item.def_id.to_def_id(), item.ident, item.span),
+        }
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, item: &'tcx hir::TraitItem<'tcx>) {
+        self.record_def(item.def_id.to_def_id(), item.ident, item.span);
+        intravisit::walk_trait_item(self, item);
+    }
+
+    fn visit_impl_item(&mut self, item: &'tcx hir::ImplItem<'tcx>) {
+        self.record_def(item.def_id.to_def_id(), item.ident, item.span);
+        intravisit::walk_impl_item(self, item);
+    }
+
+    fn visit_foreign_item(&mut self, item: &'tcx hir::ForeignItem<'tcx>) {
+        self.record_def(item.def_id.to_def_id(), item.ident, item.span);
+        intravisit::walk_foreign_item(self, item);
+    }
+
+    fn visit_variant(
+        &mut self,
+        variant: &'tcx hir::Variant<'tcx>,
+        generics: &'tcx hir::Generics<'tcx>,
+        item_id: HirId,
+    ) {
+        let def_id = self.tcx.hir().local_def_id(variant.id).to_def_id();
+        self.record_def(def_id, variant.ident, variant.span);
+        intravisit::walk_variant(self, variant, generics, item_id);
+    }
+
+    fn visit_field_def(&mut self, field: &'tcx hir::FieldDef<'tcx>) {
+        let def_id = self.tcx.hir().local_def_id(field.hir_id).to_def_id();
+        self.record_def(def_id, field.ident, field.span);
+        intravisit::walk_field_def(self, field);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'tcx hir::Stmt<'tcx>) {
+        self.record_macro_refs(stmt.span);
+        intravisit::walk_stmt(self, stmt);
+    }
+
+    fn visit_ty(&mut self, ty: &'tcx hir::Ty<'tcx>) {
+        self.record_macro_refs(ty.span);
+        intravisit::walk_ty(self, ty);
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        self.record_macro_refs(pat.span);
+        if let hir::PatKind::Binding(_, hir_id, ident, _) = pat.kind {
+            let ty = self
+                .maybe_typeck_results
+                .and_then(|typeck_results| typeck_results.node_type_opt(hir_id))
+                .map(|ty| ty.to_string());
+            let local = object(vec![
+                ("id", local_id(self.tcx, hir_id).to_json()),
+                ("name", ident.to_string().to_json()),
+                ("span", span_json(self.tcx.sess, ident.span)),
+                ("type", ty.to_json()),
+            ]);
+            self.locals.push(local);
+        }
+        intravisit::walk_pat(self, pat);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        self.record_macro_refs(expr.span);
+        match expr.kind {
+            hir::ExprKind::MethodCall(segment, ..) => {
+                self.record_type_dependent_ref("method", expr.hir_id, segment.ident.span);
+            }
+            hir::ExprKind::Field(base, ident) => {
+                let base_ty = self
+                    .maybe_typeck_results
+                    .map(|typeck_results| typeck_results.expr_ty_adjusted(base).peel_refs());
+                if let Some(&ty::Adt(adt, _)) = base_ty.map(|ty| ty.kind()) {
+                    if !adt.is_enum() {
+                        self.record_field_ref(expr.hir_id, ident.span, adt.non_enum_variant());
+                    }
+                }
+            }
+            hir::ExprKind::Struct(qpath, fields, _) => {
+                if let Some(typeck_results) = self.maybe_typeck_results {
+                    if let Some(adt) = typeck_results.expr_ty(expr).ty_adt_def() {
+                        let res = typeck_results.qpath_res(qpath, expr.hir_id);
+                        let variant = adt.variant_of_res(res);
+                        for field in fields {
+                            self.record_field_ref(field.hir_id, field.ident.span, variant);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_qpath(&mut self, qpath: &'tcx hir::QPath<'tcx>, id: HirId, span: Span) {
+        match qpath {
+            hir::QPath::Resolved(_, path) => {
+                // A path to a trait item, like `Default::default`, may still be resolved to
+                // the item of an impl.
+                if let Res::Def(DefKind::AssocFn | DefKind::AssocConst, def_id) = path.res {
+                    let span = path.segments.last().map_or(path.span, |segment| segment.ident.span);
+                    self.record_trait_resolution(id, span, def_id);
+                }
+            }
+            hir::QPath::TypeRelative(_, segment) => {
+                self.record_type_dependent_ref("path", id, segment.ident.span);
+            }
+            hir::QPath::LangItem(..) => {}
+        }
+        intravisit::walk_qpath(self, qpath, id, span);
+    }
+
+    fn visit_path_segment(&mut self, path_span: Span, segment: &'tcx hir::PathSegment<'tcx>) {
+        if let Some(res) = segment.res {
+            self.record_ref("path", segment.ident.span, res);
+        }
+        intravisit::walk_path_segment(self, path_span, segment);
+    }
+}
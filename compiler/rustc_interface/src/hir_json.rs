@@ -0,0 +1,406 @@
+//! The output of `--emit hir-json`, the HIR of the crate as a tree of JSON objects.
+//!
+//! Each node of the tree is an object with the fields:
+//!
+//! * `node`, the category of the node: `item`, `trait_item`, `impl_item`, `foreign_item`,
+//!   `variant`, `field`, `generic_param`, `param`, `local`, `block`, `stmt`, `arm`, `expr`,
+//!   `pat` or `ty`;
+//! * `kind`, for items, statements, expressions, patterns and types, the name of the variant
+//!   of the HIR enum describing the node, like `Fn` or `MethodCall`;
+//! * `hir_id`, the `HirId` of the node, as `OWNER:LOCAL_ID`;
+//! * `span`, the source location of the node, or `null` if it has none;
+//! * `children`, the nodes directly nested in this one, in the order the HIR visits them.
+//!
+//! Nodes with a definition of their own also have `def_id` and `def_path`, and nodes with a
+//! name have `name`. The format is documented in `src/doc/rustc/src/json.md`, and any
+//! incompatible change to it must bump `FORMAT_VERSION`.
+
+use rustc_hir as hir;
+use rustc_hir::def::Res;
+use rustc_hir::def_id::{DefId, CRATE_DEF_ID, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc_hir::HirId;
+use rustc_middle::hir::map::Map;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{OutputFilenames, OutputType};
+use rustc_session::Session;
+use rustc_span::symbol::Ident;
+use rustc_span::Span;
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The version of the format described in the module documentation.
+const FORMAT_VERSION: u32 = 1;
+
+/// Writes the HIR of the crate for `--emit hir-json`.
+pub fn write_hir_json(tcx: TyCtxt<'_>, outputs: &OutputFilenames) {
+    let path = outputs.path(OutputType::HirJson);
+    if let Err(e) = emit_hir_json(tcx, &path) {
+        tcx.sess.err(&format!("could not emit HIR as JSON to `{}`: {}", path.display(), e));
+        tcx.sess.abort_if_errors();
+    }
+    tcx.sess.record_artifact(&path, OutputType::HirJson.shorthand());
+}
+
+fn emit_hir_json(tcx: TyCtxt<'_>, path: &Path) -> io::Result<()> {
+    let root = tcx.sess.time("hir_json", || {
+        let mut builder = HirJsonBuilder { tcx, stack: vec![Vec::new()] };
+        let (top_mod, span, hir_id) = tcx.hir().get_module(CRATE_DEF_ID);
+        let mut fields = builder.def_fields(CRATE_DEF_ID.to_def_id());
+        fields.push(("kind", "Mod".to_json()));
+        builder.node("item", hir_id, span, fields, |this| this.visit_mod(top_mod, span, hir_id));
+        builder.stack.pop().unwrap().pop().unwrap()
+    });
+    let json = object(vec![
+        ("format_version", FORMAT_VERSION.to_json()),
+        ("crate_name", tcx.crate_name(LOCAL_CRATE).to_string().to_json()),
+        ("root", root),
+    ]);
+
+    let mut file = io::BufWriter::new(File::create(path)?);
+    writeln!(file, "{}", json)?;
+    file.flush()
+}
+
+pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+/// The source location of `span`, with 1-based lines and columns, or `null` for dummy spans.
+pub fn span_json(sess: &Session, span: Span) -> Json {
+    if span.is_dummy() {
+        return Json::Null;
+    }
+    let source_map = sess.source_map();
+    let lo = source_map.lookup_char_pos(span.lo());
+    let hi = source_map.lookup_char_pos(span.hi());
+    object(vec![
+        ("file", lo.file.name.prefer_remapped().to_string().to_json()),
+        ("line_start", lo.line.to_json()),
+        ("column_start", (lo.col.0 + 1).to_json()),
+        ("line_end", hi.line.to_json()),
+        ("column_end", (hi.col.0 + 1).to_json()),
+        ("from_expansion", span.from_expansion().to_json()),
+    ])
+}
+
+fn hir_id_json(hir_id: HirId) -> Json {
+    format!("{}:{}", hir_id.owner.local_def_index.as_u32(), hir_id.local_id.as_u32()).to_json()
+}
+
+struct HirJsonBuilder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The children of each node being built, innermost last.
+    stack: Vec<Vec<Json>>,
+}
+
+impl<'tcx> HirJsonBuilder<'tcx> {
+    /// Adds a node to the children of the current one, with the nodes pushed by `walk` as its
+    /// own children.
+    fn node(
+        &mut self,
+        node: &str,
+        hir_id: HirId,
+        span: Span,
+        mut fields: Vec<(&str, Json)>,
+        walk: impl FnOnce(&mut Self),
+    ) {
+        self.stack.push(Vec::new());
+        walk(self);
+        let children = self.stack.pop().unwrap();
+        fields.push(("node", node.to_json()));
+        fields.push(("hir_id", hir_id_json(hir_id)));
+        fields.push(("span", span_json(self.tcx.sess, span)));
+        fields.push(("children", Json::Array(children)));
+        self.stack.last_mut().unwrap().push(object(fields));
+    }
+
+    fn def_fields(&self, def_id: DefId) -> Vec<(&'static str, Json)> {
+        let def_path = with_no_trimmed_paths(|| self.tcx.def_path_str(def_id));
+        vec![
+            ("def_id", format!("{}:{}", def_id.krate.as_u32(), def_id.index.as_u32()).to_json()),
+            ("def_path", def_path.to_json()),
+        ]
+    }
+
+    fn named_def_fields(&self, def_id: DefId, ident: Ident) -> Vec<(&'static str, Json)> {
+        let mut fields = self.def_fields(def_id);
+        fields.push(("name", ident.to_string().to_json()));
+        fields
+    }
+
+    /// Describes what a path resolved to, for the `res` field of the nodes holding paths.
+    /// Paths relative to a type, like `Vec::new`, are only resolved by type checking, and
+    /// have no `res`.
+    fn res_json(&self, res: Res) -> Option<Json> {
+        if let Res::Local(hir_id) = res {
+            return Some(object(vec![("local", hir_id_json(hir_id))]));
+        }
+        let def_id = res.opt_def_id()?;
+        let mut fields = self.def_fields(def_id);
+        fields.push(("kind", res.descr().to_json()));
+        Some(object(fields))
+    }
+
+    fn qpath_res_field(&self, qpath: &hir::QPath<'_>) -> Vec<(&'static str, Json)> {
+        let res = match qpath {
+            hir::QPath::Resolved(_, path) => self.res_json(path.res),
+            hir::QPath::TypeRelative(..) | hir::QPath::LangItem(..) => None,
+        };
+        res.map(|res| ("res", res)).into_iter().collect()
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for HirJsonBuilder<'tcx> {
+    type Map = Map<'tcx>;
+
+    fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+        NestedVisitorMap::All(self.tcx.hir())
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        let mut fields = self.named_def_fields(item.def_id.to_def_id(), item.ident);
+        fields.push(("kind", item_kind_name(&item.kind).to_json()));
+        if let hir::ItemKind::Use(path, _) = item.kind {
+            fields.extend(self.res_json(path.res).map(|res| ("res", res)));
+        }
+        self.node("item", item.hir_id(), item.span, fields, |this| {
+            intravisit::walk_item(this, item)
+        });
+    }
+
+    fn visit_trait_item(&mut self, item: &'tcx hir::TraitItem<'tcx>) {
+        let mut fields = self.named_def_fields(item.def_id.to_def_id(), item.ident);
+        let kind = match item.kind {
+            hir::TraitItemKind::Const(..) => "Const",
+            hir::TraitItemKind::Fn(..) => "Fn",
+            hir::TraitItemKind::Type(..) => "Type",
+        };
+        fields.push(("kind", kind.to_json()));
+        self.node("trait_item", item.hir_id(), item.span, fields, |this| {
+            intravisit::walk_trait_item(this, item)
+        });
+    }
+
+    fn visit_impl_item(&mut self, item: &'tcx hir::ImplItem<'tcx>) {
+        let mut fields = self.named_def_fields(item.def_id.to_def_id(), item.ident);
+        let kind = match item.kind {
+            hir::ImplItemKind::Const(..) => "Const",
+            hir::ImplItemKind::Fn(..) => "Fn",
+            hir::ImplItemKind::TyAlias(..) => "TyAlias",
+        };
+        fields.push(("kind", kind.to_json()));
+        self.node("impl_item", item.hir_id(), item.span, fields, |this| {
+            intravisit::walk_impl_item(this, item)
+        });
+    }
+
+    fn visit_foreign_item(&mut self, item: &'tcx hir::ForeignItem<'tcx>) {
+        let mut fields = self.named_def_fields(item.def_id.to_def_id(), item.ident);
+        let kind = match item.kind {
+            hir::ForeignItemKind::Fn(..) => "Fn",
+            hir::ForeignItemKind::Static(..) => "Static",
+            hir::ForeignItemKind::Type => "Type",
+        };
+        fields.push(("kind", kind.to_json()));
+        self.node("foreign_item", item.hir_id(), item.span, fields, |this| {
+            intravisit::walk_foreign_item(this, item)
+        });
+    }
+
+    fn visit_variant(
+        &mut self,
+        variant: &'tcx hir::Variant<'tcx>,
+        generics: &'tcx hir::Generics<'tcx>,
+        item_id: HirId,
+    ) {
+        let def_id = self.tcx.hir().local_def_id(variant.id).to_def_id();
+        let fields = self.named_def_fields(def_id, variant.ident);
+        self.node("variant", variant.id, variant.span, fields, |this| {
+            intravisit::walk_variant(this, variant, generics, item_id)
+        });
+    }
+
+    fn visit_field_def(&mut self, field: &'tcx hir::FieldDef<'tcx>) {
+        let def_id = self.tcx.hir().local_def_id(field.hir_id).to_def_id();
+        let fields = self.named_def_fields(def_id, field.ident);
+        self.node("field", field.hir_id, field.span, fields, |this| {
+            intravisit::walk_field_def(this, field)
+        });
+    }
+
+    fn visit_generic_param(&mut self, param: &'tcx hir::GenericParam<'tcx>) {
+        let def_id = self.tcx.hir().local_def_id(param.hir_id).to_def_id();
+        let fields = self.named_def_fields(def_id, param.name.ident());
+        self.node("generic_param", param.hir_id, param.span, fields, |this| {
+            intravisit::walk_generic_param(this, param)
+        });
+    }
+
+    fn visit_param(&mut self, param: &'tcx hir::Param<'tcx>) {
+        self.node("param", param.hir_id, param.span, vec![], |this| {
+            intravisit::walk_param(this, param)
+        });
+    }
+
+    fn visit_local(&mut self, local: &'tcx hir::Local<'tcx>) {
+        self.node("local", local.hir_id, local.span, vec![], |this| {
+            intravisit::walk_local(this, local)
+        });
+    }
+
+    fn visit_block(&mut self, block: &'tcx hir::Block<'tcx>) {
+        self.node("block", block.hir_id, block.span, vec![], |this| {
+            intravisit::walk_block(this, block)
+        });
+    }
+
+    fn visit_stmt(&mut self, stmt: &'tcx hir::Stmt<'tcx>) {
+        let kind = match stmt.kind {
+            hir::StmtKind::Local(..) => "Local",
+            hir::StmtKind::Item(..) => "Item",
+            hir::StmtKind::Expr(..) => "Expr",
+            hir::StmtKind::Semi(..) => "Semi",
+        };
+        self.node("stmt", stmt.hir_id, stmt.span, vec![("kind", kind.to_json())], |this| {
+            intravisit::walk_stmt(this, stmt)
+        });
+    }
+
+    fn visit_arm(&mut self, arm: &'tcx hir::Arm<'tcx>) {
+        self.node("arm", arm.hir_id, arm.span, vec![], |this| intravisit::walk_arm(this, arm));
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        let mut fields = match expr.kind {
+            hir::ExprKind::Closure(..) => {
+                self.def_fields(self.tcx.hir().local_def_id(expr.hir_id).to_def_id())
+            }
+            hir::ExprKind::MethodCall(segment, ..) => {
+                vec![("name", segment.ident.to_string().to_json())]
+            }
+            hir::ExprKind::Field(_, ident) => vec![("name", ident.to_string().to_json())],
+            hir::ExprKind::Binary(op, ..) | hir::ExprKind::AssignOp(op, ..) => {
+                vec![("op", op.node.as_str().to_json())]
+            }
+            hir::ExprKind::Unary(op, _) => vec![("op", op.as_str().to_json())],
+            hir::ExprKind::Lit(_) => {
+                let source_map = self.tcx.sess.source_map();
+                let value = source_map.span_to_snippet(expr.span).ok();
+                vec![("value", value.to_json())]
+            }
+            hir::ExprKind::Path(ref qpath) => self.qpath_res_field(qpath),
+            hir::ExprKind::Struct(qpath, ..) => self.qpath_res_field(qpath),
+            _ => vec![],
+        };
+        fields.push(("kind", expr_kind_name(&expr.kind).to_json()));
+        self.node("expr", expr.hir_id, expr.span, fields, |this| intravisit::walk_expr(this, expr));
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        let (kind, mut fields) = match pat.kind {
+            hir::PatKind::Wild => ("Wild", vec![]),
+            hir::PatKind::Binding(_, _, ident, _) => {
+                ("Binding", vec![("name", ident.to_string().to_json())])
+            }
+            hir::PatKind::Struct(ref qpath, ..) => ("Struct", self.qpath_res_field(qpath)),
+            hir::PatKind::TupleStruct(ref qpath, ..) => {
+                ("TupleStruct", self.qpath_res_field(qpath))
+            }
+            hir::PatKind::Or(..) => ("Or", vec![]),
+            hir::PatKind::Path(ref qpath) => ("Path", self.qpath_res_field(qpath)),
+            hir::PatKind::Tuple(..) => ("Tuple", vec![]),
+            hir::PatKind::Box(..) => ("Box", vec![]),
+            hir::PatKind::Ref(..) => ("Ref", vec![]),
+            hir::PatKind::Lit(..) => ("Lit", vec![]),
+            hir::PatKind::Range(..) => ("Range", vec![]),
+            hir::PatKind::Slice(..) => ("Slice", vec![]),
+        };
+        fields.push(("kind", kind.to_json()));
+        self.node("pat", pat.hir_id, pat.span, fields, |this| intravisit::walk_pat(this, pat));
+    }
+
+    fn visit_ty(&mut self, ty: &'tcx hir::Ty<'tcx>) {
+        let (kind, mut fields) = match ty.kind {
+            hir::TyKind::Slice(..) => ("Slice", vec![]),
+            hir::TyKind::Array(..) => ("Array", vec![]),
+            hir::TyKind::Ptr(..) => ("Ptr", vec![]),
+            hir::TyKind::Rptr(..) => ("Rptr", vec![]),
+            hir::TyKind::BareFn(..) => ("BareFn", vec![]),
+            hir::TyKind::Never => ("Never", vec![]),
+            hir::TyKind::Tup(..) => ("Tup", vec![]),
+            hir::TyKind::Path(ref qpath) => ("Path", self.qpath_res_field(qpath)),
+            hir::TyKind::OpaqueDef(..) => ("OpaqueDef", vec![]),
+            hir::TyKind::TraitObject(..) => ("TraitObject", vec![]),
+            hir::TyKind::Typeof(..) => ("Typeof", vec![]),
+            hir::TyKind::Infer => ("Infer", vec![]),
+            hir::TyKind::Err => ("Err", vec![]),
+        };
+        fields.push(("kind", kind.to_json()));
+        self.node("ty", ty.hir_id, ty.span, fields, |this| intravisit::walk_ty(this, ty));
+    }
+}
+
+fn item_kind_name(kind: &hir::ItemKind<'_>) -> &'static str {
+    match kind {
+        hir::ItemKind::ExternCrate(..) => "ExternCrate",
+        hir::ItemKind::Use(..) => "Use",
+        hir::ItemKind::Static(..) => "Static",
+        hir::ItemKind::Const(..) => "Const",
+        hir::ItemKind::Fn(..) => "Fn",
+        hir::ItemKind::Macro(..) => "Macro",
+        hir::ItemKind::Mod(..) => "Mod",
+        hir::ItemKind::ForeignMod { .. } => "ForeignMod",
+        hir::ItemKind::GlobalAsm(..) => "GlobalAsm",
+        hir::ItemKind::TyAlias(..) => "TyAlias",
+        hir::ItemKind::OpaqueTy(..) => "OpaqueTy",
+        hir::ItemKind::Enum(..) => "Enum",
+        hir::ItemKind::Struct(..) => "Struct",
+        hir::ItemKind::Union(..) => "Union",
+        hir::ItemKind::Trait(..) => "Trait",
+        hir::ItemKind::TraitAlias(..) => "TraitAlias",
+        hir::ItemKind::Impl(..) => "Impl",
+    }
+}
+
+fn expr_kind_name(kind: &hir::ExprKind<'_>) -> &'static str {
+    match kind {
+        hir::ExprKind::Box(..) => "Box",
+        hir::ExprKind::ConstBlock(..) => "ConstBlock",
+        hir::ExprKind::Array(..) => "Array",
+        hir::ExprKind::Call(..) => "Call",
+        hir::ExprKind::MethodCall(..) => "MethodCall",
+        hir::ExprKind::Tup(..) => "Tup",
+        hir::ExprKind::Binary(..) => "Binary",
+        hir::ExprKind::Unary(..) => "Unary",
+        hir::ExprKind::Lit(..) => "Lit",
+        hir::ExprKind::Cast(..) => "Cast",
+        hir::ExprKind::Type(..) => "Type",
+        hir::ExprKind::DropTemps(..) => "DropTemps",
+        hir::ExprKind::Let(..) => "Let",
+        hir::ExprKind::If(..) => "If",
+        hir::ExprKind::Loop(..) => "Loop",
+        hir::ExprKind::Match(..) => "Match",
+        hir::ExprKind::Closure(..) => "Closure",
+        hir::ExprKind::Block(..) => "Block",
+        hir::ExprKind::Assign(..) => "Assign",
+        hir::ExprKind::AssignOp(..) => "AssignOp",
+        hir::ExprKind::Field(..) => "Field",
+        hir::ExprKind::Index(..) => "Index",
+        hir::ExprKind::Path(..) => "Path",
+        hir::ExprKind::AddrOf(..) => "AddrOf",
+        hir::ExprKind::Break(..) => "Break",
+        hir::ExprKind::Continue(..) => "Continue",
+        hir::ExprKind::Ret(..) => "Ret",
+        hir::ExprKind::InlineAsm(..) => "InlineAsm",
+        hir::ExprKind::LlvmInlineAsm(..) => "LlvmInlineAsm",
+        hir::ExprKind::Struct(..) => "Struct",
+        hir::ExprKind::Repeat(..) => "Repeat",
+        hir::ExprKind::Yield(..) => "Yield",
+        hir::ExprKind::Err => "Err",
+    }
+}
@@ -13,6 +13,7 @@
 use rustc_middle::ty;
 use rustc_parse::new_parser_from_source_str;
 use rustc_query_impl::QueryCtxt;
+use rustc_serialize::json::Json;
 use rustc_session::config::{self, ErrorOutputType, Input, OutputFilenames};
 use rustc_session::early_error;
 use rustc_session::lint;
@@ -40,6 +41,7 @@ pub struct Compiler {
     pub(crate) register_lints: Option<Box<dyn Fn(&Session, &mut LintStore) + Send + Sync>>,
     pub(crate) override_queries:
         Option<fn(&Session, &mut ty::query::Providers, &mut ty::query::ExternProviders)>,
+    pub(crate) custom_print_requests: Vec<CustomPrintRequest>,
 }
 
 impl Compiler {
@@ -64,6 +66,9 @@ pub fn temps_dir(&self) -> &Option<PathBuf> {
     pub fn register_lints(&self) -> &Option<Box<dyn Fn(&Session, &mut LintStore) + Send + Sync>> {
         &self.register_lints
     }
+    pub fn custom_print_requests(&self) -> &[CustomPrintRequest] {
+        &self.custom_print_requests
+    }
     pub fn build_output_filenames(
         &self,
         sess: &Session,
@@ -131,6 +136,80 @@ macro_rules! error {
     })
 }
 
+/// A `--print` request added by a custom driver, with [`Config::custom_print_requests`].
+///
+/// The compiler accepts `--print NAME` for the names of the registered requests, and the
+/// default driver prints their output after that of the built-in requests, then stops.
+/// Built-in requests take precedence over custom ones with the same name.
+pub struct CustomPrintRequest {
+    name: &'static str,
+    unstable: bool,
+    print: Box<dyn Fn(&Session, Option<&Input>) -> Json + Send + Sync>,
+}
+
+impl CustomPrintRequest {
+    /// Creates the request `--print name`, whose output is computed by `print` from the
+    /// session and the input file, if one was given.
+    ///
+    /// With `--print-json`, the output is printed as the `value` of an object, like those of
+    /// the built-in requests. Otherwise, strings are printed as is, arrays of strings one
+    /// element per line, and any other value as pretty-printed JSON.
+    pub fn new(
+        name: &'static str,
+        print: impl Fn(&Session, Option<&Input>) -> Json + Send + Sync + 'static,
+    ) -> Self {
+        CustomPrintRequest { name, unstable: false, print: Box::new(print) }
+    }
+
+    /// Makes the request require `-Z unstable-options`, like the unstable built-in ones.
+    pub fn unstable(mut self) -> Self {
+        self.unstable = true;
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Prints the output of the request.
+    pub fn print(&self, sess: &Session, input: Option<&Input>) {
+        let value = (self.print)(sess, input);
+        if sess.opts.print_json {
+            return sess.print_named_json(self.name, value);
+        }
+        match value {
+            Json::String(value) => println!("{}", value),
+            Json::Array(values) if values.iter().all(Json::is_string) => {
+                for value in values {
+                    println!("{}", value.as_string().unwrap());
+                }
+            }
+            value => println!("{}", value.pretty()),
+        }
+    }
+}
+
+/// Reports the `--print` requests that are neither built-in nor registered by the driver,
+/// and the unstable ones given without `-Z unstable-options`.
+fn check_custom_print_requests(opts: &config::Options, requests: &[CustomPrintRequest]) {
+    for name in &opts.custom_prints {
+        match requests.iter().find(|request| request.name == *name) {
+            None => early_error(opts.error_format, &format!("unknown print request `{}`", name)),
+            Some(request) if request.unstable && !opts.debugging_opts.unstable_options => {
+                early_error(
+                    opts.error_format,
+                    &format!(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the {} print option",
+                        name
+                    ),
+                )
+            }
+            Some(_) => {}
+        }
+    }
+}
+
 /// The compiler configuration
 pub struct Config {
     /// Command line options
@@ -174,9 +253,13 @@ pub struct Config {
 
     /// Registry of diagnostics codes.
     pub registry: Registry,
+
+    /// The `--print` requests added by the driver, on top of the built-in ones.
+    pub custom_print_requests: Vec<CustomPrintRequest>,
 }
 
 pub fn create_compiler_and_run<R>(config: Config, f: impl FnOnce(&Compiler) -> R) -> R {
+    check_custom_print_requests(&config.opts, &config.custom_print_requests);
     let registry = &config.registry;
     let (mut sess, codegen_backend) = util::create_session(
         config.opts,
@@ -209,6 +292,7 @@ pub fn create_compiler_and_run<R>(config: Config, f: impl FnOnce(&Compiler) -> R
         temps_dir,
         register_lints: config.register_lints,
         override_queries: config.override_queries,
+        custom_print_requests: config.custom_print_requests,
     };
 
     rustc_span::with_source_map(compiler.sess.parse_sess.clone_source_map(), move || {
@@ -6,14 +6,18 @@
 #![feature(once_cell)]
 #![recursion_limit = "256"]
 
+mod analysis_json;
 mod callbacks;
+pub mod hir_json;
 pub mod interface;
 mod passes;
 mod proc_macro_decls;
 mod queries;
 pub mod util;
 
-pub use interface::{run_compiler, Config};
+pub use analysis_json::write_analysis_json;
+pub use hir_json::write_hir_json;
+pub use interface::{run_compiler, Config, CustomPrintRequest};
 pub use passes::{DEFAULT_EXTERN_QUERY_PROVIDERS, DEFAULT_QUERY_PROVIDERS};
 pub use queries::Queries;
 
@@ -7,7 +7,10 @@
 use rustc_borrowck as mir_borrowck;
 use rustc_codegen_ssa::back::link::emit_metadata;
 use rustc_codegen_ssa::traits::CodegenBackend;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::parallel;
+use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{Lrc, OnceCell, WorkerLocal};
 use rustc_data_structures::temp_dir::MaybeTempDir;
 use rustc_errors::{Applicability, ErrorReported, PResult};
@@ -19,7 +22,7 @@
 use rustc_metadata::{encode_metadata, EncodedMetadata};
 use rustc_middle::arena::Arena;
 use rustc_middle::dep_graph::DepGraph;
-use rustc_middle::ty::query::{ExternProviders, Providers};
+use rustc_middle::ty::query::{ExternProviders, Providers, QUERY_NAMES};
 use rustc_middle::ty::{self, GlobalCtxt, ResolverOutputs, TyCtxt};
 use rustc_mir_build as mir_build;
 use rustc_parse::{parse_crate_from_file, parse_crate_from_source_str, validate_attr};
@@ -28,12 +31,14 @@
 use rustc_query_impl::{OnDiskCache, Queries as TcxQueries};
 use rustc_resolve::{Resolver, ResolverArenas};
 use rustc_serialize::json;
+use rustc_session::config::TEMP_KIND_DIRS;
 use rustc_session::config::{CrateType, Input, OutputFilenames, OutputType, PpMode, PpSourceMode};
 use rustc_session::cstore::{MetadataLoader, MetadataLoaderDyn};
 use rustc_session::lint;
 use rustc_session::output::{filename_for_input, filename_for_metadata};
+use rustc_session::sandbox::SandboxAccess;
 use rustc_session::search_paths::PathKind;
-use rustc_session::{Limit, Session};
+use rustc_session::{Limit, Session, TimingPhase};
 use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_span::{FileName, MultiSpan};
 use rustc_trait_selection::traits;
@@ -44,21 +49,26 @@
 use std::any::Any;
 use std::cell::RefCell;
 use std::ffi::OsString;
+use std::hash::Hasher;
 use std::io::{self, BufWriter, Write};
 use std::lazy::SyncLazy;
 use std::marker::PhantomPinned;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::rc::Rc;
+use std::time::Instant;
 use std::{env, fs, iter};
 
 pub fn parse<'a>(sess: &'a Session, input: &Input) -> PResult<'a, ast::Crate> {
+    sess.timing_section_start("parse");
     let krate = sess.time("parse_crate", || match input {
         Input::File(file) => parse_crate_from_file(file, &sess.parse_sess),
         Input::Str { input, name } => {
             parse_crate_from_source_str(name.clone(), input.clone(), &sess.parse_sess)
         }
-    })?;
+    });
+    sess.timing_section_end("parse");
+    let krate = krate?;
 
     if sess.opts.debugging_opts.ast_json_noexpand {
         println!("{}", json::as_json(&krate));
@@ -213,13 +223,22 @@ pub fn register_plugins<'a>(
                 );
             }
         });
+        if let Some(policy) = &sess.opts.debugging_opts.incremental_gc {
+            sess.time("incr_comp_garbage_collect_incremental_dir", || {
+                if let Err(e) = rustc_incremental::garbage_collect_incremental_dir(sess, policy) {
+                    warn!("Error while trying to garbage collect the incremental directory: {}", e);
+                }
+            });
+        }
     }
 
-    let mut lint_store = rustc_lint::new_lint_store(
-        sess.opts.debugging_opts.no_interleave_lints,
-        sess.unstable_options(),
-    );
+    let mut lint_store = rustc_lint::new_lint_store(sess, sess.unstable_options());
     register_lints(sess, &mut lint_store);
+    sess.time("lint_plugin_loading", || {
+        lint_store
+            .load_plugin_registrars(&sess.opts.debugging_opts.lint_plugin)
+            .unwrap_or_else(|err| sess.fatal(&err.to_string()))
+    });
 
     let registrars =
         sess.time("plugin_loading", || plugin::load::load_plugins(sess, metadata_loader, &krate));
@@ -276,7 +295,8 @@ pub fn configure_and_expand(
     util::check_attr_crate_type(sess, &krate.attrs, &mut resolver.lint_buffer());
 
     // Expand all macros
-    krate = sess.time("macro_expand_crate", || {
+    sess.timing_section_start("expansion");
+    let expanded = sess.time("macro_expand_crate", || {
         // Windows dlls do not have rpaths, so they don't know how to find their
         // dependencies. It's up to us to tell the system where to find all the
         // dependent dlls. Note that this uses cfg!(windows) as opposed to
@@ -327,7 +347,17 @@ pub fn configure_and_expand(
             pre_expansion_lint(sess, lint_store, &krate, &crate_attrs, &ident.name.as_str());
             (krate.attrs, krate.items)
         };
-        let mut ecx = ExtCtxt::new(sess, cfg, resolver, Some(&extern_mod_loaded));
+        let lint_level_query = |lint_name: &str, enclosing_attrs: &[Vec<ast::Attribute>]| {
+            rustc_lint::lint_level_at_expansion_site(
+                sess,
+                lint_store,
+                &crate_attrs,
+                enclosing_attrs,
+                lint_name,
+            )
+        };
+        let mut ecx =
+            ExtCtxt::new(sess, cfg, resolver, Some(&extern_mod_loaded), Some(&lint_level_query));
 
         // Expand macros now!
         let krate = sess.time("expand_crate", || ecx.monotonic_expander().expand_crate(krate));
@@ -366,7 +396,9 @@ pub fn configure_and_expand(
         } else {
             Ok(krate)
         }
-    })?;
+    });
+    sess.timing_section_end("expansion");
+    krate = expanded?;
 
     sess.time("maybe_building_test_harness", || {
         rustc_builtin_macros::test_harness::inject(sess, resolver, &mut krate)
@@ -603,6 +635,15 @@ fn escape_dep_env(symbol: Symbol) -> String {
     escaped
 }
 
+/// Hashes the contents of a binary dependency for `-Z binary-dep-depinfo=hashed`, so that
+/// build systems can key caches on it rather than on modification times.
+fn hash_dep_file(path: &Path) -> io::Result<String> {
+    let mut hasher = StableHasher::new();
+    hasher.write(&fs::read(path)?);
+    let hash: Fingerprint = hasher.finish();
+    Ok(hash.to_hex())
+}
+
 fn write_out_deps(
     sess: &Session,
     boxed_resolver: &RefCell<BoxedResolver>,
@@ -641,21 +682,30 @@ fn write_out_deps(
             files.push(backend.to_string());
         }
 
+        // With `-Z binary-dep-depinfo=hashed`, the SVH and content hash of each
+        // binary dependency, emitted as special comments below.
+        let mut binary_hashes = Vec::new();
         if sess.binary_dep_depinfo() {
-            boxed_resolver.borrow_mut().access(|resolver| {
+            boxed_resolver.borrow_mut().access(|resolver| -> io::Result<()> {
+                // Sysroot crates are commonly reachable through several crate numbers
+                // (e.g. `core` via both `std` and `alloc`), so only list each artifact once.
+                let mut seen = FxHashSet::default();
                 for cnum in resolver.cstore().crates_untracked() {
                     let source = resolver.cstore().crate_source_untracked(cnum);
-                    if let Some((path, _)) = source.dylib {
-                        files.push(escape_dep_filename(&path.display().to_string()));
-                    }
-                    if let Some((path, _)) = source.rlib {
-                        files.push(escape_dep_filename(&path.display().to_string()));
-                    }
-                    if let Some((path, _)) = source.rmeta {
-                        files.push(escape_dep_filename(&path.display().to_string()));
+                    for path in source.paths() {
+                        if !seen.insert(path.clone()) {
+                            continue;
+                        }
+                        let file = escape_dep_filename(&path.display().to_string());
+                        if sess.binary_dep_depinfo_hashed() {
+                            let svh = resolver.cstore().crate_hash_untracked(cnum);
+                            binary_hashes.push((file.clone(), svh, hash_dep_file(path)?));
+                        }
+                        files.push(file);
                     }
                 }
-            });
+                Ok(())
+            })?;
         }
 
         let mut file = BufWriter::new(fs::File::create(&deps_filename)?);
@@ -688,11 +738,20 @@ fn write_out_deps(
             }
         }
 
+        // Emit special comments with the hashes of binary dependencies.
+        if !binary_hashes.is_empty() {
+            writeln!(file)?;
+            for (path, svh, content_hash) in binary_hashes {
+                writeln!(file, "# binary-dep:{} svh={} content-hash={}", path, svh, content_hash)?;
+            }
+        }
+
         Ok(())
     })();
 
     match result {
         Ok(_) => {
+            sess.record_artifact(&deps_filename, "dep-info");
             if sess.opts.json_artifact_notifications {
                 sess.parse_sess
                     .span_diagnostic
@@ -752,8 +811,22 @@ pub fn prepare_outputs(
         }
     }
 
+    if sess.output_sandbox.is_some() {
+        let written = output_paths.iter().chain(&compiler.temps_dir).chain(&sess.opts.incremental);
+        let violations = written
+            .filter(|path| sess.check_sandboxed_access(path, SandboxAccess::Write).is_err())
+            .count();
+        if violations > 0 {
+            return Err(ErrorReported);
+        }
+    }
+
     if let Some(ref dir) = compiler.temps_dir {
-        if fs::create_dir_all(dir).is_err() {
+        let mut dirs = vec![dir.clone()];
+        if sess.opts.debugging_opts.temps_dir_per_kind {
+            dirs.extend(TEMP_KIND_DIRS.iter().map(|kind| dir.join(kind)));
+        }
+        if dirs.iter().any(|dir| fs::create_dir_all(dir).is_err()) {
             sess.err("failed to find or create the directory specified by `--temps-dir`");
             return Err(ErrorReported);
         }
@@ -846,6 +919,12 @@ pub fn create_global_ctxt<'tcx>(
         .access(|resolver| lower_to_hir(sess, &lint_store, resolver, krate, hir_arena));
     let resolver_outputs = BoxedResolver::to_resolver_outputs(resolver);
 
+    if let Some(query) = &sess.opts.debugging_opts.profile_query_keys {
+        if !QUERY_NAMES.contains(&query.as_str()) {
+            sess.err(&format!("unknown query `{}` in `-Z profile-query-keys`", query));
+        }
+    }
+
     let query_result_on_disk_cache = rustc_incremental::load_query_result_cache(sess);
 
     let codegen_backend = compiler.codegen_backend();
@@ -923,7 +1002,10 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
     });
 
     // passes are timed inside typeck
-    typeck::check_crate(tcx)?;
+    sess.timing_section_start("typeck");
+    let typeck_result = typeck::check_crate(tcx);
+    sess.timing_section_end("typeck");
+    typeck_result?;
 
     sess.time("misc_checking_2", || {
         parallel!(
@@ -947,8 +1029,19 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         );
     });
 
-    sess.time("MIR_borrow_checking", || {
-        tcx.hir().par_body_owners(|def_id| tcx.ensure().mir_borrowck(def_id));
+    sess.timing_section("borrowck", || {
+        sess.time("MIR_borrow_checking", || {
+            if sess.opts.debugging_opts.module_timings.is_none() {
+                tcx.hir().par_body_owners(|def_id| tcx.ensure().mir_borrowck(def_id));
+                return;
+            }
+            // The bodies are checked one at a time, so that the time spent on each is known.
+            for def_id in tcx.hir().body_owners() {
+                let start = Instant::now();
+                tcx.ensure().mir_borrowck(def_id);
+                tcx.record_module_time(def_id.to_def_id(), TimingPhase::FrontEnd, start.elapsed());
+            }
+        });
     });
 
     sess.time("MIR_effect_checking", || {
@@ -1012,6 +1105,8 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         );
     });
 
+    sess.time("lint_expectation_checking", || rustc_lint::check_expectations(tcx));
+
     Ok(())
 }
 
@@ -1060,11 +1155,13 @@ enum MetadataKind {
             .prefix("rmeta")
             .tempdir_in(out_filename.parent().unwrap())
             .unwrap_or_else(|err| tcx.sess.fatal(&format!("couldn't create a temp dir: {}", err)));
-        let metadata_tmpdir = MaybeTempDir::new(metadata_tmpdir, tcx.sess.opts.cg.save_temps);
+        let metadata_tmpdir = MaybeTempDir::new(metadata_tmpdir, tcx.sess.opts.cg.save_temps)
+            .keep_on_error(tcx.sess.keep_temps_on_error());
         let metadata_filename = emit_metadata(tcx.sess, metadata.raw_data(), &metadata_tmpdir);
         if let Err(e) = util::non_durable_rename(&metadata_filename, &out_filename) {
             tcx.sess.fatal(&format!("failed to write {}: {}", out_filename.display(), e));
         }
+        tcx.sess.record_artifact(&out_filename, "metadata");
         if tcx.sess.opts.json_artifact_notifications {
             tcx.sess
                 .parse_sess
@@ -1087,6 +1184,9 @@ pub fn start_codegen<'tcx>(
 ) -> Box<dyn Any> {
     info!("Pre-codegen\n{:?}", tcx.debug_stats());
 
+    // Ended in `Linker::link`, once the backend has finished.
+    tcx.sess.timing_section_start("codegen");
+
     let (metadata, need_metadata_module) = encode_and_write_metadata(tcx, outputs);
 
     let codegen = tcx.sess.time("codegen_crate", move || {
@@ -1107,6 +1207,14 @@ pub fn start_codegen<'tcx>(
             tcx.sess.err(&format!("could not emit MIR: {}", e));
             tcx.sess.abort_if_errors();
         }
+        tcx.sess.record_artifact(&outputs.path(OutputType::Mir), OutputType::Mir.shorthand());
+    }
+
+    let output_types = &tcx.sess.opts.output_types;
+    if output_types.contains_key(&OutputType::CallGraph)
+        || output_types.contains_key(&OutputType::CallGraphDot)
+    {
+        rustc_monomorphize::write_call_graph(tcx, outputs);
     }
 
     codegen
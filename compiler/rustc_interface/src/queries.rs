@@ -335,11 +335,14 @@ pub struct Linker {
 
 impl Linker {
     pub fn link(self) -> Result<()> {
-        let (codegen_results, work_products) = self.codegen_backend.join_codegen(
+        let joined = self.codegen_backend.join_codegen(
             self.ongoing_codegen,
             &self.sess,
             &self.prepare_outputs,
-        )?;
+        );
+        // Started in `passes::start_codegen`.
+        self.sess.timing_section_end("codegen");
+        let (codegen_results, work_products) = joined?;
 
         self.sess.compile_status()?;
 
@@ -356,13 +359,9 @@ pub fn link(self) -> Result<()> {
         // any more, we can finalize it (which involves renaming it)
         rustc_incremental::finalize_session_directory(&self.sess, self.crate_hash);
 
-        if !self
-            .sess
-            .opts
-            .output_types
-            .keys()
-            .any(|&i| i == OutputType::Exe || i == OutputType::Metadata)
-        {
+        if !self.sess.opts.output_types.keys().any(|&i| {
+            matches!(i, OutputType::Exe | OutputType::Metadata | OutputType::ExportedSymbols)
+        }) {
             return Ok(());
         }
 
@@ -375,11 +374,17 @@ pub fn link(self) -> Result<()> {
             std::fs::write(&rlink_file, rlink_data).map_err(|err| {
                 sess.fatal(&format!("failed to write file {}: {}", rlink_file.display(), err));
             })?;
+            sess.record_artifact(&rlink_file, config::RLINK_EXT);
             return Ok(());
         }
 
         let _timer = sess.prof.verbose_generic_activity("link_crate");
-        self.codegen_backend.link(&self.sess, codegen_results, &self.prepare_outputs)
+        // Stopping halfway through linking would leave partial outputs behind.
+        sess.without_time_limit(|| {
+            sess.timing_section("link", || {
+                self.codegen_backend.link(&self.sess, codegen_results, &self.prepare_outputs)
+            })
+        })
     }
 }
 
@@ -407,8 +412,11 @@ pub fn enter<F, T>(&self, f: F) -> T
                 gcx.enter(rustc_query_impl::print_stats);
             }
 
-            self.session()
-                .time("serialize_dep_graph", || gcx.enter(rustc_incremental::save_dep_graph));
+            // Stopping while the dep graph is saved would leave a corrupt incremental cache.
+            self.session().without_time_limit(|| {
+                self.session()
+                    .time("serialize_dep_graph", || gcx.enter(rustc_incremental::save_dep_graph))
+            });
         }
 
         _timer = Some(self.session().timer("free_global_ctxt"));
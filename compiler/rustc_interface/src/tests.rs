@@ -2,16 +2,24 @@
 
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
-use rustc_session::config::InstrumentCoverage;
 use rustc_session::config::Strip;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{
     rustc_optgroups, ErrorOutputType, ExternLocation, LocationDetail, Options, Passes,
 };
-use rustc_session::config::{CFGuard, ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
+use rustc_session::config::{AliasingModel, InstrumentFunctionHooks};
 use rustc_session::config::{
-    Externs, OutputType, OutputTypes, SymbolManglingVersion, WasiExecModel,
+    BinaryDepDepinfo, CFGuard, CguNameScheme, ExternEntry, IncrementalGc, LinkerPluginLto, LtoCli,
+    SwitchWithOptPath,
 };
+use rustc_session::config::{BinarySizeReport, ProcMacroSandbox, ReportFormat, RetagGranularity};
+use rustc_session::config::{DoctestFlag, EncodeMirFor, PanicAbortTestsFormat, PltPolicy};
+use rustc_session::config::{
+    Externs, OutputType, OutputTypes, SymbolManglingVersion, WasiComponent, WasiExecModel,
+    WasmFeature,
+};
+use rustc_session::config::{FunctionReturn, HardeningProfile, Hotpatch, InstrumentCoverage};
+use rustc_session::config::{StackProtectorGuard, StopAfter, TempsPolicy};
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -28,6 +36,7 @@
 use std::iter::FromIterator;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 type CfgSpecs = FxHashSet<(String, Option<String>)>;
 
@@ -268,6 +277,44 @@ fn test_lints_tracking_hash_different_construction_order() {
     assert_non_crate_hash_different(&v1, &v2);
 }
 
+#[test]
+fn test_lint_config_tracking_hash_different_values() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let v3 = Options::default();
+
+    v1.lint_config = vec![(String::from("a"), String::from("limit"), String::from("1"))];
+    v2.lint_config = vec![(String::from("a"), String::from("limit"), String::from("2"))];
+
+    assert_non_crate_hash_different(&v1, &v2);
+    assert_non_crate_hash_different(&v1, &v3);
+    assert_non_crate_hash_different(&v2, &v3);
+}
+
+#[test]
+fn test_lint_config_parsing() {
+    rustc_span::create_default_session_globals_then(|| {
+        let args = ["-W", "large-assignments=limit=4096,x_y=z", "-A", "dead-code"];
+        let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+        let matches = optgroups().parse(&args).unwrap();
+        let (sessopts, _) = build_session_options_and_crate_config(matches);
+        assert_eq!(
+            sessopts.lint_opts,
+            vec![
+                (String::from("large_assignments"), Level::Warn),
+                (String::from("dead_code"), Level::Allow),
+            ]
+        );
+        assert_eq!(
+            sessopts.lint_config,
+            vec![
+                (String::from("large_assignments"), String::from("limit"), String::from("4096")),
+                (String::from("large_assignments"), String::from("x-y"), String::from("z")),
+            ]
+        );
+    });
+}
+
 #[test]
 fn test_lint_cap_hash_different() {
     let mut v1 = Options::default();
@@ -282,6 +329,73 @@ fn test_lint_cap_hash_different() {
     assert_non_crate_hash_different(&v2, &v3);
 }
 
+#[test]
+fn test_lint_group_caps_hash_different() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let v3 = Options::default();
+
+    v1.lint_group_caps = vec![(String::from("future_incompatible"), Level::Warn)];
+    v2.lint_group_caps = vec![(String::from("future_incompatible"), Level::Allow)];
+
+    assert_non_crate_hash_different(&v1, &v2);
+    assert_non_crate_hash_different(&v1, &v3);
+    assert_non_crate_hash_different(&v2, &v3);
+}
+
+#[test]
+fn test_lint_group_caps_parsing() {
+    rustc_span::create_default_session_globals_then(|| {
+        let args = [
+            "--cap-lints",
+            "allow",
+            "--cap-lints-group",
+            "future-incompatible=warn",
+            "--cap-lints-group",
+            "rustdoc::all=deny",
+        ];
+        let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+        let matches = optgroups().parse(&args).unwrap();
+        let (sessopts, _) = build_session_options_and_crate_config(matches);
+        assert_eq!(sessopts.lint_cap, Some(Level::Allow));
+        assert_eq!(
+            sessopts.lint_group_caps,
+            vec![
+                (String::from("future_incompatible"), Level::Warn),
+                (String::from("rustdoc::all"), Level::Deny),
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_file_cfg_tracking_hash_different_values() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let v3 = Options::default();
+
+    v1.file_cfg = vec![(String::from("a"), None)];
+    v2.file_cfg = vec![(String::from("a"), Some(String::from("b")))];
+
+    assert_different_hash(&v1, &v2);
+    assert_different_hash(&v1, &v3);
+    assert_different_hash(&v2, &v3);
+}
+
+#[test]
+fn test_crate_version_tracking_hash_different_values() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let v3 = Options::default();
+
+    v1.crate_version = Some(String::from("1.0.0"));
+    v2.crate_version = Some(String::from("1.0.1"));
+
+    assert_different_hash(&v1, &v2);
+    assert_different_hash(&v1, &v3);
+    assert_different_hash(&v2, &v3);
+}
+
 #[test]
 fn test_search_paths_tracking_hash_different_order() {
     let mut v1 = Options::default();
@@ -574,8 +688,11 @@ macro_rules! tracked {
     tracked!(force_frame_pointers, Some(false));
     tracked!(force_unwind_tables, Some(true));
     tracked!(inline_threshold, Some(0xf007ba11));
+    tracked!(instrument_functions, true);
+    tracked!(instrument_functions_exclude, vec![String::from("core::fmt")]);
     tracked!(linker_plugin_lto, LinkerPluginLto::LinkerPluginAuto);
     tracked!(link_dead_code, Some(true));
+    tracked!(link_time_gc_sections, Some(false));
     tracked!(llvm_args, vec![String::from("1"), String::from("2")]);
     tracked!(lto, LtoCli::Fat);
     tracked!(metadata, vec![String::from("A"), String::from("B")]);
@@ -591,8 +708,10 @@ macro_rules! tracked {
     tracked!(profile_generate, SwitchWithOptPath::Enabled(None));
     tracked!(profile_use, Some(PathBuf::from("abc")));
     tracked!(relocation_model, Some(RelocModel::Pic));
+    tracked!(relro_level, Some(RelroLevel::Partial));
     tracked!(soft_float, true);
     tracked!(split_debuginfo, Some(SplitDebuginfo::Packed));
+    tracked!(split_lto_unit, Some(true));
     tracked!(target_cpu, Some(String::from("abc")));
     tracked!(target_feature, String::from("all the features, all of them"));
 }
@@ -641,9 +760,16 @@ macro_rules! untracked {
     untracked!(assert_incr_state, Some(String::from("loaded")));
     untracked!(ast_json, true);
     untracked!(ast_json_noexpand, true);
+    untracked!(
+        binary_size_report,
+        Some(BinarySizeReport { linked: true, format: ReportFormat::Json })
+    );
     untracked!(borrowck, String::from("other"));
+    untracked!(cache_proc_macros, Some(PathBuf::from("abc")));
+    untracked!(dead_code_report, Some(PathBuf::from("dead-code.json")));
     untracked!(deduplicate_diagnostics, false);
     untracked!(dep_tasks, true);
+    untracked!(doctest_compile_options, vec![DoctestFlag::Lint(Level::Deny, String::from("w"))]);
     untracked!(dont_buffer_diagnostics, true);
     untracked!(dump_dep_graph, true);
     untracked!(dump_mir, Some(String::from("abc")));
@@ -651,48 +777,77 @@ macro_rules! untracked {
     untracked!(dump_mir_dir, String::from("abc"));
     untracked!(dump_mir_exclude_pass_number, true);
     untracked!(dump_mir_graphviz, true);
+    untracked!(dump_mono_stats, Some(ReportFormat::Json));
+    untracked!(emit_artifact_manifest, Some(PathBuf::from("manifest.json")));
     untracked!(emit_stack_sizes, true);
+    untracked!(external_tool_retries, 2);
+    untracked!(external_tool_timeout, Some(60));
+    untracked!(file_access_log, Some(PathBuf::from("access.json")));
+    untracked!(fuel_trace, Some(PathBuf::from("fuel-trace.txt")));
     untracked!(future_incompat_test, true);
+    untracked!(generate_linker_script_template, Some(PathBuf::from("template.ld")));
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
+    untracked!(
+        incremental_gc,
+        Some(IncrementalGc { max_age: Some(Duration::from_secs(3600)), max_size: None })
+    );
     untracked!(incremental_ignore_spans, true);
     untracked!(incremental_info, true);
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
     untracked!(keep_hygiene_data, true);
+    untracked!(link_arg_file_threshold, Some(1024));
     untracked!(link_native_libraries, false);
+    untracked!(lint_expectations_json, true);
+    untracked!(lint_timings, true);
     untracked!(llvm_time_trace, true);
     untracked!(ls, true);
     untracked!(macro_backtrace, true);
+    untracked!(macro_stats, true);
     untracked!(meta_stats, true);
+    untracked!(module_timings, Some(PathBuf::from("timings.txt")));
     untracked!(nll_facts, true);
     untracked!(no_analysis, true);
     untracked!(no_interleave_lints, true);
     untracked!(no_leak_check, true);
     untracked!(no_parallel_llvm, true);
+    untracked!(output_sandbox, vec![PathBuf::from("abc")]);
     untracked!(parse_only, true);
     untracked!(perf_stats, true);
+    untracked!(polymorphize_report, Some(PathBuf::from("polymorphize.txt")));
     // `pre_link_arg` is omitted because it just forwards to `pre_link_args`.
     untracked!(pre_link_args, vec![String::from("abc"), String::from("def")]);
     untracked!(profile_closures, true);
+    untracked!(print_args_json, true);
     untracked!(print_link_args, true);
     untracked!(print_llvm_passes, true);
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
     untracked!(proc_macro_backtrace, true);
+    untracked!(proc_macro_sandbox, ProcMacroSandbox::Strict);
+    untracked!(profile_query_keys, Some(String::from("type_of")));
     untracked!(query_dep_graph, true);
     untracked!(query_stats, true);
+    untracked!(remark_dir, Some(PathBuf::from("remarks")));
+    untracked!(resource_summary, Some(ReportFormat::Json));
+    untracked!(run_link_plan, true);
     untracked!(save_analysis, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(shuffle_cgus, Some(1234));
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
+    untracked!(stack_protector_report, Some(PathBuf::from("stack-protector.txt")));
     untracked!(temps_dir, Some(String::from("abc")));
+    untracked!(temps_dir_per_kind, true);
+    untracked!(temps_policy, Some(TempsPolicy::KeepOnError));
     untracked!(terminal_width, Some(80));
     untracked!(threads, 99);
     untracked!(time, true);
     untracked!(time_llvm_passes, true);
     untracked!(time_passes, true);
+    untracked!(total_compile_time_limit, Some(Duration::from_secs(90)));
     untracked!(trace_macros, true);
     untracked!(trim_diagnostic_paths, false);
     untracked!(ui_testing, true);
@@ -712,34 +867,60 @@ macro_rules! tracked {
 
     // Make sure that changing a [TRACKED] option changes the hash.
     // This list is in alphabetical order.
+    tracked!(aliasing_model, Some(AliasingModel::TreeBorrows));
     tracked!(allow_features, Some(vec![String::from("lang_items")]));
     tracked!(always_encode_mir, true);
+    tracked!(android_api_level, Some(24));
+    tracked!(annotate_llvm_ir, true);
+    tracked!(apple_deployment_target, Some((10, 12)));
     tracked!(asm_comments, true);
+    tracked!(assert_module_determinism, true);
     tracked!(assume_incomplete_release, true);
-    tracked!(binary_dep_depinfo, true);
+    tracked!(binary_dep_depinfo, BinaryDepDepinfo::Paths);
+    tracked!(bind_now, Some(true));
+    tracked!(cgu_name_scheme, Some(CguNameScheme::ItemBased));
     tracked!(chalk, true);
     tracked!(codegen_backend, Some("abc".to_string()));
     tracked!(crate_attr, vec!["abc".to_string()]);
+    tracked!(data_sections, Some(false));
     tracked!(debug_info_for_profiling, true);
     tracked!(debug_macros, true);
     tracked!(dep_info_omit_d_target, true);
     tracked!(dual_proc_macros, true);
+    tracked!(emscripten_flags, vec![(String::from("ASSERTIONS"), String::from("1"))]);
+    tracked!(encode_mir_for, Some(EncodeMirFor::Reachable));
     tracked!(fewer_names, Some(true));
     tracked!(force_unstable_if_unmarked, true);
-    tracked!(fuel, Some(("abc".to_string(), 99)));
+    tracked!(fuel, Some(("abc".to_string(), Some("Inline".to_string()), 99)));
+    tracked!(function_return, FunctionReturn::ThunkExtern);
     tracked!(function_sections, Some(false));
+    tracked!(hardening, Some(HardeningProfile::Standard));
+    tracked!(hotpatch, Some(Hotpatch::NopSled { entry: 2, prefix: 5 }));
     tracked!(human_readable_cgu_names, true);
     tracked!(inline_in_all_cgus, Some(true));
     tracked!(inline_mir, Some(true));
     tracked!(inline_mir_hint_threshold, Some(123));
     tracked!(inline_mir_threshold, Some(123));
     tracked!(instrument_coverage, Some(InstrumentCoverage::All));
+    tracked!(
+        instrument_functions,
+        Some(InstrumentFunctionHooks {
+            entry: String::from("trace_enter"),
+            exit: Some(String::from("trace_exit")),
+        })
+    );
+    tracked!(instrument_functions_exclude, vec![String::from("core::fmt")]);
+    tracked!(instrument_functions_include, vec![String::from("my_crate")]);
+    tracked!(instrument_functions_post_inline, false);
     tracked!(instrument_mcount, true);
+    tracked!(layout_seed, Some(1234));
     tracked!(link_only, true);
+    tracked!(lint_plugin, vec![PathBuf::from("liblints.so")]);
     tracked!(llvm_plugins, vec![String::from("plugin_name")]);
     tracked!(location_detail, LocationDetail { file: true, line: false, column: false });
+    tracked!(max_monomorphizations, Some(16));
     tracked!(merge_functions, Some(MergeFunctions::Disabled));
-    tracked!(mir_emit_retag, true);
+    tracked!(mir_emit_retag, Some(RetagGranularity::Coarse));
     tracked!(mir_opt_level, Some(4));
     tracked!(move_size_limit, Some(4096));
     tracked!(mutable_noalias, Some(true));
@@ -750,10 +931,12 @@ macro_rules! tracked {
     tracked!(no_profiler_runtime, true);
     tracked!(osx_rpath_install_name, true);
     tracked!(panic_abort_tests, true);
+    tracked!(panic_abort_tests_env, Some(String::from("TEST_INVOKE")));
+    tracked!(panic_abort_tests_format, PanicAbortTestsFormat::Json);
     tracked!(panic_in_drop, PanicStrategy::Abort);
     tracked!(partially_uninit_const_threshold, Some(123));
     tracked!(pick_stable_methods_before_any_unstable, false);
-    tracked!(plt, Some(true));
+    tracked!(plt, PltPolicy::Always);
     tracked!(polonius, true);
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(print_fuel, Some("abc".to_string()));
@@ -765,17 +948,29 @@ macro_rules! tracked {
     tracked!(relro_level, Some(RelroLevel::Full));
     tracked!(remap_cwd_prefix, Some(PathBuf::from("abc")));
     tracked!(report_delayed_bugs, true);
+    tracked!(riscv_abi, Some(String::from("lp64")));
+    tracked!(riscv_relax, Some(false));
+    tracked!(safestack_use_pointer_address, true);
     tracked!(sanitizer, SanitizerSet::ADDRESS);
     tracked!(sanitizer_memory_track_origins, 2);
     tracked!(sanitizer_recover, SanitizerSet::ADDRESS);
     tracked!(saturating_float_casts, Some(true));
+    tracked!(sdk_root, Some(PathBuf::from("/Library/Developer/SDKs/MacOSX.sdk")));
     tracked!(share_generics, Some(true));
+    tracked!(shared_generics_dir, Some(PathBuf::from("shared-generics")));
     tracked!(show_span, Some(String::from("abc")));
     tracked!(simulate_remapped_rust_src_base, Some(PathBuf::from("/rustc/abc")));
     tracked!(src_hash_algorithm, Some(SourceFileHashAlgorithm::Sha1));
+    tracked!(stack_depth_report, Some(PathBuf::from("stack-depth.txt")));
     tracked!(stack_protector, StackProtector::All);
+    tracked!(stack_protector_guard, Some(StackProtectorGuard::Global));
+    tracked!(stack_protector_guard_offset, Some(40));
     tracked!(symbol_mangling_version, Some(SymbolManglingVersion::V0));
+    tracked!(symbol_prefix, Some(String::from("plugin_")));
+    tracked!(symbol_visibility_file, Some(PathBuf::from("exports.txt")));
     tracked!(teach, true);
+    tracked!(test_attr, Some(String::from("my_test")));
+    tracked!(test_runner, Some(String::from("my_harness::run_tests")));
     tracked!(thinlto, Some(true));
     tracked!(thir_unsafeck, true);
     tracked!(tls_model, Some(TlsModel::GeneralDynamic));
@@ -785,7 +980,12 @@ macro_rules! tracked {
     tracked!(unleash_the_miri_inside_of_you, true);
     tracked!(use_ctors_section, Some(true));
     tracked!(verify_llvm_ir, true);
+    tracked!(
+        wasi_component,
+        Some(WasiComponent { adapter: None, world: Some(PathBuf::from("world.wit")) })
+    );
     tracked!(wasi_exec_model, Some(WasiExecModel::Reactor));
+    tracked!(wasm_features, vec![(WasmFeature::Atomics, true)]);
 
     macro_rules! tracked_no_crate_hash {
         ($name: ident, $non_default_value: expr) => {
@@ -796,6 +996,7 @@ macro_rules! tracked_no_crate_hash {
         };
     }
     tracked_no_crate_hash!(no_codegen, true);
+    tracked_no_crate_hash!(stop_after, Some(StopAfter::Codegen));
 }
 
 #[test]
@@ -808,3 +1009,44 @@ fn test_edition_parsing() {
     let (sessopts, _) = build_session_options_and_crate_config(matches);
     assert!(sessopts.edition == Edition::Edition2018)
 }
+
+#[test]
+fn test_hardening_profile_expansion() {
+    let matches = optgroups().parse(&["-Zhardening=standard".to_string()]).unwrap();
+    let (sessopts, _) = build_session_options_and_crate_config(matches);
+    assert_eq!(sessopts.debugging_opts.relro_level, Some(RelroLevel::Full));
+    assert_eq!(sessopts.debugging_opts.bind_now, Some(true));
+    assert_eq!(sessopts.debugging_opts.stack_protector, StackProtector::Strong);
+
+    // Explicitly passed options win over the preset, regardless of their order.
+    let matches = optgroups()
+        .parse(&[
+            "-Zstack-protector=none".to_string(),
+            "-Zhardening=strict".to_string(),
+            "-Zrelro-level=partial".to_string(),
+        ])
+        .unwrap();
+    let (sessopts, _) = build_session_options_and_crate_config(matches);
+    assert_eq!(sessopts.debugging_opts.relro_level, Some(RelroLevel::Partial));
+    assert_eq!(sessopts.debugging_opts.bind_now, Some(true));
+    assert_eq!(sessopts.debugging_opts.stack_protector, StackProtector::None);
+}
+
+#[test]
+fn test_stop_after_aliases() {
+    let stop_after = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let matches = optgroups().parse(&args).unwrap();
+        let (sessopts, _) = build_session_options_and_crate_config(matches);
+        sessopts.debugging_opts.stop_after
+    };
+    assert_eq!(stop_after(&[]), None);
+    assert_eq!(stop_after(&["-Zstop-after=codegen"]), Some(StopAfter::Codegen));
+    assert_eq!(stop_after(&["-Zparse-only"]), Some(StopAfter::Parsing));
+    assert_eq!(stop_after(&["-Zno-analysis"]), Some(StopAfter::Expansion));
+    assert_eq!(stop_after(&["-Zno-codegen"]), Some(StopAfter::Analysis));
+
+    // Compilation stops after the earliest of the stages that are given.
+    assert_eq!(stop_after(&["-Zstop-after=codegen", "-Zno-analysis"]), Some(StopAfter::Expansion));
+    assert_eq!(stop_after(&["-Zno-codegen", "-Zstop-after=parsing"]), Some(StopAfter::Parsing));
+}
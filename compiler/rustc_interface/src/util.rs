@@ -6,6 +6,7 @@
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 #[cfg(parallel_compiler)]
 use rustc_data_structures::jobserver;
+use rustc_data_structures::stack::get_stack_size;
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::registry::Registry;
 #[cfg(parallel_compiler)]
@@ -15,7 +16,7 @@
 use rustc_query_impl::QueryCtxt;
 use rustc_resolve::{self, Resolver};
 use rustc_session as session;
-use rustc_session::config::{self, CrateType};
+use rustc_session::config::{self, CfgSource, CrateType};
 use rustc_session::config::{ErrorOutputType, Input, OutputFilenames};
 use rustc_session::lint::{self, BuiltinLintDiagnostics, LintBuffer};
 use rustc_session::parse::CrateConfig;
@@ -25,7 +26,6 @@
 use rustc_span::source_map::FileLoader;
 use rustc_span::symbol::{sym, Symbol};
 use smallvec::SmallVec;
-use std::env;
 use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
 use std::io;
 use std::lazy::SyncOnceCell;
@@ -55,13 +55,16 @@ pub fn add_configuration(
     let tf = sym::target_feature;
 
     let target_features = codegen_backend.target_features(sess);
-    sess.target_features.extend(target_features.iter().cloned());
+    sess.target_features.extend(target_features);
+    sess.check_riscv_abi_features();
 
-    cfg.extend(target_features.into_iter().map(|feat| (tf, Some(feat))));
+    let feature_cfg: Vec<_> =
+        sess.enabled_target_features().into_iter().map(|feat| (tf, Some(feat))).collect();
 
-    if sess.crt_static(None) {
-        cfg.insert((tf, Some(sym::crt_dash_static)));
+    for &feat in &feature_cfg {
+        sess.parse_sess.config_provenance.entry(feat).or_default().push(CfgSource::TargetFeature);
     }
+    cfg.extend(feature_cfg);
 }
 
 pub fn create_session(
@@ -100,21 +103,15 @@ pub fn create_session(
 
     codegen_backend.init(&sess);
 
-    let mut cfg = config::build_configuration(&sess, config::to_crate_config(cfg));
+    let user_cfg = config::to_crate_config(cfg);
+    sess.parse_sess.config_provenance = config::configuration_provenance(&sess, &user_cfg);
+    let mut cfg = config::build_configuration(&sess, user_cfg);
     add_configuration(&mut cfg, &mut sess, &*codegen_backend);
     sess.parse_sess.config = cfg;
 
     (Lrc::new(sess), Lrc::new(codegen_backend))
 }
 
-const STACK_SIZE: usize = 8 * 1024 * 1024;
-
-fn get_stack_size() -> Option<usize> {
-    // FIXME: Hacks on hacks. If the env is trying to override the stack size
-    // then *don't* set it explicitly.
-    env::var_os("RUST_MIN_STACK").is_none().then_some(STACK_SIZE)
-}
-
 /// Like a `thread::Builder::spawn` followed by a `join()`, but avoids the need
 /// for `'static` bounds.
 #[cfg(not(parallel_compiler))]
@@ -624,6 +621,7 @@ pub fn build_output_filenames(
                 stem,
                 None,
                 temps_dir.clone(),
+                sess.opts.debugging_opts.temps_dir_per_kind,
                 sess.opts.cg.extra_filename.clone(),
                 sess.opts.output_types.clone(),
             )
@@ -653,6 +651,7 @@ pub fn build_output_filenames(
                 out_file.file_stem().unwrap_or_default().to_str().unwrap().to_string(),
                 ofile,
                 temps_dir.clone(),
+                sess.opts.debugging_opts.temps_dir_per_kind,
                 sess.opts.cg.extra_filename.clone(),
                 sess.opts.output_types.clone(),
             )
@@ -16,13 +16,17 @@
 
 use self::TargetLint::*;
 
+use crate::late::{AggregatingPass, LateAggregatingPass};
 use crate::levels::{is_known_lint_tool, LintLevelsBuilder};
 use crate::passes::{EarlyLintPassObject, LateLintPassObject};
 use ast::util::unicode::TEXT_FLOW_CONTROL_CHARS;
 use rustc_ast as ast;
 use rustc_data_structures::fx::FxHashMap;
-use rustc_data_structures::sync;
-use rustc_errors::{struct_span_err, Applicability, SuggestionStyle};
+use rustc_data_structures::stable_hasher::HashStable;
+use rustc_data_structures::sync::{self, Lock, Lrc};
+use rustc_errors::{
+    struct_span_err, Applicability, Diagnostic, DiagnosticBuilder, SuggestionStyle,
+};
 use rustc_hir as hir;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::{CrateNum, DefId};
@@ -33,9 +37,12 @@
 use rustc_middle::ty::layout::{LayoutError, LayoutOfHelpers, TyAndLayout};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, print::Printer, subst::GenericArg, Ty, TyCtxt};
+use rustc_query_system::ich::StableHashingContext;
 use rustc_serialize::json::Json;
 use rustc_session::lint::{BuiltinLintDiagnostics, ExternDepSpec};
-use rustc_session::lint::{FutureIncompatibleInfo, Level, Lint, LintBuffer, LintId};
+use rustc_session::lint::{
+    FutureIncompatibleInfo, Level, Lint, LintBuffer, LintConfigValue, LintId,
+};
 use rustc_session::Session;
 use rustc_span::lev_distance::find_best_match_for_name;
 use rustc_span::{symbol::Symbol, BytePos, MultiSpan, Span, DUMMY_SP};
@@ -44,6 +51,7 @@
 
 use std::cell::Cell;
 use std::iter;
+use std::marker::PhantomData;
 use std::slice;
 
 /// Information about the registered lints.
@@ -65,6 +73,8 @@ pub struct LintStore {
     pub late_passes: Vec<Box<dyn Fn() -> LateLintPassObject + sync::Send + sync::Sync>>,
     /// This is unique in that we construct them per-module, so not once.
     pub late_module_passes: Vec<Box<dyn Fn() -> LateLintPassObject + sync::Send + sync::Sync>>,
+    /// The passes of `register_late_aggregating_pass`, with the type of their state erased.
+    pub late_aggregating_passes: Vec<Box<dyn LateAggregatingPass>>,
 
     /// Lints indexed by name.
     by_name: FxHashMap<String, TargetLint>,
@@ -73,6 +83,38 @@ pub struct LintStore {
     lint_groups: FxHashMap<&'static str, LintGroup>,
 }
 
+/// The state of a pass registered with [`LintStore::register_late_aggregating_pass`], like a map
+/// of the impls of a trait, that the instance checking a module fills in. The instance checking
+/// the crate gets the states of all the modules merged, to look at in `check_crate_post`.
+pub struct LintAggregate<S>(Lrc<Lock<S>>);
+
+impl<S> LintAggregate<S> {
+    crate fn new(state: S) -> Self {
+        LintAggregate(Lrc::new(Lock::new(state)))
+    }
+
+    /// Calls `f` with the state, which is locked during the call.
+    pub fn with<R>(&self, f: impl FnOnce(&mut S) -> R) -> R {
+        f(&mut self.0.lock())
+    }
+}
+
+impl<S> Clone for LintAggregate<S> {
+    fn clone(&self) -> Self {
+        LintAggregate(self.0.clone())
+    }
+}
+
+/// The type of the state of a pass registered with [`LintStore::register_late_aggregating_pass`].
+/// The states of the modules are the results of a query, whose `HashStable` implementation
+/// incremental compilation uses to tell whether they changed.
+pub trait LintAggregateState:
+    Default + for<'a> HashStable<StableHashingContext<'a>> + sync::Send + sync::Sync + 'static
+{
+    /// Adds the state of another module to this one.
+    fn merge(&mut self, other: &Self);
+}
+
 /// The target of the `by_name` map, which accounts for renaming/deprecation.
 #[derive(Debug)]
 enum TargetLint {
@@ -101,6 +143,9 @@ struct LintAlias {
     name: &'static str,
     /// Whether deprecation warnings should be suppressed for this alias.
     silent: bool,
+    /// Whether this alias is an old name of a renamed lint group, which is warned about with a
+    /// suggestion to use the new name instead.
+    renamed: bool,
 }
 
 struct LintGroup {
@@ -134,6 +179,7 @@ pub fn new() -> LintStore {
             early_passes: vec![],
             late_passes: vec![],
             late_module_passes: vec![],
+            late_aggregating_passes: vec![],
             by_name: Default::default(),
             lint_groups: Default::default(),
         }
@@ -185,6 +231,23 @@ pub fn register_late_mod_pass(
         self.late_module_passes.push(Box::new(pass));
     }
 
+    /// Registers a late lint pass that checks the crate as a whole, while still checking each
+    /// module on its own like the passes of `register_late_mod_pass`.
+    ///
+    /// The pass is constructed once per module, with a new [`LintAggregate`] where it stores
+    /// what it found in the module, and once more for the crate, with the states of all the
+    /// modules merged in the order of the modules, to have its `check_crate` and
+    /// `check_crate_post` methods called. The modules are checked by the `lint_aggregating_mod`
+    /// query, in parallel as `-Z threads` allows. As the states can't be stored in the
+    /// incremental cache, the query runs again on the modules that didn't change.
+    pub fn register_late_aggregating_pass<S: LintAggregateState>(
+        &mut self,
+        pass: impl Fn(LintAggregate<S>) -> LateLintPassObject + 'static + sync::Send + sync::Sync,
+    ) {
+        self.late_aggregating_passes
+            .push(Box::new(AggregatingPass { new_pass: pass, state: PhantomData }));
+    }
+
     // Helper method for register_early/late_pass
     pub fn register_lints(&mut self, lints: &[&'static Lint]) {
         for lint in lints {
@@ -230,11 +293,28 @@ pub fn register_group_alias(&mut self, lint_name: &'static str, alias: &'static
             LintGroup {
                 lint_ids: vec![],
                 from_plugin: false,
-                depr: Some(LintAlias { name: lint_name, silent: true }),
+                depr: Some(LintAlias { name: lint_name, silent: true, renamed: false }),
             },
         );
     }
 
+    /// This lint group has been renamed; warn about using the old name and apply the new group.
+    #[track_caller]
+    pub fn register_renamed_group(&mut self, old_name: &'static str, new_name: &'static str) {
+        let from_plugin = match self.lint_groups.get(new_name) {
+            Some(LintGroup { from_plugin, depr: None, .. }) => *from_plugin,
+            _ => bug!("invalid lint group renaming of {} to {}", old_name, new_name),
+        };
+        let alias = LintGroup {
+            lint_ids: vec![],
+            from_plugin,
+            depr: Some(LintAlias { name: new_name, silent: false, renamed: true }),
+        };
+        if self.lint_groups.insert(old_name, alias).is_some() {
+            bug!("duplicate specification of lint group {}", old_name);
+        }
+    }
+
     pub fn register_group(
         &mut self,
         from_plugin: bool,
@@ -252,7 +332,7 @@ pub fn register_group(
                 LintGroup {
                     lint_ids: vec![],
                     from_plugin,
-                    depr: Some(LintAlias { name, silent: false }),
+                    depr: Some(LintAlias { name, silent: false, renamed: false }),
                 },
             );
         }
@@ -326,9 +406,10 @@ pub fn check_lint_name_cmdline(
             )
             .emit();
         }
-        let db = match self.check_lint_name(sess, lint_name_only, tool_name, crate_attrs) {
+        let result = self.check_lint_name(sess, lint_name_only, tool_name, crate_attrs);
+        let db = match &result {
             CheckLintNameResult::Ok(_) => None,
-            CheckLintNameResult::Warning(ref msg, _) => Some(sess.struct_warn(msg)),
+            CheckLintNameResult::Warning(msg, _) => Some(sess.struct_warn(msg)),
             CheckLintNameResult::NoLint(suggestion) => {
                 let mut err =
                     struct_span_err!(sess, DUMMY_SP, E0602, "unknown lint: `{}`", lint_name);
@@ -362,6 +443,7 @@ pub fn check_lint_name_cmdline(
                 "requested on the command line with `{} {}`",
                 match level {
                     Level::Allow => "-A",
+                    Level::Expect(_) => unreachable!("`expect` can't be set on the command line"),
                     Level::Warn => "-W",
                     Level::ForceWarn => "--force-warn",
                     Level::Deny => "-D",
@@ -370,10 +452,58 @@ pub fn check_lint_name_cmdline(
                 lint_name
             );
             db.note(&msg);
+            if let CheckLintNameResult::Warning(_, Some(new_name)) = result {
+                db.help(&format!("use the new name `{}`", new_name));
+            }
             db.emit();
         }
     }
 
+    /// Checks a lint configuration value from the command line against the options the
+    /// lint declares. Unknown lints are already reported by `check_lint_name_cmdline`.
+    pub fn check_lint_config_cmdline(
+        &self,
+        sess: &Session,
+        lint_name: &str,
+        option_name: &str,
+        value: &str,
+    ) {
+        if self.is_lint_group(Symbol::intern(lint_name)) {
+            sess.err(&format!("lint group `{}` cannot be configured", lint_name));
+            return;
+        }
+        let lint = match self.find_lints(lint_name).as_deref() {
+            Ok(&[id]) => id.lint,
+            _ => return,
+        };
+        let option = match lint.config_option(option_name) {
+            Some(option) => option,
+            None => {
+                let mut err = sess.struct_err(&format!(
+                    "unknown configuration option `{}` for lint `{}`",
+                    option_name, lint_name
+                ));
+                if lint.config.is_empty() {
+                    err.note(&format!("lint `{}` has no configuration options", lint_name));
+                } else {
+                    let options: Vec<_> =
+                        lint.config.iter().map(|option| format!("`{}`", option.name)).collect();
+                    err.note(&format!("available options: {}", options.join(", ")));
+                }
+                err.emit();
+                return;
+            }
+        };
+        if option.kind.parse(value).is_none() {
+            sess.struct_err(&format!(
+                "invalid value `{}` for configuration option `{}` of lint `{}`",
+                value, option_name, lint_name
+            ))
+            .note(&format!("expected {}", option.kind.expected()))
+            .emit();
+        }
+    }
+
     /// True if this symbol represents a lint group name.
     pub fn is_lint_group(&self, lint_name: Symbol) -> bool {
         debug!(
@@ -431,6 +561,8 @@ pub fn check_lint_name(
                             CheckLintNameResult::Tool(Err((None, String::new())))
                         };
                     }
+                    // Renamed tool lint groups are handled like renamed rustc lint groups below.
+                    Some(LintGroup { depr: Some(LintAlias { renamed: true, .. }), .. }) => {}
                     Some(LintGroup { lint_ids, .. }) => {
                         return CheckLintNameResult::Tool(Ok(&lint_ids));
                     }
@@ -456,9 +588,17 @@ pub fn check_lint_name(
                 None => self.check_tool_name_for_backwards_compat(&complete_name, "clippy"),
                 Some(LintGroup { lint_ids, depr, .. }) => {
                     // Check if the lint group name is deprecated
-                    if let Some(LintAlias { name, silent }) = depr {
+                    if let Some(LintAlias { name, silent, renamed }) = depr {
                         let LintGroup { lint_ids, .. } = self.lint_groups.get(name).unwrap();
-                        return if *silent {
+                        return if *renamed {
+                            CheckLintNameResult::Warning(
+                                format!(
+                                    "lint group `{}` has been renamed to `{}`",
+                                    complete_name, name
+                                ),
+                                Some((*name).to_string()),
+                            )
+                        } else if *silent {
                             CheckLintNameResult::Ok(&lint_ids)
                         } else {
                             CheckLintNameResult::Tool(Err((Some(&lint_ids), (*name).to_string())))
@@ -499,7 +639,7 @@ fn check_tool_name_for_backwards_compat(
                 None => self.no_lint_suggestion(lint_name),
                 Some(LintGroup { lint_ids, depr, .. }) => {
                     // Reaching this would be weird, but let's cover this case anyway
-                    if let Some(LintAlias { name, silent }) = depr {
+                    if let Some(LintAlias { name, silent, .. }) = depr {
                         let LintGroup { lint_ids, .. } = self.lint_groups.get(name).unwrap();
                         return if *silent {
                             CheckLintNameResult::Tool(Err((Some(&lint_ids), complete_name)))
@@ -551,6 +691,10 @@ pub struct LateContext<'tcx> {
 
     /// We are only looking at one module
     pub only_module: bool,
+
+    /// Where the lints are buffered instead of being emitted, when the pass runs concurrently
+    /// with others.
+    pub(super) lint_buffer: Option<Lrc<Lock<Vec<Diagnostic>>>>,
 }
 
 /// Context for lint checking of the AST, after expansion, before lowering to
@@ -790,6 +934,11 @@ fn struct_span_lint<S: Into<MultiSpan>>(
     fn lint(&self, lint: &'static Lint, decorate: impl for<'a> FnOnce(LintDiagnosticBuilder<'a>)) {
         self.lookup(lint, None as Option<Span>, decorate);
     }
+    /// Gets the value of the configuration option `name` of `lint` given on the command
+    /// line, e.g., with `-W large-assignments=limit=4096`.
+    fn lint_config(&self, lint: &'static Lint, name: &str) -> Option<LintConfigValue> {
+        self.sess().lint_config(lint, name)
+    }
 }
 
 impl<'a> EarlyContext<'a> {
@@ -830,11 +979,7 @@ fn lookup<S: Into<MultiSpan>>(
         decorate: impl for<'a> FnOnce(LintDiagnosticBuilder<'a>),
     ) {
         let hir_id = self.last_node_with_lint_attrs;
-
-        match span {
-            Some(s) => self.tcx.struct_span_lint_hir(lint, hir_id, s, decorate),
-            None => self.tcx.struct_lint_node(lint, hir_id, decorate),
-        }
+        self.lint_at_node(lint, hir_id, span.map(|s| s.into()), decorate);
     }
 }
 
@@ -861,6 +1006,49 @@ fn lookup<S: Into<MultiSpan>>(
 }
 
 impl<'tcx> LateContext<'tcx> {
+    /// Emits a lint at the node `hir_id`, with its lint level, rather than at the node being
+    /// visited like `struct_span_lint` does.
+    pub fn struct_span_lint_hir(
+        &self,
+        lint: &'static Lint,
+        hir_id: hir::HirId,
+        span: impl Into<MultiSpan>,
+        decorate: impl for<'a> FnOnce(LintDiagnosticBuilder<'a>),
+    ) {
+        self.lint_at_node(lint, hir_id, Some(span.into()), decorate);
+    }
+
+    /// Emits a lint at the node `hir_id`, or buffers it when the pass runs concurrently with
+    /// others.
+    fn lint_at_node(
+        &self,
+        lint: &'static Lint,
+        hir_id: hir::HirId,
+        span: Option<MultiSpan>,
+        decorate: impl for<'a> FnOnce(LintDiagnosticBuilder<'a>),
+    ) {
+        let decorate = |lint: LintDiagnosticBuilder<'_>| match &self.lint_buffer {
+            Some(buffer) => decorate(lint.buffer_emission(buffer)),
+            None => decorate(lint),
+        };
+
+        match span {
+            Some(s) => self.tcx.struct_span_lint_hir(lint, hir_id, s, decorate),
+            None => self.tcx.struct_lint_node(lint, hir_id, decorate),
+        }
+    }
+
+    /// Creates an error that a pass reports besides its lints, which is buffered like them when
+    /// the pass runs concurrently with others. Errors created through the session directly are
+    /// emitted right away, and so interleave with the ones of the other passes.
+    pub fn struct_span_err(&self, span: impl Into<MultiSpan>, msg: &str) -> DiagnosticBuilder<'_> {
+        let err = self.tcx.sess.struct_span_err(span, msg);
+        match &self.lint_buffer {
+            Some(buffer) => err.buffer_emission(buffer),
+            None => err,
+        }
+    }
+
     /// Gets the type-checking results for the current body,
     /// or `None` if outside a body.
     pub fn maybe_typeck_results(&self) -> Option<&'tcx ty::TypeckResults<'tcx>> {
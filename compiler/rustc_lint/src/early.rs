@@ -57,7 +57,7 @@ fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f
         F: FnOnce(&mut Self),
     {
         let is_crate_node = id == ast::CRATE_NODE_ID;
-        let push = self.context.builder.push(attrs, &self.context.lint_store, is_crate_node);
+        let push = self.context.builder.push(attrs, &self.context.lint_store, is_crate_node, None);
         self.check_id(id);
         self.enter_attrs(attrs);
         f(self);
@@ -373,7 +373,7 @@ pub fn check_ast_crate<T: EarlyLintPass>(
     let mut passes: Vec<_> = passes.iter().map(|p| (p)()).collect();
     let mut buffered = lint_buffer.unwrap_or_default();
 
-    if !sess.opts.debugging_opts.no_interleave_lints {
+    if !sess.no_interleave_lints() {
         buffered = early_lint_crate(
             sess,
             lint_store,
@@ -396,9 +396,11 @@ pub fn check_ast_crate<T: EarlyLintPass>(
             );
         }
     } else {
+        let kind = if pre_expansion { "pre-expansion" } else { "early" };
         for (i, pass) in passes.iter_mut().enumerate() {
-            buffered =
-                sess.prof.extra_verbose_generic_activity("run_lint", pass.name()).run(|| {
+            let name = pass.name();
+            buffered = sess.prof.extra_verbose_generic_activity("run_lint", name).run(|| {
+                sess.time_lint_pass(kind, name, || {
                     early_lint_crate(
                         sess,
                         lint_store,
@@ -408,7 +410,8 @@ pub fn check_ast_crate<T: EarlyLintPass>(
                         buffered,
                         pre_expansion && i == 0,
                     )
-                });
+                })
+            });
         }
     }
 
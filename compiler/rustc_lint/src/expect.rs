@@ -0,0 +1,92 @@
+//! Checks the lint expectations of the `#[expect]` attributes (RFC 2383) once the lints have
+//! been emitted, and writes the report of `-Z lint-expectations-json`.
+//!
+//! An expectation is fulfilled by emitting the diagnostic of a lint at its `Expect` level, which
+//! the diagnostics handler records. The diagnostics of the lints emitted by a query are stored
+//! with its result and replayed when the result is reused, so the expectations they fulfill are
+//! also fulfilled in incremental sessions that don't run the query again.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_middle::lint::{struct_lint_level, LintExpectation};
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::lint::builtin::UNFULFILLED_LINT_EXPECTATIONS;
+use rustc_session::lint::LintExpectationId;
+use rustc_span::Span;
+
+use std::collections::BTreeMap;
+
+/// Emits `unfulfilled_lint_expectations` for each expected lint that wasn't emitted.
+///
+/// This must run after every pass that emits lints of the crate's code.
+pub fn check_expectations(tcx: TyCtxt<'_>) {
+    let lint_levels = tcx.lint_levels(());
+    let fulfilled = tcx.sess.diagnostic().fulfilled_expectations();
+
+    for expectation in &lint_levels.expectations {
+        if is_fulfilled(&fulfilled, expectation) {
+            continue;
+        }
+        let lint = UNFULFILLED_LINT_EXPECTATIONS;
+        let (level, src) =
+            lint_levels.sets.get_lint_level(lint, expectation.lint_stack_index, None, tcx.sess);
+        struct_lint_level(tcx.sess, lint, level, src, Some(expectation.span.into()), |lint| {
+            let mut err = lint.build(&format!(
+                "this lint expectation is unfulfilled: `{}` wasn't emitted",
+                expectation.lint_name
+            ));
+            if let Some(reason) = expectation.reason {
+                err.note(&reason.as_str());
+            }
+            err.emit();
+        });
+    }
+
+    if tcx.sess.opts.debugging_opts.lint_expectations_json {
+        let expectations = lint_levels
+            .expectations
+            .iter()
+            .map(|expectation| {
+                expectation_json(tcx, expectation, is_fulfilled(&fulfilled, expectation))
+            })
+            .collect();
+        let mut obj = BTreeMap::new();
+        obj.insert("lint_expectations".to_string(), Json::Array(expectations));
+        eprintln!("{}", Json::Object(obj));
+    }
+}
+
+/// Returns whether a lint was emitted at the level of `expectation`, either by the early lint
+/// passes, which only know the attribute of the expectation, or after lowering.
+fn is_fulfilled(fulfilled: &FxHashSet<LintExpectationId>, expectation: &LintExpectation) -> bool {
+    let LintExpectationId::Stable { lint_index, .. } = expectation.id else {
+        unreachable!("the expectations of the HIR have stable ids")
+    };
+    fulfilled.contains(&expectation.id)
+        || fulfilled.contains(&LintExpectationId::Unstable {
+            attr_id: expectation.attr_id,
+            lint_index: Some(lint_index),
+        })
+}
+
+fn expectation_json(tcx: TyCtxt<'_>, expectation: &LintExpectation, fulfilled: bool) -> Json {
+    let mut obj = BTreeMap::new();
+    obj.insert("fulfilled".to_string(), fulfilled.to_json());
+    obj.insert("lint".to_string(), expectation.lint_name.to_string().to_json());
+    obj.insert("reason".to_string(), expectation.reason.map(|reason| reason.to_string()).to_json());
+    obj.insert("span".to_string(), span_json(tcx, expectation.span));
+    Json::Object(obj)
+}
+
+fn span_json(tcx: TyCtxt<'_>, span: Span) -> Json {
+    let source_map = tcx.sess.source_map();
+    let lo = source_map.lookup_char_pos(span.lo());
+    let hi = source_map.lookup_char_pos(span.hi());
+    let mut obj = BTreeMap::new();
+    obj.insert("file".to_string(), lo.file.name.prefer_remapped().to_string().to_json());
+    obj.insert("line_start".to_string(), lo.line.to_json());
+    obj.insert("column_start".to_string(), (lo.col.0 + 1).to_json());
+    obj.insert("line_end".to_string(), hi.line.to_json());
+    obj.insert("column_end".to_string(), (hi.col.0 + 1).to_json());
+    Json::Object(obj)
+}
@@ -15,13 +15,17 @@
 //! for all lint attributes.
 
 use crate::{passes::LateLintPassObject, LateContext, LateLintPass, LintStore};
+use crate::{LintAggregate, LintAggregateState};
 use rustc_ast as ast;
-use rustc_data_structures::sync::join;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_data_structures::sync::{self, join, par_for_each_in, Lock, Lrc};
+use rustc_errors::Diagnostic;
 use rustc_hir as hir;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit as hir_visit;
 use rustc_hir::intravisit::Visitor;
 use rustc_middle::hir::map::Map;
+use rustc_middle::lint::{ErasedLintAggregate, ModuleLintAggregates};
 use rustc_middle::ty::{self, TyCtxt};
 use rustc_session::lint::LintPass;
 use rustc_span::symbol::Symbol;
@@ -29,6 +33,8 @@
 
 use std::any::Any;
 use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem;
 use std::slice;
 use tracing::debug;
 
@@ -379,6 +385,7 @@ fn late_lint_mod_pass<'tcx, T: LateLintPass<'tcx>>(
     tcx: TyCtxt<'tcx>,
     module_def_id: LocalDefId,
     pass: T,
+    lint_buffer: Option<Lrc<Lock<Vec<Diagnostic>>>>,
 ) {
     let access_levels = &tcx.privacy_access_levels(());
 
@@ -392,6 +399,7 @@ fn late_lint_mod_pass<'tcx, T: LateLintPass<'tcx>>(
         last_node_with_lint_attrs: tcx.hir().local_def_id_to_hir_id(module_def_id),
         generics: None,
         only_module: true,
+        lint_buffer,
     };
 
     let mut cx = LateContextAndPass { context, pass };
@@ -412,22 +420,27 @@ pub fn late_lint_mod<'tcx, T: LateLintPass<'tcx>>(
     module_def_id: LocalDefId,
     builtin_lints: T,
 ) {
-    if tcx.sess.opts.debugging_opts.no_interleave_lints {
+    if tcx.sess.no_interleave_lints() {
         // These passes runs in late_lint_crate with -Z no_interleave_lints
         return;
     }
 
-    late_lint_mod_pass(tcx, module_def_id, builtin_lints);
+    late_lint_mod_pass(tcx, module_def_id, builtin_lints, None);
 
     let mut passes: Vec<_> =
         unerased_lint_store(tcx).late_module_passes.iter().map(|pass| (pass)()).collect();
 
     if !passes.is_empty() {
-        late_lint_mod_pass(tcx, module_def_id, LateLintPassObjects { lints: &mut passes[..] });
+        let lints = LateLintPassObjects { lints: &mut passes[..] };
+        late_lint_mod_pass(tcx, module_def_id, lints, None);
     }
 }
 
-fn late_lint_pass_crate<'tcx, T: LateLintPass<'tcx>>(tcx: TyCtxt<'tcx>, pass: T) {
+fn late_lint_pass_crate<'tcx, T: LateLintPass<'tcx>>(
+    tcx: TyCtxt<'tcx>,
+    pass: T,
+    lint_buffer: Option<Lrc<Lock<Vec<Diagnostic>>>>,
+) {
     let access_levels = &tcx.privacy_access_levels(());
 
     let context = LateContext {
@@ -440,6 +453,7 @@ fn late_lint_pass_crate<'tcx, T: LateLintPass<'tcx>>(tcx: TyCtxt<'tcx>, pass: T)
         last_node_with_lint_attrs: hir::CRATE_HIR_ID,
         generics: None,
         only_module: false,
+        lint_buffer,
     };
 
     let mut cx = LateContextAndPass { context, pass };
@@ -458,16 +472,20 @@ fn late_lint_pass_crate<'tcx, T: LateLintPass<'tcx>>(tcx: TyCtxt<'tcx>, pass: T)
 fn late_lint_crate<'tcx, T: LateLintPass<'tcx>>(tcx: TyCtxt<'tcx>, builtin_lints: T) {
     let mut passes = unerased_lint_store(tcx).late_passes.iter().map(|p| (p)()).collect::<Vec<_>>();
 
-    if !tcx.sess.opts.debugging_opts.no_interleave_lints {
+    if !tcx.sess.no_interleave_lints() {
         if !passes.is_empty() {
-            late_lint_pass_crate(tcx, LateLintPassObjects { lints: &mut passes[..] });
+            late_lint_pass_crate(tcx, LateLintPassObjects { lints: &mut passes[..] }, None);
         }
 
-        late_lint_pass_crate(tcx, builtin_lints);
+        late_lint_pass_crate(tcx, builtin_lints, None);
     } else {
         for pass in &mut passes {
-            tcx.sess.prof.extra_verbose_generic_activity("run_late_lint", pass.name()).run(|| {
-                late_lint_pass_crate(tcx, LateLintPassObjects { lints: slice::from_mut(pass) });
+            let name = pass.name();
+            tcx.sess.prof.extra_verbose_generic_activity("run_late_lint", name).run(|| {
+                tcx.sess.time_lint_pass("late", name, || {
+                    let lints = LateLintPassObjects { lints: slice::from_mut(pass) };
+                    late_lint_pass_crate(tcx, lints, None);
+                })
             });
         }
 
@@ -475,15 +493,164 @@ fn late_lint_crate<'tcx, T: LateLintPass<'tcx>>(tcx: TyCtxt<'tcx>, builtin_lints
             unerased_lint_store(tcx).late_module_passes.iter().map(|pass| (pass)()).collect();
 
         for pass in &mut passes {
-            tcx.sess.prof.extra_verbose_generic_activity("run_late_module_lint", pass.name()).run(
-                || {
-                    late_lint_pass_crate(tcx, LateLintPassObjects { lints: slice::from_mut(pass) });
-                },
-            );
+            let name = pass.name();
+            tcx.sess.prof.extra_verbose_generic_activity("run_late_module_lint", name).run(|| {
+                tcx.sess.time_lint_pass("late module", name, || {
+                    let lints = LateLintPassObjects { lints: slice::from_mut(pass) };
+                    late_lint_pass_crate(tcx, lints, None);
+                })
+            });
         }
     }
 }
 
+/// Runs the late lint passes over the whole crate concurrently, as `-Z threads` allows, each of
+/// them on its own like with `-Z no-interleave-lints`. The builtin passes are registered apart in
+/// the `LintStore` for this. The lints of each pass are buffered, and emitted once all of them
+/// are done, pass after pass in the order they were registered in, so that the diagnostics don't
+/// depend on how the passes were scheduled. Only the diagnostics created through the
+/// `LateContext` are buffered: those that passes create through the session or the queries they
+/// run are emitted as soon as they are.
+fn late_lint_crate_parallel(tcx: TyCtxt<'_>) {
+    let mut passes: Vec<_> = unerased_lint_store(tcx)
+        .late_passes
+        .iter()
+        .map(|pass| ((pass)(), Lrc::new(Lock::new(Vec::new()))))
+        .collect();
+
+    par_for_each_in(&mut passes, |(pass, lint_buffer)| {
+        let name = pass.name();
+        tcx.sess.prof.extra_verbose_generic_activity("run_late_lint", name).run(|| {
+            let lints = LateLintPassObjects { lints: slice::from_mut(pass) };
+            late_lint_pass_crate(tcx, lints, Some(lint_buffer.clone()));
+        });
+    });
+
+    let handler = tcx.sess.diagnostic();
+    for (_, lint_buffer) in passes {
+        for diagnostic in lint_buffer.lock().drain(..) {
+            handler.emit_diagnostic(&diagnostic);
+        }
+    }
+}
+
+/// A pass registered with `LintStore::register_late_aggregating_pass`, with the type of its
+/// state erased.
+pub trait LateAggregatingPass: sync::Send + sync::Sync {
+    /// Runs an instance of the pass on a module, with a new state, buffering its lints in
+    /// `lint_buffer`, and returns the state it aggregated.
+    fn check_module(
+        &self,
+        tcx: TyCtxt<'_>,
+        module_def_id: LocalDefId,
+        lint_buffer: Lrc<Lock<Vec<Diagnostic>>>,
+    ) -> ErasedLintAggregate;
+
+    /// Runs the `check_crate` and `check_crate_post` methods of the instance of the pass that
+    /// gets the states of all the modules, merged in the order they are given in.
+    fn check_crate(&self, tcx: TyCtxt<'_>, states: &[&ErasedLintAggregate]);
+}
+
+crate struct AggregatingPass<S, F> {
+    crate new_pass: F,
+    crate state: PhantomData<fn() -> S>,
+}
+
+impl<S, F> LateAggregatingPass for AggregatingPass<S, F>
+where
+    S: LintAggregateState,
+    F: Fn(LintAggregate<S>) -> LateLintPassObject + sync::Send + sync::Sync,
+{
+    fn check_module(
+        &self,
+        tcx: TyCtxt<'_>,
+        module_def_id: LocalDefId,
+        lint_buffer: Lrc<Lock<Vec<Diagnostic>>>,
+    ) -> ErasedLintAggregate {
+        let aggregate = LintAggregate::new(S::default());
+        let mut passes = [(self.new_pass)(aggregate.clone())];
+        let lints = LateLintPassObjects { lints: &mut passes[..] };
+        late_lint_mod_pass(tcx, module_def_id, lints, Some(lint_buffer));
+
+        let state = aggregate.with(mem::take);
+        let mut hasher = StableHasher::new();
+        state.hash_stable(&mut tcx.create_stable_hashing_context(), &mut hasher);
+        ErasedLintAggregate { state: Box::new(state), fingerprint: hasher.finish() }
+    }
+
+    fn check_crate(&self, tcx: TyCtxt<'_>, states: &[&ErasedLintAggregate]) {
+        let mut merged = S::default();
+        for state in states {
+            merged.merge(state.state.downcast_ref().unwrap());
+        }
+
+        let mut pass = (self.new_pass)(LintAggregate::new(merged));
+        let name = pass.name();
+        tcx.sess.prof.extra_verbose_generic_activity("run_late_aggregating_lint", name).run(|| {
+            let context = LateContext {
+                tcx,
+                enclosing_body: None,
+                cached_typeck_results: Cell::new(None),
+                param_env: ty::ParamEnv::empty(),
+                access_levels: &tcx.privacy_access_levels(()),
+                lint_store: unerased_lint_store(tcx),
+                last_node_with_lint_attrs: hir::CRATE_HIR_ID,
+                generics: None,
+                only_module: false,
+                lint_buffer: None,
+            };
+            let pass = LateLintPassObjects { lints: slice::from_mut(&mut pass) };
+            let mut cx = LateContextAndPass { context, pass };
+            cx.with_lint_attrs(hir::CRATE_HIR_ID, |cx| {
+                lint_callback!(cx, check_crate,);
+                lint_callback!(cx, check_crate_post,);
+            });
+        });
+    }
+}
+
+/// Runs the passes of `LintStore::register_late_aggregating_pass` on a module, buffering their
+/// lints in the result.
+crate fn lint_aggregating_mod(tcx: TyCtxt<'_>, module_def_id: LocalDefId) -> ModuleLintAggregates {
+    let lint_buffer = Lrc::new(Lock::new(Vec::new()));
+    let states = unerased_lint_store(tcx)
+        .late_aggregating_passes
+        .iter()
+        .map(|pass| pass.check_module(tcx, module_def_id, lint_buffer.clone()))
+        .collect();
+    let lints = mem::take(&mut *lint_buffer.lock());
+    ModuleLintAggregates { states, lints }
+}
+
+/// Runs the passes of `LintStore::register_late_aggregating_pass`. The `lint_aggregating_mod`
+/// query checks the modules concurrently, as `-Z threads` allows, and their lints are emitted in
+/// the order of the modules once all of them are checked. Then the instance of each pass checking
+/// the crate gets the states of the modules, in the same order.
+fn late_lint_aggregating_passes(tcx: TyCtxt<'_>) {
+    let store = unerased_lint_store(tcx);
+    if store.late_aggregating_passes.is_empty() {
+        return;
+    }
+
+    tcx.hir().par_for_each_module(|module| {
+        tcx.lint_aggregating_mod(module);
+    });
+    let mut modules = Vec::new();
+    tcx.hir().for_each_module(|module| modules.push(tcx.lint_aggregating_mod(module)));
+
+    let handler = tcx.sess.diagnostic();
+    for module in &modules {
+        for diagnostic in &module.lints {
+            handler.emit_diagnostic(diagnostic);
+        }
+    }
+
+    for (i, pass) in store.late_aggregating_passes.iter().enumerate() {
+        let states: Vec<_> = modules.iter().map(|module| &module.states[i]).collect();
+        pass.check_crate(tcx, &states);
+    }
+}
+
 /// Performs lint checking on a crate.
 pub fn check_crate<'tcx, T: LateLintPass<'tcx>>(
     tcx: TyCtxt<'tcx>,
@@ -493,7 +660,12 @@ pub fn check_crate<'tcx, T: LateLintPass<'tcx>>(
         || {
             tcx.sess.time("crate_lints", || {
                 // Run whole crate non-incremental lints
-                late_lint_crate(tcx, builtin_lints());
+                if tcx.sess.parallel_lint_passes() {
+                    late_lint_crate_parallel(tcx);
+                } else {
+                    late_lint_crate(tcx, builtin_lints());
+                }
+                late_lint_aggregating_passes(tcx);
             });
         },
         || {
@@ -10,14 +10,14 @@
 use rustc_middle::lint::LevelAndSource;
 use rustc_middle::lint::LintDiagnosticBuilder;
 use rustc_middle::lint::{
-    struct_lint_level, LintLevelMap, LintLevelSets, LintLevelSource, LintSet, LintStackIndex,
-    COMMAND_LINE,
+    struct_lint_level, LintExpectation, LintLevelMap, LintLevelSets, LintLevelSource, LintSet,
+    LintStackIndex, COMMAND_LINE,
 };
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::lint::{
     builtin::{self, FORBIDDEN_LINT_GROUPS},
-    Level, Lint, LintId,
+    Level, Lint, LintExpectationId, LintId,
 };
 use rustc_session::parse::feature_err;
 use rustc_session::Session;
@@ -34,7 +34,7 @@ fn lint_levels(tcx: TyCtxt<'_>, (): ()) -> LintLevelMap {
 
     builder.levels.id_to_set.reserve(krate.owners.len() + 1);
 
-    let push = builder.levels.push(tcx.hir().attrs(hir::CRATE_HIR_ID), &store, true);
+    let push = builder.levels.push(crate_attrs, &store, true, Some(hir::CRATE_HIR_ID));
     builder.levels.register_id(hir::CRATE_HIR_ID);
     tcx.hir().walk_toplevel_module(&mut builder);
     builder.levels.pop(push);
@@ -42,12 +42,42 @@ fn lint_levels(tcx: TyCtxt<'_>, (): ()) -> LintLevelMap {
     builder.levels.build_map()
 }
 
+/// Computes the level of the lint named `lint_name` at the call site of a macro, given the lint
+/// level attributes of the nodes enclosing it, outermost first, for proc macros querying it
+/// through `proc_macro::lint_levels`.
+///
+/// Returns `None` for unknown lints and for lint groups.
+pub fn lint_level_at_expansion_site(
+    sess: &Session,
+    store: &LintStore,
+    crate_attrs: &[ast::Attribute],
+    enclosing_attrs: &[Vec<ast::Attribute>],
+    lint_name: &str,
+) -> Option<Level> {
+    let lint = match store.find_lints(lint_name) {
+        Ok(ids) if ids.len() == 1 && !store.is_lint_group(Symbol::intern(lint_name)) => ids[0].lint,
+        _ => return None,
+    };
+
+    // Unknown lints in the attributes and the problems with the command-line lint options are
+    // reported by the early lint passes, and the other problems with the attributes are
+    // deduplicated with the ones reported there.
+    let mut builder = LintLevelsBuilder::without_command_line_checks(sess, store, crate_attrs);
+    builder.push(crate_attrs, store, true, None);
+    for attrs in enclosing_attrs {
+        builder.push(attrs, store, false, None);
+    }
+    Some(builder.lint_level(lint).0)
+}
+
 pub struct LintLevelsBuilder<'s> {
     sess: &'s Session,
     sets: LintLevelSets,
     id_to_set: FxHashMap<HirId, LintStackIndex>,
+    expectations: Vec<LintExpectation>,
     cur: LintStackIndex,
     warn_about_weird_lints: bool,
+    check_command_line: bool,
     store: &'s LintStore,
     crate_attrs: &'s [ast::Attribute],
 }
@@ -63,13 +93,36 @@ pub fn new(
         warn_about_weird_lints: bool,
         store: &'s LintStore,
         crate_attrs: &'s [ast::Attribute],
+    ) -> Self {
+        Self::new_inner(sess, warn_about_weird_lints, true, store, crate_attrs)
+    }
+
+    /// Creates a builder that doesn't report unknown lints, nor the problems with the
+    /// command-line lint options, for computing lint levels after the other builders of the
+    /// session reported them.
+    pub(crate) fn without_command_line_checks(
+        sess: &'s Session,
+        store: &'s LintStore,
+        crate_attrs: &'s [ast::Attribute],
+    ) -> Self {
+        Self::new_inner(sess, false, false, store, crate_attrs)
+    }
+
+    fn new_inner(
+        sess: &'s Session,
+        warn_about_weird_lints: bool,
+        check_command_line: bool,
+        store: &'s LintStore,
+        crate_attrs: &'s [ast::Attribute],
     ) -> Self {
         let mut builder = LintLevelsBuilder {
             sess,
             sets: LintLevelSets::new(),
             cur: COMMAND_LINE,
             id_to_set: Default::default(),
+            expectations: Vec::new(),
             warn_about_weird_lints,
+            check_command_line,
             store,
             crate_attrs,
         };
@@ -82,8 +135,27 @@ fn process_command_line(&mut self, sess: &Session, store: &LintStore) {
         let mut specs = FxHashMap::default();
         self.sets.lint_cap = sess.opts.lint_cap.unwrap_or(Level::Forbid);
 
+        // A later cap of a group takes precedence over an earlier one for the lints they share.
+        for &(ref group, cap) in &sess.opts.lint_group_caps {
+            let ids = match store.find_lints(group) {
+                Ok(ids) if store.is_lint_group(Symbol::intern(group)) => ids,
+                _ => {
+                    // Like unknown lint attributes, unknown groups are only reported once.
+                    if self.warn_about_weird_lints {
+                        sess.err(&format!("unknown lint group `{}` in `--cap-lints-group`", group));
+                    }
+                    continue;
+                }
+            };
+            for id in ids {
+                self.sets.lint_group_caps.insert(id, cap);
+            }
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
-            store.check_lint_name_cmdline(sess, &lint_name, level, self.crate_attrs);
+            if self.check_command_line {
+                store.check_lint_name_cmdline(sess, &lint_name, level, self.crate_attrs);
+            }
             let orig_level = level;
             let lint_flag_val = Symbol::intern(lint_name);
 
@@ -97,12 +169,21 @@ fn process_command_line(&mut self, sess: &Session, store: &LintStore) {
                     continue;
                 }
 
-                self.check_gated_lint(id, DUMMY_SP);
+                if self.check_command_line {
+                    self.check_gated_lint(id, DUMMY_SP);
+                }
                 let src = LintLevelSource::CommandLine(lint_flag_val, orig_level);
                 specs.insert(id, (level, src));
             }
         }
 
+        // Like unknown lint attributes, configuration errors are only reported once.
+        if self.warn_about_weird_lints {
+            for (lint_name, option, value) in &sess.opts.lint_config {
+                store.check_lint_config_cmdline(sess, lint_name, option, value);
+            }
+        }
+
         self.cur = self.sets.list.push(LintSet { specs, parent: COMMAND_LINE });
     }
 
@@ -216,18 +297,25 @@ fn insert_spec(
     /// * Lint attributes are validated, e.g., a `#[forbid]` can't be switched to
     ///   `#[allow]`
     ///
+    /// The `#[expect]` attributes get stable ids if `source_hir_id`, the node of the attributes,
+    /// is known, and unstable ones otherwise.
+    ///
     /// Don't forget to call `pop`!
     pub(crate) fn push(
         &mut self,
         attrs: &[ast::Attribute],
         store: &LintStore,
         is_crate_node: bool,
+        source_hir_id: Option<HirId>,
     ) -> BuilderPush {
         let mut specs = FxHashMap::default();
+        // The ids, lint names and spans of the `#[expect]` attributes, which are recorded once
+        // the lint set of the attributes is known.
+        let mut expectations = Vec::new();
         let sess = self.sess;
         let bad_attr = |span| struct_span_err!(sess, span, E0452, "malformed lint attribute input");
-        for attr in attrs {
-            let Some(level) = Level::from_symbol(attr.name_or_empty()) else {
+        for (attr_index, attr) in attrs.iter().enumerate() {
+            let Some(attr_level) = Level::from_attr(attr) else {
                 continue
             };
 
@@ -281,7 +369,24 @@ pub(crate) fn push(
                 }
             }
 
-            for li in metas {
+            for (lint_index, li) in metas.into_iter().enumerate() {
+                let level = match attr_level {
+                    Level::Expect(LintExpectationId::Unstable { attr_id, .. }) => {
+                        let lint_index = lint_index.try_into().unwrap();
+                        Level::Expect(match source_hir_id {
+                            Some(hir_id) => LintExpectationId::Stable {
+                                hir_id,
+                                attr_index: attr_index.try_into().unwrap(),
+                                lint_index,
+                            },
+                            None => LintExpectationId::Unstable {
+                                attr_id,
+                                lint_index: Some(lint_index),
+                            },
+                        })
+                    }
+                    level => level,
+                };
                 let sp = li.span();
                 let mut meta_item = match li {
                     ast::NestedMetaItem::MetaItem(meta_item) if meta_item.is_word() => meta_item,
@@ -322,6 +427,9 @@ pub(crate) fn push(
                             self.check_gated_lint(id, attr.span);
                             self.insert_spec(&mut specs, id, (level, src));
                         }
+                        if let Level::Expect(id) = level {
+                            expectations.push((id, attr.id, src.name(), sp, reason));
+                        }
                     }
 
                     CheckLintNameResult::Tool(result) => {
@@ -337,6 +445,9 @@ pub(crate) fn push(
                                 for id in ids {
                                     self.insert_spec(&mut specs, *id, (level, src));
                                 }
+                                if let Level::Expect(id) = level {
+                                    expectations.push((id, attr.id, src.name(), sp, reason));
+                                }
                             }
                             Err((Some(ids), ref new_lint_name)) => {
                                 let lint = builtin::RENAMED_AND_REMOVED_LINTS;
@@ -373,6 +484,9 @@ pub(crate) fn push(
                                 for id in ids {
                                     self.insert_spec(&mut specs, *id, (level, src));
                                 }
+                                if let Level::Expect(id) = level {
+                                    expectations.push((id, attr.id, src.name(), sp, reason));
+                                }
                             }
                             Err((None, _)) => {
                                 // If Tool(Err(None, _)) is returned, then either the lint does not
@@ -466,6 +580,9 @@ pub(crate) fn push(
                             self.check_gated_lint(id, attr.span);
                             self.insert_spec(&mut specs, id, (level, src));
                         }
+                        if let Level::Expect(id) = level {
+                            expectations.push((id, attr.id, src.name(), sp, reason));
+                        }
                     } else {
                         panic!("renamed lint does not exist: {}", new_name);
                     }
@@ -510,6 +627,17 @@ pub(crate) fn push(
         if !specs.is_empty() {
             self.cur = self.sets.list.push(LintSet { specs, parent: prev });
         }
+        let lint_stack_index = self.cur;
+        self.expectations.extend(expectations.into_iter().map(
+            |(id, attr_id, lint_name, span, reason)| LintExpectation {
+                id,
+                attr_id,
+                span,
+                lint_name,
+                reason,
+                lint_stack_index,
+            },
+        ));
 
         BuilderPush { prev, changed: prev != self.cur }
     }
@@ -558,7 +686,7 @@ pub fn register_id(&mut self, id: HirId) {
     }
 
     pub fn build_map(self) -> LintLevelMap {
-        LintLevelMap { sets: self.sets, id_to_set: self.id_to_set }
+        LintLevelMap { sets: self.sets, id_to_set: self.id_to_set, expectations: self.expectations }
     }
 }
 
@@ -589,7 +717,7 @@ fn with_lint_attrs<F>(&mut self, id: hir::HirId, f: F)
     {
         let is_crate_hir = id == hir::CRATE_HIR_ID;
         let attrs = self.tcx.hir().attrs(id);
-        let push = self.levels.push(attrs, self.store, is_crate_hir);
+        let push = self.levels.push(attrs, self.store, is_crate_hir, Some(id));
         if push.changed {
             self.levels.register_id(id);
         }
@@ -47,6 +47,7 @@
 mod context;
 mod early;
 mod enum_intrinsics_non_enums;
+mod expect;
 pub mod hidden_unicode_codepoints;
 mod internal;
 mod late;
@@ -57,6 +58,7 @@
 mod nonstandard_style;
 mod noop_method_call;
 mod passes;
+pub mod plugin;
 mod redundant_semicolon;
 mod traits;
 mod types;
@@ -72,6 +74,7 @@
 use rustc_session::lint::builtin::{
     BARE_TRAIT_OBJECTS, ELIDED_LIFETIMES_IN_PATHS, EXPLICIT_OUTLIVES_REQUIREMENTS,
 };
+use rustc_session::Session;
 use rustc_span::symbol::{Ident, Symbol};
 use rustc_span::Span;
 
@@ -92,9 +95,14 @@
 
 /// Useful for other parts of the compiler / Clippy.
 pub use builtin::SoftLints;
-pub use context::{CheckLintNameResult, EarlyContext, LateContext, LintContext, LintStore};
+pub use context::{
+    CheckLintNameResult, EarlyContext, LateContext, LintAggregate, LintAggregateState,
+    LintContext, LintStore,
+};
 pub use early::check_ast_crate;
-pub use late::check_crate;
+pub use expect::check_expectations;
+pub use late::{check_crate, LateAggregatingPass};
+pub use levels::lint_level_at_expansion_site;
 pub use passes::{EarlyLintPass, LateLintPass};
 pub use rustc_session::lint::Level::{self, *};
 pub use rustc_session::lint::{BufferedEarlyLint, FutureIncompatibleInfo, Lint, LintId};
@@ -102,7 +110,8 @@
 
 pub fn provide(providers: &mut Providers) {
     levels::provide(providers);
-    *providers = Providers { lint_mod, ..*providers };
+    *providers =
+        Providers { lint_mod, lint_aggregating_mod: late::lint_aggregating_mod, ..*providers };
 }
 
 fn lint_mod(tcx: TyCtxt<'_>, module_def_id: LocalDefId) {
@@ -226,10 +235,10 @@ macro_rules! declare_combined_late_pass {
 
 late_lint_mod_passes!(declare_combined_late_pass, [BuiltinCombinedModuleLateLintPass]);
 
-pub fn new_lint_store(no_interleave_lints: bool, internal_lints: bool) -> LintStore {
+pub fn new_lint_store(sess: &Session, internal_lints: bool) -> LintStore {
     let mut lint_store = LintStore::new();
 
-    register_builtins(&mut lint_store, no_interleave_lints);
+    register_builtins(&mut lint_store, sess.no_interleave_lints(), sess.parallel_lint_passes());
     if internal_lints {
         register_internals(&mut lint_store);
     }
@@ -240,7 +249,7 @@ pub fn new_lint_store(no_interleave_lints: bool, internal_lints: bool) -> LintSt
 /// Tell the `LintStore` about all the built-in lints (the ones
 /// defined in this crate and the ones defined in
 /// `rustc_session::lint::builtin`).
-fn register_builtins(store: &mut LintStore, no_interleave_lints: bool) {
+fn register_builtins(store: &mut LintStore, no_interleave_lints: bool, parallel_lint_passes: bool) {
     macro_rules! add_lint_group {
         ($name:expr, $($lint:ident),*) => (
             store.register_group(false, $name, None, vec![$(LintId::of($lint)),*]);
@@ -271,7 +280,13 @@ macro_rules! register_passes {
         store.register_lints(&BuiltinCombinedPreExpansionLintPass::get_lints());
         store.register_lints(&BuiltinCombinedEarlyLintPass::get_lints());
         store.register_lints(&BuiltinCombinedModuleLateLintPass::get_lints());
-        store.register_lints(&BuiltinCombinedLateLintPass::get_lints());
+        // The late passes over the whole crate run concurrently with each other, which they can
+        // only do when they are apart.
+        if parallel_lint_passes {
+            late_lint_passes!(register_passes, register_late_pass);
+        } else {
+            store.register_lints(&BuiltinCombinedLateLintPass::get_lints());
+        }
     }
 
     add_lint_group!(
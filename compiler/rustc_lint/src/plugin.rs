@@ -0,0 +1,207 @@
+//! Loading of lint plugins, shared libraries passed with `-Z lint-plugin` that register lints
+//! and lint passes with the `LintStore`.
+//!
+//! A plugin declares itself with [`declare_lint_plugin!`], which exports a
+//! [`LintPluginDescriptor`] as the static `__rustc_lint_plugin`. The descriptor is a C struct
+//! whose first field, the version of this protocol the plugin was built for, keeps its name
+//! and type in every later version, so that it can be checked before anything else is read.
+//!
+//! Lint passes are Rust trait objects, so a plugin only works with the exact build of the
+//! compiler whose `rustc_lint` it was built against. This is checked twice: against the
+//! version of the compiler, and against an identifier of the `LintStore` type the plugin was
+//! compiled with, which also tells apart builds from modified sources with the same version.
+//! Only then is the `LintStore`, which the descriptor only sees as an opaque pointer, handed
+//! to the plugin.
+
+use crate::LintStore;
+
+use libloading::Library;
+
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::error;
+use std::ffi::c_void;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::slice;
+
+/// The version of the protocol between the compiler and lint plugins described in the
+/// module documentation.
+pub const LINT_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The version of the compiler this `rustc_lint` is part of, which a plugin must have been
+/// built against.
+pub const RUSTC_VERSION: &str = match option_env!("CFG_VERSION") {
+    Some(version) => version,
+    None => "unknown version",
+};
+
+/// What `declare_lint_plugin!` exports as `__rustc_lint_plugin`. Its layout only changes
+/// together with `LINT_PLUGIN_ABI_VERSION`.
+#[repr(C)]
+pub struct LintPluginDescriptor {
+    /// The `LINT_PLUGIN_ABI_VERSION` the plugin was built with.
+    pub abi_version: u32,
+    /// The UTF-8 `RUSTC_VERSION` the plugin was built against, `rustc_version_len` bytes long.
+    pub rustc_version: *const u8,
+    pub rustc_version_len: usize,
+    /// Returns the `lint_store_id` of the `LintStore` the plugin was compiled with.
+    pub lint_store_id: extern "C" fn() -> u64,
+    /// Registers the plugin's lints and passes with the `LintStore` behind `store`.
+    pub register: extern "C" fn(store: *mut c_void),
+}
+
+// The descriptor only points to static data.
+unsafe impl Sync for LintPluginDescriptor {}
+
+/// Identifies the `LintStore` type, given as `TypeId::of::<LintStore>()` by the caller so
+/// that it is computed when the caller is compiled. Differs between any two builds of
+/// `rustc_lint` whose types can't be mixed.
+pub fn lint_store_id(type_id: TypeId) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    type_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Declares the function `$register`, of type `fn(&mut LintStore)`, as the registrar of a
+/// lint plugin. The plugin must be built as a `dylib` rather than a `cdylib`, so that it
+/// shares the compiler's own copy of `rustc_lint` and the crates it depends on.
+///
+/// ```ignore (requires a `dylib` loaded by rustc)
+/// fn register(store: &mut LintStore) {
+///     store.register_lints(&[&MY_LINT]);
+///     store.register_late_pass(|| Box::new(MyLintPass));
+/// }
+///
+/// rustc_lint::declare_lint_plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! declare_lint_plugin {
+    ($register:path) => {
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static __rustc_lint_plugin: $crate::plugin::LintPluginDescriptor = {
+            extern "C" fn lint_store_id() -> u64 {
+                $crate::plugin::lint_store_id(::std::any::TypeId::of::<$crate::LintStore>())
+            }
+
+            extern "C" fn register(store: *mut ::std::ffi::c_void) {
+                // The compiler only calls this once `lint_store_id` matched its own.
+                $register(unsafe { &mut *(store as *mut $crate::LintStore) })
+            }
+
+            $crate::plugin::LintPluginDescriptor {
+                abi_version: $crate::plugin::LINT_PLUGIN_ABI_VERSION,
+                rustc_version: $crate::plugin::RUSTC_VERSION.as_ptr(),
+                rustc_version_len: $crate::plugin::RUSTC_VERSION.len(),
+                lint_store_id,
+                register,
+            }
+        };
+    };
+}
+
+#[derive(Debug)]
+pub enum LintPluginError {
+    CurrentDir(PathBuf, io::Error),
+    Load(PathBuf, libloading::Error),
+    AbiVersion(PathBuf, u32),
+    RustcVersion(PathBuf, String),
+    LintStore(PathBuf),
+}
+
+impl fmt::Display for LintPluginError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintPluginError::CurrentDir(path, err) => write!(
+                fmt,
+                "couldn't load lint plugin `{}`: couldn't get the current directory: {}",
+                path.display(),
+                err
+            ),
+            LintPluginError::Load(path, err) => {
+                write!(fmt, "couldn't load lint plugin `{}`: {}", path.display(), err)
+            }
+            LintPluginError::AbiVersion(path, version) => write!(
+                fmt,
+                "lint plugin `{}` uses version {} of the plugin ABI, but this compiler \
+                 only supports version {}",
+                path.display(),
+                version,
+                LINT_PLUGIN_ABI_VERSION
+            ),
+            LintPluginError::RustcVersion(path, version) => write!(
+                fmt,
+                "lint plugin `{}` was built for rustc {}, but this is rustc {}",
+                path.display(),
+                version,
+                RUSTC_VERSION
+            ),
+            LintPluginError::LintStore(path) => write!(
+                fmt,
+                "lint plugin `{}` was built against a different build of rustc {}",
+                path.display(),
+                RUSTC_VERSION
+            ),
+        }
+    }
+}
+
+impl error::Error for LintPluginError {}
+
+impl LintStore {
+    /// Loads the lint plugins at `paths` in order and lets each register its lints and
+    /// passes. Plugins are never unloaded, as the passes they register live as long as the
+    /// compiler.
+    pub fn load_plugin_registrars(&mut self, paths: &[PathBuf]) -> Result<(), LintPluginError> {
+        for path in paths {
+            let plugin = load_plugin(path)?;
+            (plugin.register)(self as *mut LintStore as *mut c_void);
+        }
+        Ok(())
+    }
+}
+
+fn load_plugin(path: &Path) -> Result<&'static LintPluginDescriptor, LintPluginError> {
+    // Make sure the path contains a / or the loader will search for it.
+    let path = &if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        let current_dir = env::current_dir()
+            .map_err(|err| LintPluginError::CurrentDir(path.to_path_buf(), err))?;
+        current_dir.join(path)
+    };
+    let load_err = |err| LintPluginError::Load(path.to_path_buf(), err);
+
+    let lib = unsafe { Library::new(path) }.map_err(load_err)?;
+
+    // Only the version, the first field of the descriptor, is known to have the same layout
+    // in every version of the protocol.
+    let abi_version = unsafe { lib.get::<*const u32>(b"__rustc_lint_plugin") }.map_err(load_err)?;
+    let abi_version = unsafe { **abi_version };
+    if abi_version != LINT_PLUGIN_ABI_VERSION {
+        return Err(LintPluginError::AbiVersion(path.to_path_buf(), abi_version));
+    }
+
+    let plugin = unsafe { lib.get::<*const LintPluginDescriptor>(b"__rustc_lint_plugin") }
+        .map_err(load_err)?;
+    let plugin = unsafe { &**plugin };
+    let rustc_version =
+        unsafe { slice::from_raw_parts(plugin.rustc_version, plugin.rustc_version_len) };
+    if rustc_version != RUSTC_VERSION.as_bytes() {
+        let rustc_version = String::from_utf8_lossy(rustc_version).into_owned();
+        return Err(LintPluginError::RustcVersion(path.to_path_buf(), rustc_version));
+    }
+    if (plugin.lint_store_id)() != lint_store_id(TypeId::of::<LintStore>()) {
+        return Err(LintPluginError::LintStore(path.to_path_buf()));
+    }
+
+    // Intentionally leak the library, like `#[plugin]` registrars, since the descriptor and
+    // the passes it registers must outlive any use of them.
+    mem::forget(lib);
+    Ok(plugin)
+}
@@ -2932,6 +2932,11 @@
     pub LARGE_ASSIGNMENTS,
     Warn,
     "detects large moves or copies",
+    @config = [crate::LintConfigOption {
+        name: "limit",
+        kind: crate::LintConfigKind::Number,
+        desc: "the size in bytes above which moves are linted, like `-Z move-size-limit`",
+    }];
 }
 
 declare_lint! {
@@ -3067,6 +3072,8 @@
         DEREF_INTO_DYN_SUPERTRAIT,
         DEPRECATED_CFG_ATTR_CRATE_TYPE_NAME,
         DUPLICATE_MACRO_ATTRIBUTES,
+        UNFULFILLED_LINT_EXPECTATIONS,
+        EXCESSIVE_MONOMORPHIZATIONS,
     ]
 }
 
@@ -3633,3 +3640,79 @@
     Warn,
     "duplicated attribute"
 }
+
+declare_lint! {
+    /// The `unfulfilled_lint_expectations` lint detects `#[expect]` attributes expecting a lint
+    /// that isn't emitted.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![feature(lint_reasons)]
+    ///
+    /// #[expect(unused_variables)]
+    /// fn main() {
+    ///     let x = 1;
+    ///     println!("{}", x);
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// `#[expect(lint)]` allows a lint like `#[allow(lint)]`, but also expects it to be emitted
+    /// in its scope, so that the attribute can be removed once the code no longer triggers the
+    /// lint. This lint is emitted for each lint named by an `#[expect]` attribute that wasn't
+    /// emitted, which usually means that the attribute isn't needed anymore.
+    pub UNFULFILLED_LINT_EXPECTATIONS,
+    Warn,
+    "unfulfilled lint expectation"
+}
+
+declare_lint! {
+    /// The `excessive_monomorphizations` lint detects generic functions that are instantiated
+    /// more times than the limit set with `-Z max-monomorphizations`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z max-monomorphizations=2)
+    /// fn show<T: std::fmt::Debug>(x: T) {
+    ///     println!("{:?}", x);
+    /// }
+    ///
+    /// fn main() {
+    ///     show(1u8);
+    ///     show(1u16);
+    ///     show(1u32);
+    /// }
+    /// ```
+    ///
+    /// produces:
+    ///
+    /// ```text
+    /// warning: `show` is instantiated 3 times, more than the limit of 2
+    ///  --> src/main.rs:1:1
+    ///   |
+    /// 1 | fn show<T: std::fmt::Debug>(x: T) {
+    ///   | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Each instantiation of a generic function is a separate copy of its code, so a function
+    /// instantiated with many different types can make up a large part of the binary. This
+    /// lint lists the sites that instantiated each of its copies, so that the instantiations
+    /// can be reduced, for example by moving the code that doesn't depend on the type
+    /// parameters into a non-generic function. It does nothing unless a limit is set, and
+    /// can be denied to enforce the limit.
+    pub EXCESSIVE_MONOMORPHIZATIONS,
+    Warn,
+    "detects generic functions with too many instantiations",
+    @config = [crate::LintConfigOption {
+        name: "limit",
+        kind: crate::LintConfigKind::Number,
+        desc: "the number of instantiations above which generic functions are linted, like \
+               `-Z max-monomorphizations`",
+    }];
+}
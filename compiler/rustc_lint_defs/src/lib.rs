@@ -3,7 +3,9 @@
 
 pub use self::Level::*;
 use rustc_ast::node_id::{NodeId, NodeMap};
+use rustc_ast::{AttrId, Attribute};
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher, ToStableHashKey};
+use rustc_hir::HirId;
 use rustc_serialize::json::Json;
 use rustc_span::edition::Edition;
 use rustc_span::{sym, symbol::Ident, MultiSpan, Span, Symbol};
@@ -46,23 +48,68 @@ pub enum Applicability {
     Unspecified,
 }
 
+/// Identifies a lint name of an `#[expect]` attribute (RFC 2383).
+///
+/// The lints emitted at the `Expect` level carry the id of the expectation they fulfill, and so
+/// do their diagnostics. The diagnostics stored with the result of a query thus fulfill the
+/// expectation again when the result is loaded in a later incremental session.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash, Encodable, Decodable)]
+pub enum LintExpectationId {
+    /// The id of an expectation of the early lint passes, which run before the nodes of the
+    /// crate have a `HirId`. It is only valid in the session that created it, and is matched
+    /// with the expectations of the HIR through the `AttrId` of the attribute.
+    ///
+    /// `lint_index` is `None` until the lint name of the attribute is known.
+    Unstable { attr_id: AttrId, lint_index: Option<u16> },
+    /// The id of an expectation of the HIR, which is valid across sessions.
+    Stable { hir_id: HirId, attr_index: u16, lint_index: u16 },
+}
+
+impl<HCX: rustc_hir::HashStableContext> HashStable<HCX> for LintExpectationId {
+    #[inline]
+    fn hash_stable(&self, hcx: &mut HCX, hasher: &mut StableHasher) {
+        match *self {
+            LintExpectationId::Stable { hir_id, attr_index, lint_index } => {
+                hir_id.hash_stable(hcx, hasher);
+                attr_index.hash_stable(hcx, hasher);
+                lint_index.hash_stable(hcx, hasher);
+            }
+            LintExpectationId::Unstable { .. } => {
+                unreachable!("an unstable `LintExpectationId` can't be hashed")
+            }
+        }
+    }
+}
+
 /// Setting for how to handle a lint.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 pub enum Level {
     Allow,
+    /// Set with `#[expect]` (RFC 2383): the lint is allowed, but expected to be emitted, and
+    /// `unfulfilled_lint_expectations` is emitted if it isn't.
+    Expect(LintExpectationId),
     Warn,
     ForceWarn,
     Deny,
     Forbid,
 }
 
-rustc_data_structures::impl_stable_hash_via_hash!(Level);
+impl<HCX: rustc_hir::HashStableContext> HashStable<HCX> for Level {
+    #[inline]
+    fn hash_stable(&self, hcx: &mut HCX, hasher: &mut StableHasher) {
+        std::mem::discriminant(self).hash_stable(hcx, hasher);
+        if let Level::Expect(id) = self {
+            id.hash_stable(hcx, hasher);
+        }
+    }
+}
 
 impl Level {
     /// Converts a level to a lower-case string.
     pub fn as_str(self) -> &'static str {
         match self {
             Level::Allow => "allow",
+            Level::Expect(_) => "expect",
             Level::Warn => "warn",
             Level::ForceWarn => "force-warn",
             Level::Deny => "deny",
@@ -81,10 +128,15 @@ pub fn from_str(x: &str) -> Option<Level> {
         }
     }
 
-    /// Converts a symbol to a level.
-    pub fn from_symbol(x: Symbol) -> Option<Level> {
-        match x {
+    /// Converts the name of a lint attribute to a level. The level of an `#[expect]` attribute
+    /// has an unstable id without a lint index.
+    pub fn from_attr(attr: &Attribute) -> Option<Level> {
+        match attr.name_or_empty() {
             sym::allow => Some(Level::Allow),
+            sym::expect => Some(Level::Expect(LintExpectationId::Unstable {
+                attr_id: attr.id,
+                lint_index: None,
+            })),
             sym::warn => Some(Level::Warn),
             sym::deny => Some(Level::Deny),
             sym::forbid => Some(Level::Forbid),
@@ -137,6 +189,81 @@ pub struct Lint {
     pub feature_gate: Option<Symbol>,
 
     pub crate_level_only: bool,
+
+    /// The configuration options of the lint, which are set on the command line together
+    /// with its level, as in `-W large-assignments=limit=4096`.
+    pub config: &'static [LintConfigOption],
+}
+
+/// A configuration option of a lint.
+#[derive(Copy, Clone, Debug)]
+pub struct LintConfigOption {
+    /// The name of the option, written with dashes, e.g., "limit".
+    pub name: &'static str,
+    pub kind: LintConfigKind,
+    pub desc: &'static str,
+}
+
+/// The type of the values of a lint configuration option.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LintConfigKind {
+    Bool,
+    Number,
+    String,
+}
+
+impl LintConfigKind {
+    pub fn parse(self, value: &str) -> Option<LintConfigValue> {
+        match self {
+            LintConfigKind::Bool => match value {
+                "y" | "yes" | "on" | "true" => Some(LintConfigValue::Bool(true)),
+                "n" | "no" | "off" | "false" => Some(LintConfigValue::Bool(false)),
+                _ => None,
+            },
+            LintConfigKind::Number => value.parse().ok().map(LintConfigValue::Number),
+            LintConfigKind::String => Some(LintConfigValue::String(value.to_string())),
+        }
+    }
+
+    /// Describes the values of this kind, for diagnostics.
+    pub fn expected(self) -> &'static str {
+        match self {
+            LintConfigKind::Bool => "a boolean (`yes`, `no`, `on`, `off`, etc)",
+            LintConfigKind::Number => "a non-negative integer",
+            LintConfigKind::String => "a string",
+        }
+    }
+}
+
+/// The value of a lint configuration option.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LintConfigValue {
+    Bool(bool),
+    Number(u64),
+    String(String),
+}
+
+impl LintConfigValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            LintConfigValue::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<u64> {
+        match *self {
+            LintConfigValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LintConfigValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
 }
 
 /// Extra information for a future incompatibility lint.
@@ -203,6 +330,7 @@ pub const fn default_fields_for_macro() -> Self {
             future_incompatible: None,
             feature_gate: None,
             crate_level_only: false,
+            config: &[],
         }
     }
 
@@ -217,6 +345,11 @@ pub fn default_level(&self, edition: Edition) -> Level {
             .map(|(_, l)| l)
             .unwrap_or(self.default_level)
     }
+
+    /// Gets the configuration option called `name`, if the lint has one.
+    pub fn config_option(&self, name: &str) -> Option<&'static LintConfigOption> {
+        self.config.iter().find(|option| option.name == name)
+    }
 }
 
 /// Identifies a lint known to the compiler.
@@ -446,6 +579,7 @@ macro_rules! declare_lint {
     );
     ($(#[$attr:meta])* $vis: vis $NAME: ident, $Level: ident, $desc: expr,
      $(@feature_gate = $gate:expr;)?
+     $(@config = [$($option:expr),* $(,)?];)?
      $(@future_incompatible = FutureIncompatibleInfo { $($field:ident : $val:expr),* $(,)*  }; )?
      $($v:ident),*) => (
         $(#[$attr])*
@@ -457,6 +591,7 @@ macro_rules! declare_lint {
             is_plugin: false,
             $($v: true,)*
             $(feature_gate: Some($gate),)*
+            $(config: &[$($option),*],)*
             $(future_incompatible: Some($crate::FutureIncompatibleInfo {
                 $($field: $val,)*
                 ..$crate::FutureIncompatibleInfo::default_fields_for_macro()
@@ -507,6 +642,7 @@ macro_rules! declare_tool_lint {
             is_plugin: true,
             feature_gate: None,
             crate_level_only: false,
+            config: &[],
         };
     );
 }
@@ -82,6 +82,8 @@ enum LLVMRustAttribute {
   StackProtectReq = 30,
   StackProtectStrong = 31,
   StackProtect = 32,
+  SafeStack = 33,
+  FnRetThunkExtern = 34,
 };
 
 typedef struct OpaqueRustString *RustStringRef;
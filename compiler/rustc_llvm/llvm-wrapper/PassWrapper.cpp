@@ -44,6 +44,7 @@
 #include "llvm/Transforms/Instrumentation/MemorySanitizer.h"
 #include "llvm/Transforms/Instrumentation/HWAddressSanitizer.h"
 #include "llvm/Transforms/Utils/CanonicalizeAliases.h"
+#include "llvm/Transforms/Utils/EntryExitInstrumenter.h"
 #include "llvm/Transforms/Utils/NameAnonGlobals.h"
 #include "llvm/Transforms/Utils.h"
 
@@ -424,6 +425,20 @@ extern "C" void LLVMRustPrintTargetCPUs(LLVMTargetMachineRef TM) {
   printf("\n");
 }
 
+extern "C" size_t LLVMRustGetTargetCPUsCount(LLVMTargetMachineRef TM) {
+  const TargetMachine *Target = unwrap(TM);
+  const MCSubtargetInfo *MCInfo = Target->getMCSubtargetInfo();
+  const ArrayRef<SubtargetSubTypeKV> CPUTable = MCInfo->getCPUTable();
+  return CPUTable.size();
+}
+
+extern "C" const char *LLVMRustGetTargetCPU(LLVMTargetMachineRef TM, size_t Index) {
+  const TargetMachine *Target = unwrap(TM);
+  const MCSubtargetInfo *MCInfo = Target->getMCSubtargetInfo();
+  const ArrayRef<SubtargetSubTypeKV> CPUTable = MCInfo->getCPUTable();
+  return CPUTable[Index].Key;
+}
+
 extern "C" size_t LLVMRustGetTargetFeaturesCount(LLVMTargetMachineRef TM) {
   const TargetMachine *Target = unwrap(TM);
   const MCSubtargetInfo *MCInfo = Target->getMCSubtargetInfo();
@@ -447,6 +462,14 @@ extern "C" void LLVMRustPrintTargetCPUs(LLVMTargetMachineRef) {
   printf("Target CPU help is not supported by this LLVM version.\n\n");
 }
 
+extern "C" size_t LLVMRustGetTargetCPUsCount(LLVMTargetMachineRef) {
+  return 0;
+}
+
+extern "C" const char *LLVMRustGetTargetCPU(LLVMTargetMachineRef, size_t) {
+  return nullptr;
+}
+
 extern "C" size_t LLVMRustGetTargetFeaturesCount(LLVMTargetMachineRef) {
   return 0;
 }
@@ -748,7 +771,7 @@ LLVMRustOptimizeWithNewPassManager(
     bool DisableSimplifyLibCalls, bool EmitLifetimeMarkers,
     LLVMRustSanitizerOptions *SanitizerOptions,
     const char *PGOGenPath, const char *PGOUsePath,
-    bool InstrumentCoverage, bool InstrumentGCOV,
+    bool InstrumentCoverage, bool InstrumentGCOV, bool InstrumentFunctionsPreInline,
     const char *PGOSampleUsePath, bool DebugInfoForProfiling,
     void* LlvmSelfProfiler,
     LLVMRustSelfProfileBeforePassCallback BeforePassCallback,
@@ -845,6 +868,19 @@ LLVMRustOptimizeWithNewPassManager(
     );
   }
 
+  // The calls of the functions marked with `instrument-function-entry` and
+  // `instrument-function-exit` are inserted before inlining, like Clang's
+  // `-finstrument-functions` does. Those marked with the `-inlined` variants
+  // are instrumented by the code generator, after inlining.
+  if (InstrumentFunctionsPreInline) {
+    PipelineStartEPCallbacks.push_back(
+      [](ModulePassManager &MPM, OptimizationLevel Level) {
+        MPM.addPass(createModuleToFunctionPassAdaptor(
+            EntryExitInstrumenterPass(/*PostInlining=*/false)));
+      }
+    );
+  }
+
   if (InstrumentCoverage) {
     PipelineStartEPCallbacks.push_back(
       [](ModulePassManager &MPM, OptimizationLevel Level) {
@@ -1051,6 +1087,15 @@ public:
 
   void emitFunctionAnnot(const Function *F,
                          formatted_raw_ostream &OS) override {
+    // Added by `-Z annotate-llvm-ir`.
+    if (MDNode *Annotation = F->getMetadata("rust.annotation")) {
+      for (const MDOperand &Op : Annotation->operands()) {
+        if (auto *Line = dyn_cast<MDString>(Op)) {
+          OS << "; " << Line->getString() << "\n";
+        }
+      }
+    }
+
     StringRef Demangled = CallDemangle(F->getName());
     if (Demangled.empty()) {
         return;
@@ -1186,6 +1231,12 @@ extern "C" void LLVMRustSetModulePIELevel(LLVMModuleRef M) {
   unwrap(M)->setPIELevel(PIELevel::Level::Large);
 }
 
+// Clang adds the flag with the error behavior, which modules linked together with LTO must
+// agree on.
+extern "C" void LLVMRustSetModuleSplitLTOUnit(LLVMModuleRef M) {
+  unwrap(M)->addModuleFlag(Module::Error, "EnableSplitLTOUnit", 1);
+}
+
 extern "C" void LLVMRustSetModuleCodeModel(LLVMModuleRef M,
                                            LLVMRustCodeModel Model) {
   auto CM = fromRust(Model);
@@ -6,10 +6,14 @@
 #include "llvm/IR/GlobalVariable.h"
 #include "llvm/IR/Instructions.h"
 #include "llvm/IR/Intrinsics.h"
+#include "llvm/IR/LLVMRemarkStreamer.h"
 #include "llvm/Object/Archive.h"
 #include "llvm/Object/COFFImportFile.h"
 #include "llvm/Object/ObjectFile.h"
 #include "llvm/Bitcode/BitcodeWriterPass.h"
+#include "llvm/Remarks/RemarkSerializer.h"
+#include "llvm/Remarks/RemarkStreamer.h"
+#include "llvm/Support/ToolOutputFile.h"
 #include "llvm/Support/Signals.h"
 #include "llvm/ADT/Optional.h"
 
@@ -220,6 +224,14 @@ static Attribute::AttrKind fromRust(LLVMRustAttribute Kind) {
     return Attribute::StackProtectStrong;
   case StackProtect:
     return Attribute::StackProtect;
+  case SafeStack:
+    return Attribute::SafeStack;
+  case FnRetThunkExtern:
+#if LLVM_VERSION_GE(15, 0)
+    return Attribute::FnRetThunkExtern;
+#else
+    report_fatal_error("FnRetThunkExtern attribute requires LLVM 15 or later");
+#endif
   }
   report_fatal_error("bad AttributeKind");
 }
@@ -721,6 +733,25 @@ extern "C" void LLVMRustAddModuleFlag(LLVMModuleRef M, const char *Name,
   unwrap(M)->addModuleFlag(Module::Warning, Name, Value);
 }
 
+extern "C" void LLVMRustSetModuleStackProtectorGuard(LLVMModuleRef M,
+                                                     const char *Kind,
+                                                     size_t KindLen) {
+#if LLVM_VERSION_GE(13, 0)
+  unwrap(M)->setStackProtectorGuard(StringRef(Kind, KindLen));
+#else
+  report_fatal_error("-Z stack-protector-guard requires LLVM 13 or later");
+#endif
+}
+
+extern "C" void LLVMRustSetModuleStackProtectorGuardOffset(LLVMModuleRef M,
+                                                           int Offset) {
+#if LLVM_VERSION_GE(13, 0)
+  unwrap(M)->setStackProtectorGuardOffset(Offset);
+#else
+  report_fatal_error("-Z stack-protector-guard-offset requires LLVM 13 or later");
+#endif
+}
+
 extern "C" LLVMValueRef LLVMRustMetadataAsValue(LLVMContextRef C, LLVMMetadataRef MD) {
   return wrap(MetadataAsValue::get(*unwrap(C), unwrap(MD)));
 }
@@ -1645,6 +1676,20 @@ extern "C" void LLVMRustSetDSOLocal(LLVMValueRef Global, bool is_dso_local) {
   unwrap<GlobalValue>(Global)->setDSOLocal(is_dso_local);
 }
 
+// Appends a line to the `rust.annotation` metadata of a function, which is
+// printed as a comment above its definition when emitting LLVM IR.
+extern "C" void LLVMRustAddFunctionAnnotation(LLVMValueRef Fn,
+                                              const char *Annotation,
+                                              size_t AnnotationLen) {
+  Function *F = unwrap<Function>(Fn);
+  LLVMContext &Ctx = F->getContext();
+  SmallVector<Metadata *, 4> Ops;
+  if (MDNode *Existing = F->getMetadata("rust.annotation"))
+    Ops.append(Existing->op_begin(), Existing->op_end());
+  Ops.push_back(MDString::get(Ctx, StringRef(Annotation, AnnotationLen)));
+  F->setMetadata("rust.annotation", MDNode::get(Ctx, Ops));
+}
+
 struct LLVMRustModuleBuffer {
   std::string data;
 };
@@ -1819,23 +1864,42 @@ using LLVMDiagnosticHandlerTy = DiagnosticHandler::DiagnosticHandlerTy;
 // When RemarkAllPasses is true, remarks are enabled for all passes. Otherwise
 // the RemarkPasses array specifies individual passes for which remarks will be
 // enabled.
+//
+// When RemarkFilePath is not null, the enabled remarks are serialized into
+// that file as YAML, as read by LLVM's opt-viewer, instead of being passed to
+// the callback.
 extern "C" void LLVMRustContextConfigureDiagnosticHandler(
     LLVMContextRef C, LLVMDiagnosticHandlerTy DiagnosticHandlerCallback,
     void *DiagnosticHandlerContext, bool RemarkAllPasses,
-    const char * const * RemarkPasses, size_t RemarkPassesLen) {
+    const char * const * RemarkPasses, size_t RemarkPassesLen,
+    const char *RemarkFilePath) {
 
   class RustDiagnosticHandler final : public DiagnosticHandler {
   public:
-    RustDiagnosticHandler(LLVMDiagnosticHandlerTy DiagnosticHandlerCallback,
-                          void *DiagnosticHandlerContext,
-                          bool RemarkAllPasses,
-                          std::vector<std::string> RemarkPasses)
+    RustDiagnosticHandler(
+        LLVMDiagnosticHandlerTy DiagnosticHandlerCallback,
+        void *DiagnosticHandlerContext,
+        bool RemarkAllPasses,
+        std::vector<std::string> RemarkPasses,
+        std::unique_ptr<ToolOutputFile> RemarkFile,
+        std::unique_ptr<remarks::RemarkStreamer> RemarkStreamer,
+        std::unique_ptr<LLVMRemarkStreamer> LlvmRemarkStreamer)
         : DiagnosticHandlerCallback(DiagnosticHandlerCallback),
           DiagnosticHandlerContext(DiagnosticHandlerContext),
           RemarkAllPasses(RemarkAllPasses),
-          RemarkPasses(RemarkPasses) {}
+          RemarkPasses(RemarkPasses),
+          RemarkFile(std::move(RemarkFile)),
+          RemarkStreamer(std::move(RemarkStreamer)),
+          LlvmRemarkStreamer(std::move(LlvmRemarkStreamer)) {}
 
     virtual bool handleDiagnostics(const DiagnosticInfo &DI) override {
+      if (LlvmRemarkStreamer) {
+        if (auto *OptDiag = dyn_cast<DiagnosticInfoOptimizationBase>(&DI)) {
+          if (OptDiag->isEnabled())
+            LlvmRemarkStreamer->emit(*OptDiag);
+          return true;
+        }
+      }
       if (DiagnosticHandlerCallback) {
         DiagnosticHandlerCallback(DI, DiagnosticHandlerContext);
         return true;
@@ -1876,12 +1940,47 @@ extern "C" void LLVMRustContextConfigureDiagnosticHandler(
 
     bool RemarkAllPasses = false;
     std::vector<std::string> RemarkPasses;
+
+    // The streamers write into the file, so they are destroyed first.
+    std::unique_ptr<ToolOutputFile> RemarkFile;
+    std::unique_ptr<remarks::RemarkStreamer> RemarkStreamer;
+    std::unique_ptr<LLVMRemarkStreamer> LlvmRemarkStreamer;
   };
 
   std::vector<std::string> Passes;
   for (size_t I = 0; I != RemarkPassesLen; ++I)
     Passes.push_back(RemarkPasses[I]);
 
+  std::unique_ptr<ToolOutputFile> RemarkFile;
+  std::unique_ptr<remarks::RemarkStreamer> RemarkStreamer;
+  std::unique_ptr<LLVMRemarkStreamer> LlvmRemarkStreamer;
+  if (RemarkFilePath != nullptr) {
+    std::error_code EC;
+    RemarkFile = std::make_unique<ToolOutputFile>(RemarkFilePath, EC,
+                                                  sys::fs::OF_TextWithCRLF);
+    if (EC) {
+      std::string Error = std::string("Cannot create remark file: ") +
+                          toString(errorCodeToError(EC));
+      report_fatal_error(Twine(Error));
+    }
+    // Keep the file once the remarks are written.
+    RemarkFile->keep();
+
+    auto RemarkSerializer = remarks::createRemarkSerializer(
+        remarks::Format::YAML, remarks::SerializerMode::Separate,
+        RemarkFile->os());
+    if (Error E = RemarkSerializer.takeError()) {
+      std::string Error = std::string("Cannot create remark serializer: ") +
+                          toString(std::move(E));
+      report_fatal_error(Twine(Error));
+    }
+    RemarkStreamer =
+        std::make_unique<remarks::RemarkStreamer>(std::move(*RemarkSerializer));
+    LlvmRemarkStreamer = std::make_unique<LLVMRemarkStreamer>(*RemarkStreamer);
+  }
+
   unwrap(C)->setDiagnosticHandler(std::make_unique<RustDiagnosticHandler>(
-      DiagnosticHandlerCallback, DiagnosticHandlerContext, RemarkAllPasses, Passes));
+      DiagnosticHandlerCallback, DiagnosticHandlerContext, RemarkAllPasses, Passes,
+      std::move(RemarkFile), std::move(RemarkStreamer),
+      std::move(LlvmRemarkStreamer)));
 }
@@ -1,6 +1,7 @@
 //! Validates all used crates and extern libraries and loads their metadata
 
 use crate::locator::{CrateError, CrateLocator, CratePaths};
+use crate::locator::{DualProcMacroArtifact, DualProcMacroSide};
 use crate::rmeta::{CrateDep, CrateMetadata, CrateNumMap, CrateRoot, MetadataBlob};
 
 use rustc_ast::expand::allocator::AllocatorKind;
@@ -8,6 +9,7 @@
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::Lrc;
+use rustc_errors::UnusedExtern;
 use rustc_expand::base::SyntaxExtension;
 use rustc_hir::def_id::{CrateNum, LocalDefId, StableCrateId, LOCAL_CRATE};
 use rustc_hir::definitions::Definitions;
@@ -214,9 +216,21 @@ pub fn report_unused_deps(&self, tcx: TyCtxt<'_>) {
             .lint_level_at_node(lint::builtin::UNUSED_CRATE_DEPENDENCIES, rustc_hir::CRATE_HIR_ID)
             .0;
         if level != lint::Level::Allow {
-            let unused_externs =
-                self.unused_externs.iter().map(|ident| ident.to_ident_string()).collect::<Vec<_>>();
-            let unused_externs = unused_externs.iter().map(String::as_str).collect::<Vec<&str>>();
+            let opts = &tcx.sess.opts;
+            let unused_externs = self
+                .unused_externs
+                .iter()
+                .map(|&name| {
+                    let location =
+                        opts.extern_dep_specs.get(name.as_str()).map(|spec| spec.to_json());
+                    let path = opts
+                        .externs
+                        .get(name.as_str())
+                        .and_then(|entry| entry.files()?.next())
+                        .map(|file| file.canonicalized().display().to_string());
+                    UnusedExtern { name: name.to_ident_string(), location, path }
+                })
+                .collect::<Vec<_>>();
             tcx.sess
                 .parse_sess
                 .span_diagnostic
@@ -469,43 +483,88 @@ fn load_proc_macro<'b>(
                 Some(LoadResult::Previous(cnum)) => {
                     return Ok(Some((LoadResult::Previous(cnum), None)));
                 }
-                Some(LoadResult::Loaded(library)) => Some(LoadResult::Loaded(library)),
-                None => return Ok(None),
+                Some(LoadResult::Loaded(library)) => library,
+                None => {
+                    // Look for a host build anyway, so that the error can say which of the
+                    // two builds is missing instead of failing to find the crate at all.
+                    let search = proc_macro_locator.dual_proc_macro_search();
+                    let mut host_locator = proc_macro_locator.clone();
+                    self.use_host_target(&mut host_locator, path_kind);
+                    host_locator.hash = host_hash;
+                    if let Some(LoadResult::Loaded(host)) = self.load(&mut host_locator)? {
+                        return Err(CrateError::DualProcMacroMissing(
+                            locator.crate_name,
+                            DualProcMacroArtifact::new(DualProcMacroSide::Host, &host),
+                            search,
+                        ));
+                    }
+                    return Ok(None);
+                }
             };
             locator.hash = host_hash;
             // Use the locator when looking for the host proc macro crate, as that is required
             // so we want it to affect the error message
-            (locator, result)
+            (locator, Some(result))
         } else {
             (&mut proc_macro_locator, None)
         };
 
         // Load the proc macro crate for the host
 
-        locator.reset();
-        locator.is_proc_macro = true;
-        locator.target = &self.sess.host;
-        locator.triple = TargetTriple::from_triple(config::host_triple());
-        locator.filesearch = self.sess.host_filesearch(path_kind);
+        self.use_host_target(locator, path_kind);
 
         let host_result = match self.load(locator)? {
             Some(host_result) => host_result,
-            None => return Ok(None),
+            None => {
+                return match target_result {
+                    Some(target) => Err(CrateError::DualProcMacroMissing(
+                        locator.crate_name,
+                        DualProcMacroArtifact::new(DualProcMacroSide::Target, &target),
+                        locator.dual_proc_macro_search(),
+                    )),
+                    None => Ok(None),
+                };
+            }
         };
 
-        Ok(Some(if self.sess.opts.debugging_opts.dual_proc_macros {
+        Ok(Some(if let Some(target) = target_result {
             let host_result = match host_result {
                 LoadResult::Previous(..) => {
                     panic!("host and target proc macros must be loaded in lock-step")
                 }
                 LoadResult::Loaded(library) => library,
             };
-            (target_result.unwrap(), Some(host_result))
+            // The host build is only used to run the macros, so nothing else would notice
+            // if it was built from a different crate than the target one.
+            let target_root = target.metadata.get_root();
+            let host_root = host_result.metadata.get_root();
+            if target_root.name() != host_root.name()
+                || target_root.stable_crate_id() != host_root.stable_crate_id()
+            {
+                return Err(CrateError::DualProcMacroMismatch(
+                    locator.crate_name,
+                    DualProcMacroArtifact::new(DualProcMacroSide::Target, &target),
+                    DualProcMacroArtifact::new(DualProcMacroSide::Host, &host_result),
+                ));
+            }
+            (LoadResult::Loaded(target), Some(host_result))
         } else {
             (host_result, None)
         }))
     }
 
+    /// Points `locator` at proc macro crates built for the host.
+    fn use_host_target<'b>(&self, locator: &mut CrateLocator<'b>, path_kind: PathKind)
+    where
+        'a: 'b,
+    {
+        locator.reset();
+        locator.is_proc_macro = true;
+        locator.target = &self.sess.host;
+        locator.triple = TargetTriple::from_triple(config::host_triple());
+        locator.filesearch = self.sess.host_filesearch(path_kind);
+    }
+
     fn resolve_crate<'b>(
         &'b mut self,
         name: Symbol,
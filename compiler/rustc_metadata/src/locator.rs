@@ -224,6 +224,7 @@
 use rustc_session::config::{self, CrateType};
 use rustc_session::cstore::{CrateSource, MetadataLoader};
 use rustc_session::filesearch::{FileDoesntMatch, FileMatches, FileSearch};
+use rustc_session::sandbox::{OutputSandbox, SandboxAccess};
 use rustc_session::search_paths::PathKind;
 use rustc_session::utils::CanonicalizedPath;
 use rustc_session::Session;
@@ -244,9 +245,10 @@
     only_needs_metadata: bool,
     sysroot: &'a Path,
     metadata_loader: &'a dyn MetadataLoader,
+    output_sandbox: Option<&'a OutputSandbox>,
 
     // Immutable per-search configuration.
-    crate_name: Symbol,
+    pub crate_name: Symbol,
     exact_paths: Vec<CanonicalizedPath>,
     pub hash: Option<Svh>,
     extra_filename: Option<&'a str>,
@@ -311,6 +313,7 @@ impl<'a> CrateLocator<'a> {
             only_needs_metadata,
             sysroot: &sess.sysroot,
             metadata_loader,
+            output_sandbox: sess.output_sandbox.as_deref(),
             crate_name,
             exact_paths: if hash.is_none() {
                 sess.opts
@@ -529,6 +532,11 @@ fn extract_one(
         let mut ret: Option<(PathBuf, PathKind)> = None;
         let mut err_data: Option<Vec<PathBuf>> = None;
         for (lib, kind) in m {
+            if let Some(sandbox) = self.output_sandbox {
+                if !sandbox.allows(&lib, SandboxAccess::Read) {
+                    return Err(CrateError::OutsideSandbox(self.crate_name, lib));
+                }
+            }
             info!("{} reading metadata from: {}", flavor, lib.display());
             if flavor == CrateFlavor::Rmeta && lib.metadata().map_or(false, |m| m.len() == 0) {
                 // Empty files will cause get_metadata_section to fail. Rmeta
@@ -722,6 +730,17 @@ fn find_commandline_library(&mut self) -> Result<Option<Library>, CrateError> {
         Ok(self.extract_lib(rlibs, rmetas, dylibs)?.map(|(_, lib)| lib))
     }
 
+    /// Describes the search so far, for reporting that `-Z dual-proc-macros` found only one
+    /// of the two builds of a proc macro crate.
+    crate fn dual_proc_macro_search(&self) -> DualProcMacroSearch {
+        DualProcMacroSearch {
+            triple: self.triple.clone(),
+            hash: self.hash,
+            via_hash: self.crate_rejections.via_hash.clone(),
+            via_triple: self.crate_rejections.via_triple.clone(),
+        }
+    }
+
     crate fn into_error(self, root: Option<CratePaths>) -> CrateError {
         CrateError::LocatorCombined(CombinedLocatorError {
             crate_name: self.crate_name,
@@ -910,6 +929,75 @@ struct CrateRejections {
     DlSym(String),
     LocatorCombined(CombinedLocatorError),
     NonDylibPlugin(Symbol),
+    OutsideSandbox(Symbol, PathBuf),
+    DualProcMacroMissing(Symbol, DualProcMacroArtifact, DualProcMacroSearch),
+    DualProcMacroMismatch(Symbol, DualProcMacroArtifact, DualProcMacroArtifact),
+}
+
+/// Which of the two builds of a proc macro crate loaded with `-Z dual-proc-macros` is meant.
+#[derive(Copy, Clone, PartialEq)]
+crate enum DualProcMacroSide {
+    /// The build that is run by the compiler.
+    Host,
+    /// The build whose metadata is used for the crate being compiled.
+    Target,
+}
+
+impl DualProcMacroSide {
+    fn other(self) -> DualProcMacroSide {
+        match self {
+            DualProcMacroSide::Host => DualProcMacroSide::Target,
+            DualProcMacroSide::Target => DualProcMacroSide::Host,
+        }
+    }
+}
+
+impl fmt::Display for DualProcMacroSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            DualProcMacroSide::Host => "host",
+            DualProcMacroSide::Target => "target",
+        })
+    }
+}
+
+/// One build of a proc macro crate loaded with `-Z dual-proc-macros`.
+crate struct DualProcMacroArtifact {
+    side: DualProcMacroSide,
+    triple: TargetTriple,
+    hash: Svh,
+    path: PathBuf,
+}
+
+impl DualProcMacroArtifact {
+    crate fn new(side: DualProcMacroSide, library: &Library) -> DualProcMacroArtifact {
+        let root = library.metadata.get_root();
+        DualProcMacroArtifact {
+            side,
+            triple: root.triple().clone(),
+            hash: root.hash(),
+            path: library.source.paths().next().unwrap().clone(),
+        }
+    }
+
+    fn note(&self) -> String {
+        format!(
+            "{} build for {} (hash {}): {}",
+            self.side,
+            self.triple,
+            self.hash,
+            self.path.display()
+        )
+    }
+}
+
+/// The unsuccessful search for one build of a proc macro crate loaded with
+/// `-Z dual-proc-macros`.
+crate struct DualProcMacroSearch {
+    triple: TargetTriple,
+    hash: Option<Svh>,
+    via_hash: Vec<CrateMismatch>,
+    via_triple: Vec<CrateMismatch>,
 }
 
 enum MetadataError<'a> {
@@ -1208,6 +1296,68 @@ impl CrateError {
                 "plugin `{}` only found in rlib format, but must be available in dylib format",
                 crate_name,
             ),
+            CrateError::OutsideSandbox(crate_name, path) => {
+                let mut err = sess.struct_span_err(
+                    span,
+                    &format!(
+                        "refusing to read `{}` for crate `{}`: outside the output sandbox",
+                        path.display(),
+                        crate_name
+                    ),
+                );
+                if let Some(ref sandbox) = sess.output_sandbox {
+                    err.note(&sandbox.describe_roots());
+                }
+                err
+            }
+            CrateError::DualProcMacroMissing(crate_name, found, search) => {
+                let missing = found.side.other();
+                let mut err = sess.struct_span_err(
+                    span,
+                    &format!(
+                        "found the {} build of proc macro crate `{}`, but not its {} build",
+                        found.side, crate_name, missing
+                    ),
+                );
+                err.note(&found.note());
+                let with_hash =
+                    search.hash.map_or(String::new(), |hash| format!(" with hash {}", hash));
+                err.note(&format!(
+                    "no {} build for {}{} was found",
+                    missing, search.triple, with_hash
+                ));
+                for CrateMismatch { path, got } in &search.via_hash {
+                    err.note(&format!(
+                        "{} build with mismatched hash {}: {}",
+                        missing,
+                        got,
+                        path.display()
+                    ));
+                }
+                for CrateMismatch { path, got } in &search.via_triple {
+                    err.note(&format!("build for {} instead: {}", got, path.display()));
+                }
+                err.help(&format!(
+                    "`-Z dual-proc-macros` needs `{}` built for both the host and the target; \
+                     rebuild it for {}",
+                    crate_name, search.triple
+                ));
+                err
+            }
+            CrateError::DualProcMacroMismatch(crate_name, target, host) => {
+                let mut err = sess.struct_span_err(
+                    span,
+                    &format!(
+                        "the host and target builds of proc macro crate `{}` do not match",
+                        crate_name
+                    ),
+                );
+                err.note(&target.note());
+                err.note(&host.note());
+                err.note("the two builds have different crate names or `-C metadata` values");
+                err.help("build both from the same source with the same `-C metadata`");
+                err
+            }
         };
 
         diag.emit();
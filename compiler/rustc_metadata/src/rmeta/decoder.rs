@@ -13,7 +13,7 @@
 use rustc_data_structures::unhash::UnhashMap;
 use rustc_errors::ErrorReported;
 use rustc_expand::base::{SyntaxExtension, SyntaxExtensionKind};
-use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, ProcMacroDerive};
+use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, ProcMacroDerive, PureProcMacro};
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, CtorOf, DefKind, Res};
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, CRATE_DEF_INDEX, LOCAL_CRATE};
@@ -656,6 +656,9 @@ impl MetadataBlob {
         let root = self.get_root();
         writeln!(out, "Crate info:")?;
         writeln!(out, "name {}{}", root.name, root.extra_filename)?;
+        if let Some(version) = root.version {
+            writeln!(out, "version {}", version)?;
+        }
         writeln!(out, "hash {} stable_crate_id {:?}", root.hash, root.stable_crate_id)?;
         writeln!(out, "proc_macro {:?}", root.proc_macro_data.is_some())?;
         writeln!(out, "=External Dependencies=")?;
@@ -778,25 +781,36 @@ fn get_span(&self, index: DefIndex, sess: &Session) -> Span {
     }
 
     fn load_proc_macro(&self, id: DefIndex, sess: &Session) -> SyntaxExtension {
+        let attrs: Vec<_> = self.get_item_attrs(id, sess).collect();
+        let is_pure = sess.contains_name(&attrs, sym::proc_macro_pure);
+        let pure = |name: &str| {
+            let name = Symbol::intern(name);
+            is_pure.then(|| PureProcMacro { crate_hash: self.root.hash, name })
+        };
+
         let (name, kind, helper_attrs) = match *self.raw_proc_macro(id) {
             ProcMacro::CustomDerive { trait_name, attributes, client } => {
                 let helper_attrs =
                     attributes.iter().cloned().map(Symbol::intern).collect::<Vec<_>>();
+                let pure = pure(trait_name);
                 (
                     trait_name,
-                    SyntaxExtensionKind::Derive(Box::new(ProcMacroDerive { client })),
+                    SyntaxExtensionKind::Derive(Box::new(ProcMacroDerive { client, pure })),
                     helper_attrs,
                 )
             }
             ProcMacro::Attr { name, client } => {
-                (name, SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client })), Vec::new())
+                let pure = pure(name);
+                let kind = SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client, pure }));
+                (name, kind, Vec::new())
             }
             ProcMacro::Bang { name, client } => {
-                (name, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })), Vec::new())
+                let pure = pure(name);
+                let kind = SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client, pure }));
+                (name, kind, Vec::new())
             }
         };
 
-        let attrs: Vec<_> = self.get_item_attrs(id, sess).collect();
         SyntaxExtension::new(
             sess,
             kind,
@@ -4,6 +4,7 @@
 
 use rustc_ast as ast;
 use rustc_data_structures::stable_map::FxHashMap;
+use rustc_data_structures::svh::Svh;
 use rustc_hir::def::{CtorKind, DefKind};
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, CRATE_DEF_INDEX, LOCAL_CRATE};
 use rustc_hir::definitions::{DefKey, DefPath, DefPathHash};
@@ -170,6 +171,8 @@ fn into_args(self) -> (DefId, DefId) {
     }
     is_no_builtins => { cdata.root.no_builtins }
     symbol_mangling_version => { cdata.root.symbol_mangling_version }
+    retag_granularity => { cdata.root.retag_granularity }
+    symbol_prefix => { cdata.root.symbol_prefix }
     impl_defaultness => { cdata.get_impl_defaultness(def_id.index) }
     impl_constness => { cdata.get_impl_constness(def_id.index) }
     reachable_non_generics => {
@@ -192,6 +195,7 @@ fn into_args(self) -> (DefId, DefId) {
     crate_hash => { cdata.root.hash }
     crate_host_hash => { cdata.host_hash }
     crate_name => { cdata.root.name }
+    crate_version => { cdata.root.version }
 
     extra_filename => { cdata.root.extra_filename.clone() }
 
@@ -217,6 +221,9 @@ fn into_args(self) -> (DefId, DefId) {
     defined_lang_items => { cdata.get_lang_items(tcx) }
     diagnostic_items => { cdata.get_diagnostic_items() }
     missing_lang_items => { cdata.get_missing_lang_items(tcx) }
+    shared_generics_providers => {
+        tcx.arena.alloc_from_iter(cdata.root.shared_generics_providers.decode(cdata))
+    }
 
     missing_extern_crate_item => {
         let r = matches!(*cdata.extern_crate.borrow(), Some(extern_crate) if !extern_crate.is_direct());
@@ -258,6 +265,14 @@ pub fn provide(providers: &mut Providers) {
             assert_eq!(cnum, LOCAL_CRATE);
             false
         },
+        retag_granularity: |tcx, cnum| {
+            assert_eq!(cnum, LOCAL_CRATE);
+            tcx.sess.opts.retag_granularity()
+        },
+        symbol_prefix: |tcx, cnum| {
+            assert_eq!(cnum, LOCAL_CRATE);
+            tcx.sess.opts.debugging_opts.symbol_prefix.as_deref().map(Symbol::intern)
+        },
         native_library_kind: |tcx, id| {
             tcx.native_libraries(id.krate)
                 .iter()
@@ -357,6 +372,9 @@ pub fn provide(providers: &mut Providers) {
             tcx.arena
                 .alloc_slice(&CStore::from_tcx(tcx).crate_dependencies_in_postorder(LOCAL_CRATE))
         },
+        crate_dependencies: |tcx, cnum| {
+            tcx.arena.alloc_slice(&CStore::from_tcx(tcx).crate_dependencies_in_postorder(cnum))
+        },
         crates: |tcx, ()| tcx.arena.alloc_slice(&CStore::from_tcx(tcx).crates_untracked()),
 
         ..*providers
@@ -432,6 +450,10 @@ pub fn crate_source_untracked(&self, cnum: CrateNum) -> CrateSource {
         self.get_crate_data(cnum).source.clone()
     }
 
+    pub fn crate_hash_untracked(&self, cnum: CrateNum) -> Svh {
+        self.get_crate_data(cnum).root.hash
+    }
+
     pub fn get_span_untracked(&self, def_id: DefId, sess: &Session) -> Span {
         self.get_crate_data(def_id.krate).get_span(def_id.index, sess)
     }
@@ -2,7 +2,7 @@
 use crate::rmeta::table::{FixedSizeEncoding, TableBuilder};
 use crate::rmeta::*;
 
-use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexSet};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{join, par_iter, Lrc, ParallelIterator};
 use rustc_hir as hir;
@@ -22,14 +22,15 @@
 use rustc_middle::middle::exported_symbols::{
     metadata_symbol_name, ExportedSymbol, SymbolExportLevel,
 };
-use rustc_middle::mir::interpret;
+use rustc_middle::mir::visit::Visitor as MirVisitor;
+use rustc_middle::mir::{self, interpret, Location};
 use rustc_middle::thir;
 use rustc_middle::traits::specialization_graph;
 use rustc_middle::ty::codec::TyEncoder;
 use rustc_middle::ty::fast_reject::{self, SimplifyParams, StripReferences};
 use rustc_middle::ty::{self, SymbolName, Ty, TyCtxt};
 use rustc_serialize::{opaque, Encodable, Encoder};
-use rustc_session::config::CrateType;
+use rustc_session::config::{CrateType, EncodeMirFor};
 use rustc_session::cstore::{ForeignModule, LinkagePreference, NativeLib};
 use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_span::{self, ExternalSource, FileName, SourceFile, Span, SyntaxContext};
@@ -72,6 +73,7 @@ pub(super) struct EncodeContext<'a, 'tcx> {
     required_source_files: Option<GrowableBitSet<usize>>,
     is_proc_macro: bool,
     hygiene_ctxt: &'a HygieneEncodeContext,
+    extra_mir: &'a ExtraMir,
 }
 
 /// If the current crate is a proc-macro, returns early with `Lazy:empty()`.
@@ -669,6 +671,7 @@ fn encode_crate_root(&mut self) -> Lazy<CrateRoot<'tcx>> {
         i = self.position();
         let exported_symbols = tcx.exported_symbols(LOCAL_CRATE);
         let exported_symbols = self.encode_exported_symbols(&exported_symbols);
+        let shared_generics_providers = self.lazy(tcx.shared_generics_providers(LOCAL_CRATE));
         let exported_symbols_bytes = self.position() - i;
 
         // Encode the hygiene data,
@@ -698,6 +701,7 @@ fn encode_crate_root(&mut self) -> Lazy<CrateRoot<'tcx>> {
 
         let root = self.lazy(CrateRoot {
             name: tcx.crate_name(LOCAL_CRATE),
+            version: tcx.crate_version(LOCAL_CRATE),
             extra_filename: tcx.sess.opts.cg.extra_filename.clone(),
             triple: tcx.sess.opts.target_triple.clone(),
             hash: tcx.crate_hash(LOCAL_CRATE),
@@ -716,6 +720,8 @@ fn encode_crate_root(&mut self) -> Lazy<CrateRoot<'tcx>> {
             panic_runtime: tcx.sess.contains_name(&attrs, sym::panic_runtime),
             profiler_runtime: tcx.sess.contains_name(&attrs, sym::profiler_runtime),
             symbol_mangling_version: tcx.sess.opts.debugging_opts.get_symbol_mangling_version(),
+            retag_granularity: tcx.sess.opts.retag_granularity(),
+            symbol_prefix: tcx.symbol_prefix(LOCAL_CRATE),
 
             crate_deps,
             dylib_dependency_formats,
@@ -728,6 +734,7 @@ fn encode_crate_root(&mut self) -> Lazy<CrateRoot<'tcx>> {
             source_map,
             impls,
             exported_symbols,
+            shared_generics_providers,
             interpret_alloc_index,
             tables,
             syntax_contexts,
@@ -847,15 +854,15 @@ fn should_encode_stability(def_kind: DefKind) -> bool {
 /// Computing, optimizing and encoding the MIR is a relatively expensive operation.
 /// We want to avoid this work when not required. Therefore:
 /// - we only compute `mir_for_ctfe` on items with const-eval semantics;
-/// - we skip `optimized_mir` for check runs.
+/// - we skip `optimized_mir` for check runs, unless `extra_mir` asks for it.
 ///
 /// Return a pair, resp. for CTFE and for LLVM.
-fn should_encode_mir(tcx: TyCtxt<'_>, def_id: LocalDefId) -> (bool, bool) {
+fn should_encode_mir(tcx: TyCtxt<'_>, extra_mir: &ExtraMir, def_id: LocalDefId) -> (bool, bool) {
     match tcx.def_kind(def_id) {
         // Constructors
         DefKind::Ctor(_, _) => {
-            let mir_opt_base = tcx.sess.opts.output_types.should_codegen()
-                || tcx.sess.opts.debugging_opts.always_encode_mir;
+            let mir_opt_base =
+                tcx.sess.opts.output_types.should_codegen() || extra_mir.contains(def_id);
             (true, mir_opt_base)
         }
         // Constants
@@ -873,8 +880,7 @@ fn should_encode_mir(tcx: TyCtxt<'_>, def_id: LocalDefId) -> (bool, bool) {
             // The function has a `const` modifier or is annotated with `default_method_body_is_const`.
             let is_const_fn = tcx.is_const_fn_raw(def_id.to_def_id())
                 || tcx.has_attr(def_id.to_def_id(), sym::default_method_body_is_const);
-            let always_encode_mir = tcx.sess.opts.debugging_opts.always_encode_mir;
-            (is_const_fn, needs_inline || always_encode_mir)
+            (is_const_fn, needs_inline || extra_mir.contains(def_id))
         }
         // Closures can't be const fn.
         DefKind::Closure => {
@@ -882,8 +888,7 @@ fn should_encode_mir(tcx: TyCtxt<'_>, def_id: LocalDefId) -> (bool, bool) {
             let needs_inline = (generics.requires_monomorphization(tcx)
                 || tcx.codegen_fn_attrs(def_id).requests_inline())
                 && tcx.sess.opts.output_types.should_codegen();
-            let always_encode_mir = tcx.sess.opts.debugging_opts.always_encode_mir;
-            (false, needs_inline || always_encode_mir)
+            (false, needs_inline || extra_mir.contains(def_id))
         }
         // Generators require optimized MIR to compute layout.
         DefKind::Generator => (false, true),
@@ -892,6 +897,99 @@ fn should_encode_mir(tcx: TyCtxt<'_>, def_id: LocalDefId) -> (bool, bool) {
     }
 }
 
+/// The items whose optimized MIR is encoded even when other crates don't need it for codegen,
+/// as requested with `-Z encode-mir-for` or `-Z always-encode-mir`.
+enum ExtraMir {
+    None,
+    All,
+    Items(FxHashSet<LocalDefId>),
+}
+
+impl ExtraMir {
+    fn new(tcx: TyCtxt<'_>) -> ExtraMir {
+        let encode_mir_for = match tcx.sess.opts.encode_mir_for() {
+            None => return ExtraMir::None,
+            Some(EncodeMirFor::All) => return ExtraMir::All,
+            Some(encode_mir_for) => encode_mir_for,
+        };
+
+        // Closures are as public as the function they're defined in.
+        let access_levels = tcx.privacy_access_levels(());
+        let mir_keys = tcx.mir_keys(());
+        let mut items: FxHashSet<_> = mir_keys
+            .iter()
+            .copied()
+            .filter(|&def_id| {
+                let root = tcx.typeck_root_def_id(def_id.to_def_id()).expect_local();
+                access_levels.is_exported(root)
+            })
+            .collect();
+
+        if encode_mir_for == EncodeMirFor::Reachable {
+            let mut worklist: Vec<_> = items.iter().copied().collect();
+            while let Some(def_id) = worklist.pop() {
+                if !matches!(
+                    tcx.def_kind(def_id),
+                    DefKind::Fn | DefKind::AssocFn | DefKind::Closure | DefKind::Generator
+                ) {
+                    continue;
+                }
+                let mut collector =
+                    CalleeCollector { tcx, param_env: tcx.param_env(def_id), callees: Vec::new() };
+                collector.visit_body(tcx.optimized_mir(def_id));
+                for callee in collector.callees {
+                    if mir_keys.contains(&callee) && items.insert(callee) {
+                        worklist.push(callee);
+                    }
+                }
+            }
+        }
+
+        ExtraMir::Items(items)
+    }
+
+    fn contains(&self, def_id: LocalDefId) -> bool {
+        match self {
+            ExtraMir::None => false,
+            ExtraMir::All => true,
+            ExtraMir::Items(items) => items.contains(&def_id),
+        }
+    }
+}
+
+/// Collects the local functions and closures that a body calls or refers to.
+struct CalleeCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    callees: Vec<LocalDefId>,
+}
+
+impl<'tcx> MirVisitor<'tcx> for CalleeCollector<'tcx> {
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: Location) {
+        if let ty::FnDef(def_id, substs) = *constant.ty().kind() {
+            // Trait methods are resolved to their implementation where the types are known.
+            if let Ok(Some(instance)) =
+                ty::Instance::resolve(self.tcx, self.param_env, def_id, substs)
+            {
+                self.callees.extend(instance.def_id().as_local());
+            }
+            self.callees.extend(def_id.as_local());
+        }
+        self.super_constant(constant, location);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        if let mir::Rvalue::Aggregate(kind, _) = rvalue {
+            if let mir::AggregateKind::Closure(def_id, _)
+            | mir::AggregateKind::Generator(def_id, ..) = **kind
+            {
+                self.callees.extend(def_id.as_local());
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
 fn should_encode_variances(def_kind: DefKind) -> bool {
     match def_kind {
         DefKind::Struct
@@ -1297,7 +1395,8 @@ fn encode_mir(&mut self) {
             .mir_keys(())
             .iter()
             .filter_map(|&def_id| {
-                let (encode_const, encode_opt) = should_encode_mir(self.tcx, def_id);
+                let (encode_const, encode_opt) =
+                    should_encode_mir(self.tcx, self.extra_mir, def_id);
                 if encode_const || encode_opt {
                     Some((def_id, encode_const, encode_opt))
                 } else {
@@ -2068,14 +2167,14 @@ fn visit_foreign_item(&mut self, _foreign_item: &'v hir::ForeignItem<'v>) {}
 
 /// Used to prefetch queries which will be needed later by metadata encoding.
 /// Only a subset of the queries are actually prefetched to keep this code smaller.
-fn prefetch_mir(tcx: TyCtxt<'_>) {
+fn prefetch_mir(tcx: TyCtxt<'_>, extra_mir: &ExtraMir) {
     if !tcx.sess.opts.output_types.should_codegen() {
         // We won't emit MIR, so don't prefetch it.
         return;
     }
 
     par_iter(tcx.mir_keys(())).for_each(|&def_id| {
-        let (encode_const, encode_opt) = should_encode_mir(tcx, def_id);
+        let (encode_const, encode_opt) = should_encode_mir(tcx, extra_mir, def_id);
 
         if encode_const {
             tcx.ensure().mir_for_ctfe(def_id);
@@ -2136,8 +2235,10 @@ pub fn encode_metadata(tcx: TyCtxt<'_>) -> EncodedMetadata {
     // there's no need to do dep-graph tracking for any of it.
     tcx.dep_graph.assert_ignored();
 
+    let extra_mir = ExtraMir::new(tcx);
+
     join(
-        || encode_metadata_impl(tcx),
+        || encode_metadata_impl(tcx, &extra_mir),
         || {
             if tcx.sess.threads() == 1 {
                 return;
@@ -2145,13 +2246,13 @@ pub fn encode_metadata(tcx: TyCtxt<'_>) -> EncodedMetadata {
             // Prefetch some queries used by metadata encoding.
             // This is not necessary for correctness, but is only done for performance reasons.
             // It can be removed if it turns out to cause trouble or be detrimental to performance.
-            join(|| prefetch_mir(tcx), || tcx.exported_symbols(LOCAL_CRATE));
+            join(|| prefetch_mir(tcx, &extra_mir), || tcx.exported_symbols(LOCAL_CRATE));
         },
     )
     .0
 }
 
-fn encode_metadata_impl(tcx: TyCtxt<'_>) -> EncodedMetadata {
+fn encode_metadata_impl(tcx: TyCtxt<'_>, extra_mir: &ExtraMir) -> EncodedMetadata {
     let mut encoder = opaque::Encoder::new(vec![]);
     encoder.emit_raw_bytes(METADATA_HEADER).unwrap();
 
@@ -2178,6 +2279,7 @@ fn encode_metadata_impl(tcx: TyCtxt<'_>) -> EncodedMetadata {
         required_source_files,
         is_proc_macro: tcx.sess.crate_types().contains(&CrateType::ProcMacro),
         hygiene_ctxt: &hygiene_ctxt,
+        extra_mir,
     };
 
     // Encode the rustc version string in a predictable location.
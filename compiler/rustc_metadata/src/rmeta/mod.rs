@@ -18,7 +18,7 @@
 use rustc_middle::thir;
 use rustc_middle::ty::{self, ReprOptions, Ty};
 use rustc_serialize::opaque::Encoder;
-use rustc_session::config::SymbolManglingVersion;
+use rustc_session::config::{RetagGranularity, SymbolManglingVersion};
 use rustc_session::cstore::{CrateDepKind, ForeignModule, LinkagePreference, NativeLib};
 use rustc_span::edition::Edition;
 use rustc_span::hygiene::{ExpnIndex, MacroKind};
@@ -203,6 +203,7 @@ macro_rules! Lazy {
 #[derive(MetadataEncodable, MetadataDecodable)]
 crate struct CrateRoot<'tcx> {
     name: Symbol,
+    version: Option<Symbol>,
     triple: TargetTriple,
     extra_filename: String,
     hash: Svh,
@@ -229,6 +230,7 @@ macro_rules! Lazy {
     tables: LazyTables<'tcx>,
 
     exported_symbols: Lazy!([(ExportedSymbol<'tcx>, SymbolExportLevel)]),
+    shared_generics_providers: Lazy<[u64]>,
 
     syntax_contexts: SyntaxContextTable,
     expn_data: ExpnDataTable,
@@ -245,6 +247,8 @@ macro_rules! Lazy {
     panic_runtime: bool,
     profiler_runtime: bool,
     symbol_mangling_version: SymbolManglingVersion,
+    retag_granularity: Option<RetagGranularity>,
+    symbol_prefix: Option<Symbol>,
 }
 
 #[derive(Encodable, Decodable)]
@@ -1,14 +1,19 @@
+use std::any::Any;
 use std::cmp;
+use std::fmt;
 
+use rustc_ast::AttrId;
+use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
-use rustc_errors::{DiagnosticBuilder, DiagnosticId};
+use rustc_data_structures::sync::{self, Lock};
+use rustc_errors::{Diagnostic, DiagnosticBuilder, DiagnosticId};
 use rustc_hir::HirId;
 use rustc_index::vec::IndexVec;
 use rustc_query_system::ich::StableHashingContext;
 use rustc_session::lint::{
     builtin::{self, FORBIDDEN_LINT_GROUPS},
-    FutureIncompatibilityReason, Level, Lint, LintId,
+    FutureIncompatibilityReason, Level, Lint, LintExpectationId, LintId,
 };
 use rustc_session::{DiagnosticMessageId, Session};
 use rustc_span::hygiene::MacroKind;
@@ -56,6 +61,9 @@ pub fn span(&self) -> Span {
 pub struct LintLevelSets {
     pub list: IndexVec<LintStackIndex, LintSet>,
     pub lint_cap: Level,
+    /// The caps of `--cap-lints-group`, which replace `lint_cap` for the lints of the capped
+    /// groups.
+    pub lint_group_caps: FxHashMap<LintId, Level>,
 }
 
 rustc_index::newtype_index! {
@@ -76,7 +84,11 @@ pub struct LintSet {
 
 impl LintLevelSets {
     pub fn new() -> Self {
-        LintLevelSets { list: IndexVec::new(), lint_cap: Level::Forbid }
+        LintLevelSets {
+            list: IndexVec::new(),
+            lint_cap: Level::Forbid,
+            lint_group_caps: FxHashMap::default(),
+        }
     }
 
     pub fn get_lint_level(
@@ -111,12 +123,14 @@ pub fn get_lint_level(
             }
         }
 
-        // Ensure that we never exceed the `--cap-lints` argument
-        // unless the source is a --force-warn
+        // Ensure that we never exceed the `--cap-lints` argument, or the
+        // `--cap-lints-group` argument of a group of the lint, unless the
+        // source is a --force-warn
         level = if let LintLevelSource::CommandLine(_, Level::ForceWarn) = src {
             level
         } else {
-            cmp::min(level, self.lint_cap)
+            let cap = self.lint_group_caps.get(&LintId::of(lint)).copied();
+            cmp::min(level, cap.unwrap_or(self.lint_cap))
         };
 
         if let Some(driver_level) = sess.driver_lint_caps.get(&LintId::of(lint)) {
@@ -151,10 +165,31 @@ pub fn get_lint_id_level(
     }
 }
 
+/// A lint expected to be emitted by an `#[expect]` attribute (RFC 2383).
+#[derive(Debug, HashStable)]
+pub struct LintExpectation {
+    /// The id of the expectation, which the diagnostics of the lints fulfilling it carry.
+    pub id: LintExpectationId,
+    /// The attribute of the expectation, which matches it with the `Unstable` ids of the lints
+    /// emitted by the early lint passes.
+    #[stable_hasher(ignore)]
+    pub attr_id: AttrId,
+    /// The span of the lint name in the attribute, which is also the span of the
+    /// `LintLevelSource` of the lints emitted at the `Expect` level it sets.
+    pub span: Span,
+    pub lint_name: Symbol,
+    pub reason: Option<Symbol>,
+    /// The lint levels in effect at the attribute, which decide the level of
+    /// `unfulfilled_lint_expectations` if the expectation isn't fulfilled.
+    pub lint_stack_index: LintStackIndex,
+}
+
 #[derive(Debug)]
 pub struct LintLevelMap {
     pub sets: LintLevelSets,
     pub id_to_set: FxHashMap<HirId, LintStackIndex>,
+    /// The expectations of the `#[expect]` attributes of the crate, in source order.
+    pub expectations: Vec<LintExpectation>,
 }
 
 impl LintLevelMap {
@@ -178,11 +213,54 @@ pub fn level_and_source(
 impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
     #[inline]
     fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHasher) {
-        let LintLevelMap { ref sets, ref id_to_set } = *self;
+        let LintLevelMap { ref sets, ref id_to_set, ref expectations } = *self;
 
         id_to_set.hash_stable(hcx, hasher);
 
-        hcx.while_hashing_spans(true, |hcx| sets.hash_stable(hcx, hasher))
+        hcx.while_hashing_spans(true, |hcx| {
+            sets.hash_stable(hcx, hasher);
+            expectations.hash_stable(hcx, hasher);
+        })
+    }
+}
+
+/// The state that a pass registered with `LintStore::register_late_aggregating_pass` aggregated
+/// over a module, with its type erased as the queries can't name it.
+pub struct ErasedLintAggregate {
+    pub state: Box<dyn Any + sync::Send + sync::Sync>,
+    /// The stable hash of `state`, from the `HashStable` implementation of its type.
+    pub fingerprint: Fingerprint,
+}
+
+impl fmt::Debug for ErasedLintAggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedLintAggregate").field("fingerprint", &self.fingerprint).finish()
+    }
+}
+
+/// The result of the `lint_aggregating_mod` query.
+#[derive(Debug)]
+pub struct ModuleLintAggregates {
+    /// The state of each aggregating pass, in the order the passes were registered in.
+    pub states: Vec<ErasedLintAggregate>,
+    /// The lints the passes emitted on the module, for the caller of the query to emit. The
+    /// query doesn't emit them itself, as it runs again when its result is needed in a later
+    /// session, and the lints it emitted would be replayed as well.
+    pub lints: Vec<Diagnostic>,
+}
+
+impl<'a> HashStable<StableHashingContext<'a>> for ModuleLintAggregates {
+    fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHasher) {
+        let ModuleLintAggregates { ref states, ref lints } = *self;
+
+        for state in states {
+            state.fingerprint.hash_stable(hcx, hasher);
+        }
+        lints.len().hash_stable(hcx, hasher);
+        for lint in lints {
+            lint.message().hash_stable(hcx, hasher);
+            lint.span.primary_spans().hash_stable(hcx, hasher);
+        }
     }
 }
 
@@ -200,6 +278,15 @@ pub fn build(mut self, msg: &str) -> DiagnosticBuilder<'a> {
     pub fn new(err: DiagnosticBuilder<'a>) -> LintDiagnosticBuilder<'a> {
         LintDiagnosticBuilder(err)
     }
+
+    /// Makes emitting the lint add it to `buffer` instead, see
+    /// [`DiagnosticBuilder::buffer_emission`].
+    pub fn buffer_emission<'b>(self, buffer: &'b Lock<Vec<Diagnostic>>) -> LintDiagnosticBuilder<'b>
+    where
+        'a: 'b,
+    {
+        LintDiagnosticBuilder(self.0.buffer_emission(buffer))
+    }
 }
 
 pub fn struct_lint_level<'s, 'd>(
@@ -244,6 +331,12 @@ fn struct_lint_level_impl(
                     return;
                 }
             }
+            (Level::Expect(expect_id), Some(span)) => {
+                let mut builder = sess.struct_expect("", expect_id);
+                builder.set_span(span);
+                builder
+            }
+            (Level::Expect(expect_id), None) => sess.struct_expect("", expect_id),
             (Level::Warn, Some(span)) => sess.struct_span_warn(span, ""),
             (Level::Warn, None) => sess.struct_warn(""),
             (Level::ForceWarn, Some(span)) => sess.struct_span_force_warn(span, ""),
@@ -256,6 +349,16 @@ fn struct_lint_level_impl(
             (Level::Deny | Level::Forbid, None) => sess.diagnostic().struct_err_lint(""),
         };
 
+        // The diagnostic of an expected lint is never shown, but emitting it fulfills the
+        // expectation, and so does replaying it when it is loaded from the incremental cache. This
+        // also holds for lints in external macros.
+        if let Level::Expect(_) = level {
+            let name = lint.name_lower();
+            err.code(DiagnosticId::Lint { name, has_future_breakage, is_force_warn: false });
+            decorate(LintDiagnosticBuilder::new(err));
+            return;
+        }
+
         // If this code originates in a foreign macro, aka something that this crate
         // did not itself author, then it's likely that there's nothing this crate
         // can do about it. We probably want to skip the lint entirely.
@@ -294,6 +397,7 @@ fn struct_lint_level_impl(
                     Level::Forbid => "-F",
                     Level::Allow => "-A",
                     Level::ForceWarn => "--force-warn",
+                    Level::Expect(_) => unreachable!("`expect` can't be set on the command line"),
                 };
                 let hyphen_case_lint_name = name.replace('_', "-");
                 if lint_flag_val.as_str() == name {
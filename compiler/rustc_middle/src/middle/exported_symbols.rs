@@ -1,5 +1,8 @@
 use crate::ty::subst::SubstsRef;
 use crate::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_macros::HashStable;
 
@@ -53,3 +56,42 @@ pub fn metadata_symbol_name(tcx: TyCtxt<'_>) -> String {
         tcx.sess.local_stable_crate_id().to_u64(),
     )
 }
+
+/// The index of `-Z shared-generics-dir`, through which the crates compiled with the same
+/// index directory link to the generic instances exported by each other, as they do with the
+/// instances exported by their dependencies.
+#[derive(Debug, Default)]
+pub struct SharedGenericsIndex {
+    /// The names of the crates of the index, by `StableCrateId`.
+    pub crates: FxHashMap<u64, String>,
+    /// The crate exporting each instance and the symbol name it exports the instance as, by
+    /// the stable hash of the instance.
+    pub instances: FxHashMap<Fingerprint, (u64, String)>,
+}
+
+impl SharedGenericsIndex {
+    /// Returns the `StableCrateId` of the crate exporting `instance`, other than the local
+    /// crate, and the symbol name it exports the instance as.
+    pub fn provider<'a, 'tcx>(
+        &'a self,
+        tcx: TyCtxt<'tcx>,
+        instance: ty::Instance<'tcx>,
+    ) -> Option<(u64, &'a str)> {
+        if self.instances.is_empty() {
+            return None;
+        }
+        let (stable_crate_id, symbol_name) = self.instances.get(&instance_hash(tcx, instance))?;
+        if *stable_crate_id == tcx.sess.local_stable_crate_id().to_u64() {
+            return None;
+        }
+        Some((*stable_crate_id, symbol_name))
+    }
+}
+
+/// The hash identifying `instance` in the index, which doesn't depend on the crate computing it.
+pub fn instance_hash<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::Instance<'tcx>) -> Fingerprint {
+    let mut hcx = tcx.create_stable_hashing_context();
+    let mut hasher = StableHasher::new();
+    instance.hash_stable(&mut hcx, &mut hasher);
+    hasher.finish()
+}
@@ -12,6 +12,7 @@
 use crate::bug;
 use crate::ty;
 use rustc_ast::Attribute;
+use rustc_session::lint::builtin::LARGE_ASSIGNMENTS;
 use rustc_session::Session;
 use rustc_session::{Limit, Limits};
 use rustc_span::symbol::{sym, Symbol};
@@ -25,7 +26,12 @@ pub fn provide(providers: &mut ty::query::Providers) {
             tcx.hir().krate_attrs(),
             tcx.sess,
             sym::move_size_limit,
-            tcx.sess.opts.debugging_opts.move_size_limit.unwrap_or(0),
+            tcx.sess
+                .lint_config(LARGE_ASSIGNMENTS, "limit")
+                .and_then(|limit| limit.as_number())
+                .map(|limit| limit as usize)
+                .or(tcx.sess.opts.debugging_opts.move_size_limit)
+                .unwrap_or(0),
         ),
         type_length_limit: get_limit(
             tcx.hir().krate_attrs(),
@@ -60,6 +60,7 @@
 pub mod tcx;
 pub mod terminator;
 pub use terminator::*;
+pub mod text;
 pub mod traversal;
 mod type_foldable;
 pub mod visit;
@@ -8,7 +8,7 @@
 use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc_hir::{HirId, ItemId};
 use rustc_query_system::ich::{NodeIdHashingMode, StableHashingContext};
-use rustc_session::config::OptLevel;
+use rustc_session::config::{CguNameScheme, OptLevel};
 use rustc_span::source_map::Span;
 use rustc_span::symbol::Symbol;
 use std::fmt;
@@ -467,7 +467,9 @@ pub fn build_cgu_name<I, C, S>(
     {
         let cgu_name = self.build_cgu_name_no_mangle(cnum, components, special_suffix);
 
-        if self.tcx.sess.opts.debugging_opts.human_readable_cgu_names {
+        if self.tcx.sess.opts.debugging_opts.human_readable_cgu_names
+            || self.tcx.sess.cgu_name_scheme() == CguNameScheme::ItemBased
+        {
             cgu_name
         } else {
             Symbol::intern(&CodegenUnit::mangle_name(&cgu_name.as_str()))
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::fmt::Write as _;
@@ -98,16 +99,87 @@ pub fn dump_enabled<'tcx>(tcx: TyCtxt<'tcx>, pass_name: &str, def_id: DefId) ->
         // see notes on #41697 below
         tcx.def_path_str(def_id)
     });
+    // Local paths are printed without the crate name, which patterns may start with.
+    let crate_name = tcx.crate_name(def_id.krate);
+    let full_path = if def_id.is_local() {
+        format!("{}::{}", crate_name, node_path)
+    } else {
+        node_path.clone()
+    };
     filters.split('|').any(|or_filter| {
         or_filter.split('&').all(|and_filter| {
             let and_filter_trimmed = and_filter.trim();
-            and_filter_trimmed == "all"
-                || pass_name.contains(and_filter_trimmed)
-                || node_path.contains(and_filter_trimmed)
+            let (negated, pattern) = match and_filter_trimmed.strip_prefix('!') {
+                Some(pattern) => (true, pattern.trim()),
+                None => (false, and_filter_trimmed),
+            };
+            let matches = if pattern == "all" {
+                true
+            } else if pattern.contains('*') {
+                glob_matches_path(pattern, pass_name)
+                    || glob_matches_path(pattern, &node_path)
+                    || glob_matches_path(pattern, &full_path)
+            } else {
+                pass_name.contains(pattern) || full_path.contains(pattern)
+            };
+            matches != negated
         })
     })
 }
 
+/// Matches a `-Z dump-mir` pattern against a whole path, segment by segment. Within a segment,
+/// `*` matches any characters and `?` any one character, and a segment without a `#`
+/// disambiguator matches all of them, so that `{closure}` matches `{closure#0}`. A `**`
+/// segment matches any number of segments.
+fn glob_matches_path(pattern: &str, path: &str) -> bool {
+    fn matches(pattern: &[&str], path: &[&str]) -> bool {
+        match (pattern.split_first(), path.split_first()) {
+            (None, None) => true,
+            (Some((&"**", rest)), _) => {
+                (0..=path.len()).any(|skipped| matches(rest, &path[skipped..]))
+            }
+            (Some((&first, rest)), Some((&segment, path_rest))) => {
+                let segment = if first.contains('#') {
+                    Cow::Borrowed(segment)
+                } else {
+                    strip_disambiguator(segment)
+                };
+                glob_matches_segment(first.as_bytes(), segment.as_bytes())
+                    && matches(rest, path_rest)
+            }
+            _ => false,
+        }
+    }
+
+    let pattern: Vec<_> = pattern.split("::").collect();
+    let path: Vec<_> = path.split("::").collect();
+    matches(&pattern, &path)
+}
+
+/// Removes the disambiguator of a path segment, e.g. `{closure#0}` becomes `{closure}`.
+fn strip_disambiguator(segment: &str) -> Cow<'_, str> {
+    match segment.find('#') {
+        Some(hash) if segment.starts_with('{') && segment.ends_with('}') => {
+            format!("{}}}", &segment[..hash]).into()
+        }
+        _ => segment.into(),
+    }
+}
+
+fn glob_matches_segment(pattern: &[u8], segment: &[u8]) -> bool {
+    match (pattern.split_first(), segment.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            (0..=segment.len()).any(|skipped| glob_matches_segment(rest, &segment[skipped..]))
+        }
+        (Some((b'?', rest)), Some((_, segment_rest))) => glob_matches_segment(rest, segment_rest),
+        (Some((first, rest)), Some((c, segment_rest))) => {
+            first == c && glob_matches_segment(rest, segment_rest)
+        }
+        _ => false,
+    }
+}
+
 // #41697 -- we use `with_forced_impl_filename_line()` because
 // `def_path_str()` would otherwise trigger `type_of`, and this can
 // run while we are already attempting to evaluate `type_of`.
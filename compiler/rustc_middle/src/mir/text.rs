@@ -0,0 +1,503 @@
+//! The versioned textual format of `--emit=mir`, and a reader for it.
+//!
+//! Unlike the output of `-Z dump-mir`, which is meant for people debugging the compiler, this
+//! format is normalized so that tools can rely on it: it has no spans, source scopes or
+//! comments, paths are never trimmed, and each statement and terminator is on a line of its
+//! own. Its grammar is documented in the rustc book, and any change to it bumps
+//! [`MIR_TEXT_FORMAT_VERSION`].
+//!
+//! The output is built as a [`MirText`], whose `Display` impl writes the format, so that
+//! [`parse_mir_text`] can read back everything the compiler writes.
+
+use crate::mir::pretty::{dump_mir_def_ids, write_allocations};
+use crate::mir::{Body, Mutability, Place};
+use crate::ty::print::{with_forced_impl_filename_line, with_no_trimmed_paths};
+use crate::ty::{self, TyCtxt};
+use rustc_hir::def::DefKind;
+
+use std::fmt;
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the format written by [`write_mir_text`].
+pub const MIR_TEXT_FORMAT_VERSION: u32 = 1;
+
+const HEADER: &str = "// rustc MIR text format, version ";
+const INDENT: &str = "    ";
+
+/// The MIR bodies of a crate, as written by `--emit=mir`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MirText {
+    pub version: u32,
+    pub bodies: Vec<MirTextBody>,
+}
+
+/// What a MIR body is for, which decides how its signature is written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MirTextBodyKind {
+    /// `fn PATH(ARGS) -> TYPE`
+    Fn,
+    /// `const PATH: TYPE =`
+    Const,
+    /// `static PATH: TYPE =`
+    Static,
+    /// `static mut PATH: TYPE =`
+    StaticMut,
+    /// `promoted[INDEX] in PATH: TYPE =`, a constant promoted out of another body.
+    Promoted(usize),
+    /// `PATH: TYPE =`, like the length of an array type.
+    AnonConst,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MirTextBody {
+    pub kind: MirTextBodyKind,
+    /// Whether this is the MIR used to evaluate a `const fn` at compile time, written with a
+    /// `ctfe` prefix, rather than the MIR used for codegen.
+    pub ctfe: bool,
+    /// The path of the item the body belongs to.
+    pub path: String,
+    /// The locals holding the arguments of a function, and their types.
+    pub args: Vec<(String, String)>,
+    pub return_ty: String,
+    /// The type yielded by a generator.
+    pub yield_ty: Option<String>,
+    /// The user variables, and the places holding them.
+    pub debug_info: Vec<(String, String)>,
+    /// All the locals other than the arguments, starting with the return place `_0`.
+    pub locals: Vec<MirTextLocal>,
+    pub blocks: Vec<MirTextBlock>,
+    /// The allocations the constants of the body refer to, each as the lines of its hex dump.
+    pub allocations: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MirTextLocal {
+    pub local: String,
+    pub mutable: bool,
+    pub ty: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MirTextBlock {
+    pub name: String,
+    pub cleanup: bool,
+    pub statements: Vec<String>,
+    pub terminator: String,
+}
+
+/// An error found by [`parse_mir_text`], on the given 1-based line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MirTextError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for MirTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+/// Writes the MIR of every body of the crate in the format of `--emit=mir`.
+pub fn write_mir_text<'tcx>(tcx: TyCtxt<'tcx>, w: &mut dyn Write) -> io::Result<()> {
+    let text = with_no_trimmed_paths(|| mir_text(tcx))?;
+    write!(w, "{}", text)
+}
+
+fn mir_text<'tcx>(tcx: TyCtxt<'tcx>) -> io::Result<MirText> {
+    let mut bodies = Vec::new();
+    for def_id in dump_mir_def_ids(tcx, None) {
+        // For `const fn`, both the MIR used for codegen and the one used for CTFE are written.
+        // Promoteds are shared between the two, so they are only written once.
+        let body = if tcx.is_const_fn_raw(def_id) {
+            tcx.optimized_mir(def_id)
+        } else {
+            tcx.instance_mir(ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)))
+        };
+        bodies.push(body_text(tcx, body, false)?);
+        for promoted in tcx.promoted_mir(def_id) {
+            bodies.push(body_text(tcx, promoted, false)?);
+        }
+        if tcx.is_const_fn_raw(def_id) {
+            bodies.push(body_text(tcx, tcx.mir_for_ctfe(def_id), true)?);
+        }
+    }
+    Ok(MirText { version: MIR_TEXT_FORMAT_VERSION, bodies })
+}
+
+fn body_text<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, ctfe: bool) -> io::Result<MirTextBody> {
+    let def_id = body.source.def_id();
+    let def_kind = tcx.def_kind(def_id);
+    let is_function = matches!(def_kind, DefKind::Fn | DefKind::AssocFn | DefKind::Ctor(..))
+        || tcx.is_closure(def_id);
+    let kind = match (def_kind, body.source.promoted) {
+        (_, Some(promoted)) => MirTextBodyKind::Promoted(promoted.index()),
+        (DefKind::Const | DefKind::AssocConst, _) => MirTextBodyKind::Const,
+        (DefKind::Static, _) if tcx.is_mutable_static(def_id) => MirTextBodyKind::StaticMut,
+        (DefKind::Static, _) => MirTextBodyKind::Static,
+        _ if is_function => MirTextBodyKind::Fn,
+        (DefKind::AnonConst | DefKind::InlineConst, _) => MirTextBodyKind::AnonConst,
+        _ => bug!("unexpected def kind {:?}", def_kind),
+    };
+
+    let args = body
+        .args_iter()
+        .map(|arg| (format!("{:?}", Place::from(arg)), body.local_decls[arg].ty.to_string()))
+        .collect();
+    let debug_info = body
+        .var_debug_info
+        .iter()
+        .map(|info| (info.name.to_string(), format!("{:?}", info.value)))
+        .collect();
+    let locals = body
+        .local_decls
+        .iter_enumerated()
+        .filter(|(local, _)| !(1..body.arg_count + 1).contains(&local.index()))
+        .map(|(local, decl)| MirTextLocal {
+            local: format!("{:?}", local),
+            mutable: decl.mutability == Mutability::Mut,
+            ty: decl.ty.to_string(),
+        })
+        .collect();
+    let blocks = body
+        .basic_blocks()
+        .iter_enumerated()
+        .map(|(block, data)| MirTextBlock {
+            name: format!("{:?}", block),
+            cleanup: data.is_cleanup,
+            statements: data.statements.iter().map(|s| format!("{:?}", s)).collect(),
+            terminator: format!("{:?}", data.terminator().kind),
+        })
+        .collect();
+
+    let mut allocations = Vec::new();
+    write_allocations(tcx, body, &mut allocations)?;
+    let allocations = String::from_utf8(allocations).unwrap();
+
+    Ok(MirTextBody {
+        kind,
+        ctfe,
+        path: with_forced_impl_filename_line(|| tcx.def_path_str(def_id)),
+        args,
+        return_ty: body.return_ty().to_string(),
+        yield_ty: body.yield_ty().map(|ty| ty.to_string()),
+        debug_info,
+        locals,
+        blocks,
+        allocations: split_allocations(&allocations),
+    })
+}
+
+/// Whether `line` starts one of the allocations written after a body, like `alloc1 (...`.
+fn is_allocation_start(line: &str) -> bool {
+    line.strip_prefix("alloc").map_or(false, |rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Splits the output of `write_allocations` into its allocations, without the empty lines
+/// between them.
+fn split_allocations(text: &str) -> Vec<String> {
+    let mut allocations: Vec<String> = Vec::new();
+    for line in text.lines().filter(|line| !line.is_empty()) {
+        match allocations.last_mut() {
+            Some(allocation) if !is_allocation_start(line) => {
+                allocation.push('\n');
+                allocation.push_str(line);
+            }
+            _ => allocations.push(line.to_string()),
+        }
+    }
+    allocations
+}
+
+impl fmt::Display for MirText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}{}", HEADER, self.version)?;
+        for body in &self.bodies {
+            writeln!(f)?;
+            write!(f, "{}", body)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MirTextBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctfe {
+            write!(f, "ctfe ")?;
+        }
+        match self.kind {
+            MirTextBodyKind::Fn => {
+                write!(f, "fn {}(", self.path)?;
+                for (i, (local, ty)) in self.args.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", local, ty)?;
+                }
+                writeln!(f, ") -> {} {{", self.return_ty)?;
+            }
+            _ => {
+                match self.kind {
+                    MirTextBodyKind::Const => write!(f, "const ")?,
+                    MirTextBodyKind::Static => write!(f, "static ")?,
+                    MirTextBodyKind::StaticMut => write!(f, "static mut ")?,
+                    MirTextBodyKind::Promoted(index) => write!(f, "promoted[{}] in ", index)?,
+                    MirTextBodyKind::Fn | MirTextBodyKind::AnonConst => {}
+                }
+                writeln!(f, "{}: {} = {{", self.path, self.return_ty)?;
+            }
+        }
+
+        if let Some(yield_ty) = &self.yield_ty {
+            writeln!(f, "{}yields {};", INDENT, yield_ty)?;
+        }
+        for (name, value) in &self.debug_info {
+            writeln!(f, "{}debug {} => {};", INDENT, name, value)?;
+        }
+        for local in &self.locals {
+            let mut_str = if local.mutable { "mut " } else { "" };
+            writeln!(f, "{}let {}{}: {};", INDENT, mut_str, local.local, local.ty)?;
+        }
+        for block in &self.blocks {
+            writeln!(f)?;
+            let cleanup = if block.cleanup { " (cleanup)" } else { "" };
+            writeln!(f, "{}{}{}: {{", INDENT, block.name, cleanup)?;
+            for statement in block.statements.iter().chain(Some(&block.terminator)) {
+                writeln!(f, "{0}{0}{1};", INDENT, statement)?;
+            }
+            writeln!(f, "{}}}", INDENT)?;
+        }
+        writeln!(f, "}}")?;
+
+        for allocation in &self.allocations {
+            writeln!(f)?;
+            writeln!(f, "{}", allocation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the output of `--emit=mir`. Statements, terminators, types and places are kept as
+/// the text they were written as.
+pub fn parse_mir_text(text: &str) -> Result<MirText, MirTextError> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line)).peekable();
+
+    let (_, header) = lines.next().unwrap_or((1, ""));
+    let version = header
+        .strip_prefix(HEADER)
+        .and_then(|version| version.parse().ok())
+        .ok_or_else(|| error(1, "expected the MIR text format header"))?;
+    if version != MIR_TEXT_FORMAT_VERSION {
+        return Err(error(
+            1,
+            &format!(
+                "unsupported MIR text format version {}, expected version {}",
+                version, MIR_TEXT_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    let mut bodies: Vec<MirTextBody> = Vec::new();
+    while let Some((n, line)) = lines.next() {
+        if !line.is_empty() {
+            return Err(error(n, "expected an empty line before the next body or allocation"));
+        }
+        let (n, line) = lines.next().ok_or_else(|| error(n + 1, "unexpected end of input"))?;
+
+        if is_allocation_start(line) {
+            let body = bodies
+                .last_mut()
+                .ok_or_else(|| error(n, "expected a MIR body before the allocation"))?;
+            let mut allocation = line.to_string();
+            if line.ends_with('{') {
+                loop {
+                    let (n, line) =
+                        lines.next().ok_or_else(|| error(n, "unterminated allocation"))?;
+                    if line.is_empty() {
+                        return Err(error(n, "unterminated allocation"));
+                    }
+                    allocation.push('\n');
+                    allocation.push_str(line);
+                    if line == "}" {
+                        break;
+                    }
+                }
+            }
+            body.allocations.push(allocation);
+            continue;
+        }
+
+        let mut body = parse_signature(line).ok_or_else(|| error(n, "expected a MIR body"))?;
+
+        // The declarations, up to the empty line before the first block.
+        loop {
+            let (n, line) = lines.next().ok_or_else(|| error(n, "unterminated MIR body"))?;
+            if line.is_empty() {
+                break;
+            }
+            let decl = line
+                .strip_prefix(INDENT)
+                .and_then(|decl| decl.strip_suffix(';'))
+                .ok_or_else(|| error(n, "expected a declaration"))?;
+            if let Some(yield_ty) = decl.strip_prefix("yields ") {
+                body.yield_ty = Some(yield_ty.to_string());
+            } else if let Some(debug) = decl.strip_prefix("debug ") {
+                let (name, value) = debug
+                    .split_once(" => ")
+                    .ok_or_else(|| error(n, "expected `debug NAME => PLACE;`"))?;
+                body.debug_info.push((name.to_string(), value.to_string()));
+            } else if let Some(local) = decl.strip_prefix("let ") {
+                let (mutable, local) = match local.strip_prefix("mut ") {
+                    Some(local) => (true, local),
+                    None => (false, local),
+                };
+                let (local, ty) = local
+                    .split_once(": ")
+                    .ok_or_else(|| error(n, "expected `let LOCAL: TYPE;`"))?;
+                body.locals.push(MirTextLocal {
+                    local: local.to_string(),
+                    mutable,
+                    ty: ty.to_string(),
+                });
+            } else {
+                return Err(error(n, "expected a declaration"));
+            }
+        }
+
+        // The blocks, separated by empty lines, up to the end of the body.
+        loop {
+            let (n, line) = lines.next().ok_or_else(|| error(n, "unterminated MIR body"))?;
+            let label = line
+                .strip_prefix(INDENT)
+                .and_then(|label| label.strip_suffix(": {"))
+                .ok_or_else(|| error(n, "expected a basic block"))?;
+            let (name, cleanup) = match label.strip_suffix(" (cleanup)") {
+                Some(name) => (name, true),
+                None => (label, false),
+            };
+            let mut statements = Vec::new();
+            loop {
+                let (n, line) = lines.next().ok_or_else(|| error(n, "unterminated basic block"))?;
+                if line == format!("{}}}", INDENT) {
+                    break;
+                }
+                let statement = line
+                    .strip_prefix(INDENT)
+                    .and_then(|line| line.strip_prefix(INDENT))
+                    .and_then(|line| line.strip_suffix(';'))
+                    .ok_or_else(|| error(n, "expected a statement or terminator"))?;
+                statements.push(statement.to_string());
+            }
+            let terminator =
+                statements.pop().ok_or_else(|| error(n, "basic block without a terminator"))?;
+            let name = name.to_string();
+            body.blocks.push(MirTextBlock { name, cleanup, statements, terminator });
+
+            match lines.next() {
+                Some((_, "}")) => break,
+                Some((_, "")) => {}
+                Some((n, _)) => return Err(error(n, "expected an empty line or `}`")),
+                None => return Err(error(n, "unterminated MIR body")),
+            }
+        }
+        bodies.push(body);
+    }
+
+    Ok(MirText { version, bodies })
+}
+
+fn error(line: usize, msg: &str) -> MirTextError {
+    MirTextError { line, msg: msg.to_string() }
+}
+
+/// Parses the first line of a body, up to its ` {`.
+fn parse_signature(line: &str) -> Option<MirTextBody> {
+    let line = line.strip_suffix(" {")?;
+    let (ctfe, line) = match line.strip_prefix("ctfe ") {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let mut body = MirTextBody {
+        kind: MirTextBodyKind::Fn,
+        ctfe,
+        path: String::new(),
+        args: Vec::new(),
+        return_ty: String::new(),
+        yield_ty: None,
+        debug_info: Vec::new(),
+        locals: Vec::new(),
+        blocks: Vec::new(),
+        allocations: Vec::new(),
+    };
+
+    if let Some(sig) = line.strip_prefix("fn ") {
+        let open = sig.find('(')?;
+        let close = open + 1 + find_at_depth_zero(&sig[open + 1..], ")")?;
+        let args = &sig[open + 1..close];
+        body.path = sig[..open].to_string();
+        body.return_ty = sig[close..].strip_prefix(") -> ")?.to_string();
+        body.args = split_at_depth_zero(args, ", ")
+            .into_iter()
+            .map(|arg| {
+                let (local, ty) = arg.split_once(": ")?;
+                Some((local.to_string(), ty.to_string()))
+            })
+            .collect::<Option<_>>()?;
+        return Some(body);
+    }
+
+    let line = line.strip_suffix(" =")?;
+    let (kind, line) = if let Some(line) = line.strip_prefix("const ") {
+        (MirTextBodyKind::Const, line)
+    } else if let Some(line) = line.strip_prefix("static mut ") {
+        (MirTextBodyKind::StaticMut, line)
+    } else if let Some(line) = line.strip_prefix("static ") {
+        (MirTextBodyKind::Static, line)
+    } else if let Some(line) = line.strip_prefix("promoted[") {
+        let (index, line) = line.split_once("] in ")?;
+        (MirTextBodyKind::Promoted(index.parse().ok()?), line)
+    } else {
+        (MirTextBodyKind::AnonConst, line)
+    };
+    let colon = find_at_depth_zero(line, ": ")?;
+    body.kind = kind;
+    body.path = line[..colon].to_string();
+    body.return_ty = line[colon + 2..].to_string();
+    Some(body)
+}
+
+/// Finds the first `pattern` of `s` that isn't nested in brackets of any kind. The `>` of a
+/// `->` doesn't close a bracket.
+fn find_at_depth_zero(s: &str, pattern: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut prev = None;
+    for (i, c) in s.char_indices() {
+        if depth == 0 && s[i..].starts_with(pattern) {
+            return Some(i);
+        }
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == Some('-') => {}
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    None
+}
+
+fn split_at_depth_zero<'a>(mut s: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    if s.is_empty() {
+        return parts;
+    }
+    while let Some(i) = find_at_depth_zero(s, separator) {
+        parts.push(&s[..i]);
+        s = &s[i + separator.len()..];
+    }
+    parts.push(s);
+    parts
+}
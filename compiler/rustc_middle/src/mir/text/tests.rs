@@ -0,0 +1,138 @@
+use super::*;
+
+const TEXT: &str = r#"// rustc MIR text format, version 1
+
+fn main() -> () {
+    let mut _0: ();
+    let _1: i32;
+
+    bb0: {
+        _1 = foo(const 1_i32, const 2_i32) -> bb1;
+    }
+
+    bb1: {
+        return;
+    }
+}
+
+fn foo(_1: i32, _2: (i32, [closure@main.rs:3:13: 3:20])) -> i32 {
+    debug x => _1;
+    debug y => (_2.0: i32);
+    let mut _0: i32;
+    let mut _3: (i32, bool);
+
+    bb0: {
+        _3 = CheckedAdd(_1, (_2.0: i32));
+        assert(!move (_3.1: bool), "attempt to compute `{} + {}`, which would overflow", _1, (_2.0: i32)) -> [success: bb1, unwind: bb2];
+    }
+
+    bb1: {
+        _0 = move (_3.0: i32);
+        return;
+    }
+
+    bb2 (cleanup): {
+        resume;
+    }
+}
+
+promoted[0] in foo: &[u8; 4] = {
+    let mut _0: &[u8; 4];
+
+    bb0: {
+        _0 = const b"abcd";
+        return;
+    }
+}
+
+alloc1 (size: 4, align: 1) {
+    61 62 63 64                                     │ abcd
+}
+
+alloc2 (fn: main)
+
+static mut COUNTER: u32 = {
+    let mut _0: u32;
+
+    bb0: {
+        _0 = const 0_u32;
+        return;
+    }
+}
+
+ctfe fn <impl at main.rs:10:1: 14:2>::new() -> Foo {
+    yields u32;
+    let mut _0: Foo;
+
+    bb0: {
+        return;
+    }
+}
+"#;
+
+#[test]
+fn test_parse_mir_text() {
+    let text = parse_mir_text(TEXT).unwrap();
+    assert_eq!(text.version, MIR_TEXT_FORMAT_VERSION);
+    assert_eq!(text.bodies.len(), 5);
+
+    let foo = &text.bodies[1];
+    assert_eq!(foo.kind, MirTextBodyKind::Fn);
+    assert_eq!(foo.path, "foo");
+    assert_eq!(
+        foo.args,
+        vec![
+            ("_1".to_string(), "i32".to_string()),
+            ("_2".to_string(), "(i32, [closure@main.rs:3:13: 3:20])".to_string()),
+        ]
+    );
+    assert_eq!(foo.return_ty, "i32");
+    assert_eq!(foo.debug_info[1], ("y".to_string(), "(_2.0: i32)".to_string()));
+    assert_eq!(foo.locals.len(), 2);
+    assert_eq!(
+        foo.locals[1],
+        MirTextLocal { local: "_3".to_string(), mutable: true, ty: "(i32, bool)".to_string() }
+    );
+    assert_eq!(foo.blocks.len(), 3);
+    assert_eq!(foo.blocks[0].statements, vec!["_3 = CheckedAdd(_1, (_2.0: i32))".to_string()]);
+    assert!(foo.blocks[0].terminator.ends_with("-> [success: bb1, unwind: bb2]"));
+    assert!(foo.blocks[2].cleanup);
+    assert_eq!(foo.blocks[2].terminator, "resume");
+
+    let promoted = &text.bodies[2];
+    assert_eq!(promoted.kind, MirTextBodyKind::Promoted(0));
+    assert_eq!(promoted.return_ty, "&[u8; 4]");
+    assert_eq!(promoted.allocations.len(), 2);
+    assert_eq!(promoted.allocations[1], "alloc2 (fn: main)");
+
+    assert_eq!(text.bodies[3].kind, MirTextBodyKind::StaticMut);
+    assert_eq!(text.bodies[3].path, "COUNTER");
+
+    let new = &text.bodies[4];
+    assert!(new.ctfe);
+    assert_eq!(new.path, "<impl at main.rs:10:1: 14:2>::new");
+    assert_eq!(new.yield_ty.as_deref(), Some("u32"));
+}
+
+#[test]
+fn test_mir_text_round_trip() {
+    assert_eq!(parse_mir_text(TEXT).unwrap().to_string(), TEXT);
+}
+
+#[test]
+fn test_parse_mir_text_errors() {
+    let error = parse_mir_text("// rustc MIR text format, version 0\n").unwrap_err();
+    assert_eq!(error.line, 1);
+    assert!(error.msg.starts_with("unsupported MIR text format version 0"));
+
+    let error = parse_mir_text("fn main() -> () {\n}\n").unwrap_err();
+    assert_eq!(error, MirTextError { line: 1, msg: "expected the MIR text format header".into() });
+
+    let text = TEXT.replace("        resume;\n", "");
+    let error = parse_mir_text(&text).unwrap_err();
+    assert_eq!(error.msg, "basic block without a terminator");
+
+    let text = TEXT.replace("    let _1: i32;\n", "    let _1 i32;\n");
+    let error = parse_mir_text(&text).unwrap_err();
+    assert_eq!(error, MirTextError { line: 5, msg: "expected `let LOCAL: TYPE;`".into() });
+}
@@ -697,6 +697,13 @@
         desc { |tcx| "linting {}", describe_as_module(key, tcx) }
     }
 
+    /// Runs the passes of `LintStore::register_late_aggregating_pass` on the module, returning
+    /// the state each of them aggregated and the lints they emitted.
+    query lint_aggregating_mod(key: LocalDefId) -> ModuleLintAggregates {
+        storage(ArenaCacheSelector<'tcx>)
+        desc { |tcx| "linting {} with the aggregating lint passes", describe_as_module(key, tcx) }
+    }
+
     /// Checks the attributes in the module.
     query check_mod_attrs(key: LocalDefId) -> () {
         desc { |tcx| "checking attributes in {}", describe_as_module(key, tcx) }
@@ -1258,6 +1265,19 @@
         desc { "query a crate's symbol mangling version" }
         separate_provide_extern
     }
+    /// Which retag statements the MIR of a crate has, if any. Interpreters like Miri check it
+    /// to know which aliasing violations they can detect in the crate.
+    query retag_granularity(_: CrateNum) -> Option<RetagGranularity> {
+        fatal_cycle
+        desc { "query a crate's retag granularity" }
+        separate_provide_extern
+    }
+    /// The prefix namespacing the symbols the crate defines, from `-Z symbol-prefix`.
+    query symbol_prefix(_: CrateNum) -> Option<Symbol> {
+        fatal_cycle
+        desc { "query a crate's symbol prefix" }
+        separate_provide_extern
+    }
 
     query extern_crate(def_id: DefId) -> Option<&'tcx ExternCrate> {
         eval_always
@@ -1372,6 +1392,27 @@
         desc { "available upstream drop-glue for `{:?}`", substs }
     }
 
+    /// The index of `-Z shared-generics-dir`, as written by the crates compiled before the local
+    /// crate. It is read again on each compilation since other crates may have updated it.
+    ///
+    /// You likely want to call `Instance::shared_generics_provider()` instead of invoking this
+    /// query directly.
+    query shared_generics_index(_: ()) -> SharedGenericsIndex {
+        storage(ArenaCacheSelector<'tcx>)
+        eval_always
+        no_hash
+        desc { "reading the shared generics index" }
+    }
+
+    /// The `StableCrateId`s of the crates of the `-Z shared-generics-dir` index that the crate
+    /// links to generic instances of, which must be linked together with it. They are recorded
+    /// in the metadata of the crate.
+    query shared_generics_providers(_: CrateNum) -> &'tcx [u64] {
+        eval_always
+        desc { "looking up the crates providing the shared generic instances of a crate" }
+        separate_provide_extern
+    }
+
     query foreign_modules(_: CrateNum) -> Lrc<FxHashMap<DefId, ForeignModule>> {
         desc { "looking up the foreign modules of a linked crate" }
         separate_provide_extern
@@ -1503,6 +1544,12 @@
         desc { "fetching what a crate is named" }
         separate_provide_extern
     }
+    /// Gets the version of the crate, given with `--crate-version` when it was built.
+    query crate_version(_: CrateNum) -> Option<Symbol> {
+        eval_always
+        desc { "fetching the version of a crate" }
+        separate_provide_extern
+    }
     query item_children(def_id: DefId) -> &'tcx [Export] {
         desc { |tcx| "collecting child items of `{}`", tcx.def_path_str(def_id) }
         separate_provide_extern
@@ -1573,6 +1620,12 @@
         eval_always
         desc { "generating a postorder list of CrateNums" }
     }
+    /// The crates the upstream crate `cnum` depends on, directly or indirectly, in postorder,
+    /// followed by `cnum` itself. For the local crate, this is the same as `postorder_cnums`.
+    query crate_dependencies(cnum: CrateNum) -> &'tcx [CrateNum] {
+        eval_always
+        desc { "generating a postorder list of the dependencies of crate `{}`", cnum }
+    }
     /// Returns whether or not the crate with CrateNum 'cnum'
     /// is marked as a private dependency
     query is_private_dep(c: CrateNum) -> bool {
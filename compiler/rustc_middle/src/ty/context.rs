@@ -1215,9 +1215,13 @@ pub fn const_error(self, ty: Ty<'tcx>) -> &'tcx Const<'tcx> {
         self.mk_const(ty::Const { val: ty::ConstKind::Error(DelaySpanBugEmitted(())), ty })
     }
 
-    pub fn consider_optimizing<T: Fn() -> String>(self, msg: T) -> bool {
+    /// Checks whether the optimization `msg` in the pass `pass` may be applied under
+    /// `-Z fuel`, consuming fuel if so.
+    pub fn consider_optimizing<T: Fn() -> String>(self, pass: &str, msg: T) -> bool {
         let cname = self.crate_name(LOCAL_CRATE).as_str();
-        self.sess.consider_optimizing(&cname, msg)
+        // `msg` may name items, and is also computed for `-Z fuel-trace` when no
+        // diagnostic is emitted, so trimmed paths must not be used for it.
+        self.sess.consider_optimizing(&cname, pass, || ty::print::with_no_trimmed_paths(&msg))
     }
 
     /// Obtain all lang items of this crate and all dependencies (recursively)
@@ -2602,7 +2606,7 @@ pub fn maybe_lint_level_root_bounded(self, mut id: HirId, bound: HirId) -> HirId
                 return bound;
             }
 
-            if hir.attrs(id).iter().any(|attr| Level::from_symbol(attr.name_or_empty()).is_some()) {
+            if hir.attrs(id).iter().any(|attr| Level::from_attr(attr).is_some()) {
                 return id;
             }
             let next = hir.get_parent_node(id);
@@ -2816,6 +2820,10 @@ pub fn provide(providers: &mut ty::query::Providers) {
         assert_eq!(id, LOCAL_CRATE);
         tcx.crate_name
     };
+    providers.crate_version = |tcx, id| {
+        assert_eq!(id, LOCAL_CRATE);
+        tcx.sess.crate_version().map(Symbol::intern)
+    };
     providers.maybe_unused_trait_import =
         |tcx, id| tcx.resolutions(()).maybe_unused_trait_imports.contains(&id);
     providers.maybe_unused_extern_crates =
@@ -135,6 +135,30 @@ pub fn upstream_monomorphization(&self, tcx: TyCtxt<'tcx>) -> Option<CrateNum> {
             _ => None,
         }
     }
+
+    /// If this instance is not provided by an upstream crate, returns the crate of the
+    /// `-Z shared-generics-dir` index that exports it, by `StableCrateId`, along with the symbol
+    /// name it exports the instance as. The local crate then links to that instance instead of
+    /// instantiating its own.
+    pub fn shared_generics_provider(&self, tcx: TyCtxt<'tcx>) -> Option<(u64, &'tcx str)> {
+        if tcx.sess.opts.debugging_opts.shared_generics_dir.is_none()
+            || !tcx.sess.opts.share_generics()
+            || self.def_id().is_local()
+        {
+            return None;
+        }
+
+        self.substs.non_erasable_generics().next()?;
+
+        match self.def {
+            InstanceDef::Item(_) | InstanceDef::DropGlue(_, Some(_)) => {}
+            _ => return None,
+        }
+        if self.upstream_monomorphization(tcx).is_some() {
+            return None;
+        }
+        tcx.shared_generics_index(()).provider(tcx, *self)
+    }
 }
 
 impl<'tcx> InstanceDef<'tcx> {
@@ -3084,7 +3084,7 @@ fn fn_abi_new_uncached(
                     // Due to past miscompiles in LLVM, we apply a separate NoAliasMutRef attribute
                     // for UniqueBorrowed arguments, so that the codegen backend can decide whether
                     // or not to actually emit the attribute. It can also be controlled with the
-                    // `-Zmutable-noalias` debugging option.
+                    // `-Zmutable-noalias` and `-Zaliasing-model` debugging options.
                     let no_alias = match kind {
                         PointerKind::Shared | PointerKind::UniqueBorrowed => false,
                         PointerKind::UniqueOwned => true,
@@ -1640,7 +1640,13 @@ pub fn new(tcx: TyCtxt<'_>, did: DefId) -> ReprOptions {
 
         // Generate a deterministically-derived seed from the item's path hash
         // to allow for cross-crate compilation to actually work
-        let field_shuffle_seed = tcx.def_path_hash(did).0.to_smaller_hash();
+        let mut field_shuffle_seed = tcx.def_path_hash(did).0.to_smaller_hash();
+
+        // `-Z layout-seed` lets the user pick a different (but still reproducible)
+        // randomization for every type
+        if let Some(user_seed) = tcx.sess.opts.debugging_opts.layout_seed {
+            field_shuffle_seed ^= user_seed;
+        }
 
         for attr in tcx.get_attrs(did).iter() {
             for r in attr::find_repr_attrs(&tcx.sess, attr) {
@@ -1677,7 +1683,9 @@ pub fn new(tcx: TyCtxt<'_>, did: DefId) -> ReprOptions {
         }
 
         // This is here instead of layout because the choice must make it into metadata.
-        if !tcx.consider_optimizing(|| format!("Reorder fields of {:?}", tcx.def_path_str(did))) {
+        if !tcx.consider_optimizing("ReorderFields", || {
+            format!("Reorder fields of {:?}", tcx.def_path_str(did))
+        }) {
             flags.insert(ReprFlags::IS_LINEAR);
         }
 
@@ -1,9 +1,9 @@
 use crate::dep_graph;
 use crate::hir::exports::Export;
 use crate::infer::canonical::{self, Canonical};
-use crate::lint::LintLevelMap;
+use crate::lint::{LintLevelMap, ModuleLintAggregates};
 use crate::middle::codegen_fn_attrs::CodegenFnAttrs;
-use crate::middle::exported_symbols::{ExportedSymbol, SymbolExportLevel};
+use crate::middle::exported_symbols::{ExportedSymbol, SharedGenericsIndex, SymbolExportLevel};
 use crate::middle::lib_features::LibFeatures;
 use crate::middle::privacy::AccessLevels;
 use crate::middle::region;
@@ -43,11 +43,12 @@
 use rustc_hir::lang_items::{LangItem, LanguageItems};
 use rustc_hir::{Crate, ItemLocalId, TraitCandidate};
 use rustc_index::{bit_set::FiniteBitSet, vec::IndexVec};
-use rustc_session::config::{EntryFnType, OptLevel, OutputFilenames, SymbolManglingVersion};
+use rustc_session::config::SymbolManglingVersion;
+use rustc_session::config::{EntryFnType, OptLevel, OutputFilenames, RetagGranularity};
 use rustc_session::cstore::{CrateDepKind, CrateSource};
 use rustc_session::cstore::{ExternCrate, ForeignModule, LinkagePreference, NativeLib};
 use rustc_session::utils::NativeLibKind;
-use rustc_session::Limits;
+use rustc_session::{Limits, QueryKeyOutcome};
 use rustc_target::abi;
 use rustc_target::spec::PanicStrategy;
 
@@ -202,6 +203,9 @@ pub mod query_stored {
             $(pub type $name<$tcx> = <query_storage::$name<$tcx> as QueryStorage>::Stored;)*
         }
 
+        /// The names of all the queries, for `-Z profile-query-keys`.
+        pub const QUERY_NAMES: &[&str] = &[$(stringify!($name),)*];
+
         #[derive(Default)]
         pub struct QueryCaches<$tcx> {
             $($(#[$attr])* pub $name: QueryCacheStore<query_storage::$name<$tcx>>,)*
@@ -217,7 +221,11 @@ pub fn $name(self, key: query_helper_param_ty!($($K)*)) {
                 let cached = try_get_cached(self.tcx, &self.tcx.query_caches.$name, &key, noop);
 
                 let lookup = match cached {
-                    Ok(()) => return,
+                    Ok(()) => {
+                        let outcome = QueryKeyOutcome::CacheHit;
+                        self.tcx.sess.record_query_key(stringify!($name), &key, outcome);
+                        return;
+                    }
                     Err(lookup) => lookup,
                 };
 
@@ -246,7 +254,11 @@ pub fn $name(self, key: query_helper_param_ty!($($K)*)) -> query_stored::$name<$
                 let cached = try_get_cached(self.tcx, &self.tcx.query_caches.$name, &key, Clone::clone);
 
                 let lookup = match cached {
-                    Ok(value) => return value,
+                    Ok(value) => {
+                        let outcome = QueryKeyOutcome::CacheHit;
+                        self.tcx.sess.record_query_key(stringify!($name), &key, outcome);
+                        return value;
+                    }
                     Err(lookup) => lookup,
                 };
 
@@ -3,6 +3,7 @@
 use crate::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use crate::ty::fold::{FallibleTypeFolder, TypeFolder};
 use crate::ty::layout::IntegerExt;
+use crate::ty::print::with_no_trimmed_paths;
 use crate::ty::query::TyCtxtAt;
 use crate::ty::subst::{GenericArgKind, Subst, SubstsRef};
 use crate::ty::TyKind::*;
@@ -18,9 +19,11 @@
 use rustc_hir::def_id::DefId;
 use rustc_macros::HashStable;
 use rustc_query_system::ich::NodeIdHashingMode;
+use rustc_session::TimingPhase;
 use rustc_span::DUMMY_SP;
 use rustc_target::abi::{Integer, Size, TargetDataLayout};
 use smallvec::SmallVec;
+use std::time::Duration;
 use std::{fmt, iter};
 
 #[derive(Copy, Clone, Debug)]
@@ -124,6 +127,25 @@ fn disr_incr<'tcx>(&self, tcx: TyCtxt<'tcx>, val: Option<Discr<'tcx>>) -> Option
 }
 
 impl<'tcx> TyCtxt<'tcx> {
+    /// Records, for `-Z module-timings`, that `phase` spent `duration` on the item `def_id`.
+    /// The items of other crates, such as the generic functions this crate instantiates, are
+    /// attributed to their crate rather than to a module.
+    pub fn record_module_time(self, def_id: DefId, phase: TimingPhase, duration: Duration) {
+        let path = |def_id| with_no_trimmed_paths(|| self.def_path_str(def_id));
+        let module = match def_id.as_local() {
+            Some(local) => {
+                let module = self.parent_module_from_def_id(local).to_def_id();
+                if module.is_top_level_module() {
+                    "crate".to_string()
+                } else {
+                    format!("crate::{}", path(module))
+                }
+            }
+            None => self.crate_name(def_id.krate).to_string(),
+        };
+        self.sess.module_timings.record(module, path(def_id), phase, duration);
+    }
+
     /// Creates a hash of the type `Ty` which will be the same no matter what crate
     /// context it's calculated within. This is used by the `type_id` intrinsic.
     pub fn type_id_hash(self, ty: Ty<'tcx>) -> u64 {
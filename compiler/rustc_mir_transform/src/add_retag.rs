@@ -59,10 +59,15 @@ fn may_be_reference(ty: Ty<'_>) -> bool {
 
 impl<'tcx> MirPass<'tcx> for AddRetag {
     fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
-        sess.opts.debugging_opts.mir_emit_retag
+        sess.opts.mir_emit_retag()
     }
 
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let granularity = match tcx.sess.opts.retag_granularity() {
+            Some(granularity) => granularity,
+            None => return,
+        };
+
         // We need an `AllCallEdges` pass before we can do any work.
         super::add_call_guards::AllCallEdges.run_pass(tcx, body);
 
@@ -112,13 +117,14 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
 
         // PART 2
         // Retag return values of functions.  Also escape-to-raw the argument of `drop`.
+        // This is skipped unless copies of references are retagged too.
         // We collect the return destinations because we cannot mutate while iterating.
         let returns = basic_blocks
             .iter_mut()
             .filter_map(|block_data| {
                 match block_data.terminator().kind {
                     TerminatorKind::Call { destination: Some(ref destination), .. }
-                        if needs_retag(&destination.0) =>
+                        if granularity.retags_copies() && needs_retag(&destination.0) =>
                     {
                         // Remember the return destination for later
                         Some((block_data.terminator().source_info, destination.0, destination.1))
@@ -143,7 +149,8 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         }
 
         // PART 3
-        // Add retag after assignment.
+        // Add retag after assignment. With `-Z mir-emit-retag=fn-entry-only`, only the
+        // retags-as-raw are added.
         for block_data in basic_blocks {
             // We want to insert statements as we iterate.  To this end, we
             // iterate backwards using indices.
@@ -156,15 +163,20 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
                     {
                         (RetagKind::Raw, lplace)
                     }
-                    // Retag after assignments of reference type.
-                    StatementKind::Assign(box (ref place, ref rvalue)) if needs_retag(place) => {
+                    // Retag after assignments of reference type. Unless copies are retagged too,
+                    // only retag the assignments that create a reference.
+                    StatementKind::Assign(box (ref place, ref rvalue))
+                        if granularity.retags_creations() && needs_retag(place) =>
+                    {
                         let kind = match rvalue {
                             Rvalue::Ref(_, borrow_kind, _)
                                 if borrow_kind.allows_two_phase_borrow() =>
                             {
                                 RetagKind::TwoPhase
                             }
-                            _ => RetagKind::Default,
+                            Rvalue::Ref(..) => RetagKind::Default,
+                            _ if granularity.retags_copies() => RetagKind::Default,
+                            _ => continue,
                         };
                         (kind, *place)
                     }
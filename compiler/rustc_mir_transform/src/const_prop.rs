@@ -912,7 +912,9 @@ fn should_const_prop(&mut self, op: &OpTy<'tcx>) -> bool {
             return false;
         }
 
-        if !self.tcx.consider_optimizing(|| format!("ConstantPropagation - OpTy: {:?}", op)) {
+        if !self.tcx.consider_optimizing("ConstantPropagation", || {
+            format!("ConstantPropagation - OpTy: {:?}", op)
+        }) {
             return false;
         }
 
@@ -191,7 +191,7 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
                 continue;
             }
 
-            if !tcx.consider_optimizing(|| {
+            if !tcx.consider_optimizing("DestinationPropagation", || {
                 format!("DestinationPropagation {:?} {:?}", def_id, candidate)
             }) {
                 break;
@@ -5,7 +5,7 @@
 use std::io;
 
 use crate::MirPass;
-use rustc_middle::mir::write_mir_pretty;
+use rustc_middle::mir::text::write_mir_text;
 use rustc_middle::mir::Body;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::{OutputFilenames, OutputType};
@@ -23,6 +23,6 @@ fn run_pass(&self, _tcx: TyCtxt<'tcx>, _body: &mut Body<'tcx>) {}
 pub fn emit_mir(tcx: TyCtxt<'_>, outputs: &OutputFilenames) -> io::Result<()> {
     let path = outputs.path(OutputType::Mir);
     let mut f = io::BufWriter::new(File::create(&path)?);
-    write_mir_pretty(tcx, None, &mut f)?;
+    write_mir_text(tcx, &mut f)?;
     Ok(())
 }
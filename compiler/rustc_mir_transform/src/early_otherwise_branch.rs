@@ -49,7 +49,9 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         let should_cleanup = !opts_to_apply.is_empty();
 
         for opt_to_apply in opts_to_apply {
-            if !tcx.consider_optimizing(|| format!("EarlyOtherwiseBranch {:?}", &opt_to_apply)) {
+            if !tcx.consider_optimizing("EarlyOtherwiseBranch", || {
+                format!("EarlyOtherwiseBranch {:?}", &opt_to_apply)
+            }) {
                 break;
             }
 
@@ -147,7 +147,7 @@ fn try_inlining(
         let callee_body = self.tcx.instance_mir(callsite.callee.def);
         self.check_mir_body(callsite, callee_body, callee_attrs)?;
 
-        if !self.tcx.consider_optimizing(|| {
+        if !self.tcx.consider_optimizing("Inline", || {
             format!("Inline {:?} into {:?}", callsite.callee, caller_body.source)
         }) {
             return Err("optimization fuel exhausted");
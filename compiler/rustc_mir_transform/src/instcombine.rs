@@ -40,7 +40,7 @@ struct InstCombineContext<'tcx, 'a> {
 
 impl<'tcx> InstCombineContext<'tcx, '_> {
     fn should_combine(&self, source_info: &SourceInfo, rvalue: &Rvalue<'tcx>) -> bool {
-        self.tcx.consider_optimizing(|| {
+        self.tcx.consider_optimizing("InstCombine", || {
             format!("InstCombine - Rvalue: {:?} SourceInfo: {:?}", rvalue, source_info)
         })
     }
@@ -27,7 +27,9 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         }
 
         for bb in bbs {
-            if !tcx.consider_optimizing(|| format!("MultipleReturnTerminators {:?} ", def_id)) {
+            if !tcx.consider_optimizing("MultipleReturnTerminators", || {
+                format!("MultipleReturnTerminators {:?} ", def_id)
+            }) {
                 break;
             }
 
@@ -47,7 +47,9 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut mir::Body<'tcx>) {
             }
         };
 
-        if !tcx.consider_optimizing(|| format!("RenameReturnPlace {:?}", def_id)) {
+        if !tcx
+            .consider_optimizing("RenameReturnPlace", || format!("RenameReturnPlace {:?}", def_id))
+        {
             return;
         }
 
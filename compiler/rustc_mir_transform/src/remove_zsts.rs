@@ -36,7 +36,7 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
                     if involves_a_union(place, local_decls, tcx) {
                         continue;
                     }
-                    if tcx.consider_optimizing(|| {
+                    if tcx.consider_optimizing("RemoveZsts", || {
                         format!(
                             "RemoveZsts - Place: {:?} SourceInfo: {:?}",
                             place, statement.source_info
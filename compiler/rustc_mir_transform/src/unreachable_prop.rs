@@ -49,7 +49,7 @@ fn run_pass<'tcx>(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
 
         let replaced = !replacements.is_empty();
         for (bb, terminator_kind) in replacements {
-            if !tcx.consider_optimizing(|| {
+            if !tcx.consider_optimizing("UnreachablePropagation", || {
                 format!("UnreachablePropagation {:?} ", body.source.def_id())
             }) {
                 break;
@@ -0,0 +1,424 @@
+//! The output of `--emit callgraph` and `--emit callgraph-dot`, the call graph of the functions
+//! the crate generates code for, at the level of mono items.
+//!
+//! Each function is identified by its symbol name, which is what tools looking at the object
+//! files or the LLVM IR of the crate see. The edges are the call sites of the optimized MIR
+//! used for code generation, so calls inlined by the MIR inliner are attributed to the
+//! function they were inlined into. Calls through a vtable or a function pointer are indirect
+//! edges without a callee; the functions that can be reached this way are marked as having
+//! their address taken instead.
+//!
+//! The JSON format is documented in `src/doc/rustc/src/json.md`, and any incompatible change to
+//! it must bump `FORMAT_VERSION`. The DOT rendering is for looking at the graph, and is
+//! described in `src/doc/rustc/src/command-line-arguments.md`.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_graphviz as dot;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_hir::lang_items::LangItem;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::mir::visit::Visitor as MirVisitor;
+use rustc_middle::mir::{self, Location};
+use rustc_middle::ty::adjustment::PointerCast;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, TyCtxt, TypeFoldable};
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{OutputFilenames, OutputType};
+use rustc_span::Span;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::collector::for_each_unsize_vtable_fn;
+use crate::util::{location_json, object};
+
+/// The version of the JSON format described in the module documentation.
+const FORMAT_VERSION: u32 = 1;
+
+/// Writes the call graph of the crate for `--emit callgraph` and `--emit callgraph-dot`.
+pub fn write_call_graph(tcx: TyCtxt<'_>, outputs: &OutputFilenames) {
+    let graph = tcx.sess.time("call_graph", || with_no_trimmed_paths(|| build_call_graph(tcx)));
+    for output_type in [OutputType::CallGraph, OutputType::CallGraphDot] {
+        if !tcx.sess.opts.output_types.contains_key(&output_type) {
+            continue;
+        }
+        let path = outputs.path(output_type);
+        let result = match output_type {
+            OutputType::CallGraph => emit_json(tcx, &graph, &path),
+            _ => emit_dot(&graph, &path),
+        };
+        if let Err(e) = result {
+            tcx.sess.err(&format!("could not emit call graph to `{}`: {}", path.display(), e));
+            tcx.sess.abort_if_errors();
+        }
+        tcx.sess.record_artifact(&path, output_type.shorthand());
+    }
+}
+
+fn build_call_graph(tcx: TyCtxt<'_>) -> CallGraph {
+    let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+    let mut graph = CallGraph { nodes: BTreeMap::new(), edges: Vec::new() };
+    // Functions available for inlining are placed in several codegen units.
+    let mut instances: Vec<_> = codegen_units
+        .iter()
+        .flat_map(|cgu| cgu.items().keys())
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance) => Some(instance),
+            MonoItem::Static(_) | MonoItem::GlobalAsm(_) => None,
+        })
+        .map(|instance| {
+            let (node, symbol) = graph.node(tcx, instance);
+            node.defined = true;
+            (symbol, instance)
+        })
+        .collect();
+    instances.sort_by(|(a, _), (b, _)| a.cmp(b));
+    instances.dedup_by(|(a, _), (b, _)| a == b);
+
+    for (caller, instance) in instances {
+        let body = tcx.instance_mir(instance.def);
+        CallGraphBuilder { tcx, body, instance, caller, graph: &mut graph }.visit_body(body);
+    }
+    graph
+}
+
+fn emit_json(tcx: TyCtxt<'_>, graph: &CallGraph, path: &Path) -> io::Result<()> {
+    let (nodes, edges) = graph.to_json();
+    let json = object(vec![
+        ("format_version", FORMAT_VERSION.to_json()),
+        ("crate_name", tcx.crate_name(LOCAL_CRATE).to_string().to_json()),
+        ("nodes", nodes),
+        ("edges", edges),
+    ]);
+
+    let mut file = io::BufWriter::new(File::create(path)?);
+    writeln!(file, "{}", json)?;
+    file.flush()
+}
+
+fn emit_dot(graph: &CallGraph, path: &Path) -> io::Result<()> {
+    let mut file = io::BufWriter::new(File::create(path)?);
+    dot::render_opts(&graph.to_dot(), &mut file, &[dot::RenderOption::NoEdgeLabels])?;
+    file.flush()
+}
+
+struct Node {
+    name: String,
+    crate_name: String,
+    kind: &'static str,
+    location: Json,
+    /// Whether the crate generates code for the function.
+    defined: bool,
+    /// Whether the function can be called through a function pointer or a vtable.
+    address_taken: bool,
+}
+
+struct Edge {
+    /// The symbol name of the calling function.
+    caller: String,
+    /// The symbol name of the called function, for direct calls.
+    callee: Option<String>,
+    via: &'static str,
+    /// For calls through a vtable, the path of the trait method.
+    trait_method: Option<String>,
+    location: Json,
+}
+
+struct CallGraph {
+    /// The functions of the graph, by symbol name.
+    nodes: BTreeMap<String, Node>,
+    edges: Vec<Edge>,
+}
+
+impl CallGraph {
+    /// Returns the node of `instance`, adding it to the graph if it isn't in it yet, and its
+    /// symbol name.
+    fn node<'tcx>(&mut self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> (&mut Node, String) {
+        let symbol = tcx.symbol_name(instance).name.to_string();
+        let node = self.nodes.entry(symbol.clone()).or_insert_with(|| {
+            let def_id = instance.def_id();
+            let kind = match instance.def {
+                ty::InstanceDef::Item(_) if tcx.is_closure(def_id) => "closure",
+                ty::InstanceDef::Item(_) => "fn",
+                ty::InstanceDef::DropGlue(..) => "drop-glue",
+                _ => "shim",
+            };
+            Node {
+                name: instance.to_string(),
+                crate_name: tcx.crate_name(def_id.krate).to_string(),
+                kind,
+                location: location_json(tcx, tcx.def_span(def_id)),
+                defined: false,
+                address_taken: false,
+            }
+        });
+        (node, symbol)
+    }
+
+    fn take_address<'tcx>(&mut self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) {
+        self.node(tcx, instance).0.address_taken = true;
+    }
+
+    fn to_json(&self) -> (Json, Json) {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(symbol, node)| {
+                object(vec![
+                    ("symbol", symbol.to_json()),
+                    ("name", node.name.to_json()),
+                    ("crate", node.crate_name.to_json()),
+                    ("kind", node.kind.to_json()),
+                    ("location", node.location.clone()),
+                    ("defined", node.defined.to_json()),
+                    ("address_taken", node.address_taken.to_json()),
+                ])
+            })
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let kind = if edge.callee.is_some() { "direct" } else { "indirect" };
+                object(vec![
+                    ("caller", edge.caller.to_json()),
+                    ("callee", edge.callee.to_json()),
+                    ("kind", kind.to_json()),
+                    ("via", edge.via.to_json()),
+                    ("trait_method", edge.trait_method.to_json()),
+                    ("location", edge.location.clone()),
+                ])
+            })
+            .collect();
+        (Json::Array(nodes), Json::Array(edges))
+    }
+
+    /// Returns the graph to render as DOT. The indirect calls go to a node standing for the
+    /// functions they can call: one for each trait method called through a vtable, and one for
+    /// all the calls through function pointers.
+    fn to_dot(&self) -> DotGraph<'_> {
+        let mut nodes: Vec<_> = self.nodes.values().map(DotNode::Fn).collect();
+        let indices: FxHashMap<&str, usize> =
+            self.nodes.keys().enumerate().map(|(i, symbol)| (&symbol[..], i)).collect();
+        let mut indirect_targets: FxHashMap<String, usize> = FxHashMap::default();
+        let mut edges = BTreeSet::new();
+        for edge in &self.edges {
+            let target = match (&edge.callee, &edge.trait_method) {
+                (Some(callee), _) => indices[&callee[..]],
+                (None, trait_method) => {
+                    let label = match trait_method {
+                        Some(trait_method) => format!("dyn {}", trait_method),
+                        None => "fn pointer".to_string(),
+                    };
+                    *indirect_targets.entry(label.clone()).or_insert_with(|| {
+                        nodes.push(DotNode::Indirect(label));
+                        nodes.len() - 1
+                    })
+                }
+            };
+            edges.insert((indices[&edge.caller[..]], target));
+        }
+        DotGraph { nodes, edges: edges.into_iter().collect() }
+    }
+}
+
+enum DotNode<'a> {
+    Fn(&'a Node),
+    /// The functions an indirect call can call, with the label of the node.
+    Indirect(String),
+}
+
+struct DotGraph<'a> {
+    nodes: Vec<DotNode<'a>>,
+    /// The pairs of indices into `nodes` of a caller and its callees, without duplicates.
+    edges: Vec<(usize, usize)>,
+}
+
+impl<'a, 'g> dot::Labeller<'a> for DotGraph<'g> {
+    type Node = usize;
+    type Edge = (usize, usize);
+    fn graph_id(&self) -> dot::Id<'_> {
+        dot::Id::new("callgraph").unwrap()
+    }
+    fn node_id(&self, n: &usize) -> dot::Id<'_> {
+        dot::Id::new(format!("n{}", n)).unwrap()
+    }
+    fn node_label(&self, n: &usize) -> dot::LabelText<'_> {
+        match self.nodes[*n] {
+            DotNode::Fn(node) => dot::LabelText::label(&node.name[..]),
+            DotNode::Indirect(ref label) => dot::LabelText::label(&label[..]),
+        }
+    }
+    fn node_shape(&self, n: &usize) -> Option<dot::LabelText<'_>> {
+        match self.nodes[*n] {
+            DotNode::Fn(node) if node.address_taken => Some(dot::LabelText::label("box")),
+            DotNode::Fn(_) => None,
+            DotNode::Indirect(_) => Some(dot::LabelText::label("diamond")),
+        }
+    }
+    fn node_style(&self, n: &usize) -> dot::Style {
+        match self.nodes[*n] {
+            DotNode::Fn(node) if !node.defined => dot::Style::Dashed,
+            _ => dot::Style::None,
+        }
+    }
+    fn edge_style(&self, &(_, target): &(usize, usize)) -> dot::Style {
+        match self.nodes[target] {
+            DotNode::Fn(_) => dot::Style::None,
+            DotNode::Indirect(_) => dot::Style::Dashed,
+        }
+    }
+}
+
+impl<'a, 'g> dot::GraphWalk<'a> for DotGraph<'g> {
+    type Node = usize;
+    type Edge = (usize, usize);
+    fn nodes(&self) -> dot::Nodes<'_, usize> {
+        (0..self.nodes.len()).collect()
+    }
+    fn edges(&self) -> dot::Edges<'_, (usize, usize)> {
+        self.edges[..].into()
+    }
+    fn source(&self, &(source, _): &(usize, usize)) -> usize {
+        source
+    }
+    fn target(&self, &(_, target): &(usize, usize)) -> usize {
+        target
+    }
+}
+
+/// Adds the call sites of the body of `instance` to the graph, and marks the functions it takes
+/// the address of.
+struct CallGraphBuilder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    instance: Instance<'tcx>,
+    /// The symbol name of `instance`.
+    caller: String,
+    graph: &'a mut CallGraph,
+}
+
+impl<'a, 'tcx> CallGraphBuilder<'a, 'tcx> {
+    fn monomorphize<T: TypeFoldable<'tcx>>(&self, value: T) -> T {
+        self.instance.subst_mir_and_normalize_erasing_regions(
+            self.tcx,
+            ty::ParamEnv::reveal_all(),
+            value,
+        )
+    }
+
+    fn add_call(&mut self, callee: Instance<'tcx>, span: Span) {
+        let tcx = self.tcx;
+        match callee.def {
+            // Intrinsics are expanded in place, and no-op drop glue isn't called at all.
+            ty::InstanceDef::Intrinsic(_) | ty::InstanceDef::DropGlue(_, None) => {}
+            ty::InstanceDef::Virtual(def_id, _) => {
+                self.add_edge(None, Some(tcx.def_path_str(def_id)), "vtable", span)
+            }
+            // Dropping a trait object calls the destructor in its vtable.
+            ty::InstanceDef::DropGlue(def_id, Some(ty)) if ty.is_trait() => {
+                self.add_edge(None, Some(tcx.def_path_str(def_id)), "vtable", span)
+            }
+            _ => {
+                let (_, callee) = self.graph.node(tcx, callee);
+                self.add_edge(Some(callee), None, "direct", span)
+            }
+        }
+    }
+
+    fn add_edge(
+        &mut self,
+        callee: Option<String>,
+        trait_method: Option<String>,
+        via: &'static str,
+        span: Span,
+    ) {
+        self.graph.edges.push(Edge {
+            caller: self.caller.clone(),
+            callee,
+            via,
+            trait_method,
+            location: location_json(self.tcx, span),
+        });
+    }
+}
+
+impl<'a, 'tcx> MirVisitor<'tcx> for CallGraphBuilder<'a, 'tcx> {
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        let tcx = self.tcx;
+        match *rvalue {
+            mir::Rvalue::NullaryOp(mir::NullOp::Box, _) => {
+                let span = self.body.source_info(location).span;
+                let exchange_malloc = tcx.require_lang_item(LangItem::ExchangeMalloc, Some(span));
+                self.add_call(Instance::mono(tcx, exchange_malloc), span);
+            }
+            mir::Rvalue::Cast(mir::CastKind::Pointer(cast), ref operand, target_ty) => {
+                let source_ty = self.monomorphize(operand.ty(self.body, tcx));
+                match (cast, *source_ty.kind()) {
+                    (PointerCast::ReifyFnPointer, ty::FnDef(def_id, substs)) => {
+                        let param_env = ty::ParamEnv::reveal_all();
+                        if let Some(instance) =
+                            Instance::resolve_for_fn_ptr(tcx, param_env, def_id, substs)
+                        {
+                            self.graph.take_address(tcx, instance);
+                        }
+                    }
+                    (PointerCast::ClosureFnPointer(_), ty::Closure(def_id, substs)) => {
+                        let instance =
+                            Instance::resolve_closure(tcx, def_id, substs, ty::ClosureKind::FnOnce);
+                        self.graph.take_address(tcx, instance);
+                    }
+                    // The methods of the vtables created by unsizing to a trait object can be
+                    // called through them.
+                    (PointerCast::Unsize, _) => {
+                        let target_ty = self.monomorphize(target_ty);
+                        for_each_unsize_vtable_fn(tcx, source_ty, target_ty, |instance| {
+                            self.graph.take_address(tcx, instance)
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+        let tcx = self.tcx;
+        let span = self.body.source_info(location).span;
+        match terminator.kind {
+            mir::TerminatorKind::Call { ref func, .. } => {
+                let callee_ty = self.monomorphize(func.ty(self.body, tcx));
+                match *callee_ty.kind() {
+                    ty::FnDef(def_id, substs) => {
+                        let param_env = ty::ParamEnv::reveal_all();
+                        if let Ok(Some(callee)) = Instance::resolve(tcx, param_env, def_id, substs)
+                        {
+                            self.add_call(callee, span);
+                        }
+                    }
+                    ty::FnPtr(_) => self.add_edge(None, None, "fn-ptr", span),
+                    _ => {}
+                }
+            }
+            mir::TerminatorKind::Drop { ref place, .. }
+            | mir::TerminatorKind::DropAndReplace { ref place, .. } => {
+                let ty = self.monomorphize(place.ty(self.body, tcx).ty);
+                self.add_call(Instance::resolve_drop_in_place(tcx, ty), span);
+            }
+            mir::TerminatorKind::Assert { ref msg, .. } => {
+                let lang_item = match msg {
+                    mir::AssertKind::BoundsCheck { .. } => LangItem::PanicBoundsCheck,
+                    _ => LangItem::Panic,
+                };
+                let callee = Instance::mono(tcx, tcx.require_lang_item(lang_item, Some(span)));
+                self.add_call(callee, span);
+            }
+            _ => {}
+        }
+        self.super_terminator(terminator, location);
+    }
+}
@@ -197,7 +197,7 @@
 use rustc_middle::ty::{self, GenericParamDefKind, Instance, Ty, TyCtxt, TypeFoldable, VtblEntry};
 use rustc_middle::{middle::codegen_fn_attrs::CodegenFnAttrFlags, mir::visit::TyContext};
 use rustc_session::config::EntryFnType;
-use rustc_session::lint::builtin::LARGE_ASSIGNMENTS;
+use rustc_session::lint::builtin::{EXCESSIVE_MONOMORPHIZATIONS, LARGE_ASSIGNMENTS};
 use rustc_session::Limit;
 use rustc_span::source_map::{dummy_spanned, respan, Span, Spanned, DUMMY_SP};
 use rustc_target::abi::Size;
@@ -226,6 +226,10 @@ pub struct InliningMap<'tcx> {
     inlines: GrowableBitSet<usize>,
 }
 
+/// The instantiations of each generic function and the site each one was first used at, which
+/// are only recorded for `-Z max-monomorphizations`.
+type Instantiations<'tcx> = Option<DefIdMap<Vec<Spanned<Instance<'tcx>>>>>;
+
 impl<'tcx> InliningMap<'tcx> {
     fn new() -> InliningMap<'tcx> {
         InliningMap {
@@ -293,11 +297,14 @@ pub fn collect_crate_mono_items(
 
     let mut visited = MTLock::new(FxHashSet::default());
     let mut inlining_map = MTLock::new(InliningMap::new());
+    let max_monomorphizations = max_monomorphizations(tcx);
+    let mut instantiations = MTLock::new(max_monomorphizations.map(|_| DefIdMap::default()));
     let recursion_limit = tcx.recursion_limit();
 
     {
         let visited: MTRef<'_, _> = &mut visited;
         let inlining_map: MTRef<'_, _> = &mut inlining_map;
+        let instantiations: MTRef<'_, _> = &mut instantiations;
 
         tcx.sess.time("monomorphization_collector_graph_walk", || {
             par_iter(roots).for_each(|root| {
@@ -309,14 +316,84 @@ pub fn collect_crate_mono_items(
                     &mut recursion_depths,
                     recursion_limit,
                     inlining_map,
+                    instantiations,
                 );
             });
         });
     }
 
+    if let Some(limit) = max_monomorphizations {
+        check_max_monomorphizations(tcx, limit, instantiations.into_inner().unwrap_or_default());
+    }
+
     (visited.into_inner(), inlining_map.into_inner())
 }
 
+/// The limit of `-Z max-monomorphizations`, or of the `limit` configuration option of the
+/// `excessive_monomorphizations` lint, which takes precedence.
+fn max_monomorphizations(tcx: TyCtxt<'_>) -> Option<usize> {
+    tcx.sess
+        .lint_config(EXCESSIVE_MONOMORPHIZATIONS, "limit")
+        .and_then(|limit| limit.as_number())
+        .map(|limit| limit as usize)
+        .or(tcx.sess.opts.debugging_opts.max_monomorphizations)
+}
+
+/// Lints the generic functions with more instantiations than `limit`, given the instantiations
+/// of each generic function and the site each one was first used at.
+fn check_max_monomorphizations<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    limit: usize,
+    instantiations: DefIdMap<Vec<Spanned<Instance<'tcx>>>>,
+) {
+    let mut excessive: Vec<_> = instantiations
+        .into_iter()
+        .filter(|(_, instances)| instances.len() > limit)
+        .map(|(def_id, instances)| {
+            (with_no_trimmed_paths(|| tcx.def_path_str(def_id)), def_id, instances)
+        })
+        .collect();
+    excessive.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (name, def_id, instances) in excessive {
+        let mut instances: Vec<_> = instances
+            .into_iter()
+            .map(|instance| (with_no_trimmed_paths(|| instance.node.to_string()), instance.span))
+            .collect();
+        // List the instances by their path, like the generic functions, rather than by their
+        // spans, whose order depends on the order the source files were loaded in.
+        instances.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Generic functions of other crates can't have lint attributes that apply to them, so
+        // their lint is only controlled by the crate-level lint attributes.
+        let lint_root = def_id
+            .as_local()
+            .map_or(hir::CRATE_HIR_ID, |def_id| tcx.hir().local_def_id_to_hir_id(def_id));
+        tcx.struct_span_lint_hir(
+            EXCESSIVE_MONOMORPHIZATIONS,
+            lint_root,
+            tcx.def_span(def_id),
+            |lint| {
+                let mut err = lint.build(&format!(
+                    "`{}` is instantiated {} times, more than the limit of {}",
+                    name,
+                    instances.len(),
+                    limit
+                ));
+                for (instance, span) in &instances {
+                    let msg = format!("`{}` is instantiated here", instance);
+                    if span.is_dummy() {
+                        err.note(&msg);
+                    } else {
+                        err.span_note(*span, &msg);
+                    }
+                }
+                err.emit();
+            },
+        );
+    }
+}
+
 // Find all non-generic items by walking the HIR. These items serve as roots to
 // start monomorphizing from.
 fn collect_roots(tcx: TyCtxt<'_>, mode: MonoItemCollectionMode) -> Vec<MonoItem<'_>> {
@@ -353,11 +430,20 @@ fn collect_items_rec<'tcx>(
     recursion_depths: &mut DefIdMap<usize>,
     recursion_limit: Limit,
     inlining_map: MTRef<'_, MTLock<InliningMap<'tcx>>>,
+    instantiations: MTRef<'_, MTLock<Instantiations<'tcx>>>,
 ) {
     if !visited.lock_mut().insert(starting_point.node) {
         // We've been here already, no need to search again.
         return;
     }
+    if let MonoItem::Fn(instance) = starting_point.node {
+        if instance.substs.non_erasable_generics().next().is_some() {
+            if let Some(instantiations) = instantiations.lock_mut().as_mut() {
+                let instances = instantiations.entry(instance.def_id()).or_default();
+                instances.push(respan(starting_point.span, instance));
+            }
+        }
+    }
     debug!("BEGIN collect_items_rec({})", starting_point.node);
 
     let mut neighbors = Vec::new();
@@ -464,7 +550,15 @@ fn collect_items_rec<'tcx>(
     record_accesses(tcx, starting_point.node, neighbors.iter().map(|i| &i.node), inlining_map);
 
     for neighbour in neighbors {
-        collect_items_rec(tcx, neighbour, visited, recursion_depths, recursion_limit, inlining_map);
+        collect_items_rec(
+            tcx,
+            neighbour,
+            visited,
+            recursion_depths,
+            recursion_limit,
+            inlining_map,
+            instantiations,
+        );
     }
 
     if let Some((def_id, depth)) = recursion_depth_reset {
@@ -971,6 +1065,13 @@ fn should_codegen_locally<'tcx>(tcx: TyCtxt<'tcx>, instance: &Instance<'tcx>) ->
         return false;
     }
 
+    if let Some((provider, _)) = instance.polymorphize(tcx).shared_generics_provider(tcx) {
+        // Another crate of the `-Z shared-generics-dir` index exports the instance, which
+        // must then be linked together with this crate.
+        tcx.sess.shared_generics_providers.lock().insert(provider);
+        return false;
+    }
+
     if !tcx.is_mir_available(def_id) {
         bug!("no MIR available for {:?}", def_id);
     }
@@ -1015,7 +1116,7 @@ fn should_codegen_locally<'tcx>(tcx: TyCtxt<'tcx>, instance: &Instance<'tcx>) ->
 ///
 /// Finally, there is also the case of custom unsizing coercions, e.g., for
 /// smart pointers such as `Rc` and `Arc`.
-fn find_vtable_types_for_unsizing<'tcx>(
+crate fn find_vtable_types_for_unsizing<'tcx>(
     tcx: TyCtxt<'tcx>,
     source_ty: Ty<'tcx>,
     target_ty: Ty<'tcx>,
@@ -1076,6 +1177,28 @@ fn find_vtable_types_for_unsizing<'tcx>(
     }
 }
 
+/// Calls `f` with the methods and the drop glue of the vtable that unsizing `source_ty` to
+/// `target_ty` creates, if any, for the reports that follow what can be called through vtables.
+crate fn for_each_unsize_vtable_fn<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source_ty: Ty<'tcx>,
+    target_ty: Ty<'tcx>,
+    mut f: impl FnMut(Instance<'tcx>),
+) {
+    let (source_ty, target_ty) = find_vtable_types_for_unsizing(tcx, source_ty, target_ty);
+    if let (ty::Dynamic(trait_ty, _), false) = (target_ty.kind(), source_ty.is_trait()) {
+        if let Some(principal) = trait_ty.principal() {
+            let trait_ref = principal.with_self_ty(tcx, source_ty);
+            for entry in tcx.vtable_entries(trait_ref) {
+                if let VtblEntry::Method(instance) = *entry {
+                    f(instance);
+                }
+            }
+        }
+        f(Instance::resolve_drop_in_place(tcx, source_ty));
+    }
+}
+
 fn create_fn_mono_item<'tcx>(
     tcx: TyCtxt<'tcx>,
     instance: Instance<'tcx>,
@@ -1089,7 +1212,14 @@ fn create_fn_mono_item<'tcx>(
         crate::util::dump_closure_profile(tcx, instance);
     }
 
-    respan(source, MonoItem::Fn(instance.polymorphize(tcx)))
+    let polymorphized = instance.polymorphize(tcx);
+    if tcx.sess.opts.debugging_opts.polymorphize_report.is_some() && polymorphized != instance {
+        let (instance, polymorphized) =
+            with_no_trimmed_paths(|| (instance.to_string(), polymorphized.to_string()));
+        tcx.sess.polymorphized_instances.lock().entry(polymorphized).or_default().insert(instance);
+    }
+
+    respan(source, MonoItem::Fn(polymorphized))
 }
 
 /// Creates a `MonoItem` for each method that is referenced by the vtable for
@@ -0,0 +1,404 @@
+//! The report of `-Z dead-code-report`, which lists the functions and statics that end up in the
+//! linked artifact without being referenced from any of its entry points.
+//!
+//! The roots are the entry function, the items whose symbols are exported at the C level or
+//! looked up by other crates, the `#[used]` statics and, for dylibs, every exported item. The
+//! references between items are followed through the optimized MIR of the functions and the
+//! initializers of the statics, after monomorphization. The candidates are the items the crate
+//! generates code for and the items exported by the crates linked statically into it.
+//!
+//! The references of an upstream function can only be followed if its MIR is available, which
+//! for most non-generic functions requires building their crate with `-Z encode-mir-for=all`.
+//! Whenever they can't, the items of its crate and of the crates it depends on could be used
+//! without us seeing it, so these crates are listed as incomplete rather than reported.
+//!
+//! The format is documented in the unstable book, and any incompatible change to it must bump
+//! `FORMAT_VERSION`.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_hir::lang_items::LangItem;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::middle::dependency_format::Linkage;
+use rustc_middle::middle::exported_symbols::ExportedSymbol;
+use rustc_middle::mir::interpret::{AllocId, ConstValue, GlobalAlloc, Scalar};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::mir::visit::Visitor as MirVisitor;
+use rustc_middle::mir::{self, Location};
+use rustc_middle::ty::adjustment::PointerCast;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, TyCtxt, TypeFoldable};
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::{CrateType, EntryFnType};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::collector::for_each_unsize_vtable_fn;
+use crate::util::{location_json, object};
+
+/// The version of the format described in the module documentation.
+const FORMAT_VERSION: u32 = 1;
+
+/// Writes the report of the items collected in `items` and of the upstream crates linked
+/// statically that aren't reachable from the roots of the crate.
+crate fn write_dead_code_report<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    path: &Path,
+) -> io::Result<()> {
+    let crate_type = match tcx.sess.crate_types().iter().find(|crate_type| {
+        matches!(
+            crate_type,
+            CrateType::Executable | CrateType::Dylib | CrateType::Cdylib | CrateType::Staticlib
+        )
+    }) {
+        Some(&crate_type) => crate_type,
+        None => {
+            tcx.sess.warn(
+                "`-Z dead-code-report` only reports on executables, dylibs, cdylibs and \
+                staticlibs, no report was written",
+            );
+            return Ok(());
+        }
+    };
+
+    let json = tcx.sess.time("dead_code_report", || {
+        with_no_trimmed_paths(|| {
+            let candidates = candidates(tcx, crate_type, items);
+            let mut liveness = Liveness {
+                tcx,
+                live: FxHashSet::default(),
+                worklist: Vec::new(),
+                opaque_crates: FxHashSet::default(),
+            };
+            for &(instance, is_root) in &candidates {
+                if is_root {
+                    liveness.mark(instance);
+                }
+            }
+            if let Some((main_def_id, entry_type)) = tcx.entry_fn(()) {
+                liveness.mark(Instance::mono(tcx, main_def_id));
+                if entry_type == EntryFnType::Main {
+                    liveness.mark(start_instance(tcx, main_def_id));
+                }
+            }
+            while let Some(instance) = liveness.worklist.pop() {
+                liveness.visit(instance);
+            }
+
+            let incomplete: BTreeSet<CrateNum> = liveness
+                .opaque_crates
+                .iter()
+                .flat_map(|&cnum| tcx.crate_dependencies(cnum).iter().copied())
+                .collect();
+            let mut dead = BTreeMap::new();
+            for &(instance, _) in &candidates {
+                let krate = instance.def_id().krate;
+                let live = liveness.live.contains(&instance.polymorphize(tcx));
+                if live || incomplete.contains(&krate) {
+                    continue;
+                }
+                let symbol = tcx.symbol_name(instance).name.to_string();
+                dead.entry(symbol.clone()).or_insert_with(|| dead_item_json(tcx, instance, symbol));
+            }
+
+            object(vec![
+                ("format_version", FORMAT_VERSION.to_json()),
+                ("crate_name", tcx.crate_name(LOCAL_CRATE).to_string().to_json()),
+                ("dead", Json::Array(dead.into_values().collect())),
+                (
+                    "incomplete_crates",
+                    Json::Array(
+                        incomplete
+                            .into_iter()
+                            .map(|cnum| tcx.crate_name(cnum).to_string().to_json())
+                            .collect(),
+                    ),
+                ),
+            ])
+        })
+    });
+
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "{}", json)?;
+    file.flush()
+}
+
+/// The functions and statics that end up in the artifact, as instances, each with whether it is
+/// a root. Statics are represented by their `Instance::mono`.
+fn candidates<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    crate_type: CrateType,
+    items: &FxHashSet<MonoItem<'tcx>>,
+) -> Vec<(Instance<'tcx>, bool)> {
+    // Items with an extern indicator or internal to the standard library can be looked up by
+    // their symbol name, for instance from an `extern` block.
+    let is_root = |instance: Instance<'tcx>, exported: bool| {
+        let attrs = tcx.codegen_fn_attrs(instance.def_id());
+        let flags = CodegenFnAttrFlags::RUSTC_STD_INTERNAL_SYMBOL | CodegenFnAttrFlags::USED;
+        exported && crate_type == CrateType::Dylib
+            || attrs.contains_extern_indicator()
+            || attrs.flags.intersects(flags)
+    };
+
+    let reachable_non_generics = tcx.reachable_non_generics(LOCAL_CRATE);
+    let mut candidates: Vec<_> = items
+        .iter()
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance) => Some(instance),
+            MonoItem::Static(def_id) => Some(Instance::mono(tcx, def_id)),
+            MonoItem::GlobalAsm(_) => None,
+        })
+        .map(|instance| {
+            let exported = instance.substs.is_empty()
+                && reachable_non_generics.contains_key(&instance.def_id());
+            (instance, is_root(instance, exported))
+        })
+        .collect();
+
+    let formats = tcx.dependency_formats(());
+    let linkage = formats.iter().find_map(|(t, list)| (*t == crate_type).then(|| list)).unwrap();
+    for (index, &linkage) in linkage.iter().enumerate() {
+        if linkage != Linkage::Static {
+            continue;
+        }
+        let cnum = CrateNum::new(index + 1);
+        for &(symbol, _) in tcx.exported_symbols(cnum) {
+            let instance = match symbol {
+                ExportedSymbol::NonGeneric(def_id) => Instance::mono(tcx, def_id),
+                ExportedSymbol::Generic(def_id, substs) => Instance::new(def_id, substs),
+                ExportedSymbol::DropGlue(ty) => Instance::resolve_drop_in_place(tcx, ty),
+                ExportedSymbol::NoDefId(_) => continue,
+            };
+            candidates.push((instance, is_root(instance, false)));
+        }
+    }
+    candidates
+}
+
+/// The instance of the `start` lang item that calls `main`.
+fn start_instance(tcx: TyCtxt<'_>, main_def_id: DefId) -> Instance<'_> {
+    let start_def_id = tcx.require_lang_item(LangItem::Start, None);
+    // `main` has no arguments, so its return type has no late-bound regions.
+    let main_ret_ty = tcx.erase_regions(tcx.fn_sig(main_def_id).output().no_bound_vars().unwrap());
+    Instance::resolve(
+        tcx,
+        ty::ParamEnv::reveal_all(),
+        start_def_id,
+        tcx.intern_substs(&[main_ret_ty.into()]),
+    )
+    .unwrap()
+    .unwrap()
+}
+
+fn dead_item_json<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>, symbol: String) -> Json {
+    let def_id = instance.def_id();
+    let kind = match instance.def {
+        ty::InstanceDef::Item(_) if tcx.is_static(def_id) => "static",
+        ty::InstanceDef::Item(_) if tcx.is_closure(def_id) => "closure",
+        ty::InstanceDef::Item(_) => "fn",
+        ty::InstanceDef::DropGlue(..) => "drop-glue",
+        _ => "shim",
+    };
+    let location =
+        if def_id.is_local() { location_json(tcx, tcx.def_span(def_id)) } else { Json::Null };
+    object(vec![
+        ("symbol", symbol.to_json()),
+        ("name", instance.to_string().to_json()),
+        ("crate", tcx.crate_name(def_id.krate).to_string().to_json()),
+        ("kind", kind.to_json()),
+        ("location", location),
+    ])
+}
+
+/// The items found to be reachable from the roots so far.
+struct Liveness<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    live: FxHashSet<Instance<'tcx>>,
+    /// The live items whose references haven't been followed yet.
+    worklist: Vec<Instance<'tcx>>,
+    /// The crates of the live functions whose MIR isn't available.
+    opaque_crates: FxHashSet<CrateNum>,
+}
+
+impl<'tcx> Liveness<'tcx> {
+    fn mark(&mut self, instance: Instance<'tcx>) {
+        match instance.def {
+            // Neither is an item of its own.
+            ty::InstanceDef::Intrinsic(_) | ty::InstanceDef::Virtual(..) => {}
+            _ => {
+                let instance = instance.polymorphize(self.tcx);
+                if self.live.insert(instance) {
+                    self.worklist.push(instance);
+                }
+            }
+        }
+    }
+
+    fn mark_alloc(&mut self, alloc_id: AllocId) {
+        match self.tcx.global_alloc(alloc_id) {
+            GlobalAlloc::Static(def_id) => self.mark(Instance::mono(self.tcx, def_id)),
+            GlobalAlloc::Function(instance) => self.mark(instance),
+            GlobalAlloc::Memory(alloc) => {
+                for &inner in alloc.relocations().values() {
+                    rustc_data_structures::stack::ensure_sufficient_stack(|| {
+                        self.mark_alloc(inner);
+                    });
+                }
+            }
+        }
+    }
+
+    fn mark_const_value(&mut self, value: ConstValue<'tcx>) {
+        match value {
+            ConstValue::Scalar(Scalar::Ptr(ptr, _)) => self.mark_alloc(ptr.provenance),
+            ConstValue::Slice { data: alloc, .. } | ConstValue::ByRef { alloc, .. } => {
+                for &id in alloc.relocations().values() {
+                    self.mark_alloc(id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Marks the items referenced by `instance`.
+    fn visit(&mut self, instance: Instance<'tcx>) {
+        let tcx = self.tcx;
+        let def_id = instance.def_id();
+        match instance.def {
+            ty::InstanceDef::Item(_) if tcx.is_static(def_id) => {
+                if tcx.is_foreign_item(def_id) {
+                    return;
+                }
+                if let Ok(alloc) = tcx.eval_static_initializer(def_id) {
+                    for &id in alloc.relocations().values() {
+                        self.mark_alloc(id);
+                    }
+                }
+                return;
+            }
+            ty::InstanceDef::Item(_) if tcx.is_foreign_item(def_id) => return,
+            ty::InstanceDef::Item(_) if !def_id.is_local() && !tcx.is_mir_available(def_id) => {
+                self.opaque_crates.insert(def_id.krate);
+                return;
+            }
+            _ => {}
+        }
+        let body = tcx.instance_mir(instance.def);
+        ReferenceCollector { liveness: self, body, instance }.visit_body(body);
+    }
+}
+
+/// Marks the items referenced by the body of `instance`.
+struct ReferenceCollector<'a, 'tcx> {
+    liveness: &'a mut Liveness<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    instance: Instance<'tcx>,
+}
+
+impl<'a, 'tcx> ReferenceCollector<'a, 'tcx> {
+    fn monomorphize<T: TypeFoldable<'tcx>>(&self, value: T) -> T {
+        self.instance.subst_mir_and_normalize_erasing_regions(
+            self.liveness.tcx,
+            ty::ParamEnv::reveal_all(),
+            value,
+        )
+    }
+
+    fn mark_lang_item(&mut self, lang_item: LangItem) {
+        let tcx = self.liveness.tcx;
+        self.liveness.mark(Instance::mono(tcx, tcx.require_lang_item(lang_item, None)));
+    }
+}
+
+impl<'a, 'tcx> MirVisitor<'tcx> for ReferenceCollector<'a, 'tcx> {
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        let tcx = self.liveness.tcx;
+        match *rvalue {
+            mir::Rvalue::NullaryOp(mir::NullOp::Box, _) => {
+                self.mark_lang_item(LangItem::ExchangeMalloc)
+            }
+            mir::Rvalue::ThreadLocalRef(def_id) => self.liveness.mark(Instance::mono(tcx, def_id)),
+            mir::Rvalue::Cast(mir::CastKind::Pointer(cast), ref operand, target_ty) => {
+                let source_ty = self.monomorphize(operand.ty(self.body, tcx));
+                match (cast, *source_ty.kind()) {
+                    (PointerCast::ClosureFnPointer(_), ty::Closure(def_id, substs)) => {
+                        let instance =
+                            Instance::resolve_closure(tcx, def_id, substs, ty::ClosureKind::FnOnce);
+                        self.liveness.mark(instance);
+                    }
+                    // The methods of the vtables created by unsizing to a trait object can be
+                    // called through them.
+                    (PointerCast::Unsize, _) => {
+                        let target_ty = self.monomorphize(target_ty);
+                        for_each_unsize_vtable_fn(tcx, source_ty, target_ty, |instance| {
+                            self.liveness.mark(instance)
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, _location: Location) {
+        let tcx = self.liveness.tcx;
+        let literal = self.monomorphize(constant.literal);
+        // Function items are zero-sized constants, which are either called or reified.
+        if let ty::FnDef(def_id, substs) = *literal.ty().kind() {
+            let param_env = ty::ParamEnv::reveal_all();
+            if let Ok(Some(instance)) = Instance::resolve(tcx, param_env, def_id, substs) {
+                self.liveness.mark(instance);
+            }
+            if let Some(instance) = Instance::resolve_for_fn_ptr(tcx, param_env, def_id, substs) {
+                self.liveness.mark(instance);
+            }
+            return;
+        }
+        let val = match literal {
+            mir::ConstantKind::Val(val, _) => val,
+            mir::ConstantKind::Ty(ct) => match ct.val {
+                ty::ConstKind::Value(val) => val,
+                ty::ConstKind::Unevaluated(ct) => {
+                    match tcx.const_eval_resolve(ty::ParamEnv::reveal_all(), ct, None) {
+                        Ok(val) => val,
+                        Err(_) => return,
+                    }
+                }
+                _ => return,
+            },
+        };
+        self.liveness.mark_const_value(val);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+        let tcx = self.liveness.tcx;
+        match terminator.kind {
+            mir::TerminatorKind::Drop { ref place, .. }
+            | mir::TerminatorKind::DropAndReplace { ref place, .. } => {
+                let ty = self.monomorphize(place.ty(self.body, tcx).ty);
+                self.liveness.mark(Instance::resolve_drop_in_place(tcx, ty));
+            }
+            mir::TerminatorKind::Assert { ref msg, .. } => match msg {
+                mir::AssertKind::BoundsCheck { .. } => {
+                    self.mark_lang_item(LangItem::PanicBoundsCheck)
+                }
+                _ => self.mark_lang_item(LangItem::Panic),
+            },
+            mir::TerminatorKind::InlineAsm { ref operands, .. } => {
+                for op in operands {
+                    if let mir::InlineAsmOperand::SymStatic { def_id } = *op {
+                        self.liveness.mark(Instance::mono(tcx, def_id));
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.super_terminator(terminator, location);
+    }
+}
@@ -17,11 +17,17 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, Ty, TyCtxt};
 
+mod call_graph;
 mod collector;
+mod dead_code_report;
+mod mono_stats;
 mod partitioning;
 mod polymorphize;
+mod polymorphize_report;
 mod util;
 
+pub use call_graph::write_call_graph;
+
 fn custom_coerce_unsize_info<'tcx>(
     tcx: TyCtxt<'tcx>,
     source_ty: Ty<'tcx>,
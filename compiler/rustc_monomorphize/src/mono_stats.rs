@@ -0,0 +1,96 @@
+//! The statistics of `-Z dump-mono-stats`, which tell how many times each generic function
+//! of the crate and its dependencies was instantiated, how much MIR those instantiations add
+//! up to, and which codegen units they were placed in.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_session::config::ReportFormat;
+
+use std::collections::BTreeSet;
+
+/// The instantiations of one generic function.
+struct GenericStats {
+    name: String,
+    crate_name: String,
+    instantiations: usize,
+    /// The sum of the size estimates of the instantiations, in MIR statements.
+    size_estimate: usize,
+    cgus: BTreeSet<String>,
+}
+
+/// Prints the statistics of `-Z dump-mono-stats` for the collected mono items and the codegen
+/// units they were partitioned into.
+crate fn dump_mono_stats<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    format: ReportFormat,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    codegen_units: &[CodegenUnit<'tcx>],
+) {
+    let mut stats: FxHashMap<DefId, GenericStats> = FxHashMap::default();
+    for item in items {
+        let instance = match *item {
+            MonoItem::Fn(instance) => instance,
+            MonoItem::Static(_) | MonoItem::GlobalAsm(_) => continue,
+        };
+        if instance.substs.non_erasable_generics().next().is_none() {
+            continue;
+        }
+        let def_id = instance.def_id();
+        let entry = stats.entry(def_id).or_insert_with(|| GenericStats {
+            name: with_no_trimmed_paths(|| tcx.def_path_str(def_id)),
+            crate_name: tcx.crate_name(def_id.krate).to_string(),
+            instantiations: 0,
+            size_estimate: 0,
+            cgus: BTreeSet::new(),
+        });
+        entry.instantiations += 1;
+        entry.size_estimate += item.size_estimate(tcx);
+    }
+    for cgu in codegen_units {
+        for item in cgu.items().keys() {
+            if let MonoItem::Fn(instance) = item {
+                if let Some(entry) = stats.get_mut(&instance.def_id()) {
+                    entry.cgus.insert(cgu.name().to_string());
+                }
+            }
+        }
+    }
+
+    let mut stats: Vec<_> = stats.into_values().collect();
+    stats.sort_by(|a, b| b.size_estimate.cmp(&a.size_estimate).then_with(|| a.name.cmp(&b.name)));
+
+    if format == ReportFormat::Json {
+        let generics = stats
+            .iter()
+            .map(|generic| {
+                let fields = vec![
+                    ("name", generic.name.to_json()),
+                    ("crate", generic.crate_name.to_json()),
+                    ("instantiations", generic.instantiations.to_json()),
+                    ("size_estimate", generic.size_estimate.to_json()),
+                    ("cgus", Json::Array(generic.cgus.iter().map(|cgu| cgu.to_json()).collect())),
+                ];
+                Json::Object(
+                    fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect(),
+                )
+            })
+            .collect();
+        println!("{}", Json::Array(generics));
+        return;
+    }
+
+    println!("{:>10} {:>6} {:>6}  generic", "size", "count", "cgus");
+    for generic in &stats {
+        println!(
+            "{:>10} {:>6} {:>6}  {}",
+            generic.size_estimate,
+            generic.instantiations,
+            generic.cgus.len(),
+            generic.name
+        );
+    }
+}
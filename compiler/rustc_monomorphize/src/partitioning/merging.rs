@@ -3,6 +3,7 @@
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_middle::mir::mono::{CodegenUnit, CodegenUnitNameBuilder};
+use rustc_session::config::CguNameScheme;
 use rustc_span::symbol::{Symbol, SymbolStr};
 
 use super::PartitioningCx;
@@ -57,8 +58,9 @@ pub fn merge_codegen_units<'tcx>(
 
     let cgu_name_builder = &mut CodegenUnitNameBuilder::new(cx.tcx);
 
-    if cx.tcx.sess.opts.incremental.is_some() {
-        // If we are doing incremental compilation, we want CGU names to
+    let name_scheme = cx.tcx.sess.cgu_name_scheme();
+    if name_scheme != CguNameScheme::Numbered {
+        // Unless asked for numbered names, we want CGU names to
         // reflect the path of the source level module they correspond to.
         // For CGUs that contain the code of multiple modules because of the
         // merging done above, we use a concatenation of the names of
@@ -83,7 +85,9 @@ pub fn merge_codegen_units<'tcx>(
 
         for cgu in codegen_units.iter_mut() {
             if let Some(new_cgu_name) = new_cgu_names.get(&cgu.name()) {
-                if cx.tcx.sess.opts.debugging_opts.human_readable_cgu_names {
+                if name_scheme == CguNameScheme::ItemBased
+                    || cx.tcx.sess.opts.debugging_opts.human_readable_cgu_names
+                {
                     cgu.set_name(Symbol::intern(&new_cgu_name));
                 } else {
                     // If we don't require CGU names to be human-readable, we
@@ -95,8 +99,15 @@ pub fn merge_codegen_units<'tcx>(
             }
         }
     } else {
-        // If we are compiling non-incrementally we just generate simple CGU
-        // names containing an index.
+        // Otherwise we just generate simple CGU names containing an index. The
+        // index follows the names of the source level modules each CGU contains,
+        // so that the same input always gets the same names.
+        codegen_units.sort_by_cached_key(|cgu| {
+            let mut contents: Vec<String> =
+                cgu_contents[&cgu.name()].iter().map(|name| name.to_string()).collect();
+            contents.sort_unstable();
+            contents
+        });
         for (index, cgu) in codegen_units.iter_mut().enumerate() {
             cgu.set_name(numbered_codegen_unit_name(cgu_name_builder, index));
         }
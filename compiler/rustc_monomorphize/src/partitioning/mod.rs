@@ -107,6 +107,9 @@
 
 use crate::collector::InliningMap;
 use crate::collector::{self, MonoItemCollectionMode};
+use crate::dead_code_report::write_dead_code_report;
+use crate::mono_stats::dump_mono_stats;
+use crate::polymorphize_report::write_polymorphize_report;
 
 pub struct PartitioningCx<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -432,6 +435,30 @@ fn collect_and_partition_mono_items<'tcx>(
         }
     }
 
+    if let Some(format) = tcx.sess.opts.debugging_opts.dump_mono_stats {
+        dump_mono_stats(tcx, format, &items, codegen_units);
+    }
+
+    if let Some(path) = &tcx.sess.opts.debugging_opts.dead_code_report {
+        if let Err(err) = write_dead_code_report(tcx, &items, path) {
+            tcx.sess.err(&format!(
+                "failed to write the `-Z dead-code-report` report to `{}`: {}",
+                path.display(),
+                err
+            ));
+        }
+    }
+
+    if let Some(path) = &tcx.sess.opts.debugging_opts.polymorphize_report {
+        if let Err(err) = write_polymorphize_report(tcx, &items, path) {
+            tcx.sess.err(&format!(
+                "failed to write the `-Z polymorphize-report` report to `{}`: {}",
+                path.display(),
+                err
+            ));
+        }
+    }
+
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
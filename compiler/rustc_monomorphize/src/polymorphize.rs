@@ -16,7 +16,7 @@
     fold::{TypeFoldable, TypeVisitor},
     query::Providers,
     subst::SubstsRef,
-    Const, Ty, TyCtxt,
+    Const, DefIdTree, Ty, TyCtxt,
 };
 use rustc_span::symbol::sym;
 use std::convert::TryInto;
@@ -113,6 +113,11 @@ fn should_polymorphize<'tcx>(
         return false;
     }
 
+    if is_opted_out(tcx, def_id) {
+        debug!("opted out with `#[polymorphize(never)]`");
+        return false;
+    }
+
     // Make sure there is MIR available.
     match tcx.hir().body_const_context(def_id.expect_local()) {
         Some(ConstContext::ConstFn) | None if !tcx.is_mir_available(def_id) => {
@@ -127,6 +132,22 @@ fn should_polymorphize<'tcx>(
     }
 }
 
+/// Returns `true` if the closest `#[polymorphize]` attribute, on the item or on the closest item
+/// or module containing it, is `#[polymorphize(never)]`, so that `#[polymorphize(always)]` can opt
+/// an item back in within an item or module that opted out.
+crate fn is_opted_out(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    let mut next = Some(def_id);
+    while let Some(def_id) = next {
+        let attr = tcx.get_attrs(def_id).iter().find(|attr| attr.has_name(sym::polymorphize));
+        if let Some(attr) = attr {
+            let arg = attr.meta_item_list().and_then(|list| list.first()?.ident());
+            return arg.map_or(false, |arg| arg.name == sym::never);
+        }
+        next = tcx.parent(def_id);
+    }
+    false
+}
+
 /// Some parameters are considered used-by-default, such as non-generic parameters and the dummy
 /// generic parameters from closures, this function marks them as used. `leaf_is_closure` should
 /// be `true` if the item that `unused_generic_params` was invoked on is a closure.
@@ -0,0 +1,95 @@
+//! The report of `-Z polymorphize-report`, which tells which generic parameters polymorphization
+//! found unused in each function, and which of its instantiations were shared as a result.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::FiniteBitSet;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, TyCtxt};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::polymorphize::is_opted_out;
+
+/// Writes the generic functions among the collected mono items, by path. Each function with
+/// unused parameters is followed by the instances it was polymorphized into, each with the
+/// instances it replaced, and the functions of the crate that were opted out with
+/// `#[polymorphize(never)]` are marked as such.
+crate fn write_polymorphize_report<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    path: &Path,
+) -> io::Result<()> {
+    // The instances of each generic function, by path.
+    let mut functions: BTreeMap<String, (ty::InstanceDef<'tcx>, BTreeSet<String>)> =
+        BTreeMap::new();
+    for item in items {
+        let instance = match *item {
+            MonoItem::Fn(instance @ ty::Instance { def: ty::InstanceDef::Item(_), .. }) => instance,
+            MonoItem::Fn(_) | MonoItem::Static(_) | MonoItem::GlobalAsm(_) => continue,
+        };
+        if instance.substs.non_erasable_generics().next().is_none() {
+            continue;
+        }
+        let (name, instance_name) =
+            with_no_trimmed_paths(|| (tcx.def_path_str(instance.def_id()), instance.to_string()));
+        let (_, instances) =
+            functions.entry(name).or_insert_with(|| (instance.def, BTreeSet::new()));
+        instances.insert(instance_name);
+    }
+
+    let shared = tcx.sess.polymorphized_instances.lock();
+    let mut file = BufWriter::new(File::create(path)?);
+    for (name, (def, instances)) in &functions {
+        let unused = tcx.unused_generic_params(*def);
+        if unused.is_empty() {
+            let def_id = def.def_id();
+            if def_id.is_local() && is_opted_out(tcx, def_id) {
+                writeln!(file, "{}: not polymorphized, `#[polymorphize(never)]`", name)?;
+            }
+            continue;
+        }
+
+        let params = unused_params(tcx, def.def_id(), &unused);
+        writeln!(file, "{}: {} unused", name, params.join(", "))?;
+        for instance in instances {
+            match shared.get(instance) {
+                Some(replaced) => {
+                    let plural = if replaced.len() == 1 { "" } else { "s" };
+                    writeln!(
+                        file,
+                        "    {} shared by {} instance{}:",
+                        instance,
+                        replaced.len(),
+                        plural
+                    )?;
+                    for replaced in replaced {
+                        writeln!(file, "        {}", replaced)?;
+                    }
+                }
+                None => writeln!(file, "    {}", instance)?,
+            }
+        }
+    }
+    file.flush()
+}
+
+/// The names of the unused parameters of `def_id` and the items it is nested in, in order.
+fn unused_params(tcx: TyCtxt<'_>, def_id: DefId, unused: &FiniteBitSet<u32>) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut next_generics = Some(tcx.generics_of(def_id));
+    while let Some(generics) = next_generics {
+        for param in &generics.params {
+            if unused.contains(param.index).unwrap_or(false) {
+                params.push((param.index, format!("`{}`", param.name)));
+            }
+        }
+        next_generics = generics.parent.map(|did| tcx.generics_of(did));
+    }
+    params.sort();
+    params.into_iter().map(|(_, name)| name).collect()
+}
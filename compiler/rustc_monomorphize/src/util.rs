@@ -1,4 +1,6 @@
 use rustc_middle::ty::{self, ClosureSizeProfileData, Instance, TyCtxt};
+use rustc_serialize::json::{Json, ToJson};
+use rustc_span::Span;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
 
@@ -71,3 +73,22 @@
         }
     }
 }
+
+/// Builds a JSON object out of its fields, for the JSON reports about mono items.
+crate fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+/// The file, line and 1-based column of the start of `span` as a JSON object, or `null` for a
+/// dummy span.
+crate fn location_json(tcx: TyCtxt<'_>, span: Span) -> Json {
+    if span.is_dummy() {
+        return Json::Null;
+    }
+    let lo = tcx.sess.source_map().lookup_char_pos(span.lo());
+    object(vec![
+        ("file", lo.file.name.prefer_remapped().to_string().to_json()),
+        ("line", lo.line.to_json()),
+        ("column", (lo.col.0 + 1).to_json()),
+    ])
+}
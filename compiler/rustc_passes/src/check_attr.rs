@@ -77,6 +77,8 @@ fn check_attributes(
                 sym::inline => self.check_inline(hir_id, attr, span, target),
                 sym::non_exhaustive => self.check_non_exhaustive(hir_id, attr, span, target),
                 sym::marker => self.check_marker(hir_id, attr, span, target),
+                sym::proc_macro_pure => self.check_proc_macro_pure(attr, span, target, attrs),
+                sym::polymorphize => self.check_polymorphize(attr, span, target),
                 sym::target_feature => self.check_target_feature(hir_id, attr, span, target),
                 sym::track_caller => {
                     self.check_track_caller(hir_id, &attr.span, attrs, span, target)
@@ -477,6 +479,71 @@ fn check_marker(&self, hir_id: HirId, attr: &Attribute, span: &Span, target: Tar
         }
     }
 
+    /// Checks if `#[proc_macro_pure]` is applied to a function defining a proc macro.
+    /// Returns `true` if valid.
+    fn check_proc_macro_pure(
+        &self,
+        attr: &Attribute,
+        span: &Span,
+        target: Target,
+        attrs: &[Attribute],
+    ) -> bool {
+        let is_proc_macro = attrs.iter().any(|attr| {
+            attr.has_name(sym::proc_macro)
+                || attr.has_name(sym::proc_macro_attribute)
+                || attr.has_name(sym::proc_macro_derive)
+        });
+        if target == Target::Fn && is_proc_macro {
+            true
+        } else {
+            self.tcx
+                .sess
+                .struct_span_err(attr.span, "attribute should be applied to a proc macro")
+                .span_label(*span, "not a proc macro")
+                .emit();
+            false
+        }
+    }
+
+    /// Checks if `#[polymorphize]` takes `always` or `never` and is applied to a function, a
+    /// closure, or an item that can contain them. Returns `true` if valid.
+    fn check_polymorphize(&self, attr: &Attribute, span: &Span, target: Target) -> bool {
+        let is_valid_arg = match attr.meta_item_list().as_deref() {
+            Some([arg]) => arg.is_word() && (arg.has_name(sym::always) || arg.has_name(sym::never)),
+            _ => false,
+        };
+        if !is_valid_arg {
+            self.tcx
+                .sess
+                .struct_span_err(attr.span, "malformed `polymorphize` attribute input")
+                .span_label(
+                    attr.span,
+                    "expected `#[polymorphize(always)]` or `#[polymorphize(never)]`",
+                )
+                .emit();
+            return false;
+        }
+        match target {
+            Target::Fn
+            | Target::Method(..)
+            | Target::Closure
+            | Target::Mod
+            | Target::Impl
+            | Target::Trait => true,
+            _ => {
+                self.tcx
+                    .sess
+                    .struct_span_err(
+                        attr.span,
+                        "attribute should be applied to a function, closure, module, impl or trait",
+                    )
+                    .span_label(*span, "not a function, closure, module, impl or trait")
+                    .emit();
+                false
+            }
+        }
+    }
+
     /// Checks if the `#[target_feature]` attribute on `item` is valid. Returns `true` if valid.
     fn check_target_feature(
         &self,
@@ -340,6 +340,7 @@ fn make_vtable(tcx: QueryCtxt<'tcx>, key: &Self::Key) ->
                 let compute = get_provider!([$($modifiers)*][tcx, $name, key]);
                 let cache_on_disk = Self::cache_on_disk(tcx.tcx, key);
                 QueryVtable {
+                    name: Self::NAME,
                     anon: is_anon!([$($modifiers)*]),
                     eval_always: is_eval_always!([$($modifiers)*]),
                     dep_kind: dep_graph::DepKind::$name,
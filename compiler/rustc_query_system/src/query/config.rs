@@ -20,6 +20,7 @@ pub trait QueryConfig {
 }
 
 pub struct QueryVtable<CTX: QueryContext, K, V> {
+    pub name: &'static str,
     pub anon: bool,
     pub dep_kind: CTX::DepKind,
     pub eval_always: bool,
@@ -18,7 +18,7 @@
 use rustc_data_structures::sync::{Lock, LockGuard};
 use rustc_data_structures::thin_vec::ThinVec;
 use rustc_errors::{DiagnosticBuilder, FatalError};
-use rustc_session::Session;
+use rustc_session::{QueryKeyOutcome, Session};
 use rustc_span::{Span, DUMMY_SP};
 use std::cell::Cell;
 use std::collections::hash_map::Entry;
@@ -417,6 +417,7 @@ fn try_execute_query<CTX, C>(
                 tcx.dep_context().profiler().query_cache_hit(index.into());
             }
             query_blocked_prof_timer.finish_with_query_invocation_id(index.into());
+            tcx.dep_context().sess().record_query_key(query.name, &key, QueryKeyOutcome::CacheHit);
 
             (v, Some(index))
         }
@@ -440,8 +441,12 @@ fn execute_job<CTX, K, V>(
     // Fast path for when incr. comp. is off.
     if !dep_graph.is_fully_enabled() {
         let prof_timer = tcx.dep_context().profiler().query_provider();
+        let prof_memory = tcx.dep_context().profiler().query_memory();
+        let sess = tcx.dep_context().sess();
+        sess.record_query_key(query.name, &key, QueryKeyOutcome::Recomputed);
         let result = tcx.start_query(job_id, None, || query.compute(*tcx.dep_context(), key));
         let dep_node_index = dep_graph.next_virtual_depnode_index();
+        prof_memory.finish_with_query_invocation_id(dep_node_index.into());
         prof_timer.finish_with_query_invocation_id(dep_node_index.into());
         return (result, dep_node_index);
     }
@@ -460,7 +465,9 @@ fn execute_job<CTX, K, V>(
         }
     }
 
+    tcx.dep_context().sess().record_query_key(query.name, &key, QueryKeyOutcome::Recomputed);
     let prof_timer = tcx.dep_context().profiler().query_provider();
+    let prof_memory = tcx.dep_context().profiler().query_memory();
     let diagnostics = Lock::new(ThinVec::new());
 
     let (result, dep_node_index) = tcx.start_query(job_id, Some(&diagnostics), || {
@@ -476,6 +483,7 @@ fn execute_job<CTX, K, V>(
         dep_graph.with_task(dep_node, *tcx.dep_context(), key, query.compute, query.hash_result)
     });
 
+    prof_memory.finish_with_query_invocation_id(dep_node_index.into());
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
 
     let diagnostics = diagnostics.into_inner();
@@ -499,7 +507,7 @@ fn try_load_from_disk_and_cache_in_memory<CTX, K, V>(
     query: &QueryVtable<CTX, K, V>,
 ) -> Option<(V, DepNodeIndex)>
 where
-    K: Clone,
+    K: Clone + Debug,
     CTX: QueryContext,
     V: Debug,
 {
@@ -538,6 +546,8 @@ fn try_load_from_disk_and_cache_in_memory<CTX, K, V>(
                 incremental_verify_ich(*tcx.dep_context(), &result, dep_node, query);
             }
 
+            let sess = tcx.dep_context().sess();
+            sess.record_query_key(query.name, key, QueryKeyOutcome::LoadedFromDisk);
             return Some((result, dep_node_index));
         }
 
@@ -552,11 +562,14 @@ fn try_load_from_disk_and_cache_in_memory<CTX, K, V>(
 
     // We could not load a result from the on-disk cache, so
     // recompute.
+    tcx.dep_context().sess().record_query_key(query.name, key, QueryKeyOutcome::Recomputed);
     let prof_timer = tcx.dep_context().profiler().query_provider();
+    let prof_memory = tcx.dep_context().profiler().query_memory();
 
     // The dep-graph for this computation is already in-place.
     let result = dep_graph.with_ignore(|| query.compute(*tcx.dep_context(), key.clone()));
 
+    prof_memory.finish_with_query_invocation_id(dep_node_index.into());
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
 
     // Verify that re-running the query produced a result with the expected hash
@@ -719,6 +732,7 @@ fn ensure_must_run<CTX, K, V>(
         Some((_, dep_node_index)) => {
             dep_graph.read_index(dep_node_index);
             tcx.dep_context().profiler().query_cache_hit(dep_node_index.into());
+            tcx.dep_context().sess().record_query_key(query.name, key, QueryKeyOutcome::CacheHit);
             (false, None)
         }
     }
@@ -782,6 +796,7 @@ pub fn force_query<Q, CTX>(tcx: CTX, key: Q::Key, dep_node: DepNode<CTX::DepKind
         if unlikely!(tcx.dep_context().profiler().enabled()) {
             tcx.dep_context().profiler().query_cache_hit(index.into());
         }
+        tcx.dep_context().sess().record_query_key(Q::NAME, &key, QueryKeyOutcome::CacheHit);
     });
 
     let lookup = match cached {
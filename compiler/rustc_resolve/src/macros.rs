@@ -146,7 +146,11 @@ fn registered_idents(
     sess: &Session,
     attrs: &[ast::Attribute],
 ) -> (FxHashSet<Ident>, FxHashSet<Ident>) {
-    let registered_attrs = registered_idents(sess, attrs, sym::register_attr, "attribute");
+    let mut registered_attrs = registered_idents(sess, attrs, sym::register_attr, "attribute");
+    // The attribute marking test cases for `-Z test-attr` needs no `#![register_attr]`.
+    if let Some(test_attr) = &sess.opts.debugging_opts.test_attr {
+        registered_attrs.insert(Ident::from_str(test_attr));
+    }
     let mut registered_tools = registered_idents(sess, attrs, sym::register_tool, "tool");
     // We implicitly add `rustfmt` and `clippy` to known tools,
     // but it's not an error to register them explicitly.
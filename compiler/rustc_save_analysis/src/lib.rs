@@ -957,6 +957,7 @@ fn save(&mut self, save_ctxt: &SaveContext<'_>, analysis: &Analysis) {
         if let Err(e) = serde_json::to_writer(output, &analysis) {
             error!("Can't serialize save-analysis: {:?}", e);
         }
+        sess.record_artifact(&file_name, "save-analysis");
 
         if sess.opts.json_artifact_notifications {
             sess.parse_sess.span_diagnostic.emit_artifact_notification(&file_name, "save-analysis");
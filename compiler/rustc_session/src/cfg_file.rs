@@ -0,0 +1,173 @@
+//! Loading of the cfg values in the files given with `--cfg-file`.
+//!
+//! A cfg file is a table from cfg names to values, written either as a JSON object, if the
+//! file name ends in `.json`, or otherwise in the subset of TOML made of `name = value`
+//! lines and comments. The values are:
+//!
+//! * `true` for `--cfg name`, or `false` for no cfg at all;
+//! * a string for `--cfg name="value"`;
+//! * an array of strings for `--cfg name="value"` with each of the strings.
+
+use rustc_serialize::json::{self, Json};
+
+use std::fs;
+use std::path::Path;
+
+enum Value {
+    Bool(bool),
+    String(String),
+    Array(Vec<String>),
+}
+
+/// Loads the cfg file at `path` as `(name, value)` pairs, like those of `--cfg`.
+pub fn load_cfg_file(path: &Path) -> Result<Vec<(String, Option<String>)>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read cfg file `{}`: {}", path.display(), err))?;
+    let entries = if path.extension().map_or(false, |ext| ext == "json") {
+        parse_json(&contents)
+    } else {
+        parse_toml(&contents)
+    };
+    let entries =
+        entries.map_err(|err| format!("invalid cfg file `{}`: {}", path.display(), err))?;
+
+    let mut cfgs = Vec::new();
+    for (name, value) in entries {
+        if !is_cfg_name(&name) {
+            return Err(format!(
+                "invalid cfg file `{}`: `{}` is not a valid cfg name",
+                path.display(),
+                name
+            ));
+        }
+        match value {
+            Value::Bool(true) => cfgs.push((name, None)),
+            Value::Bool(false) => {}
+            Value::String(value) => cfgs.push((name, Some(value))),
+            Value::Array(values) => {
+                cfgs.extend(values.into_iter().map(|value| (name.clone(), Some(value))))
+            }
+        }
+    }
+    Ok(cfgs)
+}
+
+fn is_cfg_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, |c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
+
+fn parse_json(contents: &str) -> Result<Vec<(String, Value)>, String> {
+    let object = match json::from_str(contents) {
+        Ok(Json::Object(object)) => object,
+        Ok(_) => return Err("expected a JSON object".to_string()),
+        Err(err) => return Err(err.to_string()),
+    };
+    let invalid_value = |name: &str| {
+        format!("the value of `{}` must be a boolean, a string or an array of strings", name)
+    };
+    object
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Json::Boolean(value) => Value::Bool(value),
+                Json::String(value) => Value::String(value),
+                Json::Array(values) => Value::Array(
+                    values
+                        .into_iter()
+                        .map(|value| match value {
+                            Json::String(value) => Ok(value),
+                            _ => Err(invalid_value(&name)),
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+                _ => return Err(invalid_value(&name)),
+            };
+            Ok((name, value))
+        })
+        .collect()
+}
+
+fn parse_toml(contents: &str) -> Result<Vec<(String, Value)>, String> {
+    let mut entries = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let error = |msg: &str| format!("line {}: {}", i + 1, msg);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(error("tables are not supported"));
+        }
+        let (name, mut value) =
+            line.split_once('=').ok_or_else(|| error("expected `name = value`"))?;
+        value = value.trim_start();
+        let parsed = parse_toml_value(&mut value)
+            .ok_or_else(|| error("expected `true`, `false`, a string or an array of strings"))?;
+        let rest = value.trim_start();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Err(error("unexpected characters after the value"));
+        }
+        entries.push((name.trim().to_string(), parsed));
+    }
+    Ok(entries)
+}
+
+/// Parses the value at the start of `s` and advances `s` past it.
+fn parse_toml_value(s: &mut &str) -> Option<Value> {
+    let full: &str = *s;
+    if let Some(rest) = full.strip_prefix("true") {
+        *s = rest;
+        return Some(Value::Bool(true));
+    }
+    if let Some(rest) = full.strip_prefix("false") {
+        *s = rest;
+        return Some(Value::Bool(false));
+    }
+    if full.starts_with('"') {
+        return parse_toml_string(s).map(Value::String);
+    }
+
+    let mut rest = full.strip_prefix('[')?;
+    let mut values = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix(']') {
+            *s = after;
+            return Some(Value::Array(values));
+        }
+        values.push(parse_toml_string(&mut rest)?);
+        rest = rest.trim_start();
+        match rest.strip_prefix(',') {
+            Some(after) => rest = after,
+            None if rest.starts_with(']') => {}
+            None => return None,
+        }
+    }
+}
+
+/// Parses the basic string (`"..."`) at the start of `s` and advances `s` past it.
+fn parse_toml_string(s: &mut &str) -> Option<String> {
+    let full: &str = *s;
+    let inner = full.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = inner.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                *s = &inner[i + 1..];
+                return Some(value);
+            }
+            '\\' => match chars.next()?.1 {
+                '"' => value.push('"'),
+                '\\' => value.push('\\'),
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                _ => return None,
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
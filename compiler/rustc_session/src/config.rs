@@ -3,16 +3,30 @@
 
 pub use crate::options::*;
 
+mod cfg;
+mod lint_opts;
+mod print;
+mod values;
+mod wasm;
+
+pub use self::cfg::*;
+pub use self::lint_opts::*;
+pub use self::print::*;
+pub use self::values::*;
+pub use self::wasm::*;
+
+use crate::cfg_file;
 use crate::lint;
 use crate::search_paths::SearchPath;
 use crate::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
 use crate::{early_error, early_warn, Session};
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::impl_stable_hash_via_hash;
 
 use rustc_target::abi::{Align, TargetDataLayout};
-use rustc_target::spec::{SplitDebuginfo, Target, TargetTriple, TargetWarnings};
+use rustc_target::spec::{resolve_target_alias, RelroLevel, SplitDebuginfo, StackProtector};
+use rustc_target::spec::{Target, TargetTriple, TargetWarnings};
 
 use rustc_serialize::json;
 
@@ -238,10 +252,15 @@ pub enum OutputType {
     Assembly,
     LlvmAssembly,
     Mir,
+    HirJson,
+    AnalysisJson,
+    CallGraph,
+    CallGraphDot,
     Metadata,
     Object,
     Exe,
     DepInfo,
+    ExportedSymbols,
 }
 
 impl_stable_hash_via_hash!(OutputType);
@@ -249,7 +268,14 @@ pub enum OutputType {
 impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
-            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata => true,
+            OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::ExportedSymbols
+            | OutputType::Metadata
+            | OutputType::HirJson
+            | OutputType::AnalysisJson
+            | OutputType::CallGraph
+            | OutputType::CallGraphDot => true,
             OutputType::Bitcode
             | OutputType::Assembly
             | OutputType::LlvmAssembly
@@ -258,16 +284,21 @@ fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         }
     }
 
-    fn shorthand(&self) -> &'static str {
+    pub fn shorthand(&self) -> &'static str {
         match *self {
             OutputType::Bitcode => "llvm-bc",
             OutputType::Assembly => "asm",
             OutputType::LlvmAssembly => "llvm-ir",
             OutputType::Mir => "mir",
+            OutputType::HirJson => "hir-json",
+            OutputType::AnalysisJson => "analysis-json",
+            OutputType::CallGraph => "callgraph",
+            OutputType::CallGraphDot => "callgraph-dot",
             OutputType::Object => "obj",
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::ExportedSymbols => "exported-symbols",
         }
     }
 
@@ -276,26 +307,36 @@ fn from_shorthand(shorthand: &str) -> Option<Self> {
             "asm" => OutputType::Assembly,
             "llvm-ir" => OutputType::LlvmAssembly,
             "mir" => OutputType::Mir,
+            "hir-json" => OutputType::HirJson,
+            "analysis-json" => OutputType::AnalysisJson,
+            "callgraph" => OutputType::CallGraph,
+            "callgraph-dot" => OutputType::CallGraphDot,
             "llvm-bc" => OutputType::Bitcode,
             "obj" => OutputType::Object,
             "metadata" => OutputType::Metadata,
             "link" => OutputType::Exe,
             "dep-info" => OutputType::DepInfo,
+            "exported-symbols" => OutputType::ExportedSymbols,
             _ => return None,
         })
     }
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
             OutputType::Mir.shorthand(),
+            OutputType::HirJson.shorthand(),
+            OutputType::AnalysisJson.shorthand(),
+            OutputType::CallGraph.shorthand(),
+            OutputType::CallGraphDot.shorthand(),
             OutputType::Object.shorthand(),
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
             OutputType::DepInfo.shorthand(),
+            OutputType::ExportedSymbols.shorthand(),
         )
     }
 
@@ -305,9 +346,14 @@ pub fn extension(&self) -> &'static str {
             OutputType::Assembly => "s",
             OutputType::LlvmAssembly => "ll",
             OutputType::Mir => "mir",
+            OutputType::HirJson => "hir.json",
+            OutputType::AnalysisJson => "analysis.json",
+            OutputType::CallGraph => "callgraph.json",
+            OutputType::CallGraphDot => "callgraph.dot",
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
+            OutputType::ExportedSymbols => "exports.json",
             OutputType::Exe => "",
         }
     }
@@ -385,9 +431,15 @@ pub fn should_codegen(&self) -> bool {
             | OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::CallGraph
+            | OutputType::CallGraphDot
             | OutputType::Object
-            | OutputType::Exe => true,
-            OutputType::Metadata | OutputType::DepInfo => false,
+            | OutputType::Exe
+            | OutputType::ExportedSymbols => true,
+            OutputType::Metadata
+            | OutputType::DepInfo
+            | OutputType::HirJson
+            | OutputType::AnalysisJson => false,
         })
     }
 
@@ -398,12 +450,27 @@ pub fn should_link(&self) -> bool {
             | OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::HirJson
+            | OutputType::AnalysisJson
+            | OutputType::CallGraph
+            | OutputType::CallGraphDot
             | OutputType::Metadata
             | OutputType::Object
-            | OutputType::DepInfo => false,
+            | OutputType::DepInfo
+            | OutputType::ExportedSymbols => false,
             OutputType::Exe => true,
         })
     }
+
+    // Returns `true` if a JSON output of the frontend is requested, and all of the other
+    // output types are produced by the frontend too, so that compilation can stop after
+    // analysis without running the codegen backend.
+    pub fn is_frontend_only(&self) -> bool {
+        self.0.keys().any(|k| matches!(*k, OutputType::HirJson | OutputType::AnalysisJson))
+            && self.0.keys().all(|k| {
+                matches!(*k, OutputType::HirJson | OutputType::AnalysisJson | OutputType::DepInfo)
+            })
+    }
 }
 
 /// Use tree-based collections to cheaply get a deterministic `Hash` implementation.
@@ -509,6 +576,16 @@ pub fn get(&self, key: &str) -> Option<&ExternDepSpec> {
     }
 }
 
+impl ExternDepSpec {
+    /// Returns the location as JSON, with raw locations as JSON strings.
+    pub fn to_json(&self) -> json::Json {
+        match self {
+            ExternDepSpec::Raw(raw) => json::Json::String(raw.clone()),
+            ExternDepSpec::Json(json) => json.clone(),
+        }
+    }
+}
+
 impl fmt::Display for ExternDepSpec {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -518,24 +595,6 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub enum PrintRequest {
-    FileNames,
-    Sysroot,
-    TargetLibdir,
-    CrateName,
-    Cfg,
-    TargetList,
-    TargetCPUs,
-    TargetFeatures,
-    RelocationModels,
-    CodeModels,
-    TlsModels,
-    TargetSpec,
-    NativeStaticLibs,
-    StackProtectorStrategies,
-}
-
 #[derive(Copy, Clone)]
 pub enum BorrowckMode {
     Mir,
@@ -587,6 +646,9 @@ pub struct OutputFilenames {
     filestem: String,
     pub single_output_file: Option<PathBuf>,
     pub temps_directory: Option<PathBuf>,
+    /// Whether intermediate files go into a subdirectory of `temps_directory` for their
+    /// kind, see `TEMP_KIND_DIRS`.
+    temps_per_kind: bool,
     pub outputs: OutputTypes,
 }
 
@@ -596,12 +658,31 @@ pub struct OutputFilenames {
 pub const RUST_CGU_EXT: &str = "rcgu";
 pub const DWARF_OBJECT_EXT: &str = "dwo";
 
+/// The subdirectories of the temps directory used with `-Z temps-dir-per-kind`.
+pub const TEMP_KIND_DIRS: &[&str] =
+    &["obj", "llvm-bc", "llvm-ir", "asm", "dwo", "metadata", "misc"];
+
+/// Returns the subdirectory for intermediate files with the given extension, which may
+/// have several components, like `no-opt.bc`.
+fn temp_kind_dir(ext: &str) -> &'static str {
+    match ext.rsplit('.').next() {
+        Some("o") => "obj",
+        Some("bc") => "llvm-bc",
+        Some("ll") => "llvm-ir",
+        Some("s") => "asm",
+        Some(DWARF_OBJECT_EXT) => "dwo",
+        Some("rmeta") => "metadata",
+        _ => "misc",
+    }
+}
+
 impl OutputFilenames {
     pub fn new(
         out_directory: PathBuf,
         out_filestem: String,
         single_output_file: Option<PathBuf>,
         temps_directory: Option<PathBuf>,
+        temps_per_kind: bool,
         extra: String,
         outputs: OutputTypes,
     ) -> Self {
@@ -609,6 +690,7 @@ pub fn new(
             out_directory,
             single_output_file,
             temps_directory,
+            temps_per_kind,
             outputs,
             filestem: format!("{}{}", out_filestem, extra),
         }
@@ -661,7 +743,11 @@ pub fn temp_path_ext(&self, ext: &str, codegen_unit_name: Option<&str>) -> PathB
             extension.push_str(ext);
         }
 
-        let temps_directory = self.temps_directory.as_ref().unwrap_or(&self.out_directory);
+        let temps_directory = match self.temps_directory {
+            Some(ref dir) if self.temps_per_kind => dir.join(temp_kind_dir(ext)),
+            Some(ref dir) => dir.clone(),
+            None => self.out_directory.clone(),
+        };
 
         self.with_directory_and_extension(&temps_directory, &extension)
     }
@@ -714,10 +800,13 @@ fn default() -> Options {
         Options {
             assert_incr_state: None,
             crate_types: Vec::new(),
+            file_cfg: Vec::new(),
             optimize: OptLevel::No,
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
+            lint_config: Vec::new(),
             lint_cap: None,
+            lint_group_caps: Vec::new(),
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -727,12 +816,16 @@ fn default() -> Options {
             incremental: None,
             debugging_opts: Default::default(),
             prints: Vec::new(),
+            custom_prints: Vec::new(),
+            print_json: false,
+            warn_inconsistent_option_combinations: false,
             borrowck_mode: BorrowckMode::Migrate,
             cg: Default::default(),
             error_format: ErrorOutputType::default(),
             externs: Externs(BTreeMap::new()),
             extern_dep_specs: ExternDepSpecs(BTreeMap::new()),
             crate_name: None,
+            crate_version: None,
             alt_std_name: None,
             libs: Vec::new(),
             unstable_features: UnstableFeatures::Disallow,
@@ -747,6 +840,7 @@ fn default() -> Options {
             json_artifact_notifications: false,
             json_unused_externs: false,
             json_future_incompat: false,
+            json_timings: false,
             pretty: None,
             working_dir: RealFileName::LocalPath(std::env::current_dir().unwrap()),
         }
@@ -765,9 +859,15 @@ pub fn file_path_mapping(&self) -> FilePathMapping {
         FilePathMapping::new(self.remap_path_prefix.clone())
     }
 
+    /// Returns `true` if `-Z stop-after`, or one of the flags it replaces, stops compilation
+    /// after `stage` or an earlier one.
+    pub fn stops_after(&self, stage: StopAfter) -> bool {
+        self.debugging_opts.stop_after.map_or(false, |stop_after| stop_after <= stage)
+    }
+
     /// Returns `true` if there will be an output file generated.
     pub fn will_create_output_file(&self) -> bool {
-        !self.debugging_opts.parse_only && // The file is just being parsed
+        !self.stops_after(StopAfter::Parsing) && // The file is just being parsed
             !self.debugging_opts.ls // The file is just being queried
     }
 
@@ -847,103 +947,6 @@ pub const fn default_lib_output() -> CrateType {
     CrateType::Rlib
 }
 
-fn default_configuration(sess: &Session) -> CrateConfig {
-    let end = &sess.target.endian;
-    let arch = &sess.target.arch;
-    let wordsz = sess.target.pointer_width.to_string();
-    let os = &sess.target.os;
-    let env = &sess.target.env;
-    let abi = &sess.target.abi;
-    let vendor = &sess.target.vendor;
-    let min_atomic_width = sess.target.min_atomic_width();
-    let max_atomic_width = sess.target.max_atomic_width();
-    let atomic_cas = sess.target.atomic_cas;
-    let layout = TargetDataLayout::parse(&sess.target).unwrap_or_else(|err| {
-        sess.fatal(&err);
-    });
-
-    let mut ret = FxHashSet::default();
-    ret.reserve(7); // the minimum number of insertions
-    // Target bindings.
-    ret.insert((sym::target_os, Some(Symbol::intern(os))));
-    for fam in &sess.target.families {
-        ret.insert((sym::target_family, Some(Symbol::intern(fam))));
-        if fam == "windows" {
-            ret.insert((sym::windows, None));
-        } else if fam == "unix" {
-            ret.insert((sym::unix, None));
-        }
-    }
-    ret.insert((sym::target_arch, Some(Symbol::intern(arch))));
-    ret.insert((sym::target_endian, Some(Symbol::intern(end.as_str()))));
-    ret.insert((sym::target_pointer_width, Some(Symbol::intern(&wordsz))));
-    ret.insert((sym::target_env, Some(Symbol::intern(env))));
-    ret.insert((sym::target_abi, Some(Symbol::intern(abi))));
-    ret.insert((sym::target_vendor, Some(Symbol::intern(vendor))));
-    if sess.target.has_elf_tls {
-        ret.insert((sym::target_thread_local, None));
-    }
-    for (i, align) in [
-        (8, layout.i8_align.abi),
-        (16, layout.i16_align.abi),
-        (32, layout.i32_align.abi),
-        (64, layout.i64_align.abi),
-        (128, layout.i128_align.abi),
-    ] {
-        if i >= min_atomic_width && i <= max_atomic_width {
-            let mut insert_atomic = |s, align: Align| {
-                ret.insert((sym::target_has_atomic_load_store, Some(Symbol::intern(s))));
-                if atomic_cas {
-                    ret.insert((sym::target_has_atomic, Some(Symbol::intern(s))));
-                }
-                if align.bits() == i {
-                    ret.insert((sym::target_has_atomic_equal_alignment, Some(Symbol::intern(s))));
-                }
-            };
-            let s = i.to_string();
-            insert_atomic(&s, align);
-            if s == wordsz {
-                insert_atomic("ptr", layout.pointer_align.abi);
-            }
-        }
-    }
-
-    let panic_strategy = sess.panic_strategy();
-    ret.insert((sym::panic, Some(panic_strategy.desc_symbol())));
-
-    for s in sess.opts.debugging_opts.sanitizer {
-        let symbol = Symbol::intern(&s.to_string());
-        ret.insert((sym::sanitize, Some(symbol)));
-    }
-
-    if sess.opts.debug_assertions {
-        ret.insert((sym::debug_assertions, None));
-    }
-    if sess.opts.crate_types.contains(&CrateType::ProcMacro) {
-        ret.insert((sym::proc_macro, None));
-    }
-    ret
-}
-
-/// Converts the crate `cfg!` configuration from `String` to `Symbol`.
-/// `rustc_interface::interface::Config` accepts this in the compiler configuration,
-/// but the symbol interner is not yet set up then, so we must convert it later.
-pub fn to_crate_config(cfg: FxHashSet<(String, Option<String>)>) -> CrateConfig {
-    cfg.into_iter().map(|(a, b)| (Symbol::intern(&a), b.map(|b| Symbol::intern(&b)))).collect()
-}
-
-pub fn build_configuration(sess: &Session, mut user_cfg: CrateConfig) -> CrateConfig {
-    // Combine the configuration requested by the session (command line) with
-    // some default and generated configuration items.
-    let default_cfg = default_configuration(sess);
-    // If the user wants a test runner, then add the test cfg.
-    if sess.opts.test {
-        user_cfg.insert((sym::test, None));
-    }
-    user_cfg.extend(default_cfg.iter().cloned());
-    user_cfg
-}
-
 pub(super) fn build_target_config(
     opts: &Options,
     target_override: Option<Target>,
@@ -953,7 +956,7 @@ pub(super) fn build_target_config(
         || Target::search(&opts.target_triple, sysroot),
         |t| Ok((t, TargetWarnings::empty())),
     );
-    let (target, target_warnings) = target_result.unwrap_or_else(|e| {
+    let (mut target, target_warnings) = target_result.unwrap_or_else(|e| {
         early_error(
             opts.error_format,
             &format!(
@@ -967,6 +970,55 @@ pub(super) fn build_target_config(
         early_warn(opts.error_format, &warning)
     }
 
+    if let Some(level) = opts.debugging_opts.android_api_level {
+        if !target.set_android_api_level(level) {
+            early_error(
+                opts.error_format,
+                &format!(
+                    "`-Z android-api-level` is only supported on Android targets, not `{}`",
+                    opts.target_triple
+                ),
+            );
+        }
+    }
+
+    if let Some(version) = opts.debugging_opts.apple_deployment_target {
+        if !target.set_apple_deployment_target(version) {
+            early_error(
+                opts.error_format,
+                &format!(
+                    "`-Z apple-deployment-target` is only supported on macOS and iOS targets \
+                     other than Mac Catalyst, not `{}`",
+                    opts.target_triple
+                ),
+            );
+        }
+    }
+
+    if let Some(ref abi) = opts.debugging_opts.riscv_abi {
+        if let Err(reason) = target.set_riscv_abi(abi) {
+            early_error(
+                opts.error_format,
+                &format!(
+                    "`-Z riscv-abi={}` is not supported for target `{}`: {}",
+                    abi, opts.target_triple, reason
+                ),
+            );
+        }
+    }
+
+    if let Some(relax) = opts.debugging_opts.riscv_relax {
+        if !target.set_riscv_relax(relax) {
+            early_error(
+                opts.error_format,
+                &format!(
+                    "`-Z riscv-relax` is only supported on RISC-V targets, not `{}`",
+                    opts.target_triple
+                ),
+            );
+        }
+    }
+
     if !matches!(target.pointer_width, 16 | 32 | 64) {
         early_error(
             opts.error_format,
@@ -1066,6 +1118,9 @@ pub fn opt(a: S, b: S, c: S, d: S) -> R {
     pub fn multi(a: S, b: S, c: S, d: S) -> R {
         unstable(longer(a, b), move |opts| opts.optmulti(a, b, c, d))
     }
+    pub fn flag(a: S, b: S, c: S) -> R {
+        unstable(longer(a, b), move |opts| opts.optflag(a, b, c))
+    }
 }
 
 /// Returns the "short" subset of the rustc command line options,
@@ -1113,9 +1168,12 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "",
             "print",
             "Compiler information to print on stdout",
-            "[crate-name|file-names|sysroot|target-libdir|cfg|target-list|\
-             target-cpus|target-features|relocation-models|code-models|\
-             tls-models|target-spec-json|native-static-libs|stack-protector-strategies]",
+            "[crate-name|file-names|sysroot|target-libdir|cfg|cfg-json|target-list|\
+             target-cpus|target-features|target-features-json|enabled-target-features|\
+             relocation-models|code-models|tls-models|target-spec-json|native-static-libs|\
+             exported-symbols|\
+             stack-protector-strategies|build-env-report|link-plan|resolved-linker|host-tuple|\
+             target-aliases|lints-json]",
         ),
         opt::flagmulti_s("g", "", "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
@@ -1182,6 +1240,30 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "human|json|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
+        opt::flag(
+            "",
+            "print-json",
+            "Print the output of `--print` as one line of JSON per request",
+        ),
+        opt::flag(
+            "",
+            "warn-inconsistent-option-combinations",
+            "Warn about options that are silently overridden by other options",
+        ),
+        opt::multi("", "cfg-file", "Load `--cfg` values from a JSON or TOML file", "PATH"),
+        opt::opt(
+            "",
+            "crate-version",
+            "Specify the version of the crate being built, recorded in its metadata",
+            "VERSION",
+        ),
+        opt::multi(
+            "",
+            "cap-lints-group",
+            "Set the most restrictive lint level of the lints of a group, \
+             instead of that of `--cap-lints`",
+            "GROUP=LEVEL",
+        ),
         opt::opt_s(
             "",
             "color",
@@ -1201,38 +1283,6 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
     opts
 }
 
-pub fn get_cmd_lint_options(
-    matches: &getopts::Matches,
-    error_format: ErrorOutputType,
-) -> (Vec<(String, lint::Level)>, bool, Option<lint::Level>) {
-    let mut lint_opts_with_position = vec![];
-    let mut describe_lints = false;
-
-    for level in [lint::Allow, lint::Warn, lint::ForceWarn, lint::Deny, lint::Forbid] {
-        for (arg_pos, lint_name) in matches.opt_strs_pos(level.as_str()) {
-            if lint_name == "help" {
-                describe_lints = true;
-            } else {
-                lint_opts_with_position.push((arg_pos, lint_name.replace('-', "_"), level));
-            }
-        }
-    }
-
-    lint_opts_with_position.sort_by_key(|x| x.0);
-    let lint_opts = lint_opts_with_position
-        .iter()
-        .cloned()
-        .map(|(_, lint_name, level)| (lint_name, level))
-        .collect();
-
-    let lint_cap = matches.opt_str("cap-lints").map(|cap| {
-        lint::Level::from_str(&cap)
-            .unwrap_or_else(|| early_error(error_format, &format!("unknown lint level: `{}`", cap)))
-    });
-
-    (lint_opts, describe_lints, lint_cap)
-}
-
 /// Parses the `--color` flag.
 pub fn parse_color(matches: &getopts::Matches) -> ColorConfig {
     match matches.opt_str("color").as_ref().map(|s| &s[..]) {
@@ -1259,6 +1309,7 @@ pub struct JsonConfig {
     pub json_artifact_notifications: bool,
     pub json_unused_externs: bool,
     pub json_future_incompat: bool,
+    pub json_timings: bool,
 }
 
 /// Parse the `--json` flag.
@@ -1272,6 +1323,7 @@ pub fn parse_json(matches: &getopts::Matches) -> JsonConfig {
     let mut json_artifact_notifications = false;
     let mut json_unused_externs = false;
     let mut json_future_incompat = false;
+    let mut json_timings = false;
     for option in matches.opt_strs("json") {
         // For now conservatively forbid `--color` with `--json` since `--json`
         // won't actually be emitting any colors and anything colorized is
@@ -1290,6 +1342,7 @@ pub fn parse_json(matches: &getopts::Matches) -> JsonConfig {
                 "artifacts" => json_artifact_notifications = true,
                 "unused-externs" => json_unused_externs = true,
                 "future-incompat" => json_future_incompat = true,
+                "timings" => json_timings = true,
                 s => early_error(
                     ErrorOutputType::default(),
                     &format!("unknown `--json` option `{}`", s),
@@ -1303,6 +1356,7 @@ pub fn parse_json(matches: &getopts::Matches) -> JsonConfig {
         json_artifact_notifications,
         json_unused_externs,
         json_future_incompat,
+        json_timings,
     }
 }
 
@@ -1420,7 +1474,7 @@ fn parse_output_types(
     error_format: ErrorOutputType,
 ) -> OutputTypes {
     let mut output_types = BTreeMap::new();
-    if !debugging_opts.parse_only {
+    if debugging_opts.stop_after != Some(StopAfter::Parsing) {
         for list in matches.opt_strs("emit") {
             for output_type in list.split(',') {
                 let (shorthand, path) = match output_type.split_once('=') {
@@ -1437,6 +1491,24 @@ fn parse_output_types(
                         ),
                     )
                 });
+                let is_unstable = matches!(
+                    output_type,
+                    OutputType::HirJson
+                        | OutputType::AnalysisJson
+                        | OutputType::CallGraph
+                        | OutputType::CallGraphDot
+                        | OutputType::ExportedSymbols
+                );
+                if is_unstable && !debugging_opts.unstable_options {
+                    early_error(
+                        error_format,
+                        &format!(
+                            "the `-Z unstable-options` flag must also be passed to \
+                             enable `--emit {}`",
+                            output_type.shorthand()
+                        ),
+                    );
+                }
                 output_types.insert(output_type, path);
             }
         }
@@ -1506,53 +1578,6 @@ fn check_thread_count(debugging_opts: &DebuggingOptions, error_format: ErrorOutp
     }
 }
 
-fn collect_print_requests(
-    cg: &mut CodegenOptions,
-    dopts: &mut DebuggingOptions,
-    matches: &getopts::Matches,
-    error_format: ErrorOutputType,
-) -> Vec<PrintRequest> {
-    let mut prints = Vec::<PrintRequest>::new();
-    if cg.target_cpu.as_ref().map_or(false, |s| s == "help") {
-        prints.push(PrintRequest::TargetCPUs);
-        cg.target_cpu = None;
-    };
-    if cg.target_feature == "help" {
-        prints.push(PrintRequest::TargetFeatures);
-        cg.target_feature = String::new();
-    }
-
-    prints.extend(matches.opt_strs("print").into_iter().map(|s| match &*s {
-        "crate-name" => PrintRequest::CrateName,
-        "file-names" => PrintRequest::FileNames,
-        "sysroot" => PrintRequest::Sysroot,
-        "target-libdir" => PrintRequest::TargetLibdir,
-        "cfg" => PrintRequest::Cfg,
-        "target-list" => PrintRequest::TargetList,
-        "target-cpus" => PrintRequest::TargetCPUs,
-        "target-features" => PrintRequest::TargetFeatures,
-        "relocation-models" => PrintRequest::RelocationModels,
-        "code-models" => PrintRequest::CodeModels,
-        "tls-models" => PrintRequest::TlsModels,
-        "native-static-libs" => PrintRequest::NativeStaticLibs,
-        "stack-protector-strategies" => PrintRequest::StackProtectorStrategies,
-        "target-spec-json" => {
-            if dopts.unstable_options {
-                PrintRequest::TargetSpec
-            } else {
-                early_error(
-                    error_format,
-                    "the `-Z unstable-options` flag must also be passed to \
-                     enable the target-spec-json print option",
-                );
-            }
-        }
-        req => early_error(error_format, &format!("unknown print request `{}`", req)),
-    }));
-
-    prints
-}
-
 pub fn parse_target_triple(
     matches: &getopts::Matches,
     error_format: ErrorOutputType,
@@ -1564,7 +1589,22 @@ pub fn parse_target_triple(
                 early_error(error_format, &format!("target file {:?} does not exist", path))
             })
         }
-        Some(target) => TargetTriple::TargetTriple(target),
+        Some(target) => match resolve_target_alias(&target) {
+            Some(triple) => {
+                if !nightly_options::is_unstable_enabled(matches) {
+                    early_error(
+                        error_format,
+                        &format!(
+                            "the target alias `{}` is unstable and requires `-Z unstable-options`; \
+                             use `--target {}` instead",
+                            target, triple
+                        ),
+                    );
+                }
+                TargetTriple::from_triple(triple)
+            }
+            None => TargetTriple::TargetTriple(target),
+        },
         _ => TargetTriple::from_triple(host_triple()),
     }
 }
@@ -1985,6 +2025,55 @@ fn parse_extern_dep_specs(
     ExternDepSpecs::new(map)
 }
 
+/// Parses the value of a `--remap-path-prefix` flag, `FROM=TO`, into `FROM` and `TO`.
+///
+/// `FROM` and `TO` are split at the last `=`, so only `FROM` may contain a plain `=`. In
+/// either of them, `${NAME}` expands to the value of the environment variable `NAME`, and
+/// `$=` and `$$` stand for a literal `=` and `$`. Expanded values are never split. This is
+/// only used with `-Z unstable-options`.
+fn parse_remap_path_prefix_arg(arg: &str) -> Result<(PathBuf, PathBuf), String> {
+    // The parts of `arg` between its unescaped `=`s, expanded.
+    let mut parts = vec![String::new()];
+    let mut chars = arg.chars().peekable();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().unwrap();
+        match (c, chars.peek()) {
+            ('=', _) => parts.push(String::new()),
+            ('$', Some('=' | '$')) => part.push(chars.next().unwrap()),
+            ('$', Some('{')) => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("`${{{}` is missing a closing `}}`", name)),
+                    }
+                }
+                match std::env::var(&name) {
+                    Ok(value) => part.push_str(&value),
+                    Err(std::env::VarError::NotPresent) => {
+                        return Err(format!("environment variable `{}` is not set", name));
+                    }
+                    Err(std::env::VarError::NotUnicode(_)) => {
+                        return Err(format!(
+                            "environment variable `{}` is not valid unicode",
+                            name
+                        ));
+                    }
+                }
+            }
+            (c, _) => part.push(c),
+        }
+    }
+
+    let to = parts.pop().unwrap();
+    if parts.is_empty() {
+        return Err("expected an unescaped `=` between FROM and TO".to_string());
+    }
+    Ok((PathBuf::from(parts.join("=")), PathBuf::from(to)))
+}
+
 fn parse_remap_path_prefix(
     matches: &getopts::Matches,
     debugging_opts: &DebuggingOptions,
@@ -1993,12 +2082,23 @@ fn parse_remap_path_prefix(
     let mut mapping: Vec<(PathBuf, PathBuf)> = matches
         .opt_strs("remap-path-prefix")
         .into_iter()
-        .map(|remap| match remap.rsplit_once('=') {
-            None => early_error(
-                error_format,
-                "--remap-path-prefix must contain '=' between FROM and TO",
-            ),
-            Some((from, to)) => (PathBuf::from(from), PathBuf::from(to)),
+        .map(|remap| {
+            // The expansions are unstable, so stable builds keep splitting at the last `=`.
+            if !debugging_opts.unstable_options {
+                return match remap.rsplit_once('=') {
+                    None => early_error(
+                        error_format,
+                        "--remap-path-prefix must contain '=' between FROM and TO",
+                    ),
+                    Some((from, to)) => (PathBuf::from(from), PathBuf::from(to)),
+                };
+            }
+            parse_remap_path_prefix_arg(&remap).unwrap_or_else(|err| {
+                early_error(
+                    error_format,
+                    &format!("invalid `--remap-path-prefix={}`: {}", remap, err),
+                )
+            })
         })
         .collect();
     match &debugging_opts.remap_cwd_prefix {
@@ -2011,6 +2111,32 @@ fn parse_remap_path_prefix(
     mapping
 }
 
+/// Expands `-Z hardening` into the individual options it stands for. Options passed
+/// explicitly on the command line take precedence over the preset. Each setting stays
+/// recorded in its own option, so dependency tracking needs no special handling.
+fn apply_hardening_profile(matches: &getopts::Matches, debugging_opts: &mut DebuggingOptions) {
+    let profile = match debugging_opts.hardening {
+        Some(profile) => profile,
+        None => return,
+    };
+
+    debugging_opts.relro_level.get_or_insert(RelroLevel::Full);
+    debugging_opts.bind_now.get_or_insert(true);
+
+    // `-Z stack-protector=none` is indistinguishable from the default value,
+    // so look at what was actually passed.
+    let stack_protector_given = matches.opt_strs("Z").iter().any(|opt| {
+        let name = opt.split_once('=').map_or(&**opt, |(name, _)| name);
+        name.replace('_', "-") == "stack-protector"
+    });
+    if !stack_protector_given {
+        debugging_opts.stack_protector = match profile {
+            HardeningProfile::Standard => StackProtector::Strong,
+            HardeningProfile::Strict => StackProtector::All,
+        };
+    }
+}
+
 pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let color = parse_color(matches);
 
@@ -2021,6 +2147,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         json_artifact_notifications,
         json_unused_externs,
         json_future_incompat,
+        json_timings,
     } = parse_json(matches);
 
     let error_format = parse_error_format(matches, color, json_rendered);
@@ -2031,9 +2158,32 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let mut debugging_opts = DebuggingOptions::build(matches, error_format);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_config = get_cmd_lint_config(matches, error_format);
+    let lint_group_caps = get_cmd_lint_group_caps(matches, error_format);
+    let file_cfg = matches
+        .opt_strs("cfg-file")
+        .iter()
+        .flat_map(|path| {
+            cfg_file::load_cfg_file(Path::new(path))
+                .unwrap_or_else(|err| early_error(error_format, &err))
+        })
+        .collect();
 
     check_debug_option_stability(&debugging_opts, error_format, json_rendered);
 
+    // `-Z parse-only`, `-Z no-analysis` and `-Z no-codegen` are aliases of `-Z stop-after`. If
+    // several of them are given, compilation stops after the earliest of their stages.
+    debugging_opts.stop_after = [
+        (debugging_opts.parse_only, StopAfter::Parsing),
+        (debugging_opts.no_analysis, StopAfter::Expansion),
+        (debugging_opts.no_codegen, StopAfter::Analysis),
+    ]
+    .iter()
+    .filter(|&&(alias, _)| alias)
+    .map(|&(_, stage)| stage)
+    .chain(debugging_opts.stop_after)
+    .min();
+
     if !debugging_opts.unstable_options && json_unused_externs {
         early_error(
             error_format,
@@ -2042,6 +2192,14 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         );
     }
 
+    if !debugging_opts.unstable_options && json_timings {
+        early_error(
+            error_format,
+            "the `-Z unstable-options` flag must also be passed to enable \
+            the flag `--json=timings`",
+        );
+    }
+
     let output_types = parse_output_types(&debugging_opts, matches, error_format);
 
     let mut cg = CodegenOptions::build(matches, error_format);
@@ -2054,6 +2212,17 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     check_thread_count(&debugging_opts, error_format);
 
+    apply_hardening_profile(matches, &mut debugging_opts);
+
+    match debugging_opts.temps_policy {
+        Some(TempsPolicy::AlwaysKeep) => cg.save_temps = true,
+        Some(policy) if cg.save_temps => early_error(
+            error_format,
+            &format!("`-C save-temps` conflicts with `-Z temps-policy={}`", policy.desc()),
+        ),
+        _ => {}
+    }
+
     let incremental = cg.incremental.as_ref().map(PathBuf::from);
 
     let assert_incr_state =
@@ -2106,19 +2275,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         // `-Z instrument-coverage` implies `-Z symbol-mangling-version=v0` - to ensure consistent
         // and reversible name mangling. Note, LLVM coverage tools can analyze coverage over
         // multiple runs, including some changes to source code; so mangled names must be consistent
-        // across compilations.
-        match debugging_opts.symbol_mangling_version {
-            None => {
-                debugging_opts.symbol_mangling_version = Some(SymbolManglingVersion::V0);
-            }
-            Some(SymbolManglingVersion::Legacy) => {
-                early_warn(
-                    error_format,
-                    "-Z instrument-coverage requires symbol mangling version `v0`, \
-                    but `-Z symbol-mangling-version=legacy` was specified",
-                );
-            }
-            Some(SymbolManglingVersion::V0) => {}
+        // across compilations. An explicit `legacy` is reported with the other inconsistent
+        // option combinations.
+        if debugging_opts.symbol_mangling_version.is_none() {
+            debugging_opts.symbol_mangling_version = Some(SymbolManglingVersion::V0);
         }
     }
 
@@ -2126,17 +2286,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debugging_opts.graphviz_font = graphviz_font;
     }
 
-    if !cg.embed_bitcode {
-        match cg.lto {
-            LtoCli::No | LtoCli::Unspecified => {}
-            LtoCli::Yes | LtoCli::NoParam | LtoCli::Thin | LtoCli::Fat => early_error(
-                error_format,
-                "options `-C embed-bitcode=no` and `-C lto` are incompatible",
-            ),
-        }
-    }
-
-    let prints = collect_print_requests(&mut cg, &mut debugging_opts, matches, error_format);
+    let (prints, custom_prints) =
+        collect_print_requests(&mut cg, &mut debugging_opts, matches, error_format);
 
     let cg = cg;
 
@@ -2168,6 +2319,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let extern_dep_specs = parse_extern_dep_specs(matches, &debugging_opts, error_format);
 
     let crate_name = matches.opt_str("crate-name");
+    let crate_version = matches.opt_str("crate-version");
+    if crate_version.as_deref().map_or(false, |version| version.trim().is_empty()) {
+        early_error(error_format, "`--crate-version` must not be empty");
+    }
 
     let remap_path_prefix = parse_remap_path_prefix(matches, &debugging_opts, error_format);
 
@@ -2225,10 +2380,13 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     Options {
         assert_incr_state,
         crate_types,
+        file_cfg,
         optimize: opt_level,
         debuginfo,
         lint_opts,
+        lint_config,
         lint_cap,
+        lint_group_caps,
         describe_lints,
         output_types,
         search_paths,
@@ -2238,6 +2396,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         incremental,
         debugging_opts,
         prints,
+        custom_prints,
+        print_json: matches.opt_present("print-json"),
+        warn_inconsistent_option_combinations: matches
+            .opt_present("warn-inconsistent-option-combinations"),
         borrowck_mode,
         cg,
         error_format,
@@ -2245,6 +2407,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         unstable_features: UnstableFeatures::from_environment(crate_name.as_deref()),
         extern_dep_specs,
         crate_name,
+        crate_version,
         alt_std_name: None,
         libs,
         debug_assertions,
@@ -2258,6 +2421,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         json_artifact_notifications,
         json_unused_externs,
         json_future_incompat,
+        json_timings,
         pretty,
         working_dir,
     }
@@ -2273,6 +2437,7 @@ fn parse_pretty(debugging_opts: &DebuggingOptions, efmt: ErrorOutputType) -> Opt
         "expanded" => Source(PpSourceMode::Expanded),
         "expanded,identified" => Source(PpSourceMode::ExpandedIdentified),
         "expanded,hygiene" => Source(PpSourceMode::ExpandedHygiene),
+        "expanded,hygiene,json" => Source(PpSourceMode::ExpandedHygieneJson),
         "ast-tree" => AstTree(PpAstTreeMode::Normal),
         "ast-tree,expanded" => AstTree(PpAstTreeMode::Expanded),
         "hir" => Hir(PpHirMode::Normal),
@@ -2287,7 +2452,7 @@ fn parse_pretty(debugging_opts: &DebuggingOptions, efmt: ErrorOutputType) -> Opt
             &format!(
                 "argument to `unpretty` must be one of `normal`, \
                             `expanded`, `identified`, `expanded,identified`, \
-                            `expanded,hygiene`, `everybody_loops`, \
+                            `expanded,hygiene`, `expanded,hygiene,json`, `everybody_loops`, \
                             `ast-tree`, `ast-tree,expanded`, `hir`, `hir,identified`, \
                             `hir,typed`, `hir-tree`, `mir` or `mir-cfg`; got {}",
                 name
@@ -2415,6 +2580,8 @@ pub enum PpSourceMode {
     ExpandedIdentified,
     /// `-Zunpretty=expanded,hygiene`
     ExpandedHygiene,
+    /// `-Zunpretty=expanded,hygiene,json`
+    ExpandedHygieneJson,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -2460,7 +2627,10 @@ pub fn needs_ast_map(&self) -> bool {
         match *self {
             Source(Normal | Identified) | AstTree(PpAstTreeMode::Normal) => false,
 
-            Source(Expanded | EveryBodyLoops | ExpandedIdentified | ExpandedHygiene)
+            Source(
+                Expanded | EveryBodyLoops | ExpandedIdentified | ExpandedHygiene
+                | ExpandedHygieneJson,
+            )
             | AstTree(PpAstTreeMode::Expanded)
             | Hir(_)
             | HirTree
@@ -2497,12 +2667,15 @@ pub fn needs_analysis(&self) -> bool {
 crate mod dep_tracking {
     use super::LdImpl;
     use super::{
-        CFGuard, CrateType, DebugInfo, ErrorOutputType, InstrumentCoverage, LinkerPluginLto,
-        LocationDetail, LtoCli, OptLevel, OutputType, OutputTypes, Passes, SourceFileHashAlgorithm,
-        SwitchWithOptPath, SymbolManglingVersion, TrimmedDefPaths,
+        AliasingModel, BinaryDepDepinfo, CFGuard, CguNameScheme, CrateType, DebugInfo,
+        EncodeMirFor, ErrorOutputType, FunctionReturn, HardeningProfile, Hotpatch,
+        InstrumentCoverage, InstrumentFunctionHooks, LinkerPluginLto, LocationDetail, LtoCli,
+        OptLevel, OutputType, OutputTypes, Passes, PltPolicy, RetagGranularity,
+        SourceFileHashAlgorithm, StackProtectorGuard, SwitchWithOptPath, SymbolManglingVersion,
+        TrimmedDefPaths, WasmFeature,
     };
     use crate::lint;
-    use crate::options::WasiExecModel;
+    use crate::options::{WasiComponent, WasiExecModel};
     use crate::utils::{NativeLib, NativeLibKind};
     use rustc_feature::UnstableFeatures;
     use rustc_span::edition::Edition;
@@ -2558,18 +2731,29 @@ fn hash(
         usize,
         NonZeroUsize,
         u64,
+        i32,
         String,
         PathBuf,
         lint::Level,
         WasiExecModel,
+        WasiComponent,
         u32,
         RelocModel,
         CodeModel,
         TlsModel,
         InstrumentCoverage,
+        InstrumentFunctionHooks,
+        Hotpatch,
+        FunctionReturn,
+        AliasingModel,
+        RetagGranularity,
+        EncodeMirFor,
+        WasmFeature,
         CrateType,
         MergeFunctions,
         PanicStrategy,
+        PanicAbortTestsFormat,
+        StopAfter,
         RelroLevel,
         Passes,
         OptLevel,
@@ -2580,11 +2764,16 @@ fn hash(
         NativeLibKind,
         SanitizerSet,
         CFGuard,
+        CguNameScheme,
+        BinaryDepDepinfo,
+        HardeningProfile,
+        PltPolicy,
         TargetTriple,
         Edition,
         LinkerPluginLto,
         SplitDebuginfo,
         StackProtector,
+        StackProtectorGuard,
         SwitchWithOptPath,
         SymbolManglingVersion,
         SourceFileHashAlgorithm,
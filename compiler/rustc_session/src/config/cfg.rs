@@ -0,0 +1,183 @@
+//! The crate configuration, the `cfg` values that conditional compilation tests, and where
+//! each of them comes from, as `--print cfg-json` reports it.
+
+use super::*;
+
+/// Where an entry of the crate configuration came from, as reported by
+/// `--print cfg-json`. A single `cfg` may have several sources.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum CfgSource {
+    /// Derived from the target specification, e.g. `target_os` or `unix`.
+    Target,
+    /// A `target_feature` enabled by the target CPU, the target specification
+    /// or `-C target-feature`.
+    TargetFeature,
+    /// Passed explicitly with `--cfg`.
+    CommandLine,
+    /// Loaded from a file given with `--cfg-file`.
+    CfgFile,
+    /// Implied by other compiler options, e.g. `debug_assertions` or `test`.
+    Compiler,
+}
+
+impl CfgSource {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            CfgSource::Target => "target",
+            CfgSource::TargetFeature => "target-feature",
+            CfgSource::CommandLine => "command-line",
+            CfgSource::CfgFile => "cfg-file",
+            CfgSource::Compiler => "compiler",
+        }
+    }
+}
+
+/// The configuration derived from the target specification alone.
+fn target_configuration(sess: &Session) -> CrateConfig {
+    let end = &sess.target.endian;
+    let arch = &sess.target.arch;
+    let wordsz = sess.target.pointer_width.to_string();
+    let os = &sess.target.os;
+    let env = &sess.target.env;
+    let abi = &sess.target.abi;
+    let vendor = &sess.target.vendor;
+    let min_atomic_width = sess.target.min_atomic_width();
+    let max_atomic_width = sess.target.max_atomic_width();
+    let atomic_cas = sess.target.atomic_cas;
+    let layout = TargetDataLayout::parse(&sess.target).unwrap_or_else(|err| {
+        sess.fatal(&err);
+    });
+
+    let mut ret = FxHashSet::default();
+    ret.reserve(7); // the minimum number of insertions
+    // Target bindings.
+    ret.insert((sym::target_os, Some(Symbol::intern(os))));
+    for fam in &sess.target.families {
+        ret.insert((sym::target_family, Some(Symbol::intern(fam))));
+        if fam == "windows" {
+            ret.insert((sym::windows, None));
+        } else if fam == "unix" {
+            ret.insert((sym::unix, None));
+        }
+    }
+    ret.insert((sym::target_arch, Some(Symbol::intern(arch))));
+    ret.insert((sym::target_endian, Some(Symbol::intern(end.as_str()))));
+    ret.insert((sym::target_pointer_width, Some(Symbol::intern(&wordsz))));
+    ret.insert((sym::target_env, Some(Symbol::intern(env))));
+    ret.insert((sym::target_abi, Some(Symbol::intern(abi))));
+    ret.insert((sym::target_vendor, Some(Symbol::intern(vendor))));
+    if sess.target.has_elf_tls {
+        ret.insert((sym::target_thread_local, None));
+    }
+    if let Some(level) = sess.opts.debugging_opts.android_api_level {
+        ret.insert((sym::target_android_api_level, Some(Symbol::intern(&level.to_string()))));
+    }
+    for (i, align) in [
+        (8, layout.i8_align.abi),
+        (16, layout.i16_align.abi),
+        (32, layout.i32_align.abi),
+        (64, layout.i64_align.abi),
+        (128, layout.i128_align.abi),
+    ] {
+        if i >= min_atomic_width && i <= max_atomic_width {
+            let mut insert_atomic = |s, align: Align| {
+                ret.insert((sym::target_has_atomic_load_store, Some(Symbol::intern(s))));
+                if atomic_cas {
+                    ret.insert((sym::target_has_atomic, Some(Symbol::intern(s))));
+                }
+                if align.bits() == i {
+                    ret.insert((sym::target_has_atomic_equal_alignment, Some(Symbol::intern(s))));
+                }
+            };
+            let s = i.to_string();
+            insert_atomic(&s, align);
+            if s == wordsz {
+                insert_atomic("ptr", layout.pointer_align.abi);
+            }
+        }
+    }
+
+    ret
+}
+
+/// The configuration implied by compiler options rather than by the target.
+fn compiler_configuration(sess: &Session) -> CrateConfig {
+    let mut ret = FxHashSet::default();
+    let panic_strategy = sess.panic_strategy();
+    ret.insert((sym::panic, Some(panic_strategy.desc_symbol())));
+
+    for s in sess.opts.debugging_opts.sanitizer {
+        let symbol = Symbol::intern(&s.to_string());
+        ret.insert((sym::sanitize, Some(symbol)));
+    }
+
+    if sess.opts.debug_assertions {
+        ret.insert((sym::debug_assertions, None));
+    }
+    if sess.opts.crate_types.contains(&CrateType::ProcMacro) {
+        ret.insert((sym::proc_macro, None));
+    }
+    // Lets build scripts and `cfg`s pick matching settings for code rustc doesn't
+    // compile itself, e.g. `_FORTIFY_SOURCE` for bundled C sources.
+    if let Some(profile) = sess.opts.debugging_opts.hardening {
+        ret.insert((sym::hardening, Some(Symbol::intern(profile.desc()))));
+    }
+    ret
+}
+
+fn default_configuration(sess: &Session) -> CrateConfig {
+    let mut ret = target_configuration(sess);
+    ret.extend(compiler_configuration(sess));
+    ret
+}
+
+/// Converts the crate `cfg!` configuration from `String` to `Symbol`.
+/// `rustc_interface::interface::Config` accepts this in the compiler configuration,
+/// but the symbol interner is not yet set up then, so we must convert it later.
+pub fn to_crate_config(cfg: FxHashSet<(String, Option<String>)>) -> CrateConfig {
+    cfg.into_iter().map(|(a, b)| (Symbol::intern(&a), b.map(|b| Symbol::intern(&b)))).collect()
+}
+
+pub fn build_configuration(sess: &Session, mut user_cfg: CrateConfig) -> CrateConfig {
+    // Combine the configuration requested by the session (command line) with
+    // some default and generated configuration items.
+    let default_cfg = default_configuration(sess);
+    user_cfg.extend(file_configuration(sess));
+    // If the user wants a test runner, then add the test cfg.
+    if sess.opts.test {
+        user_cfg.insert((sym::test, None));
+    }
+    user_cfg.extend(default_cfg.iter().cloned());
+    user_cfg
+}
+
+/// The configuration loaded with `--cfg-file`.
+fn file_configuration(sess: &Session) -> impl Iterator<Item = (Symbol, Option<Symbol>)> + '_ {
+    sess.opts
+        .file_cfg
+        .iter()
+        .map(|(name, value)| (Symbol::intern(name), value.as_deref().map(Symbol::intern)))
+}
+
+/// Computes the provenance of every entry `build_configuration` would produce
+/// for `user_cfg`. Target features are added later by the codegen backend and
+/// are recorded separately.
+pub fn configuration_provenance(
+    sess: &Session,
+    user_cfg: &CrateConfig,
+) -> FxHashMap<(Symbol, Option<Symbol>), Vec<CfgSource>> {
+    let mut provenance: FxHashMap<_, Vec<_>> = FxHashMap::default();
+    let mut compiler_cfg = compiler_configuration(sess);
+    if sess.opts.test {
+        compiler_cfg.insert((sym::test, None));
+    }
+    for (cfg, source) in iter::empty()
+        .chain(target_configuration(sess).into_iter().map(|cfg| (cfg, CfgSource::Target)))
+        .chain(compiler_cfg.into_iter().map(|cfg| (cfg, CfgSource::Compiler)))
+        .chain(user_cfg.iter().map(|&cfg| (cfg, CfgSource::CommandLine)))
+        .chain(file_configuration(sess).map(|cfg| (cfg, CfgSource::CfgFile)))
+    {
+        provenance.entry(cfg).or_default().push(source);
+    }
+    provenance
+}
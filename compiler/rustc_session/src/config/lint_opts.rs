@@ -0,0 +1,102 @@
+//! The lint levels, configuration values and caps given on the command line.
+
+use super::*;
+
+pub fn get_cmd_lint_options(
+    matches: &getopts::Matches,
+    error_format: ErrorOutputType,
+) -> (Vec<(String, lint::Level)>, bool, Option<lint::Level>) {
+    let mut lint_opts_with_position = vec![];
+    let mut describe_lints = false;
+
+    for level in [lint::Allow, lint::Warn, lint::ForceWarn, lint::Deny, lint::Forbid] {
+        for (arg_pos, lint_name) in matches.opt_strs_pos(level.as_str()) {
+            if lint_name == "help" {
+                describe_lints = true;
+            } else {
+                // Configuration values after the name are collected by `get_cmd_lint_config`.
+                let lint_name = lint_name.split_once('=').map_or(&lint_name[..], |(name, _)| name);
+                lint_opts_with_position.push((arg_pos, lint_name.replace('-', "_"), level));
+            }
+        }
+    }
+
+    lint_opts_with_position.sort_by_key(|x| x.0);
+    let lint_opts = lint_opts_with_position
+        .iter()
+        .cloned()
+        .map(|(_, lint_name, level)| (lint_name, level))
+        .collect();
+
+    let lint_cap = matches.opt_str("cap-lints").map(|cap| {
+        lint::Level::from_str(&cap)
+            .unwrap_or_else(|| early_error(error_format, &format!("unknown lint level: `{}`", cap)))
+    });
+
+    (lint_opts, describe_lints, lint_cap)
+}
+
+/// Collects the configuration values given with lint levels, as in
+/// `-W large-assignments=limit=4096,other=value`, as `(lint, option, value)` triples in
+/// command-line order.
+pub fn get_cmd_lint_config(
+    matches: &getopts::Matches,
+    error_format: ErrorOutputType,
+) -> Vec<(String, String, String)> {
+    let mut lint_config_with_position = vec![];
+
+    for level in [lint::Allow, lint::Warn, lint::ForceWarn, lint::Deny, lint::Forbid] {
+        for (arg_pos, arg) in matches.opt_strs_pos(level.as_str()) {
+            let (lint_name, config) = match arg.split_once('=') {
+                Some(split) => split,
+                None => continue,
+            };
+            for entry in config.split(',') {
+                let (option, value) = entry.split_once('=').unwrap_or_else(|| {
+                    early_error(
+                        error_format,
+                        &format!(
+                            "invalid lint configuration `{}` for `{}`, expected `option=value`",
+                            entry, lint_name
+                        ),
+                    )
+                });
+                lint_config_with_position.push((
+                    arg_pos,
+                    lint_name.replace('-', "_"),
+                    option.replace('_', "-"),
+                    value.to_string(),
+                ));
+            }
+        }
+    }
+
+    lint_config_with_position.sort_by_key(|x| x.0);
+    lint_config_with_position
+        .into_iter()
+        .map(|(_, lint_name, option, value)| (lint_name, option, value))
+        .collect()
+}
+
+/// Collects the caps given with `--cap-lints-group GROUP=LEVEL`, in command-line order.
+pub fn get_cmd_lint_group_caps(
+    matches: &getopts::Matches,
+    error_format: ErrorOutputType,
+) -> Vec<(String, lint::Level)> {
+    matches
+        .opt_strs("cap-lints-group")
+        .iter()
+        .map(|arg| {
+            let (group, cap) = arg.split_once('=').unwrap_or_else(|| {
+                early_error(
+                    error_format,
+                    &format!("invalid `--cap-lints-group` value `{}`, expected `GROUP=LEVEL`", arg),
+                )
+            });
+            let cap = lint::Level::from_str(cap).unwrap_or_else(|| {
+                early_error(error_format, &format!("unknown lint level: `{}`", cap))
+            });
+            (group.replace('-', "_"), cap)
+        })
+        .collect()
+}
@@ -0,0 +1,230 @@
+//! The `--print` requests, and how they are collected from the command line.
+
+use super::*;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PrintRequest {
+    FileNames,
+    Sysroot,
+    TargetLibdir,
+    CrateName,
+    Cfg,
+    CfgJson,
+    TargetList,
+    TargetCPUs,
+    TargetFeatures,
+    TargetFeaturesJson,
+    EnabledTargetFeatures,
+    RelocationModels,
+    CodeModels,
+    TlsModels,
+    TargetSpec,
+    NativeStaticLibs,
+    ExportedSymbols,
+    StackProtectorStrategies,
+    BuildEnvReport,
+    LinkPlan,
+    ResolvedLinker,
+    HostTuple,
+    TargetAliases,
+    LintsJson,
+}
+
+impl PrintRequest {
+    /// The name of the request on the command line, which also identifies it in the
+    /// output of `--print-json`.
+    pub fn name(self) -> &'static str {
+        match self {
+            PrintRequest::FileNames => "file-names",
+            PrintRequest::Sysroot => "sysroot",
+            PrintRequest::TargetLibdir => "target-libdir",
+            PrintRequest::CrateName => "crate-name",
+            PrintRequest::Cfg => "cfg",
+            PrintRequest::CfgJson => "cfg-json",
+            PrintRequest::TargetList => "target-list",
+            PrintRequest::TargetCPUs => "target-cpus",
+            PrintRequest::TargetFeatures => "target-features",
+            PrintRequest::TargetFeaturesJson => "target-features-json",
+            PrintRequest::EnabledTargetFeatures => "enabled-target-features",
+            PrintRequest::RelocationModels => "relocation-models",
+            PrintRequest::CodeModels => "code-models",
+            PrintRequest::TlsModels => "tls-models",
+            PrintRequest::TargetSpec => "target-spec-json",
+            PrintRequest::NativeStaticLibs => "native-static-libs",
+            PrintRequest::ExportedSymbols => "exported-symbols",
+            PrintRequest::StackProtectorStrategies => "stack-protector-strategies",
+            PrintRequest::BuildEnvReport => "build-env-report",
+            PrintRequest::LinkPlan => "link-plan",
+            PrintRequest::ResolvedLinker => "resolved-linker",
+            PrintRequest::HostTuple => "host-tuple",
+            PrintRequest::TargetAliases => "target-aliases",
+            PrintRequest::LintsJson => "lints-json",
+        }
+    }
+}
+
+pub(super) fn collect_print_requests(
+    cg: &mut CodegenOptions,
+    dopts: &mut DebuggingOptions,
+    matches: &getopts::Matches,
+    error_format: ErrorOutputType,
+) -> (Vec<PrintRequest>, Vec<String>) {
+    let mut prints = Vec::<PrintRequest>::new();
+    let mut custom_prints = Vec::new();
+    if cg.target_cpu.as_ref().map_or(false, |s| s == "help") {
+        prints.push(PrintRequest::TargetCPUs);
+        cg.target_cpu = None;
+    };
+    if cg.target_feature == "help" {
+        prints.push(PrintRequest::TargetFeatures);
+        cg.target_feature = String::new();
+    }
+
+    for req in matches.opt_strs("print") {
+        let print = match &*req {
+            "crate-name" => PrintRequest::CrateName,
+            "file-names" => PrintRequest::FileNames,
+            "sysroot" => PrintRequest::Sysroot,
+            "target-libdir" => PrintRequest::TargetLibdir,
+            "cfg" => PrintRequest::Cfg,
+            "target-list" => PrintRequest::TargetList,
+            "target-cpus" => PrintRequest::TargetCPUs,
+            "target-features" => PrintRequest::TargetFeatures,
+            "relocation-models" => PrintRequest::RelocationModels,
+            "code-models" => PrintRequest::CodeModels,
+            "tls-models" => PrintRequest::TlsModels,
+            "native-static-libs" => PrintRequest::NativeStaticLibs,
+            "stack-protector-strategies" => PrintRequest::StackProtectorStrategies,
+            "cfg-json" => {
+                if dopts.unstable_options {
+                    PrintRequest::CfgJson
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the cfg-json print option",
+                    );
+                }
+            }
+            "target-features-json" => {
+                if dopts.unstable_options {
+                    PrintRequest::TargetFeaturesJson
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the target-features-json print option",
+                    );
+                }
+            }
+            "enabled-target-features" => {
+                if dopts.unstable_options {
+                    PrintRequest::EnabledTargetFeatures
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the enabled-target-features print option",
+                    );
+                }
+            }
+            "exported-symbols" => {
+                if dopts.unstable_options {
+                    PrintRequest::ExportedSymbols
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the exported-symbols print option",
+                    );
+                }
+            }
+            "build-env-report" => {
+                if dopts.unstable_options {
+                    PrintRequest::BuildEnvReport
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the build-env-report print option",
+                    );
+                }
+            }
+            "link-plan" => {
+                if dopts.unstable_options {
+                    PrintRequest::LinkPlan
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the link-plan print option",
+                    );
+                }
+            }
+            "resolved-linker" => {
+                if dopts.unstable_options {
+                    PrintRequest::ResolvedLinker
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the resolved-linker print option",
+                    );
+                }
+            }
+            "target-spec-json" => {
+                if dopts.unstable_options {
+                    PrintRequest::TargetSpec
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the target-spec-json print option",
+                    );
+                }
+            }
+            "host-tuple" => {
+                if dopts.unstable_options {
+                    PrintRequest::HostTuple
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the host-tuple print option",
+                    );
+                }
+            }
+            "target-aliases" => {
+                if dopts.unstable_options {
+                    PrintRequest::TargetAliases
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the target-aliases print option",
+                    );
+                }
+            }
+            "lints-json" => {
+                if dopts.unstable_options {
+                    PrintRequest::LintsJson
+                } else {
+                    early_error(
+                        error_format,
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the lints-json print option",
+                    );
+                }
+            }
+            // Whether the driver knows this request is only checked once it has registered its
+            // own, when the compiler is created.
+            _ => {
+                custom_prints.push(req);
+                continue;
+            }
+        };
+        prints.push(print);
+    }
+
+    (prints, custom_prints)
+}
@@ -0,0 +1,339 @@
+//! The types of the values of the unstable options that choose between a few behaviors, with
+//! their descriptions and the behaviors they imply.
+
+use super::*;
+
+/// The different settings that the `-Z stack-protector-guard` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum StackProtectorGuard {
+    /// Load the guard value from thread-local storage, at `-Z stack-protector-guard-offset`
+    /// from the thread pointer.
+    Tls,
+
+    /// Load the guard value from the global `__stack_chk_guard` symbol.
+    Global,
+}
+
+impl StackProtectorGuard {
+    pub fn desc(&self) -> &str {
+        match *self {
+            StackProtectorGuard::Tls => "tls",
+            StackProtectorGuard::Global => "global",
+        }
+    }
+}
+
+/// The naming schemes that the `-Z cgu-name-scheme` flag can select for codegen units, and
+/// thus for the object and LLVM IR files emitted for them.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum CguNameScheme {
+    /// `<crate>.<id>-cgu.<index>`, numbered in an order derived from the modules each CGU
+    /// contains. The default for non-incremental builds.
+    Numbered,
+
+    /// A fixed-length hash of the modules each CGU contains. The default for incremental
+    /// builds.
+    Hashed,
+
+    /// The paths of the modules each CGU contains, as with `-Z human-readable-cgu-names`.
+    ItemBased,
+}
+
+impl CguNameScheme {
+    pub fn desc(&self) -> &str {
+        match *self {
+            CguNameScheme::Numbered => "numbered",
+            CguNameScheme::Hashed => "hashed",
+            CguNameScheme::ItemBased => "item-based",
+        }
+    }
+}
+
+/// The policies that the `-Z temps-policy` flag can select for intermediate files.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum TempsPolicy {
+    /// Keep intermediate files only if compilation fails.
+    KeepOnError,
+
+    /// Always keep intermediate files, like `-C save-temps`.
+    AlwaysKeep,
+
+    /// Always remove intermediate files. The default without `-C save-temps`.
+    AlwaysClean,
+}
+
+impl TempsPolicy {
+    pub fn desc(&self) -> &str {
+        match *self {
+            TempsPolicy::KeepOnError => "keep-on-error",
+            TempsPolicy::AlwaysKeep => "always-keep",
+            TempsPolicy::AlwaysClean => "always-clean",
+        }
+    }
+}
+
+/// A flag given with `-Z doctest-compile-options`, which rustdoc passes on to the compiler
+/// when it builds doctests.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub enum DoctestFlag {
+    /// `-C name=value`, stored as `name=value`.
+    Codegen(String),
+
+    /// `-A`, `-W`, `-D` or `-F` with the name of a lint or lint group.
+    Lint(lint::Level, String),
+}
+
+impl DoctestFlag {
+    /// The compiler arguments this flag stands for.
+    pub fn to_args(&self) -> [&str; 2] {
+        match self {
+            DoctestFlag::Codegen(option) => ["-C", option],
+            DoctestFlag::Lint(level, name) => {
+                let flag = match level {
+                    lint::Allow => "-A",
+                    lint::Expect(_) => unreachable!("`expect` can't be set on the command line"),
+                    lint::Warn => "-W",
+                    lint::ForceWarn => "--force-warn",
+                    lint::Deny => "-D",
+                    lint::Forbid => "-F",
+                };
+                [flag, name]
+            }
+        }
+    }
+}
+
+/// How the subprocesses running tests built with `-Z panic-abort-tests` report their
+/// result, set with `-Z panic-abort-tests-format`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum PanicAbortTestsFormat {
+    /// Only through the exit code of the subprocess. The default.
+    ExitCode,
+
+    /// Also with a line of JSON on stdout, for harnesses that spawn the subprocesses
+    /// themselves.
+    Json,
+}
+
+impl PanicAbortTestsFormat {
+    pub fn desc(&self) -> &str {
+        match *self {
+            PanicAbortTestsFormat::ExitCode => "exit-code",
+            PanicAbortTestsFormat::Json => "json",
+        }
+    }
+}
+
+/// How reports such as those of `-Z resource-summary`, `-Z binary-size-report` and
+/// `-Z dump-mono-stats` are printed.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum ReportFormat {
+    /// Text meant to be read by people. The default.
+    Human,
+
+    /// JSON meant to be read by tools.
+    Json,
+}
+
+/// What `-Z binary-size-report` reports on, and how.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub struct BinarySizeReport {
+    /// Whether to report on the linked executables and dynamic libraries, rather than on the
+    /// object files of the codegen units.
+    pub linked: bool,
+    pub format: ReportFormat,
+}
+
+/// The last stage of compilation that runs with `-Z stop-after`, in the order they run.
+///
+/// Only the outputs of the stages that ran are written: none after parsing, `dep-info`
+/// after expansion, and also `metadata`, `hir-json` and `analysis-json` after analysis. After
+/// codegen, every requested output is written except for the linked crate.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum StopAfter {
+    /// Stop once the crate is parsed, like `-Z parse-only`.
+    Parsing,
+
+    /// Stop once macros are expanded and names resolved, like `-Z no-analysis`.
+    Expansion,
+
+    /// Stop once the crate is checked, without generating code, like `-Z no-codegen`.
+    Analysis,
+
+    /// Stop once code is generated, without linking.
+    Codegen,
+}
+
+/// How the `-Z proc-macro-sandbox` flag restricts code run by procedural macros.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum ProcMacroSandbox {
+    /// Deny file, process and network access, and report each attempt as an error.
+    Strict,
+
+    /// Allow all accesses, but report each one as a warning.
+    Warn,
+
+    /// Run proc macros without restrictions. The default.
+    Off,
+}
+
+impl ProcMacroSandbox {
+    pub fn desc(&self) -> &str {
+        match *self {
+            ProcMacroSandbox::Strict => "strict",
+            ProcMacroSandbox::Warn => "warn",
+            ProcMacroSandbox::Off => "off",
+        }
+    }
+}
+
+/// The hardening presets that the `-Z hardening` flag can select.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum HardeningProfile {
+    /// Full RELRO, immediate binding, PIE and `-Z stack-protector=strong`.
+    Standard,
+
+    /// Like `Standard`, but with `-Z stack-protector=all`.
+    Strict,
+}
+
+impl HardeningProfile {
+    pub fn desc(&self) -> &str {
+        match *self {
+            HardeningProfile::Standard => "standard",
+            HardeningProfile::Strict => "strict",
+        }
+    }
+}
+
+/// The different settings that the `-Z plt` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum PltPolicy {
+    /// Skip the PLT only when lazy binding is disabled anyway and the target allows it.
+    Auto,
+
+    /// Always call functions from shared libraries through the PLT.
+    Always,
+
+    /// Never use the PLT, calling through the GOT instead.
+    Never,
+}
+
+/// The different settings that the `-Z binary-dep-depinfo` flag can have.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum BinaryDepDepinfo {
+    /// Do not list binary dependencies in dep-info.
+    Disabled,
+
+    /// List the paths of binary dependencies in dep-info.
+    Paths,
+
+    /// List the paths of binary dependencies along with their SVH and a hash of
+    /// their contents.
+    Hashed,
+}
+
+/// The functions that `-Z instrument-functions` calls on entry to and exit from the
+/// instrumented functions.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct InstrumentFunctionHooks {
+    pub entry: String,
+    pub exit: Option<String>,
+}
+
+/// The hot-patching support that `-Z hotpatch` adds to every function.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum Hotpatch {
+    /// The hot-patchable prologue of the target, like with MSVC's `/hotpatch`.
+    Prologue,
+    /// `entry` NOPs at the start of each function, and `prefix` NOPs before it.
+    NopSled { entry: u32, prefix: u32 },
+}
+
+/// The aliasing model selected with `-Z aliasing-model`, which decides whether MIR has retag
+/// statements and which references are marked `noalias` in the generated code.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum AliasingModel {
+    /// Stacked Borrows, which justifies `noalias` on mutable references. Retags are emitted.
+    StackedBorrows,
+    /// Tree Borrows, which lets a mutable reference be read through other pointers until it is
+    /// first written to, so mutable references aren't marked `noalias`. Retags are emitted.
+    TreeBorrows,
+    /// What the codegen backend assumes by default: mutable references are marked `noalias`, and
+    /// no retags are emitted.
+    LlvmDefault,
+}
+
+impl AliasingModel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AliasingModel::StackedBorrows => "stacked-borrows",
+            AliasingModel::TreeBorrows => "tree-borrows",
+            AliasingModel::LlvmDefault => "llvm-default",
+        }
+    }
+
+    /// Whether MIR has retag statements, for interpreters like Miri to check the model.
+    pub fn emits_retags(self) -> bool {
+        self != AliasingModel::LlvmDefault
+    }
+
+    /// Whether mutable references are marked `noalias`.
+    pub fn mutable_noalias(self) -> bool {
+        self != AliasingModel::TreeBorrows
+    }
+}
+
+/// The items whose optimized MIR is encoded into the crate metadata even when other crates
+/// don't need it for codegen, as requested with `-Z encode-mir-for`. Interpreters and verifiers
+/// like Miri and Kani need it to run the code of dependencies.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum EncodeMirFor {
+    /// The public items and the local functions and closures they can call.
+    Reachable,
+    /// The public items and their closures.
+    Public,
+    /// Every item, like `-Z always-encode-mir`.
+    All,
+}
+
+/// Which retag statements are emitted with `-Z mir-emit-retag`. Interpreters like Miri spend
+/// most of their time processing retags, so the coarser granularities trade checking precision
+/// for speed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Encodable, Decodable)]
+pub enum RetagGranularity {
+    /// Retag references where they're created, copied, passed to functions and returned from
+    /// calls.
+    Full,
+    /// Retag references where they're created and passed to functions, but not where they're
+    /// copied or returned from calls.
+    Coarse,
+    /// Only retag the arguments of functions on entry.
+    FnEntryOnly,
+}
+
+impl_stable_hash_via_hash!(RetagGranularity);
+
+impl RetagGranularity {
+    /// Whether references are retagged after being copied or returned from calls.
+    pub fn retags_copies(self) -> bool {
+        self == RetagGranularity::Full
+    }
+
+    /// Whether references are retagged where they're created.
+    pub fn retags_creations(self) -> bool {
+        self != RetagGranularity::FnEntryOnly
+    }
+}
+
+/// How functions return, as requested with `-Z function-return`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum FunctionReturn {
+    /// Return with the `ret` instruction, as usual.
+    Keep,
+    /// Jump to `__x86_return_thunk` instead of returning, like GCC's and Clang's
+    /// `-mfunction-return=thunk-extern`. The thunk is defined outside of the crate, e.g. by the
+    /// Linux kernel, which patches it to mitigate return-address speculation attacks.
+    ThunkExtern,
+}
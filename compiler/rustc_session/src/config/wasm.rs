@@ -0,0 +1,146 @@
+//! The WebAssembly proposals of `-Z wasm-features`, and the Emscripten settings of
+//! `-Z emscripten-flags`.
+
+use super::*;
+
+/// The Emscripten settings that `-Z emscripten-flags` accepts, from Emscripten's
+/// `src/settings.js`.
+pub const EMSCRIPTEN_SETTINGS: &[&str] = &[
+    "ABORTING_MALLOC",
+    "ALLOW_MEMORY_GROWTH",
+    "ALLOW_TABLE_GROWTH",
+    "ASSERTIONS",
+    "ASYNCIFY",
+    "ASYNCIFY_ADD",
+    "ASYNCIFY_IMPORTS",
+    "ASYNCIFY_REMOVE",
+    "ASYNCIFY_STACK_SIZE",
+    "AUTO_JS_LIBRARIES",
+    "DEMANGLE_SUPPORT",
+    "DETERMINISTIC",
+    "DYNAMIC_EXECUTION",
+    "ENVIRONMENT",
+    "ERROR_ON_UNDEFINED_SYMBOLS",
+    "EXCEPTION_DEBUG",
+    "EXIT_RUNTIME",
+    "EXPORTED_RUNTIME_METHODS",
+    "EXPORT_ALL",
+    "EXPORT_ES6",
+    "EXPORT_NAME",
+    "FETCH",
+    "FILESYSTEM",
+    "FORCE_FILESYSTEM",
+    "FULL_ES2",
+    "FULL_ES3",
+    "GL_ASSERTIONS",
+    "IMPORTED_MEMORY",
+    "INCOMING_MODULE_JS_API",
+    "INITIAL_MEMORY",
+    "INVOKE_RUN",
+    "LEGACY_GL_EMULATION",
+    "LINKABLE",
+    "MAIN_MODULE",
+    "MALLOC",
+    "MAXIMUM_MEMORY",
+    "MAX_WEBGL_VERSION",
+    "MEMORY_GROWTH_GEOMETRIC_STEP",
+    "MEMORY_GROWTH_LINEAR_STEP",
+    "MIN_WEBGL_VERSION",
+    "MODULARIZE",
+    "NODERAWFS",
+    "OFFSCREENCANVAS_SUPPORT",
+    "OFFSCREEN_FRAMEBUFFER",
+    "PROXY_TO_PTHREAD",
+    "PTHREAD_POOL_SIZE",
+    "RELOCATABLE",
+    "SAFE_HEAP",
+    "SIDE_MODULE",
+    "SINGLE_FILE",
+    "STACK_OVERFLOW_CHECK",
+    "STANDALONE_WASM",
+    "STRICT",
+    "SUPPORT_LONGJMP",
+    "TOTAL_MEMORY",
+    "TOTAL_STACK",
+    "USE_GLFW",
+    "USE_PTHREADS",
+    "USE_SDL",
+    "USE_SDL_IMAGE",
+    "USE_SDL_MIXER",
+    "USE_SDL_TTF",
+    "USE_WEBGL2",
+    "USE_ZLIB",
+    "WASM",
+    "WASM_ASYNC_COMPILATION",
+    "WASM_BIGINT",
+];
+
+/// The Emscripten settings that rustc passes itself, from the exported symbols, the panic
+/// strategy and `-C default-linker-libraries`.
+pub const EMSCRIPTEN_SETTINGS_SET_BY_RUSTC: &[&str] =
+    &["DEFAULT_LIBRARY_FUNCS_TO_INCLUDE", "DISABLE_EXCEPTION_CATCHING", "EXPORTED_FUNCTIONS"];
+
+/// A WebAssembly proposal that `-Z wasm-features` enables or disables.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WasmFeature {
+    Atomics,
+    BulkMemory,
+    ExceptionHandling,
+    MultiMemory,
+    Multivalue,
+    MutableGlobals,
+    NontrappingFptoint,
+    ReferenceTypes,
+    SignExt,
+    Simd128,
+    TailCall,
+}
+
+impl WasmFeature {
+    pub const ALL: &'static [WasmFeature] = &[
+        WasmFeature::Atomics,
+        WasmFeature::BulkMemory,
+        WasmFeature::ExceptionHandling,
+        WasmFeature::MultiMemory,
+        WasmFeature::Multivalue,
+        WasmFeature::MutableGlobals,
+        WasmFeature::NontrappingFptoint,
+        WasmFeature::ReferenceTypes,
+        WasmFeature::SignExt,
+        WasmFeature::Simd128,
+        WasmFeature::TailCall,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WasmFeature::Atomics => "atomics",
+            WasmFeature::BulkMemory => "bulk-memory",
+            WasmFeature::ExceptionHandling => "exception-handling",
+            WasmFeature::MultiMemory => "multi-memory",
+            WasmFeature::Multivalue => "multivalue",
+            WasmFeature::MutableGlobals => "mutable-globals",
+            WasmFeature::NontrappingFptoint => "nontrapping-fptoint",
+            WasmFeature::ReferenceTypes => "reference-types",
+            WasmFeature::SignExt => "sign-ext",
+            WasmFeature::Simd128 => "simd128",
+            WasmFeature::TailCall => "tail-call",
+        }
+    }
+
+    /// The name of the LLVM target feature enabling the proposal.
+    pub fn llvm_feature(self) -> &'static str {
+        match self {
+            WasmFeature::MultiMemory => "multimemory",
+            _ => self.name(),
+        }
+    }
+
+    /// The features that the feature can't be used without: shared memories need passive
+    /// segments to be initialized once, and thread-local storage needs mutable globals.
+    pub fn implied_features(self) -> &'static [WasmFeature] {
+        match self {
+            WasmFeature::Atomics => &[WasmFeature::BulkMemory, WasmFeature::MutableGlobals],
+            _ => &[],
+        }
+    }
+}
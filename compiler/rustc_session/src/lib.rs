@@ -1,9 +1,12 @@
+#![feature(core_intrinsics)]
 #![feature(crate_visibility_modifier)]
 #![feature(derive_default_enum)]
 #![feature(min_specialization)]
 #![feature(once_cell)]
 #![recursion_limit = "256"]
 
+#[macro_use]
+extern crate rustc_data_structures;
 #[macro_use]
 extern crate rustc_macros;
 
@@ -13,7 +16,10 @@
 pub use rustc_lint_defs as lint;
 pub mod parse;
 
+mod cfg_file;
 mod code_stats;
+mod module_timings;
+mod option_combinations;
 #[macro_use]
 pub mod config;
 pub mod cstore;
@@ -25,6 +31,7 @@
 pub use session::*;
 
 pub mod output;
+pub mod sandbox;
 
 pub use getopts;
 
@@ -0,0 +1,90 @@
+//! The report of `-Z module-timings`, which attributes the time spent compiling a crate to
+//! its modules and items.
+//!
+//! The attribution is a best effort: the front end checks the bodies of the crate one at a
+//! time, so that the time spent on each is known, but a query shared by several bodies is
+//! only attributed to the first one that needs it. The time spent generating the code of a
+//! codegen unit is split between its items in proportion to their size estimates.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::profiling::duration_to_secs_str;
+use rustc_data_structures::sync::Lock;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// The part of the compilation some time was spent in.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TimingPhase {
+    /// Type checking, building and borrow checking the MIR of a body.
+    FrontEnd,
+    /// Generating the code of a codegen unit, before it is optimized.
+    Codegen,
+}
+
+#[derive(Copy, Clone, Default)]
+struct ItemTimes {
+    front_end: Duration,
+    codegen: Duration,
+}
+
+impl ItemTimes {
+    fn add(&mut self, other: ItemTimes) {
+        self.front_end += other.front_end;
+        self.codegen += other.codegen;
+    }
+
+    fn total(&self) -> Duration {
+        self.front_end + self.codegen
+    }
+}
+
+#[derive(Default)]
+pub struct ModuleTimings {
+    /// The times of each item, by module and item path.
+    items: Lock<FxHashMap<(String, String), ItemTimes>>,
+}
+
+impl ModuleTimings {
+    pub fn record(&self, module: String, item: String, phase: TimingPhase, duration: Duration) {
+        let mut items = self.items.borrow_mut();
+        let times = items.entry((module, item)).or_default();
+        match phase {
+            TimingPhase::FrontEnd => times.front_end += duration,
+            TimingPhase::Codegen => times.codegen += duration,
+        }
+    }
+
+    /// Writes the modules, slowest first, each followed by its items, slowest first.
+    pub fn write_report(&self, path: &Path) -> io::Result<()> {
+        let items = self.items.borrow();
+        let mut modules: FxHashMap<&str, (ItemTimes, Vec<(&str, ItemTimes)>)> =
+            FxHashMap::default();
+        for (&(ref module, ref item), &times) in items.iter() {
+            let (module_times, module_items) = modules.entry(module.as_str()).or_default();
+            module_times.add(times);
+            module_items.push((item.as_str(), times));
+        }
+        let mut modules: Vec<_> = modules.into_iter().collect();
+        modules.sort_by(|a, b| (b.1).0.total().cmp(&(a.1).0.total()).then(a.0.cmp(b.0)));
+
+        let secs = duration_to_secs_str;
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{:>10} {:>10} {:>10}  module / item", "total", "front end", "codegen")?;
+        for (module, (times, mut module_items)) in modules {
+            writeln!(file)?;
+            let (total, front_end, codegen) =
+                (secs(times.total()), secs(times.front_end), secs(times.codegen));
+            writeln!(file, "{:>10} {:>10} {:>10}  {}", total, front_end, codegen, module)?;
+            module_items.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.cmp(b.0)));
+            for (item, times) in module_items {
+                let (total, front_end, codegen) =
+                    (secs(times.total()), secs(times.front_end), secs(times.codegen));
+                writeln!(file, "{:>10} {:>10} {:>10}    {}", total, front_end, codegen, item)?;
+            }
+        }
+        file.flush()
+    }
+}
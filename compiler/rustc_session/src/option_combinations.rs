@@ -0,0 +1,150 @@
+//! Cross-checks of command-line options that are fine on their own, but contradict each other
+//! or are silently overridden when combined.
+//!
+//! Combinations that can't work are always errors, and combinations where an option doesn't do
+//! what it says are always warnings. The remaining ones, where the compiler quietly picks one
+//! option over another, are only reported with `--warn-inconsistent-option-combinations`. Every
+//! report explains why the options don't go together.
+
+use crate::config::{Lto, LtoCli, SymbolManglingVersion};
+use crate::Session;
+
+use rustc_target::spec::PanicStrategy;
+
+/// How an [`Inconsistency`] is reported.
+#[derive(Clone, Copy, PartialEq)]
+enum Severity {
+    /// The options can't be used together.
+    Error,
+    /// One of the options doesn't do what it says.
+    Warning,
+    /// One of the options is silently overridden by another, which is only reported with
+    /// `--warn-inconsistent-option-combinations`.
+    OnRequest,
+}
+
+/// An inconsistent combination of options.
+struct Inconsistency {
+    severity: Severity,
+    message: String,
+    explanation: &'static str,
+}
+
+impl Inconsistency {
+    fn new(severity: Severity, message: impl Into<String>, explanation: &'static str) -> Self {
+        Inconsistency { severity, message: message.into(), explanation }
+    }
+}
+
+/// The checks, each looking at one combination of options.
+const CHECKS: &[fn(&Session) -> Option<Inconsistency>] = &[
+    lto_without_embedded_bitcode,
+    linker_plugin_lto_with_prefer_dynamic,
+    instrument_coverage_with_legacy_mangling,
+    thin_lto_with_single_codegen_unit,
+    lto_disabled_on_target_requiring_lto,
+    unwind_tables_disabled_with_unwinding,
+    incremental_with_fat_lto,
+];
+
+/// Reports the inconsistent combinations of options of `sess`.
+crate fn check_option_combinations(sess: &Session) {
+    let report_on_request = sess.opts.warn_inconsistent_option_combinations;
+    for inconsistency in CHECKS.iter().filter_map(|check| check(sess)) {
+        let mut diag = match inconsistency.severity {
+            Severity::Error => sess.struct_err(&inconsistency.message),
+            Severity::Warning => sess.struct_warn(&inconsistency.message),
+            Severity::OnRequest if report_on_request => sess.struct_warn(&inconsistency.message),
+            Severity::OnRequest => continue,
+        };
+        diag.note(inconsistency.explanation).emit();
+    }
+}
+
+fn lto_without_embedded_bitcode(sess: &Session) -> Option<Inconsistency> {
+    let lto =
+        matches!(sess.opts.cg.lto, LtoCli::Yes | LtoCli::NoParam | LtoCli::Thin | LtoCli::Fat);
+    (lto && !sess.opts.cg.embed_bitcode).then(|| {
+        Inconsistency::new(
+            Severity::Error,
+            "options `-C embed-bitcode=no` and `-C lto` are incompatible",
+            "LTO optimizes the LLVM bitcode of the crates together, which `-C embed-bitcode=no` \
+            leaves out of their rlibs",
+        )
+    })
+}
+
+fn linker_plugin_lto_with_prefer_dynamic(sess: &Session) -> Option<Inconsistency> {
+    let cg = &sess.opts.cg;
+    (cg.linker_plugin_lto.enabled() && cg.prefer_dynamic && sess.target.is_like_windows).then(
+        || {
+            Inconsistency::new(
+                Severity::Error,
+                "Linker plugin based LTO is not supported together with `-C prefer-dynamic` \
+                when targeting Windows-like targets",
+                "the `__imp_` symbols generated for dynamic linking confuse LLD when it merges \
+                bitcode during ThinLTO",
+            )
+        },
+    )
+}
+
+fn instrument_coverage_with_legacy_mangling(sess: &Session) -> Option<Inconsistency> {
+    let legacy =
+        sess.opts.debugging_opts.symbol_mangling_version == Some(SymbolManglingVersion::Legacy);
+    (sess.instrument_coverage() && legacy).then(|| {
+        Inconsistency::new(
+            Severity::Warning,
+            "-Z instrument-coverage requires symbol mangling version `v0`, \
+            but `-Z symbol-mangling-version=legacy` was specified",
+            "coverage tools match functions across runs by their mangled names, which are only \
+            consistent and reversible with `v0` mangling",
+        )
+    })
+}
+
+fn thin_lto_with_single_codegen_unit(sess: &Session) -> Option<Inconsistency> {
+    (sess.opts.cg.lto == LtoCli::Thin && sess.opts.cli_forced_thinlto_off).then(|| {
+        Inconsistency::new(
+            Severity::OnRequest,
+            "`-C lto=thin` is overridden to fat LTO",
+            "emitting LLVM IR, assembly or bitcode forces a single codegen unit, which ThinLTO \
+            can't work with",
+        )
+    })
+}
+
+fn lto_disabled_on_target_requiring_lto(sess: &Session) -> Option<Inconsistency> {
+    (sess.opts.cg.lto == LtoCli::No && sess.target.requires_lto).then(|| {
+        Inconsistency::new(
+            Severity::OnRequest,
+            format!("`-C lto=no` is overridden to fat LTO on `{}`", sess.opts.target_triple),
+            "the target requires LTO to produce working code",
+        )
+    })
+}
+
+fn unwind_tables_disabled_with_unwinding(sess: &Session) -> Option<Inconsistency> {
+    let unwinding = sess.panic_strategy() == PanicStrategy::Unwind;
+    // Targets that require unwind tables already reject `-C force-unwind-tables=no`.
+    let disabled = sess.opts.cg.force_unwind_tables == Some(false) && !sess.target.requires_uwtable;
+    (unwinding && disabled).then(|| {
+        Inconsistency::new(
+            Severity::OnRequest,
+            "`-C force-unwind-tables=no` has little effect with `-C panic=unwind`",
+            "LLVM still emits unwind tables for every function that can unwind, which is most \
+            of them when panics unwind",
+        )
+    })
+}
+
+fn incremental_with_fat_lto(sess: &Session) -> Option<Inconsistency> {
+    (sess.opts.incremental.is_some() && sess.lto() == Lto::Fat).then(|| {
+        Inconsistency::new(
+            Severity::OnRequest,
+            "`-C incremental` can reuse little of the previous compilation with fat LTO",
+            "fat LTO optimizes all codegen units together, so a change anywhere redoes the \
+            optimization and code generation of the whole crate",
+        )
+    })
+}
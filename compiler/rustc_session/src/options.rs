@@ -21,6 +21,7 @@
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::str;
+use std::time::Duration;
 
 macro_rules! insert {
     ($opt_name:ident, $opt_expr:expr, $sub_hashes:expr) => {
@@ -108,6 +109,37 @@ pub fn mir_opt_level(&self) -> usize {
             .unwrap_or_else(|| if self.optimize != OptLevel::No { 2 } else { 1 })
     }
 
+    /// Whether MIR has retag statements, as `-Z mir-emit-retag` or the aliasing model of
+    /// `-Z aliasing-model` ask.
+    pub fn mir_emit_retag(&self) -> bool {
+        self.retag_granularity().is_some()
+    }
+
+    /// Which retag statements MIR has, if any. The granularity of `-Z mir-emit-retag` wins over
+    /// the full retags of the aliasing model of `-Z aliasing-model`.
+    pub fn retag_granularity(&self) -> Option<RetagGranularity> {
+        self.debugging_opts.mir_emit_retag.or_else(|| {
+            let model = self.debugging_opts.aliasing_model?;
+            model.emits_retags().then(|| RetagGranularity::Full)
+        })
+    }
+
+    /// Whether mutable references are marked `noalias`, as `-Z mutable-noalias` or else the
+    /// aliasing model of `-Z aliasing-model` ask.
+    pub fn mutable_noalias(&self) -> bool {
+        self.debugging_opts.mutable_noalias.unwrap_or_else(|| {
+            self.debugging_opts.aliasing_model.map_or(true, |model| model.mutable_noalias())
+        })
+    }
+
+    /// The items whose optimized MIR is encoded even when other crates don't need it, as
+    /// `-Z encode-mir-for` or else `-Z always-encode-mir` ask.
+    pub fn encode_mir_for(&self) -> Option<EncodeMirFor> {
+        self.debugging_opts
+            .encode_mir_for
+            .or_else(|| self.debugging_opts.always_encode_mir.then(|| EncodeMirFor::All))
+    }
+
     pub fn instrument_coverage(&self) -> bool {
         self.debugging_opts.instrument_coverage.unwrap_or(InstrumentCoverage::Off)
             != InstrumentCoverage::Off
@@ -153,13 +185,18 @@ pub struct Options {
         /// The crate config requested for the session, which may be combined
         /// with additional crate configurations during the compile process.
         crate_types: Vec<CrateType> [TRACKED],
+        /// The cfg values loaded from the files given with `--cfg-file`, tracked by value so
+        /// that editing a file invalidates incremental results like changing `--cfg` would.
+        file_cfg: Vec<(String, Option<String>)> [TRACKED],
         optimize: OptLevel [TRACKED],
         /// Include the `debug_assertions` flag in dependency tracking, since it
         /// can influence whether overflow checks are done or not.
         debug_assertions: bool [TRACKED],
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
+        lint_config: Vec<(String, String, String)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        lint_group_caps: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -178,6 +215,14 @@ pub struct Options {
 
         debugging_opts: DebuggingOptions [SUBSTRUCT],
         prints: Vec<PrintRequest> [UNTRACKED],
+        /// The `--print` requests the compiler doesn't know, in the order they were given.
+        /// They are left to the driver, which may have registered handlers for them.
+        custom_prints: Vec<String> [UNTRACKED],
+        /// Whether `prints` are printed as JSON, with `--print-json`.
+        print_json: bool [UNTRACKED],
+        /// Whether option combinations where one option silently overrides another are
+        /// reported, with `--warn-inconsistent-option-combinations`.
+        warn_inconsistent_option_combinations: bool [UNTRACKED],
         /// Determines which borrow checker(s) to run. This is the parsed, sanitized
         /// version of `debugging_opts.borrowck`, which is just a plain string.
         borrowck_mode: BorrowckMode [UNTRACKED],
@@ -185,6 +230,9 @@ pub struct Options {
         externs: Externs [UNTRACKED],
         extern_dep_specs: ExternDepSpecs [UNTRACKED],
         crate_name: Option<String> [TRACKED],
+        /// The version of the crate being built, given with `--crate-version`, which is recorded
+        /// in its metadata.
+        crate_version: Option<String> [TRACKED],
         /// An optional name to use as the crate for std during std injection,
         /// written `extern crate name as std`. Defaults to `std`. Used by
         /// out-of-tree drivers.
@@ -231,6 +279,10 @@ pub struct Options {
         /// `true` if we're emitting a JSON job containg a future-incompat report for lints
         json_future_incompat: bool [TRACKED],
 
+        /// `true` if we're emitting JSON blobs marking the start and end of
+        /// each phase of the compilation.
+        json_timings: bool [UNTRACKED],
+
         pretty: Option<PpMode> [UNTRACKED],
 
         /// The (potentially remapped) working directory
@@ -246,13 +298,21 @@ pub struct Options {
 /// necessary code. The main gotcha of this macro is the `cgsetters` module which is a bunch of
 /// generated code to parse an option into its respective field in the struct. There are a few
 /// hand-written parsers for parsing specific types of values in this module.
+///
+/// Each option names the `OptionCategory` it is listed under by `-C help` and `-Z help`, and
+/// can end with a `default: "..."` shown there when its description doesn't mention it.
 macro_rules! options {
+    (@default) => (None);
+    (@default $default:literal) => (Some($default));
+
     ($struct_name:ident, $stat:ident, $optmod:ident, $prefix:expr, $outputname:expr,
      $($( #[$attr:meta] )* $opt:ident : $t:ty = (
         $init:expr,
         $parse:ident,
         [$dep_tracking_marker:ident],
-        $desc:expr)
+        $category:ident,
+        $desc:expr
+        $(, default: $default:literal)?)
      ),* ,) =>
 (
     #[derive(Clone)]
@@ -292,7 +352,14 @@ fn dep_tracking_hash(&self, for_crate_hash: bool, error_format: ErrorOutputType)
     }
 
     pub const $stat: OptionDescrs<$struct_name> =
-        &[ $( (stringify!($opt), $optmod::$opt, desc::$parse, $desc) ),* ];
+        &[ $( (
+            stringify!($opt),
+            $optmod::$opt,
+            desc::$parse,
+            $desc,
+            OptionCategory::$category,
+            options!(@default $($default)?),
+        ) ),* ];
 
     mod $optmod {
     $(
@@ -318,8 +385,65 @@ macro_rules! redirect_field {
     };
 }
 
+/// The groups `-C help` and `-Z help` list options in, in the order they are listed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OptionCategory {
+    CodeGeneration,
+    DebugInfo,
+    DebuggingOutput,
+    Diagnostics,
+    Incremental,
+    Instrumentation,
+    Linking,
+    Mir,
+    ProcMacros,
+    Profiling,
+    Other,
+}
+
+impl OptionCategory {
+    pub const ALL: &'static [OptionCategory] = &[
+        OptionCategory::CodeGeneration,
+        OptionCategory::DebugInfo,
+        OptionCategory::DebuggingOutput,
+        OptionCategory::Diagnostics,
+        OptionCategory::Incremental,
+        OptionCategory::Instrumentation,
+        OptionCategory::Linking,
+        OptionCategory::Mir,
+        OptionCategory::ProcMacros,
+        OptionCategory::Profiling,
+        OptionCategory::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OptionCategory::CodeGeneration => "Code generation",
+            OptionCategory::DebugInfo => "Debug info",
+            OptionCategory::DebuggingOutput => "Debugging output",
+            OptionCategory::Diagnostics => "Diagnostics",
+            OptionCategory::Incremental => "Incremental compilation",
+            OptionCategory::Instrumentation => "Instrumentation",
+            OptionCategory::Linking => "Linking",
+            OptionCategory::Mir => "MIR",
+            OptionCategory::ProcMacros => "Proc macros",
+            OptionCategory::Profiling => "Profiling",
+            OptionCategory::Other => "Other",
+        }
+    }
+}
+
 type OptionSetter<O> = fn(&mut O, v: Option<&str>) -> bool;
-type OptionDescrs<O> = &'static [(&'static str, OptionSetter<O>, &'static str, &'static str)];
+/// The name, setter, value description, help text, help category and default of each option.
+/// The default is only given for options whose help text doesn't already mention it.
+type OptionDescrs<O> = &'static [(
+    &'static str,
+    OptionSetter<O>,
+    &'static str,
+    &'static str,
+    OptionCategory,
+    Option<&'static str>,
+)];
 
 fn build_options<O: Default>(
     matches: &getopts::Matches,
@@ -337,7 +461,7 @@ fn build_options<O: Default>(
 
         let option_to_lookup = key.replace('-', "_");
         match descrs.iter().find(|(name, ..)| *name == option_to_lookup) {
-            Some((_, setter, type_desc, _)) => {
+            Some((_, setter, type_desc, ..)) => {
                 if !setter(&mut op, value) {
                     match value {
                         None => early_error(
@@ -372,25 +496,44 @@ mod desc {
     pub const parse_opt_string: &str = parse_string;
     pub const parse_string_push: &str = parse_string;
     pub const parse_opt_pathbuf: &str = "a path";
+    pub const parse_pathbuf_push: &str = parse_opt_pathbuf;
     pub const parse_list: &str = "a space-separated list of strings";
     pub const parse_opt_comma_list: &str = "a comma-separated list of strings";
     pub const parse_number: &str = "a number";
     pub const parse_opt_number: &str = parse_number;
+    pub const parse_duration: &str =
+        "a duration with a unit of `ms`, `s`, `m`, `h` or `d`, such as `90s` or `5m`";
+    pub const parse_seed: &str = "a 64-bit number or `random`";
     pub const parse_threads: &str = parse_number;
     pub const parse_passes: &str = "a space-separated list of passes, or `all`";
     pub const parse_panic_strategy: &str = "either `unwind` or `abort`";
     pub const parse_opt_panic_strategy: &str = parse_panic_strategy;
+    pub const parse_hardening: &str = "one of: `none`, `standard`, or `strict`";
+    pub const parse_plt: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or `auto`";
     pub const parse_relro_level: &str = "one of: `full`, `partial`, or `off`";
-    pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `cfi`, `hwaddress`, `leak`, `memory` or `thread`";
+    pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `cfi`, `hwaddress`, `leak`, `memory`, `safestack` or `thread`";
     pub const parse_sanitizer_memory_track_origins: &str = "0, 1, or 2";
     pub const parse_cfguard: &str =
         "either a boolean (`yes`, `no`, `on`, `off`, etc), `checks`, or `nochecks`";
+    pub const parse_function_return: &str = "either `keep` or `thunk-extern`";
+    pub const parse_retag_granularity: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), \
+        `full`, `coarse`, or `fn-entry-only`";
+    pub const parse_encode_mir_for: &str = "one of: `reachable`, `public`, or `all`";
+    pub const parse_aliasing_model: &str = "one of: `stacked-borrows`, `tree-borrows`, or \
+        `llvm-default`";
+    pub const parse_binary_dep_depinfo: &str =
+        "either a boolean (`yes`, `no`, `on`, `off`, etc), or `hashed`";
     pub const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
     pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavor::one_of();
-    pub const parse_optimization_fuel: &str = "crate=integer";
+    pub const parse_optimization_fuel: &str = "`crate=integer` or `crate=pass:integer`";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_instrument_coverage: &str =
         "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
+    pub const parse_hotpatch: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or the \
+        number of NOPs at the start of each function, optionally followed by a comma and the \
+        number of NOPs before it";
+    pub const parse_instrument_function_hooks: &str =
+        "the symbol of the entry hook, optionally followed by a comma and the exit hook";
     pub const parse_unpretty: &str = "`string` or `string=string`";
     pub const parse_treat_err_as_bug: &str = "either no value or a number bigger than 0";
     pub const parse_lto: &str =
@@ -409,17 +552,43 @@ mod desc {
     pub const parse_code_model: &str = "one of supported code models (`rustc --print code-models`)";
     pub const parse_tls_model: &str = "one of supported TLS models (`rustc --print tls-models`)";
     pub const parse_target_feature: &str = parse_string;
+    pub const parse_incremental_gc: &str = "a comma-separated list of `max-age=<duration>`, \
+        with a unit of `s`, `m`, `h` or `d`, and `max-size=<size>`, with a unit of `K`, `M` or `G`";
+    pub const parse_apple_deployment_target: &str = "a version, as `<major>[.<minor>]`";
     pub const parse_wasi_exec_model: &str = "either `command` or `reactor`";
+    pub const parse_wasi_component: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), \
+        or a comma-separated list of `adapter=<path>` and `world=<path>`";
+    pub const parse_emscripten_flags: &str =
+        "a space-separated list of Emscripten settings, as `KEY=VALUE`, or `KEY` for `KEY=1`";
+    pub const parse_wasm_features: &str = "a comma-separated list of WebAssembly features, each \
+        prefixed with `+` or `-`: `atomics`, `bulk-memory`, `exception-handling`, \
+        `multi-memory`, `multivalue`, `mutable-globals`, `nontrapping-fptoint`, \
+        `reference-types`, `sign-ext`, `simd128` or `tail-call`";
     pub const parse_split_debuginfo: &str =
         "one of supported split-debuginfo modes (`off`, `packed`, or `unpacked`)";
     pub const parse_gcc_ld: &str = "one of: no value, `lld`";
     pub const parse_stack_protector: &str =
         "one of (`none` (default), `basic`, `strong`, or `all`)";
+    pub const parse_stack_protector_guard: &str = "either `tls` or `global`";
+    pub const parse_cgu_name_scheme: &str = "one of: `numbered`, `hashed`, or `item-based`";
+    pub const parse_temps_policy: &str =
+        "one of: `keep-on-error`, `always-keep`, or `always-clean`";
+    pub const parse_proc_macro_sandbox: &str = "one of: `strict`, `warn`, or `off`";
+    pub const parse_panic_abort_tests_format: &str = "either `exit-code` or `json`";
+    pub const parse_report_format: &str = "either `human` or `json`";
+    pub const parse_binary_size_report: &str =
+        "a comma-separated list of `linked` and either `human` or `json`";
+    pub const parse_stop_after: &str = "one of: `parsing`, `expansion`, `analysis`, or `codegen`";
+    pub const parse_doctest_flags: &str = "a space-separated list of `-C` options and lint \
+        flags (`-A`, `-W`, `-D` or `-F`), each written without a space before its value";
 }
 
 mod parse {
     crate use super::*;
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hash};
     use std::str::FromStr;
+    use std::time::SystemTime;
 
     /// This is for boolean options that don't take a value and start with
     /// `no-`. This style of option is deprecated.
@@ -497,6 +666,16 @@ mod parse {
         }
     }
 
+    crate fn parse_pathbuf_push(slot: &mut Vec<PathBuf>, v: Option<&str>) -> bool {
+        match v {
+            Some(s) => {
+                slot.push(PathBuf::from(s));
+                true
+            }
+            None => false,
+        }
+    }
+
     crate fn parse_string_push(slot: &mut Vec<String>, v: Option<&str>) -> bool {
         match v {
             Some(s) => {
@@ -584,6 +763,46 @@ mod parse {
         }
     }
 
+    crate fn parse_duration(slot: &mut Option<Duration>, v: Option<&str>) -> bool {
+        let s = match v {
+            Some(s) => s,
+            None => return false,
+        };
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (value, unit) = s.split_at(split);
+        let value: u64 = match value.parse() {
+            Ok(value) => value,
+            Err(_) => return false,
+        };
+        *slot = match unit {
+            "ms" => Some(Duration::from_millis(value)),
+            "s" => Some(Duration::from_secs(value)),
+            "m" => value.checked_mul(60).map(Duration::from_secs),
+            "h" => value.checked_mul(60 * 60).map(Duration::from_secs),
+            "d" => value.checked_mul(24 * 60 * 60).map(Duration::from_secs),
+            _ => None,
+        };
+        slot.is_some()
+    }
+
+    /// Resolves `random` to a number here, so that the seed that was used is part of the
+    /// options and their hash.
+    crate fn parse_seed(slot: &mut Option<u64>, v: Option<&str>) -> bool {
+        match v {
+            Some("random") => {
+                let mut hasher = RandomState::new().build_hasher();
+                SystemTime::now().hash(&mut hasher);
+                *slot = Some(hasher.finish());
+                true
+            }
+            Some(s) => {
+                *slot = s.parse().ok();
+                slot.is_some()
+            }
+            None => false,
+        }
+    }
+
     crate fn parse_passes(slot: &mut Passes, v: Option<&str>) -> bool {
         match v {
             Some("all") => {
@@ -620,6 +839,33 @@ mod parse {
         true
     }
 
+    crate fn parse_hardening(slot: &mut Option<HardeningProfile>, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("none") => None,
+            Some("standard") => Some(HardeningProfile::Standard),
+            Some("strict") => Some(HardeningProfile::Strict),
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_plt(slot: &mut PltPolicy, v: Option<&str>) -> bool {
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = if bool_arg.unwrap() { PltPolicy::Always } else { PltPolicy::Never };
+                return true;
+            }
+        }
+
+        *slot = match v {
+            None => PltPolicy::Always,
+            Some("auto") => PltPolicy::Auto,
+            Some(_) => return false,
+        };
+        true
+    }
+
     crate fn parse_relro_level(slot: &mut Option<RelroLevel>, v: Option<&str>) -> bool {
         match v {
             Some(s) => match s.parse::<RelroLevel>() {
@@ -641,6 +887,7 @@ mod parse {
                     "memory" => SanitizerSet::MEMORY,
                     "thread" => SanitizerSet::THREAD,
                     "hwaddress" => SanitizerSet::HWADDRESS,
+                    "safestack" => SanitizerSet::SAFESTACK,
                     _ => return false,
                 }
             }
@@ -696,6 +943,74 @@ mod parse {
         true
     }
 
+    crate fn parse_retag_granularity(slot: &mut Option<RetagGranularity>, v: Option<&str>) -> bool {
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = bool_arg.unwrap().then(|| RetagGranularity::Full);
+                return true;
+            }
+        }
+
+        *slot = Some(match v {
+            None | Some("full") => RetagGranularity::Full,
+            Some("coarse") => RetagGranularity::Coarse,
+            Some("fn-entry-only") => RetagGranularity::FnEntryOnly,
+            Some(_) => return false,
+        });
+        true
+    }
+
+    crate fn parse_encode_mir_for(slot: &mut Option<EncodeMirFor>, v: Option<&str>) -> bool {
+        *slot = Some(match v {
+            Some("reachable") => EncodeMirFor::Reachable,
+            Some("public") => EncodeMirFor::Public,
+            Some("all") => EncodeMirFor::All,
+            _ => return false,
+        });
+        true
+    }
+
+    crate fn parse_aliasing_model(slot: &mut Option<AliasingModel>, v: Option<&str>) -> bool {
+        *slot = Some(match v {
+            Some("stacked-borrows") => AliasingModel::StackedBorrows,
+            Some("tree-borrows") => AliasingModel::TreeBorrows,
+            Some("llvm-default") => AliasingModel::LlvmDefault,
+            _ => return false,
+        });
+        true
+    }
+
+    crate fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("keep") => FunctionReturn::Keep,
+            Some("thunk-extern") => FunctionReturn::ThunkExtern,
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_binary_dep_depinfo(slot: &mut BinaryDepDepinfo, v: Option<&str>) -> bool {
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = if bool_arg.unwrap() {
+                    BinaryDepDepinfo::Paths
+                } else {
+                    BinaryDepDepinfo::Disabled
+                };
+                return true;
+            }
+        }
+
+        *slot = match v {
+            None => BinaryDepDepinfo::Paths,
+            Some("hashed") => BinaryDepDepinfo::Hashed,
+            Some(_) => return false,
+        };
+        true
+    }
+
     crate fn parse_linker_flavor(slot: &mut Option<LinkerFlavor>, v: Option<&str>) -> bool {
         match v.and_then(LinkerFlavor::from_str) {
             Some(lf) => *slot = Some(lf),
@@ -704,7 +1019,10 @@ mod parse {
         true
     }
 
-    crate fn parse_optimization_fuel(slot: &mut Option<(String, u64)>, v: Option<&str>) -> bool {
+    crate fn parse_optimization_fuel(
+        slot: &mut Option<(String, Option<String>, u64)>,
+        v: Option<&str>,
+    ) -> bool {
         match v {
             None => false,
             Some(s) => {
@@ -713,11 +1031,16 @@ mod parse {
                     return false;
                 }
                 let crate_name = parts[0].to_string();
-                let fuel = parts[1].parse::<u64>();
+                let (pass, fuel) = match parts[1].split_once(':') {
+                    Some((pass, fuel)) if !pass.is_empty() => (Some(pass.to_string()), fuel),
+                    Some(_) => return false,
+                    None => (None, parts[1]),
+                };
+                let fuel = fuel.parse::<u64>();
                 if fuel.is_err() {
                     return false;
                 }
-                *slot = Some((crate_name, fuel.unwrap()));
+                *slot = Some((crate_name, pass, fuel.unwrap()));
                 true
             }
         }
@@ -760,6 +1083,51 @@ mod parse {
         true
     }
 
+    crate fn parse_hotpatch(slot: &mut Option<Hotpatch>, v: Option<&str>) -> bool {
+        let mut bool_arg = None;
+        if parse_opt_bool(&mut bool_arg, v) {
+            *slot = if bool_arg.unwrap() { Some(Hotpatch::Prologue) } else { None };
+            return true;
+        }
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        let (entry, prefix) = match v.split_once(',') {
+            Some((entry, prefix)) => (entry, prefix),
+            None => (v, "0"),
+        };
+        match (entry.parse(), prefix.parse()) {
+            (Ok(entry), Ok(prefix)) if entry != 0 || prefix != 0 => {
+                *slot = Some(Hotpatch::NopSled { entry, prefix });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    crate fn parse_instrument_function_hooks(
+        slot: &mut Option<InstrumentFunctionHooks>,
+        v: Option<&str>,
+    ) -> bool {
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        let (entry, exit) = match v.split_once(',') {
+            Some((entry, exit)) => (entry, Some(exit)),
+            None => (v, None),
+        };
+        if entry.is_empty() || exit.map_or(false, str::is_empty) {
+            return false;
+        }
+        *slot = Some(InstrumentFunctionHooks {
+            entry: entry.to_string(),
+            exit: exit.map(str::to_string),
+        });
+        true
+    }
+
     crate fn parse_instrument_coverage(
         slot: &mut Option<InstrumentCoverage>,
         v: Option<&str>,
@@ -922,6 +1290,63 @@ mod parse {
         }
     }
 
+    crate fn parse_incremental_gc(slot: &mut Option<IncrementalGc>, v: Option<&str>) -> bool {
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        let mut policy = IncrementalGc::default();
+        for part in v.split(',') {
+            match part.split_once('=') {
+                Some(("max-age", age)) => {
+                    if !parse_duration(&mut policy.max_age, Some(age)) {
+                        return false;
+                    }
+                }
+                Some(("max-size", size)) => {
+                    let split = size.find(|c: char| !c.is_ascii_digit()).unwrap_or(size.len());
+                    let (value, unit) = size.split_at(split);
+                    let value: u64 = match value.parse() {
+                        Ok(value) => value,
+                        Err(_) => return false,
+                    };
+                    let shift = match unit {
+                        "" => 0,
+                        "K" => 10,
+                        "M" => 20,
+                        "G" => 30,
+                        _ => return false,
+                    };
+                    policy.max_size = value.checked_mul(1 << shift);
+                    if policy.max_size.is_none() {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+        *slot = Some(policy);
+        true
+    }
+
+    crate fn parse_apple_deployment_target(
+        slot: &mut Option<(u32, u32)>,
+        v: Option<&str>,
+    ) -> bool {
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        let (major, minor) = v.split_once('.').unwrap_or((v, "0"));
+        match (major.parse(), minor.parse()) {
+            (Ok(major), Ok(minor)) => {
+                *slot = Some((major, minor));
+                true
+            }
+            _ => false,
+        }
+    }
+
     crate fn parse_wasi_exec_model(slot: &mut Option<WasiExecModel>, v: Option<&str>) -> bool {
         match v {
             Some("command") => *slot = Some(WasiExecModel::Command),
@@ -931,6 +1356,66 @@ mod parse {
         true
     }
 
+    crate fn parse_wasi_component(slot: &mut Option<WasiComponent>, v: Option<&str>) -> bool {
+        let mut enabled = false;
+        if parse_bool(&mut enabled, v) {
+            *slot = if enabled { Some(WasiComponent::default()) } else { None };
+            return true;
+        }
+        let mut component = WasiComponent::default();
+        for part in v.unwrap().split(',') {
+            match part.split_once('=') {
+                Some(("adapter", path)) if !path.is_empty() => {
+                    component.adapter = Some(PathBuf::from(path))
+                }
+                Some(("world", path)) if !path.is_empty() => {
+                    component.world = Some(PathBuf::from(path))
+                }
+                _ => return false,
+            }
+        }
+        *slot = Some(component);
+        true
+    }
+
+    crate fn parse_emscripten_flags(slot: &mut Vec<(String, String)>, v: Option<&str>) -> bool {
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        for setting in v.split_whitespace() {
+            let (key, value) = setting.split_once('=').unwrap_or((setting, "1"));
+            if key.is_empty()
+                || !key.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+            {
+                return false;
+            }
+            slot.push((key.to_string(), value.to_string()));
+        }
+        true
+    }
+
+    crate fn parse_wasm_features(slot: &mut Vec<(WasmFeature, bool)>, v: Option<&str>) -> bool {
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        for feature in v.split(',') {
+            let (enabled, name) = match feature.strip_prefix('+') {
+                Some(name) => (true, name),
+                None => match feature.strip_prefix('-') {
+                    Some(name) => (false, name),
+                    None => return false,
+                },
+            };
+            match WasmFeature::ALL.iter().find(|feature| feature.name() == name) {
+                Some(&feature) => slot.push((feature, enabled)),
+                None => return false,
+            }
+        }
+        true
+    }
+
     crate fn parse_split_debuginfo(slot: &mut Option<SplitDebuginfo>, v: Option<&str>) -> bool {
         match v.and_then(|s| SplitDebuginfo::from_str(s).ok()) {
             Some(e) => *slot = Some(e),
@@ -955,6 +1440,125 @@ mod parse {
         }
         true
     }
+
+    crate fn parse_stack_protector_guard(
+        slot: &mut Option<StackProtectorGuard>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            Some("tls") => Some(StackProtectorGuard::Tls),
+            Some("global") => Some(StackProtectorGuard::Global),
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_cgu_name_scheme(slot: &mut Option<CguNameScheme>, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("numbered") => Some(CguNameScheme::Numbered),
+            Some("hashed") => Some(CguNameScheme::Hashed),
+            Some("item-based") => Some(CguNameScheme::ItemBased),
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_temps_policy(slot: &mut Option<TempsPolicy>, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("keep-on-error") => Some(TempsPolicy::KeepOnError),
+            Some("always-keep") => Some(TempsPolicy::AlwaysKeep),
+            Some("always-clean") => Some(TempsPolicy::AlwaysClean),
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_report_format(slot: &mut Option<ReportFormat>, v: Option<&str>) -> bool {
+        *slot = match v {
+            None | Some("human") => Some(ReportFormat::Human),
+            Some("json") => Some(ReportFormat::Json),
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_binary_size_report(
+        slot: &mut Option<BinarySizeReport>,
+        v: Option<&str>,
+    ) -> bool {
+        let mut report = BinarySizeReport { linked: false, format: ReportFormat::Human };
+        for part in v.into_iter().flat_map(|v| v.split(',')) {
+            match part {
+                "linked" => report.linked = true,
+                "human" => report.format = ReportFormat::Human,
+                "json" => report.format = ReportFormat::Json,
+                _ => return false,
+            }
+        }
+        *slot = Some(report);
+        true
+    }
+
+    crate fn parse_panic_abort_tests_format(
+        slot: &mut PanicAbortTestsFormat,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            Some("exit-code") => PanicAbortTestsFormat::ExitCode,
+            Some("json") => PanicAbortTestsFormat::Json,
+            _ => return false,
+        };
+        true
+    }
+
+    crate fn parse_stop_after(slot: &mut Option<StopAfter>, v: Option<&str>) -> bool {
+        *slot = Some(match v {
+            Some("parsing") => StopAfter::Parsing,
+            Some("expansion") => StopAfter::Expansion,
+            Some("analysis") => StopAfter::Analysis,
+            Some("codegen") => StopAfter::Codegen,
+            _ => return false,
+        });
+        true
+    }
+
+    crate fn parse_doctest_flags(slot: &mut Vec<DoctestFlag>, v: Option<&str>) -> bool {
+        let v = match v {
+            Some(v) => v,
+            None => return false,
+        };
+        for flag in v.split_whitespace() {
+            let (kind, value) = match (flag.get(..2), flag.get(2..)) {
+                (Some(kind), Some(value)) if !value.is_empty() => (kind, value.to_string()),
+                _ => return false,
+            };
+            slot.push(match kind {
+                "-C" => {
+                    let name = value.split('=').next().unwrap().replace('-', "_");
+                    if !CG_OPTIONS.iter().any(|&(option, ..)| option == name) {
+                        return false;
+                    }
+                    DoctestFlag::Codegen(value)
+                }
+                "-A" => DoctestFlag::Lint(lint::Allow, value),
+                "-W" => DoctestFlag::Lint(lint::Warn, value),
+                "-D" => DoctestFlag::Lint(lint::Deny, value),
+                "-F" => DoctestFlag::Lint(lint::Forbid, value),
+                _ => return false,
+            });
+        }
+        true
+    }
+
+    crate fn parse_proc_macro_sandbox(slot: &mut ProcMacroSandbox, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("strict") => ProcMacroSandbox::Strict,
+            Some("warn") => ProcMacroSandbox::Warn,
+            Some("off") => ProcMacroSandbox::Off,
+            _ => return false,
+        };
+        true
+    }
 }
 
 options! {
@@ -966,98 +1570,114 @@ mod parse {
     // - compiler/rustc_interface/src/tests.rs
     // - src/doc/rustc/src/codegen-options/index.md
 
-    ar: String = (String::new(), parse_string, [UNTRACKED],
+    ar: String = (String::new(), parse_string, [UNTRACKED], Other,
         "this option is deprecated and does nothing"),
-    code_model: Option<CodeModel> = (None, parse_code_model, [TRACKED],
+    code_model: Option<CodeModel> = (None, parse_code_model, [TRACKED], CodeGeneration,
         "choose the code model to use (`rustc --print code-models` for details)"),
-    codegen_units: Option<usize> = (None, parse_opt_number, [UNTRACKED],
+    codegen_units: Option<usize> = (None, parse_opt_number, [UNTRACKED], CodeGeneration,
         "divide crate into N units to optimize in parallel"),
-    control_flow_guard: CFGuard = (CFGuard::Disabled, parse_cfguard, [TRACKED],
+    control_flow_guard: CFGuard = (CFGuard::Disabled, parse_cfguard, [TRACKED], CodeGeneration,
         "use Windows Control Flow Guard (default: no)"),
-    debug_assertions: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    debug_assertions: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "explicitly enable the `cfg(debug_assertions)` directive"),
-    debuginfo: usize = (0, parse_number, [TRACKED],
+    debuginfo: usize = (0, parse_number, [TRACKED], DebugInfo,
         "debug info emission level (0 = no debug info, 1 = line tables only, \
         2 = full debug info with variable and type information; default: 0)"),
-    default_linker_libraries: bool = (false, parse_bool, [UNTRACKED],
+    default_linker_libraries: bool = (false, parse_bool, [UNTRACKED], Linking,
         "allow the linker to link its default libraries (default: no)"),
-    embed_bitcode: bool = (true, parse_bool, [TRACKED],
+    embed_bitcode: bool = (true, parse_bool, [TRACKED], CodeGeneration,
         "emit bitcode in rlibs (default: yes)"),
-    extra_filename: String = (String::new(), parse_string, [UNTRACKED],
+    extra_filename: String = (String::new(), parse_string, [UNTRACKED], Other,
         "extra data to put in each output filename"),
-    force_frame_pointers: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    force_frame_pointers: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "force use of the frame pointers"),
-    force_unwind_tables: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    force_unwind_tables: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "force use of unwind tables"),
-    incremental: Option<String> = (None, parse_opt_string, [UNTRACKED],
+    incremental: Option<String> = (None, parse_opt_string, [UNTRACKED], Incremental,
         "enable incremental compilation"),
-    inline_threshold: Option<u32> = (None, parse_opt_number, [TRACKED],
+    inline_threshold: Option<u32> = (None, parse_opt_number, [TRACKED], CodeGeneration,
         "set the threshold for inlining a function"),
-    link_arg: (/* redirected to link_args */) = ((), parse_string_push, [UNTRACKED],
+    instrument_functions: bool = (false, parse_bool, [TRACKED], Instrumentation,
+        "instrument function entries and exits with calls to `__cyg_profile_func_enter` \
+        and `__cyg_profile_func_exit` (default: no)"),
+    instrument_functions_exclude: Vec<String> = (Vec::new(), parse_list, [TRACKED], Instrumentation,
+        "a space-separated list of paths of functions, or of modules whose functions, \
+        to leave out of `-C instrument-functions`"),
+    link_arg: (/* redirected to link_args */) = ((), parse_string_push, [UNTRACKED], Linking,
         "a single extra argument to append to the linker invocation (can be used several times)"),
-    link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED],
+    link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED], Linking,
         "extra arguments to append to the linker invocation (space separated)"),
-    link_dead_code: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    link_dead_code: Option<bool> = (None, parse_opt_bool, [TRACKED], Linking,
         "keep dead code at link time (useful for code coverage) (default: no)"),
-    link_self_contained: Option<bool> = (None, parse_opt_bool, [UNTRACKED],
+    link_self_contained: Option<bool> = (None, parse_opt_bool, [UNTRACKED], Linking,
         "control whether to link Rust provided C objects/libraries or rely
         on C toolchain installed in the system"),
-    linker: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+    link_time_gc_sections: Option<bool> = (None, parse_opt_bool, [TRACKED], Linking,
+        "force or forbid the garbage collection of unused sections by the linker \
+        (default: yes, unless `-C link-dead-code`)"),
+    linker: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], Linking,
         "system linker to link outputs with"),
-    linker_flavor: Option<LinkerFlavor> = (None, parse_linker_flavor, [UNTRACKED],
+    linker_flavor: Option<LinkerFlavor> = (None, parse_linker_flavor, [UNTRACKED], Linking,
         "linker flavor"),
     linker_plugin_lto: LinkerPluginLto = (LinkerPluginLto::Disabled,
-        parse_linker_plugin_lto, [TRACKED],
+        parse_linker_plugin_lto, [TRACKED], Linking,
         "generate build artifacts that are compatible with linker-based LTO"),
-    llvm_args: Vec<String> = (Vec::new(), parse_list, [TRACKED],
+    llvm_args: Vec<String> = (Vec::new(), parse_list, [TRACKED], CodeGeneration,
         "a list of arguments to pass to LLVM (space separated)"),
-    lto: LtoCli = (LtoCli::Unspecified, parse_lto, [TRACKED],
+    lto: LtoCli = (LtoCli::Unspecified, parse_lto, [TRACKED], CodeGeneration,
         "perform LLVM link-time optimizations"),
-    metadata: Vec<String> = (Vec::new(), parse_list, [TRACKED],
+    metadata: Vec<String> = (Vec::new(), parse_list, [TRACKED], Other,
         "metadata to mangle symbol names with"),
-    no_prepopulate_passes: bool = (false, parse_no_flag, [TRACKED],
-        "give an empty list of passes to the pass manager"),
-    no_redzone: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    no_prepopulate_passes: bool = (false, parse_no_flag, [TRACKED], CodeGeneration,
+        "give an empty list of passes to the pass manager", default: "no"),
+    no_redzone: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "disable the use of the redzone"),
-    no_stack_check: bool = (false, parse_no_flag, [UNTRACKED],
+    no_stack_check: bool = (false, parse_no_flag, [UNTRACKED], Other,
         "this option is deprecated and does nothing"),
-    no_vectorize_loops: bool = (false, parse_no_flag, [TRACKED],
-        "disable loop vectorization optimization passes"),
-    no_vectorize_slp: bool = (false, parse_no_flag, [TRACKED],
-        "disable LLVM's SLP vectorization pass"),
-    opt_level: String = ("0".to_string(), parse_string, [TRACKED],
+    no_vectorize_loops: bool = (false, parse_no_flag, [TRACKED], CodeGeneration,
+        "disable loop vectorization optimization passes", default: "no"),
+    no_vectorize_slp: bool = (false, parse_no_flag, [TRACKED], CodeGeneration,
+        "disable LLVM's SLP vectorization pass", default: "no"),
+    opt_level: String = ("0".to_string(), parse_string, [TRACKED], CodeGeneration,
         "optimization level (0-3, s, or z; default: 0)"),
-    overflow_checks: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    overflow_checks: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "use overflow checks for integer arithmetic"),
-    panic: Option<PanicStrategy> = (None, parse_opt_panic_strategy, [TRACKED],
+    panic: Option<PanicStrategy> = (None, parse_opt_panic_strategy, [TRACKED], CodeGeneration,
         "panic strategy to compile crate with"),
-    passes: Vec<String> = (Vec::new(), parse_list, [TRACKED],
+    passes: Vec<String> = (Vec::new(), parse_list, [TRACKED], CodeGeneration,
         "a list of extra LLVM passes to run (space separated)"),
-    prefer_dynamic: bool = (false, parse_bool, [TRACKED],
+    prefer_dynamic: bool = (false, parse_bool, [TRACKED], Linking,
         "prefer dynamic linking to static linking (default: no)"),
     profile_generate: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
-        parse_switch_with_opt_path, [TRACKED],
+        parse_switch_with_opt_path, [TRACKED], Instrumentation,
         "compile the program with profiling instrumentation"),
-    profile_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+    profile_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Instrumentation,
         "use the given `.profdata` file for profile-guided optimization"),
-    relocation_model: Option<RelocModel> = (None, parse_relocation_model, [TRACKED],
+    relocation_model: Option<RelocModel> = (None, parse_relocation_model, [TRACKED], CodeGeneration,
         "control generation of position-independent code (PIC) \
         (`rustc --print relocation-models` for details)"),
-    remark: Passes = (Passes::Some(Vec::new()), parse_passes, [UNTRACKED],
+    relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED], Linking,
+        "choose which RELRO level to use for ELF files (`full`, `partial` or `off`, \
+        default: the target's)"),
+    remark: Passes = (Passes::Some(Vec::new()), parse_passes, [UNTRACKED], CodeGeneration,
         "print remarks for these optimization passes (space separated, or \"all\")"),
-    rpath: bool = (false, parse_bool, [UNTRACKED],
+    rpath: bool = (false, parse_bool, [UNTRACKED], Linking,
         "set rpath values in libs/exes (default: no)"),
-    save_temps: bool = (false, parse_bool, [UNTRACKED],
+    save_temps: bool = (false, parse_bool, [UNTRACKED], Other,
         "save all temporary output files during compilation (default: no)"),
-    soft_float: bool = (false, parse_bool, [TRACKED],
+    soft_float: bool = (false, parse_bool, [TRACKED], CodeGeneration,
         "use soft float ABI (*eabihf targets only) (default: no)"),
-    split_debuginfo: Option<SplitDebuginfo> = (None, parse_split_debuginfo, [TRACKED],
+    split_debuginfo: Option<SplitDebuginfo> = (None, parse_split_debuginfo, [TRACKED], DebugInfo,
         "how to handle split-debuginfo, a platform-specific option"),
-    strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
+    split_lto_unit: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
+        "split the LTO unit into a regular and a ThinLTO part, which the type metadata of \
+        control-flow integrity needs across crates and languages \
+        (default: yes with `-Z sanitizer=cfi`, no otherwise)"),
+    strip: Strip = (Strip::None, parse_strip, [UNTRACKED], Linking,
         "tell the linker which information to strip (`none` (default), `debuginfo` or `symbols`)"),
-    target_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
+    target_cpu: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
         "select target processor (`rustc --print target-cpus` for details)"),
-    target_feature: String = (String::new(), parse_target_feature, [TRACKED],
+    target_feature: String = (String::new(), parse_target_feature, [TRACKED], CodeGeneration,
         "target specific attributes. (`rustc --print target-features` for details). \
         This feature is unsafe."),
 
@@ -1076,125 +1696,224 @@ mod parse {
     // If you add a new option, please update:
     // - compiler/rustc_interface/src/tests.rs
 
-    allow_features: Option<Vec<String>> = (None, parse_opt_comma_list, [TRACKED],
+    aliasing_model: Option<AliasingModel> = (None, parse_aliasing_model, [TRACKED], Other,
+        "the aliasing model that decides whether MIR has retags and which references are marked \
+        `noalias`: `stacked-borrows`, `tree-borrows`, or `llvm-default` \
+        (default: noalias on mutable references and no retags)"),
+    allow_features: Option<Vec<String>> = (None, parse_opt_comma_list, [TRACKED], Other,
         "only allow the listed language features to be enabled in code (space separated)"),
-    always_encode_mir: bool = (false, parse_bool, [TRACKED],
+    always_encode_mir: bool = (false, parse_bool, [TRACKED], Mir,
         "encode MIR of all functions into the crate metadata (default: no)"),
-    assume_incomplete_release: bool = (false, parse_bool, [TRACKED],
+    android_api_level: Option<u32> = (None, parse_opt_number, [TRACKED], Linking,
+        "the minimum Android API level to support, which selects the NDK libraries to link \
+        against and is exposed as `cfg(target_android_api_level)`"),
+    annotate_llvm_ir: bool = (false, parse_bool, [TRACKED], CodeGeneration,
+        "annotate functions in emitted LLVM IR with their Rust item path, span and \
+        codegen unit (default: no)"),
+    apple_deployment_target: Option<(u32, u32)> = (None, parse_apple_deployment_target, [TRACKED],
+        Linking,
+        "the oldest macOS or iOS version to support, overriding `MACOSX_DEPLOYMENT_TARGET` and \
+        `IPHONEOS_DEPLOYMENT_TARGET`"),
+    assume_incomplete_release: bool = (false, parse_bool, [TRACKED], Other,
         "make cfg(version) treat the current version as incomplete (default: no)"),
-    asm_comments: bool = (false, parse_bool, [TRACKED],
+    asm_comments: bool = (false, parse_bool, [TRACKED], CodeGeneration,
         "generate comments into the assembly (may change behavior) (default: no)"),
-    assert_incr_state: Option<String> = (None, parse_opt_string, [UNTRACKED],
+    assert_incr_state: Option<String> = (None, parse_opt_string, [UNTRACKED], Incremental,
         "assert that the incremental cache is in given state: \
          either `loaded` or `not-loaded`."),
-    ast_json: bool = (false, parse_bool, [UNTRACKED],
+    assert_module_determinism: bool = (false, parse_bool, [TRACKED], Other,
+        "codegen every codegen unit twice and fail if the LLVM IR of both codegens differs \
+        (default: no)"),
+    ast_json: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "print the AST as JSON and halt (default: no)"),
-    ast_json_noexpand: bool = (false, parse_bool, [UNTRACKED],
+    ast_json_noexpand: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "print the pre-expansion AST as JSON and halt (default: no)"),
-    binary_dep_depinfo: bool = (false, parse_bool, [TRACKED],
-        "include artifacts (sysroot, crate dependencies) used during compilation in dep-info \
-        (default: no)"),
-    borrowck: String = ("migrate".to_string(), parse_string, [UNTRACKED],
+    binary_dep_depinfo: BinaryDepDepinfo = (BinaryDepDepinfo::Disabled, parse_binary_dep_depinfo, [TRACKED],
+        Incremental,
+        "include artifacts (sysroot, crate dependencies) used during compilation in dep-info; \
+        `hashed` also records the SVH and a content hash of each artifact (default: no)"),
+    binary_size_report: Option<BinarySizeReport> = (None, parse_binary_size_report,
+        [UNTRACKED], DebuggingOutput,
+        "print the size of the code and data generated for each function, generic and crate, \
+        or with `linked`, of the linked executable or dynamic library that comes from each \
+        crate and item, as `human` text or `json` (default: no)"),
+    bind_now: Option<bool> = (None, parse_opt_bool, [TRACKED], Linking,
+        "resolve all symbols at load time instead of lazily (`-z now`); only has effect on \
+        targets supporting RELRO (default: yes if full relro is enabled)"),
+    borrowck: String = ("migrate".to_string(), parse_string, [UNTRACKED], Mir,
         "select which borrowck is used (`mir` or `migrate`) (default: `migrate`)"),
-    cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED],
+    cache_proc_macros: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], ProcMacros,
+        "reuse the expansions of proc macros declared `#[proc_macro_pure]` across \
+        sessions, caching them in the given directory"),
+    cgu_name_scheme: Option<CguNameScheme> = (None, parse_cgu_name_scheme, [TRACKED],
+        CodeGeneration,
+        "how to name codegen units and the files emitted for them \
+        (`numbered`, `hashed` or `item-based`) \
+        (default: `numbered`, or `hashed` for incremental builds)"),
+    cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
         "the codegen unit partitioning strategy to use"),
-    chalk: bool = (false, parse_bool, [TRACKED],
-        "enable the experimental Chalk-based trait solving engine"),
-    codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
+    chalk: bool = (false, parse_bool, [TRACKED], Other,
+        "enable the experimental Chalk-based trait solving engine", default: "no"),
+    codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
         "the backend to use"),
-    combine_cgu: bool = (false, parse_bool, [TRACKED],
-        "combine CGUs into a single one"),
-    crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
-        "inject the given attribute in the crate"),
-    debug_info_for_profiling: bool = (false, parse_bool, [TRACKED],
-        "emit discriminators and other data necessary for AutoFDO"),
-    debug_macros: bool = (false, parse_bool, [TRACKED],
+    combine_cgu: bool = (false, parse_bool, [TRACKED], CodeGeneration,
+        "combine CGUs into a single one", default: "no"),
+    crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED], Other,
+        "inject the given attribute in the crate; several attributes may be separated by commas"),
+    data_sections: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
+        "whether each data item should go in its own section \
+        (default: same as `-Z function-sections`)"),
+    dead_code_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], DebuggingOutput,
+        "write the functions and statics of an executable or library that can't be reached from \
+        its entry points or exported symbols to this file, as JSON"),
+    debug_info_for_profiling: bool = (false, parse_bool, [TRACKED], Other,
+        "emit discriminators and other data necessary for AutoFDO", default: "no"),
+    debug_macros: bool = (false, parse_bool, [TRACKED], Other,
         "emit line numbers debug info inside macros (default: no)"),
-    deduplicate_diagnostics: bool = (true, parse_bool, [UNTRACKED],
+    deduplicate_diagnostics: bool = (true, parse_bool, [UNTRACKED], Diagnostics,
         "deduplicate identical diagnostics (default: yes)"),
-    dep_info_omit_d_target: bool = (false, parse_bool, [TRACKED],
+    dep_info_omit_d_target: bool = (false, parse_bool, [TRACKED], Incremental,
         "in dep-info output, omit targets for tracking dependencies of the dep-info files \
         themselves (default: no)"),
-    dep_tasks: bool = (false, parse_bool, [UNTRACKED],
+    dep_tasks: bool = (false, parse_bool, [UNTRACKED], Incremental,
         "print tasks that execute and the color their dep node gets (requires debug build) \
         (default: no)"),
-    dont_buffer_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+    doctest_compile_options: Vec<DoctestFlag> = (Vec::new(), parse_doctest_flags, [UNTRACKED],
+        Other,
+        "codegen and lint flags to build doctests with, after those of the crate, \
+        e.g. `-Copt-level=2 -Dwarnings` (rustdoc only)"),
+    dont_buffer_diagnostics: bool = (false, parse_bool, [UNTRACKED], Diagnostics,
         "emit diagnostics rather than buffering (breaks NLL error downgrading, sorting) \
         (default: no)"),
-    dual_proc_macros: bool = (false, parse_bool, [TRACKED],
+    dual_proc_macros: bool = (false, parse_bool, [TRACKED], ProcMacros,
         "load proc macros for both target and host, but only link to the target (default: no)"),
-    dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
+    dump_dep_graph: bool = (false, parse_bool, [UNTRACKED], Incremental,
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
-    dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
+    dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED], Mir,
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
         `all` matches all passes and functions,
         `foo` matches all passes for functions whose name contains 'foo',
         `foo & ConstProp` only the 'ConstProp' pass for function names containing 'foo',
-        `foo | bar` all passes for function names containing 'foo' or 'bar'."),
-    dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED],
+        `foo | bar` all passes for function names containing 'foo' or 'bar',
+        `foo::*::{closure}` all passes for the closures of the items in module `foo`,
+        `foo & !foo::bar` all passes for function names containing 'foo', except those \
+        containing 'foo::bar'."),
+    dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED], Mir,
         "in addition to `.mir` files, create graphviz `.dot` files with dataflow results \
         (default: no)"),
-    dump_mir_dir: String = ("mir_dump".to_string(), parse_string, [UNTRACKED],
+    dump_mir_dir: String = ("mir_dump".to_string(), parse_string, [UNTRACKED], Mir,
         "the directory the MIR is dumped into (default: `mir_dump`)"),
-    dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
+    dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED], Mir,
         "exclude the pass number when dumping MIR (used in tests) (default: no)"),
-    dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
+    dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED], Mir,
         "in addition to `.mir` files, create graphviz `.dot` files (and with \
         `-Z instrument-coverage`, also create a `.dot` file for the MIR-derived \
         coverage graph) (default: no)"),
-    dump_mir_spanview: Option<MirSpanview> = (None, parse_mir_spanview, [UNTRACKED],
+    dump_mir_spanview: Option<MirSpanview> = (None, parse_mir_spanview, [UNTRACKED], Mir,
         "in addition to `.mir` files, create `.html` files to view spans for \
         all `statement`s (including terminators), only `terminator` spans, or \
         computed `block` spans (one span encompassing a block's terminator and \
         all statements). If `-Z instrument-coverage` is also enabled, create \
         an additional `.html` file showing the computed coverage spans."),
-    emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
+    dump_mono_stats: Option<ReportFormat> = (None, parse_report_format, [UNTRACKED],
+        DebuggingOutput,
+        "print how many times each generic function was instantiated, the estimated size of \
+        its instantiations and the codegen units they were placed in, as a `human` table or \
+        `json` (default: no)"),
+    emit_artifact_manifest: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        DebuggingOutput,
+        "write a JSON manifest of all files created by the compiler, with their sizes and \
+        roles, to the given path"),
+    emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "emit a section containing stack size metadata (default: no)"),
-    fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    encode_mir_for: Option<EncodeMirFor> = (None, parse_encode_mir_for, [TRACKED], Mir,
+        "encode the MIR of more items into the crate metadata, for interpreters like miri: \
+        `reachable` for the public items and the functions they can call, `public` for the \
+        public items, or `all` like `-Z always-encode-mir` (default: only what codegen needs)"),
+    emscripten_flags: Vec<(String, String)> = (Vec::new(), parse_emscripten_flags, [TRACKED],
+        Linking,
+        "Emscripten settings to pass to emcc as `-s KEY=VALUE` when linking, checked against \
+        the settings that Emscripten knows"),
+    external_tool_retries: usize = (0, parse_number, [UNTRACKED], Other,
+        "how many times to retry the linker and other external tools if they fail to start \
+        or time out (default: 0)"),
+    external_tool_timeout: Option<u64> = (None, parse_opt_number, [UNTRACKED], Profiling,
+        "kill the linker and other external tools if they run for longer than this many \
+        seconds (default: no limit)"),
+    fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED], Other,
         "reduce memory use by retaining fewer names within compilation artifacts (LLVM-IR) \
         (default: no)"),
-    force_unstable_if_unmarked: bool = (false, parse_bool, [TRACKED],
+    file_access_log: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], Other,
+        "write a JSON list of every source, include and tracked file read by the \
+        compilation, with content hashes, to the given path"),
+    force_unstable_if_unmarked: bool = (false, parse_bool, [TRACKED], Other,
         "force all crates to be `rustc_private` unstable (default: no)"),
-    fuel: Option<(String, u64)> = (None, parse_optimization_fuel, [TRACKED],
-        "set the optimization fuel quota for a crate"),
-    function_sections: Option<bool> = (None, parse_opt_bool, [TRACKED],
-        "whether each function should go in its own section"),
-    future_incompat_test: bool = (false, parse_bool, [UNTRACKED],
+    fuel: Option<(String, Option<String>, u64)> = (None, parse_optimization_fuel, [TRACKED],
+        CodeGeneration,
+        "set the optimization fuel quota for a crate, or for one optimization pass in it"),
+    fuel_trace: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], CodeGeneration,
+        "write each optimization that used fuel under `-Z fuel` or `-Z print-fuel` to a file"),
+    function_return: FunctionReturn = (FunctionReturn::Keep, parse_function_return, [TRACKED],
+        CodeGeneration,
+        "replace returns with jumps to `__x86_return_thunk` (`thunk-extern`) or keep them \
+        (`keep`, default)"),
+    function_sections: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
+        "whether each function should go in its own section (default: target-dependent)"),
+    future_incompat_test: bool = (false, parse_bool, [UNTRACKED], Other,
         "forces all lints to be future incompatible, used for internal testing (default: no)"),
-    gcc_ld: Option<LdImpl> = (None, parse_gcc_ld, [TRACKED], "implementation of ld used by cc"),
-    graphviz_dark_mode: bool = (false, parse_bool, [UNTRACKED],
+    gcc_ld: Option<LdImpl> = (None, parse_gcc_ld, [TRACKED], Linking,
+        "implementation of ld used by cc"),
+    generate_linker_script_template: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        Linking,
+        "write a template of a linker script for a bare-metal executable, with the sections \
+        used by the crate, to the given path (default: no)"),
+    graphviz_dark_mode: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "use dark-themed colors in graphviz output (default: no)"),
     graphviz_font: String = ("Courier, monospace".to_string(), parse_string, [UNTRACKED],
+        DebuggingOutput,
         "use the given `fontname` in graphviz output; can be overridden by setting \
         environment variable `RUSTC_GRAPHVIZ_FONT` (default: `Courier, monospace`)"),
-    hir_stats: bool = (false, parse_bool, [UNTRACKED],
+    hardening: Option<HardeningProfile> = (None, parse_hardening, [TRACKED], CodeGeneration,
+        "apply a preset of hardening options: full relro, immediate binding, PIE and stack \
+        protection; options passed explicitly take precedence (`none` (default), `standard` \
+        or `strict`)"),
+    hir_stats: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "print some statistics about AST and HIR (default: no)"),
-    human_readable_cgu_names: bool = (false, parse_bool, [TRACKED],
+    hotpatch: Option<Hotpatch> = (None, parse_hotpatch, [TRACKED], Other,
+        "make every function hot-patchable, with the hot-patchable prologue of the target and \
+        the linker flags it needs, or with the given numbers of NOPs at the start of and before \
+        each function (default: no)"),
+    human_readable_cgu_names: bool = (false, parse_bool, [TRACKED], CodeGeneration,
         "generate human-readable, predictable names for codegen units (default: no)"),
-    identify_regions: bool = (false, parse_bool, [UNTRACKED],
+    identify_regions: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
-    incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
+    incremental_gc: Option<IncrementalGc> = (None, parse_incremental_gc, [UNTRACKED], Incremental,
+        "also garbage collect the directories of other crates in the incremental directory, \
+        removing the ones unused for longer than `max-age` and then the least recently used ones \
+        until the directory fits in `max-size`"),
+    incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED], Incremental,
         "ignore spans during ICH computation -- used for testing (default: no)"),
-    incremental_info: bool = (false, parse_bool, [UNTRACKED],
+    incremental_info: bool = (false, parse_bool, [UNTRACKED], Incremental,
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
-    incremental_relative_spans: bool = (false, parse_bool, [TRACKED],
+    incremental_relative_spans: bool = (false, parse_bool, [TRACKED], Incremental,
         "hash spans relative to their parent item for incr. comp. (default: no)"),
-    incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
+    incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED], Incremental,
         "verify incr. comp. hashes of green query instances (default: no)"),
-    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED], Mir,
         "enable MIR inlining (default: no)"),
-    inline_mir_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
+    inline_mir_threshold: Option<usize> = (None, parse_opt_number, [TRACKED], Mir,
         "a default MIR inlining threshold (default: 50)"),
-    inline_mir_hint_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
+    inline_mir_hint_threshold: Option<usize> = (None, parse_opt_number, [TRACKED], Mir,
         "inlining threshold for functions with inline hint (default: 100)"),
-    inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "control whether `#[inline]` functions are in all CGUs"),
-    input_stats: bool = (false, parse_bool, [UNTRACKED],
+    input_stats: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "gather statistics about the input (default: no)"),
     instrument_coverage: Option<InstrumentCoverage> = (None, parse_instrument_coverage, [TRACKED],
+        Instrumentation,
         "instrument the generated code to support LLVM source-based code coverage \
         reports (note, the compiler build config must include `profiler = true`); \
         implies `-Z symbol-mangling-version=v0`. Optional values are:
@@ -1202,217 +1921,361 @@ mod parse {
         `=except-unused-generics`
         `=except-unused-functions`
         `=off` (default)"),
-    instrument_mcount: bool = (false, parse_bool, [TRACKED],
+    instrument_functions: Option<InstrumentFunctionHooks> = (None,
+        parse_instrument_function_hooks, [TRACKED], Instrumentation,
+        "instrument function entries with calls to the given symbol, and function exits with \
+        calls to the optional second one (default: no)"),
+    instrument_functions_exclude: Vec<String> = (Vec::new(), parse_list, [TRACKED], Instrumentation,
+        "a space-separated list of paths of functions or modules, or of names of items, whose \
+        functions `-Z instrument-functions` leaves out"),
+    instrument_functions_include: Vec<String> = (Vec::new(), parse_list, [TRACKED], Instrumentation,
+        "a space-separated list of paths of functions or modules, or of names of items, that \
+        `-Z instrument-functions` only instruments the functions of (default: all functions)"),
+    instrument_functions_post_inline: bool = (true, parse_bool, [TRACKED], Instrumentation,
+        "insert the calls of `-C instrument-functions` and `-Z instrument-functions` after \
+        inlining, so that inlined functions aren't reported, rather than before it \
+        (default: yes)"),
+    instrument_mcount: bool = (false, parse_bool, [TRACKED], Instrumentation,
         "insert function instrument code for mcount-based tracing (default: no)"),
-    keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED],
+    keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED], Other,
         "keep hygiene data after analysis (default: no)"),
-    link_native_libraries: bool = (true, parse_bool, [UNTRACKED],
+    link_native_libraries: bool = (true, parse_bool, [UNTRACKED], Linking,
         "link native libraries in the linker invocation (default: yes)"),
-    link_only: bool = (false, parse_bool, [TRACKED],
+    link_arg_file_threshold: Option<usize> = (None, parse_opt_number, [UNTRACKED], Linking,
+        "pass linker arguments in a response file when the command line would be longer than \
+        this many bytes (default: only when spawning the linker fails, or above about 6k on \
+        Windows)"),
+    layout_seed: Option<u64> = (None, parse_seed, [TRACKED], Other,
+        "seed the field order chosen by `-Z randomize-layout`, or `random` to pick a seed \
+        that is then printed (default: 0)"),
+    link_only: bool = (false, parse_bool, [TRACKED], Linking,
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
-    llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
+    lint_expectations_json: bool = (false, parse_bool, [UNTRACKED], Diagnostics,
+        "print the fulfilled and unfulfilled `#[expect]` lint expectations as JSON on stderr at \
+        the end of the analysis (default: no)"),
+    lint_plugin: Vec<PathBuf> = (Vec::new(), parse_pathbuf_push, [TRACKED], Diagnostics,
+        "load a shared library that registers lints and lint passes with `declare_lint_plugin!` \
+        (may be passed multiple times)"),
+    lint_timings: bool = (false, parse_bool, [UNTRACKED], Profiling,
+        "print the time taken by each lint pass at the end of compilation; implies \
+        `-Z no-interleave-lints` (default: no)"),
+    llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED], CodeGeneration,
         "a list LLVM plugins to enable (space separated)"),
-    llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
+    llvm_time_trace: bool = (false, parse_bool, [UNTRACKED], Profiling,
         "generate JSON tracing data file from LLVM data (default: no)"),
     location_detail: LocationDetail = (LocationDetail::all(), parse_location_detail, [TRACKED],
+        Other,
         "comma seperated list of location details to be tracked when using caller_location \
         valid options are `file`, `line`, and `column` (default: all)"),
-    ls: bool = (false, parse_bool, [UNTRACKED],
+    ls: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "list the symbols defined by a library crate (default: no)"),
-    macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
+    macro_backtrace: bool = (false, parse_bool, [UNTRACKED], Diagnostics,
         "show macro backtraces (default: no)"),
+    macro_stats: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
+        "print the number of invocations of each macro, the number of tokens they produced and \
+        the time taken to expand them at the end of compilation (default: no)"),
+    max_monomorphizations: Option<usize> = (None, parse_opt_number, [TRACKED], Other,
+        "warn about the generic functions instantiated more than this many times, with the \
+        `excessive_monomorphizations` lint (default: no limit)"),
     merge_functions: Option<MergeFunctions> = (None, parse_merge_functions, [TRACKED],
+        CodeGeneration,
         "control the operation of the MergeFunctions LLVM pass, taking \
         the same values as the target option of the same name"),
-    meta_stats: bool = (false, parse_bool, [UNTRACKED],
+    meta_stats: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "gather metadata statistics (default: no)"),
-    mir_emit_retag: bool = (false, parse_bool, [TRACKED],
-        "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0 \
-        (default: no)"),
-    mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
+    mir_emit_retag: Option<RetagGranularity> = (None, parse_retag_granularity, [TRACKED], Mir,
+        "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0; \
+        `coarse` skips the retags of copies and call results, and `fn-entry-only` only retags \
+        function arguments (default: no)"),
+    mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED], Mir,
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
-    move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+    module_timings: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], Profiling,
+        "write to the given file the time spent type checking, borrow checking and generating \
+        the code of each module and item, slowest first (default: no)"),
+    move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED], Other,
         "the size at which the `large_assignments` lint starts to be emitted"),
-    mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "emit noalias metadata for mutable references (default: yes)"),
-    new_llvm_pass_manager: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    new_llvm_pass_manager: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "use new LLVM pass manager (default: no)"),
-    nll_facts: bool = (false, parse_bool, [UNTRACKED],
+    nll_facts: bool = (false, parse_bool, [UNTRACKED], Mir,
         "dump facts from NLL analysis into side files (default: no)"),
-    nll_facts_dir: String = ("nll-facts".to_string(), parse_string, [UNTRACKED],
+    nll_facts_dir: String = ("nll-facts".to_string(), parse_string, [UNTRACKED], Mir,
         "the directory the NLL facts are dumped into (default: `nll-facts`)"),
-    no_analysis: bool = (false, parse_no_flag, [UNTRACKED],
-        "parse and expand the source, but run no analysis"),
-    no_codegen: bool = (false, parse_no_flag, [TRACKED_NO_CRATE_HASH],
-        "run all passes except codegen; no output"),
-    no_generate_arange_section: bool = (false, parse_no_flag, [TRACKED],
-        "omit DWARF address ranges that give faster lookups"),
-    no_interleave_lints: bool = (false, parse_no_flag, [UNTRACKED],
-        "execute lints separately; allows benchmarking individual lints"),
-    no_leak_check: bool = (false, parse_no_flag, [UNTRACKED],
-        "disable the 'leak check' for subtyping; unsound, but useful for tests"),
-    no_link: bool = (false, parse_no_flag, [TRACKED],
-        "compile without linking"),
-    no_parallel_llvm: bool = (false, parse_no_flag, [UNTRACKED],
-        "run LLVM in non-parallel mode (while keeping codegen-units and ThinLTO)"),
-    no_unique_section_names: bool = (false, parse_bool, [TRACKED],
-        "do not use unique names for text and data sections when -Z function-sections is used"),
-    no_profiler_runtime: bool = (false, parse_no_flag, [TRACKED],
-        "prevent automatic injection of the profiler_builtins crate"),
-    normalize_docs: bool = (false, parse_bool, [TRACKED],
-        "normalize associated items in rustdoc when generating documentation"),
-    osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
+    no_analysis: bool = (false, parse_no_flag, [UNTRACKED], Other,
+        "parse and expand the source, but run no analysis (alias of `-Z stop-after=expansion`)",
+        default: "no"),
+    no_codegen: bool = (false, parse_no_flag, [TRACKED_NO_CRATE_HASH], CodeGeneration,
+        "run all passes except codegen; no output (alias of `-Z stop-after=analysis`)",
+        default: "no"),
+    no_generate_arange_section: bool = (false, parse_no_flag, [TRACKED], Other,
+        "omit DWARF address ranges that give faster lookups", default: "no"),
+    no_interleave_lints: bool = (false, parse_no_flag, [UNTRACKED], Other,
+        "execute lints separately; allows benchmarking individual lints", default: "no"),
+    no_leak_check: bool = (false, parse_no_flag, [UNTRACKED], Other,
+        "disable the 'leak check' for subtyping; unsound, but useful for tests", default: "no"),
+    no_link: bool = (false, parse_no_flag, [TRACKED], Linking,
+        "compile without linking", default: "no"),
+    no_parallel_llvm: bool = (false, parse_no_flag, [UNTRACKED], CodeGeneration,
+        "run LLVM in non-parallel mode (while keeping codegen-units and ThinLTO)", default: "no"),
+    no_unique_section_names: bool = (false, parse_bool, [TRACKED], Other,
+        "do not use unique names for text and data sections when -Z function-sections is used",
+        default: "no"),
+    no_profiler_runtime: bool = (false, parse_no_flag, [TRACKED], Profiling,
+        "prevent automatic injection of the profiler_builtins crate", default: "no"),
+    normalize_docs: bool = (false, parse_bool, [TRACKED], Other,
+        "normalize associated items in rustdoc when generating documentation", default: "no"),
+    osx_rpath_install_name: bool = (false, parse_bool, [TRACKED], Linking,
         "pass `-install_name @rpath/...` to the macOS linker (default: no)"),
-    panic_abort_tests: bool = (false, parse_bool, [TRACKED],
+    output_sandbox: Vec<PathBuf> = (Vec::new(), parse_pathbuf_push, [UNTRACKED], Other,
+        "refuse to read or write files outside the given directory; may be passed multiple \
+        times (the sysroot is always readable)"),
+    panic_abort_tests: bool = (false, parse_bool, [TRACKED], CodeGeneration,
         "support compiling tests with panic=abort (default: no)"),
+    panic_abort_tests_env: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
+        "environment variable naming the test a subprocess should run with \
+        `-Z panic-abort-tests` (default: `__RUST_TEST_INVOKE`)"),
+    panic_abort_tests_format: PanicAbortTestsFormat = (PanicAbortTestsFormat::ExitCode,
+        parse_panic_abort_tests_format, [TRACKED], CodeGeneration,
+        "how subprocesses report test results with `-Z panic-abort-tests`: \
+        `exit-code` or `json` (default: `exit-code`)"),
     panic_in_drop: PanicStrategy = (PanicStrategy::Unwind, parse_panic_strategy, [TRACKED],
-        "panic strategy for panics in drops"),
-    parse_only: bool = (false, parse_bool, [UNTRACKED],
-        "parse only; do not compile, assemble, or link (default: no)"),
-    partially_uninit_const_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
+        CodeGeneration,
+        "panic strategy for panics in drops", default: "unwind"),
+    parse_only: bool = (false, parse_bool, [UNTRACKED], Other,
+        "parse only; do not compile, assemble, or link (default: no) \
+        (alias of `-Z stop-after=parsing`)"),
+    partially_uninit_const_threshold: Option<usize> = (None, parse_opt_number, [TRACKED], Other,
         "allow generating const initializers with mixed init/uninit bytes, \
         and set the maximum total size of a const allocation for which this is allowed (default: never)"),
-    perf_stats: bool = (false, parse_bool, [UNTRACKED],
+    perf_stats: bool = (false, parse_bool, [UNTRACKED], Profiling,
         "print some performance-related statistics (default: no)"),
-    pick_stable_methods_before_any_unstable: bool = (true, parse_bool, [TRACKED],
+    pick_stable_methods_before_any_unstable: bool = (true, parse_bool, [TRACKED], Other,
         "try to pick stable methods first before picking any unstable methods (default: yes)"),
-    plt: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    plt: PltPolicy = (PltPolicy::Auto, parse_plt, [TRACKED], Linking,
         "whether to use the PLT when calling into shared libraries;
         only has effect for PIC code on systems with ELF binaries
-        (default: PLT is disabled if full relro is enabled)"),
-    polonius: bool = (false, parse_bool, [TRACKED],
+        (default: `auto`, which disables the PLT if lazy binding is disabled)"),
+    polonius: bool = (false, parse_bool, [TRACKED], Mir,
         "enable polonius-based borrow-checker (default: no)"),
-    polymorphize: bool = (false, parse_bool, [TRACKED],
-          "perform polymorphization analysis"),
+    polymorphize: bool = (false, parse_bool, [TRACKED], CodeGeneration,
+          "perform polymorphization analysis", default: "no"),
+    polymorphize_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], DebuggingOutput,
+        "write the generic parameters found unused by `-Z polymorphize` in each function, and \
+        the instances that were shared as a result, to this file"),
     pre_link_arg: (/* redirected to pre_link_args */) = ((), parse_string_push, [UNTRACKED],
+        Linking,
         "a single extra argument to prepend the linker invocation (can be used several times)"),
-    pre_link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED],
+    pre_link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED], Linking,
         "extra arguments to prepend to the linker invocation (space separated)"),
-    precise_enum_drop_elaboration: bool = (true, parse_bool, [TRACKED],
+    precise_enum_drop_elaboration: bool = (true, parse_bool, [TRACKED], Other,
         "use a more precise version of drop elaboration for matches on enums (default: yes). \
         This results in better codegen, but has caused miscompilations on some tier 2 platforms. \
         See #77382 and #74551."),
-    print_fuel: Option<String> = (None, parse_opt_string, [TRACKED],
+    print_args_json: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
+        "print the command line as JSON to stderr, after expanding `@path` argument files \
+        (default: no)"),
+    print_fuel: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
         "make rustc print the total optimization fuel used by a crate"),
-    print_link_args: bool = (false, parse_bool, [UNTRACKED],
+    print_link_args: bool = (false, parse_bool, [UNTRACKED], Linking,
         "print the arguments passed to the linker (default: no)"),
-    print_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
+    print_llvm_passes: bool = (false, parse_bool, [UNTRACKED], CodeGeneration,
         "print the LLVM optimization passes being run (default: no)"),
-    print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
+    print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED], DebuggingOutput,
         "print the result of the monomorphization collection pass"),
-    print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
+    print_type_sizes: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "print layout information for each type encountered (default: no)"),
-    proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
+    proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED], ProcMacros,
          "show backtraces for panics during proc-macro execution (default: no)"),
-    profile: bool = (false, parse_bool, [TRACKED],
+    proc_macro_sandbox: ProcMacroSandbox = (ProcMacroSandbox::Off, parse_proc_macro_sandbox,
+        [UNTRACKED], ProcMacros,
+        "restrict file, process and network access by proc macros: `strict` denies it, \
+        `warn` reports it (default: `off`)"),
+    profile: bool = (false, parse_bool, [TRACKED], Instrumentation,
         "insert profiling code (default: no)"),
-    profile_closures: bool = (false, parse_no_flag, [UNTRACKED],
-        "profile size of closures"),
-    profile_emit: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+    profile_closures: bool = (false, parse_no_flag, [UNTRACKED], Instrumentation,
+        "profile size of closures", default: "no"),
+    profile_emit: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Instrumentation,
         "file path to emit profiling data at runtime when using 'profile' \
         (default based on relative source path)"),
     profiler_runtime: String = (String::from("profiler_builtins"), parse_string, [TRACKED],
+        Profiling,
         "name of the profiler runtime crate to automatically inject (default: `profiler_builtins`)"),
-    profile_sample_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+    profile_query_keys: Option<String> = (None, parse_opt_string, [UNTRACKED], Instrumentation,
+        "write every key of the given query, with how many times it was a cache hit, loaded \
+        from disk or recomputed, to `<query>.query-keys` in the current directory"),
+    profile_sample_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Instrumentation,
         "use the given `.prof` file for sampled profile-guided optimization (also known as AutoFDO)"),
-    query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
+    query_dep_graph: bool = (false, parse_bool, [UNTRACKED], Incremental,
         "enable queries of the dependency graph for regression testing (default: no)"),
-    query_stats: bool = (false, parse_bool, [UNTRACKED],
+    query_stats: bool = (false, parse_bool, [UNTRACKED], Profiling,
         "print some statistics about the query system (default: no)"),
-    randomize_layout: bool = (false, parse_bool, [TRACKED],
+    randomize_layout: bool = (false, parse_bool, [TRACKED], Other,
         "randomize the layout of types (default: no)"),
-    relax_elf_relocations: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    relax_elf_relocations: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "whether ELF relocations can be relaxed"),
-    relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
+    relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED], Linking,
         "choose which RELRO level to use"),
-    remap_cwd_prefix: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+    remap_cwd_prefix: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Other,
         "remap paths under the current working directory to this path prefix"),
-    simulate_remapped_rust_src_base: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+    simulate_remapped_rust_src_base: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Other,
         "simulate the effect of remap-debuginfo = true at bootstrapping by remapping path \
         to rust's source base directory. only meant for testing purposes"),
-    report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
+    remark_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], Other,
+        "write the optimization remarks selected with `-C remark` into YAML files in this \
+        directory, one for each codegen unit and stage, instead of printing them"),
+    report_delayed_bugs: bool = (false, parse_bool, [TRACKED], Diagnostics,
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
-    sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
+    resource_summary: Option<ReportFormat> = (None, parse_report_format, [UNTRACKED], Profiling,
+        "print the peak memory, CPU time, threads and bytes written by the compiler when it \
+        exits, as `human` text or `json` (default: no)"),
+    riscv_abi: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
+        "the ABI of a RISC-V target, which decides whether floating-point arguments are passed \
+        in floating-point registers: `ilp32`, `ilp32f` or `ilp32d` on riscv32, `lp64`, `lp64f` \
+        or `lp64d` on riscv64 (default: the one of the target)"),
+    riscv_relax: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
+        "let the linker relax sequences of instructions into shorter ones on RISC-V targets \
+        (default: the target's setting, which only relaxes the code of other languages)"),
+    run_link_plan: bool = (false, parse_bool, [UNTRACKED], Linking,
+        "run the linker invocations printed by `--print link-plan`, given as the input file, \
+        instead of compiling (default: no)"),
+    safestack_use_pointer_address: bool = (false, parse_bool, [TRACKED], Other,
+        "with `-Z sanitizer=safestack`, access the unsafe stack pointer through a user-provided \
+        `__safestack_pointer_address` function instead of the SafeStack runtime; only supported \
+        on bare-metal targets (default: no)"),
+    sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED], Instrumentation,
         "use a sanitizer"),
     sanitizer_memory_track_origins: usize = (0, parse_sanitizer_memory_track_origins, [TRACKED],
-        "enable origins tracking in MemorySanitizer"),
+        Instrumentation,
+        "enable origins tracking in MemorySanitizer", default: "0"),
     sanitizer_recover: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
+        Instrumentation,
         "enable recovery for selected sanitizers"),
-    saturating_float_casts: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    saturating_float_casts: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "make float->int casts UB-free: numbers outside the integer type's range are clipped to \
         the max/min integer respectively, and NaN is mapped to 0 (default: yes)"),
-    save_analysis: bool = (false, parse_bool, [UNTRACKED],
+    save_analysis: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "write syntax and type analysis (in JSON format) information, in \
-        addition to normal output (default: no)"),
+        addition to normal output (default: no); superseded by `--emit analysis-json`"),
+    sdk_root: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Linking,
+        "the Apple SDK to link against, overriding `SDKROOT` and `xcrun`"),
     self_profile: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
-        parse_switch_with_opt_path, [UNTRACKED],
+        parse_switch_with_opt_path, [UNTRACKED], Profiling,
         "run the self profiler and output the raw event data"),
     /// keep this in sync with the event filter names in librustc_data_structures/profiling.rs
-    self_profile_events: Option<Vec<String>> = (None, parse_opt_comma_list, [UNTRACKED],
+    self_profile_events: Option<Vec<String>> = (None, parse_opt_comma_list, [UNTRACKED], Profiling,
         "specify the events recorded by the self profiler;
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
-                     query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
-    share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
+                     query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes, memory"),
+    share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "make the current crate share its generic instantiations"),
-    show_span: Option<String> = (None, parse_opt_string, [TRACKED],
+    shared_generics_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], CodeGeneration,
+        "share generic instantiations with the other crates compiled with the same index \
+        directory, even if they are not dependencies of the current crate (default: no)"),
+    show_span: Option<String> = (None, parse_opt_string, [TRACKED], Diagnostics,
         "show spans for compiler debugging (expr|pat|ty)"),
-    span_debug: bool = (false, parse_bool, [UNTRACKED],
-        "forward proc_macro::Span's `Debug` impl to `Span`"),
+    shuffle_cgus: Option<u64> = (None, parse_seed, [UNTRACKED], CodeGeneration,
+        "codegen and optimize codegen units in an order shuffled with the given seed, or \
+        `random` to pick a seed that is then printed"),
+    span_debug: bool = (false, parse_bool, [UNTRACKED], Diagnostics,
+        "forward proc_macro::Span's `Debug` impl to `Span`", default: "no"),
     /// o/w tests have closure@path
-    span_free_formats: bool = (false, parse_bool, [UNTRACKED],
+    span_free_formats: bool = (false, parse_bool, [UNTRACKED], Diagnostics,
         "exclude spans when debug-printing compiler state (default: no)"),
     src_hash_algorithm: Option<SourceFileHashAlgorithm> = (None, parse_src_file_hash, [TRACKED],
+        Other,
         "hash algorithm of source files in debug info (`md5`, `sha1`, or `sha256`)"),
+    stack_depth_report: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], DebuggingOutput,
+        "record the calls made by each function and, when linking a statically linked ELF \
+        executable, write the worst-case stack depth of each of its entry points to the given \
+        path; implies `-Z emit-stack-sizes` (default: no)"),
     stack_protector: StackProtector = (StackProtector::None, parse_stack_protector, [TRACKED],
-        "control stack smash protection strategy (`rustc --print stack-protector-strategies` for details)"),
-    strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
+        CodeGeneration,
+        "control stack smash protection strategy (`rustc --print stack-protector-strategies` for details)",
+        default: "none"),
+    stack_protector_guard: Option<StackProtectorGuard> = (None, parse_stack_protector_guard, [TRACKED],
+        CodeGeneration,
+        "where the stack protector guard value is loaded from: `tls` or `global` \
+        (default: the target's default)"),
+    stack_protector_guard_offset: Option<i32> = (None, parse_opt_number, [TRACKED], CodeGeneration,
+        "offset of the stack protector guard from the thread pointer, with \
+        `-Z stack-protector-guard=tls`"),
+    stack_protector_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED], CodeGeneration,
+        "write the functions that received stack smash protection, and why, to the given path \
+        (default: no)"),
+    stop_after: Option<StopAfter> = (None, parse_stop_after, [TRACKED_NO_CRATE_HASH], Other,
+        "stop compilation after the given stage, writing only the outputs of the stages \
+        that ran: `parsing`, `expansion`, `analysis` or `codegen` (nothing is linked)"),
+    strip: Strip = (Strip::None, parse_strip, [UNTRACKED], Linking,
         "tell the linker which information to strip (`none` (default), `debuginfo` or `symbols`)"),
-    split_dwarf_inlining: bool = (true, parse_bool, [UNTRACKED],
+    split_dwarf_inlining: bool = (true, parse_bool, [UNTRACKED], DebugInfo,
         "provide minimal debug info in the object/executable to facilitate online \
-         symbolication/stack traces in the absence of .dwo/.dwp files when using Split DWARF"),
+         symbolication/stack traces in the absence of .dwo/.dwp files when using Split DWARF",
+        default: "yes"),
     symbol_mangling_version: Option<SymbolManglingVersion> = (None,
-        parse_symbol_mangling_version, [TRACKED],
+        parse_symbol_mangling_version, [TRACKED], CodeGeneration,
         "which mangling version to use for symbol names ('legacy' (default) or 'v0')"),
-    teach: bool = (false, parse_bool, [TRACKED],
+    symbol_prefix: Option<String> = (None, parse_opt_string, [TRACKED], Linking,
+        "namespace the symbols defined by the crate with a prefix, in the crate's component of \
+        mangled names and in front of `#[no_mangle]` and `#[export_name]` names (default: none)"),
+    symbol_visibility_file: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED], Linking,
+        "restrict the symbols exported from dynamic libraries to those matching the patterns \
+        in the given file, one per line (`*` and `?` are wildcards, `#` starts a comment)"),
+    teach: bool = (false, parse_bool, [TRACKED], Diagnostics,
         "show extended diagnostic help (default: no)"),
-    temps_dir: Option<String> = (None, parse_opt_string, [UNTRACKED],
+    temps_dir: Option<String> = (None, parse_opt_string, [UNTRACKED], Other,
         "the directory the intermediate files are written to"),
-    terminal_width: Option<usize> = (None, parse_opt_number, [UNTRACKED],
+    temps_dir_per_kind: bool = (false, parse_bool, [UNTRACKED], Other,
+        "write intermediate files into subdirectories of `-Z temps-dir` by kind \
+        (default: no)"),
+    temps_policy: Option<TempsPolicy> = (None, parse_temps_policy, [UNTRACKED], Other,
+        "when to keep intermediate files (`keep-on-error`, `always-keep` or `always-clean`) \
+        (default: `always-clean`, or `always-keep` with `-C save-temps`)"),
+    terminal_width: Option<usize> = (None, parse_opt_number, [UNTRACKED], Diagnostics,
         "set the current terminal width"),
-    tune_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
+    test_attr: Option<String> = (None, parse_opt_string, [TRACKED], Other,
+        "also collect the items marked with this attribute as test cases when building with \
+        `--test`, like `#[test_case]`"),
+    test_runner: Option<String> = (None, parse_opt_string, [TRACKED], Other,
+        "the path of the function to run the test cases with when building with `--test`, \
+        instead of libtest, like `#![test_runner]`"),
+    tune_cpu: Option<String> = (None, parse_opt_string, [TRACKED], CodeGeneration,
         "select processor to schedule for (`rustc --print target-cpus` for details)"),
-    thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "enable ThinLTO when possible"),
-    thir_unsafeck: bool = (false, parse_bool, [TRACKED],
+    thir_unsafeck: bool = (false, parse_bool, [TRACKED], Mir,
         "use the THIR unsafety checker (default: no)"),
     /// We default to 1 here since we want to behave like
     /// a sequential compiler for now. This'll likely be adjusted
     /// in the future. Note that -Zthreads=0 is the way to get
     /// the num_cpus behavior.
-    threads: usize = (1, parse_threads, [UNTRACKED],
-        "use a thread pool with N threads"),
-    time: bool = (false, parse_bool, [UNTRACKED],
+    threads: usize = (1, parse_threads, [UNTRACKED], Other,
+        "use a thread pool with N threads", default: "1"),
+    time: bool = (false, parse_bool, [UNTRACKED], Profiling,
         "measure time of rustc processes (default: no)"),
-    time_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
+    time_llvm_passes: bool = (false, parse_bool, [UNTRACKED], Profiling,
         "measure time of each LLVM pass (default: no)"),
-    time_passes: bool = (false, parse_bool, [UNTRACKED],
+    time_passes: bool = (false, parse_bool, [UNTRACKED], Profiling,
         "measure time of each rustc pass (default: no)"),
-    tls_model: Option<TlsModel> = (None, parse_tls_model, [TRACKED],
+    tls_model: Option<TlsModel> = (None, parse_tls_model, [TRACKED], CodeGeneration,
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
-    trace_macros: bool = (false, parse_bool, [UNTRACKED],
+    total_compile_time_limit: Option<Duration> = (None, parse_duration, [UNTRACKED], Profiling,
+        "abort compilation with an error when it is still running after this long, \
+        such as `90s` or `5m`"),
+    trace_macros: bool = (false, parse_bool, [UNTRACKED], Other,
         "for every macro invocation, print its name and arguments (default: no)"),
-    trap_unreachable: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    trap_unreachable: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "generate trap instructions for unreachable intrinsics (default: use target setting, usually yes)"),
-    treat_err_as_bug: Option<NonZeroUsize> = (None, parse_treat_err_as_bug, [TRACKED],
+    treat_err_as_bug: Option<NonZeroUsize> = (None, parse_treat_err_as_bug, [TRACKED], Diagnostics,
         "treat error number `val` that occurs as bug"),
-    trim_diagnostic_paths: bool = (true, parse_bool, [UNTRACKED],
-        "in diagnostics, use heuristics to shorten paths referring to items"),
-    ui_testing: bool = (false, parse_bool, [UNTRACKED],
+    trim_diagnostic_paths: bool = (true, parse_bool, [UNTRACKED], Diagnostics,
+        "in diagnostics, use heuristics to shorten paths referring to items", default: "yes"),
+    ui_testing: bool = (false, parse_bool, [UNTRACKED], Diagnostics,
         "emit compiler diagnostics in a form suitable for UI testing (default: no)"),
-    unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED],
+    unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED], Mir,
         "take the brakes off const evaluation. NOTE: this is unsound (default: no)"),
-    unpretty: Option<String> = (None, parse_unpretty, [UNTRACKED],
+    unpretty: Option<String> = (None, parse_unpretty, [UNTRACKED], DebuggingOutput,
         "present the input source, unstable (and less-pretty) variants;
         `normal`, `identified`,
         `expanded`, `expanded,identified`,
@@ -1424,20 +2287,28 @@ mod parse {
         `hir,typed` (HIR with types for each node),
         `hir-tree` (dump the raw HIR),
         `mir` (the MIR), or `mir-cfg` (graphviz formatted MIR)"),
-    unsound_mir_opts: bool = (false, parse_bool, [TRACKED],
+    unsound_mir_opts: bool = (false, parse_bool, [TRACKED], Mir,
         "enable unsound and buggy MIR optimizations (default: no)"),
-    unstable_options: bool = (false, parse_bool, [UNTRACKED],
+    unstable_options: bool = (false, parse_bool, [UNTRACKED], Other,
         "adds unstable command line options to rustc interface (default: no)"),
-    use_ctors_section: Option<bool> = (None, parse_opt_bool, [TRACKED],
+    use_ctors_section: Option<bool> = (None, parse_opt_bool, [TRACKED], CodeGeneration,
         "use legacy .ctors section for initializers rather than .init_array"),
-    validate_mir: bool = (false, parse_bool, [UNTRACKED],
-        "validate MIR after each transformation"),
-    verbose: bool = (false, parse_bool, [UNTRACKED],
+    validate_mir: bool = (false, parse_bool, [UNTRACKED], Mir,
+        "validate MIR after each transformation", default: "no"),
+    verbose: bool = (false, parse_bool, [UNTRACKED], DebuggingOutput,
         "in general, enable more debug printouts (default: no)"),
-    verify_llvm_ir: bool = (false, parse_bool, [TRACKED],
+    verify_llvm_ir: bool = (false, parse_bool, [TRACKED], CodeGeneration,
         "verify LLVM IR (default: no)"),
-    wasi_exec_model: Option<WasiExecModel> = (None, parse_wasi_exec_model, [TRACKED],
+    wasi_component: Option<WasiComponent> = (None, parse_wasi_component, [TRACKED], Linking,
+        "link executables and cdylibs for WASI into a WASI preview2 component, optionally with \
+        the preview1 adapter module to use and a component type binding the world to target \
+        (default: no)"),
+    wasi_exec_model: Option<WasiExecModel> = (None, parse_wasi_exec_model, [TRACKED], Linking,
         "whether to build a wasi command or reactor"),
+    wasm_features: Vec<(WasmFeature, bool)> = (Vec::new(), parse_wasm_features, [TRACKED],
+        CodeGeneration,
+        "enable or disable WebAssembly features, together with the target features they need \
+        and the linker arguments they need, e.g. `+atomics,-simd128`"),
 
     // This list is in alphabetical order.
     //
@@ -1451,6 +2322,26 @@ pub enum WasiExecModel {
     Reactor,
 }
 
+/// The policy of `-Z incremental-gc` for pruning the incremental directory.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct IncrementalGc {
+    /// How long the directory of a crate is kept after its last compilation.
+    pub max_age: Option<Duration>,
+    /// How many bytes the incremental directory may take up.
+    pub max_size: Option<u64>,
+}
+
+/// How to turn a linked WASI module into a component, for `-Z wasi-component`.
+#[derive(Clone, Default, Hash, PartialEq, Eq, Debug)]
+pub struct WasiComponent {
+    /// The `wasi_snapshot_preview1` adapter module; by default, the one shipped in the
+    /// self-contained directory of the sysroot for the selected exec model.
+    pub adapter: Option<PathBuf>,
+    /// A component type, either a WIT package or an encoded `component-type` custom section,
+    /// describing the world the component targets.
+    pub world: Option<PathBuf>,
+}
+
 #[derive(Clone, Copy, Hash)]
 pub enum LdImpl {
     Lld,
@@ -0,0 +1,133 @@
+//! Support for `-Z output-sandbox`, which restricts the files a session may read
+//! or write to a set of directory roots.
+
+use rustc_span::source_map::FileLoader;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// How a file is accessed, used to decide whether the sysroot may be touched.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SandboxAccess {
+    Read,
+    Write,
+}
+
+impl SandboxAccess {
+    pub fn desc(self) -> &'static str {
+        match self {
+            SandboxAccess::Read => "read",
+            SandboxAccess::Write => "write",
+        }
+    }
+}
+
+/// The set of directories a session may access under `-Z output-sandbox`.
+#[derive(Debug)]
+pub struct OutputSandbox {
+    /// Roots passed on the command line, which may be read and written.
+    roots: Vec<PathBuf>,
+    /// The sysroot, which may only be read.
+    sysroot: PathBuf,
+}
+
+impl OutputSandbox {
+    pub fn new(roots: &[PathBuf], sysroot: &Path) -> OutputSandbox {
+        OutputSandbox {
+            roots: roots.iter().map(|root| normalize_path(root)).collect(),
+            sysroot: normalize_path(sysroot),
+        }
+    }
+
+    pub fn allows(&self, path: &Path, access: SandboxAccess) -> bool {
+        let path = normalize_path(path);
+        self.roots.iter().any(|root| path.starts_with(root))
+            || (access == SandboxAccess::Read && path.starts_with(&self.sysroot))
+    }
+
+    /// Returns an error naming the violating access if `path` lies outside the sandbox.
+    pub fn check(&self, path: &Path, access: SandboxAccess) -> io::Result<()> {
+        if self.allows(path, access) {
+            return Ok(());
+        }
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "refusing to {} `{}`: outside the output sandbox ({})",
+                access.desc(),
+                path.display(),
+                self.describe_roots()
+            ),
+        ))
+    }
+
+    /// Lists the allowed roots, for use in diagnostics.
+    pub fn describe_roots(&self) -> String {
+        let mut roots: Vec<String> =
+            self.roots.iter().map(|root| format!("`{}`", root.display())).collect();
+        roots.push(format!("`{}` (read-only)", self.sysroot.display()));
+        format!("allowed roots: {}", roots.join(", "))
+    }
+}
+
+/// Makes `path` absolute, resolves `..` lexically and canonicalizes the longest prefix
+/// that exists, so that files which have not been created yet can still be compared
+/// against the (canonicalized) roots.
+fn normalize_path(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
+    };
+
+    let mut lexical = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                lexical.pop();
+            }
+            _ => lexical.push(component),
+        }
+    }
+
+    let mut existing = lexical.as_path();
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(canonical) = fs::canonicalize(existing) {
+            return missing.iter().rev().fold(canonical, |acc, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_owned());
+                existing = parent;
+            }
+            _ => return lexical,
+        }
+    }
+}
+
+/// A `FileLoader` that refuses to read files outside the output sandbox.
+pub struct SandboxedFileLoader {
+    pub inner: Box<dyn FileLoader + Send + Sync>,
+    pub sandbox: Arc<OutputSandbox>,
+}
+
+impl FileLoader for SandboxedFileLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        self.inner.file_exists(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.sandbox.check(path, SandboxAccess::Read)?;
+        self.inner.read_file(path)
+    }
+
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.sandbox.check(path, SandboxAccess::Read)?;
+        self.inner.read_binary_file(path)
+    }
+}
@@ -1,8 +1,14 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
 pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
-use crate::config::{self, CrateType, OutputType, SwitchWithOptPath};
+use crate::config::{self, BinaryDepDepinfo, CguNameScheme, CrateType, OutputType, PltPolicy};
+use crate::config::{FunctionReturn, Hotpatch, PanicAbortTestsFormat, PrintRequest};
+use crate::config::{ReportFormat, StackProtectorGuard, SwitchWithOptPath, WasmFeature};
+use crate::module_timings::ModuleTimings;
+pub use crate::module_timings::TimingPhase;
+use crate::option_combinations;
 use crate::parse::ParseSess;
+use crate::sandbox::{OutputSandbox, SandboxAccess, SandboxedFileLoader};
 use crate::search_paths::{PathKind, SearchPath};
 use crate::{filesearch, lint};
 
@@ -11,42 +17,58 @@
 use rustc_data_structures::flock;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::jobserver::{self, Client};
-use rustc_data_structures::profiling::{duration_to_secs_str, SelfProfiler, SelfProfilerRef};
+use rustc_data_structures::profiling::{
+    duration_to_secs_str, get_process_resource_usage, SelfProfiler, SelfProfilerRef,
+};
 use rustc_data_structures::sync::{
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
+use rustc_data_structures::OnDrop;
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
-use rustc_errors::{DiagnosticBuilder, DiagnosticId, ErrorReported};
+use rustc_errors::{DiagnosticBuilder, DiagnosticId, ErrorReported, FatalError};
 use rustc_macros::HashStable_Generic;
+use rustc_serialize::json::{Json, ToJson};
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
+use rustc_span::lev_distance::find_best_match_for_name;
 use rustc_span::source_map::{FileLoader, MultiSpan, RealFileLoader, SourceMap, Span};
-use rustc_span::{sym, SourceFileHashAlgorithm, Symbol};
+use rustc_span::{sym, FileName, SourceFileHash, SourceFileHashAlgorithm, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
 use rustc_target::spec::{
     SanitizerSet, SplitDebuginfo, StackProtector, Target, TargetTriple, TlsModel,
 };
 
-use std::cell::{self, RefCell};
+use std::cell::{self, Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::ffi::OsString;
 use std::fmt;
 use std::io::Write;
+use std::mem;
 use std::num::NonZeroU32;
 use std::ops::{Div, Mul};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+mod reports;
+mod time_limit;
+
+pub use self::reports::*;
+pub use self::time_limit::*;
 
 pub struct OptimizationFuel {
     /// If `-zfuel=crate=n` is specified, initially set to `n`, otherwise `0`.
     remaining: u64,
     /// We're rejecting all further optimizations.
     out_of_fuel: bool,
+    /// The pass and description of each optimization that used fuel, for `-Z fuel-trace`.
+    trace: Vec<(String, String)>,
 }
 
 /// The behavior of the CTFE engine when an error occurs with regards to backtraces.
@@ -164,6 +186,9 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// The time spent on each module and item, gathered for `-Z module-timings`.
+    pub module_timings: ModuleTimings,
+
     /// Tracks fuel info if `-zfuel=crate=n` is specified.
     optimization_fuel: Lock<OptimizationFuel>,
 
@@ -196,6 +221,54 @@ pub struct Session {
 
     /// Set of enabled features for the current target.
     pub target_features: FxHashSet<Symbol>,
+
+    /// Files written by this session and their roles, for `-Z emit-artifact-manifest`.
+    /// Files that no longer exist at the end of the session are left out of the manifest.
+    artifacts: Lock<Vec<(PathBuf, String)>>,
+
+    /// The roles and sizes of the files written by this session, for `-Z resource-summary`.
+    /// Sizes are taken when the files are recorded, as temporary files are removed before
+    /// the end of the session.
+    artifact_sizes: Lock<FxHashMap<PathBuf, (String, u64)>>,
+
+    /// Files opened by proc macros running under `-Z proc-macro-sandbox`, for
+    /// `-Z file-access-log`.
+    proc_macro_accesses: Lock<Vec<PathBuf>>,
+
+    /// Environment variables the session read and their values, and the `PATH` entries
+    /// tools were found in, for `--print build-env-report`.
+    env_reads: Lock<BTreeMap<String, Option<OsString>>>,
+    tool_path_entries: Lock<Vec<(PathBuf, PathBuf)>>,
+
+    /// Directories this session may read and write, if `-Z output-sandbox` was passed.
+    pub output_sandbox: Option<Arc<OutputSandbox>>,
+
+    /// When `-Z total-compile-time-limit` runs out, and the time taken by each finished
+    /// outermost pass and the start of the ones in progress, for the summary printed then.
+    /// The limit isn't checked while `time_limit_suspended` is non-zero.
+    compile_deadline: Option<Instant>,
+    pass_times: Lock<Vec<(&'static str, Duration)>>,
+    current_passes: Lock<Vec<(&'static str, Instant)>>,
+    time_limit_suspended: AtomicUsize,
+
+    /// The time taken by each lint pass, by kind of pass and name, for `-Z lint-timings`.
+    lint_pass_times: Lock<Vec<(&'static str, String, Duration)>>,
+
+    /// The number of invocations, the number of tokens produced and the time taken by the
+    /// expansions of each macro, by kind of macro and name, for `-Z macro-stats`.
+    macro_stats: Lock<FxHashMap<(&'static str, String), (usize, usize, Duration)>>,
+
+    /// The instances that polymorphization replaced while collecting mono items, by the
+    /// polymorphized instance that replaced them, for `-Z polymorphize-report`.
+    pub polymorphized_instances: Lock<FxHashMap<String, BTreeSet<String>>>,
+
+    /// The `StableCrateId`s of the crates of the `-Z shared-generics-dir` index that the
+    /// local crate links to generic instances of, instead of instantiating them itself.
+    pub shared_generics_providers: Lock<FxHashSet<u64>>,
+
+    /// How many times each key of the query of `-Z profile-query-keys` got its value in each
+    /// way, indexed by `QueryKeyOutcome`.
+    query_keys: Lock<FxHashMap<String, [usize; 3]>>,
 }
 
 pub struct PerfStats {
@@ -275,8 +348,41 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.emit_artifact_manifest();
+        self.emit_file_access_log();
+        self.emit_fuel_trace();
+        self.print_build_env_report();
+        self.print_lint_timings();
+        self.print_macro_stats();
+        self.write_module_timings();
+        self.write_query_keys();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
+        self.print_resource_summary();
+    }
+
+    /// Checks `path` against `-Z output-sandbox`, reporting an error naming the
+    /// violating access if it lies outside the allowed roots.
+    pub fn check_sandboxed_access(
+        &self,
+        path: &Path,
+        access: SandboxAccess,
+    ) -> Result<(), ErrorReported> {
+        let sandbox = match self.output_sandbox {
+            Some(ref sandbox) => sandbox,
+            None => return Ok(()),
+        };
+        if sandbox.allows(path, access) {
+            return Ok(());
+        }
+        self.struct_err(&format!(
+            "refusing to {} `{}`: outside the output sandbox",
+            access.desc(),
+            path.display()
+        ))
+        .note(&sandbox.describe_roots())
+        .emit();
+        Err(ErrorReported)
     }
 
     fn emit_future_breakage(&self) {
@@ -299,6 +405,11 @@ pub fn crate_types(&self) -> &[CrateType] {
         self.crate_types.get().unwrap().as_slice()
     }
 
+    /// The version of the local crate, if one was given with `--crate-version`.
+    pub fn crate_version(&self) -> Option<&str> {
+        self.opts.crate_version.as_deref()
+    }
+
     pub fn init_crate_types(&self, crate_types: Vec<CrateType>) {
         self.crate_types.set(crate_types).expect("`crate_types` was initialized twice")
     }
@@ -333,6 +444,9 @@ pub fn struct_span_allow<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> Diagnos
     pub fn struct_allow(&self, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_allow(msg)
     }
+    pub fn struct_expect(&self, msg: &str, id: lint::LintExpectationId) -> DiagnosticBuilder<'_> {
+        self.diagnostic().struct_expect(msg, id)
+    }
     pub fn struct_span_err<S: Into<MultiSpan>>(&self, sp: S, msg: &str) -> DiagnosticBuilder<'_> {
         self.diagnostic().struct_span_err(sp, msg)
     }
@@ -453,7 +567,7 @@ pub fn delay_good_path_bug(&self, msg: &str) {
             || self.opts.debugging_opts.dump_mir.is_some()
             || self.opts.debugging_opts.unpretty.is_some()
             || self.opts.output_types.contains_key(&OutputType::Mir)
-            || std::env::var_os("RUSTC_LOG").is_some()
+            || self.env_var_os("RUSTC_LOG").is_some()
         {
             return;
         }
@@ -537,12 +651,84 @@ pub fn source_map(&self) -> &SourceMap {
     pub fn verbose(&self) -> bool {
         self.opts.debugging_opts.verbose
     }
+    /// Whether lint passes run one at a time, which `-Z lint-timings` needs to time them.
+    pub fn no_interleave_lints(&self) -> bool {
+        self.opts.debugging_opts.no_interleave_lints || self.opts.debugging_opts.lint_timings
+    }
+    /// Whether the late lint passes over the whole crate run one at a time but concurrently
+    /// with each other, which `-Z threads` allows unless they have to run one at a time anyway.
+    pub fn parallel_lint_passes(&self) -> bool {
+        self.threads() > 1 && !self.no_interleave_lints()
+    }
+    /// Gets the value of the configuration option `name` of `lint`, as last given on the
+    /// command line, e.g., with `-W large-assignments=limit=4096`. Invalid values are
+    /// reported when the lint levels are set up and ignored here.
+    pub fn lint_config(
+        &self,
+        lint: &'static lint::Lint,
+        name: &str,
+    ) -> Option<lint::LintConfigValue> {
+        let option = lint.config_option(name)?;
+        let lint_name = lint.name_lower();
+        let (_, _, value) = self
+            .opts
+            .lint_config
+            .iter()
+            .rev()
+            .find(|(config_lint, key, _)| *config_lint == lint_name && key == name)?;
+        option.kind.parse(value)
+    }
     pub fn time_passes(&self) -> bool {
         self.opts.debugging_opts.time_passes || self.opts.debugging_opts.time
     }
     pub fn instrument_mcount(&self) -> bool {
         self.opts.debugging_opts.instrument_mcount
     }
+    /// The symbols of the functions called on entry to and exit from the instrumented
+    /// functions, with `-C instrument-functions` or `-Z instrument-functions`.
+    pub fn instrument_function_hooks(&self) -> Option<(&str, Option<&str>)> {
+        if let Some(ref hooks) = self.opts.debugging_opts.instrument_functions {
+            Some((&hooks.entry, hooks.exit.as_deref()))
+        } else if self.opts.cg.instrument_functions {
+            Some(("__cyg_profile_func_enter", Some("__cyg_profile_func_exit")))
+        } else {
+            None
+        }
+    }
+    /// Whether some of the functions are left out of the instrumentation.
+    pub fn instrument_functions_filtered(&self) -> bool {
+        let dopts = &self.opts.debugging_opts;
+        if dopts.instrument_functions.is_some() {
+            !dopts.instrument_functions_include.is_empty()
+                || !dopts.instrument_functions_exclude.is_empty()
+        } else {
+            !self.opts.cg.instrument_functions_exclude.is_empty()
+        }
+    }
+    /// Whether the function at `path` is left out of the instrumentation. The patterns of
+    /// `-C instrument-functions-exclude` name the function or a module it is in, and those of
+    /// `-Z instrument-functions-include` and `-Z instrument-functions-exclude` can name them by
+    /// any whole segments of their paths, such as `fmt::write` or `write`.
+    pub fn instrument_functions_excluded(&self, path: &str) -> bool {
+        let at_boundary = |rest: &str| rest.is_empty() || rest.starts_with("::");
+        let dopts = &self.opts.debugging_opts;
+        if dopts.instrument_functions.is_none() {
+            let exclude = &self.opts.cg.instrument_functions_exclude;
+            return exclude
+                .iter()
+                .any(|pattern| path.strip_prefix(pattern.as_str()).map_or(false, at_boundary));
+        }
+
+        let matches = |pattern: &String| {
+            path.match_indices(pattern.as_str()).any(|(start, _)| {
+                (start == 0 || path[..start].ends_with("::"))
+                    && at_boundary(&path[start + pattern.len()..])
+            })
+        };
+        let included = dopts.instrument_functions_include.is_empty()
+            || dopts.instrument_functions_include.iter().any(matches);
+        !included || dopts.instrument_functions_exclude.iter().any(matches)
+    }
     pub fn time_llvm_passes(&self) -> bool {
         self.opts.debugging_opts.time_llvm_passes
     }
@@ -559,7 +745,10 @@ pub fn print_llvm_passes(&self) -> bool {
         self.opts.debugging_opts.print_llvm_passes
     }
     pub fn binary_dep_depinfo(&self) -> bool {
-        self.opts.debugging_opts.binary_dep_depinfo
+        self.opts.debugging_opts.binary_dep_depinfo != BinaryDepDepinfo::Disabled
+    }
+    pub fn binary_dep_depinfo_hashed(&self) -> bool {
+        self.opts.debugging_opts.binary_dep_depinfo == BinaryDepDepinfo::Hashed
     }
     pub fn mir_opt_level(&self) -> usize {
         self.opts.mir_opt_level()
@@ -674,10 +863,47 @@ pub fn is_nightly_build(&self) -> bool {
     pub fn is_sanitizer_cfi_enabled(&self) -> bool {
         self.opts.debugging_opts.sanitizer.contains(SanitizerSet::CFI)
     }
+    pub fn split_lto_unit(&self) -> bool {
+        self.opts.cg.split_lto_unit.unwrap_or_else(|| self.is_sanitizer_cfi_enabled())
+    }
     pub fn overflow_checks(&self) -> bool {
         self.opts.cg.overflow_checks.unwrap_or(self.opts.debug_assertions)
     }
 
+    /// The target features in effect for this compilation, i.e. the ones visible to
+    /// `cfg(target_feature)`, sorted by name.
+    ///
+    /// This is the result of combining the features of the target CPU, the defaults of the
+    /// target specification and `-C target-feature`, along with any features those imply.
+    /// It is only complete once the codegen backend has been queried for the target features.
+    pub fn enabled_target_features(&self) -> Vec<Symbol> {
+        let mut features: Vec<_> = self.target_features.iter().copied().collect();
+        if self.crt_static(None) {
+            features.push(sym::crt_dash_static);
+        }
+        features.sort_by_cached_key(|feature| feature.as_str());
+        features
+    }
+
+    /// Reports an error if the floating-point ABI selected with `-Z riscv-abi` passes arguments
+    /// in the registers of an extension that isn't enabled. This can only be checked once the
+    /// target features are known.
+    pub fn check_riscv_abi_features(&self) {
+        let (abi, feature) = match self.opts.debugging_opts.riscv_abi.as_deref() {
+            Some(abi @ ("ilp32f" | "lp64f")) => (abi, "f"),
+            Some(abi @ ("ilp32d" | "lp64d")) => (abi, "d"),
+            _ => return,
+        };
+        if !self.target_features.contains(&Symbol::intern(feature)) {
+            self.struct_err(&format!(
+                "`-Z riscv-abi={}` requires the `{}` target feature",
+                abi, feature
+            ))
+            .help(&format!("enable it with `-C target-feature=+{}`", feature))
+            .emit();
+        }
+    }
+
     /// Check whether this compile session and crate type use static crt.
     pub fn crt_static(&self, crate_type: Option<CrateType>) -> bool {
         if !self.target.crt_static_respected {
@@ -723,6 +949,40 @@ pub fn is_wasi_reactor(&self) -> bool {
             )
     }
 
+    /// Whether `-Z wasm-features` enables or disables the given feature, its last mention
+    /// taking precedence, or `None` if it doesn't mention it.
+    pub fn wasm_feature(&self, feature: WasmFeature) -> Option<bool> {
+        let features = &self.opts.debugging_opts.wasm_features;
+        features.iter().rev().find(|&&(f, _)| f == feature).map(|&(_, enabled)| enabled)
+    }
+
+    /// The LLVM target features that `-Z wasm-features` expands to, with the features that
+    /// the enabled ones need.
+    pub fn wasm_target_features(&self) -> Vec<String> {
+        let mut features = Vec::new();
+        for &feature in WasmFeature::ALL {
+            match self.wasm_feature(feature) {
+                Some(true) => {
+                    for implied in feature.implied_features() {
+                        features.push(format!("+{}", implied.llvm_feature()));
+                    }
+                    features.push(format!("+{}", feature.llvm_feature()));
+                }
+                Some(false) => features.push(format!("-{}", feature.llvm_feature())),
+                None => {}
+            }
+        }
+        features
+    }
+
+    /// Whether WebAssembly exception handling is enabled, which unwinding needs on the targets
+    /// other than Emscripten, that uses JavaScript exceptions.
+    pub fn wasm_exception_handling(&self) -> bool {
+        self.wasm_feature(WasmFeature::ExceptionHandling).unwrap_or_else(|| {
+            self.opts.cg.target_feature.split(',').any(|f| f == "+exception-handling")
+        })
+    }
+
     pub fn split_debuginfo(&self) -> SplitDebuginfo {
         self.opts.cg.split_debuginfo.unwrap_or(self.target.split_debuginfo)
     }
@@ -880,12 +1140,19 @@ pub fn print_perf_stats(&self) {
         );
     }
 
-    /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n.
+    /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n,
+    /// or -z fuel=foo=pass:n if `pass` is the only pass that should use fuel.
     /// This expends fuel if applicable, and records fuel if applicable.
-    pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -> bool {
+    pub fn consider_optimizing<T: Fn() -> String>(
+        &self,
+        crate_name: &str,
+        pass: &str,
+        msg: T,
+    ) -> bool {
         let mut ret = true;
-        if let Some((ref c, _)) = self.opts.debugging_opts.fuel {
-            if c == crate_name {
+        let mut used_fuel = false;
+        if let Some((ref c, ref fuel_pass, _)) = self.opts.debugging_opts.fuel {
+            if c == crate_name && fuel_pass.as_ref().map_or(true, |fuel_pass| fuel_pass == pass) {
                 assert_eq!(self.threads(), 1);
                 let mut fuel = self.optimization_fuel.lock();
                 ret = fuel.remaining != 0;
@@ -899,6 +1166,7 @@ pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -
                     fuel.out_of_fuel = true;
                 } else if fuel.remaining > 0 {
                     fuel.remaining -= 1;
+                    used_fuel = true;
                 }
             }
         }
@@ -906,11 +1174,32 @@ pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -
             if c == crate_name {
                 assert_eq!(self.threads(), 1);
                 self.print_fuel.fetch_add(1, SeqCst);
+                used_fuel = true;
             }
         }
+        if used_fuel && self.opts.debugging_opts.fuel_trace.is_some() {
+            self.optimization_fuel.lock().trace.push((pass.to_string(), msg()));
+        }
         ret
     }
 
+    /// Writes one line per unit of fuel used, numbered from 1, for `-Z fuel-trace`.
+    fn emit_fuel_trace(&self) {
+        let trace_path = match self.opts.debugging_opts.fuel_trace {
+            Some(ref path) => path,
+            None => return,
+        };
+
+        let trace = mem::take(&mut self.optimization_fuel.lock().trace);
+        let mut out = String::new();
+        for (i, (pass, msg)) in trace.iter().enumerate() {
+            out.push_str(&format!("{}\t{}\t{}\n", i + 1, pass, msg.trim_end()));
+        }
+        if let Err(e) = std::fs::write(trace_path, out) {
+            self.err(&format!("failed to write fuel trace `{}`: {}", trace_path.display(), e));
+        }
+    }
+
     /// Returns the number of query threads that should be used for this
     /// compilation
     pub fn threads(&self) -> usize {
@@ -987,6 +1276,25 @@ pub fn codegen_units(&self) -> usize {
         16
     }
 
+    /// Whether intermediate files should be kept if compilation fails, see
+    /// `-Z temps-policy`.
+    pub fn keep_temps_on_error(&self) -> bool {
+        self.opts.debugging_opts.temps_policy == Some(config::TempsPolicy::KeepOnError)
+    }
+
+    /// Returns how codegen units should be named.
+    pub fn cgu_name_scheme(&self) -> CguNameScheme {
+        if let Some(scheme) = self.opts.debugging_opts.cgu_name_scheme {
+            scheme
+        } else if self.opts.incremental.is_none() {
+            CguNameScheme::Numbered
+        } else if self.opts.debugging_opts.human_readable_cgu_names {
+            CguNameScheme::ItemBased
+        } else {
+            CguNameScheme::Hashed
+        }
+    }
+
     pub fn teach(&self, code: &DiagnosticId) -> bool {
         self.opts.debugging_opts.teach && self.diagnostic().must_teach(code)
     }
@@ -1011,22 +1319,43 @@ pub fn edition(&self) -> Edition {
 
     /// Returns `true` if we cannot skip the PLT for shared library calls.
     pub fn needs_plt(&self) -> bool {
-        // Check if the current target usually needs PLT to be enabled.
-        // The user can use the command line flag to override it.
-        let needs_plt = self.target.needs_plt;
-
-        let dbg_opts = &self.opts.debugging_opts;
+        match self.opts.debugging_opts.plt {
+            PltPolicy::Always => true,
+            PltPolicy::Never => false,
+            // Check if the current target usually needs PLT to be enabled.
+            //
+            // Otherwise only enable this optimization if lazy binding is disabled.
+            // In this case, lazy binding was already unavailable, so nothing is lost.
+            // This also ensures `-Wl,-z,now` is supported by the linker.
+            PltPolicy::Auto => self.target.needs_plt || !self.bind_now(),
+        }
+    }
 
-        let relro_level = dbg_opts.relro_level.unwrap_or(self.target.relro_level);
+    /// The RELRO level requested with `-C relro-level`, or else with `-Z relro-level`, or the
+    /// target's default. `-C relro-level` is ignored on targets that don't produce ELF files.
+    pub fn relro_level(&self) -> RelroLevel {
+        let requested = self.opts.cg.relro_level.filter(|_| self.target_produces_elf());
+        requested.or(self.opts.debugging_opts.relro_level).unwrap_or(self.target.relro_level)
+    }
 
-        // Only enable this optimization by default if full relro is also enabled.
-        // In this case, lazy binding was already unavailable, so nothing is lost.
-        // This also ensures `-Wl,-z,now` is supported by the linker.
-        let full_relro = RelroLevel::Full == relro_level;
+    /// Whether the target produces ELF files, which RELRO and `-Z stack-depth-report` are
+    /// specific to.
+    pub fn target_produces_elf(&self) -> bool {
+        !(self.target.is_like_osx || self.target.is_like_windows || self.target.is_like_wasm)
+    }
 
-        // If user didn't explicitly forced us to use / skip the PLT,
-        // then try to skip it where possible.
-        dbg_opts.plt.unwrap_or(needs_plt || !full_relro)
+    /// Returns `true` if all symbols are resolved at load time, i.e. lazy binding is disabled.
+    ///
+    /// Full RELRO implies this; `-Z bind-now` can request it with a weaker RELRO level too.
+    /// Targets without RELRO support (`RelroLevel::None`) never disable lazy binding.
+    pub fn bind_now(&self) -> bool {
+        match self.relro_level() {
+            RelroLevel::None => false,
+            RelroLevel::Full => true,
+            RelroLevel::Partial | RelroLevel::Off => {
+                self.opts.debugging_opts.bind_now.unwrap_or(false)
+            }
+        }
     }
 
     /// Checks if LLVM lifetime markers should be emitted.
@@ -1042,6 +1371,28 @@ pub fn link_dead_code(&self) -> bool {
         self.opts.cg.link_dead_code.unwrap_or(false)
     }
 
+    /// Whether the linker should discard unused sections, with `--gc-sections` or its
+    /// equivalent. `-C link-time-gc-sections` overrides the default of doing so unless
+    /// `-C link-dead-code` is given.
+    pub fn link_time_gc_sections(&self) -> bool {
+        self.opts.cg.link_time_gc_sections.unwrap_or_else(|| !self.link_dead_code())
+    }
+
+    /// Whether each function should be placed in its own section, so that the linker
+    /// can discard unused ones. Forcing `-C link-time-gc-sections` on also turns this on,
+    /// unless overridden.
+    pub fn function_sections(&self) -> bool {
+        self.opts.debugging_opts.function_sections.unwrap_or_else(|| {
+            self.opts.cg.link_time_gc_sections == Some(true) || self.target.function_sections
+        })
+    }
+
+    /// Whether each data item should be placed in its own section. Follows
+    /// `function_sections` unless overridden.
+    pub fn data_sections(&self) -> bool {
+        self.opts.debugging_opts.data_sections.unwrap_or_else(|| self.function_sections())
+    }
+
     pub fn instrument_coverage(&self) -> bool {
         self.opts.instrument_coverage()
     }
@@ -1177,7 +1528,9 @@ pub fn build_session(
         .map(|&(_, ref level)| *level == lint::Allow)
         .last()
         .unwrap_or(false);
-    let cap_lints_allow = sopts.lint_cap.map_or(false, |cap| cap == lint::Allow);
+    // A group capped above `allow` can still emit warnings, even with `--cap-lints allow`.
+    let cap_lints_allow = sopts.lint_cap.map_or(false, |cap| cap == lint::Allow)
+        && sopts.lint_group_caps.iter().all(|&(_, cap)| cap == lint::Allow);
     let can_emit_warnings = !(warnings_allow || cap_lints_allow);
 
     let write_dest = match diagnostics_output {
@@ -1199,7 +1552,15 @@ pub fn build_session(
         early_warn(sopts.error_format, &warning)
     }
 
-    let loader = file_loader.unwrap_or_else(|| Box::new(RealFileLoader));
+    let output_sandbox = if sopts.debugging_opts.output_sandbox.is_empty() {
+        None
+    } else {
+        Some(Arc::new(OutputSandbox::new(&sopts.debugging_opts.output_sandbox, &sysroot)))
+    };
+    let mut loader = file_loader.unwrap_or_else(|| Box::new(RealFileLoader));
+    if let Some(ref sandbox) = output_sandbox {
+        loader = Box::new(SandboxedFileLoader { inner: loader, sandbox: sandbox.clone() });
+    }
     let hash_kind = sopts.debugging_opts.src_hash_algorithm.unwrap_or_else(|| {
         if target_cfg.is_like_msvc {
             SourceFileHashAlgorithm::Sha1
@@ -1260,8 +1621,9 @@ pub fn build_session(
         local_crate_source_file.map(|path| file_path_mapping.map_prefix(path).0);
 
     let optimization_fuel = Lock::new(OptimizationFuel {
-        remaining: sopts.debugging_opts.fuel.as_ref().map_or(0, |i| i.1),
+        remaining: sopts.debugging_opts.fuel.as_ref().map_or(0, |i| i.2),
         out_of_fuel: false,
+        trace: Vec::new(),
     });
     let print_fuel = AtomicU64::new(0);
 
@@ -1275,6 +1637,7 @@ pub fn build_session(
         self_profiler,
         sopts.debugging_opts.time_passes || sopts.debugging_opts.time,
         sopts.debugging_opts.time_passes,
+        sopts.debugging_opts.resource_summary.is_some(),
     );
 
     let ctfe_backtrace = Lock::new(match env::var("RUSTC_CTFE_BACKTRACE") {
@@ -1283,6 +1646,9 @@ pub fn build_session(
         _ => CtfeBacktrace::Disabled,
     });
 
+    let compile_deadline =
+        sopts.debugging_opts.total_compile_time_limit.map(|limit| Instant::now() + limit);
+
     let asm_arch =
         if target_cfg.allow_asm { InlineAsmArch::from_str(&target_cfg.arch).ok() } else { None };
 
@@ -1309,6 +1675,7 @@ pub fn build_session(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        module_timings: Default::default(),
         optimization_fuel,
         print_fuel,
         jobserver: jobserver::client(),
@@ -1317,8 +1684,35 @@ pub fn build_session(
         miri_unleashed_features: Lock::new(Default::default()),
         asm_arch,
         target_features: FxHashSet::default(),
+        artifacts: Lock::new(Vec::new()),
+        artifact_sizes: Lock::new(FxHashMap::default()),
+        proc_macro_accesses: Lock::new(Vec::new()),
+        env_reads: Lock::new(BTreeMap::new()),
+        tool_path_entries: Lock::new(Vec::new()),
+        output_sandbox,
+        compile_deadline,
+        pass_times: Lock::new(Vec::new()),
+        current_passes: Lock::new(Vec::new()),
+        time_limit_suspended: AtomicUsize::new(0),
+        lint_pass_times: Lock::new(Vec::new()),
+        macro_stats: Default::default(),
+        polymorphized_instances: Lock::new(FxHashMap::default()),
+        shared_generics_providers: Lock::new(FxHashSet::default()),
+        query_keys: Default::default(),
     };
 
+    // These were read before the session existed, while parsing the options and building
+    // the target spec.
+    sess.record_env_read("RUSTC_BOOTSTRAP");
+    sess.record_env_read("RUSTC_CTFE_BACKTRACE");
+    if sess.target.is_like_osx {
+        sess.record_env_read(if sess.target.os == "macos" {
+            "MACOSX_DEPLOYMENT_TARGET"
+        } else {
+            "IPHONEOS_DEPLOYMENT_TARGET"
+        });
+    }
+
     validate_commandline_args_with_session_available(&sess);
 
     sess
@@ -1327,22 +1721,7 @@ pub fn build_session(
 // If it is useful to have a Session available already for validating a
 // commandline argument, you can do so here.
 fn validate_commandline_args_with_session_available(sess: &Session) {
-    // Since we don't know if code in an rlib will be linked to statically or
-    // dynamically downstream, rustc generates `__imp_` symbols that help linkers
-    // on Windows deal with this lack of knowledge (#27438). Unfortunately,
-    // these manually generated symbols confuse LLD when it tries to merge
-    // bitcode during ThinLTO. Therefore we disallow dynamic linking on Windows
-    // when compiling for LLD ThinLTO. This way we can validly just not generate
-    // the `dllimport` attributes and `__imp_` symbols in that case.
-    if sess.opts.cg.linker_plugin_lto.enabled()
-        && sess.opts.cg.prefer_dynamic
-        && sess.target.is_like_windows
-    {
-        sess.err(
-            "Linker plugin based LTO is not supported together with \
-                  `-C prefer-dynamic` when targeting Windows-like targets",
-        );
-    }
+    option_combinations::check_option_combinations(sess);
 
     // Make sure that any given profiling data actually exists so LLVM can't
     // decide to silently skip PGO.
@@ -1375,8 +1754,36 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         }
     }
 
+    // SafeStack has no runtime for bare-metal targets, but can be used there if the user
+    // provides the location of the unsafe stack pointer.
+    let bare_metal = sess.target.os == "none";
+    if sess.opts.debugging_opts.safestack_use_pointer_address {
+        if !sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::SAFESTACK) {
+            sess.warn(
+                "`-Z safestack-use-pointer-address` has no effect without `-Z sanitizer=safestack`",
+            );
+        }
+        if !bare_metal {
+            sess.err(&format!(
+                "`-Z safestack-use-pointer-address` is only supported on bare-metal targets, \
+                 not {}",
+                sess.opts.target_triple
+            ));
+        }
+    } else if bare_metal && sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::SAFESTACK) {
+        sess.struct_err(
+            "safestack sanitizer requires a runtime, which is not available for this target",
+        )
+        .help("provide `__safestack_pointer_address` and pass `-Z safestack-use-pointer-address`")
+        .emit();
+    }
+
     // Sanitizers can only be used on platforms that we know have working sanitizer codegen.
-    let supported_sanitizers = sess.target.options.supported_sanitizers;
+    let mut supported_sanitizers = sess.target.options.supported_sanitizers;
+    if bare_metal {
+        // Either accepted above, or already reported.
+        supported_sanitizers |= SanitizerSet::SAFESTACK;
+    }
     let unsupported_sanitizers = sess.opts.debugging_opts.sanitizer - supported_sanitizers;
     match unsupported_sanitizers.into_iter().count() {
         0 => {}
@@ -1401,16 +1808,205 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         );
     }
 
-    // LLVM CFI requires LTO.
+    // LLVM CFI requires LTO, which the linker does with `-C linker-plugin-lto`, e.g. together
+    // with C and C++ code built by Clang with `-flto -fsanitize=cfi`.
     if sess.is_sanitizer_cfi_enabled() {
-        if sess.opts.cg.lto == config::LtoCli::Unspecified
+        if (sess.opts.cg.lto == config::LtoCli::Unspecified
             || sess.opts.cg.lto == config::LtoCli::No
-            || sess.opts.cg.lto == config::LtoCli::Thin
+            || sess.opts.cg.lto == config::LtoCli::Thin)
+            && !sess.opts.cg.linker_plugin_lto.enabled()
+        {
+            sess.err("`-Zsanitizer=cfi` requires `-Clto` or `-Clinker-plugin-lto`");
+        }
+        // The type metadata that CFI checks calls against is only kept across modules in the
+        // regular part of a split LTO unit.
+        if sess.opts.cg.split_lto_unit == Some(false) {
+            sess.err("`-Zsanitizer=cfi` requires `-Csplit-lto-unit`");
+        }
+    }
+
+    // Reject code models that the target architecture has no support for, instead of
+    // letting LLVM abort or the linker fail with out-of-range relocations. Values coming
+    // from the target specification are trusted.
+    if let Some(code_model) = sess.opts.cg.code_model {
+        let arch = &*sess.target.arch;
+        let supported: &[CodeModel] = match arch {
+            "x86_64" => &[CodeModel::Small, CodeModel::Kernel, CodeModel::Medium, CodeModel::Large],
+            "aarch64" if sess.target.is_like_osx || sess.target.is_like_windows => {
+                &[CodeModel::Small, CodeModel::Large]
+            }
+            "aarch64" => &[CodeModel::Tiny, CodeModel::Small, CodeModel::Large],
+            "riscv32" | "riscv64" => &[CodeModel::Small, CodeModel::Medium],
+            _ => &[CodeModel::Small, CodeModel::Medium, CodeModel::Large],
+        };
+        if !supported.contains(&code_model) {
+            let supported =
+                supported.iter().map(|cm| format!("`{}`", cm.desc())).collect::<Vec<_>>();
+            sess.struct_err(&format!(
+                "`-C code-model={}` is not supported for target `{}`",
+                code_model.desc(),
+                sess.opts.target_triple
+            ))
+            .note(&format!("code models supported on `{}` are: {}", arch, supported.join(", ")))
+            .emit();
+        } else if code_model == CodeModel::Kernel {
+            // The kernel code model places code and data at fixed addresses in the top 2GB
+            // of the address space, which position independent code cannot assume.
+            let reloc_model = sess.relocation_model();
+            if matches!(reloc_model, RelocModel::Pic | RelocModel::Pie) {
+                sess.struct_err(&format!(
+                    "`-C code-model=kernel` is incompatible with the `{}` relocation model",
+                    reloc_model.desc()
+                ))
+                .note("the kernel code model requires `-C relocation-model=static`")
+                .emit();
+            }
+        }
+    }
+
+    if let Some(reloc_model) = sess.opts.cg.relocation_model {
+        // Read-only and read-write position independence are ARM-specific.
+        if matches!(reloc_model, RelocModel::Ropi | RelocModel::Rwpi | RelocModel::RopiRwpi)
+            && sess.target.arch != "arm"
         {
-            sess.err("`-Zsanitizer=cfi` requires `-Clto`");
+            sess.err(&format!(
+                "`-C relocation-model={}` is only supported on ARM targets, not `{}`",
+                reloc_model.desc(),
+                sess.opts.target_triple
+            ));
+        }
+
+        // Shared objects on ELF-like targets must be position independent, otherwise
+        // linking fails with relocations that "can not be used when making a shared object".
+        let elf_like =
+            !sess.target.is_like_windows && !sess.target.is_like_osx && !sess.target.is_like_wasm;
+        if elf_like && matches!(reloc_model, RelocModel::Static | RelocModel::Pie) {
+            let shared_crate_type = sess.opts.crate_types.iter().find(|&&crate_type| {
+                matches!(crate_type, CrateType::Dylib | CrateType::Cdylib | CrateType::ProcMacro)
+            });
+            if let Some(crate_type) = shared_crate_type {
+                sess.struct_err(&format!(
+                    "`-C relocation-model={}` is incompatible with `--crate-type {}`",
+                    reloc_model.desc(),
+                    crate_type
+                ))
+                .note("shared libraries must be built with `-C relocation-model=pic`")
+                .emit();
+            }
         }
     }
 
+    if sess.opts.debugging_opts.temps_dir_per_kind && sess.opts.debugging_opts.temps_dir.is_none() {
+        sess.warn("`-Z temps-dir-per-kind` has no effect without `-Z temps-dir`");
+    }
+
+    // Separate sections only help binary size when the linker garbage-collects unused ones,
+    // which `-C link-dead-code` and `-C link-time-gc-sections=no` disable.
+    if !sess.link_time_gc_sections() {
+        let cause = if sess.opts.cg.link_time_gc_sections == Some(false) {
+            "-C link-time-gc-sections=no"
+        } else {
+            "-C link-dead-code"
+        };
+        let explicit = [
+            ("function-sections", sess.opts.debugging_opts.function_sections),
+            ("data-sections", sess.opts.debugging_opts.data_sections),
+        ];
+        for (flag, _) in explicit.iter().filter(|(_, value)| *value == Some(true)) {
+            sess.struct_warn(&format!("`-Z {}` has no effect with `{}`", flag, cause))
+                .note("unused sections are only discarded when the linker garbage-collects them")
+                .emit();
+        }
+    }
+
+    if sess.opts.cg.link_time_gc_sections == Some(true) {
+        if sess.link_dead_code() {
+            sess.err("`-C link-time-gc-sections=yes` is incompatible with `-C link-dead-code`");
+        }
+        if sess.opts.debugging_opts.function_sections == Some(false) {
+            sess.struct_warn(
+                "`-C link-time-gc-sections=yes` can't discard unused functions \
+                 with `-Z function-sections=no`",
+            )
+            .note("the linker only discards whole sections, and all functions share one")
+            .emit();
+        }
+    }
+
+    if sess.opts.cg.relro_level.is_some() && !sess.target_produces_elf() {
+        sess.warn(&format!(
+            "`-C relro-level` is ignored for target {}, which doesn't produce ELF files",
+            sess.opts.target_triple
+        ));
+    }
+
+    if sess.opts.debugging_opts.shared_generics_dir.is_some() && !sess.opts.share_generics() {
+        sess.warn(
+            "`-Z shared-generics-dir` has no effect when generic instantiations aren't shared, \
+             use `-Z share-generics` to share them",
+        );
+    }
+
+    // Full RELRO always disables lazy binding, so it cannot be turned back on.
+    if let Some(bind_now) = sess.opts.debugging_opts.bind_now {
+        match sess.relro_level() {
+            RelroLevel::Full if !bind_now => sess.err(
+                "`-Z bind-now=no` is incompatible with full RELRO, \
+                 use `-C relro-level=partial` to allow lazy binding",
+            ),
+            RelroLevel::None if bind_now => sess.warn(&format!(
+                "`-Z bind-now` is not supported for target {} and will be ignored",
+                sess.opts.target_triple
+            )),
+            _ => {}
+        }
+    }
+
+    let guard = sess.opts.debugging_opts.stack_protector_guard;
+    let guard_offset = sess.opts.debugging_opts.stack_protector_guard_offset;
+    if (guard.is_some() || guard_offset.is_some())
+        && sess.opts.debugging_opts.stack_protector == StackProtector::None
+    {
+        sess.warn(
+            "`-Z stack-protector-guard` and `-Z stack-protector-guard-offset` have no effect \
+             without `-Z stack-protector`",
+        );
+    }
+    if guard_offset.is_some() && guard != Some(StackProtectorGuard::Tls) {
+        sess.err("`-Z stack-protector-guard-offset` requires `-Z stack-protector-guard=tls`");
+    }
+    // LLVM only knows how to address a TLS guard relative to the thread pointer on these.
+    if guard == Some(StackProtectorGuard::Tls)
+        && !matches!(&*sess.target.arch, "x86" | "x86_64" | "powerpc" | "powerpc64")
+    {
+        sess.err(&format!(
+            "`-Z stack-protector-guard=tls` is not supported for target {}",
+            sess.opts.target_triple
+        ));
+    }
+
+    // `-Z hardening` relies on the target's default relocation model for PIE.
+    if let Some(profile) = sess.opts.debugging_opts.hardening {
+        if sess.opts.cg.relocation_model.is_none()
+            && (!sess.target.position_independent_executables
+                || sess.relocation_model() != RelocModel::Pic)
+        {
+            sess.warn(&format!(
+                "`-Z hardening={}` cannot make executables position independent \
+                 for target {}",
+                profile.desc(),
+                sess.opts.target_triple
+            ));
+        }
+    }
+
+    if sess.opts.debugging_opts.plt == PltPolicy::Never && sess.target.needs_plt {
+        sess.warn(&format!(
+            "target {} requires the PLT, `-Z plt=no` may produce broken binaries",
+            sess.opts.target_triple
+        ));
+    }
+
     if sess.opts.debugging_opts.stack_protector != StackProtector::None {
         if !sess.target.options.supports_stack_protector {
             sess.warn(&format!(
@@ -1419,6 +2015,408 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
             ))
         }
     }
+
+    let layout_seed = sess.opts.debugging_opts.layout_seed;
+    if sess.opts.debugging_opts.randomize_layout {
+        // Printed so that a layout-dependent failure can be reproduced, in particular
+        // with `-Z layout-seed=random`.
+        sess.note_without_error(&format!(
+            "randomizing type layouts with seed {} (`-Z layout-seed`)",
+            layout_seed.unwrap_or(0)
+        ));
+    } else if layout_seed.is_some() {
+        sess.warn("`-Z layout-seed` has no effect without `-Z randomize-layout`");
+    }
+
+    if let Some(seed) = sess.opts.debugging_opts.shuffle_cgus {
+        // Printed so that a failure that depends on the order can be reproduced.
+        sess.note_without_error(&format!(
+            "shuffling the order of codegen units with seed {} (`-Z shuffle-cgus`)",
+            seed
+        ));
+    }
+
+    if sess.opts.debugging_opts.fuel_trace.is_some()
+        && sess.opts.debugging_opts.fuel.is_none()
+        && sess.opts.debugging_opts.print_fuel.is_none()
+    {
+        sess.err("`-Z fuel-trace` requires `-Z fuel` or `-Z print-fuel`");
+    }
+
+    // All are inserted by the same LLVM pass, which only takes one function to call.
+    let dopts = &sess.opts.debugging_opts;
+    if sess.opts.cg.instrument_functions && dopts.instrument_functions.is_some() {
+        sess.err("`-C instrument-functions` is incompatible with `-Z instrument-functions`");
+    } else if sess.instrument_function_hooks().is_some() && sess.instrument_mcount() {
+        let flag = if sess.opts.cg.instrument_functions { "-C" } else { "-Z" };
+        sess.err(&format!(
+            "`{} instrument-functions` is incompatible with `-Z instrument-mcount`",
+            flag
+        ));
+    } else if !sess.opts.cg.instrument_functions
+        && !sess.opts.cg.instrument_functions_exclude.is_empty()
+    {
+        sess.warn(
+            "`-C instrument-functions-exclude` has no effect without `-C instrument-functions`",
+        );
+    }
+    if dopts.instrument_functions.is_none()
+        && (!dopts.instrument_functions_include.is_empty()
+            || !dopts.instrument_functions_exclude.is_empty())
+    {
+        sess.warn(
+            "`-Z instrument-functions-include` and `-Z instrument-functions-exclude` have no \
+             effect without `-Z instrument-functions`",
+        );
+    }
+
+    if dopts.remark_dir.is_some() && sess.opts.cg.remark.is_empty() {
+        sess.warn("`-Z remark-dir` has no effect without `-C remark`");
+    }
+
+    if dopts.stack_protector_report.is_some() {
+        if sess.stack_protector() == StackProtector::None {
+            sess.warn(
+                "`-Z stack-protector-report` has nothing to report without `-Z stack-protector`",
+            );
+        }
+        // The report is made of the remarks of LLVM's stack protector pass, which `-Z remark-dir`
+        // would write to its own files instead.
+        if dopts.remark_dir.is_some() {
+            sess.err("`-Z stack-protector-report` can't be combined with `-Z remark-dir`");
+        }
+    }
+
+    if let Some(model) = dopts.aliasing_model {
+        // The finer-grained flags still win, but a contradiction is most likely a leftover.
+        if let Some(noalias) = dopts.mutable_noalias {
+            if noalias != model.mutable_noalias() {
+                sess.warn(&format!(
+                    "`-Z mutable-noalias={}` overrides the aliasing model of \
+                    `-Z aliasing-model={}`",
+                    if noalias { "yes" } else { "no" },
+                    model.as_str()
+                ));
+            }
+        }
+        if dopts.mir_emit_retag.is_some() && !model.emits_retags() {
+            sess.warn(&format!(
+                "`-Z mir-emit-retag` emits retags even though `-Z aliasing-model={}` has none",
+                model.as_str()
+            ));
+        }
+    }
+
+    if dopts.incremental_gc.is_some() && sess.opts.incremental.is_none() {
+        sess.warn("`-Z incremental-gc` has no effect without `-C incremental`");
+    }
+
+    validate_emscripten_flags(sess);
+
+    if let Some(sdk_root) = &dopts.sdk_root {
+        validate_sdk_root(sess, sdk_root);
+    }
+
+    if let Some(level) = dopts.android_api_level {
+        validate_android_ndk(sess, level);
+    }
+
+    let wasm_features = &dopts.wasm_features;
+    if !wasm_features.is_empty() && !sess.target.is_like_wasm {
+        sess.err("`-Z wasm-features` is only supported on WebAssembly targets");
+    }
+    for &feature in WasmFeature::ALL {
+        if sess.wasm_feature(feature) != Some(true) {
+            continue;
+        }
+        for &implied in feature.implied_features() {
+            if sess.wasm_feature(implied) == Some(false) {
+                sess.err(&format!(
+                    "the `{}` WebAssembly feature needs `{}`, which `-Z wasm-features` disables",
+                    feature.name(),
+                    implied.name()
+                ));
+            }
+        }
+    }
+    if sess.target.is_like_wasm
+        && sess.target.os != "emscripten"
+        && sess.opts.cg.panic == Some(PanicStrategy::Unwind)
+    {
+        if sess.wasm_feature(WasmFeature::ExceptionHandling) == Some(false) {
+            sess.err(
+                "`-C panic=unwind` needs the `exception-handling` WebAssembly feature, which \
+                 `-Z wasm-features` disables",
+            );
+        } else if !sess.wasm_exception_handling() {
+            sess.warn(
+                "`-C panic=unwind` needs `-Z wasm-features=+exception-handling` on WebAssembly \
+                 targets, without which panics abort",
+            );
+        }
+    }
+
+    if dopts.wasi_component.is_some() && sess.target.os != "wasi" {
+        sess.err("`-Z wasi-component` is only supported on WASI targets");
+    }
+
+    // LLVM only knows the hot-patchable prologue of x86, where the first instruction of a
+    // function can be replaced with a short jump to the padding before it.
+    if dopts.hotpatch == Some(Hotpatch::Prologue) && !matches!(&*sess.target.arch, "x86" | "x86_64")
+    {
+        sess.err(&format!(
+            "`-Z hotpatch` has no hot-patchable prologue for the `{}` architecture; use \
+             `-Z hotpatch=<entry>,<prefix>` to reserve NOPs at the start of and before each \
+             function instead",
+            sess.target.arch
+        ));
+    }
+
+    if sess.opts.debugging_opts.function_return != FunctionReturn::Keep {
+        if !matches!(&*sess.target.arch, "x86" | "x86_64") {
+            sess.err(&format!(
+                "`-Z function-return` is only supported on x86 and x86_64, not `{}`",
+                sess.target.arch
+            ));
+        }
+        // Like with GCC, the thunk is reached with a `jmp rel32`, which can't reach it from
+        // anywhere in the address space of the large code model.
+        if sess.code_model() == Some(CodeModel::Large) {
+            sess.err(
+                "`-Z function-return=thunk-extern` is incompatible with `-C code-model=large`",
+            );
+        }
+    }
+
+    // The prefix is prepended to `#[no_mangle]` names, which have to stay identifiers that C code
+    // and assemblers can refer to.
+    if let Some(prefix) = &sess.opts.debugging_opts.symbol_prefix {
+        let is_identifier = prefix.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier {
+            sess.err(&format!(
+                "`-Z symbol-prefix={}` must only contain ASCII letters, digits and underscores, \
+                and not start with a digit",
+                prefix
+            ));
+        }
+    }
+
+    validate_panic_abort_tests(sess);
+    validate_remap_path_prefix(sess);
+}
+
+/// Checks that `-Z sdk-root` names an SDK for the platform of the target, as far as its name
+/// tells. SDKs keep the name Xcode gives them, like `iPhoneOS15.2.sdk`, even outside of Xcode.
+fn validate_sdk_root(sess: &Session, sdk_root: &Path) {
+    const PLATFORMS: &[&str] = &[
+        "AppleTVOS",
+        "AppleTVSimulator",
+        "DriverKit",
+        "MacOSX",
+        "WatchOS",
+        "WatchSimulator",
+        "iPhoneOS",
+        "iPhoneSimulator",
+    ];
+
+    let target = &sess.target;
+    if target.vendor != "apple" {
+        sess.err("`-Z sdk-root` is only supported on Apple targets");
+        return;
+    }
+    if !sdk_root.is_dir() {
+        sess.err(&format!("the SDK root `{}` is not a directory", sdk_root.display()));
+        return;
+    }
+
+    let simulator = target.is_apple_simulator();
+    let expected = match target.os.as_str() {
+        "ios" if target.llvm_target.ends_with("-macabi") => "MacOSX",
+        "ios" if simulator => "iPhoneSimulator",
+        "ios" => "iPhoneOS",
+        "tvos" if simulator => "AppleTVSimulator",
+        "tvos" => "AppleTVOS",
+        "watchos" if simulator => "WatchSimulator",
+        "watchos" => "WatchOS",
+        _ => "MacOSX",
+    };
+    let name = match sdk_root.file_name().and_then(|name| name.to_str()) {
+        Some(name) if name.ends_with(".sdk") => name,
+        _ => return,
+    };
+    if let Some(&platform) = PLATFORMS.iter().find(|&&platform| name.starts_with(platform)) {
+        if platform != expected {
+            sess.err(&format!(
+                "the SDK root `{}` is a {} SDK, but the `{}` target needs a {} SDK",
+                sdk_root.display(),
+                platform,
+                sess.opts.target_triple,
+                expected
+            ));
+        }
+    }
+}
+
+/// Checks that the linker given with `-C linker` exists and, if it's the clang of an NDK, that
+/// the NDK has libraries for the API level of `-Z android-api-level`, rather than letting the
+/// link fail after the whole crate was compiled.
+fn validate_android_ndk(sess: &Session, level: u32) {
+    let linker = match &sess.opts.cg.linker {
+        // A linker without a directory is looked up in the `PATH` when linking.
+        Some(linker) if linker.parent().map_or(false, |dir| dir != Path::new("")) => linker,
+        _ => return,
+    };
+    if !linker.is_file() {
+        sess.err(&format!("the linker `{}` doesn't exist", linker.display()));
+        return;
+    }
+
+    // NDK r19 and later have the toolchain in `toolchains/llvm/prebuilt/<host>/bin`, and the
+    // libraries for each API level in `sysroot/usr/lib/<triple>/<level>` next to it.
+    let sysroot = match linker.parent().and_then(Path::parent) {
+        Some(toolchain) => toolchain.join("sysroot"),
+        None => return,
+    };
+    let ndk_triple = match sess.target.arch.as_str() {
+        "aarch64" => "aarch64-linux-android",
+        "arm" => "arm-linux-androideabi",
+        "x86" => "i686-linux-android",
+        "x86_64" => "x86_64-linux-android",
+        _ => return,
+    };
+    let lib_dir = sysroot.join("usr").join("lib").join(ndk_triple);
+    let mut levels: Vec<u32> = match std::fs::read_dir(&lib_dir) {
+        Ok(entries) => {
+            entries.filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok()).collect()
+        }
+        // Not an NDK toolchain, or not one we know the layout of.
+        Err(_) => return,
+    };
+    levels.sort_unstable();
+    if let (Some(&min), Some(&max)) = (levels.first(), levels.last()) {
+        if !levels.contains(&level) {
+            sess.struct_err(&format!(
+                "the NDK of the linker `{}` has no libraries for Android API level {}",
+                linker.display(),
+                level
+            ))
+            .note(&format!("it supports API levels {} to {} for `{}`", min, max, ndk_triple))
+            .emit();
+        }
+    }
+}
+
+/// Checks the settings of `-Z emscripten-flags` against the settings that Emscripten knows, so
+/// that misspelled ones aren't silently ignored by emcc.
+fn validate_emscripten_flags(sess: &Session) {
+    let flags = &sess.opts.debugging_opts.emscripten_flags;
+    if !flags.is_empty() && !sess.target.is_like_emscripten {
+        sess.err("`-Z emscripten-flags` is only supported on Emscripten targets");
+        return;
+    }
+    for (key, _) in flags {
+        if config::EMSCRIPTEN_SETTINGS_SET_BY_RUSTC.contains(&&**key) {
+            sess.err(&format!(
+                "the `{}` Emscripten setting is set by rustc and cannot be passed to \
+                 `-Z emscripten-flags`",
+                key
+            ));
+        } else if !config::EMSCRIPTEN_SETTINGS.contains(&&**key) {
+            let mut err = sess.struct_err(&format!(
+                "unknown Emscripten setting `{}` in `-Z emscripten-flags`",
+                key
+            ));
+            let settings: Vec<_> =
+                config::EMSCRIPTEN_SETTINGS.iter().map(|s| Symbol::intern(s)).collect();
+            match find_best_match_for_name(&settings, Symbol::intern(key), None) {
+                Some(setting) => {
+                    err.help(&format!("did you mean `{}`?", setting));
+                }
+                None => {
+                    err.note(&format!(
+                        "settings unknown to rustc can be passed with \
+                         `-C link-args=\"-s {}=...\"`",
+                        key
+                    ));
+                }
+            }
+            err.emit();
+        }
+    }
+}
+
+/// Checks the options of `-Z panic-abort-tests` against the panic strategy, before the test
+/// harness is generated.
+fn validate_panic_abort_tests(sess: &Session) {
+    let debugging_opts = &sess.opts.debugging_opts;
+    let panic_strategy = sess.panic_strategy();
+
+    // On targets that abort by default, tests keep the old behavior of aborting the whole
+    // run on the first failure; only an explicit `-C panic=abort` needs the subprocesses.
+    if sess.opts.test
+        && panic_strategy == PanicStrategy::Abort
+        && sess.target.panic_strategy != PanicStrategy::Abort
+        && !debugging_opts.panic_abort_tests
+    {
+        sess.struct_err(
+            "building tests with panic=abort is not supported without `-Zpanic_abort_tests`",
+        )
+        .note("`-C panic=abort` was passed, but the target unwinds by default")
+        .help("pass `-Z panic-abort-tests` to run each test in its own subprocess")
+        .emit();
+    }
+
+    let mut protocol_flags = Vec::new();
+    if let Some(env) = &debugging_opts.panic_abort_tests_env {
+        if env.is_empty() || env.contains(|c| c == '=' || c == '\0') {
+            sess.err(&format!(
+                "`-Z panic-abort-tests-env` must be the name of an environment variable, \
+                 not `{}`",
+                env
+            ));
+        }
+        protocol_flags.push("panic-abort-tests-env");
+    }
+    if debugging_opts.panic_abort_tests_format != PanicAbortTestsFormat::ExitCode {
+        protocol_flags.push("panic-abort-tests-format");
+    }
+    for flag in protocol_flags {
+        if !debugging_opts.panic_abort_tests {
+            sess.err(&format!("`-Z {}` requires `-Z panic-abort-tests`", flag));
+        } else if sess.opts.test && panic_strategy == PanicStrategy::Unwind {
+            sess.struct_warn(&format!("`-Z {}` has no effect with `-C panic=unwind`", flag))
+                .note("tests only run in subprocesses when built with `-C panic=abort`")
+                .emit();
+        }
+    }
+}
+
+/// Warns about `--remap-path-prefix` mappings that are likely mistakes, showing how they
+/// were parsed.
+fn validate_remap_path_prefix(sess: &Session) {
+    let mapping = &sess.opts.remap_path_prefix;
+    let parsed = |from: &Path, to: &Path| {
+        format!("parsed as FROM=`{}` and TO=`{}`", from.display(), to.display())
+    };
+    for (i, (from, to)) in mapping.iter().enumerate() {
+        if from.as_os_str().is_empty() {
+            sess.struct_warn("`--remap-path-prefix` with an empty FROM remaps every path")
+                .note(&parsed(from, to))
+                .emit();
+        }
+        // Later mappings take precedence, so only the last one for each FROM is used.
+        if let Some((_, later_to)) = mapping[i + 1..].iter().find(|(other, _)| other == from) {
+            sess.struct_warn(&format!(
+                "`--remap-path-prefix` remaps `{}` more than once",
+                from.display()
+            ))
+            .note(&parsed(from, to))
+            .note(&parsed(from, later_to))
+            .help("only the last mapping is used")
+            .emit();
+        }
+    }
 }
 
 /// Holds data on the current incremental compilation session, if there is one.
@@ -0,0 +1,530 @@
+//! The reports that a session prints or writes as it goes, like `--print build-env-report`,
+//! `-Z emit-artifact-manifest`, `-Z resource-summary` and `-Z profile-query-keys`, and the
+//! records they are made from.
+
+use super::*;
+
+/// The environment variables read by the compiler whose values `--print build-env-report`
+/// shows. Of other variables, like the ones read by `env!`, it only shows whether they are set.
+const BUILD_ENV_REPORT_VARS: &[&str] = &[
+    "IPHONEOS_DEPLOYMENT_TARGET",
+    "MACOSX_DEPLOYMENT_TARGET",
+    "PATH",
+    "RUSTC_BOOTSTRAP",
+    "RUSTC_CTFE_BACKTRACE",
+    "RUSTC_LOG",
+    "RUSTC_RETRY_LINKER_ON_SEGFAULT",
+    "SDKROOT",
+];
+
+/// The environment variables the sanitizer runtimes read their options from.
+const SANITIZER_OPTIONS_VARS: &[(SanitizerSet, &str)] = &[
+    (SanitizerSet::ADDRESS, "ASAN_OPTIONS"),
+    (SanitizerSet::HWADDRESS, "HWASAN_OPTIONS"),
+    (SanitizerSet::LEAK, "LSAN_OPTIONS"),
+    (SanitizerSet::MEMORY, "MSAN_OPTIONS"),
+    (SanitizerSet::THREAD, "TSAN_OPTIONS"),
+];
+
+/// How a query got the value for a key, for `-Z profile-query-keys`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryKeyOutcome {
+    /// The value was already computed in this session, or is green and didn't need loading.
+    CacheHit = 0,
+    /// The value is green and was loaded from the incremental cache.
+    LoadedFromDisk = 1,
+    /// The provider of the query ran.
+    Recomputed = 2,
+}
+
+impl Session {
+    /// Records a file or directory created by this session for `-Z emit-artifact-manifest`
+    /// and `-Z resource-summary`.
+    pub fn record_artifact(&self, path: &Path, role: &str) {
+        if self.opts.debugging_opts.emit_artifact_manifest.is_some() {
+            self.artifacts.lock().push((path.to_path_buf(), role.to_string()));
+        }
+        if self.opts.debugging_opts.resource_summary.is_some() {
+            if let Ok(size) = artifact_size(path) {
+                self.artifact_sizes.lock().insert(path.to_path_buf(), (role.to_string(), size));
+            }
+        }
+    }
+
+    pub(super) fn emit_artifact_manifest(&self) {
+        let manifest_path = match self.opts.debugging_opts.emit_artifact_manifest {
+            Some(ref path) => path,
+            None => return,
+        };
+
+        let mut artifacts = mem::take(&mut *self.artifacts.lock());
+        if let IncrCompSession::Finalized { ref session_directory } =
+            *self.incr_comp_session.borrow()
+        {
+            artifacts.push((session_directory.clone(), "incremental".to_string()));
+        }
+
+        let mut seen = FxHashSet::default();
+        let entries: Vec<Json> = artifacts
+            .into_iter()
+            .filter(|(path, _)| seen.insert(path.clone()))
+            .filter_map(|(path, role)| {
+                let size = artifact_size(&path).ok()?;
+                let mut obj = BTreeMap::new();
+                obj.insert("path".to_string(), path.display().to_string().to_json());
+                obj.insert("role".to_string(), role.to_json());
+                obj.insert("size".to_string(), size.to_json());
+                Some(Json::Object(obj))
+            })
+            .collect();
+
+        let mut manifest = BTreeMap::new();
+        manifest.insert("artifacts".to_string(), Json::Array(entries));
+        if let Err(e) = std::fs::write(manifest_path, Json::Object(manifest).to_string()) {
+            self.err(&format!(
+                "failed to write artifact manifest `{}`: {}",
+                manifest_path.display(),
+                e
+            ));
+        }
+    }
+
+    /// Records a file opened by a sandboxed proc macro for `-Z file-access-log`.
+    pub fn record_proc_macro_access(&self, path: &Path) {
+        if self.opts.debugging_opts.file_access_log.is_some() {
+            self.proc_macro_accesses.lock().push(path.to_path_buf());
+        }
+    }
+
+    /// Reads the environment variable `name`, recording it for `--print build-env-report`.
+    pub fn env_var_os(&self, name: &str) -> Option<OsString> {
+        let value = env::var_os(name);
+        if self.opts.prints.contains(&PrintRequest::BuildEnvReport) {
+            self.env_reads.lock().insert(name.to_string(), value.clone());
+        }
+        value
+    }
+
+    /// Like `env_var_os`, but fails if the variable isn't valid unicode, like `env::var`.
+    pub fn env_var(&self, name: &str) -> Result<String, env::VarError> {
+        match self.env_var_os(name) {
+            Some(value) => value.into_string().map_err(env::VarError::NotUnicode),
+            None => Err(env::VarError::NotPresent),
+        }
+    }
+
+    /// Records an environment variable that was read without going through the session,
+    /// for `--print build-env-report`.
+    pub fn record_env_read(&self, name: &str) {
+        self.env_var_os(name);
+    }
+
+    /// Records which of the directories in `search_path` the program `tool` will be found
+    /// in, for `--print build-env-report`. Tools given as paths aren't looked up.
+    pub fn record_tool_lookup(&self, tool: &Path, search_path: &[PathBuf]) {
+        if !self.opts.prints.contains(&PrintRequest::BuildEnvReport)
+            || tool.parent() != Some(Path::new(""))
+        {
+            return;
+        }
+        let tool = if cfg!(windows) && tool.extension().is_none() {
+            tool.with_extension("exe")
+        } else {
+            tool.to_path_buf()
+        };
+        if let Some(dir) = search_path.iter().find(|dir| dir.join(&tool).is_file()) {
+            self.tool_path_entries.lock().push((tool, dir.clone()));
+        }
+    }
+
+    pub(super) fn print_build_env_report(&self) {
+        if !self.opts.prints.contains(&PrintRequest::BuildEnvReport) {
+            return;
+        }
+
+        // The options of the sanitizer runtimes linked into the output.
+        let sanitizers = self.opts.debugging_opts.sanitizer;
+        for (sanitizer, name) in SANITIZER_OPTIONS_VARS {
+            if sanitizers.contains(*sanitizer) {
+                self.record_env_read(name);
+            }
+        }
+
+        let mut env_reads = self.env_reads.lock().clone();
+        // `env!` and `option_env!` read the environment through the `ParseSess`.
+        for &(name, value) in self.parse_sess.env_depinfo.borrow().iter() {
+            env_reads.insert(name.to_string(), value.map(|value| OsString::from(&*value.as_str())));
+        }
+        // Only the values of variables the compiler itself reads are shown, since others,
+        // such as the ones read by `env!`, may hold secrets.
+        let shown = |name: &str| {
+            BUILD_ENV_REPORT_VARS.contains(&name)
+                || SANITIZER_OPTIONS_VARS.iter().any(|&(_, var)| var == name)
+        };
+        if self.opts.print_json {
+            let env: BTreeMap<String, Json> = env_reads
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Some(value) if shown(&name) => value.to_string_lossy().to_json(),
+                        Some(_) => Json::Boolean(true),
+                        None => Json::Null,
+                    };
+                    (name, value)
+                })
+                .collect();
+            let tools: BTreeMap<String, Json> = self
+                .tool_path_entries
+                .lock()
+                .iter()
+                .map(|(tool, dir)| {
+                    (tool.display().to_string(), dir.display().to_string().to_json())
+                })
+                .collect();
+            let names = |set: SanitizerSet| set.into_iter().map(|s| s.to_string()).collect();
+            let mut sanitizer_report = BTreeMap::new();
+            sanitizer_report.insert("enabled".to_string(), Json::Array(names(sanitizers)));
+            sanitizer_report.insert(
+                "recover".to_string(),
+                Json::Array(names(self.opts.debugging_opts.sanitizer_recover)),
+            );
+            let mut report = BTreeMap::new();
+            report.insert("env".to_string(), Json::Object(env));
+            report.insert("sanitizers".to_string(), Json::Object(sanitizer_report));
+            report.insert("tools".to_string(), Json::Object(tools));
+            self.print_json(PrintRequest::BuildEnvReport, Json::Object(report));
+            return;
+        }
+        for (name, value) in env_reads {
+            match value {
+                Some(value) if shown(&name) => println!("{}={}", name, value.to_string_lossy()),
+                Some(_) => println!("{} (set)", name),
+                None => println!("{} (unset)", name),
+            }
+        }
+        if !sanitizers.is_empty() {
+            println!(
+                "sanitizers: {} (recover: {})",
+                sanitizers, self.opts.debugging_opts.sanitizer_recover
+            );
+        }
+        for (tool, dir) in self.tool_path_entries.lock().iter() {
+            println!("{} found in PATH entry {}", tool.display(), dir.display());
+        }
+    }
+
+    /// Prints the output of `--print` for `req` with `--print-json`: one line holding an
+    /// object with the request's name in `print` and its output in `value`.
+    pub fn print_json(&self, req: PrintRequest, value: Json) {
+        self.print_named_json(req.name(), value);
+    }
+
+    /// Like `print_json`, for a `--print` request the compiler doesn't know, like one
+    /// registered by a custom driver.
+    pub fn print_named_json(&self, name: &str, value: Json) {
+        let mut obj = BTreeMap::new();
+        obj.insert("print".to_string(), name.to_json());
+        obj.insert("value".to_string(), value);
+        println!("{}", Json::Object(obj));
+    }
+
+    /// Runs the lint pass `name`, recording the time it took for `-Z lint-timings`. `kind`
+    /// is the kind of pass, e.g. `early` or `late module`.
+    pub fn time_lint_pass<R>(&self, kind: &'static str, name: &str, f: impl FnOnce() -> R) -> R {
+        if !self.opts.debugging_opts.lint_timings {
+            return f();
+        }
+        let start = Instant::now();
+        let result = f();
+        self.lint_pass_times.lock().push((kind, name.to_string(), start.elapsed()));
+        result
+    }
+
+    /// Records an expansion of the macro `name` for `-Z macro-stats`. `kind` is the kind of
+    /// macro, e.g. `builtin` or `proc macro`.
+    pub fn record_macro_expansion(
+        &self,
+        kind: &'static str,
+        name: String,
+        tokens: usize,
+        time: Duration,
+    ) {
+        let mut stats = self.macro_stats.lock();
+        let (invocations, total_tokens, total_time) = stats.entry((kind, name)).or_default();
+        *invocations += 1;
+        *total_tokens += tokens;
+        *total_time += time;
+    }
+
+    /// Prints the summary of `-Z resource-summary`, as text or JSON.
+    pub(super) fn print_resource_summary(&self) {
+        let format = match self.opts.debugging_opts.resource_summary {
+            Some(format) => format,
+            None => return,
+        };
+
+        let usage = get_process_resource_usage();
+        let peak_threads = self.prof.thread_count_high_water_mark();
+        let mut bytes_written: BTreeMap<String, u64> = BTreeMap::new();
+        for (role, size) in self.artifact_sizes.lock().values() {
+            *bytes_written.entry(role.clone()).or_default() += size;
+        }
+
+        if format == ReportFormat::Json {
+            let secs = |time: Option<Duration>| time.map(|time| time.as_secs_f64()).to_json();
+            let mut summary = BTreeMap::new();
+            summary.insert("peak_rss".to_string(), usage.peak_rss.to_json());
+            summary.insert("user_time".to_string(), secs(usage.user_time));
+            summary.insert("system_time".to_string(), secs(usage.system_time));
+            summary.insert("peak_threads".to_string(), peak_threads.to_json());
+            summary.insert("bytes_written".to_string(), bytes_written.to_json());
+            let mut obj = BTreeMap::new();
+            obj.insert("resource_summary".to_string(), Json::Object(summary));
+            eprintln!("{}", Json::Object(obj));
+            return;
+        }
+
+        let unknown = || "unknown".to_string();
+        let secs = |time: Option<Duration>| time.map_or_else(unknown, duration_to_secs_str);
+        eprintln!("resource summary:");
+        eprintln!(
+            "  peak rss:      {}",
+            usage.peak_rss.map_or_else(unknown, |rss| format!("{}MB", rss / 1_000_000))
+        );
+        eprintln!("  user time:     {}", secs(usage.user_time));
+        eprintln!("  system time:   {}", secs(usage.system_time));
+        eprintln!("  peak threads:  {}", peak_threads.map_or_else(unknown, |n| n.to_string()));
+        eprintln!("  bytes written:");
+        for (role, size) in &bytes_written {
+            eprintln!("    {:<13} {}", role, size);
+        }
+    }
+
+    pub(super) fn write_module_timings(&self) {
+        if let Some(path) = &self.opts.debugging_opts.module_timings {
+            if let Err(err) = self.module_timings.write_report(path) {
+                self.err(&format!(
+                    "failed to write the `-Z module-timings` report to `{}`: {}",
+                    path.display(),
+                    err
+                ));
+            }
+        }
+    }
+
+    /// Records how the query `query` got the value for `key`, if it's the query of
+    /// `-Z profile-query-keys`.
+    #[inline]
+    pub fn record_query_key(&self, query: &str, key: &dyn fmt::Debug, outcome: QueryKeyOutcome) {
+        if unlikely!(self.opts.debugging_opts.profile_query_keys.is_some()) {
+            self.record_query_key_cold(query, key, outcome);
+        }
+    }
+
+    #[inline(never)]
+    #[cold]
+    fn record_query_key_cold(&self, query: &str, key: &dyn fmt::Debug, outcome: QueryKeyOutcome) {
+        if self.opts.debugging_opts.profile_query_keys.as_deref() == Some(query) {
+            self.query_keys.lock().entry(format!("{:?}", key)).or_default()[outcome as usize] += 1;
+        }
+    }
+
+    /// Writes the keys of the query of `-Z profile-query-keys` to `<query>.query-keys`, sorted
+    /// by key.
+    pub(super) fn write_query_keys(&self) {
+        let query = match &self.opts.debugging_opts.profile_query_keys {
+            Some(query) => query,
+            None => return,
+        };
+        let mut keys: Vec<_> = self.query_keys.lock().drain().collect();
+        keys.sort();
+
+        let mut out = String::from("cache hits\tloaded from disk\trecomputed\tkey\n");
+        for (key, [hits, loaded, recomputed]) in keys {
+            out.push_str(&format!("{}\t{}\t{}\t{}\n", hits, loaded, recomputed, key));
+        }
+        let path = PathBuf::from(format!("{}.query-keys", query));
+        if let Err(err) = std::fs::write(&path, out) {
+            self.err(&format!(
+                "failed to write the `-Z profile-query-keys` report to `{}`: {}",
+                path.display(),
+                err
+            ));
+        }
+    }
+
+    /// Prints the table of `-Z lint-timings`, slowest lint pass first.
+    pub(super) fn print_lint_timings(&self) {
+        if !self.opts.debugging_opts.lint_timings {
+            return;
+        }
+        let mut totals: FxHashMap<(&'static str, String), Duration> = FxHashMap::default();
+        for (kind, name, time) in self.lint_pass_times.lock().drain(..) {
+            *totals.entry((kind, name)).or_default() += time;
+        }
+        if totals.is_empty() {
+            return;
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let total: Duration = totals.iter().map(|(_, time)| *time).sum();
+
+        let width =
+            totals.iter().map(|((_, name), _)| name.len()).max().unwrap_or(0).max("pass".len());
+        eprintln!("{:<13} {:<width$} {:>9} {:>6}", "kind", "pass", "time (s)", "%", width = width);
+        for ((kind, name), time) in &totals {
+            let percent = if total.is_zero() {
+                0.0
+            } else {
+                time.as_secs_f64() * 100.0 / total.as_secs_f64()
+            };
+            eprintln!(
+                "{:<13} {:<width$} {:>9} {:>5.1}%",
+                kind,
+                name,
+                duration_to_secs_str(*time),
+                percent,
+                width = width
+            );
+        }
+        eprintln!(
+            "{:<13} {:<width$} {:>9}",
+            "total",
+            "",
+            duration_to_secs_str(total),
+            width = width
+        );
+    }
+
+    /// Prints the table of `-Z macro-stats`, slowest macro first.
+    pub(super) fn print_macro_stats(&self) {
+        let mut stats: Vec<_> = self.macro_stats.lock().drain().collect();
+        if stats.is_empty() {
+            return;
+        }
+        stats.sort_by(|a, b| (b.1).2.cmp(&(a.1).2).then_with(|| a.0.cmp(&b.0)));
+        let total: Duration = stats.iter().map(|(_, (_, _, time))| *time).sum();
+
+        let width =
+            stats.iter().map(|((_, name), _)| name.len()).max().unwrap_or(0).max("macro".len());
+        eprintln!(
+            "{:<11} {:<width$} {:>11} {:>9} {:>9} {:>6}",
+            "kind",
+            "macro",
+            "invocations",
+            "tokens",
+            "time (s)",
+            "%",
+            width = width
+        );
+        for ((kind, name), (invocations, tokens, time)) in &stats {
+            let percent = if total.is_zero() {
+                0.0
+            } else {
+                time.as_secs_f64() * 100.0 / total.as_secs_f64()
+            };
+            eprintln!(
+                "{:<11} {:<width$} {:>11} {:>9} {:>9} {:>5.1}%",
+                kind,
+                name,
+                invocations,
+                tokens,
+                duration_to_secs_str(*time),
+                percent,
+                width = width
+            );
+        }
+        eprintln!(
+            "{:<11} {:<width$} {:>11} {:>9} {:>9}",
+            "total",
+            "",
+            stats.iter().map(|(_, (invocations, _, _))| invocations).sum::<usize>(),
+            stats.iter().map(|(_, (_, tokens, _))| tokens).sum::<usize>(),
+            duration_to_secs_str(total),
+            width = width
+        );
+    }
+
+    pub(super) fn emit_file_access_log(&self) {
+        let log_path = match self.opts.debugging_opts.file_access_log {
+            Some(ref path) => path,
+            None => return,
+        };
+
+        // Every local file the `SourceMap` loaded, which covers `mod` items (including
+        // `#[path]`) and the `include!` family, plus files proc macros asked to track
+        // or were seen opening by `-Z proc-macro-sandbox`.
+        let mut files: Vec<(PathBuf, &str, Option<SourceFileHash>)> = self
+            .source_map()
+            .files()
+            .iter()
+            .filter(|file| !file.is_imported())
+            .filter_map(|file| match file.name {
+                FileName::Real(ref name) => name
+                    .local_path()
+                    .map(|path| (path.to_path_buf(), "source", Some(file.src_hash))),
+                _ => None,
+            })
+            .collect();
+        files.extend(
+            self.parse_sess
+                .file_depinfo
+                .borrow()
+                .iter()
+                .map(|path| (PathBuf::from(&*path.as_str()), "tracked", None)),
+        );
+        files.extend(
+            self.proc_macro_accesses.lock().iter().map(|path| (path.clone(), "proc-macro", None)),
+        );
+        // Sorting puts the entry carrying the hash first among those for the same path.
+        files.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.is_some().cmp(&a.2.is_some())));
+        files.dedup_by(|a, b| a.0 == b.0);
+
+        let entries: Vec<Json> = files
+            .into_iter()
+            .map(|(path, kind, src_hash)| {
+                let mut obj = BTreeMap::new();
+                obj.insert("path".to_string(), path.display().to_string().to_json());
+                obj.insert("kind".to_string(), kind.to_json());
+                // The hash the source map computed when it loaded the file, which is also
+                // the one recorded in debuginfo and crate metadata. Files the source map
+                // never loaded have none.
+                let hash = match src_hash {
+                    Some(hash) => {
+                        let hex: String =
+                            hash.hash_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+                        hex.to_json()
+                    }
+                    None => Json::Null,
+                };
+                obj.insert("hash".to_string(), hash);
+                Json::Object(obj)
+            })
+            .collect();
+
+        let hash_algorithm = match self.source_map().hash_kind() {
+            SourceFileHashAlgorithm::Md5 => "md5",
+            SourceFileHashAlgorithm::Sha1 => "sha1",
+            SourceFileHashAlgorithm::Sha256 => "sha256",
+        };
+        let mut log = BTreeMap::new();
+        log.insert("files".to_string(), Json::Array(entries));
+        log.insert("hash_algorithm".to_string(), hash_algorithm.to_json());
+        if let Err(e) = std::fs::write(log_path, Json::Object(log).to_string()) {
+            self.err(&format!("failed to write file access log `{}`: {}", log_path.display(), e));
+        }
+    }
+}
+
+/// Returns the size of a file, or the total size of the files in a directory.
+fn artifact_size(path: &Path) -> std::io::Result<u64> {
+    let metadata = std::fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        size += artifact_size(&entry?.path())?;
+    }
+    Ok(size)
+}
@@ -0,0 +1,87 @@
+//! `-Z total-compile-time-limit`, which aborts the compilation once it has run for longer than
+//! the limit, listing the passes that were in progress.
+
+use super::*;
+
+thread_local! {
+    /// How many passes run with `Session::time` are in progress on this thread, so that only
+    /// the outermost ones are listed when `-Z total-compile-time-limit` runs out.
+    static PASS_DEPTH: Cell<usize> = Cell::new(0);
+}
+
+impl Session {
+    /// Runs the pass `what`, first aborting the session if it has run for longer than
+    /// `-Z total-compile-time-limit`.
+    crate fn run_pass_within_time_limit<R>(
+        &self,
+        what: &'static str,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        if self.compile_deadline.is_none() {
+            return f();
+        }
+        self.check_compile_time_limit();
+
+        // Passes run on other threads, like codegen workers, are outermost there.
+        let outermost = PASS_DEPTH.with(|depth| depth.replace(depth.get() + 1)) == 0;
+        let _depth = OnDrop(|| PASS_DEPTH.with(|depth| depth.set(depth.get() - 1)));
+        if !outermost {
+            return f();
+        }
+        let start = Instant::now();
+        self.current_passes.lock().push((what, start));
+        let _current = OnDrop(|| {
+            let mut current_passes = self.current_passes.lock();
+            if let Some(i) = current_passes.iter().position(|&pass| pass == (what, start)) {
+                current_passes.swap_remove(i);
+            }
+        });
+        let result = f();
+        self.pass_times.lock().push((what, start.elapsed()));
+        result
+    }
+
+    /// Aborts the session if it has run for longer than `-Z total-compile-time-limit`. This is
+    /// checked when a pass or a query starts, so that the session stops cleanly rather than
+    /// in the middle of a pass, except while the limit is suspended by `without_time_limit`.
+    #[inline]
+    pub fn check_compile_time_limit(&self) {
+        if let Some(deadline) = self.compile_deadline {
+            if Instant::now() > deadline && self.time_limit_suspended.load(SeqCst) == 0 {
+                self.abort_on_time_limit();
+            }
+        }
+    }
+
+    /// Runs `f` without checking `-Z total-compile-time-limit`, for work that would leave
+    /// broken outputs behind if it was stopped halfway, like linking.
+    pub fn without_time_limit<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.time_limit_suspended.fetch_add(1, SeqCst);
+        let _suspended = OnDrop(|| {
+            self.time_limit_suspended.fetch_sub(1, SeqCst);
+        });
+        f()
+    }
+
+    fn abort_on_time_limit(&self) -> ! {
+        let limit = self.opts.debugging_opts.total_compile_time_limit.unwrap();
+        let mut err = self.struct_fatal(&format!(
+            "compilation exceeded the time limit of {}s set with `-Z total-compile-time-limit`",
+            duration_to_secs_str(limit)
+        ));
+        for &(what, start) in self.current_passes.lock().iter() {
+            err.note(&format!(
+                "stopped {}s into `{}`",
+                duration_to_secs_str(start.elapsed()),
+                what
+            ));
+        }
+        let mut pass_times = self.pass_times.lock().clone();
+        pass_times.sort_by(|a, b| b.1.cmp(&a.1));
+        for (what, time) in pass_times.iter().take(5) {
+            err.note(&format!("`{}` took {}s", what, duration_to_secs_str(*time)));
+        }
+        err.emit();
+        FatalError.raise()
+    }
+}
@@ -4,6 +4,7 @@
 use rustc_ast::tokenstream::CanSynthesizeMissingTokens;
 use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
 use rustc_data_structures::profiling::VerboseTimingGuard;
+use rustc_errors::TimingEvent;
 use std::path::{Path, PathBuf};
 
 pub type NtToTokenstream = fn(&Nonterminal, &ParseSess, CanSynthesizeMissingTokens) -> TokenStream;
@@ -13,7 +14,26 @@ pub fn timer<'a>(&'a self, what: &'static str) -> VerboseTimingGuard<'a> {
         self.prof.verbose_generic_activity(what)
     }
     pub fn time<R>(&self, what: &'static str, f: impl FnOnce() -> R) -> R {
-        self.prof.verbose_generic_activity(what).run(f)
+        self.run_pass_within_time_limit(what, || self.prof.verbose_generic_activity(what).run(f))
+    }
+
+    /// Reports the start of the section `name` of the compilation with `--json=timings`.
+    pub fn timing_section_start(&self, name: &str) {
+        if self.opts.json_timings {
+            self.diagnostic().emit_timing_section(name, TimingEvent::Start);
+        }
+    }
+    /// Reports the end of the section `name` of the compilation with `--json=timings`.
+    pub fn timing_section_end(&self, name: &str) {
+        if self.opts.json_timings {
+            self.diagnostic().emit_timing_section(name, TimingEvent::End);
+        }
+    }
+    pub fn timing_section<R>(&self, name: &str, f: impl FnOnce() -> R) -> R {
+        self.timing_section_start(name);
+        let result = f();
+        self.timing_section_end(name);
+        result
     }
 }
 
@@ -320,7 +320,7 @@ fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             Anon(_) => write!(fmt, "<anon>"),
             ProcMacroSourceCode(_) => write!(fmt, "<proc-macro source code>"),
             CfgSpec(_) => write!(fmt, "<cfgspec>"),
-            CliCrateAttr(_) => write!(fmt, "<crate attribute>"),
+            CliCrateAttr(_) => write!(fmt, "<-Z crate-attr>"),
             Custom(ref s) => write!(fmt, "<{}>", s),
             DocTest(ref path, _) => write!(fmt, "{}", path.display()),
             InlineAsm(_) => write!(fmt, "<inline asm>"),
@@ -1272,10 +1272,14 @@ pub struct SourceFileHash {
 
 impl SourceFileHash {
     pub fn new(kind: SourceFileHashAlgorithm, src: &str) -> SourceFileHash {
+        Self::from_bytes(kind, src.as_bytes())
+    }
+
+    /// Hashes arbitrary file contents, e.g. those loaded by `include_bytes!`.
+    pub fn from_bytes(kind: SourceFileHashAlgorithm, data: &[u8]) -> SourceFileHash {
         let mut hash = SourceFileHash { kind, value: Default::default() };
         let len = hash.hash_len();
         let value = &mut hash.value[..len];
-        let data = src.as_bytes();
         match kind {
             SourceFileHashAlgorithm::Md5 => {
                 value.copy_from_slice(&Md5::digest(data));
@@ -102,6 +102,11 @@ pub trait FileLoader {
 
     /// Read the contents of a UTF-8 file into memory.
     fn read_file(&self, path: &Path) -> io::Result<String>;
+
+    /// Read the contents of a file into memory without any decoding.
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
 }
 
 /// A FileLoader that uses std::fs to load real files.
@@ -207,6 +212,10 @@ pub fn path_mapping(&self) -> &FilePathMapping {
         &self.path_mapping
     }
 
+    pub fn hash_kind(&self) -> SourceFileHashAlgorithm {
+        self.hash_kind
+    }
+
     pub fn file_exists(&self, path: &Path) -> bool {
         self.file_loader.file_exists(path)
     }
@@ -222,17 +231,26 @@ pub fn load_file(&self, path: &Path) -> io::Result<Lrc<SourceFile>> {
     /// Unlike `load_file`, guarantees that no normalization like BOM-removal
     /// takes place.
     pub fn load_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
-        // Ideally, this should use `self.file_loader`, but it can't
-        // deal with binary files yet.
-        let bytes = fs::read(path)?;
+        let bytes = self.file_loader.read_binary_file(path)?;
 
         // We need to add file to the `SourceMap`, so that it is present
         // in dep-info. There's also an edge case that file might be both
         // loaded as a binary via `include_bytes!` and as proper `SourceFile`
         // via `mod`, so we try to use real file contents and not just an
         // empty string.
-        let text = std::str::from_utf8(&bytes).unwrap_or("").to_string();
-        self.new_source_file(path.to_owned().into(), text);
+        //
+        // Files that are not UTF-8 have no text to hash, so their bytes are hashed
+        // instead, which keeps `src_hash` identifying the file's contents.
+        let (text, src_hash) = match std::str::from_utf8(&bytes) {
+            Ok(text) => (text.to_string(), None),
+            Err(_) => (String::new(), Some(SourceFileHash::from_bytes(self.hash_kind, &bytes))),
+        };
+        self.try_new_source_file(path.to_owned().into(), text, src_hash).unwrap_or_else(
+            |OffsetOverflowError| {
+                eprintln!("fatal error: rustc does not support files larger than 4GB");
+                crate::fatal_error::FatalError.raise()
+            },
+        );
         Ok(bytes)
     }
 
@@ -275,7 +293,7 @@ fn allocate_address_space(&self, size: usize) -> Result<usize, OffsetOverflowErr
     /// If a file already exists in the `SourceMap` with the same ID, that file is returned
     /// unmodified.
     pub fn new_source_file(&self, filename: FileName, src: String) -> Lrc<SourceFile> {
-        self.try_new_source_file(filename, src).unwrap_or_else(|OffsetOverflowError| {
+        self.try_new_source_file(filename, src, None).unwrap_or_else(|OffsetOverflowError| {
             eprintln!("fatal error: rustc does not support files larger than 4GB");
             crate::fatal_error::FatalError.raise()
         })
@@ -285,6 +303,7 @@ fn try_new_source_file(
         &self,
         filename: FileName,
         src: String,
+        src_hash: Option<SourceFileHash>,
     ) -> Result<Lrc<SourceFile>, OffsetOverflowError> {
         // Note that filename may not be a valid path, eg it may be `<anon>` etc,
         // but this is okay because the directory determined by `path.pop()` will
@@ -298,12 +317,12 @@ fn try_new_source_file(
             None => {
                 let start_pos = self.allocate_address_space(src.len())?;
 
-                let source_file = Lrc::new(SourceFile::new(
-                    filename,
-                    src,
-                    Pos::from_usize(start_pos),
-                    self.hash_kind,
-                ));
+                let mut source_file =
+                    SourceFile::new(filename, src, Pos::from_usize(start_pos), self.hash_kind);
+                if let Some(src_hash) = src_hash {
+                    source_file.src_hash = src_hash;
+                }
+                let source_file = Lrc::new(source_file);
 
                 // Let's make sure the file_id we generated above actually matches
                 // the ID we generate for the SourceFile we just created.
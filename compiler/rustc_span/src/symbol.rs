@@ -697,6 +697,7 @@
         globs,
         gt,
         half_open_range_patterns,
+        hardening,
         hash,
         hexagon_target_feature,
         hidden,
@@ -980,6 +981,7 @@
         pointer,
         pointer_trait_fmt,
         poll,
+        polymorphize,
         position,
         post_dash_lto: "post-lto",
         powerpc_target_feature,
@@ -1012,6 +1014,7 @@
         proc_macro_mod,
         proc_macro_non_items,
         proc_macro_path_invoc,
+        proc_macro_pure,
         profiler_builtins,
         profiler_runtime,
         ptr_guaranteed_eq,
@@ -1170,6 +1173,7 @@
         rustfmt,
         rvalue_static_promotion,
         s,
+        safestack,
         sanitize,
         sanitizer_runtime,
         saturating_add,
@@ -1302,6 +1306,7 @@
         sync,
         t32,
         target_abi,
+        target_android_api_level,
         target_arch,
         target_endian,
         target_env,
@@ -130,6 +130,11 @@ fn get_symbol_hash<'tcx>(
             tcx.def_path_hash(instantiating_crate.as_def_id())
                 .stable_crate_id()
                 .hash_stable(&mut hcx, &mut hasher);
+            // The instantiating crate doesn't appear in the path, so its `-Z symbol-prefix`
+            // namespaces the instance through the hash.
+            if let Some(prefix) = tcx.symbol_prefix(instantiating_crate) {
+                prefix.as_str().hash_stable(&mut hcx, &mut hasher);
+            }
         }
 
         // We want to avoid accidental collision between different types of instances.
@@ -255,6 +260,10 @@ fn print_const(self, ct: &'tcx ty::Const<'tcx>) -> Result<Self::Const, Self::Err
     }
 
     fn path_crate(self, cnum: CrateNum) -> Result<Self::Path, Self::Error> {
+        // The `-Z symbol-prefix` of the crate namespaces the paths into it.
+        if let Some(prefix) = self.tcx.symbol_prefix(cnum) {
+            self.write_str(&prefix.as_str())?;
+        }
         self.write_str(&self.tcx.crate_name(cnum).as_str())?;
         Ok(self)
     }
@@ -95,7 +95,7 @@
 #[macro_use]
 extern crate rustc_middle;
 
-use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc_hir::Node;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::mir::mono::{InstantiationMode, MonoItem};
@@ -131,6 +131,12 @@ pub fn provide(providers: &mut Providers) {
 // instance from the local crate. In particular, it will also look up the
 // correct symbol name of instances from upstream crates.
 fn symbol_name_provider<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> ty::SymbolName<'tcx> {
+    // Instances exported by another crate of the `-Z shared-generics-dir` index are linked to
+    // with the symbol name that crate gave them.
+    if let Some((_, symbol_name)) = instance.shared_generics_provider(tcx) {
+        return ty::SymbolName::new(tcx, symbol_name);
+    }
+
     let symbol_name = compute_symbol_name(tcx, instance, || {
         // This closure determines the instantiating crate for instances that
         // need an instantiating-crate-suffix for their symbol name, in order
@@ -184,7 +190,8 @@ fn compute_symbol_name<'tcx>(
     // Foreign items by default use no mangling for their symbol name. There's a
     // few exceptions to this rule though:
     //
-    // * This can be overridden with the `#[link_name]` attribute
+    // * This can be overridden with the `#[link_name]` attribute, whose symbol
+    //   version, if any, is stripped with `-Z android-api-level`
     //
     // * On the wasm32 targets there is a bug (or feature) in LLD [1] where the
     //   same-named symbol when imported from different wasm modules will get
@@ -202,19 +209,34 @@ fn compute_symbol_name<'tcx>(
             || !tcx.wasm_import_module_map(def_id.krate).contains_key(&def_id))
     {
         if let Some(name) = attrs.link_name {
+            if let Some(level) = tcx.sess.opts.debugging_opts.android_api_level {
+                return strip_android_symbol_version(tcx, def_id, &name.as_str(), level);
+            }
             return name.to_string();
         }
         return tcx.item_name(def_id).to_string();
     }
 
+    // Names that aren't mangled get the `-Z symbol-prefix` of the crate in front of them, except
+    // the symbols the standard library and its runtime look up by name.
+    let prefix = if attrs.flags.contains(CodegenFnAttrFlags::RUSTC_STD_INTERNAL_SYMBOL) {
+        None
+    } else {
+        tcx.symbol_prefix(def_id.krate)
+    };
+    let prefixed = |name: &str| match prefix {
+        Some(prefix) => format!("{}{}", prefix, name),
+        None => name.to_string(),
+    };
+
     if let Some(name) = attrs.export_name {
         // Use provided name
-        return name.to_string();
+        return prefixed(&name.as_str());
     }
 
     if attrs.flags.contains(CodegenFnAttrFlags::NO_MANGLE) {
         // Don't mangle
-        return tcx.item_name(def_id).to_string();
+        return prefixed(&tcx.item_name(def_id).as_str());
     }
 
     let avoid_cross_crate_conflicts =
@@ -264,6 +286,45 @@ fn compute_symbol_name<'tcx>(
     symbol
 }
 
+/// Strips the version of a versioned `#[link_name = "name@VERSION"]` when building for an Android
+/// API level, as the NDK's libraries for the API level already bind `name` to the version of
+/// Bionic available at that level. Errors if the version is one of Bionic's that is newer than
+/// the API level.
+fn strip_android_symbol_version(
+    tcx: TyCtxt<'_>,
+    def_id: DefId,
+    link_name: &str,
+    level: u32,
+) -> String {
+    let (name, version) = match link_name.split_once('@') {
+        // `name@@VERSION` names the default version.
+        Some((name, version)) => (name, version.trim_start_matches('@')),
+        None => return link_name.to_string(),
+    };
+    // The API level each version of Bionic's symbols was introduced at.
+    let since = match version {
+        "LIBC_N" => 24,
+        "LIBC_O" => 26,
+        "LIBC_P" => 28,
+        "LIBC_Q" => 29,
+        "LIBC_R" => 30,
+        "LIBC_S" => 31,
+        "LIBC_T" => 33,
+        _ => 0,
+    };
+    if since > level {
+        tcx.sess.span_err(
+            tcx.def_span(def_id),
+            &format!(
+                "`{}` is only available from Android API level {}, but `-Z android-api-level` \
+                 is {}",
+                link_name, since, level
+            ),
+        );
+    }
+    name.to_string()
+}
+
 fn is_generic(substs: SubstsRef<'_>) -> bool {
     substs.non_erasable_generics().next().is_some()
 }
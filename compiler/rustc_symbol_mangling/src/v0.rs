@@ -736,8 +736,12 @@ fn path_crate(self, cnum: CrateNum) -> Result<Self::Path, Self::Error> {
         self.push("C");
         let stable_crate_id = self.tcx.def_path_hash(cnum.as_def_id()).stable_crate_id();
         self.push_disambiguator(stable_crate_id.to_u64());
-        let name = self.tcx.crate_name(cnum).as_str();
-        self.push_ident(&name);
+        let name = self.tcx.crate_name(cnum);
+        // The `-Z symbol-prefix` of the crate namespaces the paths into it.
+        match self.tcx.symbol_prefix(cnum) {
+            Some(prefix) => self.push_ident(&format!("{}{}", prefix, name)),
+            None => self.push_ident(&name.as_str()),
+        }
         Ok(self)
     }
 
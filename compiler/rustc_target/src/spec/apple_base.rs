@@ -79,6 +79,22 @@ pub fn macos_llvm_target(arch: &str) -> String {
     format!("{}-apple-macosx{}.{}.0", arch, major, minor)
 }
 
+/// Replaces the deployment target in the `llvm_target` of a macOS or iOS target, which is
+/// otherwise taken from `MACOSX_DEPLOYMENT_TARGET` or `IPHONEOS_DEPLOYMENT_TARGET`. Returns
+/// `None` for the targets whose deployment target is fixed, like Mac Catalyst and tvOS.
+pub fn llvm_target_with_deployment_target(
+    llvm_target: &str,
+    (major, minor): (u32, u32),
+) -> Option<String> {
+    let (arch, rest) = llvm_target.split_once("-apple-")?;
+    let os = ["macosx", "ios"].into_iter().find(|os| rest.starts_with(os))?;
+    let suffix = rest.find('-').map_or("", |i| &rest[i..]);
+    if suffix == "-macabi" {
+        return None;
+    }
+    Some(format!("{}-apple-{}{}.{}.0{}", arch, os, major, minor, suffix))
+}
+
 pub fn macos_link_env_remove() -> Vec<String> {
     let mut env_remove = Vec::with_capacity(2);
     // Remove the `SDKROOT` environment variable if it's clearly set for the wrong platform, which
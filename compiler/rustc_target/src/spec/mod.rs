@@ -296,6 +296,20 @@ pub enum RelocModel {
     RopiRwpi,
 }
 
+impl RelocModel {
+    pub fn desc(&self) -> &str {
+        match *self {
+            RelocModel::Static => "static",
+            RelocModel::Pic => "pic",
+            RelocModel::Pie => "pie",
+            RelocModel::DynamicNoPic => "dynamic-no-pic",
+            RelocModel::Ropi => "ropi",
+            RelocModel::Rwpi => "rwpi",
+            RelocModel::RopiRwpi => "ropi-rwpi",
+        }
+    }
+}
+
 impl FromStr for RelocModel {
     type Err = ();
 
@@ -315,16 +329,7 @@ fn from_str(s: &str) -> Result<RelocModel, ()> {
 
 impl ToJson for RelocModel {
     fn to_json(&self) -> Json {
-        match *self {
-            RelocModel::Static => "static",
-            RelocModel::Pic => "pic",
-            RelocModel::Pie => "pie",
-            RelocModel::DynamicNoPic => "dynamic-no-pic",
-            RelocModel::Ropi => "ropi",
-            RelocModel::Rwpi => "rwpi",
-            RelocModel::RopiRwpi => "ropi-rwpi",
-        }
-        .to_json()
+        self.desc().to_json()
     }
 }
 
@@ -337,6 +342,18 @@ pub enum CodeModel {
     Large,
 }
 
+impl CodeModel {
+    pub fn desc(&self) -> &str {
+        match *self {
+            CodeModel::Tiny => "tiny",
+            CodeModel::Small => "small",
+            CodeModel::Kernel => "kernel",
+            CodeModel::Medium => "medium",
+            CodeModel::Large => "large",
+        }
+    }
+}
+
 impl FromStr for CodeModel {
     type Err = ();
 
@@ -354,14 +371,7 @@ fn from_str(s: &str) -> Result<CodeModel, ()> {
 
 impl ToJson for CodeModel {
     fn to_json(&self) -> Json {
-        match *self {
-            CodeModel::Tiny => "tiny",
-            CodeModel::Small => "small",
-            CodeModel::Kernel => "kernel",
-            CodeModel::Medium => "medium",
-            CodeModel::Large => "large",
-        }
-        .to_json()
+        self.desc().to_json()
     }
 }
 
@@ -604,6 +614,7 @@ pub struct SanitizerSet: u8 {
         const THREAD  = 1 << 3;
         const HWADDRESS = 1 << 4;
         const CFI     = 1 << 5;
+        const SAFESTACK = 1 << 6;
     }
 }
 
@@ -619,6 +630,7 @@ fn as_str(self) -> Option<&'static str> {
             SanitizerSet::MEMORY => "memory",
             SanitizerSet::THREAD => "thread",
             SanitizerSet::HWADDRESS => "hwaddress",
+            SanitizerSet::SAFESTACK => "safestack",
             _ => return None,
         })
     }
@@ -652,6 +664,7 @@ fn into_iter(self) -> Self::IntoIter {
             SanitizerSet::MEMORY,
             SanitizerSet::THREAD,
             SanitizerSet::HWADDRESS,
+            SanitizerSet::SAFESTACK,
         ]
         .iter()
         .copied()
@@ -1016,6 +1029,32 @@ fn $module() {
     ("x86_64-unknown-none", x86_64_unknown_none),
 }
 
+/// Short names that `--target` accepts in place of the triples of common targets, as listed
+/// by `--print target-aliases`.
+pub const TARGET_ALIASES: &[(&str, &str)] = &[
+    ("arm64-android", "aarch64-linux-android"),
+    ("arm64-ios", "aarch64-apple-ios"),
+    ("arm64-linux", "aarch64-unknown-linux-gnu"),
+    ("arm64-linux-musl", "aarch64-unknown-linux-musl"),
+    ("arm64-macos", "aarch64-apple-darwin"),
+    ("arm64-windows", "aarch64-pc-windows-msvc"),
+    ("wasi", "wasm32-wasi"),
+    ("wasm32", "wasm32-unknown-unknown"),
+    ("x64-freebsd", "x86_64-unknown-freebsd"),
+    ("x64-linux", "x86_64-unknown-linux-gnu"),
+    ("x64-linux-musl", "x86_64-unknown-linux-musl"),
+    ("x64-macos", "x86_64-apple-darwin"),
+    ("x64-windows", "x86_64-pc-windows-msvc"),
+    ("x64-windows-gnu", "x86_64-pc-windows-gnu"),
+    ("x86-linux", "i686-unknown-linux-gnu"),
+    ("x86-windows", "i686-pc-windows-msvc"),
+];
+
+/// Returns the triple `alias` stands for, if it is one of the [`TARGET_ALIASES`].
+pub fn resolve_target_alias(alias: &str) -> Option<&'static str> {
+    TARGET_ALIASES.iter().find(|&&(name, _)| name == alias).map(|&(_, triple)| triple)
+}
+
 /// Warnings encountered when parsing the target `json`.
 ///
 /// Includes fields that weren't recognized and fields that don't have the expected type.
@@ -1547,6 +1586,90 @@ fn deref_mut(&mut self) -> &mut Self::Target {
 }
 
 impl Target {
+    /// Overrides the deployment target of a macOS or iOS target, returning whether it has one
+    /// that can be overridden.
+    pub fn set_apple_deployment_target(&mut self, version: (u32, u32)) -> bool {
+        if self.vendor != "apple" {
+            return false;
+        }
+        match apple_base::llvm_target_with_deployment_target(&self.llvm_target, version) {
+            Some(llvm_target) => {
+                self.llvm_target = llvm_target;
+                if self.os == "macos" {
+                    // See `apple_base::opts` for why ELF TLS depends on the deployment target.
+                    self.options.has_elf_tls = version >= (10, 7);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the minimum API level of an Android target, which LLVM takes from the environment
+    /// of the target triple, returning whether the target is an Android target.
+    pub fn set_android_api_level(&mut self, level: u32) -> bool {
+        if self.os != "android" {
+            return false;
+        }
+        let llvm_target = self.llvm_target.trim_end_matches(|c: char| c.is_ascii_digit());
+        self.llvm_target = format!("{}{}", llvm_target, level);
+        true
+    }
+
+    /// Sets the ABI of a RISC-V target, which LLVM takes as the ABI name and which decides
+    /// whether floating-point arguments are passed in floating-point registers. Returns why
+    /// the ABI can't be used with the target, if it can't.
+    pub fn set_riscv_abi(&mut self, abi: &str) -> Result<(), String> {
+        let abis: &[&str] = match &*self.arch {
+            "riscv32" => &["ilp32", "ilp32f", "ilp32d"],
+            "riscv64" => &["lp64", "lp64f", "lp64d"],
+            _ => return Err(format!("the architecture `{}` isn't RISC-V", self.arch)),
+        };
+        if !abis.contains(&abi) {
+            return Err(format!(
+                "the ABIs of `{}` are: {}",
+                self.arch,
+                abis.iter().map(|abi| format!("`{}`", abi)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        self.options.llvm_abiname = abi.to_string();
+        Ok(())
+    }
+
+    /// Enables or disables the linker relaxation of a RISC-V target, returning whether the
+    /// target is a RISC-V target. LLVM only emits the relocations that let the linker relax
+    /// sequences of instructions into shorter ones when the `relax` feature is enabled, and
+    /// the linker only relaxes the ones of other object files without `--no-relax`.
+    pub fn set_riscv_relax(&mut self, relax: bool) -> bool {
+        if !self.arch.starts_with("riscv") {
+            return false;
+        }
+        let feature = if relax { "+relax" } else { "-relax" };
+        if self.options.features.is_empty() {
+            self.options.features = feature.to_string();
+        } else {
+            self.options.features = format!("{},{}", self.options.features, feature);
+        }
+        if !relax {
+            let args = &mut self.options.pre_link_args;
+            args.entry(LinkerFlavor::Gcc).or_default().push("-Wl,--no-relax".to_string());
+            args.entry(LinkerFlavor::Ld).or_default().push("--no-relax".to_string());
+            args.entry(LinkerFlavor::Lld(LldFlavor::Ld))
+                .or_default()
+                .push("--no-relax".to_string());
+        }
+        true
+    }
+
+    /// Whether the target is a simulator of an Apple device, like all the x86 targets of Apple
+    /// devices, whose LLVM target doesn't always say so.
+    pub fn is_apple_simulator(&self) -> bool {
+        self.vendor == "apple"
+            && self.os != "macos"
+            && (self.llvm_target.ends_with("-simulator")
+                || (self.arch.starts_with("x86") && !self.llvm_target.ends_with("-macabi")))
+    }
+
     /// Given a function ABI, turn it into the correct ABI for this target.
     pub fn adjust_abi(&self, abi: Abi) -> Abi {
         match abi {
@@ -1878,6 +2001,7 @@ macro_rules! key {
                                 Some("memory") => SanitizerSet::MEMORY,
                                 Some("thread") => SanitizerSet::THREAD,
                                 Some("hwaddress") => SanitizerSet::HWADDRESS,
+                                Some("safestack") => SanitizerSet::SAFESTACK,
                                 Some(s) => return Err(format!("unknown sanitizer {}", s)),
                                 _ => return Err(format!("not a string: {:?}", s)),
                             };
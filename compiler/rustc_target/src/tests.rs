@@ -1,4 +1,4 @@
-use crate::spec::Target;
+use crate::spec::{Target, TARGETS, TARGET_ALIASES};
 use rustc_serialize::json::Json;
 use std::str::FromStr;
 
@@ -57,3 +57,11 @@ fn no_warnings_for_valid_target() {
     let warnings = Target::from_json(json).unwrap().1;
     assert_eq!(warnings.warning_messages().len(), 0);
 }
+
+#[test]
+fn target_aliases_name_builtin_targets() {
+    for &(alias, triple) in TARGET_ALIASES {
+        assert!(TARGETS.contains(&triple), "alias `{}` names unknown target `{}`", alias, triple);
+        assert!(!TARGETS.contains(&alias), "alias `{}` shadows a target", alias);
+    }
+}
@@ -118,7 +118,7 @@
 use rustc_middle::ty::{self, Ty, TyCtxt, UserType};
 use rustc_session::config;
 use rustc_session::parse::feature_err;
-use rustc_session::Session;
+use rustc_session::{Session, TimingPhase};
 use rustc_span::source_map::DUMMY_SP;
 use rustc_span::symbol::{kw, Ident};
 use rustc_span::{self, BytePos, MultiSpan, Span};
@@ -129,6 +129,7 @@
 use rustc_trait_selection::traits::error_reporting::suggestions::ReturnsVisitor;
 
 use std::cell::{Ref, RefCell, RefMut};
+use std::time::Instant;
 
 use crate::require_c_abi_if_c_variadic;
 use crate::util::common::indenter;
@@ -916,6 +917,16 @@ fn visit_foreign_item(&mut self, _: &'tcx hir::ForeignItem<'tcx>) {}
 }
 
 fn typeck_item_bodies(tcx: TyCtxt<'_>, (): ()) {
+    if tcx.sess.opts.debugging_opts.module_timings.is_some() {
+        // The bodies are checked one at a time, so that the time spent on each is known.
+        for body_owner_def_id in tcx.hir().body_owners() {
+            let start = Instant::now();
+            tcx.ensure().typeck(body_owner_def_id);
+            let elapsed = start.elapsed();
+            tcx.record_module_time(body_owner_def_id.to_def_id(), TimingPhase::FrontEnd, elapsed);
+        }
+        return;
+    }
     tcx.hir().par_body_owners(|body_owner_def_id| tcx.ensure().typeck(body_owner_def_id));
 }
 
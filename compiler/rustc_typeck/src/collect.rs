@@ -2885,10 +2885,15 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, id: DefId) -> CodegenFnAttrs {
                         codegen_fn_attrs.no_sanitize |= SanitizerSet::THREAD;
                     } else if item.has_name(sym::hwaddress) {
                         codegen_fn_attrs.no_sanitize |= SanitizerSet::HWADDRESS;
+                    } else if item.has_name(sym::safestack) {
+                        codegen_fn_attrs.no_sanitize |= SanitizerSet::SAFESTACK;
                     } else {
                         tcx.sess
                             .struct_span_err(item.span(), "invalid argument for `no_sanitize`")
-                            .note("expected one of: `address`, `hwaddress`, `memory` or `thread`")
+                            .note(
+                                "expected one of: `address`, `cfi`, `hwaddress`, `memory`, \
+                                 `safestack` or `thread`",
+                            )
                             .emit();
                     }
                 }
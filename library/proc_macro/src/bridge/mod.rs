@@ -8,6 +8,7 @@
 
 #![deny(unsafe_code)]
 
+use crate::lint_levels::LintLevel;
 use crate::{Delimiter, Level, LineColumn, Spacing};
 use std::fmt;
 use std::hash::Hash;
@@ -56,6 +57,7 @@ macro_rules! with_api {
                 fn drop($self: $S::FreeFunctions);
                 fn track_env_var(var: &str, value: Option<&str>);
                 fn track_path(path: &str);
+                fn lint_level(lint: &str) -> Option<LintLevel>;
             },
             TokenStream {
                 fn drop($self: $S::TokenStream);
@@ -363,6 +365,7 @@ fn unmark(self) -> Self::Unmarked {
     Delimiter,
     Level,
     LineColumn,
+    LintLevel,
     Spacing,
     Bound<usize>,
 }
@@ -384,6 +387,14 @@ enum Level {
     }
 );
 rpc_encode_decode!(struct LineColumn { line, column });
+rpc_encode_decode!(
+    enum LintLevel {
+        Allow,
+        Warn,
+        Deny,
+        Forbid,
+    }
+);
 rpc_encode_decode!(
     enum Spacing {
         Alone,
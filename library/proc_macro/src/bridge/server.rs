@@ -268,6 +268,77 @@ enum State<T> {
     }
 }
 
+/// Runs the client on the current thread like `SameThread`, unless a `setup`
+/// function is given, in which case the client runs on a fresh thread like
+/// `CrossThread1` and `setup` is called on that thread first. This lets the
+/// server apply per-thread restrictions to the client without being affected
+/// by them itself.
+pub struct MaybeCrossThread {
+    pub setup: Option<std::sync::Arc<dyn Fn() + Send + Sync>>,
+    /// The stack size of the client's thread, which should match the server's so
+    /// that running on another thread does not change which clients overflow it.
+    pub stack_size: Option<usize>,
+}
+
+impl ExecutionStrategy for MaybeCrossThread {
+    fn run_bridge_and_client<D: Copy + Send + 'static>(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer<u8>,
+        run_client: extern "C" fn(Bridge<'_>, D) -> Buffer<u8>,
+        client_data: D,
+        force_show_panics: bool,
+    ) -> Buffer<u8> {
+        use std::sync::mpsc::channel;
+
+        let setup = match self.setup {
+            Some(ref setup) => setup.clone(),
+            None => {
+                return SameThread.run_bridge_and_client(
+                    dispatcher,
+                    input,
+                    run_client,
+                    client_data,
+                    force_show_panics,
+                );
+            }
+        };
+
+        let (req_tx, req_rx) = channel();
+        let (res_tx, res_rx) = channel();
+
+        let mut builder = thread::Builder::new();
+        if let Some(stack_size) = self.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        let join_handle = builder
+            .spawn(move || {
+                setup();
+
+                let mut dispatch = |b| {
+                    req_tx.send(b).unwrap();
+                    res_rx.recv().unwrap()
+                };
+
+                run_client(
+                    Bridge {
+                        cached_buffer: input,
+                        dispatch: (&mut dispatch).into(),
+                        force_show_panics,
+                    },
+                    client_data,
+                )
+            })
+            .unwrap();
+
+        for b in req_rx {
+            res_tx.send(dispatcher.dispatch(b)).unwrap();
+        }
+
+        join_handle.join().unwrap()
+    }
+}
+
 fn run_server<
     S: Server,
     I: Encode<HandleStore<MarkedTypes<S>>>,
@@ -1307,3 +1307,37 @@ pub fn path<P: AsRef<str>>(path: P) {
         crate::bridge::client::FreeFunctions::track_path(path);
     }
 }
+
+/// Access to the lint levels at the expansion site of a macro.
+#[unstable(feature = "proc_macro_lint_levels", issue = "none")]
+pub mod lint_levels {
+
+    /// The level of a lint.
+    #[unstable(feature = "proc_macro_lint_levels", issue = "none")]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum LintLevel {
+        /// The lint is allowed, or expected with `#[expect]`.
+        Allow,
+        /// The lint is a warning.
+        Warn,
+        /// The lint is an error.
+        Deny,
+        /// The lint is an error and can't be allowed anymore.
+        Forbid,
+    }
+
+    /// Returns the level of a lint at the call site of the macro being expanded.
+    ///
+    /// The level is the one the compiler would use for the lint there, taking into account
+    /// the command line (including `--cap-lints`) and the lint attributes of the crate and of
+    /// the items, statements and expressions enclosing the macro call. The attributes of the
+    /// item an attribute or derive macro is applied to aren't taken into account.
+    ///
+    /// Returns `None` if `lint` is not the name of a lint known to the compiler, like
+    /// `dead_code` or `clippy::needless_return`, including if it is the name of a lint group.
+    #[unstable(feature = "proc_macro_lint_levels", issue = "none")]
+    pub fn level<L: AsRef<str>>(lint: L) -> Option<LintLevel> {
+        crate::bridge::client::FreeFunctions::lint_level(lint.as_ref())
+    }
+}
@@ -196,6 +196,37 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
     }
 }
 
+/// The start of the report written by `write_subprocess_report`.
+const SUBPROCESS_REPORT_PREFIX: &str = r#"{ "type": "test", "event": "#;
+
+/// Writes the result of a test run in a subprocess as a JSON `test` event on the last line
+/// of stdout, for `SubprocessFormat::Json`.
+pub(crate) fn write_subprocess_report(desc: &TestDesc, result: &TestResult) {
+    let name = EscapedString(desc.name.as_slice());
+    let report = match result {
+        TestResult::TrOk => format!(r#"{}"ok", "name": "{}" }}"#, SUBPROCESS_REPORT_PREFIX, name),
+        TestResult::TrFailedMsg(msg) => format!(
+            r#"{}"failed", "name": "{}", "message": "{}" }}"#,
+            SUBPROCESS_REPORT_PREFIX,
+            name,
+            EscapedString(msg)
+        ),
+        _ => format!(r#"{}"failed", "name": "{}" }}"#, SUBPROCESS_REPORT_PREFIX, name),
+    };
+    let mut stdout = io::stdout();
+    // The subprocess exits right after, so there is no one to report errors to.
+    let _ = writeln!(stdout, "{}", report).and_then(|()| stdout.flush());
+}
+
+/// Removes the report written by `write_subprocess_report` from the end of `stdout`.
+pub(crate) fn strip_subprocess_report(stdout: &mut Vec<u8>) {
+    let body = stdout.strip_suffix(b"\n").unwrap_or(&stdout[..]);
+    let start = body.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    if body[start..].starts_with(SUBPROCESS_REPORT_PREFIX.as_bytes()) {
+        stdout.truncate(start);
+    }
+}
+
 /// A formatting utility used to print strings with characters in need of escaping.
 /// Base code taken form `libserialize::json::escape_str`
 struct EscapedString<S: AsRef<str>>(S);
@@ -35,7 +35,9 @@
 // Public reexports
 pub use self::bench::{black_box, Bencher};
 pub use self::console::run_tests_console;
-pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic};
+pub use self::options::{
+    ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic, SubprocessFormat,
+};
 pub use self::types::TestName::*;
 pub use self::types::*;
 pub use self::ColorConfig::*;
@@ -152,10 +154,36 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=abort.
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
+    test_main_static_abort_inner(tests, Options::new().panic_abort(true))
+}
+
+/// Like `test_main_static_abort`, but with the environment variable that selects the
+/// test to run in a subprocess and the format in which the subprocess reports the result
+/// (`exit-code` or `json`) given explicitly.
+///
+/// This is the entry point for the main function generated by `rustc --test`
+/// when panic=abort and `-Z panic-abort-tests-env` or `-Z panic-abort-tests-format`
+/// are given.
+pub fn test_main_static_abort_with_protocol(
+    tests: &[&TestDescAndFn],
+    subprocess_env: &'static str,
+    subprocess_format: &'static str,
+) {
+    let subprocess_format = match subprocess_format {
+        "exit-code" => SubprocessFormat::ExitCode,
+        "json" => SubprocessFormat::Json,
+        _ => panic!("unknown subprocess format '{}'", subprocess_format),
+    };
+    let options =
+        Options::new().panic_abort(true).subprocess_protocol(subprocess_env, subprocess_format);
+    test_main_static_abort_inner(tests, options)
+}
+
+fn test_main_static_abort_inner(tests: &[&TestDescAndFn], options: Options) {
     // If we're being run in SpawnedSecondary mode, run the test here. run_test
     // will then exit the process.
-    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
-        env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+    if let Ok(name) = env::var(options.subprocess_env) {
+        env::remove_var(options.subprocess_env);
         let test = tests
             .iter()
             .filter(|test| test.desc.name.as_slice() == name)
@@ -167,12 +195,12 @@ pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
             StaticTestFn(f) => f,
             _ => panic!("only static tests are supported"),
         };
-        run_test_in_spawned_subprocess(desc, Box::new(testfn));
+        run_test_in_spawned_subprocess(desc, Box::new(testfn), options.subprocess_format);
     }
 
     let args = env::args().collect::<Vec<_>>();
     let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+    test_main(&args, owned_tests, Some(options))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -484,6 +512,7 @@ struct TestRunOpts {
         pub nocapture: bool,
         pub concurrency: Concurrent,
         pub time: Option<time::TestTimeOptions>,
+        pub options: Options,
     }
 
     fn run_test_inner(
@@ -513,6 +542,7 @@ fn run_test_inner(
                 opts.time.is_some(),
                 monitor_ch,
                 opts.time,
+                opts.options,
             ),
         };
 
@@ -540,8 +570,13 @@ fn run_test_inner(
         }
     }
 
-    let test_run_opts =
-        TestRunOpts { strategy, nocapture: opts.nocapture, concurrency, time: opts.time_options };
+    let test_run_opts = TestRunOpts {
+        strategy,
+        nocapture: opts.nocapture,
+        concurrency,
+        time: opts.time_options,
+        options: opts.options,
+    };
 
     match testfn {
         DynBenchFn(bencher) => {
@@ -629,13 +664,14 @@ fn spawn_test_subprocess(
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    options: Options,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
         let current_exe = &args[0];
 
         let mut command = Command::new(current_exe);
-        command.env(SECONDARY_TEST_INVOKER_VAR, desc.name.as_slice());
+        command.env(options.subprocess_env, desc.name.as_slice());
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
@@ -654,7 +690,11 @@ fn spawn_test_subprocess(
             TestExecTime(duration)
         });
 
-        let std::process::Output { stdout, stderr, status } = output;
+        let std::process::Output { mut stdout, stderr, status } = output;
+        if options.subprocess_format == SubprocessFormat::Json {
+            // The result is taken from the exit code, so the report is only noise here.
+            formatters::strip_subprocess_report(&mut stdout);
+        }
         let mut test_output = stdout;
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
@@ -677,7 +717,11 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, testfn: Box<dyn FnOnce() + Send>) -> ! {
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    testfn: Box<dyn FnOnce() + Send>,
+    format: SubprocessFormat,
+) -> ! {
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
         let test_result = match panic_info {
@@ -695,6 +739,10 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, testfn: Box<dyn FnOnce() + Sen
             builtin_panic_hook(info);
         }
 
+        if format == SubprocessFormat::Json {
+            formatters::write_subprocess_report(&desc, &test_result);
+        }
+
         if let TrOk = test_result {
             process::exit(test_result::TR_OK);
         } else {
@@ -64,17 +64,35 @@ pub enum RunStrategy {
     SpawnPrimary,
 }
 
+/// How a test run in a subprocess reports its result to the process that spawned it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SubprocessFormat {
+    /// Only through the exit code of the subprocess.
+    ExitCode,
+    /// Also with a JSON `test` event, like those of `--format json`, as the last line of
+    /// stdout.
+    Json,
+}
+
 /// Options for the test run defined by the caller (instead of CLI arguments).
 /// In case we want to add other options as well, just add them in this struct.
 #[derive(Copy, Clone, Debug)]
 pub struct Options {
     pub display_output: bool,
     pub panic_abort: bool,
+    /// The environment variable naming the test a subprocess should run.
+    pub subprocess_env: &'static str,
+    pub subprocess_format: SubprocessFormat,
 }
 
 impl Options {
     pub fn new() -> Options {
-        Options { display_output: false, panic_abort: false }
+        Options {
+            display_output: false,
+            panic_abort: false,
+            subprocess_env: crate::SECONDARY_TEST_INVOKER_VAR,
+            subprocess_format: SubprocessFormat::ExitCode,
+        }
     }
 
     pub fn display_output(mut self, display_output: bool) -> Options {
@@ -86,4 +104,14 @@ pub fn panic_abort(mut self, panic_abort: bool) -> Options {
         self.panic_abort = panic_abort;
         self
     }
+
+    pub fn subprocess_protocol(
+        mut self,
+        subprocess_env: &'static str,
+        subprocess_format: SubprocessFormat,
+    ) -> Options {
+        self.subprocess_env = subprocess_env;
+        self.subprocess_format = subprocess_format;
+        self
+    }
 }
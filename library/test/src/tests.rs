@@ -823,3 +823,21 @@ fn should_sort_failures_before_printing_them() {
     let bpos = s.find("b").unwrap();
     assert!(apos < bpos);
 }
+
+#[test]
+fn strip_subprocess_report() {
+    let ok = br#"{ "type": "test", "event": "ok", "name": "a" }"#;
+    let mut stdout = [&b"output\n"[..], ok, b"\n"].concat();
+    formatters::strip_subprocess_report(&mut stdout);
+    assert_eq!(stdout, b"output\n");
+
+    let failed = br#"{ "type": "test", "event": "failed", "name": "a" }"#;
+    let mut stdout = [&failed[..], b"\n"].concat();
+    formatters::strip_subprocess_report(&mut stdout);
+    assert!(stdout.is_empty());
+
+    // Anything else is left alone.
+    let mut stdout = b"output\nmore output\n".to_vec();
+    formatters::strip_subprocess_report(&mut stdout);
+    assert_eq!(stdout, b"output\nmore output\n");
+}
@@ -9,7 +9,7 @@
 use rustc_session::config::{
     self, parse_crate_types_from_list, parse_externs, parse_target_triple, CrateType,
 };
-use rustc_session::config::{get_cmd_lint_options, nightly_options};
+use rustc_session::config::{get_cmd_lint_group_caps, get_cmd_lint_options, nightly_options};
 use rustc_session::config::{CodegenOptions, DebuggingOptions, ErrorOutputType, Externs};
 use rustc_session::getopts;
 use rustc_session::lint::Level;
@@ -101,6 +101,8 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
     crate describe_lints: bool,
     /// What level to cap lints at.
     crate lint_cap: Option<Level>,
+    /// What level to cap the lints of each group at, in place of `lint_cap`.
+    crate lint_group_caps: Vec<(String, Level)>,
 
     // Options specific to running doctests
     /// Whether we should run doctests instead of generating docs.
@@ -188,6 +190,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("lint_opts", &self.lint_opts)
             .field("describe_lints", &self.describe_lints)
             .field("lint_cap", &self.lint_cap)
+            .field("lint_group_caps", &self.lint_group_caps)
             .field("should_test", &self.should_test)
             .field("test_args", &self.test_args)
             .field("test_run_directory", &self.test_run_directory)
@@ -688,6 +691,7 @@ fn println_condition(condition: Condition) {
         let call_locations = crate::scrape_examples::load_call_locations(with_examples, &diag)?;
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+        let lint_group_caps = get_cmd_lint_group_caps(matches, error_format);
 
         Ok(Options {
             input,
@@ -708,6 +712,7 @@ fn println_condition(condition: Condition) {
             lint_opts,
             describe_lints,
             lint_cap,
+            lint_group_caps,
             should_test,
             test_args,
             default_passes,
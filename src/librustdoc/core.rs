@@ -178,6 +178,7 @@ impl<'tcx> DocContext<'tcx> {
     RustdocOptions {
         input,
         crate_name,
+        crate_version,
         proc_macro_crate,
         error_format,
         libs,
@@ -191,6 +192,7 @@ impl<'tcx> DocContext<'tcx> {
         lint_opts,
         describe_lints,
         lint_cap,
+        lint_group_caps,
         ..
     }: RustdocOptions,
 ) -> rustc_interface::Config {
@@ -225,6 +227,7 @@ impl<'tcx> DocContext<'tcx> {
         crate_types,
         lint_opts,
         lint_cap,
+        lint_group_caps,
         cg: codegen_options,
         externs,
         target_triple: target,
@@ -235,6 +238,7 @@ impl<'tcx> DocContext<'tcx> {
         edition,
         describe_lints,
         crate_name,
+        crate_version,
         ..Options::default()
     };
 
@@ -281,6 +285,7 @@ impl<'tcx> DocContext<'tcx> {
         }),
         make_codegen_backend: None,
         registry: rustc_driver::diagnostics_registry(),
+        custom_print_requests: Vec::new(),
     }
 }
 
@@ -103,6 +103,7 @@
         override_queries: None,
         make_codegen_backend: None,
         registry: rustc_driver::diagnostics_registry(),
+        custom_print_requests: Vec::new(),
     };
 
     let test_args = options.test_args.clone();
@@ -197,7 +198,18 @@
                 })
                 .unwrap_or("warn")
                 .to_string();
-            let uext = UnusedExterns { lint_level, unused_extern_names };
+            // The locations and paths are the same in every report.
+            let unused_externs = unused_extern_names
+                .iter()
+                .filter_map(|name| {
+                    unused_extern_reports
+                        .iter()
+                        .flat_map(|uexts| &uexts.unused_externs)
+                        .find(|uext| uext.name == *name)
+                        .cloned()
+                })
+                .collect();
+            let uext = UnusedExterns { lint_level, unused_extern_names, unused_externs };
             let unused_extern_json = serde_json::to_string(&uext).unwrap();
             eprintln!("{}", unused_extern_json);
         }
@@ -287,6 +299,19 @@ struct UnusedExterns {
     lint_level: String,
     /// List of unused externs by their names.
     unused_extern_names: Vec<String>,
+    /// List of unused externs with the location of their dependency and their crate file.
+    #[serde(default)]
+    unused_externs: Vec<UnusedExtern>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct UnusedExtern {
+    /// The name of the crate.
+    name: String,
+    /// The location of the dependency given with `--extern-location`.
+    location: Option<serde_json::Value>,
+    /// The path of the crate file given with `--extern`.
+    path: Option<String>,
 }
 
 fn run_test(
@@ -349,6 +374,10 @@ fn run_test(
     for debugging_option_str in &rustdoc_options.debugging_opts_strs {
         compiler.arg("-Z").arg(&debugging_option_str);
     }
+    // These come last so that they override the flags the crate is documented with.
+    for flag in &rustdoc_options.debugging_opts.doctest_compile_options {
+        compiler.args(&flag.to_args());
+    }
     if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
         compiler.arg("--emit=metadata");
     }
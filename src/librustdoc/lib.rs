@@ -534,6 +534,15 @@ fn opts() -> Vec<RustcOptGroup> {
                 "LEVEL",
             )
         }),
+        unstable("cap-lints-group", |o| {
+            o.optmulti(
+                "",
+                "cap-lints-group",
+                "Set the most restrictive lint level of the lints of a group, \
+                 in place of the one set by `--cap-lints`.",
+                "GROUP=LEVEL",
+            )
+        }),
         unstable("index-page", |o| {
             o.optopt("", "index-page", "Markdown file to be used as index page", "PATH")
         }),
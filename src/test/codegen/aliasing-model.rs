@@ -0,0 +1,27 @@
+// Checks that `-Z aliasing-model` decides whether mutable references are marked `noalias`, and
+// that an explicit `-Z mutable-noalias` still wins over it.
+//
+// revisions: STACKED TREE LLVM TREE_NOALIAS
+// compile-flags: -O
+// [STACKED] compile-flags: -Z aliasing-model=stacked-borrows
+// [TREE] compile-flags: -Z aliasing-model=tree-borrows
+// [LLVM] compile-flags: -Z aliasing-model=llvm-default
+// [TREE_NOALIAS] compile-flags: -Z aliasing-model=tree-borrows -Z mutable-noalias=yes
+
+#![crate_type = "lib"]
+
+// STACKED: @mutable_borrow(i32* noalias
+// TREE: @mutable_borrow(i32* align 4
+// LLVM: @mutable_borrow(i32* noalias
+// TREE_NOALIAS: @mutable_borrow(i32* noalias
+#[no_mangle]
+pub fn mutable_borrow(x: &mut i32) {
+    *x = 0;
+}
+
+// Shared references without interior mutability are `noalias` under every model.
+// CHECK: @shared_borrow(i32* noalias
+#[no_mangle]
+pub fn shared_borrow(x: &i32) -> i32 {
+    *x
+}
@@ -0,0 +1,34 @@
+// Checks that `-Z android-api-level` sets the API level in the target triple, exposes it as
+// `cfg(target_android_api_level)`, and strips the version of versioned link names.
+
+// compile-flags: -O --target=aarch64-linux-android --crate-type=rlib -Z android-api-level=24
+// needs-llvm-components: aarch64
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+
+// CHECK: target triple = "aarch64-linux-android24"
+
+// CHECK: define void @api_level_24()
+#[cfg(target_android_api_level = "24")]
+#[no_mangle]
+pub fn api_level_24() {}
+
+// CHECK-NOT: @api_level_21
+#[cfg(target_android_api_level = "21")]
+#[no_mangle]
+pub fn api_level_21() {}
+
+extern "C" {
+    #[link_name = "getifaddrs@LIBC_N"]
+    fn getifaddrs();
+}
+
+// CHECK-LABEL: @call_versioned
+// CHECK: call void @getifaddrs()
+#[no_mangle]
+pub unsafe fn call_versioned() {
+    getifaddrs();
+}
@@ -0,0 +1,22 @@
+// Verifies that `-Z annotate-llvm-ir` maps functions back to Rust items.
+//
+// compile-flags: -Z annotate-llvm-ir -C no-prepopulate-passes
+
+#![crate_type = "lib"]
+
+pub mod inner {
+    // CHECK: ; rust item: annotate_llvm_ir::inner::annotated
+    // CHECK-NEXT: ; rust span: {{.*}}annotate-llvm-ir.rs:[[@LINE+2]]:{{[0-9]+}}: {{[0-9]+}}:{{[0-9]+}}
+    // CHECK-NEXT: ; rust cgu: annotate_llvm_ir.{{[0-9a-f]+}}{{.*}} (shared)
+    pub fn annotated(x: u32) -> u32 {
+        generic(x)
+    }
+}
+
+// CHECK: ; rust item: annotate_llvm_ir::generic::<u32>
+// CHECK-NEXT: ; rust span:
+// CHECK-NEXT: ; rust cgu: {{.*}} (local copy)
+#[inline(always)]
+fn generic<T>(x: T) -> T {
+    x
+}
@@ -0,0 +1,39 @@
+//
+// Checks that `-Z apple-deployment-target` overrides the deployment target in the target triple,
+// taking precedence over MACOSX_DEPLOYMENT_TARGET and IPHONEOS_DEPLOYMENT_TARGET.
+
+// revisions: MACOS IOS IOS_SIM IOS_X86_SIM
+// compile-flags: -O --crate-type=rlib
+// [MACOS] compile-flags: --target=x86_64-apple-darwin -Z apple-deployment-target=10.14
+// [MACOS] needs-llvm-components: x86
+// [IOS] compile-flags: --target=aarch64-apple-ios -Z apple-deployment-target=13
+// [IOS] needs-llvm-components: aarch64
+// [IOS_SIM] compile-flags: --target=aarch64-apple-ios-sim -Z apple-deployment-target=14.5
+// [IOS_SIM] needs-llvm-components: aarch64
+// [IOS_X86_SIM] compile-flags: --target=x86_64-apple-ios -Z apple-deployment-target=14.5
+// [IOS_X86_SIM] needs-llvm-components: x86
+// rustc-env:MACOSX_DEPLOYMENT_TARGET=10.9
+// rustc-env:IPHONEOS_DEPLOYMENT_TARGET=10.0
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang="sized"]
+trait Sized { }
+#[lang="freeze"]
+trait Freeze { }
+#[lang="copy"]
+trait Copy { }
+
+#[repr(C)]
+pub struct Bool {
+    b: bool,
+}
+
+// MACOS: target triple = "x86_64-apple-macosx10.14.0"
+// IOS: target triple = "arm64-apple-ios13.0.0"
+// IOS_SIM: target triple = "arm64-apple-ios14.5.0-simulator"
+// IOS_X86_SIM: target triple = "x86_64-apple-ios14.5.0-simulator"
+#[no_mangle]
+pub extern "C" fn structbool() -> Bool {
+    Bool { b: true }
+}
@@ -0,0 +1,25 @@
+// Checks that `-Z function-return=thunk-extern` marks functions so that LLVM replaces their
+// returns with jumps to `__x86_return_thunk`, and that `-Z function-return=keep` doesn't.
+//
+// revisions: THUNK KEEP
+// min-llvm-version: 15.0
+// [THUNK] compile-flags: -Z function-return=thunk-extern
+// [KEEP] compile-flags: -Z function-return=keep
+// compile-flags: --target=x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+
+#![feature(no_core, lang_items)]
+#![crate_type = "lib"]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+// CHECK: define void @thunked() {{.*}}#[[ATTRS:[0-9]+]]
+#[no_mangle]
+pub fn thunked() {}
+
+// THUNK: attributes #[[ATTRS]] = {{.*}}fn_ret_thunk_extern
+// KEEP-NOT: fn_ret_thunk_extern
+// THUNK: !{{[0-9]+}} = !{i32 {{[0-9]+}}, !"function_return_thunk_extern", i32 1}
+// KEEP-NOT: function_return_thunk_extern
@@ -0,0 +1,28 @@
+// Checks that `-Z hotpatch` makes functions hot-patchable with the prologue of the target, or
+// with the given NOP sleds.
+//
+// revisions: MSVC LINUX AARCH64
+// [MSVC] compile-flags: -Z hotpatch --target=x86_64-pc-windows-msvc
+// [MSVC] needs-llvm-components: x86
+// [LINUX] compile-flags: -Z hotpatch --target=i686-unknown-linux-gnu
+// [LINUX] needs-llvm-components: x86
+// [AARCH64] compile-flags: -Z hotpatch=2,1 --target=aarch64-unknown-linux-gnu
+// [AARCH64] needs-llvm-components: aarch64
+
+#![feature(no_core, lang_items)]
+#![crate_type = "lib"]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+// CHECK: define void @patchable() {{.*}}#[[PATCHABLE:[0-9]+]]
+#[no_mangle]
+pub fn patchable() {}
+
+// MSVC: attributes #[[PATCHABLE]] = {{.*}}"patchable-function"="prologue-short-redirect"
+// MSVC-NOT: patchable-function-prefix
+// LINUX: attributes #[[PATCHABLE]] = {{.*}}"patchable-function"="prologue-short-redirect"
+// LINUX-SAME: "patchable-function-prefix"="5"
+// AARCH64: attributes #[[PATCHABLE]] = {{.*}}"patchable-function-entry"="2"
+// AARCH64-SAME: "patchable-function-prefix"="1"
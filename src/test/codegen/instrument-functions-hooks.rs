@@ -0,0 +1,31 @@
+// Checks that `-Z instrument-functions` adds the given entry and exit hooks to the functions
+// that `-Z instrument-functions-include` and `-Z instrument-functions-exclude` select.
+//
+// compile-flags: -Z instrument-functions=trace_enter,trace_exit
+// compile-flags: -Z instrument-functions-include=traced
+// compile-flags: -Z instrument-functions-exclude=flush
+
+#![crate_type = "lib"]
+
+pub struct Tracer;
+
+pub mod traced {
+    // CHECK: define void @instrumented() {{.*}}#[[INSTRUMENTED:[0-9]+]]
+    #[no_mangle]
+    pub fn instrumented() {}
+
+    impl super::Tracer {
+        // CHECK: define void @flush() {{.*}}#[[EXCLUDED:[0-9]+]]
+        #[no_mangle]
+        pub fn flush() {}
+    }
+}
+
+// CHECK: define void @not_included() {{.*}}#[[EXCLUDED]]
+#[no_mangle]
+pub fn not_included() {}
+
+// CHECK: attributes #[[INSTRUMENTED]] = {{.*}}"instrument-function-entry-inlined"="__cyg_profile_func_enter" "instrument-function-exit-inlined"="__cyg_profile_func_exit"
+// CHECK: attributes #[[EXCLUDED]] =
+// CHECK-NOT: instrument-function
+// CHECK-SAME: }
@@ -0,0 +1,20 @@
+// Checks that `-Z instrument-functions-post-inline=no` marks the functions to be instrumented
+// before inlining, and that the hooks are called through the GCC hooks LLVM knows about.
+//
+// compile-flags: -Z instrument-functions=trace_enter,trace_exit
+// compile-flags: -Z instrument-functions-post-inline=no
+
+#![crate_type = "lib"]
+
+// CHECK: define void @instrumented() {{.*}}#[[INSTRUMENTED:[0-9]+]]
+#[no_mangle]
+pub fn instrumented() {}
+
+// CHECK: define internal void @__cyg_profile_func_enter(i8* %0, i8* %1)
+// CHECK-NEXT: start:
+// CHECK-NEXT: call void @trace_enter(i8* %0, i8* %1)
+// CHECK: define internal void @__cyg_profile_func_exit(i8* %0, i8* %1)
+// CHECK-NEXT: start:
+// CHECK-NEXT: call void @trace_exit(i8* %0, i8* %1)
+
+// CHECK: attributes #[[INSTRUMENTED]] = {{.*}}"instrument-function-entry"="__cyg_profile_func_enter" "instrument-function-exit"="__cyg_profile_func_exit"
@@ -0,0 +1,22 @@
+// Checks that `-C instrument-functions` adds GCC-style entry and exit hooks to functions,
+// except to those `-C instrument-functions-exclude` leaves out.
+//
+// compile-flags: -C instrument-functions
+// compile-flags: -C instrument-functions-exclude=instrument_functions::excluded
+
+#![crate_type = "lib"]
+
+// CHECK: define void @instrumented() {{.*}}#[[INSTRUMENTED:[0-9]+]]
+#[no_mangle]
+pub fn instrumented() {}
+
+pub mod excluded {
+    // CHECK: define void @excluded() {{.*}}#[[EXCLUDED:[0-9]+]]
+    #[no_mangle]
+    pub fn excluded() {}
+}
+
+// CHECK: attributes #[[INSTRUMENTED]] = {{.*}}"instrument-function-entry-inlined"="__cyg_profile_func_enter" "instrument-function-exit-inlined"="__cyg_profile_func_exit"
+// CHECK: attributes #[[EXCLUDED]] =
+// CHECK-NOT: instrument-function
+// CHECK-SAME: }
@@ -0,0 +1,17 @@
+// only-x86_64
+// only-linux
+// compile-flags: -C relocation-model=pic -Z plt=auto -Z relro-level=partial -Z bind-now
+
+#![crate_type = "lib"]
+
+// With lazy binding disabled by `-Z bind-now`, `-Z plt=auto` skips the PLT
+// even though RELRO is only partial.
+extern "C" {
+    // CHECK: Function Attrs:{{.*}}nonlazybind
+    fn getenv(name: *const u8) -> *mut u8;
+}
+
+// Ensure the function gets referenced.
+pub unsafe fn call_through_plt() -> *mut u8 {
+    getenv(b"\0".as_ptr())
+}
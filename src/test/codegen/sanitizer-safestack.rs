@@ -0,0 +1,25 @@
+// Verifies that SafeStack can be used without a runtime on bare-metal targets.
+//
+// compile-flags: --target thumbv7em-none-eabi -Z sanitizer=safestack
+// compile-flags: -Z safestack-use-pointer-address
+// needs-llvm-components: arm
+
+#![feature(no_core, lang_items, no_sanitize)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+// CHECK-LABEL: ; sanitizer_safestack::instrumented
+// CHECK-NEXT:  ; Function Attrs:{{.*}}safestack
+#[no_mangle]
+pub fn instrumented() {}
+
+// CHECK-LABEL: ; sanitizer_safestack::not_instrumented
+// CHECK-NEXT:  ; Function Attrs:
+// CHECK-NOT:   safestack
+// CHECK:       define
+#[no_mangle]
+#[no_sanitize(safestack)]
+pub fn not_instrumented() {}
@@ -0,0 +1,12 @@
+// Verifies that "EnableSplitLTOUnit" module flag is added.
+//
+// ignore-windows
+// needs-sanitizer-cfi
+// compile-flags: -Clto -Zsanitizer=cfi
+
+#![crate_type="lib"]
+
+pub fn foo() {
+}
+
+// CHECK: !{{[0-9]+}} = !{i32 1, !"EnableSplitLTOUnit", i32 1}
@@ -0,0 +1,12 @@
+// Verifies that `-C split-lto-unit` adds the "EnableSplitLTOUnit" module flag, with the error
+// behavior Clang adds it with, and that it isn't added by default.
+//
+// revisions: SPLIT DEFAULT
+// [SPLIT] compile-flags: -C split-lto-unit
+
+#![crate_type = "lib"]
+
+pub fn foo() {}
+
+// SPLIT: !{{[0-9]+}} = !{i32 1, !"EnableSplitLTOUnit", i32 1}
+// DEFAULT-NOT: EnableSplitLTOUnit
@@ -0,0 +1,14 @@
+// revisions: global tls
+// min-llvm-version: 13.0
+// only-x86_64
+// [global] compile-flags: -Z stack-protector=all -Z stack-protector-guard=global
+// [tls] compile-flags: -Z stack-protector=all -Z stack-protector-guard=tls
+// [tls] compile-flags: -Z stack-protector-guard-offset=40
+
+#![crate_type = "lib"]
+
+pub fn foo() {}
+
+// global: !{i32 {{[0-9]+}}, !"stack-protector-guard", !"global"}
+// tls: !{i32 {{[0-9]+}}, !"stack-protector-guard", !"tls"}
+// tls: !{i32 {{[0-9]+}}, !"stack-protector-guard-offset", i32 40}
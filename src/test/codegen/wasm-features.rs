@@ -0,0 +1,19 @@
+// Checks that `-Z wasm-features` expands to the target features of the WebAssembly features,
+// with the features that the enabled ones need.
+//
+// compile-flags: --target wasm32-unknown-unknown -Z wasm-features=+atomics,-sign-ext
+// needs-llvm-components: webassembly
+
+#![feature(no_core, lang_items)]
+#![crate_type = "lib"]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+// CHECK: define void @simd() {{.*}}#[[SIMD:[0-9]+]]
+#[no_mangle]
+#[target_feature(enable = "simd128")]
+pub unsafe fn simd() {}
+
+// CHECK: attributes #[[SIMD]] = {{.*}}"target-features"="{{.*}}+bulk-memory,+mutable-globals,+atomics,-sign-ext,+simd128"
@@ -0,0 +1,32 @@
+// Checks that an `#[expect]` attribute stays fulfilled when the lint fulfilling it isn't
+// emitted again, because the query emitting it is reused and its diagnostics are replayed
+// from the incremental cache.
+
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph
+// build-pass
+
+#![feature(lint_reasons)]
+#![feature(rustc_attrs)]
+#![deny(unfulfilled_lint_expectations)]
+#![crate_type = "rlib"]
+
+pub mod unchanged {
+    #[rustc_clean(cfg = "cfail2")]
+    #[expect(unused_variables)]
+    pub fn expected() {
+        let x = 1;
+    }
+}
+
+pub mod changed {
+    #[cfg(cfail1)]
+    pub fn value() -> u32 {
+        1
+    }
+
+    #[cfg(cfail2)]
+    pub fn value() -> u32 {
+        2
+    }
+}
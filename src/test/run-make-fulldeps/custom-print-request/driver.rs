@@ -0,0 +1,34 @@
+#![feature(rustc_private)]
+
+//! A driver registering its own `--print` requests.
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_serialize;
+
+use rustc_interface::interface::{Config, CustomPrintRequest};
+use rustc_serialize::json::ToJson;
+
+struct Callbacks;
+
+impl rustc_driver::Callbacks for Callbacks {
+    fn config(&mut self, config: &mut Config) {
+        config
+            .custom_print_requests
+            .push(CustomPrintRequest::new("greeting", |_, _| "hello from the driver".to_json()));
+        config.custom_print_requests.push(
+            CustomPrintRequest::new("driver-options", |sess, _| {
+                vec![format!("edition={}", sess.edition())].to_json()
+            })
+            .unstable(),
+        );
+    }
+}
+
+fn main() {
+    let exit_code = rustc_driver::catch_with_exit_code(|| {
+        let args: Vec<_> = std::env::args().collect();
+        rustc_driver::RunCompiler::new(&args, &mut Callbacks).run()
+    });
+    std::process::exit(exit_code);
+}
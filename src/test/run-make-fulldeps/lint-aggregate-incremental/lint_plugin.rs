@@ -0,0 +1,100 @@
+#![feature(rustc_private)]
+
+extern crate rustc_data_structures;
+extern crate rustc_driver;
+extern crate rustc_hir;
+#[macro_use]
+extern crate rustc_lint;
+extern crate rustc_query_system;
+#[macro_use]
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_hir as hir;
+use rustc_lint::LintStore;
+use rustc_lint::{LateContext, LateLintPass, LintAggregate, LintAggregateState, LintContext};
+use rustc_query_system::ich::StableHashingContext;
+use rustc_span::def_id::{DefId, LocalDefId};
+
+declare_lint! {
+    UNIMPLEMENTED_TRAIT,
+    Warn,
+    "traits that the crate doesn't implement"
+}
+
+declare_lint! {
+    EMPTY_TRAIT,
+    Warn,
+    "traits without items"
+}
+
+#[derive(Default)]
+struct Traits {
+    defined: Vec<LocalDefId>,
+    implemented: Vec<DefId>,
+}
+
+impl<'a> HashStable<StableHashingContext<'a>> for Traits {
+    fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHasher) {
+        self.defined.hash_stable(hcx, hasher);
+        self.implemented.hash_stable(hcx, hasher);
+    }
+}
+
+impl LintAggregateState for Traits {
+    fn merge(&mut self, other: &Self) {
+        self.defined.extend_from_slice(&other.defined);
+        self.implemented.extend_from_slice(&other.implemented);
+    }
+}
+
+struct Pass {
+    traits: LintAggregate<Traits>,
+}
+
+impl_lint_pass!(Pass => [UNIMPLEMENTED_TRAIT, EMPTY_TRAIT]);
+
+impl<'tcx> LateLintPass<'tcx> for Pass {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, it: &'tcx hir::Item<'tcx>) {
+        match it.kind {
+            hir::ItemKind::Trait(.., items) => {
+                if items.is_empty() {
+                    cx.lint(EMPTY_TRAIT, |lint| {
+                        lint.build(&format!("trait `{}` has no items", it.ident))
+                            .set_span(it.span)
+                            .emit()
+                    });
+                }
+                self.traits.with(|traits| traits.defined.push(it.def_id));
+            }
+            hir::ItemKind::Impl(hir::Impl { of_trait: Some(ref trait_ref), .. }) => {
+                if let Some(def_id) = trait_ref.trait_def_id() {
+                    self.traits.with(|traits| traits.implemented.push(def_id));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let Traits { defined, implemented } = self.traits.with(std::mem::take);
+        for def_id in defined {
+            if !implemented.contains(&def_id.to_def_id()) {
+                let name = cx.tcx.item_name(def_id.to_def_id());
+                cx.lint(UNIMPLEMENTED_TRAIT, |lint| {
+                    lint.build(&format!("trait `{}` is not implemented in the crate", name))
+                        .set_span(cx.tcx.def_span(def_id))
+                        .emit()
+                });
+            }
+        }
+    }
+}
+
+fn register(store: &mut LintStore) {
+    store.register_lints(&[&UNIMPLEMENTED_TRAIT, &EMPTY_TRAIT]);
+    store.register_late_aggregating_pass(|traits| Box::new(Pass { traits }));
+}
+
+declare_lint_plugin!(register);
@@ -0,0 +1,10 @@
+mod traits {
+    pub trait Unimplemented {}
+}
+
+mod impls {
+    #[cfg(implement)]
+    impl crate::traits::Unimplemented for () {}
+}
+
+fn main() {}
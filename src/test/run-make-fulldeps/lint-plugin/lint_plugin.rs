@@ -0,0 +1,38 @@
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+#[macro_use]
+extern crate rustc_lint;
+#[macro_use]
+extern crate rustc_session;
+
+use rustc_hir as hir;
+use rustc_lint::{LateContext, LateLintPass, LintContext, LintStore};
+
+declare_lint! {
+    FORBIDDEN_FN_NAME,
+    Warn,
+    "functions named `forbidden`"
+}
+
+declare_lint_pass!(ForbiddenFnName => [FORBIDDEN_FN_NAME]);
+
+impl<'tcx> LateLintPass<'tcx> for ForbiddenFnName {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'tcx>) {
+        if let hir::ItemKind::Fn(..) = item.kind {
+            if item.ident.as_str() == "forbidden" {
+                cx.lint(FORBIDDEN_FN_NAME, |lint| {
+                    lint.build("function named `forbidden`").set_span(item.ident.span).emit()
+                });
+            }
+        }
+    }
+}
+
+fn register(store: &mut LintStore) {
+    store.register_lints(&[&FORBIDDEN_FN_NAME]);
+    store.register_late_pass(|| Box::new(ForbiddenFnName));
+}
+
+declare_lint_plugin!(register);
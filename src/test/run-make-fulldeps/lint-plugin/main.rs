@@ -0,0 +1,5 @@
+fn forbidden() {}
+
+fn main() {
+    forbidden();
+}
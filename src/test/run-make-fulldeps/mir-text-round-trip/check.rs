@@ -0,0 +1,17 @@
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+use rustc_middle::mir::text::{parse_mir_text, MIR_TEXT_FORMAT_VERSION};
+use std::{env, fs};
+
+fn main() {
+    let path = env::args().nth(1).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    let mir = parse_mir_text(&text).unwrap_or_else(|err| panic!("{}: {}", path, err));
+    assert_eq!(mir.version, MIR_TEXT_FORMAT_VERSION);
+    assert!(mir.bodies.iter().any(|body| body.path == "sum" && body.args.len() == 1));
+    assert!(mir.bodies.iter().any(|body| body.ctfe && body.path == "square"));
+    assert!(mir.bodies.iter().any(|body| !body.allocations.is_empty()));
+    assert_eq!(mir.to_string(), text);
+}
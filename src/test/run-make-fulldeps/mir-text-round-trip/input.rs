@@ -0,0 +1,41 @@
+pub static mut COUNTER: u32 = 0;
+
+pub const GREETING: &str = "hello";
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+}
+
+pub const fn square(x: i32) -> i32 {
+    x * x
+}
+
+pub fn sum(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+pub fn apply<F: Fn(i32) -> i32>(f: F, value: i32) -> i32 {
+    f(value)
+}
+
+pub fn run() -> i32 {
+    let offset = square(3);
+    let point = Point::new(1, 2);
+    let values: [i32; 3] = [point.x, point.y, offset];
+    unsafe {
+        COUNTER += 1;
+    }
+    println!("{}", GREETING);
+    apply(|value| value + offset, sum(&values))
+}
@@ -0,0 +1,24 @@
+use std::fmt::Debug;
+
+/// A point on a line.
+#[derive(Default)]
+pub struct Point {
+    x: i32,
+}
+
+trait Area {
+    fn area(&self) -> i32;
+}
+
+impl Area for Point {
+    fn area(&self) -> i32 {
+        self.x * self.x
+    }
+}
+
+fn main() {
+    let p = Point::default();
+    let q = Point { x: 2 };
+    println!("{}", p.area() + q.x);
+    let _: &dyn Debug = &q.x;
+}
@@ -0,0 +1,7 @@
+#[cfg(not(inner_set))]
+compile_error!("inner_set not set");
+
+#[cfg(not(quoted_set))]
+compile_error!("quoted_set not set");
+
+fn main() {}
@@ -0,0 +1,17 @@
+#![crate_type = "rlib"]
+
+pub static TABLE: [u64; 64] = [7; 64];
+
+pub fn exported(x: usize) -> u64 {
+    private(x) + TABLE[x % 64]
+}
+
+#[inline(never)]
+fn private(x: usize) -> u64 {
+    (x as u64).wrapping_mul(31)
+}
+
+#[inline(never)]
+pub fn generic<T: Into<u64>>(x: T) -> u64 {
+    x.into().rotate_left(3)
+}
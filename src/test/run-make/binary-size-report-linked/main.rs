@@ -0,0 +1,6 @@
+extern crate dep;
+
+fn main() {
+    let x = std::env::args().count();
+    println!("{} {}", dep::exported(x), dep::generic(x as u32));
+}
@@ -0,0 +1,9 @@
+#[inline(never)]
+fn twice<T: std::fmt::Debug>(x: T) {
+    println!("{:?} {:?}", x, x);
+}
+
+fn main() {
+    twice(1u8);
+    twice("one");
+}
@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate pure;
+
+#[derive(Answer)]
+struct S;
+
+fn main() {
+    let x = 2;
+    shadow_x!();
+    assert_eq!(x, 2);
+    assert_eq!(S::answer(), 42);
+}
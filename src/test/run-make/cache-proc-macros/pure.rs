@@ -0,0 +1,40 @@
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_pure)]
+
+extern crate proc_macro;
+
+use proc_macro::{Span, TokenStream, TokenTree};
+use std::io::Write;
+
+#[proc_macro_derive(Answer)]
+#[proc_macro_pure]
+pub fn answer(input: TokenStream) -> TokenStream {
+    // Not actually pure: count the runs, so the test can tell when the cache was used.
+    let runs = std::path::Path::new(&std::env::var("TMPDIR").unwrap()).join("runs");
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(runs).unwrap();
+    writeln!(file, "run").unwrap();
+
+    let name = input
+        .into_iter()
+        .filter_map(|tt| match tt {
+            TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        })
+        .last()
+        .unwrap();
+    format!("impl {} {{ fn answer() -> u32 {{ 42 }} }}", name).parse().unwrap()
+}
+
+#[proc_macro]
+#[proc_macro_pure]
+pub fn shadow_x(_: TokenStream) -> TokenStream {
+    // With mixed-site hygiene, this `x` doesn't shadow the caller's, cached or not.
+    let stream: TokenStream = "let x = 1;".parse().unwrap();
+    stream
+        .into_iter()
+        .map(|mut tt| {
+            tt.set_span(Span::mixed_site());
+            tt
+        })
+        .collect()
+}
@@ -0,0 +1,6 @@
+#[cfg(all(verbose, feature = "serde", feature = "std", not(quiet)))]
+pub fn configured() {}
+
+pub fn check() {
+    configured();
+}
@@ -0,0 +1,17 @@
+#![crate_type = "rlib"]
+
+pub mod alpha {
+    pub fn a() -> u32 {
+        1
+    }
+}
+
+pub mod beta {
+    pub fn b() -> u32 {
+        2
+    }
+}
+
+pub fn root() -> u32 {
+    alpha::a() + beta::b()
+}
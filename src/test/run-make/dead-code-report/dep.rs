@@ -0,0 +1,39 @@
+#![crate_type = "rlib"]
+
+pub trait Shape {
+    fn area(&self) -> u32;
+}
+
+pub struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        4
+    }
+}
+
+pub struct Circle;
+
+impl Shape for Circle {
+    fn area(&self) -> u32 {
+        3
+    }
+}
+
+pub static USED_STATIC: u32 = 1;
+pub static UNUSED_STATIC: u32 = 2;
+
+pub fn used() -> u32 {
+    helper()
+}
+
+fn helper() -> u32 {
+    USED_STATIC
+}
+
+pub fn unused() -> u32 {
+    UNUSED_STATIC
+}
+
+#[no_mangle]
+pub extern "C" fn dep_exported() {}
@@ -0,0 +1,8 @@
+extern crate dep;
+
+use dep::Shape;
+
+fn main() {
+    let shape: &dyn Shape = &dep::Square;
+    println!("{}", dep::used() + shape.area());
+}
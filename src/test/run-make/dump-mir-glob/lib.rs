@@ -0,0 +1,21 @@
+#![crate_type = "lib"]
+
+pub mod module {
+    pub fn foo() -> impl Fn() -> u32 {
+        || 1
+    }
+
+    pub fn bar() -> impl Fn() -> u32 {
+        || 2
+    }
+
+    pub mod nested {
+        pub fn baz() -> impl Fn() -> u32 {
+            || 3
+        }
+    }
+}
+
+pub fn closure_outside() -> impl Fn() -> u32 {
+    || 4
+}
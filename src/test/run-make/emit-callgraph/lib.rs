@@ -0,0 +1,34 @@
+#![crate_type = "lib"]
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+#[inline(never)]
+pub fn direct_callee() -> u32 {
+    1
+}
+
+pub fn caller(shape: &dyn Shape, f: fn() -> u32) -> f64 {
+    direct_callee() as f64 + shape.area() + f() as f64
+}
+
+pub fn make_shape() -> Box<dyn Shape> {
+    Box::new(Square(2.0))
+}
+
+pub fn pointer() -> fn() -> u32 {
+    direct_callee
+}
+
+pub fn index(v: &[u32], i: usize) -> u32 {
+    v[i]
+}
@@ -0,0 +1,15 @@
+// Each function returns a string that is only in the metadata if its MIR is.
+
+pub fn public() -> &'static str {
+    helper();
+    "PUBLIC_MIR"
+}
+
+fn helper() -> &'static str {
+    "HELPER_MIR"
+}
+
+#[allow(dead_code)]
+fn unused() -> &'static str {
+    "UNUSED_MIR"
+}
@@ -0,0 +1,8 @@
+#[path = "other/renamed.rs"]
+mod renamed;
+
+static DATA: &[u8] = include_bytes!("data.bin");
+
+fn main() {
+    renamed::f(DATA);
+}
@@ -0,0 +1,5 @@
+#![crate_name = "foo"]
+#![crate_type = "lib"]
+
+pub struct S1(pub u8, pub u16, pub u8);
+pub struct S2(pub u8, pub u16, pub u8);
@@ -0,0 +1,12 @@
+struct Point {
+    x: i32,
+}
+
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn main() {
+    let p = Point { x: add(1, 2) };
+    println!("{}", p.x);
+}
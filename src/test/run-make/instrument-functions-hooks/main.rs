@@ -0,0 +1,26 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ENTERED: AtomicUsize = AtomicUsize::new(0);
+static EXITED: AtomicUsize = AtomicUsize::new(0);
+
+#[no_mangle]
+pub extern "C" fn trace_enter(_this_fn: *const u8, _call_site: *const u8) {
+    ENTERED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[no_mangle]
+pub extern "C" fn trace_exit(_this_fn: *const u8, _call_site: *const u8) {
+    EXITED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[inline(never)]
+fn traced(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {
+    // The argument isn't known at compile time, so the call can't be folded away.
+    assert_eq!(traced(std::env::args().count() as u32 + 40), 42);
+    assert_eq!(ENTERED.load(Ordering::SeqCst), 1);
+    assert_eq!(EXITED.load(Ordering::SeqCst), 1);
+}
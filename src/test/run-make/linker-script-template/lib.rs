@@ -0,0 +1,11 @@
+#![no_std]
+
+use core::sync::atomic::AtomicU32;
+
+#[no_mangle]
+#[link_section = ".vector_table"]
+pub static VECTOR_TABLE: [u32; 2] = [0x2000_0000, 0x0000_0101];
+
+#[no_mangle]
+#[link_section = ".ram_buffers"]
+pub static COUNTER: AtomicU32 = AtomicU32::new(0);
@@ -0,0 +1,9 @@
+#![feature(lint_reasons)]
+
+#[expect(unused_variables)]
+pub fn fulfilled() {
+    let x = 1;
+}
+
+#[expect(dead_code, reason = "used by the tests")]
+pub fn unfulfilled() {}
@@ -0,0 +1,16 @@
+#![crate_type = "lib"]
+
+macro_rules! repeat {
+    ($name:ident) => {
+        pub fn $name() -> u32 {
+            1 + 1
+        }
+    };
+}
+
+repeat!(one);
+repeat!(two);
+repeat!(three);
+
+#[derive(Debug)]
+pub struct Derived;
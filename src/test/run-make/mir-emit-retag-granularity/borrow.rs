@@ -0,0 +1,4 @@
+// A reference that is created from another one.
+pub fn borrow(x: &mut i32) -> &i32 {
+    &*x
+}
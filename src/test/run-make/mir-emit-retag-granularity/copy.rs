@@ -0,0 +1,5 @@
+// A reference that is only copied.
+pub fn copy(x: &i32) -> &i32 {
+    let y = x;
+    y
+}
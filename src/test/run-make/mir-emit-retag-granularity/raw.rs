@@ -0,0 +1,4 @@
+// A reference that escapes to a raw pointer.
+pub fn raw(x: &mut i32) -> *mut i32 {
+    x
+}
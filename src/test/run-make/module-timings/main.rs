@@ -0,0 +1,16 @@
+mod parser {
+    pub fn parse(input: &str) -> Vec<u32> {
+        input.split(',').map(|word| word.trim().parse().unwrap()).collect()
+    }
+}
+
+mod math {
+    pub fn sum<T: Copy + std::iter::Sum<T>>(values: &[T]) -> T {
+        values.iter().copied().sum()
+    }
+}
+
+fn main() {
+    let values = parser::parse("1, 2, 3");
+    println!("{}", math::sum(&values));
+}
@@ -0,0 +1,12 @@
+#![feature(polymorphize)]
+
+fn unused<T>() {}
+
+#[polymorphize(never)]
+fn opted_out<T>() {}
+
+fn main() {
+    unused::<u8>();
+    unused::<u16>();
+    opted_out::<u8>();
+}
@@ -0,0 +1,7 @@
+const SET: &str = env!("BUILD_ENV_REPORT_SET");
+const UNSET: Option<&str> = option_env!("BUILD_ENV_REPORT_UNSET");
+
+fn main() {
+    assert_eq!(SET, "hello");
+    assert_eq!(UNSET, None);
+}
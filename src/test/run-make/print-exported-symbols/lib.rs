@@ -0,0 +1,7 @@
+#[no_mangle]
+pub extern "C" fn exported_b() {}
+
+#[no_mangle]
+pub extern "C" fn exported_a() {}
+
+pub fn not_exported() {}
@@ -0,0 +1 @@
+#![crate_type = "lib"]
@@ -0,0 +1,7 @@
+pub struct Marker;
+
+pub fn marker() -> Marker {
+    #[cfg(changed)]
+    let _ = 1;
+    Marker
+}
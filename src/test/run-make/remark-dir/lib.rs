@@ -0,0 +1,10 @@
+#![crate_type = "lib"]
+
+#[inline(never)]
+pub fn sum(values: &[u32]) -> u32 {
+    values.iter().sum()
+}
+
+pub fn call() -> u32 {
+    sum(&[1, 2, 3])
+}
@@ -0,0 +1,5 @@
+#![crate_type = "lib"]
+
+pub fn answer() -> u32 {
+    42
+}
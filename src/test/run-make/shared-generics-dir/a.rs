@@ -0,0 +1,5 @@
+extern crate common;
+
+pub fn sum_a(values: &[u8]) -> u64 {
+    common::sum(values)
+}
@@ -0,0 +1,5 @@
+extern crate common;
+
+pub fn sum_b(values: &[u8]) -> u64 {
+    common::sum(values) * 2
+}
@@ -0,0 +1,3 @@
+pub fn sum<T: Copy + Into<u64>>(values: &[T]) -> u64 {
+    values.iter().map(|&value| value.into()).sum()
+}
@@ -0,0 +1,7 @@
+extern crate a;
+extern crate b;
+
+fn main() {
+    assert_eq!(a::sum_a(&[1, 2, 3]), 6);
+    assert_eq!(b::sum_b(&[1, 2, 3]), 12);
+}
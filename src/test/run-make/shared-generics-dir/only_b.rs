@@ -0,0 +1,5 @@
+extern crate b;
+
+fn main() {
+    assert_eq!(b::sum_b(&[1, 2, 3]), 12);
+}
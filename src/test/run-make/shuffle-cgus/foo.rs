@@ -0,0 +1,28 @@
+pub mod alpha {
+    pub fn alpha(x: u32) -> u32 {
+        x.wrapping_mul(3)
+    }
+}
+
+pub mod beta {
+    pub fn beta(x: u64) -> u64 {
+        x.rotate_left(7)
+    }
+}
+
+pub mod gamma {
+    pub fn gamma(v: &[u8]) -> usize {
+        v.iter().filter(|&&b| b == 0).count()
+    }
+}
+
+pub mod delta {
+    pub fn delta(s: &str) -> String {
+        s.to_uppercase()
+    }
+}
+
+pub fn all(x: u32) -> u64 {
+    let n = gamma::gamma(&[0, 1]) + delta::delta("a").len();
+    beta::beta(alpha::alpha(x) as u64) + n as u64
+}
@@ -0,0 +1,21 @@
+#![feature(bench_black_box)]
+
+use std::hint::black_box;
+
+#[inline(never)]
+fn deep() -> u8 {
+    let buffer = black_box([0u8; 4096]);
+    buffer[black_box(17)]
+}
+
+#[inline(never)]
+fn indirect(f: fn()) {
+    f()
+}
+
+fn nothing() {}
+
+fn main() {
+    indirect(black_box(nothing));
+    println!("{}", deep());
+}
@@ -0,0 +1,27 @@
+#![crate_type = "lib"]
+
+extern "C" {
+    fn fill(buf: *mut u8, len: usize);
+    fn observe(value: *const u32);
+}
+
+// A byte array on the stack, protected with both `basic` and `strong`.
+#[no_mangle]
+pub fn with_buffer() -> u8 {
+    let mut buf = [0u8; 64];
+    unsafe { fill(buf.as_mut_ptr(), buf.len()) };
+    buf[0]
+}
+
+// A local whose address escapes, protected with `strong` only.
+#[no_mangle]
+pub fn with_address_taken(x: u32) {
+    let value = x;
+    unsafe { observe(&value) };
+}
+
+// Nothing on the stack, protected with `all` only.
+#[no_mangle]
+pub fn plain(x: u32) -> u32 {
+    x.wrapping_mul(3)
+}
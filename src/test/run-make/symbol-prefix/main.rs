@@ -0,0 +1,16 @@
+extern crate plugin;
+
+// The same symbol as the one of `plugin`, which doesn't conflict with it thanks to its prefix.
+#[no_mangle]
+pub extern "C" fn exported() -> u32 {
+    10
+}
+
+fn main() {
+    let sum = exported()
+        + plugin::exported()
+        + plugin::exported_as_renamed()
+        + plugin::mangled()
+        + plugin::generic::<u8>();
+    assert_eq!(sum, 20);
+}
@@ -0,0 +1,20 @@
+#![crate_type = "rlib"]
+
+#[no_mangle]
+pub extern "C" fn exported() -> u32 {
+    1
+}
+
+#[export_name = "renamed"]
+pub extern "C" fn exported_as_renamed() -> u32 {
+    2
+}
+
+#[inline(never)]
+pub fn mangled() -> u32 {
+    3
+}
+
+pub fn generic<T: Default + Into<u32>>() -> u32 {
+    T::default().into() + 4
+}
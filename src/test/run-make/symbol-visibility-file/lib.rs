@@ -0,0 +1,10 @@
+#![crate_type = "cdylib"]
+
+#[no_mangle]
+pub extern "C" fn api_init() {}
+
+#[no_mangle]
+pub extern "C" fn api_shutdown() {}
+
+#[no_mangle]
+pub extern "C" fn internal_helper() {}
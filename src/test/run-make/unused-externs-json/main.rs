@@ -0,0 +1,3 @@
+#![warn(unused_crate_dependencies)]
+
+fn main() {}
@@ -0,0 +1,10 @@
+// check-pass
+// compile-flags: -Z unstable-options --cap-lints-group rustdoc::all=warn
+
+// `--cap-lints-group` also caps the lints of the groups rustdoc registers.
+
+#![deny(rustdoc::broken_intra_doc_links)]
+
+/// [error]
+//~^ WARNING unresolved link to `error`
+pub struct S;
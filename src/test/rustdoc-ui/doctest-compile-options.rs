@@ -0,0 +1,12 @@
+// Flags given with `-Z doctest-compile-options` only apply to the doctests.
+
+// compile-flags:--test --test-args --test-threads=1
+// compile-flags:-Z doctest-compile-options=-Cdebug-assertions=no
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// check-pass
+
+/// ```
+/// assert!(!cfg!(debug_assertions));
+/// ```
+pub struct NoDebugAssertions;
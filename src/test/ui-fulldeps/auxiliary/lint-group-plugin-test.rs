@@ -43,4 +43,5 @@ fn __rustc_plugin_registrar(reg: &mut Registry) {
         None,
         vec![LintId::of(&TEST_LINT), LintId::of(&PLEASE_LINT)],
     );
+    reg.lint_store.register_renamed_group("lint_me_old", "lint_me");
 }
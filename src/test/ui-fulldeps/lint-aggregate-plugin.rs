@@ -0,0 +1,21 @@
+// check-pass
+// aux-build:lint-aggregate-plugin-test.rs
+// ignore-stage1
+// compile-flags: -Z crate-attr=plugin(lint_aggregate_plugin_test)
+
+// A late lint pass looking at all the modules of the crate at once, although they are checked
+// one by one.
+
+#![feature(plugin)]
+
+mod traits {
+    pub trait Implemented {}
+
+    pub trait Unimplemented {} //~ WARNING trait `Unimplemented` is not implemented in the crate
+}
+
+mod impls {
+    impl crate::traits::Implemented for () {}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// aux-build:lint-group-plugin-test.rs
+// ignore-stage1
+// compile-flags: -D lint_me_old
+
+#![feature(plugin)]
+
+#![plugin(lint_group_plugin_test)]
+//~^ WARN use of deprecated attribute `plugin`
+
+fn lintme() { } //~ ERROR item is named 'lintme'
+
+pub fn main() {
+    lintme();
+}
@@ -0,0 +1,15 @@
+// aux-build:lint-group-plugin-test.rs
+// ignore-stage1
+
+#![feature(plugin)]
+
+#![plugin(lint_group_plugin_test)]
+//~^ WARN use of deprecated attribute `plugin`
+
+#[deny(lint_me_old)]
+//~^ WARN lint group `lint_me_old` has been renamed to `lint_me`
+fn lintme() { } //~ ERROR item is named 'lintme'
+
+pub fn main() {
+    lintme();
+}
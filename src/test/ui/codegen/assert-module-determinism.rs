@@ -0,0 +1,28 @@
+// Checks that codegen units are deterministic with `-Z assert-module-determinism`, including
+// with debuginfo and generics instantiated in several codegen units.
+
+// build-pass
+// compile-flags: -Z assert-module-determinism -C codegen-units=4 -C debuginfo=2
+
+mod a {
+    pub fn sum<T: Copy + Into<u64>>(values: &[T]) -> u64 {
+        values.iter().map(|&value| value.into()).sum()
+    }
+}
+
+mod b {
+    use std::collections::HashMap;
+
+    pub fn count(words: &[&str]) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for word in words {
+            *counts.entry(word.to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+fn main() {
+    let counts = b::count(&["a", "b", "a"]);
+    println!("{} {} {:?}", a::sum(&[1u8, 2, 3]), a::sum(&[4u32]), counts.get("a"));
+}
@@ -0,0 +1,4 @@
+// compile-flags: --test -Z test-runner=runner()
+// error-pattern: `-Z test-runner` must be a path
+
+fn main() {}
@@ -0,0 +1,37 @@
+// run-pass
+// aux-build:example_runner.rs
+// compile-flags: --test -Z test-runner=example_runner::runner -Z test-attr=example_test
+
+// The test runner and an attribute marking test cases can be given on the command line
+// instead of with `#![test_runner]` and `#[test_case]`. Like `#[test_case]` items, the
+// marked items are made public, so they can be in private modules.
+
+extern crate example_runner;
+
+pub struct IsFoo(&'static str);
+
+impl example_runner::Testable for IsFoo {
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn run(&self) -> Option<String> {
+        if self.0 != "foo" {
+            return Some(format!("{} != foo", self.0));
+        }
+        None
+    }
+}
+
+#[example_test]
+const TEST_1: IsFoo = IsFoo("hello");
+
+#[example_test]
+const TEST_2: IsFoo = IsFoo("foo");
+
+mod private {
+    use super::IsFoo;
+
+    #[example_test]
+    const TEST_3: IsFoo = IsFoo("foo");
+}
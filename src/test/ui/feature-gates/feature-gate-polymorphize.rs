@@ -0,0 +1,4 @@
+#[polymorphize(never)] //~ ERROR the `#[polymorphize]` attribute is an experimental feature
+pub fn f<T>() {}
+
+fn main() {}
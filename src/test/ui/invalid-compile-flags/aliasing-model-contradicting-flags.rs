@@ -0,0 +1,4 @@
+// check-pass
+// compile-flags: -Z aliasing-model=llvm-default -Z mir-emit-retag -Z mutable-noalias=no
+
+fn main() {}
@@ -0,0 +1,6 @@
+// compile-flags: -Z apple-deployment-target=10.12 --target x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,5 @@
+// compile-flags: --target x86_64-unknown-linux-gnu -C code-model=kernel -C relocation-model=pic
+// needs-llvm-components: x86
+#![feature(no_core)]
+#![no_core]
+#![no_main]
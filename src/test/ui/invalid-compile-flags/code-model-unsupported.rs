@@ -0,0 +1,5 @@
+// compile-flags: --target aarch64-unknown-linux-gnu -C code-model=kernel
+// needs-llvm-components: aarch64
+#![feature(no_core)]
+#![no_core]
+#![no_main]
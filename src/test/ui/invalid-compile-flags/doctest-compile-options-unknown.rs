@@ -0,0 +1,3 @@
+// compile-flags: -Z doctest-compile-options=-Cno-such-option=1
+
+fn main() {}
@@ -0,0 +1,6 @@
+// compile-flags: -Z emscripten-flags=ASSERTIONS --target wasm32-unknown-unknown
+// needs-llvm-components: webassembly
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,9 @@
+// compile-flags: --target wasm32-unknown-emscripten
+// compile-flags: -Z emscripten-flags=ALLOW_MEMORY_GROWHT=1
+// compile-flags: -Z emscripten-flags=EXPORTED_FUNCTIONS=[]
+// compile-flags: -Z emscripten-flags=XYZZY
+// needs-llvm-components: webassembly
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,8 @@
+// compile-flags: -Z function-return=thunk-extern -C code-model=large
+// compile-flags: --target=x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+// min-llvm-version: 15.0
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,7 @@
+// compile-flags: -Z function-return=thunk-extern --target=aarch64-unknown-linux-gnu
+// needs-llvm-components: aarch64
+// min-llvm-version: 15.0
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,6 @@
+// compile-flags: -Z hotpatch --target=aarch64-unknown-linux-gnu
+// needs-llvm-components: aarch64
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,9 @@
+// Checks that silently overridden options aren't reported without
+// `--warn-inconsistent-option-combinations`.
+//
+// check-pass
+// compile-flags: -C panic=unwind -C force-unwind-tables=no
+// ignore-emscripten the target requires unwind tables
+// ignore-wasm32-bare unwinding isn't supported
+
+fn main() {}
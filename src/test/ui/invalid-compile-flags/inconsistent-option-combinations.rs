@@ -0,0 +1,11 @@
+// Checks that `--warn-inconsistent-option-combinations` reports options that are silently
+// overridden by other options.
+//
+// check-pass
+// compile-flags: -Z unstable-options --warn-inconsistent-option-combinations
+// compile-flags: -C panic=unwind -C force-unwind-tables=no
+// compile-flags: -C lto=thin --emit=llvm-ir
+// ignore-emscripten the target requires LTO and unwind tables
+// ignore-wasm32-bare unwinding isn't supported
+
+fn main() {}
@@ -0,0 +1,3 @@
+// compile-flags: -Z instrument-functions=trace_enter -Z instrument-mcount
+
+fn main() {}
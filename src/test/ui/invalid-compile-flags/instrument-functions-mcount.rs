@@ -0,0 +1,3 @@
+// compile-flags: -C instrument-functions -Z instrument-mcount
+
+fn main() {}
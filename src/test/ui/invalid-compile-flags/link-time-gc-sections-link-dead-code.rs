@@ -0,0 +1,3 @@
+// compile-flags: -C link-time-gc-sections=yes -C link-dead-code
+
+fn main() {}
@@ -0,0 +1,4 @@
+// check-pass
+// compile-flags: -C link-time-gc-sections=yes -Z function-sections=no
+
+fn main() {}
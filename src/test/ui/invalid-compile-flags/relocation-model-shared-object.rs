@@ -0,0 +1,4 @@
+// compile-flags: --target x86_64-unknown-linux-gnu --crate-type cdylib -C relocation-model=static
+// needs-llvm-components: x86
+#![feature(no_core)]
+#![no_core]
@@ -0,0 +1,6 @@
+// check-pass
+// compile-flags: --target x86_64-apple-darwin -C relro-level=full
+// needs-llvm-components: x86
+#![feature(no_core)]
+#![no_core]
+#![no_main]
@@ -0,0 +1,3 @@
+// compile-flags: -Z unstable-options --remap-path-prefix=${RUSTC_REMAP_PATH_PREFIX_UNSET}=remapped
+
+fn main() {}
@@ -0,0 +1,6 @@
+// compile-flags: -Z riscv-abi=lp64d --target riscv32imac-unknown-none-elf
+// needs-llvm-components: riscv
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1 @@
+// compile-flags: -C save-temps -Z temps-policy=always-clean
@@ -0,0 +1,4 @@
+// check-pass
+// compile-flags: -Z function-sections=yes -Z data-sections=yes -C link-time-gc-sections=no
+
+fn main() {}
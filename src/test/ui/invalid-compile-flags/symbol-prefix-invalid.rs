@@ -0,0 +1,3 @@
+// compile-flags: -Z symbol-prefix=1plugin
+
+#![crate_type = "lib"]
@@ -0,0 +1,3 @@
+// compile-flags: -Z total-compile-time-limit=90
+
+fn main() {}
@@ -0,0 +1,6 @@
+// compile-flags: -Z wasm-features=+atomics --target x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
@@ -0,0 +1,7 @@
+// compile-flags: -C panic=unwind -Z wasm-features=-exception-handling
+// compile-flags: --target wasm32-unknown-unknown
+// needs-llvm-components: webassembly
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
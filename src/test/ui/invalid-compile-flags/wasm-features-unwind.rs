@@ -0,0 +1,7 @@
+// check-pass
+// compile-flags: -C panic=unwind --target wasm32-unknown-unknown
+// needs-llvm-components: webassembly
+
+#![feature(no_core)]
+#![crate_type = "lib"]
+#![no_core]
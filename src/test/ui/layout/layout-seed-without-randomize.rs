@@ -0,0 +1,4 @@
+// check-pass
+// compile-flags: -Z layout-seed=1234
+
+fn main() {}
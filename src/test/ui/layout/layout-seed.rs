@@ -0,0 +1,14 @@
+// Layout randomization prints its seed, and the seed doesn't change which layouts are valid.
+//
+// run-pass
+// compile-flags: -Z randomize-layout -Z layout-seed=1234
+
+use std::mem::size_of;
+
+struct S(u8, u16, u8, u32);
+
+fn main() {
+    let s = S(1, 2, 3, 4);
+    assert_eq!((s.0, s.1, s.2, s.3), (1, 2, 3, 4));
+    assert!(size_of::<S>() >= 8);
+}
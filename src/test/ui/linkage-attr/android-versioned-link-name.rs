@@ -0,0 +1,24 @@
+// Checks that `-Z android-api-level` rejects the versions of Bionic's symbols that are newer
+// than the API level.
+
+// build-fail
+// compile-flags: -Z android-api-level=24 --target aarch64-linux-android
+// needs-llvm-components: aarch64
+
+#![feature(no_core, lang_items)]
+#![crate_type = "lib"]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+extern "C" {
+    #[link_name = "getrandom@LIBC_P"]
+    fn getrandom();
+    //~^ ERROR `getrandom@LIBC_P` is only available from Android API level 28
+}
+
+#[no_mangle]
+pub unsafe fn call() {
+    getrandom();
+}
@@ -0,0 +1,4 @@
+// compile-flags: --cap-lints-group not_a_group=warn -Z unstable-options
+// error-pattern: unknown lint group `not_a_group` in `--cap-lints-group`
+
+fn main() {}
@@ -0,0 +1,13 @@
+// check-pass
+// compile-flags: --cap-lints allow --cap-lints-group unused=warn -Z unstable-options
+
+#![deny(unused)]
+#![deny(non_camel_case_types)]
+
+use std::option; //~ WARN unused import
+
+struct foo;
+
+fn main() {
+    let _ = foo;
+}
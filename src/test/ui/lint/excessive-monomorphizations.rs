@@ -0,0 +1,23 @@
+// Check that `-Z max-monomorphizations` lints the generic functions instantiated more times than
+// the limit, with the sites that instantiated them.
+
+// build-pass
+// compile-flags: -Z max-monomorphizations=2
+
+fn show<T>(_: T) {} //~ WARN `show` is instantiated 3 times, more than the limit of 2
+
+fn twice<T>(_: T) {}
+
+#[allow(excessive_monomorphizations)]
+fn allowed<T>(_: T) {}
+
+fn main() {
+    show(1u8);
+    show(1u16);
+    show(1u32);
+    twice(1u8);
+    twice(1u16);
+    allowed(1u8);
+    allowed(1u16);
+    allowed(1u32);
+}
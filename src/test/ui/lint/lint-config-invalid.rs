@@ -0,0 +1,5 @@
+// compile-flags: -W large-assignments=limit=lots -W large-assignments=size=1
+// compile-flags: -W unused=limit=1 -W dead-code=limit=1
+// error-pattern: invalid value `lots` for configuration option `limit`
+
+fn main() {}
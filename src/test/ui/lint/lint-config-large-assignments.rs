@@ -0,0 +1,22 @@
+// Check that the size limit of `large_assignments` can be set with its lint level.
+
+// build-pass
+// compile-flags: -W large-assignments=limit=1000
+
+struct Big([u8; 9999]);
+
+fn take(_: Big) {}
+
+fn pass(x: Big) {
+    take(x); //~ WARN moving 9999 bytes
+}
+
+#[allow(large_assignments)]
+fn make() -> Big {
+    Big([0; 9999])
+}
+
+#[allow(large_assignments)]
+fn main() {
+    pass(make());
+}
@@ -0,0 +1,42 @@
+// check-pass
+
+#![feature(lint_reasons)]
+
+#[expect(unused_variables)]
+fn fulfilled() {
+    let x = 1;
+}
+
+#[expect(unused_variables, reason = "`y` is printed")]
+//~^ WARNING this lint expectation is unfulfilled
+fn unfulfilled() {
+    let y = 1;
+    println!("{}", y);
+}
+
+fn param(#[expect(unused_variables)] z: u32) {}
+
+#[allow(unfulfilled_lint_expectations)]
+#[expect(dead_code)]
+fn allowed() {}
+
+// `unused_parens` is emitted by the early lint passes, before the nodes have a `HirId`.
+#[expect(unused_parens)]
+fn early() {
+    let _a = (1);
+}
+
+#[expect(unused_mut, unused_variables)]
+//~^ WARNING this lint expectation is unfulfilled
+fn second_lint() {
+    let w = 1;
+}
+
+fn main() {
+    fulfilled();
+    unfulfilled();
+    param(0);
+    allowed();
+    early();
+    second_lint();
+}
@@ -0,0 +1,17 @@
+// Checks that with `-Z threads`, which runs the late lint passes concurrently, the lints are
+// emitted pass after pass in the order the passes are registered in, rather than as the items
+// are visited.
+//
+// compile-flags: -Z threads=2
+// check-pass
+
+#![warn(missing_docs, missing_debug_implementations)]
+//! The crate.
+
+/// Documented, but not `Debug`.
+pub struct First;
+//~^ WARN type does not implement `Debug`
+
+pub struct Second;
+//~^ WARN missing documentation for a struct
+//~| WARN type does not implement `Debug`
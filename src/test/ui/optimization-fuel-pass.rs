@@ -0,0 +1,16 @@
+// run-pass
+
+#![crate_name="foo"]
+
+use std::mem::size_of;
+
+// Fuel for one pass doesn't limit the others.
+// compile-flags: -Z fuel=foo=Inline:0
+
+struct S1(u8, u16, u8);
+struct S2(u8, u16, u8);
+
+fn main() {
+    assert_eq!(size_of::<S1>(), 4);
+    assert_eq!(size_of::<S2>(), 4);
+}
@@ -0,0 +1,9 @@
+#![feature(polymorphize)]
+
+#[polymorphize(sometimes)] //~ ERROR malformed `polymorphize` attribute input
+pub fn f<T>() {}
+
+#[polymorphize(never)] //~ ERROR attribute should be applied to a function, closure, module, impl
+pub struct S;
+
+fn main() {}
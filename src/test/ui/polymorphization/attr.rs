@@ -0,0 +1,32 @@
+// build-fail
+// compile-flags:-Zpolymorphize=on
+#![feature(polymorphize, rustc_attrs)]
+
+// This test checks that `#[polymorphize(never)]` keeps items and the items nested in them from
+// being polymorphized, and that `#[polymorphize(always)]` opts nested items back in.
+
+#[rustc_polymorphize_error]
+#[polymorphize(never)]
+pub fn never<T>() {}
+
+#[polymorphize(never)]
+mod opted_out {
+    #[rustc_polymorphize_error]
+    pub fn inherited<T>() {}
+
+    #[rustc_polymorphize_error]
+    #[polymorphize(always)]
+    pub fn always<T>() {}
+    //~^ ERROR item has unused generic parameters
+}
+
+#[rustc_polymorphize_error]
+pub fn polymorphized<T>() {}
+//~^ ERROR item has unused generic parameters
+
+fn main() {
+    never::<u32>();
+    opted_out::inherited::<u32>();
+    opted_out::always::<u32>();
+    polymorphized::<u32>();
+}
@@ -0,0 +1,23 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_lint_levels)]
+
+extern crate proc_macro;
+
+use proc_macro::lint_levels::{self, LintLevel};
+use proc_macro::TokenStream;
+
+#[proc_macro]
+pub fn lint_level(input: TokenStream) -> TokenStream {
+    let level = match lint_levels::level(input.to_string().replace(' ', "")) {
+        Some(LintLevel::Allow) => "allow",
+        Some(LintLevel::Warn) => "warn",
+        Some(LintLevel::Deny) => "deny",
+        Some(LintLevel::Forbid) => "forbid",
+        Some(_) => "unknown level",
+        None => "none",
+    };
+    format!("{:?}", level).parse().unwrap()
+}
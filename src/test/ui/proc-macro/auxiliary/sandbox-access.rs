@@ -0,0 +1,26 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::TokenStream;
+
+#[proc_macro_derive(OpensFile)]
+pub fn opens_file(_: TokenStream) -> TokenStream {
+    // Whether the open succeeds depends on the sandbox mode; either way the
+    // attempt is reported.
+    let _ = std::fs::File::open("/dev/null");
+    TokenStream::new()
+}
+
+#[proc_macro_derive(OpensFileOnThread)]
+pub fn opens_file_on_thread(_: TokenStream) -> TokenStream {
+    // Threads spawned by the macro inherit its sandbox.
+    std::thread::spawn(|| {
+        let _ = std::fs::File::open("/dev/zero");
+    })
+    .join()
+    .unwrap();
+    TokenStream::new()
+}
@@ -0,0 +1,14 @@
+// Checks the error for a proc macro crate that `-Z dual-proc-macros` finds a host build of,
+// but no build for the target.
+
+// aux-build:test-macros.rs
+// compile-flags: -Z dual-proc-macros --target thumbv7m-none-eabi
+// needs-llvm-components: arm
+// only-linux
+// normalize-stderr-test: "\(hash [0-9a-f]+\)" -> "(hash HASH)"
+// normalize-stderr-test: "build for [^ ]+ (\(hash HASH\)|instead): .*" -> "build for HOST $1: LIB"
+
+#![feature(no_core)]
+#![no_core]
+
+extern crate test_macros; //~ ERROR found the host build of proc macro crate `test_macros`
@@ -0,0 +1,15 @@
+// Checks that querying lint levels from a proc macro doesn't report the unknown lints of the
+// command line again, on top of the early and late lint passes.
+
+// aux-build:lint-levels.rs
+// compile-flags: -W bogus
+// error-pattern: unknown lint: `bogus`
+
+extern crate lint_levels;
+
+use lint_levels::lint_level;
+
+fn main() {
+    let _ = lint_level!(dead_code);
+    let _ = lint_level!(bogus);
+}
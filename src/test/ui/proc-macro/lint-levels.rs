@@ -0,0 +1,40 @@
+// run-pass
+// aux-build:lint-levels.rs
+// compile-flags: -D unused-variables
+
+#![allow(dead_code)]
+#![deny(unused_mut)]
+
+extern crate lint_levels;
+
+use lint_levels::lint_level;
+
+#[warn(dead_code)]
+mod m {
+    pub fn dead_code() -> &'static str {
+        lint_level!(dead_code)
+    }
+
+    #[forbid(unused_mut)]
+    pub fn unused_mut() -> &'static str {
+        lint_level!(unused_mut)
+    }
+}
+
+fn main() {
+    assert_eq!(lint_level!(dead_code), "allow");
+    assert_eq!(lint_level!(unused_mut), "deny");
+    assert_eq!(lint_level!(unused_variables), "deny");
+    assert_eq!(lint_level!(unused_imports), "warn");
+    assert_eq!(m::dead_code(), "warn");
+    assert_eq!(m::unused_mut(), "forbid");
+
+    #[allow(unused_variables)]
+    let unused_variables = lint_level!(unused_variables);
+    assert_eq!(unused_variables, "allow");
+
+    // Tool lints, lint groups and unknown lints have no level.
+    assert_eq!(lint_level!(clippy::needless_return), "none");
+    assert_eq!(lint_level!(unused), "none");
+    assert_eq!(lint_level!(not_a_lint), "none");
+}
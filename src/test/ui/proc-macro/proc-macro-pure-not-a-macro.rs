@@ -0,0 +1,6 @@
+#![feature(proc_macro_pure)]
+
+#[proc_macro_pure] //~ ERROR attribute should be applied to a proc macro
+fn not_a_macro() {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// aux-build:sandbox-access.rs
+// compile-flags: -Z proc-macro-sandbox=strict
+// only-linux
+// only-x86_64
+
+#[macro_use]
+extern crate sandbox_access;
+
+#[derive(OpensFile)] //~ ERROR proc macro tried to open `/dev/null`
+struct S;
+
+#[derive(OpensFileOnThread)] //~ ERROR proc macro tried to open `/dev/zero`
+struct T;
+
+fn main() {}
@@ -0,0 +1,13 @@
+// check-pass
+// aux-build:sandbox-access.rs
+// compile-flags: -Z proc-macro-sandbox=warn
+// only-linux
+// only-x86_64
+
+#[macro_use]
+extern crate sandbox_access;
+
+#[derive(OpensFile)] //~ WARN proc macro tried to open `/dev/null`
+struct S;
+
+fn main() {}
@@ -0,0 +1,8 @@
+// compile-flags: --remap-path-prefix={{src-base}}=${REMAPPED_SRC}
+// rustc-env:REMAPPED_SRC=remapped
+
+fn main() {
+    // Without `-Z unstable-options` nothing is expanded, so the file name in the error
+    // message is remapped to `${REMAPPED_SRC}` as written.
+    ferris
+}
@@ -0,0 +1,8 @@
+// compile-flags: -Z unstable-options --remap-path-prefix={{src-base}}=${REMAPPED_SRC}$=env
+// rustc-env:REMAPPED_SRC=remapped
+
+fn main() {
+    // As in `remap-path-prefix.rs`, the error can't be marked here since the file name
+    // in the error message is remapped, here to `remapped=env`.
+    ferris
+}
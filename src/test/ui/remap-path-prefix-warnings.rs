@@ -0,0 +1,5 @@
+// check-pass
+// compile-flags: --remap-path-prefix=/a=/b --remap-path-prefix=/a=/c
+// compile-flags: --remap-path-prefix==/d
+
+fn main() {}
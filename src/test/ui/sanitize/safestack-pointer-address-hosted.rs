@@ -0,0 +1,6 @@
+// compile-flags: -Z sanitizer=safestack -Z safestack-use-pointer-address
+// compile-flags: --target x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+#![feature(no_core)]
+#![no_core]
+#![no_main]
@@ -0,0 +1,5 @@
+// compile-flags: -Z sanitizer=safestack --target thumbv7em-none-eabi
+// needs-llvm-components: arm
+#![feature(no_core)]
+#![no_core]
+#![no_main]
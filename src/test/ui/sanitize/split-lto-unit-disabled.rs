@@ -0,0 +1,8 @@
+// Verifies that CFI can't be used without splitting the LTO unit.
+//
+// compile-flags: -Clto -Zsanitizer=cfi -Csplit-lto-unit=no --target x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+
+#![feature(no_core)]
+#![no_core]
+#![no_main]
@@ -0,0 +1,7 @@
+// error-pattern:`-Z panic-abort-tests-format` requires `-Z panic-abort-tests`
+// compile-flags: --test -Zpanic-abort-tests-format=json
+
+#![cfg(test)]
+
+#[test]
+fn it_works() {}
@@ -0,0 +1,34 @@
+// no-prefer-dynamic
+// compile-flags: --test -Cpanic=abort -Zpanic-abort-tests
+// compile-flags: -Zpanic-abort-tests-env=CUSTOM_TEST_INVOKE -Zpanic-abort-tests-format=json
+// run-flags: --test-threads=1 --nocapture
+// run-pass
+// check-run-results
+// exec-env:RUST_BACKTRACE=0
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+// ignore-wasm no panic or subprocess support
+// ignore-emscripten no panic or subprocess support
+// ignore-sgx no subprocess support
+
+#![cfg(test)]
+
+use std::env;
+
+#[test]
+fn it_works() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[test]
+#[should_panic]
+fn it_panics() {
+    assert_eq!(1 + 1, 4);
+}
+
+#[test]
+fn it_runs_in_a_subprocess() {
+    // The variable is removed before the test runs.
+    assert!(env::var("CUSTOM_TEST_INVOKE").is_err());
+    assert!(env::var("__RUST_TEST_INVOKE").is_err());
+}
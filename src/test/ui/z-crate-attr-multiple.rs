@@ -0,0 +1,13 @@
+// This test checks that a single -Zcrate-attr flag can carry several comma-separated attributes,
+// and that each of them takes effect.
+
+// compile-flags: -Zcrate-attr=feature(abi_unadjusted),deny(unused_mut)
+
+#![allow(dead_code)]
+
+extern "unadjusted" fn foo() {}
+
+fn main() {
+    let mut x = 0; //~ ERROR variable does not need to be mutable
+    drop(x);
+}
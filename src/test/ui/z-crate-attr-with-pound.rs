@@ -0,0 +1,3 @@
+// compile-flags: -Zcrate-attr=#![feature(abi_unadjusted)]
+
+fn main() {}